@@ -7,6 +7,7 @@ use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
 use deno_core::ModuleSpecifier;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::ops::Range;
@@ -21,6 +22,8 @@ use swc_common::SourceFile;
 use swc_common::SourceMap;
 use swc_common::Span;
 use swc_common::Spanned;
+use swc_common::DUMMY_SP;
+use swc_ecmascript::ast;
 use swc_ecmascript::ast::Module;
 use swc_ecmascript::ast::Program;
 use swc_ecmascript::codegen::text_writer::JsWriter;
@@ -42,6 +45,11 @@ use swc_ecmascript::transforms::proposals;
 use swc_ecmascript::transforms::react;
 use swc_ecmascript::transforms::typescript;
 use swc_ecmascript::visit::FoldWith;
+use swc_ecmascript::visit::Node as VisitNode;
+use swc_ecmascript::visit::Visit;
+use swc_ecmascript::visit::VisitMut;
+use swc_ecmascript::visit::VisitMutWith;
+use swc_ecmascript::visit::VisitWith;
 
 static TARGET: JscTarget = JscTarget::Es2020;
 
@@ -105,6 +113,7 @@ fn get_es_config(jsx: bool) -> EsConfig {
     dynamic_import: true,
     export_default_from: true,
     export_namespace_from: true,
+    import_assertions: true,
     import_meta: true,
     jsx,
     nullish_coalescing: true,
@@ -228,6 +237,315 @@ fn strip_config_from_emit_options(
   }
 }
 
+/// Collects the spans of the import assertions of a module, starting after the
+/// specifier they apply to.
+#[derive(Default)]
+struct ImportAssertionCollector {
+  spans: Vec<Span>,
+}
+
+impl ImportAssertionCollector {
+  fn add(&mut self, src: &ast::Str, maybe_asserts: &Option<ast::ObjectLit>) {
+    if let Some(asserts) = maybe_asserts {
+      self.spans.push(Span::new(
+        src.span.hi,
+        asserts.span.hi,
+        Default::default(),
+      ));
+    }
+  }
+}
+
+impl Visit for ImportAssertionCollector {
+  fn visit_import_decl(
+    &mut self,
+    node: &ast::ImportDecl,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.src, &node.asserts);
+  }
+
+  fn visit_named_export(
+    &mut self,
+    node: &ast::NamedExport,
+    _parent: &dyn VisitNode,
+  ) {
+    if let Some(src) = &node.src {
+      self.add(src, &node.asserts);
+    }
+  }
+
+  fn visit_export_all(
+    &mut self,
+    node: &ast::ExportAll,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.src, &node.asserts);
+  }
+
+  fn visit_call_expr(&mut self, node: &ast::CallExpr, _parent: &dyn VisitNode) {
+    if let ast::ExprOrSuper::Expr(callee) = &node.callee {
+      if let ast::Expr::Ident(ident) = callee.as_ref() {
+        // the options of a dynamic import are stripped as a whole
+        if ident.sym == *"import" && node.args.len() > 1 {
+          let lo = node.args[0].expr.span().hi;
+          let hi = node.args[node.args.len() - 1].expr.span().hi;
+          self.spans.push(Span::new(lo, hi, Default::default()));
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// Collects the `type` import assertions of dynamic imports with a string
+/// literal specifier, which are not part of the dependency descriptors, keyed
+/// by the specifier.
+#[derive(Default)]
+struct DynamicImportAssertionCollector {
+  assertions: HashMap<String, String>,
+}
+
+fn get_prop_value<'a>(
+  object: &'a ast::ObjectLit,
+  key: &str,
+) -> Option<&'a ast::Expr> {
+  object.props.iter().find_map(|prop| match prop {
+    ast::PropOrSpread::Prop(prop) => match prop.as_ref() {
+      ast::Prop::KeyValue(kv) => {
+        let matches = match &kv.key {
+          ast::PropName::Ident(ident) => ident.sym == *key,
+          ast::PropName::Str(lit) => lit.value == *key,
+          _ => false,
+        };
+        if matches {
+          Some(kv.value.as_ref())
+        } else {
+          None
+        }
+      }
+      _ => None,
+    },
+    _ => None,
+  })
+}
+
+impl Visit for DynamicImportAssertionCollector {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr, _parent: &dyn VisitNode) {
+    if let ast::ExprOrSuper::Expr(callee) = &node.callee {
+      if let ast::Expr::Ident(ident) = callee.as_ref() {
+        if ident.sym == *"import" && node.args.len() > 1 {
+          if let (
+            ast::Expr::Lit(ast::Lit::Str(src)),
+            ast::Expr::Object(options),
+          ) = (node.args[0].expr.as_ref(), node.args[1].expr.as_ref())
+          {
+            if let Some(ast::Expr::Object(asserts)) =
+              get_prop_value(options, "assert")
+            {
+              if let Some(ast::Expr::Lit(ast::Lit::Str(assert_type))) =
+                get_prop_value(asserts, "type")
+              {
+                self
+                  .assertions
+                  .insert(src.value.to_string(), assert_type.value.to_string());
+              }
+            }
+          }
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// A dynamic import of a template literal with a single expression, like
+/// ``import(`./plugins/${name}.ts`)``, where the specifier is only statically
+/// known to start with the prefix and to end with the suffix.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DynamicImportTemplate {
+  pub prefix: String,
+  pub suffix: String,
+  pub location: Location,
+}
+
+/// Collects the spans, prefixes and suffixes of dynamic imports of template
+/// literals with a single expression, which are not part of the dependency
+/// descriptors.
+#[derive(Default)]
+struct DynamicImportTemplateCollector {
+  templates: Vec<(Span, String, String)>,
+}
+
+impl Visit for DynamicImportTemplateCollector {
+  fn visit_call_expr(&mut self, node: &ast::CallExpr, _parent: &dyn VisitNode) {
+    if let ast::ExprOrSuper::Expr(callee) = &node.callee {
+      if let ast::Expr::Ident(ident) = callee.as_ref() {
+        if ident.sym == *"import" && !node.args.is_empty() {
+          if let ast::Expr::Tpl(tpl) = node.args[0].expr.as_ref() {
+            if tpl.exprs.len() == 1 && tpl.quasis.len() == 2 {
+              let prefix = tpl.quasis[0].raw.value.to_string();
+              let suffix = tpl.quasis[1].raw.value.to_string();
+              self.templates.push((node.span, prefix, suffix));
+            }
+          }
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+struct DynamicImportRewriter<'a> {
+  rewrite: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl VisitMut for DynamicImportRewriter<'_> {
+  fn visit_mut_call_expr(&mut self, node: &mut ast::CallExpr) {
+    node.visit_mut_children_with(self);
+    if let ast::ExprOrSuper::Expr(callee) = &node.callee {
+      if let ast::Expr::Ident(ident) = callee.as_ref() {
+        if ident.sym == *"import" && !node.args.is_empty() {
+          if let ast::Expr::Lit(ast::Lit::Str(specifier)) =
+            node.args[0].expr.as_mut()
+          {
+            if let Some(value) = (self.rewrite)(&specifier.value) {
+              *specifier = ast::Str {
+                span: specifier.span,
+                value: value.into(),
+                kind: ast::StrKind::Synthesized,
+                has_escape: false,
+              };
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Rewrite the string literal specifiers of the dynamic imports of a module
+/// with the specifiers returned by `rewrite`, which returns `None` to keep a
+/// specifier as it is.
+pub fn rewrite_dynamic_imports(
+  module: &mut Module,
+  rewrite: &dyn Fn(&str) -> Option<String>,
+) {
+  module.visit_mut_with(&mut DynamicImportRewriter { rewrite });
+}
+
+/// Collects the string literal specifiers of imports, exports, import types
+/// and `import x = require()` declarations, with the spans of the literals.
+#[derive(Default)]
+struct SpecifierCollector {
+  specifiers: Vec<(Span, String)>,
+}
+
+impl SpecifierCollector {
+  fn add(&mut self, src: &ast::Str) {
+    self.specifiers.push((src.span, src.value.to_string()));
+  }
+}
+
+impl Visit for SpecifierCollector {
+  fn visit_import_decl(
+    &mut self,
+    node: &ast::ImportDecl,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.src);
+  }
+
+  fn visit_export_all(
+    &mut self,
+    node: &ast::ExportAll,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.src);
+  }
+
+  fn visit_named_export(
+    &mut self,
+    node: &ast::NamedExport,
+    _parent: &dyn VisitNode,
+  ) {
+    if let Some(src) = &node.src {
+      self.add(src);
+    }
+  }
+
+  fn visit_ts_import_type(
+    &mut self,
+    node: &ast::TsImportType,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.arg);
+    node.visit_children_with(self);
+  }
+
+  fn visit_ts_external_module_ref(
+    &mut self,
+    node: &ast::TsExternalModuleRef,
+    _parent: &dyn VisitNode,
+  ) {
+    self.add(&node.expr);
+  }
+
+  fn visit_call_expr(&mut self, node: &ast::CallExpr, _parent: &dyn VisitNode) {
+    if let ast::ExprOrSuper::Expr(callee) = &node.callee {
+      if let ast::Expr::Ident(ident) = callee.as_ref() {
+        if ident.sym == *"import" && !node.args.is_empty() {
+          if let ast::Expr::Lit(ast::Lit::Str(src)) = node.args[0].expr.as_ref()
+          {
+            self.add(src);
+          }
+        }
+      }
+    }
+    node.visit_children_with(self);
+  }
+}
+
+/// Return `true` if the declaration has a `declare` modifier, other than
+/// `declare global`.
+fn is_declared(decl: &ast::Decl) -> bool {
+  match decl {
+    ast::Decl::Class(decl) => decl.declare,
+    ast::Decl::Fn(decl) => decl.declare,
+    ast::Decl::Var(decl) => decl.declare,
+    ast::Decl::TsInterface(decl) => decl.declare,
+    ast::Decl::TsTypeAlias(decl) => decl.declare,
+    ast::Decl::TsEnum(decl) => decl.declare,
+    ast::Decl::TsModule(decl) => decl.declare && !decl.global,
+  }
+}
+
+/// Return the value of the `type` import assertion of a dependency, where the
+/// assertions of dynamic imports are provided by
+/// `ParsedModule::get_dynamic_import_assertions()`.
+pub fn get_assert_type(
+  desc: &DependencyDescriptor,
+  dynamic_import_assertions: &HashMap<String, String>,
+) -> Option<String> {
+  desc
+    .import_assertions
+    .iter()
+    .find_map(|(key, value)| {
+      if &**key == "type" {
+        Some(value.to_string())
+      } else {
+        None
+      }
+    })
+    .or_else(|| {
+      if desc.is_dynamic {
+        dynamic_import_assertions.get(&*desc.specifier).cloned()
+      } else {
+        None
+      }
+    })
+}
+
 /// A logical structure to hold the value of a parsed module for further
 /// processing.
 #[derive(Clone)]
@@ -282,6 +600,116 @@ impl ParsedModule {
     self.source_map.lookup_char_pos(span.lo).into()
   }
 
+  /// Get the byte range of a given span within the source of the module.
+  pub fn get_range(&self, span: &Span) -> Range<usize> {
+    let start_pos = self.source_file.start_pos;
+    (span.lo - start_pos).0 as usize..(span.hi - start_pos).0 as usize
+  }
+
+  /// Return the string literal specifiers of the imports, exports and import
+  /// types of the module, with the spans of the literals, which include the
+  /// quotes.
+  pub fn get_specifiers(&self) -> Vec<(Span, String)> {
+    let mut collector = SpecifierCollector::default();
+    self
+      .module
+      .visit_with(&ast::Invalid { span: DUMMY_SP }, &mut collector);
+    collector.specifiers
+  }
+
+  /// Return the byte ranges of the `declare` modifiers of the top level
+  /// declarations of the module, other than `declare global`, including the
+  /// whitespace which follows them.
+  pub fn get_declare_modifiers(&self) -> Vec<Range<usize>> {
+    let src: &str = &self.source_file.src;
+    self
+      .module
+      .body
+      .iter()
+      .filter_map(|item| {
+        let (span, decl) = match item {
+          ast::ModuleItem::Stmt(ast::Stmt::Decl(decl)) => (decl.span(), decl),
+          ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(export)) => {
+            (export.span, &export.decl)
+          }
+          _ => return None,
+        };
+        if !is_declared(decl) {
+          return None;
+        }
+        let mut offset = self.get_range(&span).start;
+        if src[offset..].starts_with("export") {
+          let rest = &src[offset + "export".len()..];
+          offset = src.len() - rest.trim_start().len();
+        }
+        if !src[offset..].starts_with("declare") {
+          // the span of the declaration can start after the modifier
+          let before = src[..offset].trim_end();
+          if !before.ends_with("declare") {
+            return None;
+          }
+          offset = before.len() - "declare".len();
+        }
+        let rest = &src[offset + "declare".len()..];
+        let whitespace = rest.len() - rest.trim_start().len();
+        if whitespace == 0 {
+          return None;
+        }
+        Some(offset..offset + "declare".len() + whitespace)
+      })
+      .collect()
+  }
+
+  /// Return the `type` import assertions of the dynamic imports of the module,
+  /// keyed by specifier.
+  pub fn get_dynamic_import_assertions(&self) -> HashMap<String, String> {
+    let mut collector = DynamicImportAssertionCollector::default();
+    self
+      .module
+      .visit_with(&ast::Invalid { span: DUMMY_SP }, &mut collector);
+    collector.assertions
+  }
+
+  /// Return the dynamic imports of template literals of the module, which are
+  /// resolved heuristically, as the specifier is not statically known.
+  pub fn get_dynamic_import_templates(&self) -> Vec<DynamicImportTemplate> {
+    let mut collector = DynamicImportTemplateCollector::default();
+    self
+      .module
+      .visit_with(&ast::Invalid { span: DUMMY_SP }, &mut collector);
+    collector
+      .templates
+      .into_iter()
+      .map(|(span, prefix, suffix)| DynamicImportTemplate {
+        prefix,
+        suffix,
+        location: self.get_location(&span),
+      })
+      .collect()
+  }
+
+  /// Return the source of the module, where any import assertions have been
+  /// replaced with whitespace, for consumers which do not support import
+  /// assertions, like the TypeScript compiler.
+  pub fn strip_import_assertions(&self) -> String {
+    let mut collector = ImportAssertionCollector::default();
+    self
+      .module
+      .visit_with(&ast::Invalid { span: DUMMY_SP }, &mut collector);
+    let mut bytes = self.source_file.src.as_bytes().to_vec();
+    let start_pos = self.source_file.start_pos;
+    for span in collector.spans {
+      let range =
+        (span.lo - start_pos).0 as usize..(span.hi - start_pos).0 as usize;
+      for byte in &mut bytes[range] {
+        if *byte != b'\n' && *byte != b'\r' {
+          *byte = b' ';
+        }
+      }
+    }
+    String::from_utf8(bytes).unwrap()
+  }
+
   /// Transform a TypeScript file into a JavaScript file, based on the supplied
   /// options.
   ///
@@ -627,6 +1055,114 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_strip_import_assertions() {
+    let specifier = resolve_url_or_path("https://deno.land/x/mod.ts").unwrap();
+    let source = r#"import a from "./a.json" assert { type: "json" };
+export * from "./b.json" assert { type: "json" };
+const c = await import("./c.json", { assert: { type: "json" } });
+"#;
+    let parsed_module =
+      parse(specifier.as_str(), source, &MediaType::TypeScript)
+        .expect("could not parse module");
+    let actual = parsed_module.strip_import_assertions();
+    assert_eq!(
+      actual,
+      r#"import a from "./a.json"                        ;
+export * from "./b.json"                        ;
+const c = await import("./c.json"                              );
+"#
+    );
+  }
+
+  #[test]
+  fn test_get_assert_type() {
+    let specifier = resolve_url_or_path("https://deno.land/x/mod.ts").unwrap();
+    let source = r#"import a from "./a.json" assert { type: "json" };
+import b from "./b.ts";
+const c = await import("./c.json", { assert: { type: "json" } });
+const d = await import("./d.ts");
+"#;
+    let parsed_module =
+      parse(specifier.as_str(), source, &MediaType::TypeScript)
+        .expect("could not parse module");
+    let dynamic_import_assertions =
+      parsed_module.get_dynamic_import_assertions();
+    let actual: Vec<(String, Option<String>)> = parsed_module
+      .analyze_dependencies()
+      .iter()
+      .map(|desc| {
+        (
+          desc.specifier.to_string(),
+          get_assert_type(desc, &dynamic_import_assertions),
+        )
+      })
+      .collect();
+    assert_eq!(
+      actual,
+      vec![
+        ("./a.json".to_string(), Some("json".to_string())),
+        ("./b.ts".to_string(), None),
+        ("./c.json".to_string(), Some("json".to_string())),
+        ("./d.ts".to_string(), None),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_get_dynamic_import_templates() {
+    let specifier = resolve_url_or_path("file:///a/mod.ts").unwrap();
+    let source = r#"const name = "a";
+const a = await import(`./plugins/${name}.ts`);
+const b = await import(`./plugins/${name}/mod.js`);
+const c = await import(`./plugins/${name}/${name}.js`);
+const d = await import(`./d.ts`);
+const e = await import("./e.ts");
+"#;
+    let parsed_module =
+      parse(specifier.as_str(), source, &MediaType::TypeScript)
+        .expect("could not parse module");
+    let actual: Vec<(String, String, usize)> = parsed_module
+      .get_dynamic_import_templates()
+      .into_iter()
+      .map(|template| {
+        (template.prefix, template.suffix, template.location.line)
+      })
+      .collect();
+    assert_eq!(
+      actual,
+      vec![
+        ("./plugins/".to_string(), ".ts".to_string(), 2),
+        ("./plugins/".to_string(), "/mod.js".to_string(), 3),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_rewrite_dynamic_imports() {
+    let specifier = resolve_url_or_path("file:///a/mod.ts").unwrap();
+    let source = r#"const a = await import("./a.ts");
+const b = await import("./b.ts");
+const c = await import(`./c.ts`);
+"#;
+    let mut parsed_module =
+      parse(specifier.as_str(), source, &MediaType::TypeScript)
+        .expect("could not parse module");
+    rewrite_dynamic_imports(&mut parsed_module.module, &|specifier| {
+      if specifier == "./a.ts" {
+        Some("./chunk-1.js".to_string())
+      } else {
+        None
+      }
+    });
+    let (code, _) = parsed_module
+      .transpile(&EmitOptions::default())
+      .expect("could not transpile");
+    assert!(code.contains(r#"import("./chunk-1.js")"#));
+    assert!(code.contains(r#"import("./b.ts")"#));
+    assert!(code.contains("import(`./c.ts`)"));
+  }
+
   #[test]
   fn test_transpile() {
     let specifier = resolve_url_or_path("https://deno.land/x/mod.ts")
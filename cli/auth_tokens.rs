@@ -5,15 +5,27 @@ use log::debug;
 use log::error;
 use std::fmt;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthTokenData {
+  Bearer(String),
+  Basic { username: String, password: String },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AuthToken {
   host: String,
-  token: String,
+  token: AuthTokenData,
 }
 
 impl fmt::Display for AuthToken {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "Bearer {}", self.token)
+    match &self.token {
+      AuthTokenData::Bearer(token) => write!(f, "Bearer {}", token),
+      AuthTokenData::Basic { username, password } => {
+        let credentials = format!("{}:{}", username, password);
+        write!(f, "Basic {}", base64::encode(credentials))
+      }
+    }
   }
 }
 
@@ -27,16 +39,30 @@ impl AuthTokens {
   /// Create a new set of tokens based on the provided string. It is intended
   /// that the string be the value of an environment variable and the string is
   /// parsed for token values.  The string is expected to be a semi-colon
-  /// separated string, where each value is `{token}@{hostname}`.
+  /// separated string, where each value is `{token}@{hostname}` for a bearer
+  /// token or `{username}:{password}@{hostname}` for basic authentication.
   pub fn new(maybe_tokens_str: Option<String>) -> Self {
     let mut tokens = Vec::new();
     if let Some(tokens_str) = maybe_tokens_str {
       for token_str in tokens_str.split(';') {
         if token_str.contains('@') {
           let pair: Vec<&str> = token_str.rsplitn(2, '@').collect();
-          let token = pair[1].to_string();
+          let token = pair[1];
           let host = pair[0].to_lowercase();
-          tokens.push(AuthToken { host, token });
+          if token.contains(':') {
+            let pair: Vec<&str> = token.splitn(2, ':').collect();
+            let username = pair[0].to_string();
+            let password = pair[1].to_string();
+            tokens.push(AuthToken {
+              host,
+              token: AuthTokenData::Basic { username, password },
+            });
+          } else {
+            tokens.push(AuthToken {
+              host,
+              token: AuthTokenData::Bearer(token.to_string()),
+            });
+          }
         } else {
           error!("Badly formed auth token discarded.");
         }
@@ -133,4 +159,26 @@ mod tests {
       "Bearer abc@123".to_string()
     );
   }
+
+  #[test]
+  fn test_auth_token_basic() {
+    let auth_tokens = AuthTokens::new(Some("abc:123@deno.land".to_string()));
+    let fixture = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Basic YWJjOjEyMw=="
+    );
+    let fixture = resolve_url("https://www.deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Basic YWJjOjEyMw==".to_string()
+    );
+    let fixture = resolve_url("http://127.0.0.1:8080/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.get(&fixture), None);
+    let fixture =
+      resolve_url("https://deno.land.example.com/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.get(&fixture), None);
+    let fixture = resolve_url("https://deno.land:8080/x/mod.ts").unwrap();
+    assert_eq!(auth_tokens.get(&fixture), None);
+  }
 }
@@ -63,6 +63,7 @@ fn create_compiler_snapshot(
   op_crate_libs.insert("deno.webgpu", deno_webgpu::get_declaration());
   op_crate_libs.insert("deno.websocket", deno_websocket::get_declaration());
   op_crate_libs.insert("deno.webstorage", deno_webstorage::get_declaration());
+  op_crate_libs.insert("deno.cache", deno_cache::get_declaration());
   op_crate_libs.insert("deno.crypto", deno_crypto::get_declaration());
   op_crate_libs.insert(
     "deno.broadcast_channel",
@@ -294,6 +295,10 @@ fn main() {
     "cargo:rustc-env=DENO_WEBSTORAGE_LIB_PATH={}",
     deno_webstorage::get_declaration().display()
   );
+  println!(
+    "cargo:rustc-env=DENO_CACHE_LIB_PATH={}",
+    deno_cache::get_declaration().display()
+  );
   println!(
     "cargo:rustc-env=DENO_CRYPTO_LIB_PATH={}",
     deno_crypto::get_declaration().display()
@@ -1,7 +1,11 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use ring::digest::digest;
+use ring::digest::Algorithm;
 use ring::digest::Context;
 use ring::digest::SHA256;
+use ring::digest::SHA384;
+use ring::digest::SHA512;
 
 pub fn gen(v: &[impl AsRef<[u8]>]) -> String {
   let mut ctx = Context::new(&SHA256);
@@ -17,6 +21,56 @@ pub fn gen(v: &[impl AsRef<[u8]>]) -> String {
   out.join("")
 }
 
+/// The hash algorithms supported in integrity metadata, from the weakest to
+/// the strongest.
+static INTEGRITY_ALGORITHMS: &[(&str, &Algorithm)] = &[
+  ("sha256", &SHA256),
+  ("sha384", &SHA384),
+  ("sha512", &SHA512),
+];
+
+/// Verify the source against integrity metadata, which is a whitespace
+/// separated list of hashes like `sha384-<base64 digest>`, following the
+/// subresource integrity specification where only the hashes of the strongest
+/// algorithm are considered and the source has to match one of them. Unlike
+/// the specification, metadata without any hash of a supported algorithm
+/// doesn't match any source, so that a mistyped hash doesn't go unnoticed.
+pub fn verify_integrity(source: &[u8], integrity: &str) -> bool {
+  let hashes: Vec<(usize, &str)> = integrity
+    .split_whitespace()
+    .filter_map(parse_integrity_hash)
+    .collect();
+  let strongest = match hashes.iter().map(|(index, _)| *index).max() {
+    Some(strongest) => strongest,
+    None => return false,
+  };
+  let (_, algorithm) = INTEGRITY_ALGORITHMS[strongest];
+  let actual = base64::encode(digest(algorithm, source));
+  hashes
+    .iter()
+    .any(|(index, expected)| *index == strongest && *expected == actual)
+}
+
+/// Whether integrity metadata consists of at least one hash, and only of
+/// hashes of the supported algorithms.
+pub fn is_valid_integrity(integrity: &str) -> bool {
+  let mut hashes = integrity.split_whitespace().peekable();
+  hashes.peek().is_some()
+    && hashes.all(|hash| parse_integrity_hash(hash).is_some())
+}
+
+/// Returns the index of the algorithm of a hash in `INTEGRITY_ALGORITHMS`
+/// along with its digest, or `None` if the algorithm isn't supported.
+fn parse_integrity_hash(hash: &str) -> Option<(usize, &str)> {
+  // options, like `sha384-<digest>?foo`, are ignored
+  let hash = hash.split('?').next().unwrap();
+  let (name, digest) = hash.split_at(hash.find('-')?);
+  INTEGRITY_ALGORITHMS
+    .iter()
+    .position(|(n, _)| *n == name)
+    .map(|index| (index, &digest[1..]))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -29,4 +83,42 @@ mod tests {
       "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
     );
   }
+
+  #[test]
+  fn test_verify_integrity() {
+    // sha256 and sha384 of "hello world"
+    let sha256 = "sha256-uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek=";
+    let sha384 =
+      "sha384-/b2OdaZ/KfcBpOBAOF4uI5hjA+oQI5IRr5B/y7g1eLPkF8txzmRu/QgZ3YwIjeG9";
+    assert!(verify_integrity(b"hello world", sha256));
+    assert!(verify_integrity(b"hello world", sha384));
+    assert!(!verify_integrity(b"hello deno", sha256));
+    // only the strongest algorithm is considered
+    assert!(verify_integrity(
+      b"hello world",
+      &format!("sha256-invalid {}", sha384)
+    ));
+    assert!(!verify_integrity(
+      b"hello world",
+      &format!("{} sha384-invalid", sha256)
+    ));
+    // metadata without a hash of a supported algorithm matches nothing
+    assert!(!verify_integrity(b"hello world", "md5-invalid"));
+    assert!(!verify_integrity(b"hello deno", "md5-invalid sha1-invalid"));
+    assert!(!verify_integrity(
+      b"hello deno",
+      &format!("md5-invalid {}", sha256)
+    ));
+  }
+
+  #[test]
+  fn test_is_valid_integrity() {
+    assert!(is_valid_integrity("sha256-abc"));
+    assert!(is_valid_integrity("sha256-abc sha512-def?foo"));
+    assert!(!is_valid_integrity(""));
+    assert!(!is_valid_integrity("md5-abc"));
+    assert!(!is_valid_integrity("sha-256-abc"));
+    assert!(!is_valid_integrity("sha256-abc sha-384-def"));
+    assert!(!is_valid_integrity("sha256"));
+  }
 }
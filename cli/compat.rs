@@ -0,0 +1,120 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Support for the Node compatibility mode (`--compat`), where the built-in
+//! Node modules are mapped to the polyfills of `std/node`, Node-style globals
+//! are set up and CommonJS entry points are loaded with `require`.
+
+use deno_core::error::AnyError;
+use deno_core::located_script_name;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_core::JsRuntime;
+use deno_core::ModuleSpecifier;
+
+static STD_NODE: &str = "https://deno.land/std@0.100.0/node/";
+
+static SUPPORTED_MODULES: &[&str] = &[
+  "assert",
+  "buffer",
+  "child_process",
+  "console",
+  "constants",
+  "crypto",
+  "events",
+  "fs",
+  "module",
+  "os",
+  "path",
+  "perf_hooks",
+  "process",
+  "querystring",
+  "stream",
+  "string_decoder",
+  "timers",
+  "tty",
+  "url",
+  "util",
+];
+
+/// The polyfills are loaded from `std/node`, unless `DENO_NODE_COMPAT_URL`
+/// points to another copy of them.
+fn get_std_node_url(path: &str) -> Url {
+  let base = std::env::var("DENO_NODE_COMPAT_URL")
+    .ok()
+    .and_then(|url| Url::parse(&url).ok())
+    .unwrap_or_else(|| Url::parse(STD_NODE).unwrap());
+  base.join(path).unwrap()
+}
+
+/// The module which sets up the Node globals, like `process` and `Buffer`.
+pub fn get_node_globals_url() -> Url {
+  get_std_node_url("global.ts")
+}
+
+/// The import map entries which map the built-in Node modules, both as bare
+/// specifiers and with the `node:` scheme, to their `std/node` polyfills.
+pub fn get_node_imports() -> Vec<(String, Url)> {
+  SUPPORTED_MODULES
+    .iter()
+    .flat_map(|name| {
+      let url = get_std_node_url(&format!("{}.ts", name));
+      vec![
+        (name.to_string(), url.clone()),
+        (format!("node:{}", name), url),
+      ]
+    })
+    .collect()
+}
+
+/// Returns `true` if the specifier refers to a CommonJS module, which needs to
+/// be loaded with `require` instead of being imported.
+pub fn is_cjs_module(specifier: &ModuleSpecifier) -> bool {
+  specifier.path().ends_with(".cjs")
+}
+
+/// Load and evaluate a CommonJS entry point using the `std/node` module
+/// loader.  The returned promise is driven by the event loop of the runtime.
+pub fn load_cjs_module(
+  js_runtime: &mut JsRuntime,
+  main_module: &ModuleSpecifier,
+) -> Result<(), AnyError> {
+  // The URLs are passed as JSON strings, so that they can't be mistaken for
+  // code whatever characters they contain.
+  let source_code = format!(
+    r#"(async function loadCjsModule(moduleUrl, main) {{
+      const {{ createRequire }} = await import(moduleUrl);
+      const require = createRequire(main);
+      require(main);
+    }})({}, {});"#,
+    serde_json::to_string(get_std_node_url("module.ts").as_str())?,
+    serde_json::to_string(main_module.as_str())?,
+  );
+  js_runtime.execute_script(&located_script_name!(), &source_code)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::resolve_url;
+
+  #[test]
+  fn test_get_node_imports() {
+    let imports = get_node_imports();
+    assert!(imports.contains(&(
+      "fs".to_string(),
+      Url::parse("https://deno.land/std@0.100.0/node/fs.ts").unwrap()
+    )));
+    assert!(imports.contains(&(
+      "node:path".to_string(),
+      Url::parse("https://deno.land/std@0.100.0/node/path.ts").unwrap()
+    )));
+    assert_eq!(imports.len(), SUPPORTED_MODULES.len() * 2);
+  }
+
+  #[test]
+  fn test_is_cjs_module() {
+    assert!(is_cjs_module(&resolve_url("file:///a/index.cjs").unwrap()));
+    assert!(!is_cjs_module(&resolve_url("file:///a/index.mjs").unwrap()));
+    assert!(!is_cjs_module(&resolve_url("file:///a/index.ts").unwrap()));
+  }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::fs_util::canonicalize_path;
+use crate::import_map::ImportMap;
 use deno_core::error::anyhow;
 use deno_core::error::AnyError;
 use deno_core::error::Context;
@@ -10,6 +11,7 @@ use deno_core::serde::Serializer;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
+use deno_core::url::Url;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
@@ -43,19 +45,89 @@ pub struct CompilerOptions {
 /// path those options came from.
 #[derive(Debug, Clone, PartialEq)]
 pub struct IgnoredCompilerOptions {
+  /// The options which are overridden by Deno.
   pub items: Vec<String>,
+  /// The options which are not known compiler options, which are passed to
+  /// the TypeScript compiler as they are.
+  pub unknown: Vec<String>,
   pub maybe_path: Option<PathBuf>,
+  /// The options of the configuration files which are `"extends"`-ed, each
+  /// reported for the configuration file which sets them.
+  pub extended: Vec<IgnoredCompilerOptions>,
 }
 
 impl fmt::Display for IgnoredCompilerOptions {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    let mut codes = self.items.clone();
-    codes.sort();
+    let has_own_options = !self.items.is_empty() || !self.unknown.is_empty();
+    if has_own_options || self.extended.is_empty() {
+      self.fmt_own_options(f)?;
+    }
+    for (i, extended) in self.extended.iter().enumerate() {
+      if has_own_options || i > 0 {
+        writeln!(f)?;
+      }
+      write!(f, "{}", extended)?;
+    }
+    Ok(())
+  }
+}
+
+impl IgnoredCompilerOptions {
+  /// Return the ignored and unknown options of this configuration file and of
+  /// the configuration files it extends.
+  fn all_items(&self) -> Vec<&String> {
+    self
+      .items
+      .iter()
+      .chain(self.unknown.iter())
+      .chain(
+        self
+          .extended
+          .iter()
+          .flat_map(|extended| extended.all_items()),
+      )
+      .collect()
+  }
+
+  fn fmt_own_options(&self, f: &mut fmt::Formatter) -> fmt::Result {
     if let Some(path) = &self.maybe_path {
-      write!(f, "Unsupported compiler options in \"{}\".\n  The following options were ignored:\n    {}", path.to_string_lossy(), codes.join(", "))
+      write!(
+        f,
+        "Unsupported compiler options in \"{}\".",
+        path.to_string_lossy()
+      )?;
     } else {
-      write!(f, "Unsupported compiler options provided.\n  The following options were ignored:\n    {}", codes.join(", "))
+      write!(f, "Unsupported compiler options provided.")?;
+    }
+    let mut items = self.items.clone();
+    items.sort();
+    if !items.is_empty() {
+      write!(f, "\n  The following options were ignored:")?;
+      for item in items {
+        match get_ignored_reason(&item) {
+          Some(reason) => write!(f, "\n    {} - {}", item, reason)?,
+          None => write!(f, "\n    {}", item)?,
+        }
+      }
     }
+    let mut unknown = self.unknown.clone();
+    unknown.sort();
+    if !unknown.is_empty() {
+      write!(
+        f,
+        "\n  The following options are unknown, and were passed to \
+         TypeScript as they are:"
+      )?;
+      for item in unknown {
+        match get_known_option(&item) {
+          Some(known) => {
+            write!(f, "\n    {} - did you mean \"{}\"?", item, known)?
+          }
+          None => write!(f, "\n    {}", item)?,
+        }
+      }
+    }
+    Ok(())
   }
 }
 
@@ -64,85 +136,213 @@ impl Serialize for IgnoredCompilerOptions {
   where
     S: Serializer,
   {
-    Serialize::serialize(&self.items, serializer)
+    Serialize::serialize(&self.all_items(), serializer)
   }
 }
 
+const EMIT_REASON: &str =
+  "modules are emitted by Deno as ES modules for the latest JavaScript version";
+const INTEROP_REASON: &str =
+  "Deno only supports ES modules, so there is no CommonJS or UMD interop";
+const OUTPUT_REASON: &str = "emitted modules are stored in the Deno cache";
+const RESOLUTION_REASON: &str =
+  "modules are resolved by URL, use an import map to remap specifiers";
+const SOURCE_MAP_REASON: &str = "source maps are always inlined by Deno";
+const TOOLING_REASON: &str =
+  "the option only applies to the tsc command line tool";
+
 /// A static slice of all the compiler options that should be ignored that
 /// either have no effect on the compilation or would cause the emit to not work
-/// in Deno.
-pub const IGNORED_COMPILER_OPTIONS: &[&str] = &[
-  "allowSyntheticDefaultImports",
-  "allowUmdGlobalAccess",
-  "baseUrl",
-  "declaration",
-  "declarationMap",
-  "downlevelIteration",
-  "esModuleInterop",
-  "emitDeclarationOnly",
-  "importHelpers",
-  "inlineSourceMap",
-  "inlineSources",
-  "module",
-  "noEmitHelpers",
-  "noErrorTruncation",
-  "noLib",
-  "noResolve",
-  "outDir",
-  "paths",
-  "preserveConstEnums",
-  "reactNamespace",
-  "rootDir",
-  "rootDirs",
-  "skipLibCheck",
-  "sourceMap",
-  "sourceRoot",
-  "target",
-  "useDefineForClassFields",
+/// in Deno, with the reason they are ignored.
+pub const IGNORED_COMPILER_OPTIONS: &[(&str, &str)] = &[
+  ("allowSyntheticDefaultImports", INTEROP_REASON),
+  ("allowUmdGlobalAccess", INTEROP_REASON),
+  ("baseUrl", RESOLUTION_REASON),
+  (
+    "declaration",
+    "declarations are only emitted by Deno.emit()",
+  ),
+  (
+    "declarationMap",
+    "declarations are only emitted by Deno.emit()",
+  ),
+  ("downlevelIteration", EMIT_REASON),
+  ("esModuleInterop", INTEROP_REASON),
+  (
+    "emitDeclarationOnly",
+    "declarations are only emitted by Deno.emit()",
+  ),
+  ("importHelpers", EMIT_REASON),
+  ("inlineSourceMap", SOURCE_MAP_REASON),
+  ("inlineSources", SOURCE_MAP_REASON),
+  ("module", EMIT_REASON),
+  ("noEmitHelpers", EMIT_REASON),
+  ("noErrorTruncation", "diagnostics are formatted by Deno"),
+  (
+    "noLib",
+    "the libraries are provided by Deno, use \"lib\" to select them",
+  ),
+  ("noResolve", RESOLUTION_REASON),
+  ("outDir", OUTPUT_REASON),
+  ("paths", RESOLUTION_REASON),
+  ("preserveConstEnums", EMIT_REASON),
+  ("reactNamespace", "use \"jsxFactory\" instead"),
+  ("rootDir", OUTPUT_REASON),
+  ("rootDirs", RESOLUTION_REASON),
+  (
+    "skipLibCheck",
+    "the type checking of libraries is managed by Deno",
+  ),
+  ("sourceMap", SOURCE_MAP_REASON),
+  ("sourceRoot", SOURCE_MAP_REASON),
+  ("target", EMIT_REASON),
+  ("useDefineForClassFields", EMIT_REASON),
+];
+
+pub const IGNORED_RUNTIME_COMPILER_OPTIONS: &[(&str, &str)] = &[
+  (
+    "assumeChangesOnlyAffectDirectDependencies",
+    "incremental type checking is managed by Deno",
+  ),
+  ("build", TOOLING_REASON),
+  ("charset", "source files are always decoded by Deno"),
+  ("composite", "project references are not supported"),
+  ("diagnostics", TOOLING_REASON),
+  (
+    "disableReferencedProjectLoad",
+    "project references are not supported",
+  ),
+  ("disableSizeLimit", TOOLING_REASON),
+  (
+    "disableSolutionSearching",
+    "project references are not supported",
+  ),
+  (
+    "disableSourceOfProjectReferenceRedirect",
+    "project references are not supported",
+  ),
+  ("emitBOM", EMIT_REASON),
+  ("explainFiles", TOOLING_REASON),
+  ("extendedDiagnostics", TOOLING_REASON),
+  (
+    "forceConsistentCasingInFileNames",
+    "module specifiers are URLs, which are case sensitive",
+  ),
+  ("generateCpuProfile", TOOLING_REASON),
+  ("generateTrace", TOOLING_REASON),
+  ("help", TOOLING_REASON),
+  (
+    "incremental",
+    "incremental type checking is managed by Deno",
+  ),
+  ("init", TOOLING_REASON),
+  (
+    "isolatedModules",
+    "modules are always transpiled in isolation by Deno",
+  ),
+  ("listEmittedFiles", TOOLING_REASON),
+  ("listFiles", TOOLING_REASON),
+  ("listFilesOnly", TOOLING_REASON),
+  ("mapRoot", SOURCE_MAP_REASON),
+  ("maxNodeModuleJsDepth", RESOLUTION_REASON),
+  ("moduleResolution", RESOLUTION_REASON),
+  ("newLine", EMIT_REASON),
+  ("noEmit", OUTPUT_REASON),
+  ("noEmitOnError", OUTPUT_REASON),
+  ("out", OUTPUT_REASON),
+  ("outDir", OUTPUT_REASON),
+  ("outFile", OUTPUT_REASON),
+  ("preserveSymlinks", RESOLUTION_REASON),
+  ("preserveWatchOutput", TOOLING_REASON),
+  ("pretty", "diagnostics are formatted by Deno"),
+  ("project", TOOLING_REASON),
+  (
+    "resolveJsonModule",
+    "JSON modules are imported with import assertions",
+  ),
+  ("showConfig", "use `deno info --config` instead"),
+  (
+    "skipDefaultLibCheck",
+    "the type checking of libraries is managed by Deno",
+  ),
+  (
+    "stripInternal",
+    "declarations are only emitted by Deno.emit()",
+  ),
+  ("traceResolution", TOOLING_REASON),
+  (
+    "tsBuildInfoFile",
+    "incremental type checking is managed by Deno",
+  ),
+  (
+    "typeRoots",
+    "use \"types\" to include type declarations instead",
+  ),
+  ("useDefineForClassFields", EMIT_REASON),
+  ("version", TOOLING_REASON),
+  ("watch", "use the --watch flag instead"),
 ];
 
-pub const IGNORED_RUNTIME_COMPILER_OPTIONS: &[&str] = &[
-  "assumeChangesOnlyAffectDirectDependencies",
-  "build",
-  "charset",
-  "composite",
-  "diagnostics",
-  "disableSizeLimit",
-  "emitBOM",
-  "extendedDiagnostics",
-  "forceConsistentCasingInFileNames",
-  "generateCpuProfile",
-  "help",
-  "incremental",
-  "init",
-  "isolatedModules",
-  "listEmittedFiles",
-  "listFiles",
-  "mapRoot",
-  "maxNodeModuleJsDepth",
-  "moduleResolution",
-  "newLine",
-  "noEmit",
-  "noEmitOnError",
-  "out",
-  "outDir",
-  "outFile",
-  "preserveSymlinks",
-  "preserveWatchOutput",
-  "pretty",
-  "project",
-  "resolveJsonModule",
-  "showConfig",
-  "skipDefaultLibCheck",
-  "stripInternal",
-  "traceResolution",
-  "tsBuildInfoFile",
-  "typeRoots",
-  "useDefineForClassFields",
-  "version",
-  "watch",
+/// The compiler options which are passed to the TypeScript compiler, in
+/// addition to the ignored options.  Any other option is unknown.
+pub const SUPPORTED_COMPILER_OPTIONS: &[&str] = &[
+  "allowJs",
+  "allowUnreachableCode",
+  "allowUnusedLabels",
+  "alwaysStrict",
+  "checkJs",
+  "declarationDir",
+  "emitDecoratorMetadata",
+  "experimentalDecorators",
+  "importsNotUsedAsValues",
+  "jsx",
+  "jsxFactory",
+  "jsxFragmentFactory",
+  "jsxImportSource",
+  "keyofStringsOnly",
+  "lib",
+  "locale",
+  "noFallthroughCasesInSwitch",
+  "noImplicitAny",
+  "noImplicitOverride",
+  "noImplicitReturns",
+  "noImplicitThis",
+  "noImplicitUseStrict",
+  "noPropertyAccessFromIndexSignature",
+  "noStrictGenericChecks",
+  "noUncheckedIndexedAccess",
+  "noUnusedLocals",
+  "noUnusedParameters",
+  "plugins",
+  "removeComments",
+  "strict",
+  "strictBindCallApply",
+  "strictFunctionTypes",
+  "strictNullChecks",
+  "strictPropertyInitialization",
+  "suppressExcessPropertyErrors",
+  "suppressImplicitAnyIndexErrors",
+  "types",
 ];
 
+fn get_ignored_reason(option: &str) -> Option<&'static str> {
+  IGNORED_COMPILER_OPTIONS
+    .iter()
+    .chain(IGNORED_RUNTIME_COMPILER_OPTIONS.iter())
+    .find(|(o, _)| *o == option)
+    .map(|(_, reason)| *reason)
+}
+
+/// Return the known compiler option which matches the option, ignoring case.
+fn get_known_option(option: &str) -> Option<&'static str> {
+  SUPPORTED_COMPILER_OPTIONS
+    .iter()
+    .copied()
+    .chain(IGNORED_COMPILER_OPTIONS.iter().map(|(o, _)| *o))
+    .chain(IGNORED_RUNTIME_COMPILER_OPTIONS.iter().map(|(o, _)| *o))
+    .find(|o| o.eq_ignore_ascii_case(option))
+}
+
 /// A function that works like JavaScript's `Object.assign()`.
 pub fn json_merge(a: &mut Value, b: &Value) {
   match (a, b) {
@@ -164,20 +364,32 @@ fn parse_compiler_options(
 ) -> Result<(Value, Option<IgnoredCompilerOptions>), AnyError> {
   let mut filtered: HashMap<String, Value> = HashMap::new();
   let mut items: Vec<String> = Vec::new();
+  let mut unknown: Vec<String> = Vec::new();
 
   for (key, value) in compiler_options.iter() {
     let key = key.as_str();
-    if (!is_runtime && IGNORED_COMPILER_OPTIONS.contains(&key))
-      || IGNORED_RUNTIME_COMPILER_OPTIONS.contains(&key)
+    let is_ignored = |options: &[(&str, &str)]| {
+      options.iter().any(|(option, _)| *option == key)
+    };
+    if (!is_runtime && is_ignored(IGNORED_COMPILER_OPTIONS))
+      || is_ignored(IGNORED_RUNTIME_COMPILER_OPTIONS)
     {
       items.push(key.to_string());
     } else {
+      if get_known_option(key) != Some(key) {
+        unknown.push(key.to_string());
+      }
       filtered.insert(key.to_string(), value.to_owned());
     }
   }
   let value = serde_json::to_value(filtered)?;
-  let maybe_ignored_options = if !items.is_empty() {
-    Some(IgnoredCompilerOptions { items, maybe_path })
+  let maybe_ignored_options = if !items.is_empty() || !unknown.is_empty() {
+    Some(IgnoredCompilerOptions {
+      items,
+      unknown,
+      maybe_path,
+      extended: Vec::new(),
+    })
   } else {
     None
   };
@@ -270,6 +482,13 @@ impl Serialize for TsConfig {
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
+  pub imports: Option<Value>,
+  pub scopes: Option<Value>,
+  pub integrity: Option<Value>,
+  pub types: Option<Value>,
+  pub lock: Option<String>,
+  pub cache_dir: Option<String>,
+  pub extends: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -331,14 +550,139 @@ impl ConfigFile {
   pub fn as_compiler_options(
     &self,
   ) -> Result<(Value, Option<IgnoredCompilerOptions>), AnyError> {
+    let mut visited = Vec::new();
+    let options = self.get_compiler_options(&mut visited)?;
+
+    // The options are parsed per configuration file, so that an ignored option
+    // is reported for the configuration file which sets it.
+    let mut options_by_path: HashMap<PathBuf, HashMap<String, Value>> =
+      HashMap::new();
+    for (key, (value, path)) in options {
+      options_by_path.entry(path).or_default().insert(key, value);
+    }
+    let mut value = json!({});
+    let mut ignored = Vec::new();
+    for path in visited {
+      let options = options_by_path.remove(&path).unwrap_or_default();
+      let (file_value, maybe_ignored_options) =
+        parse_compiler_options(&options, Some(path), false)?;
+      json_merge(&mut value, &file_value);
+      ignored.extend(maybe_ignored_options);
+    }
+
+    let maybe_ignored_options = match ignored.first() {
+      None => None,
+      Some(first) if first.maybe_path.as_ref() == Some(&self.path) => {
+        let mut ignored = ignored.into_iter();
+        let mut own = ignored.next().unwrap();
+        own.extended = ignored.collect();
+        Some(own)
+      }
+      Some(_) => Some(IgnoredCompilerOptions {
+        items: Vec::new(),
+        unknown: Vec::new(),
+        maybe_path: Some(self.path.to_owned()),
+        extended: ignored,
+      }),
+    };
+    Ok((value, maybe_ignored_options))
+  }
+
+  /// Return the `compilerOptions` of the configuration file, merged over the
+  /// compiler options of the configuration file it `"extends"`, if any, with
+  /// the path of the configuration file which sets each option.  The paths of
+  /// the configuration files which have been visited are used to detect
+  /// circular extends.
+  fn get_compiler_options(
+    &self,
+    visited: &mut Vec<PathBuf>,
+  ) -> Result<HashMap<String, (Value, PathBuf)>, AnyError> {
+    visited.push(self.path.clone());
+    let mut options = if let Some(extends) = &self.json.extends {
+      let config_dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+      let extends_path = config_dir.join(extends);
+      let base = ConfigFile::read(&extends_path.to_string_lossy())
+        .with_context(|| {
+          format!(
+            "Unable to read the config file extended by \"{}\".",
+            self.path.to_string_lossy()
+          )
+        })?;
+      if visited.contains(&base.path) {
+        return Err(anyhow!(
+          "The config file \"{}\" is circularly extended.",
+          base.path.to_string_lossy()
+        ));
+      }
+      base.get_compiler_options(visited)?
+    } else {
+      HashMap::new()
+    };
     if let Some(compiler_options) = self.json.compiler_options.clone() {
-      let options: HashMap<String, Value> =
+      let own_options: HashMap<String, Value> =
         serde_json::from_value(compiler_options)
           .context("compilerOptions should be an object")?;
-      parse_compiler_options(&options, Some(self.path.to_owned()), false)
-    } else {
-      Ok((json!({}), None))
+      options.extend(
+        own_options
+          .into_iter()
+          .map(|(key, value)| (key, (value, self.path.clone()))),
+      );
     }
+    Ok(options)
+  }
+
+  /// Return the path of the lock file configured in the configuration file,
+  /// resolved relative to the configuration file.
+  pub fn to_lock_file_path(&self) -> Option<PathBuf> {
+    let lock = self.json.lock.as_ref()?;
+    let config_dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+    Some(config_dir.join(lock))
+  }
+
+  /// Return the cache directory configured in the configuration file,
+  /// resolved relative to the configuration file.
+  pub fn to_cache_dir(&self) -> Option<PathBuf> {
+    let cache_dir = self.json.cache_dir.as_ref()?;
+    let config_dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+    Some(config_dir.join(cache_dir))
+  }
+
+  /// Return an import map built from the `"imports"` and `"scopes"` which are
+  /// embedded in the configuration file, or `None` if neither are present.
+  /// Specifiers in the import map are resolved relative to the configuration
+  /// file.
+  pub fn to_import_map(&self) -> Result<Option<ImportMap>, AnyError> {
+    if self.json.imports.is_none()
+      && self.json.scopes.is_none()
+      && self.json.integrity.is_none()
+      && self.json.types.is_none()
+    {
+      return Ok(None);
+    }
+    let mut import_map_json = serde_json::Map::new();
+    if let Some(imports) = &self.json.imports {
+      import_map_json.insert("imports".to_string(), imports.clone());
+    }
+    if let Some(scopes) = &self.json.scopes {
+      import_map_json.insert("scopes".to_string(), scopes.clone());
+    }
+    if let Some(integrity) = &self.json.integrity {
+      import_map_json.insert("integrity".to_string(), integrity.clone());
+    }
+    if let Some(types) = &self.json.types {
+      import_map_json.insert("types".to_string(), types.clone());
+    }
+    let base_url = Url::from_file_path(&self.path).map_err(|_| {
+      anyhow!(
+        "Unable to convert the config file path \"{}\" to a URL.",
+        self.path.to_string_lossy()
+      )
+    })?;
+    let import_map = ImportMap::from_json(
+      base_url.as_str(),
+      &Value::Object(import_map_json).to_string(),
+    )?;
+    Ok(Some(import_map))
   }
 }
 
@@ -412,11 +756,117 @@ mod tests {
       ignored,
       Some(IgnoredCompilerOptions {
         items: vec!["build".to_string()],
+        unknown: vec![],
         maybe_path: Some(config_path),
+        extended: vec![],
       }),
     );
   }
 
+  #[test]
+  fn test_parse_config_unknown_options() {
+    let config_text = r#"{
+      "compilerOptions": {
+        "strictnullchecks": true,
+        "target": "es5",
+        "foo": true,
+        "noImplicitAny": false
+      }
+    }"#;
+    let config_path = PathBuf::from("/deno/tsconfig.json");
+    let config_file = ConfigFile::new(config_text, &config_path).unwrap();
+    let (options_value, ignored) =
+      config_file.as_compiler_options().expect("error parsing");
+    assert_eq!(
+      options_value,
+      json!({
+        "strictnullchecks": true,
+        "foo": true,
+        "noImplicitAny": false,
+      })
+    );
+    let ignored = ignored.unwrap();
+    assert_eq!(ignored.items, vec!["target".to_string()]);
+    assert_eq!(ignored.unknown.len(), 2);
+    assert_eq!(
+      ignored.to_string(),
+      r#"Unsupported compiler options in "/deno/tsconfig.json".
+  The following options were ignored:
+    target - modules are emitted by Deno as ES modules for the latest JavaScript version
+  The following options are unknown, and were passed to TypeScript as they are:
+    foo
+    strictnullchecks - did you mean "strictNullChecks"?"#
+    );
+  }
+
+  #[test]
+  fn test_parse_config_extends() {
+    let config_path = std::env::current_dir()
+      .unwrap()
+      .join("tests/config_extends/tsconfig.json");
+    let config_file = ConfigFile::read(&config_path.to_string_lossy())
+      .expect("failed to load config file");
+    let (options_value, ignored) =
+      config_file.as_compiler_options().expect("error parsing");
+    assert_eq!(
+      options_value,
+      json!({
+        "checkJs": true,
+        "strict": false,
+      })
+    );
+    assert!(ignored.is_none());
+
+    let config_path = std::env::current_dir()
+      .unwrap()
+      .join("tests/config_extends/circular.json");
+    let config_file = ConfigFile::read(&config_path.to_string_lossy())
+      .expect("failed to load config file");
+    assert!(config_file.as_compiler_options().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_extends_ignored_options() {
+    let config_dir = std::env::current_dir()
+      .unwrap()
+      .join("tests/config_extends");
+    let config_file = ConfigFile::read(
+      &config_dir.join("ignored_options.json").to_string_lossy(),
+    )
+    .expect("failed to load config file");
+    let (options_value, ignored) =
+      config_file.as_compiler_options().expect("error parsing");
+    assert_eq!(options_value, json!({ "strict": true }));
+    let ignored = ignored.unwrap();
+    // The base sets `module` as well, but it is overridden.
+    assert_eq!(ignored.items, vec!["module".to_string()]);
+    assert_eq!(ignored.extended.len(), 1);
+    assert_eq!(ignored.extended[0].items, vec!["target".to_string()]);
+    assert_eq!(
+      ignored.extended[0].maybe_path,
+      Some(config_dir.join("ignored_options_base.json"))
+    );
+    assert!(ignored.extended[0].extended.is_empty());
+    assert!(ignored.to_string().contains(
+      r#"ignored_options_base.json".
+  The following options were ignored:
+    target"#
+    ));
+
+    let config_file = ConfigFile::read(
+      &config_dir
+        .join("ignored_options_in_base.json")
+        .to_string_lossy(),
+    )
+    .expect("failed to load config file");
+    let (_, ignored) =
+      config_file.as_compiler_options().expect("error parsing");
+    let ignored = ignored.unwrap();
+    assert!(ignored.items.is_empty());
+    assert_eq!(ignored.extended.len(), 1);
+    assert!(!ignored.to_string().contains("ignored_options_in_base.json"));
+  }
+
   #[test]
   fn test_parse_config_with_empty_file() {
     let config_text = "";
@@ -427,6 +877,74 @@ mod tests {
     assert!(options_value.is_object());
   }
 
+  #[test]
+  fn test_config_file_inline_import_map() {
+    let config_text = r#"{
+      "imports": {
+        "std/": "https://deno.land/std@0.100.0/",
+        "lib": "./lib/mod.ts"
+      },
+      "scopes": {
+        "./vendor/": {
+          "lib": "./vendor/lib.ts"
+        }
+      }
+    }"#;
+    let config_dir = std::env::current_dir().unwrap();
+    let config_path = config_dir.join("deno.json");
+    let config_file = ConfigFile::new(config_text, &config_path).unwrap();
+    let import_map = config_file
+      .to_import_map()
+      .expect("error parsing import map")
+      .expect("missing import map");
+    let referrer = Url::from_file_path(config_dir.join("main.ts")).unwrap();
+    assert_eq!(
+      import_map
+        .resolve("std/fs/mod.ts", referrer.as_str())
+        .unwrap(),
+      Url::parse("https://deno.land/std@0.100.0/fs/mod.ts").unwrap()
+    );
+    assert_eq!(
+      import_map.resolve("lib", referrer.as_str()).unwrap(),
+      Url::from_file_path(config_dir.join("lib/mod.ts")).unwrap()
+    );
+    let referrer =
+      Url::from_file_path(config_dir.join("vendor/mod.ts")).unwrap();
+    assert_eq!(
+      import_map.resolve("lib", referrer.as_str()).unwrap(),
+      Url::from_file_path(config_dir.join("vendor/lib.ts")).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_config_file_lock_file_path() {
+    let config_text = r#"{ "lock": "./lock.json" }"#;
+    let config_dir = std::env::current_dir().unwrap();
+    let config_file =
+      ConfigFile::new(config_text, &config_dir.join("deno.json")).unwrap();
+    assert_eq!(
+      config_file.to_lock_file_path(),
+      Some(config_dir.join("./lock.json"))
+    );
+  }
+
+  #[test]
+  fn test_config_file_cache_dir() {
+    let config_text = r#"{ "cacheDir": "./.deno" }"#;
+    let config_dir = std::env::current_dir().unwrap();
+    let config_file =
+      ConfigFile::new(config_text, &config_dir.join("deno.json")).unwrap();
+    assert_eq!(config_file.to_cache_dir(), Some(config_dir.join("./.deno")));
+  }
+
+  #[test]
+  fn test_config_file_without_import_map() {
+    let config_text = r#"{ "compilerOptions": { "strict": true } }"#;
+    let config_path = std::env::current_dir().unwrap().join("deno.json");
+    let config_file = ConfigFile::new(config_text, &config_path).unwrap();
+    assert!(config_file.to_import_map().unwrap().is_none());
+  }
+
   #[test]
   fn test_parse_config_with_commented_file() {
     let config_text = r#"//{"foo":"bar"}"#;
@@ -480,7 +998,9 @@ mod tests {
       maybe_ignored_options,
       Some(IgnoredCompilerOptions {
         items: vec!["build".to_string()],
-        maybe_path: None
+        unknown: vec![],
+        maybe_path: None,
+        extended: vec![],
       })
     );
   }
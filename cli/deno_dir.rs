@@ -1,6 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::disk_cache::DiskCache;
+use crate::http_cache::HttpCache;
 use std::path::PathBuf;
 
 /// `DenoDir` serves as coordinator for multiple `DiskCache`s containing them
@@ -11,6 +12,9 @@ pub struct DenoDir {
   pub root: PathBuf,
   /// Used by TsCompiler to cache compiler output.
   pub gen_cache: DiskCache,
+  /// The root of the `DenoDir` which is read from when something is not
+  /// present in this one, when this is the cache directory of a project.
+  pub maybe_fallback_root: Option<PathBuf>,
 }
 
 impl DenoDir {
@@ -38,11 +42,40 @@ impl DenoDir {
     let deno_dir = Self {
       root,
       gen_cache: DiskCache::new(&gen_path),
+      maybe_fallback_root: None,
     };
     deno_dir.gen_cache.ensure_dir_exists(&gen_path)?;
 
     Ok(deno_dir)
   }
+
+  /// Create a `DenoDir` in the cache directory of a project.  Remote modules
+  /// and emitted files which are not present in it are read from the
+  /// fallback `DenoDir`, which is usually the global `$DENO_DIR`.
+  pub fn with_fallback(
+    root: PathBuf,
+    fallback: &DenoDir,
+  ) -> std::io::Result<Self> {
+    let mut deno_dir = Self::new(Some(root))?;
+    if deno_dir.root != fallback.root {
+      deno_dir.gen_cache.maybe_fallback_location =
+        Some(fallback.gen_cache.location.clone());
+      deno_dir.maybe_fallback_root = Some(fallback.root.clone());
+    }
+    Ok(deno_dir)
+  }
+
+  /// Return the cache of remote modules, which falls back to the cache of the
+  /// fallback `DenoDir`, if any.
+  pub fn get_http_cache(&self) -> HttpCache {
+    let http_cache = HttpCache::new(&self.root.join("deps"));
+    match &self.maybe_fallback_root {
+      Some(fallback_root) => {
+        http_cache.with_fallback(HttpCache::new(&fallback_root.join("deps")))
+      }
+      None => http_cache,
+    }
+  }
 }
 
 /// To avoid the poorly managed dirs crate
@@ -357,7 +357,6 @@ impl fmt::Display for Diagnostic {
 pub struct Diagnostics(Vec<Diagnostic>);
 
 impl Diagnostics {
-  #[cfg(test)]
   pub fn new(diagnostics: Vec<Diagnostic>) -> Self {
     Diagnostics(diagnostics)
   }
@@ -404,6 +403,15 @@ impl Serialize for Diagnostics {
   }
 }
 
+impl IntoIterator for Diagnostics {
+  type Item = Diagnostic;
+  type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
+
 impl fmt::Display for Diagnostics {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let mut i = 0;
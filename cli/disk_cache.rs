@@ -15,6 +15,9 @@ use std::str;
 #[derive(Clone)]
 pub struct DiskCache {
   pub location: PathBuf,
+  /// The location of a cache which is read when a file is not present in
+  /// this cache, like the global cache when this is the cache of a project.
+  pub maybe_fallback_location: Option<PathBuf>,
 }
 
 fn with_io_context<T: AsRef<str>>(
@@ -30,6 +33,7 @@ impl DiskCache {
     assert!(location.is_absolute());
     Self {
       location: location.to_owned(),
+      maybe_fallback_location: None,
     }
   }
 
@@ -133,7 +137,15 @@ impl DiskCache {
 
   pub fn get(&self, filename: &Path) -> std::io::Result<Vec<u8>> {
     let path = self.location.join(filename);
-    fs::read(&path)
+    match fs::read(&path) {
+      Err(err) if err.kind() == io::ErrorKind::NotFound => {
+        match &self.maybe_fallback_location {
+          Some(fallback_location) => fs::read(fallback_location.join(filename)),
+          None => Err(err),
+        }
+      }
+      result => result,
+    }
   }
 
   pub fn set(&self, filename: &Path, data: &[u8]) -> std::io::Result<()> {
@@ -96,15 +96,25 @@ declare namespace Deno {
   export const ppid: number;
 
   export interface MemoryUsage {
+    /** The resident set size of the process. */
     rss: number;
+    /** The total size of the V8 heap of the current isolate. */
     heapTotal: number;
+    /** The used size of the V8 heap of the current isolate. */
     heapUsed: number;
+    /** The memory used by objects outside of the V8 heap which are bound to
+     * JavaScript objects, such as the contents of `ArrayBuffer`s. */
     external: number;
   }
 
   /**
    * Returns an object describing the memory usage of the Deno process measured
-   * in bytes.
+   * in bytes. The heap statistics are those of the isolate of the calling
+   * thread, so they differ between the main thread and workers.
+   *
+   * ```ts
+   * const { rss, heapUsed } = Deno.memoryUsage();
+   * ```
    */
   export function memoryUsage(): MemoryUsage;
 
@@ -187,7 +197,7 @@ declare namespace Deno {
   export function test(name: string, fn: () => void | Promise<void>): void;
 
   /** Exit the Deno process with optional exit code. If no exit code is supplied
-   * then Deno will exit with return code of 0.
+   * then Deno will exit with `Deno.exitCode`, which is 0 unless it was set.
    *
    * ```ts
    * Deno.exit(5);
@@ -195,6 +205,18 @@ declare namespace Deno {
    */
   export function exit(code?: number): never;
 
+  /** The code the process exits with when it finishes without calling
+   * `Deno.exit()` with a code. Setting it doesn't exit the process, so
+   * cleanup work, like `unload` listeners, still runs.
+   *
+   * ```ts
+   * if (!ok) {
+   *   Deno.exitCode = 1;
+   * }
+   * ```
+   */
+  export let exitCode: number;
+
   export const env: {
     /** Retrieve the value of an environment variable. Returns `undefined` if that
      * key doesn't exist.
@@ -806,6 +828,13 @@ declare namespace Deno {
       SeekerSync,
       Closer {
     readonly rid: number;
+    /** A stream of the contents read from the file, which closes the file
+     * when it ends. Piping it into the `writable` of another file, a
+     * connection or stdio is done without the chunks going through JS. */
+    readonly readable: ReadableStream<Uint8Array>;
+    /** A stream of chunks which are written to the file, which closes the
+     * file when it is closed. */
+    readonly writable: WritableStream<Uint8Array>;
     constructor(rid: number);
     write(p: Uint8Array): Promise<number>;
     writeSync(p: Uint8Array): number;
@@ -821,11 +850,20 @@ declare namespace Deno {
   }
 
   /** A handle for `stdin`. */
-  export const stdin: Reader & ReaderSync & Closer & { readonly rid: number };
+  export const stdin: Reader & ReaderSync & Closer & {
+    readonly rid: number;
+    readonly readable: ReadableStream<Uint8Array>;
+  };
   /** A handle for `stdout`. */
-  export const stdout: Writer & WriterSync & Closer & { readonly rid: number };
+  export const stdout: Writer & WriterSync & Closer & {
+    readonly rid: number;
+    readonly writable: WritableStream<Uint8Array>;
+  };
   /** A handle for `stderr`. */
-  export const stderr: Writer & WriterSync & Closer & { readonly rid: number };
+  export const stderr: Writer & WriterSync & Closer & {
+    readonly rid: number;
+    readonly writable: WritableStream<Uint8Array>;
+  };
 
   export interface OpenOptions {
     /** Sets the option for read access. This option, when `true`, means that the
@@ -1805,6 +1843,12 @@ declare namespace Deno {
     readonly remoteAddr: Addr;
     /** The resource ID of the connection. */
     readonly rid: number;
+    /** A stream of the data received on the connection. Piping it into the
+     * `writable` of a file, another connection or stdio is done without the
+     * chunks going through JS. */
+    readonly readable: ReadableStream<Uint8Array>;
+    /** A stream of chunks which are sent on the connection. */
+    readonly writable: WritableStream<Uint8Array>;
     /** Shuts down (`shutdown(2)`) the write side of the connection. Most
      * callers should just use `close()`. */
     closeWrite(): Promise<void>;
@@ -2210,6 +2254,7 @@ declare namespace Deno {
     | "net"
     | "env"
     | "plugin"
+    | "ffi"
     | "hrtime";
 
   /** The current status of the permission. */
@@ -2249,6 +2294,10 @@ declare namespace Deno {
     name: "plugin";
   }
 
+  export interface FfiPermissionDescriptor {
+    name: "ffi";
+  }
+
   export interface HrtimePermissionDescriptor {
     name: "hrtime";
   }
@@ -2262,6 +2311,7 @@ declare namespace Deno {
     | NetPermissionDescriptor
     | EnvPermissionDescriptor
     | PluginPermissionDescriptor
+    | FfiPermissionDescriptor
     | HrtimePermissionDescriptor;
 
   export interface PermissionStatusEventMap {
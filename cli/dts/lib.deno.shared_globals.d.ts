@@ -364,6 +364,22 @@ declare class ErrorEvent extends Event {
   constructor(type: string, eventInitDict?: ErrorEventInit);
 }
 
+interface PromiseRejectionEventInit extends EventInit {
+  promise: Promise<any>;
+  reason?: any;
+}
+
+/** Dispatched on the global scope when a promise is rejected without a
+ * handler ("unhandledrejection"), and when a handler is attached to such a
+ * promise later on ("rejectionhandled"). Calling `preventDefault()` on an
+ * "unhandledrejection" event keeps the rejection from terminating the
+ * program. */
+declare class PromiseRejectionEvent extends Event {
+  readonly promise: Promise<any>;
+  readonly reason: any;
+  constructor(type: string, eventInitDict: PromiseRejectionEventInit);
+}
+
 interface AbstractWorkerEventMap {
   "error": ErrorEvent;
 }
@@ -528,3 +544,18 @@ interface ErrorConstructor {
   // TODO(nayeemrmn): Support `Error.prepareStackTrace()`. We currently use this
   // internally in a way that makes it unavailable for users.
 }
+
+interface Atomics {
+  /** Like `Atomics.wait()`, but instead of blocking, returns a promise that
+   * resolves when the element is notified or the timeout in milliseconds
+   * elapses. If the element doesn't hold `value` or the timeout is 0, the
+   * result is returned right away instead. */
+  waitAsync(
+    typedArray: Int32Array | BigInt64Array,
+    index: number,
+    value: number | bigint,
+    timeout?: number,
+  ):
+    | { async: false; value: "not-equal" | "timed-out" }
+    | { async: true; value: Promise<"ok" | "timed-out"> };
+}
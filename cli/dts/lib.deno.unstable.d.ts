@@ -53,6 +53,34 @@ declare namespace Deno {
    */
   export function loadavg(): number[];
 
+  export interface DrainOptions {
+    /** Milliseconds to wait for pending work before exiting anyway. Defaults
+     * to `5000`. */
+    deadline?: number;
+    /** The exit code used when the deadline is reached. Defaults to
+     * `Deno.exitCode`. */
+    code?: number;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Requests a graceful exit. The process keeps running until its pending
+   * work has finished, at which point `beforeunload` (no longer cancelable)
+   * and `unload` are dispatched as on any natural exit. If work is still
+   * pending once `deadline` milliseconds have passed, the process exits as
+   * with `Deno.exit(code)`, which still dispatches `unload`.
+   *
+   * ```ts
+   * const listener = Deno.listen({ port: 8000 });
+   * addEventListener("unload", () => flushLogs());
+   * Deno.signal(Deno.Signal.SIGTERM).then(() => {
+   *   listener.close();
+   *   Deno.drain({ deadline: 10_000 });
+   * });
+   * ```
+   */
+  export function drain(options?: DrainOptions): void;
+
   /** **Unstable** new API. yet to be vetted. Under consideration to possibly move to
    * Deno.build or Deno.versions and if it should depend sys-info, which may not
    * be desireable.
@@ -159,6 +187,126 @@ declare namespace Deno {
    */
   export function openPlugin(filename: string): number;
 
+  /** All possible types for interfacing with foreign functions. Pointers are
+   * passed and returned as numbers, and a `"buffer"` parameter is passed a
+   * typed array whose contents are passed by pointer. */
+  export type NativeType =
+    | "void"
+    | "u8"
+    | "i8"
+    | "u16"
+    | "i16"
+    | "u32"
+    | "i32"
+    | "u64"
+    | "i64"
+    | "usize"
+    | "isize"
+    | "f32"
+    | "f64"
+    | "pointer"
+    | "buffer";
+
+  /** A foreign function as defined by its parameter and result types.
+   *
+   * Integer parameters can be passed as numbers or as `BigInt`s, and `u64`
+   * and `i64` results are returned as `BigInt`s, so that they are not rounded
+   * to the precision of a number. */
+  export interface ForeignFunction {
+    parameters: NativeType[];
+    result: NativeType;
+  }
+
+  /** A dynamic library resource. */
+  export interface DynamicLibrary<S extends Record<string, ForeignFunction>> {
+    /** All of the registered symbols along with functions for calling them. */
+    symbols: { [K in keyof S]: (...args: unknown[]) => unknown };
+
+    close(): void;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Opens a dynamic library and registers symbols.
+   *
+   * ```ts
+   * const lib = Deno.dlopen("./libadd.so", {
+   *   add: { parameters: ["i32", "i32"], result: "i32" },
+   * });
+   * console.log(lib.symbols.add(1, 2)); // 3
+   * lib.close();
+   * ```
+   *
+   * Requires `allow-ffi` permission.
+   */
+  export function dlopen<S extends Record<string, ForeignFunction>>(
+    filename: string,
+    symbols: S,
+  ): DynamicLibrary<S>;
+
+  /** A node of the call tree of a `CpuProfile`. */
+  export interface CpuProfileNode {
+    id: number;
+    callFrame: {
+      functionName: string;
+      scriptId: string;
+      url: string;
+      lineNumber: number;
+      columnNumber: number;
+    };
+    hitCount?: number;
+    children?: number[];
+  }
+
+  /** A CPU profile in the format of the `Profiler` domain of the Chrome
+   * DevTools Protocol, which is also the format of `.cpuprofile` files. */
+  export interface CpuProfile {
+    nodes: CpuProfileNode[];
+    /** The start time of the profile in microseconds. */
+    startTime: number;
+    /** The end time of the profile in microseconds. */
+    endTime: number;
+    /** The ids of the sampled nodes. */
+    samples?: number[];
+    /** The intervals between the samples in microseconds. */
+    timeDeltas?: number[];
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Controls the V8 CPU profiler of the current thread through the inspector.
+   * The returned profile can be written to a `.cpuprofile` file and loaded in
+   * the Performance panel of Chrome DevTools.
+   *
+   * ```ts
+   * await Deno.Profiler.start();
+   * doWork();
+   * const profile = await Deno.Profiler.stop();
+   * await Deno.writeTextFile("work.cpuprofile", JSON.stringify(profile));
+   * ```
+   */
+  export const Profiler: {
+    /** Starts the profiler. Rejects if it is already running. A running
+     * profiler doesn't keep the program alive, its profile is discarded when
+     * the program exits without calling `stop()`. */
+    start(): Promise<void>;
+    /** Stops the profiler and resolves with the recorded profile. */
+    stop(): Promise<CpuProfile>;
+  };
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Writes a snapshot of the V8 heap of the current thread to the given path,
+   * which can be loaded in the Memory panel of Chrome DevTools. The file
+   * conventionally has the `.heapsnapshot` extension.
+   *
+   * ```ts
+   * await Deno.heapSnapshot("./server.heapsnapshot");
+   * ```
+   *
+   * Requires `allow-write` permission. */
+  export function heapSnapshot(path: string | URL): Promise<void>;
+
   /** The log category for a diagnostic message. */
   export enum DiagnosticCategory {
     Warning = 0,
@@ -489,6 +637,13 @@ declare namespace Deno {
     /** A record of sources to use when doing the emit.  If provided, Deno will
      * use these sources instead of trying to resolve the modules externally. */
     sources?: Record<string, string>;
+    /** If `true`, a `"module"` bundle is split into a bundle of the root
+     * module in `deno:///bundle.js` and a bundle of each module which is
+     * dynamically imported, named like `deno:///chunk-1.js`, where the dynamic
+     * imports are rewritten to import the bundles.  Modules which are
+     * statically imported by more than one bundle are included in each of
+     * them.  Requires `bundle` to be `"module"`.  Defaults to `false`. */
+    splitting?: boolean;
   }
 
   /**
@@ -504,7 +659,8 @@ declare namespace Deno {
     diagnostics: Diagnostic[];
     /** Any emitted files.  If bundled, then the JavaScript will have the
      * key of `deno:///bundle.js` with an optional map (based on
-     * `compilerOptions`) in `deno:///bundle.js.map`. */
+     * `compilerOptions`) in `deno:///bundle.js.map`.  If the bundle is split,
+     * each of the other bundles has its own map as well. */
     files: Record<string, string>;
     /** An optional array of any compiler options that were ignored by Deno. */
     ignoredOptions?: string[];
@@ -704,6 +860,41 @@ declare namespace Deno {
    */
   export function signal(signo: number): SignalStream;
 
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Registers the given function as a listener of the given signal. Unlike
+   * the streams returned by `Deno.signal()`, any number of listeners can be
+   * registered for the same signal, and each of them is called every time
+   * the signal is received. Registering a listener twice has no effect.
+   *
+   * ```ts
+   * const listener = () => {
+   *   console.log("SIGTERM!")
+   * };
+   * Deno.addSignalListener(Deno.Signal.SIGTERM, listener);
+   * ```
+   *
+   * NOTE: This functionality is not yet implemented on Windows.
+   */
+  export function addSignalListener(signo: number, listener: () => void): void;
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Removes a listener registered with `Deno.addSignalListener()`. Once the
+   * last listener of a signal is removed, it no longer keeps the process
+   * alive.
+   *
+   * ```ts
+   * Deno.removeSignalListener(Deno.Signal.SIGTERM, listener);
+   * ```
+   *
+   * NOTE: This functionality is not yet implemented on Windows.
+   */
+  export function removeSignalListener(
+    signo: number,
+    listener: () => void,
+  ): void;
+
   /** **UNSTABLE**: new API, yet to be vetted. */
   export const signals: {
     /** Returns the stream of SIGALRM signals.
@@ -1168,6 +1359,37 @@ declare namespace Deno {
     ops: Record<string, OpMetrics>;
   }
 
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * The name of an open resource together with the details its
+   * implementation reports about it, like the path of a file, the addresses
+   * of a connection or the pid of a child process. */
+  export interface ResourceInfo {
+    name: string;
+    // deno-lint-ignore no-explicit-any
+    metadata: Record<string, any> | null;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * Returns a map of open resource ids (rid) along with their names and
+   * metadata. Like the resource names, the metadata is for debugging and its
+   * shape can change any time.
+   *
+   * ```ts
+   * Deno.openSync("./test.file");
+   * console.log(Deno.resources({ metadata: true }));
+   * // {
+   * //   0: { name: "stdin", metadata: null },
+   * //   ...
+   * //   3: { name: "fsFile", metadata: { path: "./test.file" } }
+   * // }
+   * ```
+   */
+  export function resources(
+    options: { metadata: true },
+  ): Record<number, ResourceInfo>;
+
   export interface OpMetrics {
     opsDispatched: number;
     opsDispatchedSync: number;
@@ -1180,6 +1402,23 @@ declare namespace Deno {
     bytesSentControl: number;
     bytesSentData: number;
     bytesReceived: number;
+    /** The latencies of the op, only present for the metrics of individual
+     * ops when running with `--op-latency-metrics`. */
+    latency?: OpLatency;
+  }
+
+  /** **UNSTABLE**: new API, yet to be vetted.
+   *
+   * A summary of the latencies of an op, in milliseconds. The percentiles are
+   * approximated by a histogram with power of two buckets. */
+  export interface OpLatency {
+    count: number;
+    total: number;
+    mean: number;
+    p50: number;
+    p90: number;
+    p99: number;
+    max: number;
   }
 
   export interface RequestEvent {
@@ -1321,6 +1560,15 @@ declare namespace Deno {
       */
       plugin?: "inherit" | boolean;
 
+      /** Specifies if the `ffi` permission should be requested or revoked.
+      * If set to `"inherit"`, the current `ffi` permission will be inherited.
+      * If set to `true`, the global `ffi` permission will be requested.
+      * If set to `false`, the global `ffi` permission will be revoked.
+      *
+      * Defaults to "inherit".
+      */
+      ffi?: "inherit" | boolean;
+
       /** Specifies if the `read` permission should be requested or revoked.
       * If set to `"inherit"`, the current `read` permission will be inherited.
       * If set to `true`, the global `read` permission will be requested.
@@ -1399,6 +1647,7 @@ declare interface WorkerOptions {
     /** Set to `"none"` to disable all the permissions in the worker. */
     permissions?: "inherit" | "none" | {
       env?: "inherit" | boolean;
+      ffi?: "inherit" | boolean;
       hrtime?: "inherit" | boolean;
       /** The format of the net access list must be `hostname[:port]`
        * in order to be resolved.
@@ -1411,5 +1660,16 @@ declare interface WorkerOptions {
       run?: "inherit" | boolean;
       write?: "inherit" | boolean | Array<string | URL>;
     };
+    /** Limits on the resources the worker can use. */
+    resourceLimits?: {
+      /** The size the worker's heap can grow to, in megabytes. A worker that
+       * exceeds it is terminated, and an "error" event is dispatched on it. */
+      maxHeapSizeMb?: number;
+      /** The stack size of the worker's native thread, in megabytes. Must be
+       * at least 2. This doesn't change how deep JavaScript can recurse, which
+       * is limited by V8's own stack limit. That can be raised with
+       * `--v8-flags=--stack-size`, as long as it fits in this. */
+      stackSizeMb?: number;
+    };
   };
 }
@@ -5,6 +5,7 @@
 /// <reference lib="deno.shared_globals" />
 /// <reference lib="deno.webgpu" />
 /// <reference lib="deno.webstorage" />
+/// <reference lib="deno.cache" />
 /// <reference lib="esnext" />
 
 declare class Window extends EventTarget {
@@ -12,7 +13,14 @@ declare class Window extends EventTarget {
   readonly window: Window & typeof globalThis;
   readonly self: Window & typeof globalThis;
   onload: ((this: Window, ev: Event) => any) | null;
+  onbeforeunload: ((this: Window, ev: Event) => any) | null;
   onunload: ((this: Window, ev: Event) => any) | null;
+  onunhandledrejection:
+    | ((this: Window, ev: PromiseRejectionEvent) => any)
+    | null;
+  onrejectionhandled:
+    | ((this: Window, ev: PromiseRejectionEvent) => any)
+    | null;
   close: () => void;
   readonly closed: boolean;
   alert: (message?: string) => void;
@@ -30,7 +38,14 @@ declare class Window extends EventTarget {
 declare var window: Window & typeof globalThis;
 declare var self: Window & typeof globalThis;
 declare var onload: ((this: Window, ev: Event) => any) | null;
+declare var onbeforeunload: ((this: Window, ev: Event) => any) | null;
 declare var onunload: ((this: Window, ev: Event) => any) | null;
+declare var onunhandledrejection:
+  | ((this: Window, ev: PromiseRejectionEvent) => any)
+  | null;
+declare var onrejectionhandled:
+  | ((this: Window, ev: PromiseRejectionEvent) => any)
+  | null;
 declare var localStorage: Storage;
 declare var sessionStorage: Storage;
 
@@ -42,14 +57,14 @@ declare class Navigator {
 declare var navigator: Navigator;
 
 /**
- * Shows the given message and waits for the enter key pressed.
+ * Shows the given message on stderr and waits for the enter key pressed.
  * If the stdin is not interactive, it does nothing.
  * @param message
  */
 declare function alert(message?: string): void;
 
 /**
- * Shows the given message and waits for the answer. Returns the user's answer as boolean.
+ * Shows the given message on stderr and waits for the answer. Returns the user's answer as boolean.
  * Only `y` and `Y` are considered as true.
  * If the stdin is not interactive, it returns false.
  * @param message
@@ -57,11 +72,11 @@ declare function alert(message?: string): void;
 declare function confirm(message?: string): boolean;
 
 /**
- * Shows the given message and waits for the user's input. Returns the user's input as string.
+ * Shows the given message on stderr and waits for the user's input. Returns the user's input as string.
  * If the default value is given and the user inputs the empty string, then it returns the given
  * default value.
  * If the default value is not given and the user inputs the empty string, it returns null.
- * If the stdin is not interactive, it returns null.
+ * If the stdin is not interactive or reaches EOF, it returns null.
  * @param message
  * @param defaultValue
  */
@@ -4,6 +4,7 @@
 /// <reference lib="deno.ns" />
 /// <reference lib="deno.shared_globals" />
 /// <reference lib="deno.webgpu" />
+/// <reference lib="deno.cache" />
 /// <reference lib="esnext" />
 
 interface WorkerGlobalScopeEventMap {
@@ -14,6 +15,12 @@ declare class WorkerGlobalScope extends EventTarget {
   readonly location: WorkerLocation;
   readonly navigator: WorkerNavigator;
   onerror: ((this: WorkerGlobalScope, ev: ErrorEvent) => any) | null;
+  onunhandledrejection:
+    | ((this: WorkerGlobalScope, ev: PromiseRejectionEvent) => any)
+    | null;
+  onrejectionhandled:
+    | ((this: WorkerGlobalScope, ev: PromiseRejectionEvent) => any)
+    | null;
 
   readonly self: WorkerGlobalScope & typeof globalThis;
 
@@ -110,6 +117,12 @@ declare var navigator: WorkerNavigator;
 declare var onerror:
   | ((this: DedicatedWorkerGlobalScope, ev: ErrorEvent) => any)
   | null;
+declare var onunhandledrejection:
+  | ((this: DedicatedWorkerGlobalScope, ev: PromiseRejectionEvent) => any)
+  | null;
+declare var onrejectionhandled:
+  | ((this: DedicatedWorkerGlobalScope, ev: PromiseRejectionEvent) => any)
+  | null;
 declare var self: WorkerGlobalScope & typeof globalThis;
 declare function addEventListener<
   K extends keyof DedicatedWorkerGlobalScopeEventMap,
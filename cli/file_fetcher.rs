@@ -7,9 +7,12 @@ use crate::http_util::create_http_client;
 use crate::http_util::fetch_once;
 use crate::http_util::FetchOnceArgs;
 use crate::http_util::FetchOnceResult;
+use crate::http_util::ProxyConfig;
 use crate::media_type::MediaType;
+use crate::specifier_handler::HandlerError;
 use crate::text_encoding;
 use crate::version::get_user_agent;
+use crate::wasm;
 use data_url::DataUrl;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
@@ -23,18 +26,30 @@ use deno_runtime::deno_web::BlobUrlStore;
 use deno_runtime::permissions::Permissions;
 use log::debug;
 use log::info;
+use regex::Regex;
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::fs;
 use std::future::Future;
 use std::io::Read;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 static DENO_AUTH_TOKENS: &str = "DENO_AUTH_TOKENS";
+static DENO_DOWNLOAD_CONCURRENCY: &str = "DENO_DOWNLOAD_CONCURRENCY";
+const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 16;
+/// The number of times a download is retried after a transient network or
+/// server error.
+const MAX_DOWNLOAD_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY_MS: u64 = 250;
 pub const SUPPORTED_SCHEMES: [&str; 5] =
   ["data", "blob", "file", "http", "https"];
 
@@ -52,6 +67,11 @@ pub struct File {
   pub media_type: MediaType,
   /// The source of the file as a string.
   pub source: String,
+  /// The bytes of the file as they were fetched, when the source differs from
+  /// them, for example because a shebang was stripped or because the file is
+  /// a Wasm module which the source is generated from. The integrity of the
+  /// file is verified against these bytes.
+  pub maybe_raw_source: Option<Arc<Vec<u8>>>,
   /// The _final_ specifier for the file.  The requested specifier and the final
   /// specifier maybe different for remote files that have been redirected.
   pub specifier: ModuleSpecifier,
@@ -85,13 +105,83 @@ pub enum CacheSetting {
   ReloadAll,
   /// Only some cached resources should be used.  This is the equivalent of
   /// `--reload=https://deno.land/std` or
-  /// `--reload=https://deno.land/std,https://deno.land/x/example`.
-  ReloadSome(Vec<String>),
+  /// `--reload=https://deno.land/std,https://deno.land/x/example`.  Entries
+  /// can also be patterns, see `parse_reload_pattern`.
+  ReloadSome(ReloadList),
   /// The cached source files should be used for local modules.  This is the
   /// default behavior of the CLI.
   Use,
 }
 
+/// Parse an entry of `--reload` which is a pattern into a regular expression,
+/// returning `None` if the entry is a URL.  An entry which is enclosed in
+/// slashes, like `/^https:\/\/deno\.land\/x\//`, is a regular expression, and
+/// an entry which contains a `*` is a glob, where `*` matches within a path
+/// segment and `**` matches across path segments.  Patterns are matched
+/// against the whole URL of a module, without its fragment.
+pub fn parse_reload_pattern(
+  entry: &str,
+) -> Option<Result<Regex, regex::Error>> {
+  if entry.len() > 2 && entry.starts_with('/') && entry.ends_with('/') {
+    return Some(Regex::new(&entry[1..entry.len() - 1]));
+  }
+  if !entry.contains('*') {
+    return None;
+  }
+  let mut pattern = String::from("^");
+  let mut chars = entry.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c == '*' {
+      if chars.peek() == Some(&'*') {
+        chars.next();
+        pattern.push_str(".*");
+      } else {
+        pattern.push_str("[^/]*");
+      }
+    } else {
+      pattern.push_str(&regex::escape(&c.to_string()));
+    }
+  }
+  pattern.push('$');
+  Some(Regex::new(&pattern))
+}
+
+/// The entries of `--reload` when only some modules are reloaded, with the
+/// entries which are patterns compiled once, when the list is built.
+#[derive(Debug, Clone)]
+pub struct ReloadList {
+  urls: Vec<String>,
+  patterns: Vec<Regex>,
+}
+
+impl ReloadList {
+  pub fn new(entries: &[String]) -> Result<Self, regex::Error> {
+    let mut urls = Vec::new();
+    let mut patterns = Vec::new();
+    for entry in entries {
+      match parse_reload_pattern(entry) {
+        Some(pattern) => patterns.push(pattern?),
+        None => urls.push(entry.clone()),
+      }
+    }
+    Ok(Self { urls, patterns })
+  }
+}
+
+impl PartialEq for ReloadList {
+  fn eq(&self, other: &Self) -> bool {
+    self.urls == other.urls
+      && self.patterns.len() == other.patterns.len()
+      && self
+        .patterns
+        .iter()
+        .zip(other.patterns.iter())
+        .all(|(a, b)| a.as_str() == b.as_str())
+  }
+}
+
+impl Eq for ReloadList {}
+
 impl CacheSetting {
   /// Returns if the cache should be used for a given specifier.
   pub fn should_use(&self, specifier: &ModuleSpecifier) -> bool {
@@ -101,13 +191,16 @@ impl CacheSetting {
       CacheSetting::ReloadSome(list) => {
         let mut url = specifier.clone();
         url.set_fragment(None);
-        if list.contains(&url.as_str().to_string()) {
+        if list.urls.contains(&url.as_str().to_string()) {
+          return false;
+        }
+        if list.patterns.iter().any(|re| re.is_match(url.as_str())) {
           return false;
         }
         url.set_query(None);
         let mut path = PathBuf::from(url.as_str());
         loop {
-          if list.contains(&path.to_str().unwrap().to_string()) {
+          if list.urls.contains(&path.to_str().unwrap().to_string()) {
             return false;
           }
           if !path.pop() {
@@ -120,21 +213,39 @@ impl CacheSetting {
   }
 }
 
+/// Returns the fetched bytes of a file, unless they are the same as its source.
+fn get_raw_source_if_changed(
+  bytes: Vec<u8>,
+  source: &str,
+) -> Option<Arc<Vec<u8>>> {
+  if bytes == source.as_bytes() {
+    None
+  } else {
+    Some(Arc::new(bytes))
+  }
+}
+
 /// Fetch a source file from the local file system.
 fn fetch_local(specifier: &ModuleSpecifier) -> Result<File, AnyError> {
   let local = specifier.to_file_path().map_err(|_| {
     uri_error(format!("Invalid file path.\n  Specifier: {}", specifier))
   })?;
   let bytes = fs::read(local.clone())?;
-  let charset = text_encoding::detect_charset(&bytes).to_string();
-  let source = strip_shebang(get_source_from_bytes(bytes, Some(charset))?);
   let media_type = MediaType::from(specifier);
+  let source = if media_type == MediaType::Wasm {
+    wasm::generate_module_source(&bytes)?
+  } else {
+    let charset = text_encoding::detect_charset(&bytes).to_string();
+    strip_shebang(get_source_from_bytes(bytes.clone(), Some(charset))?)
+  };
+  let maybe_raw_source = get_raw_source_if_changed(bytes, &source);
 
   Ok(File {
     local,
     maybe_types: None,
     media_type,
     source,
+    maybe_raw_source,
     specifier: specifier.clone(),
   })
 }
@@ -203,6 +314,93 @@ pub fn strip_shebang(mut value: String) -> String {
   value
 }
 
+/// The error of fetching a remote module which is not in the cache, when only
+/// cached modules are allowed to be used.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NotCachedError {
+  pub specifier: ModuleSpecifier,
+}
+
+impl fmt::Display for NotCachedError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Specifier not found in cache: \"{}\", --cached-only is specified.",
+      self.specifier
+    )
+  }
+}
+
+impl std::error::Error for NotCachedError {}
+
+/// Returns `true` if the error is a `NotCachedError`, also when the location
+/// of the import was added to it.
+pub fn is_not_cached_error(err: &AnyError) -> bool {
+  err.downcast_ref::<NotCachedError>().is_some()
+    || matches!(
+      err.downcast_ref::<HandlerError>(),
+      Some(HandlerError::NotCachedWithLocation(..))
+    )
+}
+
+/// Return the delay before retrying a download for the nth time, doubling the
+/// delay with each retry.
+fn get_retry_delay(retries: u32) -> Duration {
+  Duration::from_millis(RETRY_BASE_DELAY_MS * 2u64.pow(retries))
+}
+
+/// Return the maximum number of concurrent downloads, which can be configured
+/// with the `DENO_DOWNLOAD_CONCURRENCY` environment variable.
+fn get_download_concurrency(maybe_value: Option<String>) -> usize {
+  maybe_value
+    .and_then(|value| value.parse::<usize>().ok())
+    .filter(|value| *value > 0)
+    .unwrap_or(DEFAULT_DOWNLOAD_CONCURRENCY)
+}
+
+/// Counts the started and finished downloads of remote modules, which is
+/// displayed as the progress of the downloads when stderr is a terminal.
+#[derive(Debug, Clone, Default)]
+struct DownloadProgress {
+  started: Arc<AtomicUsize>,
+  finished: Arc<AtomicUsize>,
+  show: bool,
+}
+
+impl DownloadProgress {
+  fn new(show: bool) -> Self {
+    Self {
+      show,
+      ..Default::default()
+    }
+  }
+
+  /// Start a download, which is finished when the returned value is dropped.
+  fn start(&self) -> Download {
+    self.started.fetch_add(1, Ordering::SeqCst);
+    Download(self.finished.clone())
+  }
+}
+
+impl fmt::Display for DownloadProgress {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}/{}",
+      self.finished.load(Ordering::SeqCst),
+      self.started.load(Ordering::SeqCst)
+    )
+  }
+}
+
+struct Download(Arc<AtomicUsize>);
+
+impl Drop for Download {
+  fn drop(&mut self) {
+    self.0.fetch_add(1, Ordering::SeqCst);
+  }
+}
+
 /// A structure for resolving, fetching and caching source files.
 #[derive(Debug, Clone)]
 pub struct FileFetcher {
@@ -210,6 +408,8 @@ pub struct FileFetcher {
   allow_remote: bool,
   cache: FileCache,
   cache_setting: CacheSetting,
+  download_permits: Arc<Semaphore>,
+  download_progress: DownloadProgress,
   http_cache: HttpCache,
   http_client: reqwest::Client,
   blob_url_store: BlobUrlStore,
@@ -221,6 +421,7 @@ impl FileFetcher {
     cache_setting: CacheSetting,
     allow_remote: bool,
     ca_data: Option<Vec<u8>>,
+    maybe_proxy_config: Option<ProxyConfig>,
     blob_url_store: BlobUrlStore,
   ) -> Result<Self, AnyError> {
     Ok(Self {
@@ -228,8 +429,16 @@ impl FileFetcher {
       allow_remote,
       cache: Default::default(),
       cache_setting,
+      download_permits: Arc::new(Semaphore::new(get_download_concurrency(
+        env::var(DENO_DOWNLOAD_CONCURRENCY).ok(),
+      ))),
+      download_progress: DownloadProgress::new(atty::is(atty::Stream::Stderr)),
       http_cache,
-      http_client: create_http_client(get_user_agent(), ca_data)?,
+      http_client: create_http_client(
+        get_user_agent(),
+        ca_data,
+        maybe_proxy_config,
+      )?,
       blob_url_store,
     })
   }
@@ -251,7 +460,12 @@ impl FileFetcher {
     let maybe_content_type = headers.get("content-type").cloned();
     let (media_type, maybe_charset) =
       map_content_type(specifier, maybe_content_type);
-    let source = strip_shebang(get_source_from_bytes(bytes, maybe_charset)?);
+    let source = if media_type == MediaType::Wasm {
+      wasm::generate_module_source(&bytes)?
+    } else {
+      strip_shebang(get_source_from_bytes(bytes.clone(), maybe_charset)?)
+    };
+    let maybe_raw_source = get_raw_source_if_changed(bytes, &source);
     let maybe_types = match media_type {
       MediaType::JavaScript | MediaType::Jsx => {
         headers.get("x-typescript-types").cloned()
@@ -264,6 +478,7 @@ impl FileFetcher {
       maybe_types,
       media_type,
       source,
+      maybe_raw_source,
       specifier: specifier.clone(),
     })
   }
@@ -318,13 +533,12 @@ impl FileFetcher {
     }
 
     if self.cache_setting == CacheSetting::Only {
-      return Err(custom_error(
-        "NotFound",
-        format!(
-          "Specifier not found in cache: \"{}\", --cached-only is specified.",
-          specifier
-        ),
-      ));
+      return Err(
+        NotCachedError {
+          specifier: specifier.clone(),
+        }
+        .into(),
+      );
     }
 
     let data_url = DataUrl::process(specifier.as_str())
@@ -334,7 +548,7 @@ impl FileFetcher {
     let (bytes, _) = data_url
       .decode_to_vec()
       .map_err(|e| uri_error(format!("{:?}", e)))?;
-    let source = strip_shebang(get_source_from_bytes(bytes, charset)?);
+    let source = strip_shebang(get_source_from_bytes(bytes.clone(), charset)?);
     let content_type = format!("{}", mime);
     let (media_type, _) =
       map_content_type(specifier, Some(content_type.clone()));
@@ -348,13 +562,17 @@ impl FileFetcher {
         })?;
     let mut headers = HashMap::new();
     headers.insert("content-type".to_string(), content_type);
-    self.http_cache.set(specifier, headers, source.as_bytes())?;
+    // The fetched bytes are cached rather than the source, like the responses
+    // of remote modules, so that the cached file has the same integrity.
+    self.http_cache.set(specifier, headers, &bytes)?;
+    let maybe_raw_source = get_raw_source_if_changed(bytes, &source);
 
     Ok(File {
       local,
       maybe_types: None,
       media_type,
       source,
+      maybe_raw_source,
       specifier: specifier.clone(),
     })
   }
@@ -372,13 +590,12 @@ impl FileFetcher {
     }
 
     if self.cache_setting == CacheSetting::Only {
-      return Err(custom_error(
-        "NotFound",
-        format!(
-          "Specifier not found in cache: \"{}\", --cached-only is specified.",
-          specifier
-        ),
-      ));
+      return Err(
+        NotCachedError {
+          specifier: specifier.clone(),
+        }
+        .into(),
+      );
     }
 
     let blob_url_storage = self.blob_url_store.borrow();
@@ -393,8 +610,9 @@ impl FileFetcher {
 
     let (media_type, maybe_charset) =
       map_content_type(specifier, Some(content_type.clone()));
+    let bytes = blob.data;
     let source =
-      strip_shebang(get_source_from_bytes(blob.data, maybe_charset)?);
+      strip_shebang(get_source_from_bytes(bytes.clone(), maybe_charset)?);
 
     let local =
       self
@@ -405,13 +623,17 @@ impl FileFetcher {
         })?;
     let mut headers = HashMap::new();
     headers.insert("content-type".to_string(), content_type);
-    self.http_cache.set(specifier, headers, source.as_bytes())?;
+    // The fetched bytes are cached rather than the source, like the responses
+    // of remote modules, so that the cached file has the same integrity.
+    self.http_cache.set(specifier, headers, &bytes)?;
+    let maybe_raw_source = get_raw_source_if_changed(bytes, &source);
 
     Ok(File {
       local,
       maybe_types: None,
       media_type,
       source,
+      maybe_raw_source,
       specifier: specifier.clone(),
     })
   }
@@ -449,55 +671,95 @@ impl FileFetcher {
     }
 
     if self.cache_setting == CacheSetting::Only {
-      return futures::future::err(custom_error(
-        "NotFound",
-        format!(
-          "Specifier not found in cache: \"{}\", --cached-only is specified.",
-          specifier
-        ),
-      ))
+      return futures::future::err(
+        NotCachedError {
+          specifier: specifier.clone(),
+        }
+        .into(),
+      )
       .boxed();
     }
 
-    info!("{} {}", colors::green("Download"), specifier);
+    let download = self.download_progress.start();
+    if self.download_progress.show {
+      info!(
+        "{} [{}] {}",
+        colors::green("Download"),
+        self.download_progress,
+        specifier
+      );
+    } else {
+      info!("{} {}", colors::green("Download"), specifier);
+    }
 
-    let maybe_etag = match self.http_cache.get(specifier) {
-      Ok((_, headers)) => headers.get("etag").cloned(),
-      _ => None,
+    // a cached response is revalidated, so a module which was not modified is
+    // not downloaded again when it is reloaded
+    let (maybe_etag, maybe_last_modified) = match self.http_cache.get(specifier)
+    {
+      Ok((_, headers)) => (
+        headers.get("etag").cloned(),
+        headers.get("last-modified").cloned(),
+      ),
+      _ => (None, None),
     };
     let maybe_auth_token = self.auth_tokens.get(&specifier);
     let specifier = specifier.clone();
     let mut permissions = permissions.clone();
     let client = self.http_client.clone();
     let file_fetcher = self.clone();
-    // A single pass of fetch either yields code or yields a redirect.
+    // A single pass of fetch either yields code or yields a redirect, where
+    // transient errors are retried with an exponential backoff.
     async move {
-      match fetch_once(FetchOnceArgs {
-        client,
-        url: specifier.clone(),
-        maybe_etag,
-        maybe_auth_token,
-      })
-      .await?
-      {
-        FetchOnceResult::NotModified => {
-          let file = file_fetcher.fetch_cached(&specifier, 10)?.unwrap();
-          Ok(file)
-        }
-        FetchOnceResult::Redirect(redirect_url, headers) => {
-          file_fetcher.http_cache.set(&specifier, headers, &[])?;
-          file_fetcher
-            .fetch_remote(&redirect_url, &mut permissions, redirect_limit - 1)
-            .await
-        }
-        FetchOnceResult::Code(bytes, headers) => {
-          file_fetcher
-            .http_cache
-            .set(&specifier, headers.clone(), &bytes)?;
-          let file =
-            file_fetcher.build_remote_file(&specifier, bytes, &headers)?;
-          Ok(file)
+      let _download = download;
+      let mut retries = 0;
+      loop {
+        let result = {
+          // the permit is released before following a redirect, so nested
+          // fetches can't exhaust the permits
+          let _permit = file_fetcher.download_permits.acquire().await?;
+          fetch_once(FetchOnceArgs {
+            client: client.clone(),
+            url: specifier.clone(),
+            maybe_etag: maybe_etag.clone(),
+            maybe_last_modified: maybe_last_modified.clone(),
+            maybe_auth_token: maybe_auth_token.clone(),
+          })
+          .await?
+        };
+        let err = match result {
+          FetchOnceResult::NotModified => {
+            let file = file_fetcher.fetch_cached(&specifier, 10)?.unwrap();
+            return Ok(file);
+          }
+          FetchOnceResult::Redirect(redirect_url, headers) => {
+            file_fetcher.http_cache.set(&specifier, headers, &[])?;
+            return file_fetcher
+              .fetch_remote(&redirect_url, &mut permissions, redirect_limit - 1)
+              .await;
+          }
+          FetchOnceResult::Code(bytes, headers) => {
+            file_fetcher
+              .http_cache
+              .set(&specifier, headers.clone(), &bytes)?;
+            let file =
+              file_fetcher.build_remote_file(&specifier, bytes, &headers)?;
+            return Ok(file);
+          }
+          FetchOnceResult::RequestError(err) => custom_error("Http", err),
+          FetchOnceResult::ServerError(status) => {
+            generic_error(format!("Import '{}' failed: {}", specifier, status))
+          }
+        };
+        if retries >= MAX_DOWNLOAD_RETRIES {
+          return Err(err);
         }
+        let delay = get_retry_delay(retries);
+        debug!(
+          "FileFetcher::fetch_remote() - retrying {} in {:?}: {}",
+          specifier, delay, err
+        );
+        tokio::time::sleep(delay).await;
+        retries += 1;
       }
     }
     .boxed()
@@ -549,6 +811,34 @@ impl FileFetcher {
     self.http_cache.location.clone()
   }
 
+  /// Get the chain of redirects of a remote specifier which has been fetched,
+  /// as recorded in the HTTP cache.  The chain ends with the final specifier,
+  /// and is empty if the specifier was not redirected.
+  pub fn get_redirect_chain(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Vec<ModuleSpecifier> {
+    let mut chain = Vec::new();
+    let mut current = specifier.clone();
+    // the same limit is used when following the redirects
+    for _ in 0..=10 {
+      let maybe_redirect = match self.http_cache.get(&current) {
+        Ok((_, headers)) => headers.get("location").and_then(|redirect_to| {
+          deno_core::resolve_import(redirect_to, current.as_str()).ok()
+        }),
+        Err(_) => None,
+      };
+      match maybe_redirect {
+        Some(redirect) => {
+          chain.push(redirect.clone());
+          current = redirect;
+        }
+        None => break,
+      }
+    }
+    chain
+  }
+
   /// A synchronous way to retrieve a source file, where if the file has already
   /// been cached in memory it will be returned, otherwise for local files will
   /// be read from disk.
@@ -606,6 +896,7 @@ mod tests {
       cache_setting,
       true,
       None,
+      None,
       blob_url_store.clone(),
     )
     .expect("setup failed");
@@ -669,6 +960,39 @@ mod tests {
     assert_eq!(file.source, expected);
   }
 
+  #[test]
+  fn test_get_retry_delay() {
+    assert_eq!(get_retry_delay(0), Duration::from_millis(250));
+    assert_eq!(get_retry_delay(1), Duration::from_millis(500));
+    assert_eq!(get_retry_delay(2), Duration::from_millis(1000));
+  }
+
+  #[test]
+  fn test_get_download_concurrency() {
+    assert_eq!(get_download_concurrency(None), DEFAULT_DOWNLOAD_CONCURRENCY);
+    assert_eq!(get_download_concurrency(Some("4".to_string())), 4);
+    assert_eq!(
+      get_download_concurrency(Some("0".to_string())),
+      DEFAULT_DOWNLOAD_CONCURRENCY
+    );
+    assert_eq!(
+      get_download_concurrency(Some("many".to_string())),
+      DEFAULT_DOWNLOAD_CONCURRENCY
+    );
+  }
+
+  #[test]
+  fn test_download_progress() {
+    let progress = DownloadProgress::new(true);
+    let download_a = progress.start();
+    let download_b = progress.start();
+    assert_eq!(progress.to_string(), "0/2");
+    drop(download_a);
+    assert_eq!(progress.to_string(), "1/2");
+    drop(download_b);
+    assert_eq!(progress.to_string(), "2/2");
+  }
+
   #[test]
   fn test_get_validated_scheme() {
     let fixtures = vec![
@@ -869,6 +1193,33 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_cache_setting_should_use() {
+    let cache_setting = CacheSetting::ReloadSome(
+      ReloadList::new(&[
+        "https://deno.land/std".to_string(),
+        "https://deno.land/x/*/mod.ts".to_string(),
+        "https://esm.sh/**".to_string(),
+        r"/^https:\/\/cdn\.skypack\.dev\/.*\.js$/".to_string(),
+      ])
+      .unwrap(),
+    );
+    let should_use = |specifier: &str| {
+      cache_setting.should_use(&resolve_url(specifier).unwrap())
+    };
+    assert!(!should_use("https://deno.land/std/fs/mod.ts"));
+    assert!(!should_use("https://deno.land/x/oak/mod.ts"));
+    assert!(should_use("https://deno.land/x/oak/deps.ts"));
+    assert!(should_use("https://deno.land/x/oak/lib/mod.ts"));
+    assert!(!should_use("https://esm.sh/preact@10/hooks"));
+    assert!(!should_use("https://cdn.skypack.dev/react.js"));
+    assert!(should_use("https://cdn.skypack.dev/react.ts"));
+    assert!(should_use("https://example.com/mod.ts"));
+    assert!(parse_reload_pattern("https://deno.land/std").is_none());
+    assert!(matches!(parse_reload_pattern("/(/"), Some(Err(_))));
+    assert!(ReloadList::new(&["/(/".to_string()]).is_err());
+  }
+
   #[tokio::test]
   async fn test_insert_cached() {
     let (file_fetcher, temp_dir) = setup(CacheSetting::Use, None);
@@ -880,6 +1231,7 @@ mod tests {
       maybe_types: None,
       media_type: MediaType::TypeScript,
       source: "some source code".to_string(),
+      maybe_raw_source: None,
       specifier: specifier.clone(),
     };
     file_fetcher.insert_cached(file.clone());
@@ -946,6 +1298,24 @@ mod tests {
     assert_eq!(file.specifier, specifier);
   }
 
+  #[tokio::test]
+  async fn test_fetch_data_url_javascript() {
+    let (file_fetcher, _) = setup(CacheSetting::Use, None);
+    let specifier =
+      resolve_url("data:text/javascript,export%20const%20a%20=%20%22a%22;")
+        .unwrap();
+
+    let result = file_fetcher
+      .fetch(&specifier, &mut Permissions::allow_all())
+      .await;
+    assert!(result.is_ok());
+    let file = result.unwrap();
+    assert_eq!(file.source, "export const a = \"a\";");
+    assert_eq!(file.media_type, MediaType::JavaScript);
+    assert_eq!(file.maybe_types, None);
+    assert_eq!(file.specifier, specifier);
+  }
+
   #[tokio::test]
   async fn test_fetch_blob_url() {
     let (file_fetcher, _, blob_url_store) =
@@ -1049,6 +1419,7 @@ mod tests {
       CacheSetting::ReloadAll,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("setup failed");
@@ -1076,6 +1447,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1104,6 +1476,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1265,6 +1638,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1296,6 +1670,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1406,6 +1781,7 @@ mod tests {
       CacheSetting::Use,
       false,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1433,6 +1809,7 @@ mod tests {
       CacheSetting::Only,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1441,6 +1818,7 @@ mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not create file fetcher");
@@ -1452,7 +1830,8 @@ mod tests {
       .await;
     assert!(result.is_err());
     let err = result.unwrap_err();
-    assert_eq!(get_custom_error_class(&err), Some("NotFound"));
+    assert!(is_not_cached_error(&err));
+    assert_eq!(crate::errors::get_error_class_name(&err), "NotFound");
     assert_eq!(err.to_string(), "Specifier not found in cache: \"http://localhost:4545/cli/tests/002_hello.ts\", --cached-only is specified.");
 
     let result = file_fetcher_02
@@ -4,6 +4,7 @@ use crate::colors;
 use deno_core::error::AnyError;
 use deno_core::futures::stream::{Stream, StreamExt};
 use deno_core::futures::Future;
+use log::error;
 use log::info;
 use notify::event::Event as NotifyEvent;
 use notify::event::EventKind;
@@ -196,7 +197,7 @@ where
         );
       }
       Err(error) => {
-        eprintln!("{}: {}", colors::red_bold("error"), error);
+        error!("{}: {}", colors::red_bold("error"), error);
         info!(
           "{} {} failed. Restarting on file change...",
           colors::intense_blue("Watcher"),
@@ -39,6 +39,7 @@ pub enum DenoSubcommand {
   },
   Cache {
     files: Vec<String>,
+    gc: Option<u64>,
   },
   Compile {
     source_file: String,
@@ -75,6 +76,8 @@ pub enum DenoSubcommand {
   },
   Info {
     json: bool,
+    dot: bool,
+    mermaid: bool,
     file: Option<String>,
   },
   Install {
@@ -93,7 +96,16 @@ pub enum DenoSubcommand {
     rules: bool,
     json: bool,
   },
-  Repl,
+  Repl {
+    eval: Option<String>,
+    init: Option<PathBuf>,
+    check: bool,
+    jsx: bool,
+    record: Option<PathBuf>,
+    connect: Option<String>,
+    quiet: bool,
+    exit_code_from_last_error: bool,
+  },
   Run {
     script: String,
   },
@@ -106,8 +118,11 @@ pub enum DenoSubcommand {
     include: Option<Vec<String>>,
     filter: Option<String>,
     concurrent_jobs: usize,
+    trace_ops: bool,
+  },
+  Types {
+    specifiers: Vec<String>,
   },
-  Types,
   Upgrade {
     dry_run: bool,
     force: bool,
@@ -120,7 +135,51 @@ pub enum DenoSubcommand {
 
 impl Default for DenoSubcommand {
   fn default() -> DenoSubcommand {
-    DenoSubcommand::Repl
+    DenoSubcommand::Repl {
+      eval: None,
+      init: None,
+      check: false,
+      jsx: false,
+      record: None,
+      connect: None,
+      quiet: false,
+      exit_code_from_last_error: false,
+    }
+  }
+}
+
+/// The modules which are type checked, which is set with `--no-check`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeCheckMode {
+  /// Type check all modules, which is the default.
+  All,
+  /// Skip type checking of all modules (`--no-check`).
+  None,
+  /// Only type check local modules, skipping the type checking of remote
+  /// modules (`--no-check=remote`).
+  Local,
+}
+
+impl Default for TypeCheckMode {
+  fn default() -> Self {
+    TypeCheckMode::All
+  }
+}
+
+/// The format of the diagnostic output of the CLI, which is set with
+/// `--log-format`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFormat {
+  /// Human-readable messages, which is the default.
+  Text,
+  /// One JSON object per line, with the timestamp, level, target and message
+  /// of each log record (`--log-format=json`).
+  Json,
+}
+
+impl Default for LogFormat {
+  fn default() -> Self {
+    LogFormat::Text
   }
 }
 
@@ -132,6 +191,7 @@ pub struct Flags {
   pub subcommand: DenoSubcommand,
 
   pub allow_env: Option<Vec<String>>,
+  pub allow_ffi: bool,
   pub allow_hrtime: bool,
   pub allow_net: Option<Vec<String>>,
   pub allow_plugin: bool,
@@ -140,23 +200,32 @@ pub struct Flags {
   pub allow_write: Option<Vec<PathBuf>>,
   pub location: Option<Url>,
   pub cache_blocklist: Vec<String>,
+  pub cache_dir: Option<PathBuf>,
   pub ca_file: Option<String>,
   pub cached_only: bool,
+  pub compat: bool,
   pub config_path: Option<String>,
   pub coverage_dir: Option<String>,
+  pub heap_snapshot_on_oom: bool,
+  pub icu_data_path: Option<PathBuf>,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
   pub inspect: Option<SocketAddr>,
   pub inspect_brk: Option<SocketAddr>,
   pub lock: Option<PathBuf>,
   pub lock_write: bool,
+  pub log_format: LogFormat,
   pub log_level: Option<Level>,
-  pub no_check: bool,
   pub prompt: bool,
+  pub prof_dir: Option<PathBuf>,
   pub no_remote: bool,
+  pub op_latency_metrics: bool,
+  pub proxy: Option<String>,
   pub reload: bool,
   pub repl: bool,
   pub seed: Option<u64>,
+  pub template_imports: bool,
+  pub typecheck_mode: TypeCheckMode,
   pub unstable: bool,
   pub v8_flags: Vec<String>,
   pub version: bool,
@@ -236,6 +305,10 @@ impl Flags {
       args.push("--allow-plugin".to_string());
     }
 
+    if self.allow_ffi {
+      args.push("--allow-ffi".to_string());
+    }
+
     if self.allow_hrtime {
       args.push("--allow-hrtime".to_string());
     }
@@ -248,6 +321,7 @@ impl From<Flags> for PermissionsOptions {
   fn from(flags: Flags) -> Self {
     Self {
       allow_env: flags.allow_env,
+      allow_ffi: flags.allow_ffi,
       allow_hrtime: flags.allow_hrtime,
       allow_net: flags.allow_net,
       allow_plugin: flags.allow_plugin,
@@ -260,19 +334,47 @@ impl From<Flags> for PermissionsOptions {
 }
 
 static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
-    DENO_AUTH_TOKENS     A semi-colon separated list of bearer tokens and
-                         hostnames to use when fetching remote modules from
-                         private repositories
-                         (e.g. "abcde12345@deno.land;54321edcba@github.com")
+    DENO_AUTH_TOKENS     A semi-colon separated list of bearer tokens or
+                         basic credentials and hostnames to use when fetching
+                         remote modules from private repositories
+                         (e.g. "abcde12345@deno.land;user:pass@github.com")
     DENO_CERT            Load certificate authority from PEM encoded file
     DENO_DIR             Set the cache directory
+    DENO_DOWNLOAD_CONCURRENCY
+                         Maximum number of concurrent downloads of remote
+                         modules (defaults to 16)
     DENO_INSTALL_ROOT    Set deno install's output directory
                          (defaults to $HOME/.deno/bin)
+    DENO_REPL_AWAIT_TIMEOUT
+                         Milliseconds for which a promise that REPL input
+                         evaluates to is awaited, or 0 not to await it
+                         (defaults to 5000)
+    DENO_REPL_CONTINUATION_PROMPT
+                         Set the REPL prompt for continuing incomplete
+                         input, instead of editing it as multiple lines
+    DENO_REPL_HISTORY    Set the REPL history file, or disable the history
+                         when empty (defaults to $DENO_DIR/deno_history.txt)
+    DENO_REPL_HISTORY_SIZE
+                         Maximum number of REPL history entries
+                         (defaults to 100)
+    DENO_REPL_PAGER      Set the pager for REPL output taller than the
+                         terminal, or disable paging when empty
+                         (defaults to $PAGER, then an internal pager)
+    DENO_REPL_PROMPT     Set the REPL prompt, in which {line} is replaced
+                         by the line number (defaults to "> ")
+    DENO_REPL_SEARCH_KEY Set the key starting a reverse search of the REPL
+                         history as ctrl-<key> or alt-<key>, or disable
+                         the search when empty (defaults to ctrl-r)
+    DENO_REPL_THEME      Set the colors of the REPL syntax highlighting as
+                         comma separated class=color entries, like
+                         "keyword=magenta,comment=none"
     DENO_WEBGPU_TRACE    Directory to use for wgpu traces
     HTTP_PROXY           Proxy address for HTTP requests
-                         (module downloads, fetch)
+                         (module downloads, fetch), overridden for
+                         module downloads by --proxy
     HTTPS_PROXY          Proxy address for HTTPS requests
-                         (module downloads, fetch)
+                         (module downloads, fetch), overridden for
+                         module downloads by --proxy
     NO_COLOR             Set to disable color
     NO_PROXY             Comma-separated list of hosts which do not use a proxy
                          (module downloads, fetch)"#;
@@ -320,6 +422,16 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
   if matches.is_present("quiet") {
     flags.log_level = Some(Level::Error);
   }
+  if let Some(log_format) = matches.value_of("log-format") {
+    flags.log_format = match log_format {
+      "text" => LogFormat::Text,
+      "json" => LogFormat::Json,
+      _ => unreachable!(),
+    };
+  }
+  if let Some(cache_dir) = matches.value_of("cache-dir") {
+    flags.cache_dir = Some(PathBuf::from(cache_dir));
+  }
 
   if let Some(m) = matches.subcommand_matches("run") {
     run_parse(&mut flags, m);
@@ -359,6 +471,13 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     repl_parse(&mut flags, &matches);
   }
 
+  if flags.op_latency_metrics && !flags.unstable {
+    return Err(clap::Error::with_description(
+      "--op-latency-metrics is unstable and requires --unstable",
+      clap::ErrorKind::MissingRequiredArgument,
+    ));
+  }
+
   Ok(flags)
 }
 
@@ -390,6 +509,35 @@ fn clap_root<'a, 'b>(version: &'b str) -> App<'a, 'b> {
         .possible_values(&["debug", "info"])
         .global(true),
     )
+    .arg(
+      Arg::with_name("log-format")
+        .long("log-format")
+        .help("Set the format of diagnostic output")
+        .long_help(
+          "Set the format of diagnostic output
+With 'json', log messages like download progress, permission prompts, watcher
+restarts and uncaught errors are printed to stderr as one JSON object per line,
+with the 'timestamp', 'level', 'target' and 'message' of each.",
+        )
+        .takes_value(true)
+        .value_name("FORMAT")
+        .possible_values(&["text", "json"])
+        .global(true),
+    )
+    .arg(
+      Arg::with_name("cache-dir")
+        .long("cache-dir")
+        .help("Set the cache directory of the project")
+        .long_help(
+          "Set the cache directory of the project
+Remote modules and emitted files are cached in the directory, instead of
+$DENO_DIR. Anything which is not present in the directory is read from
+$DENO_DIR, and then cached in the directory.",
+        )
+        .takes_value(true)
+        .value_name("DIR")
+        .global(true),
+    )
     .arg(
       Arg::with_name("quiet")
         .short("q")
@@ -449,9 +597,28 @@ fn cache_subcommand<'a, 'b>() -> App<'a, 'b> {
     .arg(
       Arg::with_name("file")
         .takes_value(true)
-        .required(true)
+        .required_unless("gc")
         .min_values(1),
     )
+    .arg(
+      Arg::with_name("gc")
+        .long("gc")
+        .min_values(0)
+        .max_values(1)
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("DAYS")
+        .validator(|val: String| match val.parse::<u64>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("gc should be a number of days".to_string()),
+        })
+        .help("Remove the cached remote modules which have not been used within the number of days (defaults to 30)"),
+    )
+    .arg(
+      Arg::with_name("template-imports")
+        .long("template-imports")
+        .help("Also cache the local modules which match dynamic imports of template literals"),
+    )
     .about("Cache the dependencies")
     .long_about(
       "Cache and compile remote dependencies recursively.
@@ -462,7 +629,23 @@ in the local cache, without running any code:
   deno cache https://deno.land/std/http/file_server.ts
 
 Future runs of this module will trigger no downloads or compilation unless
---reload is specified.",
+--reload is specified.
+
+Identical remote modules are only stored once in the cache. Remove the cached
+remote modules which have not been used within the last 30 days, and the
+contents which are no longer referenced by any of them:
+
+  deno cache --gc
+
+Or specify the number of days explicitly:
+
+  deno cache --gc=7
+
+The specifier of a dynamic import of a template literal, like
+import(`./plugins/${name}.ts`), is only known when it is evaluated. Also cache
+the local modules which match the static parts of such imports:
+
+  deno cache --template-imports main.ts",
     )
 }
 
@@ -784,15 +967,28 @@ compiled: Local path of compiled source code. (TypeScript only.)
 map: Local path of source map. (TypeScript only.)
 deps: Dependency tree of the source file.
 
+The module graph can also be output as a Graphviz DOT graph or a Mermaid
+flowchart, where the modules of import cycles are highlighted:
+
+  deno info --dot https://deno.land/std/http/file_server.ts | dot -Tsvg > deps.svg
+
 Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
 Remote modules cache: Subdirectory containing downloaded remote modules.
-TypeScript compiler cache: Subdirectory containing TS compiler output.",
+TypeScript compiler cache: Subdirectory containing TS compiler output.
+
+When a configuration file is provided, the compiler options which are in effect
+when type checking, after the options of the configuration file are merged with
+the defaults of Deno, are shown as well:
+
+  deno info --config tsconfig.json",
     )
     .arg(Arg::with_name("file").takes_value(true).required(false))
+    .arg(config_arg())
     .arg(reload_arg().requires("file"))
     .arg(ca_file_arg())
+    .arg(proxy_arg())
     .arg(
       location_arg()
         .conflicts_with("file")
@@ -807,6 +1003,22 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
         .help("UNSTABLE: Outputs the information in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::with_name("dot")
+        .long("dot")
+        .help("UNSTABLE: Outputs the module graph in the Graphviz DOT format")
+        .requires("file")
+        .conflicts_with_all(&["json", "mermaid"])
+        .takes_value(false),
+    )
+    .arg(
+      Arg::with_name("mermaid")
+        .long("mermaid")
+        .help("UNSTABLE: Outputs the module graph as a Mermaid flowchart")
+        .requires("file")
+        .conflicts_with("json")
+        .takes_value(false),
+    )
 }
 
 fn install_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -955,6 +1167,77 @@ Ignore linting a file by adding an ignore comment at the top of the file:
 fn repl_subcommand<'a, 'b>() -> App<'a, 'b> {
   runtime_args(SubCommand::with_name("repl"), false, true)
     .about("Read Eval Print Loop")
+    .arg(
+      Arg::with_name("eval")
+        .long("eval")
+        .help("Evaluates the provided code when the REPL starts.")
+        .takes_value(true)
+        .value_name("code"),
+    )
+    .arg(
+      Arg::with_name("init")
+        .long("init")
+        .help("Evaluates the provided file when the REPL starts.")
+        .long_help(
+          "Evaluates the provided file when the REPL starts, before --eval.
+Defaults to $DENO_DIR/repl_init.ts when it exists.",
+        )
+        .takes_value(true)
+        .value_name("file"),
+    )
+    .arg(
+      Arg::with_name("check")
+        .long("check")
+        .conflicts_with("no-check")
+        .help("Type check each input before evaluating it.")
+        .long_help(
+          "Type check each input before evaluating it, against the declarations
+of the inputs evaluated so far. Type errors are reported as warnings
+and do not prevent the input from being evaluated.",
+        ),
+    )
+    .arg(
+      Arg::with_name("jsx")
+        .long("jsx")
+        .help("Accept JSX in the input.")
+        .long_help(
+          "Accept JSX in the input, which is transformed with the jsxFactory and
+jsxFragmentFactory of the config file, defaulting to React.createElement
+and React.Fragment.",
+        ),
+    )
+    .arg(
+      Arg::with_name("record")
+        .long("record")
+        .help("Append every input and its output to a transcript file.")
+        .long_help(
+          "Append every input and its output to a transcript file as the session
+proceeds. The outputs are commented out, so that the transcript can be run
+as a script.",
+        )
+        .takes_value(true)
+        .value_name("file"),
+    )
+    .arg(
+      Arg::with_name("connect")
+        .long("connect")
+        .help("Evaluate the input in an already running Deno process.")
+        .long_help(
+          "Evaluate the input in an already running Deno process, through the
+inspector websocket URL which it prints when started with --inspect.",
+        )
+        .takes_value(true)
+        .value_name("url"),
+    )
+    .arg(
+      Arg::with_name("exit-code-from-last-error")
+        .long("exit-code-from-last-error")
+        .help("Exit with code 1 when the last input threw.")
+        .long_help(
+          "Exit with code 1 when the last input evaluated before the REPL
+is exited threw, as it does when the input is not read from a terminal.",
+        ),
+    )
 }
 
 fn run_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -964,6 +1247,32 @@ fn run_subcommand<'a, 'b>() -> App<'a, 'b> {
         .conflicts_with("inspect")
         .conflicts_with("inspect-brk"),
     )
+    .arg(
+      Arg::with_name("prof")
+        .long("prof")
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .value_name("DIR")
+        .conflicts_with("watch")
+        .help("Write a CPU profile of the program on exit")
+        .long_help(
+          "Start the V8 CPU profiler before running the program and write a
+.cpuprofile file to the given directory, or the current directory, on exit.
+It can be loaded in the Performance panel of Chrome DevTools.",
+        ),
+    )
+    .arg(
+      Arg::with_name("heap-snapshot-on-oom")
+        .long("heap-snapshot-on-oom")
+        .help("UNSTABLE: Write a heap snapshot when running out of memory")
+        .long_help(
+          "UNSTABLE: Write a .heapsnapshot file to the current directory the first time
+the heap approaches its limit. It can be loaded in the Memory panel of Chrome
+DevTools.",
+        ),
+    )
     .setting(AppSettings::TrailingVarArg)
     .arg(script_arg().required(true))
     .about("Run a JavaScript or TypeScript program")
@@ -1021,6 +1330,12 @@ fn test_subcommand<'a, 'b>() -> App<'a, 'b> {
         .help("Don't return error code if no test files are found")
         .takes_value(false),
     )
+    .arg(
+      Arg::with_name("trace-ops")
+        .long("trace-ops")
+        .help("Show the call sites of async ops and resources leaked by tests")
+        .takes_value(false),
+    )
     .arg(
       Arg::with_name("filter")
         .set(ArgSettings::AllowLeadingHyphen)
@@ -1079,13 +1394,35 @@ Directory arguments are expanded to all contained files matching the glob
 
 fn types_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("types")
+    .arg(
+      Arg::with_name("for")
+        .long("for")
+        .takes_value(true)
+        .value_name("SPECIFIER")
+        .multiple(true)
+        .number_of_values(1)
+        .help("Print the type declarations of the module instead"),
+    )
+    .arg(import_map_arg())
+    .arg(config_arg())
+    .arg(reload_arg())
+    .arg(ca_file_arg())
+    .arg(proxy_arg())
     .about("Print runtime TypeScript declarations")
     .long_about(
       "Print runtime TypeScript declarations.
 
   deno types > lib.deno.d.ts
 
-The declaration file could be saved and used for typing information.",
+The declaration file could be saved and used for typing information.
+
+The type declarations of remote modules, provided by X-TypeScript-Types headers
+or triple-slash references, can be vendored for tooling which is unable to fetch
+them. The declarations of the modules and of their type dependencies are
+consolidated into a single file, where each module is declared as an ambient
+module named by its URL:
+
+  deno types --for https://cdn.skypack.dev/preact > preact.d.ts",
     )
 }
 
@@ -1146,6 +1483,7 @@ fn compile_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     .arg(lock_arg())
     .arg(lock_write_arg())
     .arg(ca_file_arg())
+    .arg(proxy_arg())
 }
 
 fn permission_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -1209,6 +1547,11 @@ fn permission_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
         .long("allow-plugin")
         .help("Allow loading plugins"),
     )
+    .arg(
+      Arg::with_name("allow-ffi")
+        .long("allow-ffi")
+        .help("Allow loading dynamic libraries"),
+    )
     .arg(
       Arg::with_name("allow-hrtime")
         .long("allow-hrtime")
@@ -1247,7 +1590,10 @@ fn runtime_args<'a, 'b>(
     .arg(cached_only_arg())
     .arg(location_arg())
     .arg(v8_flags_arg())
+    .arg(icu_data_arg())
     .arg(seed_arg())
+    .arg(compat_arg())
+    .arg(op_latency_metrics_arg())
 }
 
 fn inspect_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -1310,8 +1656,21 @@ fn reload_arg<'a, 'b>() -> Arg<'a, 'b> {
 --reload=https://deno.land/std
   Reload only standard modules
 --reload=https://deno.land/std/fs/utils.ts,https://deno.land/std/fmt/colors.ts
-  Reloads specific modules",
+  Reloads specific modules
+--reload=https://deno.land/x/**/mod.ts
+  Reload modules matching a glob, where * matches within a path segment
+  and ** matches across path segments
+--reload=/^https://deno\\.land/x/oak@/
+  Reload modules matching a regular expression enclosed in slashes
+Cached modules which are reloaded are revalidated with the server, so modules
+which were not modified are not downloaded again.",
     )
+    .validator(|entry| {
+      match crate::file_fetcher::parse_reload_pattern(&entry) {
+        Some(Err(err)) => Err(format!("Invalid reload pattern: {}", err)),
+        _ => Ok(()),
+      }
+    })
 }
 
 fn ca_file_arg<'a, 'b>() -> Arg<'a, 'b> {
@@ -1322,12 +1681,48 @@ fn ca_file_arg<'a, 'b>() -> Arg<'a, 'b> {
     .takes_value(true)
 }
 
+fn proxy_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("proxy")
+    .long("proxy")
+    .value_name("URL")
+    .help("Use a proxy for downloading remote modules")
+    .long_help(
+      "Use a proxy for downloading remote modules, instead of the proxies of the
+HTTP_PROXY and HTTPS_PROXY environment variables. Hosts listed in the NO_PROXY
+environment variable are still connected to directly.",
+    )
+    .takes_value(true)
+}
+
 fn cached_only_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("cached-only")
     .long("cached-only")
     .help("Require that remote dependencies are already cached")
 }
 
+fn compat_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("compat")
+    .long("compat")
+    .help("UNSTABLE: Node compatibility mode")
+    .long_help(
+      "UNSTABLE: Node compatibility mode. Built-in Node modules like 'fs' are
+mapped to their std/node polyfills, Node globals like 'process' are set up and
+CommonJS entry points (.cjs) are loaded with 'require'.",
+    )
+}
+
+fn op_latency_metrics_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("op-latency-metrics")
+    .long("op-latency-metrics")
+    .help("UNSTABLE: Record the latencies of ops for Deno.metrics()")
+    .long_help(
+      "UNSTABLE: Record the latencies of ops for Deno.metrics(). The count,
+total, mean, percentiles and maximum of the latencies of each op are reported
+in the per-op metrics. Off by default because of the overhead of reading the
+clock for every op.",
+    )
+}
+
 fn location_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("location")
     .long("location")
@@ -1358,6 +1753,18 @@ fn v8_flags_arg<'a, 'b>() -> Arg<'a, 'b> {
     .help("Set V8 command line options (for help: --v8-flags=--help)")
 }
 
+fn icu_data_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("icu-data")
+    .long("icu-data")
+    .value_name("FILE")
+    .help("Load ICU data for Intl from a file")
+    .long_help(
+      "Load the ICU data used by Intl from an icudtl.dat file instead of the \
+data built into Deno, for example a build of the data with all locales.",
+    )
+    .takes_value(true)
+}
+
 fn seed_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("seed")
     .long("seed")
@@ -1383,7 +1790,22 @@ Only local files from entry point module graph are watched.",
 fn no_check_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("no-check")
     .long("no-check")
+    .min_values(0)
+    .max_values(1)
+    .takes_value(true)
+    .require_equals(true)
+    .possible_values(&["remote"])
+    .value_name("NO_CHECK_TYPE")
     .help("Skip type checking modules")
+    .long_help(
+      "Skip type checking modules. If the value of '--no-check' is supplied, \
+type checking of modules is skipped for the specified type of modules only.
+
+Examples:
+
+  --no-check
+  --no-check=remote",
+    )
 }
 
 fn script_arg<'a, 'b>() -> Arg<'a, 'b> {
@@ -1454,10 +1876,20 @@ fn cache_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   compile_args_parse(flags, matches);
   let files = matches
     .values_of("file")
-    .unwrap()
-    .map(String::from)
-    .collect();
-  flags.subcommand = DenoSubcommand::Cache { files };
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
+  let gc = if matches.is_present("gc") {
+    Some(
+      matches
+        .value_of("gc")
+        .map(|value| value.parse().unwrap())
+        .unwrap_or(30),
+    )
+  } else {
+    None
+  };
+  flags.template_imports = matches.is_present("template-imports");
+  flags.subcommand = DenoSubcommand::Cache { files, gc };
 }
 
 fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -1547,6 +1979,7 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.allow_read = Some(vec![]);
   flags.allow_write = Some(vec![]);
   flags.allow_plugin = true;
+  flags.allow_ffi = true;
   flags.allow_hrtime = true;
   // TODO(@satyarohith): remove this flag in 2.0.
   let as_typescript = matches.is_present("ts");
@@ -1593,13 +2026,19 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn info_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   reload_arg_parse(flags, matches);
+  config_arg_parse(flags, matches);
   import_map_arg_parse(flags, matches);
   location_arg_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  proxy_arg_parse(flags, matches);
   let json = matches.is_present("json");
+  let dot = matches.is_present("dot");
+  let mermaid = matches.is_present("mermaid");
   flags.subcommand = DenoSubcommand::Info {
     file: matches.value_of("file").map(|f| f.to_string()),
     json,
+    dot,
+    mermaid,
   };
 }
 
@@ -1662,13 +2101,23 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 fn repl_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   runtime_args_parse(flags, matches, false, true);
   flags.repl = true;
-  flags.subcommand = DenoSubcommand::Repl;
+  flags.subcommand = DenoSubcommand::Repl {
+    eval: matches.value_of("eval").map(ToOwned::to_owned),
+    init: matches.value_of("init").map(PathBuf::from),
+    check: matches.is_present("check"),
+    jsx: matches.is_present("jsx"),
+    record: matches.value_of("record").map(PathBuf::from),
+    connect: matches.value_of("connect").map(ToOwned::to_owned),
+    quiet: matches.is_present("quiet"),
+    exit_code_from_last_error: matches.is_present("exit-code-from-last-error"),
+  };
   flags.allow_net = Some(vec![]);
   flags.allow_env = Some(vec![]);
   flags.allow_run = Some(vec![]);
   flags.allow_read = Some(vec![]);
   flags.allow_write = Some(vec![]);
   flags.allow_plugin = true;
+  flags.allow_ffi = true;
   flags.allow_hrtime = true;
 }
 
@@ -1688,6 +2137,11 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 
   flags.watch = matches.is_present("watch");
+  if matches.is_present("prof") {
+    flags.prof_dir =
+      Some(PathBuf::from(matches.value_of("prof").unwrap_or(".")));
+  }
+  flags.heap_snapshot_on_oom = matches.is_present("heap-snapshot-on-oom");
   flags.subcommand = DenoSubcommand::Run { script };
 }
 
@@ -1700,6 +2154,7 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let allow_none = matches.is_present("allow-none");
   let quiet = matches.is_present("quiet");
   let filter = matches.value_of("filter").map(String::from);
+  let trace_ops = matches.is_present("trace-ops");
 
   flags.watch = matches.is_present("watch");
 
@@ -1747,11 +2202,21 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     filter,
     allow_none,
     concurrent_jobs,
+    trace_ops,
   };
 }
 
-fn types_parse(flags: &mut Flags, _matches: &clap::ArgMatches) {
-  flags.subcommand = DenoSubcommand::Types;
+fn types_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  import_map_arg_parse(flags, matches);
+  config_arg_parse(flags, matches);
+  reload_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  proxy_arg_parse(flags, matches);
+  let specifiers = matches
+    .values_of("for")
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
+  flags.subcommand = DenoSubcommand::Types { specifiers };
 }
 
 fn upgrade_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -1786,6 +2251,7 @@ fn compile_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   reload_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  proxy_arg_parse(flags, matches);
 }
 
 fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -1830,6 +2296,9 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if matches.is_present("allow-plugin") {
     flags.allow_plugin = true;
   }
+  if matches.is_present("allow-ffi") {
+    flags.allow_ffi = true;
+  }
   if matches.is_present("allow-hrtime") {
     flags.allow_hrtime = true;
   }
@@ -1840,6 +2309,7 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     flags.allow_run = Some(vec![]);
     flags.allow_write = Some(vec![]);
     flags.allow_plugin = true;
+    flags.allow_ffi = true;
     flags.allow_hrtime = true;
   }
   if matches.is_present("prompt") {
@@ -1863,8 +2333,11 @@ fn runtime_args_parse(
   }
   location_arg_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
+  icu_data_arg_parse(flags, matches);
   seed_arg_parse(flags, matches);
   inspect_arg_parse(flags, matches);
+  compat_arg_parse(flags, matches);
+  op_latency_metrics_arg_parse(flags, matches);
 }
 
 fn inspect_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -1900,7 +2373,14 @@ fn reload_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
     if raw_cache_blocklist.is_empty() {
       flags.reload = true;
     } else {
-      flags.cache_blocklist = resolve_urls(raw_cache_blocklist);
+      // patterns are matched against the URLs of modules as they are, while
+      // URLs are normalized
+      let (patterns, urls): (Vec<String>, Vec<String>) =
+        raw_cache_blocklist.into_iter().partition(|entry| {
+          crate::file_fetcher::parse_reload_pattern(entry).is_some()
+        });
+      flags.cache_blocklist = resolve_urls(urls);
+      flags.cache_blocklist.extend(patterns);
       debug!("cache blocklist: {:#?}", &flags.cache_blocklist);
       flags.reload = false;
     }
@@ -1911,12 +2391,24 @@ fn ca_file_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.ca_file = matches.value_of("cert").map(ToOwned::to_owned);
 }
 
+fn proxy_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.proxy = matches.value_of("proxy").map(ToOwned::to_owned);
+}
+
 fn cached_only_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   if matches.is_present("cached-only") {
     flags.cached_only = true;
   }
 }
 
+fn compat_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.compat = matches.is_present("compat");
+}
+
+fn op_latency_metrics_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.op_latency_metrics = matches.is_present("op-latency-metrics");
+}
+
 fn location_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.location = matches
     .value_of("location")
@@ -1929,6 +2421,10 @@ fn v8_flags_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn icu_data_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.icu_data_path = matches.value_of("icu-data").map(PathBuf::from);
+}
+
 fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   if matches.is_present("seed") {
     let seed_string = matches.value_of("seed").unwrap();
@@ -1941,7 +2437,10 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
 
 fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if matches.is_present("no-check") {
-    flags.no_check = true;
+    flags.typecheck_mode = match matches.value_of("no-check") {
+      Some("remote") => TypeCheckMode::Local,
+      _ => TypeCheckMode::None,
+    };
   }
 }
 
@@ -2066,6 +2565,33 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_reload_patterns() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      r"--reload=https://deno.land/std/,https://deno.land/x/**/mod.ts,/^https:\/\/esm\.sh\//",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        cache_blocklist: svec![
+          "https://deno.land/std",
+          "https://deno.land/x/**/mod.ts",
+          r"/^https:\/\/esm\.sh\//"
+        ],
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--reload=/(/", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_watch() {
     let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
@@ -2082,6 +2608,54 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_prof() {
+    let r = flags_from_vec(svec!["deno", "run", "--prof", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        prof_dir: Some(PathBuf::from(".")),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--prof=profiles", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        prof_dir: Some(PathBuf::from("profiles")),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_heap_snapshot_on_oom() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--heap-snapshot-on-oom",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        heap_snapshot_on_oom: true,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn run_reload_allow_write() {
     let r =
@@ -2169,6 +2743,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2415,7 +2990,35 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Types,
+        subcommand: DenoSubcommand::Types { specifiers: vec![] },
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn types_for() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "types",
+      "--for",
+      "https://example.com/a.js",
+      "--for",
+      "https://example.com/b.js",
+      "--reload",
+      "--import-map=import_map.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Types {
+          specifiers: svec![
+            "https://example.com/a.js",
+            "https://example.com/b.js"
+          ],
+        },
+        reload: true,
+        import_map_path: Some("import_map.json".to_string()),
         ..Flags::default()
       }
     );
@@ -2429,6 +3032,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache {
           files: svec!["script.ts"],
+          gc: None,
         },
         ..Flags::default()
       }
@@ -2436,38 +3040,108 @@ mod tests {
   }
 
   #[test]
-  fn info() {
-    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+  fn cache_gc() {
+    let r = flags_from_vec(svec!["deno", "cache", "--gc"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info {
-          json: false,
-          file: Some("script.ts".to_string()),
+        subcommand: DenoSubcommand::Cache {
+          files: vec![],
+          gc: Some(30),
         },
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "cache", "--gc=7", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info {
-          json: false,
-          file: Some("script.ts".to_string()),
+        subcommand: DenoSubcommand::Cache {
+          files: svec!["script.ts"],
+          gc: Some(7),
+        },
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--gc=foo"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn cache_template_imports() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--template-imports", "a.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache {
+          files: svec!["a.ts"],
+          gc: None,
+        },
+        template_imports: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info() {
+    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: false,
+          mermaid: false,
+          file: Some("script.ts".to_string()),
+        },
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: false,
+          mermaid: false,
+          file: Some("script.ts".to_string()),
         },
         reload: true,
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "info", "--config", "tsconfig.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: false,
+          mermaid: false,
+          file: None,
+        },
+        config_path: Some("tsconfig.json".to_string()),
+        ..Flags::default()
+      }
+    );
+
     let r = flags_from_vec(svec!["deno", "info", "--json", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Info {
           json: true,
+          dot: false,
+          mermaid: false,
           file: Some("script.ts".to_string()),
         },
         ..Flags::default()
@@ -2480,6 +3154,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info {
           json: false,
+          dot: false,
+          mermaid: false,
           file: None
         },
         ..Flags::default()
@@ -2492,6 +3168,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info {
           json: true,
+          dot: false,
+          mermaid: false,
           file: None
         },
         ..Flags::default()
@@ -2532,6 +3210,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2555,6 +3234,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2579,6 +3259,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2600,7 +3281,7 @@ mod tests {
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
         config_path: Some("tsconfig.json".to_string()),
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         reload: true,
         lock: Some(PathBuf::from("lock.json")),
         lock_write: true,
@@ -2616,6 +3297,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2646,6 +3328,7 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2659,13 +3342,23 @@ mod tests {
       r.unwrap(),
       Flags {
         repl: true,
-        subcommand: DenoSubcommand::Repl,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
         allow_net: Some(vec![]),
         allow_env: Some(vec![]),
         allow_run: Some(vec![]),
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2680,11 +3373,20 @@ mod tests {
       r.unwrap(),
       Flags {
         repl: true,
-        subcommand: DenoSubcommand::Repl,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
         config_path: Some("tsconfig.json".to_string()),
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         reload: true,
         lock: Some(PathBuf::from("lock.json")),
         lock_write: true,
@@ -2700,6 +3402,258 @@ mod tests {
         allow_read: Some(vec![]),
         allow_write: Some(vec![]),
         allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_eval_flag() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "repl", "--eval", "console.log('hello');"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: Some("console.log('hello');".to_string()),
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_init_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--init", "init.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: Some(PathBuf::from("init.ts")),
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_check_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--check"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: true,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "repl", "--check", "--no-check"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn repl_with_jsx_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--jsx"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: true,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_record_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--record", "session.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: Some(PathBuf::from("session.ts")),
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_connect_flag() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "repl",
+      "--connect",
+      "ws://127.0.0.1:9229/ws/1"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: Some("ws://127.0.0.1:9229/ws/1".to_string()),
+          quiet: false,
+          exit_code_from_last_error: false,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_quiet_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--quiet"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: true,
+          exit_code_from_last_error: false,
+        },
+        log_level: Some(Level::Error),
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_exit_code_from_last_error_flag() {
+    let r =
+      flags_from_vec(svec!["deno", "repl", "--exit-code-from-last-error"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        repl: true,
+        subcommand: DenoSubcommand::Repl {
+          eval: None,
+          init: None,
+          check: false,
+          jsx: false,
+          record: None,
+          connect: None,
+          quiet: false,
+          exit_code_from_last_error: true,
+        },
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_plugin: true,
+        allow_ffi: true,
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -2928,7 +3882,7 @@ mod tests {
           source_file: "script.ts".to_string(),
           out_file: None,
         },
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
@@ -3004,6 +3958,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache {
           files: svec!["script.ts"],
+          gc: None,
         },
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -3043,6 +3998,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache {
           files: svec!["script.ts", "script_two.ts"],
+          gc: None,
         },
         ..Flags::default()
       }
@@ -3065,6 +4021,71 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_op_latency_metrics() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--unstable",
+      "--op-latency-metrics",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        unstable: true,
+        op_latency_metrics: true,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--op-latency-metrics", "script.ts"]);
+    assert_eq!(
+      r.unwrap_err().kind,
+      clap::ErrorKind::MissingRequiredArgument
+    );
+  }
+
+  #[test]
+  fn run_icu_data() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--icu-data",
+      "icudtl.dat",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        icu_data_path: Some(PathBuf::from("icudtl.dat")),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_compat() {
+    let r = flags_from_vec(svec!["deno", "run", "--compat", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        compat: true,
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn run_seed_with_v8_flags() {
     let r = flags_from_vec(svec![
@@ -3127,7 +4148,7 @@ mod tests {
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
         config_path: Some("tsconfig.json".to_string()),
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         reload: true,
         lock: Some(PathBuf::from("lock.json")),
         lock_write: true,
@@ -3159,6 +4180,26 @@ mod tests {
     );
   }
 
+  #[test]
+  fn log_format() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--log-format=json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        log_format: LogFormat::Json,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--log-format=xml", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn quiet() {
     let r = flags_from_vec(svec!["deno", "run", "-q", "script.ts"]);
@@ -3174,6 +4215,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn cache_dir() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--cache-dir", ".deno", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        cache_dir: Some(PathBuf::from(".deno")),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn completions() {
     let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
@@ -3275,12 +4332,32 @@ mod tests {
         subcommand: DenoSubcommand::Run {
           script: "script.ts".to_string(),
         },
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
   }
 
+  #[test]
+  fn no_check_remote() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--no-check=remote", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        typecheck_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--no-check=local", "script.ts"]);
+    assert_eq!(r.unwrap_err().kind, clap::ErrorKind::InvalidValue);
+  }
+
   #[test]
   fn no_remote() {
     let r = flags_from_vec(svec!["deno", "run", "--no-remote", "script.ts"]);
@@ -3408,6 +4485,7 @@ mod tests {
           quiet: false,
           include: Some(svec!["dir1/", "dir2/"]),
           concurrent_jobs: 1,
+          trace_ops: false,
         },
         unstable: true,
         coverage_dir: Some("cov".to_string()),
@@ -3419,6 +4497,28 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_trace_ops() {
+    let r = flags_from_vec(svec!["deno", "test", "--trace-ops"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test {
+          no_run: false,
+          doc: false,
+          fail_fast: false,
+          filter: None,
+          allow_none: false,
+          quiet: false,
+          include: None,
+          concurrent_jobs: 1,
+          trace_ops: true,
+        },
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn run_with_cafile() {
     let r = flags_from_vec(svec![
@@ -3497,6 +4597,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache {
           files: svec!["script.ts", "script_two.ts"],
+          gc: None,
         },
         ca_file: Some("example.crt".to_owned()),
         ..Flags::default()
@@ -3518,6 +4619,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info {
           json: false,
+          dot: false,
+          mermaid: false,
           file: Some("https://example.com".to_string()),
         },
         ca_file: Some("example.crt".to_owned()),
@@ -3526,6 +4629,88 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_with_proxy() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--proxy",
+      "http://proxy.example.com:3128",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run {
+          script: "script.ts".to_string(),
+        },
+        proxy: Some("http://proxy.example.com:3128".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info_dot() {
+    let r = flags_from_vec(svec!["deno", "info", "--dot", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: true,
+          mermaid: false,
+          file: Some("script.ts".to_string()),
+        },
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--mermaid", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: false,
+          mermaid: true,
+          file: Some("script.ts".to_string()),
+        },
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "info", "--dot", "--json", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--dot"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn info_with_proxy() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--proxy",
+      "proxy.example.com:3128",
+      "https://example.com"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info {
+          json: false,
+          dot: false,
+          mermaid: false,
+          file: Some("https://example.com".to_string()),
+        },
+        proxy: Some("proxy.example.com:3128".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn doc() {
     let r = flags_from_vec(svec!["deno", "doc", "--json", "path/to/module.ts"]);
@@ -3657,7 +4842,7 @@ mod tests {
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
         config_path: Some("tsconfig.json".to_string()),
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         reload: true,
         lock: Some(PathBuf::from("lock.json")),
         lock_write: true,
@@ -3,6 +3,13 @@
 //! as defined in RFC 7234 (https://tools.ietf.org/html/rfc7234).
 //! Currently it's a very simplified version to fulfill Deno needs
 //! at hand.
+//!
+//! The contents of the cached resources are content-addressed: they are
+//! stored once in the `content` directory, named by their hash, and the cache
+//! file of each URL is a hard link to its content, so identical modules which
+//! are served from different URLs only occupy disk space once.  The metadata
+//! of each URL references the hash of its content, which allows contents that
+//! are no longer referenced by any recently used URL to be garbage collected.
 use crate::fs_util;
 use crate::http_util::HeadersMap;
 use deno_core::error::generic_error;
@@ -11,15 +18,32 @@ use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::url::Url;
+use filetime::FileTime;
 use log::error;
+use std::collections::HashSet;
 use std::fs;
 use std::fs::File;
 use std::io;
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use walkdir::WalkDir;
 
 pub const CACHE_PERM: u32 = 0o644;
 
+/// The directory of the cache where the contents are stored by their hash.
+const CONTENT_DIR: &str = "content";
+const METADATA_EXTENSION: &str = ".metadata.json";
+
+fn is_not_found(err: &AnyError) -> bool {
+  matches!(
+    err.downcast_ref::<io::Error>(),
+    Some(err) if err.kind() == io::ErrorKind::NotFound
+  )
+}
+
 /// Turn base of url (scheme, hostname, port) into a valid filename.
 /// This method replaces port part with a special string token (because
 /// ":" cannot be used in filename on some platforms).
@@ -75,12 +99,31 @@ pub fn url_to_filename(url: &Url) -> Option<PathBuf> {
 #[derive(Debug, Clone, Default)]
 pub struct HttpCache {
   pub location: PathBuf,
+  /// The cache which is read when an entry is not present in this cache.
+  /// Entries which are found in the fallback are copied into this cache.
+  maybe_fallback: Option<Box<HttpCache>>,
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Metadata {
   pub headers: HeadersMap,
   pub url: String,
+  /// The hash of the content in the content directory of the cache.  Entries
+  /// which were cached before the cache was content-addressed don't have one.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub content_hash: Option<String>,
+}
+
+/// The results of a garbage collection of the cache.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GcStats {
+  /// The number of URL entries which were not used recently, and were removed.
+  pub removed_entries: usize,
+  /// The number of contents which were no longer referenced, and were removed.
+  pub removed_contents: usize,
+  /// The number of bytes freed by removing contents.
+  pub freed_bytes: u64,
 }
 
 impl Metadata {
@@ -113,6 +156,16 @@ impl HttpCache {
     assert!(location.is_absolute());
     Self {
       location: location.to_owned(),
+      maybe_fallback: None,
+    }
+  }
+
+  /// Returns the cache, reading the entries which are not present in it from
+  /// the fallback cache.
+  pub fn with_fallback(self, fallback: HttpCache) -> Self {
+    Self {
+      maybe_fallback: Some(Box::new(fallback)),
+      ..self
     }
   }
 
@@ -136,18 +189,65 @@ impl HttpCache {
     Some(self.location.join(url_to_filename(url)?))
   }
 
+  fn get_content_filename(&self, content_hash: &str) -> PathBuf {
+    self.location.join(CONTENT_DIR).join(content_hash)
+  }
+
+  /// Store the content by its hash, unless it is already stored, and link the
+  /// cache file to it.  If the file system does not support hard links, the
+  /// content is written to the cache file instead.
+  fn write_content(
+    &self,
+    cache_filename: &Path,
+    content: &[u8],
+  ) -> Result<String, AnyError> {
+    let content_hash = crate::checksum::gen(&[content]);
+    let content_filename = self.get_content_filename(&content_hash);
+    if !content_filename.is_file() {
+      self.ensure_dir_exists(content_filename.parent().unwrap())?;
+      fs_util::atomic_write_file(&content_filename, content, CACHE_PERM)?;
+    }
+    // the link is created next to the cache file and then renamed, so that an
+    // existing cache file is replaced atomically
+    let tmp_filename = cache_filename.with_extension("link.tmp");
+    let _ = fs::remove_file(&tmp_filename);
+    let linked = fs::hard_link(&content_filename, &tmp_filename)
+      .and_then(|_| fs::rename(&tmp_filename, cache_filename));
+    if linked.is_err() {
+      let _ = fs::remove_file(&tmp_filename);
+      fs_util::atomic_write_file(cache_filename, content, CACHE_PERM)?;
+    }
+    Ok(content_hash)
+  }
+
   // TODO(bartlomieju): this method should check headers file
   // and validate against ETAG/Last-modified-as headers.
   // ETAG check is currently done in `cli/file_fetcher.rs`.
   pub fn get(&self, url: &Url) -> Result<(File, HeadersMap), AnyError> {
+    match (self.get_entry(url), &self.maybe_fallback) {
+      (Err(err), Some(fallback)) if is_not_found(&err) => {
+        let (mut file, headers) = fallback.get(url)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        self.set(url, headers, &content)?;
+        self.get_entry(url)
+      }
+      (result, _) => result,
+    }
+  }
+
+  fn get_entry(&self, url: &Url) -> Result<(File, HeadersMap), AnyError> {
     let cache_filename = self.location.join(
       url_to_filename(url)
         .ok_or_else(|| generic_error("Can't convert url to filename."))?,
     );
     let metadata_filename = Metadata::filename(&cache_filename);
     let file = File::open(cache_filename)?;
-    let metadata = fs::read_to_string(metadata_filename)?;
+    let metadata = fs::read_to_string(&metadata_filename)?;
     let metadata: Metadata = serde_json::from_str(&metadata)?;
+    // the modification time of the metadata records when the entry was last
+    // used, which is used to determine which entries are garbage collected
+    let _ = filetime::set_file_mtime(&metadata_filename, FileTime::now());
     Ok((file, metadata.headers))
   }
 
@@ -167,21 +267,129 @@ impl HttpCache {
       .expect("Cache filename should have a parent dir");
     self.ensure_dir_exists(parent_filename)?;
     // Cache content
-    fs_util::atomic_write_file(&cache_filename, content, CACHE_PERM)?;
+    let content_hash = self.write_content(&cache_filename, content)?;
 
     let metadata = Metadata {
       url: url.to_string(),
       headers: headers_map,
+      content_hash: Some(content_hash),
     };
     metadata.write(&cache_filename)
   }
+
+  /// Returns the URLs of all the entries in the cache and its fallback,
+  /// sorted and without duplicates.
+  pub fn urls(&self) -> Vec<String> {
+    let mut urls = self
+      .maybe_fallback
+      .as_ref()
+      .map(|fallback| fallback.urls())
+      .unwrap_or_default();
+    if self.location.is_dir() {
+      let content_dir = self.location.join(CONTENT_DIR);
+      let metadata_filenames = WalkDir::new(&self.location)
+        .into_iter()
+        .filter_entry(|e| e.path() != content_dir)
+        .filter_map(Result::ok)
+        .filter(|e| {
+          e.file_type().is_file()
+            && e
+              .file_name()
+              .to_string_lossy()
+              .ends_with(METADATA_EXTENSION)
+        });
+      for entry in metadata_filenames {
+        let metadata = fs::read_to_string(entry.path())
+          .ok()
+          .and_then(|s| serde_json::from_str::<Metadata>(&s).ok());
+        if let Some(metadata) = metadata {
+          urls.push(metadata.url);
+        }
+      }
+    }
+    urls.sort();
+    urls.dedup();
+    urls
+  }
+
+  /// Garbage collect the cache.  The entries which have not been used within
+  /// the maximum age are removed, and then the contents which are not
+  /// referenced by any of the remaining entries.  Entries which were cached
+  /// before the cache was content-addressed are migrated to the content
+  /// directory, so their contents are deduplicated as well.
+  pub fn gc(&self, max_age: Duration) -> Result<GcStats, AnyError> {
+    let mut stats = GcStats::default();
+    if !self.location.is_dir() {
+      return Ok(stats);
+    }
+    let content_dir = self.location.join(CONTENT_DIR);
+    let cutoff = SystemTime::now()
+      .checked_sub(max_age)
+      .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut reachable = HashSet::new();
+    let entries = WalkDir::new(&self.location)
+      .into_iter()
+      .filter_entry(|e| e.path() != content_dir)
+      .filter_map(Result::ok)
+      .filter(|e| e.file_type().is_file());
+    for entry in entries {
+      let metadata_filename = entry.path();
+      let cache_filename = match metadata_filename
+        .to_str()
+        .and_then(|s| s.strip_suffix(METADATA_EXTENSION))
+      {
+        Some(cache_filename) => PathBuf::from(cache_filename),
+        None => continue,
+      };
+      let last_used = entry.metadata()?.modified()?;
+      if last_used < cutoff {
+        let _ = fs::remove_file(&cache_filename);
+        fs::remove_file(metadata_filename)?;
+        stats.removed_entries += 1;
+        continue;
+      }
+      let mut metadata: Metadata =
+        match serde_json::from_str(&fs::read_to_string(metadata_filename)?) {
+          Ok(metadata) => metadata,
+          Err(_) => continue,
+        };
+      match metadata.content_hash {
+        Some(content_hash) => {
+          reachable.insert(content_hash);
+        }
+        None => {
+          if let Ok(content) = fs::read(&cache_filename) {
+            let content_hash = self.write_content(&cache_filename, &content)?;
+            reachable.insert(content_hash.clone());
+            metadata.content_hash = Some(content_hash);
+            metadata.write(&cache_filename)?;
+            filetime::set_file_mtime(
+              metadata_filename,
+              FileTime::from_system_time(last_used),
+            )?;
+          }
+        }
+      }
+    }
+    if content_dir.is_dir() {
+      for entry in fs::read_dir(&content_dir)? {
+        let entry = entry?;
+        let content_hash = entry.file_name().to_string_lossy().to_string();
+        if !reachable.contains(&content_hash) {
+          stats.freed_bytes += entry.metadata()?.len();
+          fs::remove_file(entry.path())?;
+          stats.removed_contents += 1;
+        }
+      }
+    }
+    Ok(stats)
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
   use std::collections::HashMap;
-  use std::io::Read;
   use tempfile::TempDir;
 
   #[test]
@@ -240,6 +448,133 @@ mod tests {
     assert_eq!(headers.get("foobar"), None);
   }
 
+  #[test]
+  fn test_get_fallback() {
+    let dir = TempDir::new().unwrap();
+    let fallback = HttpCache::new(&dir.path().join("global"));
+    let cache = HttpCache::new(&dir.path().join("project"))
+      .with_fallback(fallback.clone());
+    let url = Url::parse("https://deno.land/x/welcome.ts").unwrap();
+    assert!(cache.get(&url).is_err());
+    let mut headers = HashMap::new();
+    headers.insert("etag".to_string(), "as5625rqdsfb".to_string());
+    fallback.set(&url, headers, b"Hello world").unwrap();
+    let (mut file, headers) = cache.get(&url).unwrap();
+    let mut content = String::new();
+    file.read_to_string(&mut content).unwrap();
+    assert_eq!(content, "Hello world");
+    assert_eq!(headers.get("etag").unwrap(), "as5625rqdsfb");
+    // the entry was copied into the cache, and no longer depends on the
+    // fallback
+    let cache_filename = cache.get_cache_filename(&url).unwrap();
+    assert!(cache_filename.starts_with(dir.path().join("project")));
+    assert_eq!(fs::read_to_string(&cache_filename).unwrap(), "Hello world");
+    fs::remove_dir_all(dir.path().join("global")).unwrap();
+    assert!(cache.get(&url).is_ok());
+  }
+
+  #[test]
+  fn test_set_deduplicates_content() {
+    let dir = TempDir::new().unwrap();
+    let cache = HttpCache::new(dir.path());
+    let url_a = Url::parse("https://deno.land/x/a/mod.ts").unwrap();
+    let url_b = Url::parse("https://example.com/b/mod.ts").unwrap();
+    cache.set(&url_a, HashMap::new(), b"export {};").unwrap();
+    cache.set(&url_b, HashMap::new(), b"export {};").unwrap();
+    let contents = fs::read_dir(dir.path().join(CONTENT_DIR)).unwrap().count();
+    assert_eq!(contents, 1);
+    let cache_filename = cache.get_cache_filename(&url_b).unwrap();
+    assert_eq!(fs::read_to_string(&cache_filename).unwrap(), "export {};");
+    let metadata = Metadata::read(&cache_filename).unwrap();
+    assert_eq!(
+      metadata.content_hash,
+      Some(crate::checksum::gen(&[b"export {};"]))
+    );
+
+    // replacing the content of one url does not affect the other one
+    cache
+      .set(&url_a, HashMap::new(), b"export * from \"./b.ts\";")
+      .unwrap();
+    assert_eq!(fs::read_to_string(&cache_filename).unwrap(), "export {};");
+    let contents = fs::read_dir(dir.path().join(CONTENT_DIR)).unwrap().count();
+    assert_eq!(contents, 2);
+  }
+
+  #[test]
+  fn test_urls() {
+    let dir = TempDir::new().unwrap();
+    let fallback = HttpCache::new(&dir.path().join("global"));
+    let cache = HttpCache::new(&dir.path().join("project"))
+      .with_fallback(fallback.clone());
+    assert!(cache.urls().is_empty());
+    let url_a = Url::parse("https://deno.land/x/a/mod.ts").unwrap();
+    let url_b = Url::parse("https://example.com/b/mod.ts").unwrap();
+    cache.set(&url_b, HashMap::new(), b"export {};").unwrap();
+    fallback.set(&url_a, HashMap::new(), b"export {};").unwrap();
+    fallback.set(&url_b, HashMap::new(), b"export {};").unwrap();
+    assert_eq!(cache.urls(), vec![url_a.to_string(), url_b.to_string()]);
+  }
+
+  #[test]
+  fn test_gc() {
+    let dir = TempDir::new().unwrap();
+    let cache = HttpCache::new(dir.path());
+    let url_a = Url::parse("https://deno.land/x/a.ts").unwrap();
+    let url_b = Url::parse("https://deno.land/x/b.ts").unwrap();
+    cache.set(&url_a, HashMap::new(), b"a").unwrap();
+    cache.set(&url_b, HashMap::new(), b"b").unwrap();
+    // the content of a is no longer referenced after it has been replaced
+    cache.set(&url_a, HashMap::new(), b"aa").unwrap();
+    // b was last used a long time ago
+    let cache_filename_b = cache.get_cache_filename(&url_b).unwrap();
+    let a_year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 3600);
+    filetime::set_file_mtime(
+      Metadata::filename(&cache_filename_b),
+      FileTime::from_system_time(a_year_ago),
+    )
+    .unwrap();
+
+    let stats = cache.gc(Duration::from_secs(30 * 24 * 3600)).unwrap();
+    assert_eq!(
+      stats,
+      GcStats {
+        removed_entries: 1,
+        removed_contents: 2,
+        freed_bytes: 2,
+      }
+    );
+    assert!(cache.get(&url_a).is_ok());
+    assert!(cache.get(&url_b).is_err());
+    let contents = fs::read_dir(dir.path().join(CONTENT_DIR)).unwrap().count();
+    assert_eq!(contents, 1);
+  }
+
+  #[test]
+  fn test_gc_migrates_legacy_entries() {
+    let dir = TempDir::new().unwrap();
+    let cache = HttpCache::new(dir.path());
+    let url = Url::parse("https://deno.land/x/legacy.ts").unwrap();
+    let cache_filename = cache.get_cache_filename(&url).unwrap();
+    fs::create_dir_all(cache_filename.parent().unwrap()).unwrap();
+    fs::write(&cache_filename, "legacy").unwrap();
+    fs::write(
+      Metadata::filename(&cache_filename),
+      r#"{ "headers": {}, "url": "https://deno.land/x/legacy.ts" }"#,
+    )
+    .unwrap();
+
+    let stats = cache.gc(Duration::from_secs(30 * 24 * 3600)).unwrap();
+    assert_eq!(stats, GcStats::default());
+    let metadata = Metadata::read(&cache_filename).unwrap();
+    let content_hash = crate::checksum::gen(&[b"legacy"]);
+    assert_eq!(metadata.content_hash, Some(content_hash.clone()));
+    assert_eq!(
+      fs::read_to_string(cache.get_content_filename(&content_hash)).unwrap(),
+      "legacy"
+    );
+    assert_eq!(fs::read_to_string(&cache_filename).unwrap(), "legacy");
+  }
+
   #[test]
   fn test_url_to_filename() {
     let test_cases = [
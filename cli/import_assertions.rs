@@ -0,0 +1,141 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Validation of import assertions, which is shared by the module graph and
+//! the language server.  An import assertion of `type` asserts the type of the
+//! imported module, which is checked against the media type of the module
+//! once it is resolved.
+
+use crate::media_type::MediaType;
+use std::error::Error;
+use std::fmt;
+
+/// The supported values of the `type` import assertion, and the media type a
+/// module is required to have to satisfy the assertion.
+static SUPPORTED_TYPES: &[(&str, MediaType)] = &[("json", MediaType::Json)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportAssertionError {
+  /// The value of the `type` import assertion is not supported.
+  UnsupportedType(String),
+  /// The module does not have the media type that was asserted.
+  TypeMismatch(String, MediaType),
+  /// The media type of the module requires a `type` import assertion, which is
+  /// missing.
+  MissingType(MediaType),
+}
+
+impl ImportAssertionError {
+  pub fn as_code(&self) -> &str {
+    match self {
+      Self::UnsupportedType(_) => "unsupported-assert-type",
+      Self::TypeMismatch(..) => "invalid-assert-type",
+      Self::MissingType(_) => "missing-assert-type",
+    }
+  }
+}
+
+impl fmt::Display for ImportAssertionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::UnsupportedType(assert_type) => write!(
+        f,
+        "The import assertion type of \"{}\" is unsupported.",
+        assert_type
+      ),
+      Self::TypeMismatch(assert_type, media_type) => write!(
+        f,
+        "The module was asserted to be of type \"{}\", but has a media type of {}.",
+        assert_type, media_type
+      ),
+      Self::MissingType(media_type) => write!(
+        f,
+        "A module with a media type of {} requires a \"type\" import assertion of \"{}\".",
+        media_type,
+        get_assert_type(media_type).unwrap_or_default()
+      ),
+    }
+  }
+}
+
+impl Error for ImportAssertionError {}
+
+/// Return the value of the `type` import assertion which is required to import
+/// a module of the media type, if any.
+pub fn get_assert_type(media_type: &MediaType) -> Option<&'static str> {
+  SUPPORTED_TYPES
+    .iter()
+    .find(|(_, mt)| mt == media_type)
+    .map(|(assert_type, _)| *assert_type)
+}
+
+/// Validate the optional `type` import assertion of a dependency against the
+/// media type of the module it resolved to.
+pub fn validate(
+  maybe_assert_type: Option<&str>,
+  media_type: &MediaType,
+) -> Result<(), ImportAssertionError> {
+  match maybe_assert_type {
+    Some(assert_type) => {
+      let (_, expected) = SUPPORTED_TYPES
+        .iter()
+        .find(|(t, _)| *t == assert_type)
+        .ok_or_else(|| {
+          ImportAssertionError::UnsupportedType(assert_type.to_string())
+        })?;
+      if expected != media_type {
+        Err(ImportAssertionError::TypeMismatch(
+          assert_type.to_string(),
+          *media_type,
+        ))
+      } else {
+        Ok(())
+      }
+    }
+    None => {
+      if get_assert_type(media_type).is_some() {
+        Err(ImportAssertionError::MissingType(*media_type))
+      } else {
+        Ok(())
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_validate() {
+    assert!(validate(None, &MediaType::TypeScript).is_ok());
+    assert!(validate(None, &MediaType::Wasm).is_ok());
+    assert!(validate(Some("json"), &MediaType::Json).is_ok());
+    assert_eq!(
+      validate(None, &MediaType::Json),
+      Err(ImportAssertionError::MissingType(MediaType::Json))
+    );
+    assert_eq!(
+      validate(Some("json"), &MediaType::JavaScript),
+      Err(ImportAssertionError::TypeMismatch(
+        "json".to_string(),
+        MediaType::JavaScript
+      ))
+    );
+    assert_eq!(
+      validate(Some("css"), &MediaType::Json),
+      Err(ImportAssertionError::UnsupportedType("css".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_import_assertion_error_display() {
+    assert_eq!(
+      ImportAssertionError::UnsupportedType("css".to_string()).to_string(),
+      "The import assertion type of \"css\" is unsupported."
+    );
+    assert_eq!(
+      ImportAssertionError::MissingType(MediaType::Json).to_string(),
+      "A module with a media type of Json requires a \"type\" import assertion of \"json\"."
+    );
+  }
+}
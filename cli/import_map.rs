@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::checksum;
 use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::serde_json::Map;
@@ -15,22 +16,16 @@ use std::fmt;
 
 #[derive(Debug)]
 pub enum ImportMapError {
-  UnmappedBareSpecifier(String, Option<String>),
+  UnmappedBareSpecifier(Box<UnmappedSpecifier>),
   Other(String),
 }
 
 impl fmt::Display for ImportMapError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
-      ImportMapError::UnmappedBareSpecifier(specifier, maybe_referrer) => write!(
-        f,
-        "Relative import path \"{}\" not prefixed with / or ./ or ../ and not in import map{}",
-        specifier,
-        match maybe_referrer {
-          Some(referrer) => format!(" from \"{}\"", referrer),
-          None => format!(""),
-        }
-      ),
+      ImportMapError::UnmappedBareSpecifier(unmapped) => {
+        write!(f, "{}", unmapped)
+      }
       ImportMapError::Other(message) => f.pad(message),
     }
   }
@@ -38,6 +33,234 @@ impl fmt::Display for ImportMapError {
 
 impl Error for ImportMapError {}
 
+/// The maximum number of similar keys of the import map which are reported.
+const MAX_NEAR_MISSES: usize = 3;
+
+/// A bare specifier which could not be resolved, with an analysis of the
+/// import map, if any, which is reported as hints on how to resolve it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmappedSpecifier {
+  pub specifier: String,
+  pub maybe_referrer: Option<String>,
+  /// If an import map was consulted when resolving the specifier.
+  pub has_import_map: bool,
+  /// The scopes of the import map which apply to the referrer, in the order
+  /// they were consulted before the top level imports.
+  pub scopes: Vec<String>,
+  /// The keys of the consulted mappings which are similar to the specifier,
+  /// like keys which only differ by case, by a trailing slash, or by a typo.
+  pub near_misses: Vec<String>,
+  /// The scopes which do not apply to the referrer, but map the specifier,
+  /// along with the key which maps it.
+  pub other_scopes: Vec<(String, String)>,
+  /// The relative specifier of a local file next to the referrer which has
+  /// the name of the specifier, if any.
+  pub maybe_local: Option<String>,
+  /// A suggested key and address to add to the imports of the import map.
+  pub suggestion: (String, String),
+}
+
+/// Return the package part of a bare specifier, the first path segment, or
+/// the first two for a scoped package like `@std/path`.
+fn get_package_name(specifier: &str) -> &str {
+  let segments = if specifier.starts_with('@') { 2 } else { 1 };
+  match specifier.match_indices('/').nth(segments - 1) {
+    Some((index, _)) => &specifier[..index],
+    None => specifier,
+  }
+}
+
+/// The Levenshtein distance between two strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut prev = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let current = row[j + 1];
+      row[j + 1] = if ca == *cb {
+        prev
+      } else {
+        1 + prev.min(row[j]).min(current)
+      };
+      prev = current;
+    }
+  }
+  row[b.len()]
+}
+
+fn is_near_miss(specifier: &str, key: &str) -> bool {
+  // keys which are URLs are not a likely target of a bare specifier
+  if Url::parse(key).is_ok() {
+    return false;
+  }
+  let specifier = get_package_name(specifier).to_lowercase();
+  let key = key.trim_end_matches('/').to_lowercase();
+  let distance = edit_distance(&specifier, &key);
+  distance <= 2 && distance * 3 <= key.chars().count()
+}
+
+impl UnmappedSpecifier {
+  pub fn new(
+    specifier: &str,
+    maybe_referrer: Option<&str>,
+    maybe_import_map: Option<&ImportMap>,
+  ) -> Self {
+    let package_name = get_package_name(specifier);
+    let mut scopes = Vec::new();
+    let mut near_misses = Vec::new();
+    let mut other_scopes = Vec::new();
+    let mut suggestion = if package_name != specifier {
+      (
+        format!("{}/", package_name),
+        "<URL of the package>/".to_string(),
+      )
+    } else {
+      (specifier.to_string(), "<URL of the module>".to_string())
+    };
+    if let Some(import_map) = maybe_import_map {
+      let referrer = maybe_referrer.unwrap_or("");
+      for (scope, scope_imports) in import_map.scopes.iter() {
+        let applies = scope == referrer
+          || (scope.ends_with('/') && referrer.starts_with(scope.as_str()));
+        if applies {
+          scopes.push(scope.clone());
+        }
+        for key in scope_imports.keys() {
+          if applies && is_near_miss(specifier, key) {
+            if !near_misses.contains(key) {
+              near_misses.push(key.clone());
+            }
+          } else if !applies
+            && get_package_name(key.trim_end_matches('/')) == package_name
+          {
+            other_scopes.push((scope.clone(), key.clone()));
+          }
+        }
+      }
+      for (key, maybe_address) in import_map.imports.iter() {
+        if !is_near_miss(specifier, key) {
+          continue;
+        }
+        // a package which is only mapped to its main module, but imported
+        // by a path within the package, needs a mapping of the package prefix
+        if let Some(address) = maybe_address {
+          if package_name != specifier && key == package_name {
+            if let Ok(package_url) = address.join("./") {
+              suggestion = (format!("{}/", key), package_url.to_string());
+            }
+          }
+        }
+        if !near_misses.contains(key) {
+          near_misses.push(key.clone());
+        }
+      }
+      near_misses.truncate(MAX_NEAR_MISSES);
+    }
+    let maybe_local = maybe_referrer
+      .and_then(|referrer| Url::parse(referrer).ok())
+      .filter(|referrer| referrer.scheme() == "file")
+      .and_then(|referrer| referrer.to_file_path().ok())
+      .and_then(|path| path.parent().map(|dir| dir.join(specifier)))
+      .filter(|path| path.is_file())
+      .map(|_| format!("./{}", specifier));
+
+    Self {
+      specifier: specifier.to_string(),
+      maybe_referrer: maybe_referrer.map(String::from),
+      has_import_map: maybe_import_map.is_some(),
+      scopes,
+      near_misses,
+      other_scopes,
+      maybe_local,
+      suggestion,
+    }
+  }
+
+  /// The lines of notes and hints which explain how to resolve the specifier.
+  pub fn hints(&self) -> Vec<String> {
+    let mut hints = Vec::new();
+    if !self.scopes.is_empty() {
+      hints.push(format!(
+        "note: The scopes {} of the import map were consulted before its top level imports.",
+        join_quoted(&self.scopes, "and")
+      ));
+    }
+    if let Some(local) = &self.maybe_local {
+      hints.push(format!(
+        "hint: To import the local file, use \"{}\".",
+        local
+      ));
+    }
+    if !self.near_misses.is_empty() {
+      hints.push(format!(
+        "hint: Did you mean {}?",
+        join_quoted(&self.near_misses, "or")
+      ));
+    }
+    for (scope, key) in &self.other_scopes {
+      hints.push(format!(
+        "hint: \"{}\" is mapped in the scope \"{}\" of the import map, which does not apply to the importing module.",
+        key, scope
+      ));
+    }
+    let (key, address) = &self.suggestion;
+    if self.has_import_map {
+      hints.push(format!(
+        "hint: Add a mapping to the imports of the import map, like: \"{}\": \"{}\"",
+        key, address
+      ));
+    } else {
+      if self.maybe_local.is_none() {
+        hints.push(
+          "hint: To import a local file, prefix the specifier with \"./\"."
+            .to_string(),
+        );
+      }
+      hints.push(format!(
+        "hint: To use a bare specifier, map it in an import map, like: {{ \"imports\": {{ \"{}\": \"{}\" }} }}",
+        key, address
+      ));
+    }
+    hints
+  }
+}
+
+fn join_quoted(items: &[String], conjunction: &str) -> String {
+  let quoted: Vec<String> =
+    items.iter().map(|i| format!("\"{}\"", i)).collect();
+  match quoted.split_last() {
+    Some((last, rest)) if !rest.is_empty() => {
+      format!("{} {} {}", rest.join(", "), conjunction, last)
+    }
+    _ => quoted.join(""),
+  }
+}
+
+impl fmt::Display for UnmappedSpecifier {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Relative import path \"{}\" not prefixed with / or ./ or ../{}{}",
+      self.specifier,
+      if self.has_import_map {
+        " and not in import map"
+      } else {
+        ""
+      },
+      match &self.maybe_referrer {
+        Some(referrer) => format!(" from \"{}\"", referrer),
+        None => "".to_string(),
+      }
+    )?;
+    for hint in self.hints() {
+      write!(f, "\n  {}", hint)?;
+    }
+    Ok(())
+  }
+}
+
 // https://url.spec.whatwg.org/#special-scheme
 const SPECIAL_PROTOCOLS: &[&str] =
   &["ftp", "file", "http", "https", "ws", "wss"];
@@ -47,6 +270,8 @@ fn is_special(url: &Url) -> bool {
 
 type SpecifierMap = IndexMap<String, Option<Url>>;
 type ScopesMap = IndexMap<String, SpecifierMap>;
+type IntegrityMap = IndexMap<String, String>;
+type TypesMap = IndexMap<String, Url>;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ImportMap {
@@ -55,6 +280,10 @@ pub struct ImportMap {
 
   imports: SpecifierMap,
   scopes: ScopesMap,
+  #[serde(skip_serializing_if = "IndexMap::is_empty")]
+  integrity: IntegrityMap,
+  #[serde(skip_serializing_if = "IndexMap::is_empty")]
+  types: TypesMap,
 }
 
 impl ImportMap {
@@ -109,6 +338,38 @@ impl ImportMap {
       None => IndexMap::new(),
     };
 
+    let normalized_integrity = match &v.get("integrity") {
+      Some(integrity_map) => {
+        if !integrity_map.is_object() {
+          return Err(ImportMapError::Other(
+            "Import map's 'integrity' must be an object".to_string(),
+          ));
+        }
+
+        let integrity_map = integrity_map.as_object().unwrap();
+        ImportMap::parse_integrity_map(
+          integrity_map,
+          base_url,
+          &mut diagnostics,
+        )?
+      }
+      None => IndexMap::new(),
+    };
+
+    let normalized_types = match &v.get("types") {
+      Some(types_map) => {
+        if !types_map.is_object() {
+          return Err(ImportMapError::Other(
+            "Import map's 'types' must be an object".to_string(),
+          ));
+        }
+
+        let types_map = types_map.as_object().unwrap();
+        ImportMap::parse_types_map(types_map, base_url, &mut diagnostics)
+      }
+      None => IndexMap::new(),
+    };
+
     let mut keys: HashSet<String> = v
       .as_object()
       .unwrap()
@@ -117,14 +378,18 @@ impl ImportMap {
       .collect();
     keys.remove("imports");
     keys.remove("scopes");
+    keys.remove("integrity");
+    keys.remove("types");
     for key in keys {
-      diagnostics.push(format!("Invalid top-level key \"{}\". Only \"imports\" and \"scopes\" can be present.", key));
+      diagnostics.push(format!("Invalid top-level key \"{}\". Only \"imports\", \"scopes\", \"integrity\" and \"types\" can be present.", key));
     }
 
     let import_map = ImportMap {
       base_url: base_url.to_string(),
       imports: normalized_imports,
       scopes: normalized_scopes,
+      integrity: normalized_integrity,
+      types: normalized_types,
     };
 
     if !diagnostics.is_empty() {
@@ -137,6 +402,29 @@ impl ImportMap {
     Ok(import_map)
   }
 
+  /// Add the provided specifier mappings to the imports of the import map.
+  /// Mappings which are already part of the import map take precedence and
+  /// are not overwritten.
+  pub fn update_imports(&mut self, imports: Vec<(String, Url)>) {
+    for (specifier_key, address) in imports {
+      self.imports.entry(specifier_key).or_insert(Some(address));
+    }
+  }
+
+  /// Return the integrity metadata of a module, a whitespace separated list
+  /// of hashes like `sha384-<base64 digest>`, which its source is required to
+  /// match.
+  pub fn get_integrity(&self, specifier: &Url) -> Option<&str> {
+    self.integrity.get(specifier.as_str()).map(|s| s.as_str())
+  }
+
+  /// Return the type declarations which are mapped to a module, which are
+  /// used in place of the module when type checking, like an `@deno-types`
+  /// pragma at every import of the module.
+  pub fn get_types(&self, specifier: &Url) -> Option<&Url> {
+    self.types.get(specifier.as_str())
+  }
+
   fn try_url_like_specifier(specifier: &str, base: &str) -> Option<Url> {
     if specifier.starts_with('/')
       || specifier.starts_with("./")
@@ -251,6 +539,96 @@ impl ImportMap {
     normalized_map
   }
 
+  /// Convert provided JSON map to valid IntegrityMap, where the keys are
+  /// resolved against the base URL. Unlike the other invalid entries, which
+  /// are only warned about, integrity with a hash of an unsupported algorithm
+  /// is an error, as ignoring it would leave the module unprotected.
+  fn parse_integrity_map(
+    json_map: &Map<String, Value>,
+    base_url: &str,
+    diagnostics: &mut Vec<String>,
+  ) -> Result<IntegrityMap, ImportMapError> {
+    let mut normalized_map: IntegrityMap = IntegrityMap::new();
+
+    for (specifier_key, value) in json_map.iter() {
+      let url = match ImportMap::try_url_like_specifier(specifier_key, base_url)
+      {
+        Some(url) => url,
+        None => {
+          diagnostics.push(format!(
+              "Invalid integrity key \"{}\". Integrity can only be specified for URLs.",
+              specifier_key
+            ));
+          continue;
+        }
+      };
+
+      match value {
+        Value::String(integrity) => {
+          if !checksum::is_valid_integrity(integrity) {
+            return Err(ImportMapError::Other(format!(
+              "Invalid integrity \"{}\" for \"{}\". Integrity must be a list of sha256, sha384 or sha512 hashes.",
+              integrity, specifier_key
+            )));
+          }
+          normalized_map.insert(url.to_string(), integrity.to_string());
+        }
+        _ => {
+          diagnostics.push(format!(
+            "Invalid integrity for \"{}\". Integrity must be a string.",
+            specifier_key
+          ));
+        }
+      }
+    }
+
+    Ok(normalized_map)
+  }
+
+  /// Convert provided JSON map to valid TypesMap, where both the keys and the
+  /// values are resolved against the base URL.
+  fn parse_types_map(
+    json_map: &Map<String, Value>,
+    base_url: &str,
+    diagnostics: &mut Vec<String>,
+  ) -> TypesMap {
+    let mut normalized_map: TypesMap = TypesMap::new();
+
+    for (specifier_key, value) in json_map.iter() {
+      let url = match ImportMap::try_url_like_specifier(specifier_key, base_url)
+      {
+        Some(url) => url,
+        None => {
+          diagnostics.push(format!(
+            "Invalid types key \"{}\". Types can only be specified for URLs.",
+            specifier_key
+          ));
+          continue;
+        }
+      };
+
+      let types_url = match value {
+        Value::String(types) => {
+          ImportMap::try_url_like_specifier(types, base_url)
+        }
+        _ => None,
+      };
+      match types_url {
+        Some(types_url) => {
+          normalized_map.insert(url.to_string(), types_url);
+        }
+        None => {
+          diagnostics.push(format!(
+            "Invalid types for \"{}\". Types must be a URL-like string.",
+            specifier_key
+          ));
+        }
+      }
+    }
+
+    normalized_map
+  }
+
   /// Convert provided JSON map to valid ScopeMap.
   ///
   /// From specification:
@@ -468,10 +846,9 @@ impl ImportMap {
       return Ok(as_url);
     }
 
-    Err(ImportMapError::UnmappedBareSpecifier(
-      specifier.to_string(),
-      Some(referrer.to_string()),
-    ))
+    Err(ImportMapError::UnmappedBareSpecifier(Box::new(
+      UnmappedSpecifier::new(specifier, Some(referrer), Some(self)),
+    )))
   }
 }
 
@@ -733,6 +1110,158 @@ mod tests {
     }
   }
 
+  #[test]
+  fn from_json_integrity() {
+    let json_map = r#"{
+      "imports": {
+        "foo": "./foo.ts"
+      },
+      "integrity": {
+        "./foo.ts": "sha256-abc",
+        "https://example.com/bar.ts": "sha384-def",
+        "baz": "sha256-ghi",
+        "https://example.com/qux.ts": 1
+      }
+    }"#;
+    let import_map =
+      ImportMap::from_json("https://deno.land/x/mod/", json_map).unwrap();
+    assert_eq!(
+      import_map
+        .get_integrity(&Url::parse("https://deno.land/x/mod/foo.ts").unwrap()),
+      Some("sha256-abc")
+    );
+    assert_eq!(
+      import_map
+        .get_integrity(&Url::parse("https://example.com/bar.ts").unwrap()),
+      Some("sha384-def")
+    );
+    assert_eq!(
+      import_map
+        .get_integrity(&Url::parse("https://example.com/qux.ts").unwrap()),
+      None
+    );
+    assert_eq!(import_map.integrity.len(), 2);
+    assert!(
+      ImportMap::from_json("https://deno.land", r#"{"integrity": []}"#)
+        .is_err()
+    );
+    for integrity in &["md5-abc", "sha-256-abc", "sha256-abc sha1-def", ""] {
+      let json_map =
+        format!(r#"{{ "integrity": {{ "./foo.ts": "{}" }} }}"#, integrity);
+      assert!(ImportMap::from_json("https://deno.land", &json_map).is_err());
+    }
+  }
+
+  #[test]
+  fn from_json_types() {
+    let json_map = r#"{
+      "types": {
+        "https://example.com/lib.js": "./types/lib.d.ts",
+        "./local.js": "https://example.com/local.d.ts",
+        "bare": "./types/bare.d.ts",
+        "https://example.com/invalid.js": 1
+      }
+    }"#;
+    let import_map =
+      ImportMap::from_json("https://deno.land/x/mod/", json_map).unwrap();
+    assert_eq!(
+      import_map.get_types(&Url::parse("https://example.com/lib.js").unwrap()),
+      Some(&Url::parse("https://deno.land/x/mod/types/lib.d.ts").unwrap())
+    );
+    assert_eq!(
+      import_map
+        .get_types(&Url::parse("https://deno.land/x/mod/local.js").unwrap()),
+      Some(&Url::parse("https://example.com/local.d.ts").unwrap())
+    );
+    assert_eq!(
+      import_map
+        .get_types(&Url::parse("https://example.com/invalid.js").unwrap()),
+      None
+    );
+    assert_eq!(import_map.types.len(), 2);
+    assert!(
+      ImportMap::from_json("https://deno.land", r#"{"types": []}"#).is_err()
+    );
+  }
+
+  #[test]
+  fn unmapped_specifier_hints() {
+    let json_map = r#"{
+      "imports": {
+        "lodash": "https://example.com/lodash@4/lodash.js",
+        "preact/": "https://example.com/preact@10/"
+      },
+      "scopes": {
+        "/app/": {
+          "Moment": "https://example.com/moment.js"
+        },
+        "/vendor/": {
+          "react": "https://example.com/react.js"
+        }
+      }
+    }"#;
+    let import_map =
+      ImportMap::from_json("https://deno.land/", json_map).unwrap();
+
+    let err = import_map
+      .resolve("lodash/fp.js", "https://deno.land/app/main.ts")
+      .unwrap_err();
+    let unmapped = match err {
+      ImportMapError::UnmappedBareSpecifier(unmapped) => unmapped,
+      err => panic!("unexpected error: {:?}", err),
+    };
+    assert_eq!(unmapped.scopes, vec!["https://deno.land/app/"]);
+    assert_eq!(unmapped.near_misses, vec!["lodash"]);
+    assert_eq!(
+      unmapped.suggestion,
+      (
+        "lodash/".to_string(),
+        "https://example.com/lodash@4/".to_string()
+      )
+    );
+    assert_eq!(
+      unmapped.to_string(),
+      r#"Relative import path "lodash/fp.js" not prefixed with / or ./ or ../ and not in import map from "https://deno.land/app/main.ts"
+  note: The scopes "https://deno.land/app/" of the import map were consulted before its top level imports.
+  hint: Did you mean "lodash"?
+  hint: Add a mapping to the imports of the import map, like: "lodash/": "https://example.com/lodash@4/""#
+    );
+
+    let unmapped = UnmappedSpecifier::new(
+      "moment",
+      Some("https://deno.land/app/main.ts"),
+      Some(&import_map),
+    );
+    assert_eq!(unmapped.near_misses, vec!["Moment"]);
+
+    let unmapped = UnmappedSpecifier::new(
+      "preact",
+      Some("https://deno.land/main.ts"),
+      Some(&import_map),
+    );
+    assert!(unmapped.scopes.is_empty());
+    assert_eq!(unmapped.near_misses, vec!["preact/"]);
+
+    let unmapped = UnmappedSpecifier::new(
+      "react",
+      Some("https://deno.land/app/main.ts"),
+      Some(&import_map),
+    );
+    assert_eq!(
+      unmapped.other_scopes,
+      vec![("https://deno.land/vendor/".to_string(), "react".to_string())]
+    );
+
+    let unmapped =
+      UnmappedSpecifier::new("oak", Some("https://deno.land/main.ts"), None);
+    assert_eq!(
+      unmapped.to_string(),
+      r#"Relative import path "oak" not prefixed with / or ./ or ../ from "https://deno.land/main.ts"
+  hint: To import a local file, prefix the specifier with "./".
+  hint: To use a bare specifier, map it in an import map, like: { "imports": { "oak": "<URL of the module>" } }"#
+    );
+  }
+
   #[test]
   fn from_json_2() {
     let json_map = r#"{
@@ -7,8 +7,10 @@ use crate::media_type::MediaType;
 use deno_core::resolve_url;
 use deno_core::serde::Serialize;
 use deno_core::ModuleSpecifier;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
+use std::fmt::Write;
 use std::iter::Iterator;
 use std::path::PathBuf;
 
@@ -240,6 +242,237 @@ impl fmt::Display for ModuleGraphInfo {
   }
 }
 
+/// An edge of the module graph, between the indexes of two modules.
+struct GraphEdge {
+  from: usize,
+  to: usize,
+  is_dynamic: bool,
+  is_type: bool,
+}
+
+impl ModuleGraphInfo {
+  fn get_edges(&self) -> Vec<GraphEdge> {
+    let indexes: HashMap<&ModuleSpecifier, usize> = self
+      .modules
+      .iter()
+      .enumerate()
+      .map(|(idx, m)| (&m.specifier, idx))
+      .collect();
+    let mut edges = Vec::new();
+    for (from, module) in self.modules.iter().enumerate() {
+      for dep in &module.dependencies {
+        if let Some(to) = dep.maybe_code.as_ref().and_then(|s| indexes.get(s)) {
+          edges.push(GraphEdge {
+            from,
+            to: *to,
+            is_dynamic: dep.is_dynamic,
+            is_type: false,
+          });
+        }
+        if let Some(to) = dep.maybe_type.as_ref().and_then(|s| indexes.get(s)) {
+          edges.push(GraphEdge {
+            from,
+            to: *to,
+            is_dynamic: dep.is_dynamic,
+            is_type: true,
+          });
+        }
+      }
+    }
+    edges
+  }
+
+  /// Returns the edges of the graph, and for each edge if it is part of an
+  /// import cycle, along with the set of module indexes which are part of an
+  /// import cycle.
+  fn get_cycles(&self) -> (Vec<(GraphEdge, bool)>, HashSet<usize>) {
+    let edges = self.get_edges();
+    let components = get_components(self.modules.len(), &edges);
+    let mut cyclic_modules = HashSet::new();
+    let edges = edges
+      .into_iter()
+      .map(|edge| {
+        // every edge within a strongly connected component is part of a cycle
+        let is_cyclic = components[edge.from] == components[edge.to];
+        if is_cyclic {
+          cyclic_modules.insert(edge.from);
+          cyclic_modules.insert(edge.to);
+        }
+        (edge, is_cyclic)
+      })
+      .collect();
+    (edges, cyclic_modules)
+  }
+
+  fn get_size_label(module: &ModuleGraphInfoMod) -> String {
+    if module.error.is_some() {
+      "error".to_string()
+    } else {
+      human_size(module.size.unwrap_or(0) as f64)
+    }
+  }
+
+  /// Returns the module graph in the Graphviz DOT format.  Modules and imports
+  /// which are part of an import cycle are highlighted in red, dynamic imports
+  /// are dashed and type dependencies are dotted.
+  pub fn to_dot(&self) -> String {
+    let (edges, cyclic_modules) = self.get_cycles();
+    let mut out = String::new();
+    writeln!(out, "digraph {{").unwrap();
+    writeln!(out, "  rankdir=\"LR\";").unwrap();
+    writeln!(out, "  node [shape=\"box\"];").unwrap();
+    for (idx, module) in self.modules.iter().enumerate() {
+      let label =
+        format!("{}\\n({})", module.specifier, Self::get_size_label(module))
+          .replace('"', "\\\"");
+      let mut attrs = vec![format!("label=\"{}\"", label)];
+      if module.specifier == self.root {
+        attrs.push("style=\"bold\"".to_string());
+      }
+      if module.error.is_some() {
+        attrs.push("style=\"dashed\"".to_string());
+      }
+      if cyclic_modules.contains(&idx) {
+        attrs.push("color=\"red\"".to_string());
+      }
+      writeln!(out, "  n{} [{}];", idx, attrs.join(", ")).unwrap();
+    }
+    for (edge, is_cyclic) in &edges {
+      let mut attrs = Vec::new();
+      if edge.is_type {
+        attrs.push("style=\"dotted\"");
+      } else if edge.is_dynamic {
+        attrs.push("style=\"dashed\"");
+      }
+      if *is_cyclic {
+        attrs.push("color=\"red\"");
+      }
+      if attrs.is_empty() {
+        writeln!(out, "  n{} -> n{};", edge.from, edge.to).unwrap();
+      } else {
+        writeln!(
+          out,
+          "  n{} -> n{} [{}];",
+          edge.from,
+          edge.to,
+          attrs.join(", ")
+        )
+        .unwrap();
+      }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+  }
+
+  /// Returns the module graph as a Mermaid flowchart.  Modules and imports
+  /// which are part of an import cycle are highlighted in red, dynamic imports
+  /// are dotted and type dependencies are labelled.
+  pub fn to_mermaid(&self) -> String {
+    let (edges, cyclic_modules) = self.get_cycles();
+    let mut out = String::new();
+    writeln!(out, "flowchart LR").unwrap();
+    for (idx, module) in self.modules.iter().enumerate() {
+      let label =
+        format!("{}<br>({})", module.specifier, Self::get_size_label(module))
+          .replace('"', "#quot;");
+      writeln!(out, "  n{}[\"{}\"]", idx, label).unwrap();
+    }
+    let mut cyclic_links = Vec::new();
+    for (link_idx, (edge, is_cyclic)) in edges.iter().enumerate() {
+      let arrow = match (edge.is_type, edge.is_dynamic) {
+        (true, _) => "-. types .->",
+        (false, true) => "-.->",
+        (false, false) => "-->",
+      };
+      writeln!(out, "  n{} {} n{}", edge.from, arrow, edge.to).unwrap();
+      if *is_cyclic {
+        cyclic_links.push(link_idx.to_string());
+      }
+    }
+    let mut cyclic_modules: Vec<_> = cyclic_modules.into_iter().collect();
+    cyclic_modules.sort_unstable();
+    let error_modules: Vec<_> = self
+      .modules
+      .iter()
+      .enumerate()
+      .filter(|(_, m)| m.error.is_some())
+      .map(|(idx, _)| format!("n{}", idx))
+      .collect();
+    if !cyclic_modules.is_empty() {
+      writeln!(out, "  classDef cycle stroke:#f00,stroke-width:2px").unwrap();
+      let nodes: Vec<_> = cyclic_modules
+        .iter()
+        .map(|idx| format!("n{}", idx))
+        .collect();
+      writeln!(out, "  class {} cycle", nodes.join(",")).unwrap();
+      writeln!(
+        out,
+        "  linkStyle {} stroke:#f00,stroke-width:2px",
+        cyclic_links.join(",")
+      )
+      .unwrap();
+    }
+    if !error_modules.is_empty() {
+      writeln!(out, "  classDef error stroke-dasharray:5 5").unwrap();
+      writeln!(out, "  class {} error", error_modules.join(",")).unwrap();
+    }
+    out
+  }
+}
+
+/// Assign each node of a directed graph to its strongly connected component,
+/// using an iterative version of Kosaraju's algorithm so that deep graphs do
+/// not overflow the stack.
+fn get_components(len: usize, edges: &[GraphEdge]) -> Vec<usize> {
+  let mut forward = vec![Vec::new(); len];
+  let mut reverse = vec![Vec::new(); len];
+  for edge in edges {
+    forward[edge.from].push(edge.to);
+    reverse[edge.to].push(edge.from);
+  }
+
+  let mut visited = vec![false; len];
+  let mut order = Vec::with_capacity(len);
+  for start in 0..len {
+    if visited[start] {
+      continue;
+    }
+    visited[start] = true;
+    let mut stack = vec![(start, 0)];
+    while let Some((node, next_idx)) = stack.pop() {
+      if let Some(next) = forward[node].get(next_idx) {
+        stack.push((node, next_idx + 1));
+        if !visited[*next] {
+          visited[*next] = true;
+          stack.push((*next, 0));
+        }
+      } else {
+        order.push(node);
+      }
+    }
+  }
+
+  let mut components = vec![usize::MAX; len];
+  let mut count = 0;
+  for start in order.into_iter().rev() {
+    if components[start] != usize::MAX {
+      continue;
+    }
+    components[start] = count;
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+      for prev in &reverse[node] {
+        if components[*prev] == usize::MAX {
+          components[*prev] = count;
+          stack.push(*prev);
+        }
+      }
+    }
+    count += 1;
+  }
+  components
+}
+
 /// An entry in the `ModuleInfoMap` the provides the size of the module and
 /// a vector of its dependencies, which should also be available as entries
 /// in the map.
@@ -430,4 +663,108 @@ https://deno.land/x/a.ts (123B)
       })
     );
   }
+
+  fn get_cyclic_fixture() -> ModuleGraphInfo {
+    let specifier_a = resolve_url("https://deno.land/x/a.ts").unwrap();
+    let specifier_b = resolve_url("https://deno.land/x/b.ts").unwrap();
+    let specifier_c = resolve_url("https://deno.land/x/c.ts").unwrap();
+    let modules = vec![
+      ModuleGraphInfoMod {
+        specifier: specifier_a.clone(),
+        dependencies: vec![ModuleGraphInfoDep {
+          specifier: "./b.ts".to_string(),
+          is_dynamic: false,
+          maybe_code: Some(specifier_b.clone()),
+          maybe_type: None,
+        }],
+        size: Some(10),
+        media_type: Some(MediaType::TypeScript),
+        ..Default::default()
+      },
+      ModuleGraphInfoMod {
+        specifier: specifier_b,
+        dependencies: vec![
+          ModuleGraphInfoDep {
+            specifier: "./a.ts".to_string(),
+            is_dynamic: true,
+            maybe_code: Some(specifier_a.clone()),
+            maybe_type: None,
+          },
+          ModuleGraphInfoDep {
+            specifier: "./c.ts".to_string(),
+            is_dynamic: false,
+            maybe_code: Some(specifier_c.clone()),
+            maybe_type: None,
+          },
+        ],
+        size: Some(20),
+        media_type: Some(MediaType::TypeScript),
+        ..Default::default()
+      },
+      ModuleGraphInfoMod {
+        specifier: specifier_c,
+        error: Some("Cannot resolve module".to_string()),
+        ..Default::default()
+      },
+    ];
+    ModuleGraphInfo {
+      root: specifier_a,
+      modules,
+      size: 30,
+    }
+  }
+
+  #[test]
+  fn test_module_graph_info_dot() {
+    let fixture = get_fixture();
+    let expected = r#"digraph {
+  rankdir="LR";
+  node [shape="box"];
+  n0 [label="https://deno.land/x/a.ts\n(123B)", style="bold"];
+  n1 [label="https://deno.land/x/b.ts\n(456B)"];
+  n2 [label="https://deno.land/x/c.js\n(789B)"];
+  n3 [label="https://deno.land/x/c.d.ts\n(999B)"];
+  n0 -> n1;
+  n1 -> n2;
+  n1 -> n3 [style="dotted"];
+}
+"#;
+    assert_eq!(fixture.to_dot(), expected);
+  }
+
+  #[test]
+  fn test_module_graph_info_dot_cycles() {
+    let fixture = get_cyclic_fixture();
+    let expected = r#"digraph {
+  rankdir="LR";
+  node [shape="box"];
+  n0 [label="https://deno.land/x/a.ts\n(10B)", style="bold", color="red"];
+  n1 [label="https://deno.land/x/b.ts\n(20B)", color="red"];
+  n2 [label="https://deno.land/x/c.ts\n(error)", style="dashed"];
+  n0 -> n1 [color="red"];
+  n1 -> n0 [style="dashed", color="red"];
+  n1 -> n2;
+}
+"#;
+    assert_eq!(fixture.to_dot(), expected);
+  }
+
+  #[test]
+  fn test_module_graph_info_mermaid() {
+    let fixture = get_cyclic_fixture();
+    let expected = r#"flowchart LR
+  n0["https://deno.land/x/a.ts<br>(10B)"]
+  n1["https://deno.land/x/b.ts<br>(20B)"]
+  n2["https://deno.land/x/c.ts<br>(error)"]
+  n0 --> n1
+  n1 -.-> n0
+  n1 --> n2
+  classDef cycle stroke:#f00,stroke-width:2px
+  class n0,n1 cycle
+  linkStyle 0,1 stroke:#f00,stroke-width:2px
+  classDef error stroke-dasharray:5 5
+  class n2 error
+"#;
+    assert_eq!(fixture.to_mermaid(), expected);
+  }
 }
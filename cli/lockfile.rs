@@ -1,42 +1,117 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
 use deno_core::serde_json;
-use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use log::debug;
+use log::warn;
 use std::collections::BTreeMap;
 use std::io::Result;
 use std::path::PathBuf;
 
+const LOCKFILE_VERSION: &str = "2";
+
+/// The serialized content of a lock file.  Besides the hashes of the remote
+/// modules, it records the redirects and the types dependencies which were
+/// resolved for the graph, so that tampering with either is detected.  A
+/// redirect which changes its target, a locked module which starts redirecting
+/// and a locked redirect which stops redirecting are all rejected.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LockfileContent {
+  version: String,
+  /// A map of remote module specifiers to the hash of their source.
+  remote: BTreeMap<String, String>,
+  /// A map of remote module specifiers to the specifier they redirect to.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  redirects: BTreeMap<String, String>,
+  /// A map of remote module specifiers which are redirected more than once to
+  /// the chain of specifiers they redirect through, ending with the final one.
+  #[serde(
+    rename = "redirectChains",
+    default,
+    skip_serializing_if = "BTreeMap::is_empty"
+  )]
+  redirect_chains: BTreeMap<String, Vec<String>>,
+  /// A map of remote module specifiers to the specifier of the types which
+  /// were emitted for them, for example via the `X-TypeScript-Types` header.
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  types: BTreeMap<String, String>,
+}
+
+impl Default for LockfileContent {
+  fn default() -> Self {
+    Self {
+      version: LOCKFILE_VERSION.to_string(),
+      remote: BTreeMap::new(),
+      redirects: BTreeMap::new(),
+      redirect_chains: BTreeMap::new(),
+      types: BTreeMap::new(),
+    }
+  }
+}
+
+impl LockfileContent {
+  fn from_json(s: &str) -> Result<Self> {
+    let value: Value = serde_json::from_str(s)?;
+    if value.get("version").is_some() {
+      Ok(serde_json::from_value(value)?)
+    } else {
+      // version 1 lock files are a flat map of specifiers to hashes
+      let remote: BTreeMap<String, String> = serde_json::from_value(value)?;
+      Ok(Self {
+        remote,
+        ..Default::default()
+      })
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct Lockfile {
   write: bool,
-  map: BTreeMap<String, String>,
+  has_content_changed: bool,
+  content: LockfileContent,
+  /// When the lock file is rewritten, its previous content, which is used to
+  /// warn about changed redirects.
+  maybe_previous_content: Option<LockfileContent>,
   pub filename: PathBuf,
 }
 
 impl Lockfile {
   pub fn new(filename: PathBuf, write: bool) -> Result<Lockfile> {
-    let map = if write {
-      BTreeMap::new()
+    // when the lock file does not exist yet, it is created with the contents
+    // of the graph
+    let content = if write || !filename.exists() {
+      LockfileContent::default()
     } else {
       let s = std::fs::read_to_string(&filename)?;
-      serde_json::from_str(&s)?
+      LockfileContent::from_json(&s)?
+    };
+    let maybe_previous_content = if write && filename.exists() {
+      std::fs::read_to_string(&filename)
+        .ok()
+        .and_then(|s| LockfileContent::from_json(&s).ok())
+    } else {
+      None
     };
 
     Ok(Lockfile {
       write,
-      map,
+      has_content_changed: false,
+      content,
+      maybe_previous_content,
       filename,
     })
   }
 
-  // Synchronize lock file to disk - noop if --lock-write file is not specified.
+  // Synchronize lock file to disk - noop if --lock-write file is not specified
+  // and no new entries were added to the lock file.
   pub fn write(&self) -> Result<()> {
-    if !self.write {
+    if !self.write && !self.has_content_changed {
       return Ok(());
     }
-    let j = json!(&self.map);
-    let s = serde_json::to_string_pretty(&j).unwrap();
+    let s = serde_json::to_string_pretty(&self.content).unwrap();
     let mut f = std::fs::OpenOptions::new()
       .write(true)
       .create(true)
@@ -48,36 +123,138 @@ impl Lockfile {
     Ok(())
   }
 
+  /// Checks the hash of the given module against the lock file, adding it when
+  /// the module is not yet part of the lock file.  Returns `true` if the check
+  /// passed.
   pub fn check_or_insert(&mut self, specifier: &str, code: &str) -> bool {
+    if specifier.starts_with("file:") {
+      return true;
+    }
+    let checksum = crate::checksum::gen(&[code.as_bytes()]);
+    self.check_or_insert_entry(
+      |content| &mut content.remote,
+      specifier,
+      checksum,
+    )
+  }
+
+  /// Checks the chain of a redirect, which ends with its target, against the
+  /// lock file, adding it when the redirect is not yet part of the lock file.
+  /// A specifier which is locked as a module is not allowed to start
+  /// redirecting.  Returns `true` if the check passed.
+  pub fn check_or_insert_redirect(
+    &mut self,
+    from: &str,
+    chain: &[&str],
+  ) -> bool {
+    if from.starts_with("file:") {
+      return true;
+    }
+    let to = match chain.last() {
+      Some(to) => to.to_string(),
+      None => return true,
+    };
     if self.write {
-      // In case --lock-write is specified check always passes
-      self.insert(specifier, code);
-      true
+      self.warn_changed_redirect(from, &to);
+    } else if self.content.remote.contains_key(from)
+      && !self.content.redirects.contains_key(from)
+    {
+      return false;
+    }
+    if !self.check_or_insert_entry(|content| &mut content.redirects, from, to) {
+      return false;
+    }
+    // the chains of single redirects are not recorded, unless the redirect
+    // used to go through other specifiers
+    if chain.len() > 1 || self.content.redirect_chains.contains_key(from) {
+      let chain = chain.iter().map(|s| s.to_string()).collect();
+      self.check_or_insert_entry(
+        |content| &mut content.redirect_chains,
+        from,
+        chain,
+      )
     } else {
-      self.check(specifier, code)
+      true
     }
   }
 
-  /// Checks the given module is included.
-  /// Returns Ok(true) if check passed.
-  fn check(&mut self, specifier: &str, code: &str) -> bool {
+  /// Checks that a remote module is not locked as a redirect.  Returns `true`
+  /// if the check passed.
+  pub fn check_not_redirected(&self, specifier: &str) -> bool {
     if specifier.starts_with("file:") {
       return true;
     }
-    if let Some(lockfile_checksum) = self.map.get(specifier) {
-      let compiled_checksum = crate::checksum::gen(&[code.as_bytes()]);
-      lockfile_checksum == &compiled_checksum
+    if self.write {
+      if let Some(previous) = &self.maybe_previous_content {
+        if let Some(locked) = previous.redirects.get(specifier) {
+          warn!(
+            "The module \"{}\" is no longer redirected, but was redirected to \"{}\" before.",
+            specifier, locked
+          );
+        }
+      }
+      true
     } else {
-      false
+      !self.content.redirects.contains_key(specifier)
     }
   }
 
-  fn insert(&mut self, specifier: &str, code: &str) {
+  fn warn_changed_redirect(&self, from: &str, to: &str) {
+    if let Some(previous) = &self.maybe_previous_content {
+      match previous.redirects.get(from) {
+        Some(locked) if locked != to => warn!(
+          "The redirect of \"{}\" changed from \"{}\" to \"{}\".",
+          from, locked, to
+        ),
+        None if previous.remote.contains_key(from) => warn!(
+          "The module \"{}\" now redirects to \"{}\", but was not redirected before.",
+          from, to
+        ),
+        _ => (),
+      }
+    }
+  }
+
+  /// Checks the types dependency of a module against the lock file, adding it
+  /// when the module is not yet part of the lock file.  Returns `true` if the
+  /// check passed.
+  pub fn check_or_insert_types(
+    &mut self,
+    specifier: &str,
+    types_specifier: &str,
+  ) -> bool {
     if specifier.starts_with("file:") {
-      return;
+      return true;
+    }
+    self.check_or_insert_entry(
+      |content| &mut content.types,
+      specifier,
+      types_specifier.to_string(),
+    )
+  }
+
+  fn check_or_insert_entry<F, V>(
+    &mut self,
+    get_map: F,
+    key: &str,
+    value: V,
+  ) -> bool
+  where
+    F: Fn(&mut LockfileContent) -> &mut BTreeMap<String, V>,
+    V: PartialEq,
+  {
+    let write = self.write;
+    let map = get_map(&mut self.content);
+    match map.get(key) {
+      // In case --lock-write is specified check always passes
+      Some(locked) if !write => *locked == value,
+      Some(locked) if *locked == value => true,
+      _ => {
+        map.insert(key.to_string(), value);
+        self.has_content_changed = true;
+        true
+      }
     }
-    let checksum = crate::checksum::gen(&[code.as_bytes()]);
-    self.map.insert(specifier.to_string(), checksum);
   }
 }
 
@@ -116,7 +293,9 @@ mod tests {
   #[test]
   fn new_nonexistent_lockfile() {
     let file_path = PathBuf::from("nonexistent_lock_file.json");
-    assert!(Lockfile::new(file_path, false).is_err());
+    let lockfile = Lockfile::new(file_path, false).unwrap();
+    assert!(lockfile.content.remote.is_empty());
+    assert!(!lockfile.has_content_changed);
   }
 
   #[test]
@@ -125,7 +304,7 @@ mod tests {
 
     let result = Lockfile::new(file_path, false).unwrap();
 
-    let keys: Vec<String> = result.map.keys().cloned().collect();
+    let keys: Vec<String> = result.content.remote.keys().cloned().collect();
     let expected_keys = vec![
       String::from("https://deno.land/std@0.71.0/async/delay.ts"),
       String::from("https://deno.land/std@0.71.0/textproto/mod.ts"),
@@ -143,12 +322,12 @@ mod tests {
 
     let mut lockfile = Lockfile::new(file_path, false).unwrap();
 
-    lockfile.insert(
+    lockfile.check_or_insert(
       "https://deno.land/std@0.71.0/io/util.ts",
       "Here is some source code",
     );
 
-    let keys: Vec<String> = lockfile.map.keys().cloned().collect();
+    let keys: Vec<String> = lockfile.content.remote.keys().cloned().collect();
     let expected_keys = vec![
       String::from("https://deno.land/std@0.71.0/async/delay.ts"),
       String::from("https://deno.land/std@0.71.0/io/util.ts"),
@@ -166,15 +345,15 @@ mod tests {
 
     let mut lockfile = Lockfile::new(file_path, true).unwrap();
 
-    lockfile.insert(
+    lockfile.check_or_insert(
       "https://deno.land/std@0.71.0/textproto/mod.ts",
       "Here is some source code",
     );
-    lockfile.insert(
+    lockfile.check_or_insert(
       "https://deno.land/std@0.71.0/io/util.ts",
       "more source code here",
     );
-    lockfile.insert(
+    lockfile.check_or_insert(
       "https://deno.land/std@0.71.0/async/delay.ts",
       "this source is really exciting",
     );
@@ -193,7 +372,8 @@ mod tests {
 
     let contents_json =
       serde_json::from_str::<serde_json::Value>(&contents).unwrap();
-    let object = contents_json.as_object().unwrap();
+    assert_eq!(contents_json["version"], json!("2"));
+    let object = contents_json["remote"].as_object().unwrap();
 
     assert_eq!(
       object
@@ -225,23 +405,142 @@ mod tests {
 
     let mut lockfile = Lockfile::new(file_path, false).unwrap();
 
-    lockfile.insert(
-      "https://deno.land/std@0.71.0/textproto/mod.ts",
+    lockfile.check_or_insert(
+      "https://deno.land/std@0.71.0/io/util.ts",
       "Here is some source code",
     );
 
     let check_true = lockfile.check_or_insert(
-      "https://deno.land/std@0.71.0/textproto/mod.ts",
+      "https://deno.land/std@0.71.0/io/util.ts",
       "Here is some source code",
     );
     assert!(check_true);
 
     let check_false = lockfile.check_or_insert(
-      "https://deno.land/std@0.71.0/textproto/mod.ts",
+      "https://deno.land/std@0.71.0/io/util.ts",
       "This is new Source code",
     );
     assert!(!check_false);
 
     teardown(temp_dir);
   }
+
+  #[test]
+  fn check_or_insert_adds_missing_entries() {
+    let (temp_dir, file_path) = setup();
+
+    let mut lockfile = Lockfile::new(file_path.clone(), false).unwrap();
+    assert!(lockfile.check_or_insert(
+      "https://deno.land/std@0.71.0/io/util.ts",
+      "Here is some source code",
+    ));
+    assert!(lockfile.has_content_changed);
+    lockfile.write().expect("unable to write");
+
+    // the lock file is upgraded to version 2 and keeps the existing entries
+    let lockfile = Lockfile::new(file_path, false).unwrap();
+    assert_eq!(lockfile.content.version, "2");
+    assert_eq!(lockfile.content.remote.len(), 3);
+
+    teardown(temp_dir);
+  }
+
+  #[test]
+  fn check_or_insert_redirects_and_types() {
+    let (temp_dir, file_path) = setup();
+
+    let mut lockfile = Lockfile::new(file_path, false).unwrap();
+    assert!(lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &["https://deno.land/x/lib@1.0.0/mod.ts"],
+    ));
+    assert!(lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &["https://deno.land/x/lib@1.0.0/mod.ts"],
+    ));
+    assert!(!lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &["https://example.com/x/lib/mod.ts"],
+    ));
+
+    assert!(lockfile.check_or_insert_types(
+      "https://deno.land/x/lib/mod.js",
+      "https://deno.land/x/lib/mod.d.ts",
+    ));
+    assert!(!lockfile.check_or_insert_types(
+      "https://deno.land/x/lib/mod.js",
+      "https://example.com/x/lib/mod.d.ts",
+    ));
+
+    teardown(temp_dir);
+  }
+
+  #[test]
+  fn check_or_insert_redirect_chains() {
+    let (temp_dir, file_path) = setup();
+
+    let mut lockfile = Lockfile::new(file_path, false).unwrap();
+    assert!(lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &[
+        "https://deno.land/x/lib@1.0.0/mod.ts",
+        "https://cdn.deno.land/lib/1.0.0/mod.ts",
+      ],
+    ));
+    assert_eq!(lockfile.content.redirect_chains.len(), 1);
+    // the final target is the same, but the redirect goes somewhere new
+    assert!(!lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &[
+        "https://example.com/x/lib/mod.ts",
+        "https://cdn.deno.land/lib/1.0.0/mod.ts",
+      ],
+    ));
+    assert!(!lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &["https://cdn.deno.land/lib/1.0.0/mod.ts"],
+    ));
+
+    teardown(temp_dir);
+  }
+
+  #[test]
+  fn check_redirect_changes() {
+    let (temp_dir, file_path) = setup();
+
+    let mut lockfile = Lockfile::new(file_path, false).unwrap();
+    // a locked module starting to redirect is rejected
+    assert!(!lockfile.check_or_insert_redirect(
+      "https://deno.land/std@0.71.0/async/delay.ts",
+      &["https://example.com/delay.ts"],
+    ));
+    // a locked redirect which is no longer redirected is rejected
+    assert!(lockfile.check_or_insert_redirect(
+      "https://deno.land/x/lib/mod.ts",
+      &["https://deno.land/x/lib@1.0.0/mod.ts"],
+    ));
+    assert!(!lockfile.check_not_redirected("https://deno.land/x/lib/mod.ts"));
+    assert!(
+      lockfile.check_not_redirected("https://deno.land/x/lib@1.0.0/mod.ts")
+    );
+
+    teardown(temp_dir);
+  }
+
+  #[test]
+  fn check_redirect_changes_lock_write() {
+    let (temp_dir, file_path) = setup();
+
+    // when the lock file is rewritten, the changes are accepted
+    let mut lockfile = Lockfile::new(file_path, true).unwrap();
+    assert!(lockfile.maybe_previous_content.is_some());
+    assert!(lockfile.check_or_insert_redirect(
+      "https://deno.land/std@0.71.0/async/delay.ts",
+      &["https://example.com/delay.ts"],
+    ));
+    assert!(lockfile
+      .check_not_redirected("https://deno.land/std@0.71.0/async/delay.ts"));
+
+    teardown(temp_dir);
+  }
 }
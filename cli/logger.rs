@@ -1,14 +1,48 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::colors;
+use crate::flags::LogFormat;
+use deno_core::serde_json::json;
 use std::io::Write;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 lazy_static::lazy_static! {
   pub static ref LSP_DEBUG_FLAG: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
 }
 
+static JSON_FORMAT: AtomicBool = AtomicBool::new(false);
+
+/// Returns whether diagnostic output is printed as JSON lines
+/// (`--log-format=json`), for output which doesn't go through the logger.
+pub(crate) fn is_json() -> bool {
+  JSON_FORMAT.load(Ordering::Relaxed)
+}
+
+/// Formats a log message as a JSON line without the trailing newline. The
+/// timestamp is in milliseconds since the UNIX epoch and colors are stripped
+/// from the message.
+pub(crate) fn json_line(
+  level: log::Level,
+  target: &str,
+  message: &str,
+) -> String {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0);
+  json!({
+    "timestamp": timestamp,
+    "level": level.to_string(),
+    "target": target,
+    "message": colors::strip_ansi_codes(message),
+  })
+  .to_string()
+}
+
 struct CliLogger(env_logger::Logger);
 
 impl CliLogger {
@@ -43,7 +77,18 @@ impl log::Log for CliLogger {
   }
 }
 
-pub(crate) fn init(maybe_level: Option<log::Level>) {
+pub(crate) fn init(maybe_level: Option<log::Level>, log_format: LogFormat) {
+  let json_format = log_format == LogFormat::Json;
+  JSON_FORMAT.store(json_format, Ordering::Relaxed);
+  if json_format {
+    deno_runtime::permissions::set_prompt_printer(|message| {
+      eprintln!(
+        "{}",
+        json_line(log::Level::Info, "deno::permissions", message)
+      )
+    });
+  }
+
   let log_level = maybe_level.unwrap_or(log::Level::Info);
   let logger = env_logger::Builder::from_env(
     env_logger::Env::default()
@@ -57,7 +102,14 @@ pub(crate) fn init(maybe_level: Option<log::Level>) {
   // used to make available the lsp_debug which is then filtered out at runtime
   // in the cli logger
   .filter_module("deno::lsp::performance", log::LevelFilter::Debug)
-  .format(|buf, record| {
+  .format(move |buf, record| {
+    if json_format {
+      return writeln!(
+        buf,
+        "{}",
+        json_line(record.level(), record.target(), &record.args().to_string())
+      );
+    }
     let mut target = record.target().to_string();
     if let Some(line_no) = record.line() {
       target.push(':');
@@ -5,6 +5,8 @@ use super::tsc;
 
 use crate::ast;
 use crate::import_map::ImportMap;
+use crate::import_map::ImportMapError;
+use crate::import_map::UnmappedSpecifier;
 use crate::lsp::documents::DocumentData;
 use crate::media_type::MediaType;
 use crate::module_graph::parse_deno_types;
@@ -156,6 +158,7 @@ pub fn get_lint_references(
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Dependency {
   pub is_dynamic: bool,
+  pub maybe_assert_type: Option<String>,
   pub maybe_code: Option<ResolvedDependency>,
   pub maybe_code_specifier_range: Option<Range>,
   pub maybe_type: Option<ResolvedDependency>,
@@ -168,6 +171,7 @@ pub enum ResolvedDependencyErr {
   InvalidLocalImport,
   InvalidSpecifier(ModuleResolutionError),
   Missing,
+  UnmappedSpecifier(Box<UnmappedSpecifier>),
 }
 
 impl ResolvedDependencyErr {
@@ -183,6 +187,9 @@ impl ResolvedDependencyErr {
         lsp::NumberOrString::String("invalid-specifier".to_string())
       }
       Self::Missing => lsp::NumberOrString::String("missing".to_string()),
+      Self::UnmappedSpecifier(_) => {
+        lsp::NumberOrString::String("unmapped-specifier".to_string())
+      }
     }
   }
 }
@@ -198,6 +205,7 @@ impl fmt::Display for ResolvedDependencyErr {
       }
       Self::InvalidSpecifier(err) => write!(f, "{}", err),
       Self::Missing => write!(f, "The module is unexpectedly missing."),
+      Self::UnmappedSpecifier(unmapped) => write!(f, "{}", unmapped),
     }
   }
 }
@@ -214,7 +222,15 @@ pub fn resolve_import(
   maybe_import_map: &Option<ImportMap>,
 ) -> ResolvedDependency {
   let maybe_mapped = if let Some(import_map) = maybe_import_map {
-    import_map.resolve(specifier, referrer.as_str()).ok()
+    match import_map.resolve(specifier, referrer.as_str()) {
+      Ok(resolved) => Some(resolved),
+      Err(ImportMapError::UnmappedBareSpecifier(unmapped)) => {
+        return ResolvedDependency::Err(
+          ResolvedDependencyErr::UnmappedSpecifier(unmapped),
+        )
+      }
+      Err(_) => None,
+    }
   } else {
     None
   };
@@ -224,6 +240,13 @@ pub fn resolve_import(
   } else {
     match deno_core::resolve_import(specifier, referrer.as_str()) {
       Ok(resolved) => resolved,
+      Err(ModuleResolutionError::ImportPrefixMissing(..)) => {
+        return ResolvedDependency::Err(
+          ResolvedDependencyErr::UnmappedSpecifier(Box::new(
+            UnmappedSpecifier::new(specifier, Some(referrer.as_str()), None),
+          )),
+        )
+      }
       Err(err) => {
         return ResolvedDependency::Err(
           ResolvedDependencyErr::InvalidSpecifier(err),
@@ -319,6 +342,7 @@ pub fn analyze_dependencies(
 
   // Parse ES and type only imports
   let descriptors = parsed_module.analyze_dependencies();
+  let dynamic_import_assertions = parsed_module.get_dynamic_import_assertions();
   for desc in descriptors.into_iter().filter(|desc| {
     desc.kind != swc_ecmascript::dep_graph::DependencyKind::Require
   }) {
@@ -341,6 +365,8 @@ pub fn analyze_dependencies(
 
     let dep = dependencies.entry(desc.specifier.to_string()).or_default();
     dep.is_dynamic = desc.is_dynamic;
+    dep.maybe_assert_type =
+      ast::get_assert_type(&desc, &dynamic_import_assertions);
     match desc.kind {
       swc_ecmascript::dep_graph::DependencyKind::ExportType
       | swc_ecmascript::dep_graph::DependencyKind::ImportType => {
@@ -638,6 +664,7 @@ pub struct CodeActionCollection {
 impl CodeActionCollection {
   pub(crate) fn add_deno_fix_action(
     &mut self,
+    specifier: &ModuleSpecifier,
     diagnostic: &lsp::Diagnostic,
   ) -> Result<(), AnyError> {
     if let Some(data) = diagnostic.data.clone() {
@@ -656,7 +683,7 @@ impl CodeActionCollection {
         command: Some(lsp::Command {
           title: "".to_string(),
           command: "deno.cache".to_string(),
-          arguments: Some(vec![json!([fix_data.specifier])]),
+          arguments: Some(vec![json!([fix_data.specifier]), json!(specifier)]),
         }),
         is_preferred: None,
         disabled: None,
@@ -1011,6 +1038,30 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_resolve_import_unmapped() {
+    let referrer = resolve_url("file:///a/main.ts").unwrap();
+    let import_map = ImportMap::from_json(
+      "file:///a/import_map.json",
+      r#"{ "imports": { "oak": "https://deno.land/x/oak/mod.ts" } }"#,
+    )
+    .unwrap();
+    match resolve_import("oaks", &referrer, &Some(import_map)) {
+      ResolvedDependency::Err(ResolvedDependencyErr::UnmappedSpecifier(
+        unmapped,
+      )) => {
+        assert!(unmapped.has_import_map);
+        assert_eq!(unmapped.near_misses, vec!["oak"]);
+      }
+      result => panic!("unexpected result: {:?}", result),
+    }
+    let result = resolve_import("oak", &referrer, &None);
+    assert!(matches!(
+      result,
+      ResolvedDependency::Err(ResolvedDependencyErr::UnmappedSpecifier(_))
+    ));
+  }
+
   #[test]
   fn test_as_lsp_range() {
     let fixture = deno_lint::diagnostic::Range {
@@ -1100,6 +1151,7 @@ mod tests {
       actual.get("https://cdn.skypack.dev/react").cloned(),
       Some(Dependency {
         is_dynamic: false,
+        maybe_assert_type: None,
         maybe_code: Some(ResolvedDependency::Resolved(
           resolve_url("https://cdn.skypack.dev/react").unwrap()
         )),
@@ -1132,6 +1184,7 @@ mod tests {
       actual.get("https://deno.land/x/oak@v6.3.2/mod.ts").cloned(),
       Some(Dependency {
         is_dynamic: false,
+        maybe_assert_type: None,
         maybe_code: Some(ResolvedDependency::Resolved(
           resolve_url("https://deno.land/x/oak@v6.3.2/mod.ts").unwrap()
         )),
@@ -12,6 +12,7 @@ use lspower::lsp::CodeActionOptions;
 use lspower::lsp::CodeActionProviderCapability;
 use lspower::lsp::CodeLensOptions;
 use lspower::lsp::CompletionOptions;
+use lspower::lsp::ExecuteCommandOptions;
 use lspower::lsp::FoldingRangeProviderCapability;
 use lspower::lsp::HoverProviderCapability;
 use lspower::lsp::ImplementationProviderCapability;
@@ -30,6 +31,7 @@ use lspower::lsp::WorkDoneProgressOptions;
 use lspower::lsp::WorkspaceFoldersServerCapabilities;
 use lspower::lsp::WorkspaceServerCapabilities;
 
+use super::lsp_custom;
 use super::semantic_tokens::get_legend;
 
 fn code_action_capabilities(
@@ -122,7 +124,15 @@ pub fn server_capabilities(
     rename_provider: Some(OneOf::Left(true)),
     document_link_provider: None,
     color_provider: None,
-    execute_command_provider: None,
+    execute_command_provider: Some(ExecuteCommandOptions {
+      commands: vec![
+        lsp_custom::CACHE_COMMAND.to_string(),
+        lsp_custom::RELOAD_IMPORT_REGISTRIES_COMMAND.to_string(),
+      ],
+      work_done_progress_options: WorkDoneProgressOptions {
+        work_done_progress: None,
+      },
+    }),
     call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
     semantic_tokens_provider: Some(
       SemanticTokensServerCapabilities::SemanticTokensOptions(
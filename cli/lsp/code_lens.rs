@@ -4,6 +4,8 @@ use super::analysis;
 use super::language_server;
 use super::tsc;
 
+use crate::media_type::MediaType;
+
 use deno_core::error::anyhow;
 use deno_core::error::AnyError;
 use deno_core::resolve_url;
@@ -59,8 +61,15 @@ fn span_to_range(span: &Span, source_map: Rc<SourceMap>) -> lsp::Range {
   }
 }
 
+/// A test which has been statically identified within a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestDefinition {
+  pub name: String,
+  pub range: lsp::Range,
+}
+
 struct DenoTestCollector {
-  code_lenses: Vec<lsp::CodeLens>,
+  definitions: Vec<TestDefinition>,
   source_map: Rc<SourceMap>,
   specifier: ModuleSpecifier,
   test_vars: HashSet<String>,
@@ -69,23 +78,18 @@ struct DenoTestCollector {
 impl DenoTestCollector {
   pub fn new(specifier: ModuleSpecifier, source_map: Rc<SourceMap>) -> Self {
     Self {
-      code_lenses: Vec::new(),
+      definitions: Vec::new(),
       source_map,
       specifier,
       test_vars: HashSet::new(),
     }
   }
 
-  fn add_code_lens<N: AsRef<str>>(&mut self, name: N, span: &Span) {
+  fn add_definition<N: AsRef<str>>(&mut self, name: N, span: &Span) {
     let range = span_to_range(span, self.source_map.clone());
-    self.code_lenses.push(lsp::CodeLens {
+    self.definitions.push(TestDefinition {
+      name: name.as_ref().to_string(),
       range,
-      command: Some(lsp::Command {
-        title: "▶\u{fe0e} Run Test".to_string(),
-        command: "deno.test".to_string(),
-        arguments: Some(vec![json!(self.specifier), json!(name.as_ref())]),
-      }),
-      data: None,
     });
   }
 
@@ -102,7 +106,7 @@ impl DenoTestCollector {
                       key_value_prop.value.as_ref()
                     {
                       let name = lit_str.value.to_string();
-                      self.add_code_lens(name, &span);
+                      self.add_definition(name, &span);
                     }
                   }
                 }
@@ -112,7 +116,7 @@ impl DenoTestCollector {
         }
         ast::Expr::Lit(ast::Lit::Str(lit_str)) => {
           let name = lit_str.value.to_string();
-          self.add_code_lens(name, &span);
+          self.add_definition(name, &span);
         }
         _ => (),
       }
@@ -121,7 +125,25 @@ impl DenoTestCollector {
 
   /// Move out the code lenses from the collector.
   fn take(self) -> Vec<lsp::CodeLens> {
-    self.code_lenses
+    let specifier = self.specifier;
+    self
+      .definitions
+      .into_iter()
+      .map(|definition| lsp::CodeLens {
+        range: definition.range,
+        command: Some(lsp::Command {
+          title: "▶\u{fe0e} Run Test".to_string(),
+          command: "deno.test".to_string(),
+          arguments: Some(vec![json!(specifier), json!(definition.name)]),
+        }),
+        data: None,
+      })
+      .collect()
+  }
+
+  /// Move out the test definitions from the collector.
+  fn take_definitions(self) -> Vec<TestDefinition> {
+    self.definitions
   }
 }
 
@@ -375,6 +397,37 @@ pub(crate) async fn collect(
   Ok(code_lenses)
 }
 
+/// Parse the source and visit it with a test collector, returning `None` if
+/// the source could not be parsed.
+fn visit_tests(
+  specifier: &ModuleSpecifier,
+  source: &str,
+  media_type: &MediaType,
+) -> Option<DenoTestCollector> {
+  let parsed_module =
+    analysis::parse_module(specifier, source, media_type).ok()?;
+  let mut collector =
+    DenoTestCollector::new(specifier.clone(), parsed_module.source_map.clone());
+  parsed_module.module.visit_with(
+    &ast::Invalid {
+      span: swc_common::DUMMY_SP,
+    },
+    &mut collector,
+  );
+  Some(collector)
+}
+
+/// Statically discover the tests defined in a module, returning `None` if the
+/// source could not be parsed.
+pub(crate) fn collect_test_definitions(
+  specifier: &ModuleSpecifier,
+  source: &str,
+  media_type: &MediaType,
+) -> Option<Vec<TestDefinition>> {
+  visit_tests(specifier, source, media_type)
+    .map(|collector| collector.take_definitions())
+}
+
 fn collect_test(
   specifier: &ModuleSpecifier,
   language_server: &mut language_server::Inner,
@@ -389,19 +442,7 @@ fn collect_test(
     // we swallow parsed errors, as they are meaningless here.
     // TODO(@kitsonk) consider caching previous code_lens results to return if
     // there is a parse error to avoid issues of lenses popping in and out
-    if let Ok(parsed_module) =
-      analysis::parse_module(specifier, &source, &media_type)
-    {
-      let mut collector = DenoTestCollector::new(
-        specifier.clone(),
-        parsed_module.source_map.clone(),
-      );
-      parsed_module.module.visit_with(
-        &ast::Invalid {
-          span: swc_common::DUMMY_SP,
-        },
-        &mut collector,
-      );
+    if let Some(collector) = visit_tests(specifier, &source, &media_type) {
       return Ok(collector.take());
     }
   }
@@ -505,7 +546,40 @@ async fn collect_tsc(
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::media_type::MediaType;
+
+  #[test]
+  fn test_collect_test_definitions() {
+    let specifier = resolve_url("file:///a/mod_test.ts").unwrap();
+    let source = r#"
+      const { test } = Deno;
+
+      test("test a", () => {});
+    "#;
+    let actual =
+      collect_test_definitions(&specifier, source, &MediaType::TypeScript);
+    assert_eq!(
+      actual,
+      Some(vec![TestDefinition {
+        name: "test a".to_string(),
+        range: lsp::Range {
+          start: lsp::Position {
+            line: 3,
+            character: 6
+          },
+          end: lsp::Position {
+            line: 3,
+            character: 10
+          }
+        },
+      }])
+    );
+    let actual = collect_test_definitions(
+      &specifier,
+      "Deno.test(",
+      &MediaType::TypeScript,
+    );
+    assert_eq!(actual, None);
+  }
 
   #[test]
   fn test_deno_test_collector() {
@@ -7,6 +7,7 @@ use super::sources::Sources;
 use super::tsc;
 
 use crate::diagnostics;
+use crate::import_assertions;
 use crate::media_type::MediaType;
 use crate::tokio_util::create_basic_runtime;
 
@@ -152,6 +153,14 @@ impl DiagnosticsServer {
         // A flag that is set whenever something has changed that requires the
         // diagnostics collection to be updated.
         let mut dirty = false;
+        // Type checking can take much longer than linting and analyzing the
+        // dependencies, so it runs as its own task, which publishes its
+        // diagnostics when it is done. Changes which arrive while it runs are
+        // type checked by the next task, so at most one is running and the
+        // checks are not queued up behind each other while the user types.
+        let mut ts_dirty = false;
+        let mut ts_running = false;
+        let (ts_done_tx, mut ts_done_rx) = mpsc::unbounded_channel::<()>();
 
         let debounce_timer = sleep(NEVER);
         tokio::pin!(debounce_timer);
@@ -168,21 +177,43 @@ impl DiagnosticsServer {
                 None => break,
                 Some(_) => {
                   dirty = true;
+                  ts_dirty = true;
                   debounce_timer.as_mut().reset(Instant::now() + DELAY);
                 }
               }
             }
+            Some(_) = ts_done_rx.recv() => {
+              ts_running = false;
+              if ts_dirty {
+                dirty = true;
+                debounce_timer.as_mut().reset(Instant::now() + DELAY);
+              }
+            }
             _ = debounce_timer.as_mut(), if dirty => {
               dirty = false;
               debounce_timer.as_mut().reset(Instant::now() + NEVER);
 
               let snapshot = language_server.lock().await.snapshot().unwrap();
-              update_diagnostics(
-                &client,
-                collection.clone(),
-                &snapshot,
-                &ts_server
-              ).await;
+              if ts_dirty && !ts_running {
+                ts_dirty = false;
+                ts_running = true;
+                let client = client.clone();
+                let collection = collection.clone();
+                let snapshot = snapshot.clone();
+                let ts_server = ts_server.clone();
+                let ts_done_tx = ts_done_tx.clone();
+                tokio::spawn(async move {
+                  update_ts_diagnostics(
+                    &client,
+                    collection,
+                    &snapshot,
+                    &ts_server,
+                  )
+                  .await;
+                  let _ = ts_done_tx.send(());
+                });
+              }
+              update_diagnostics(&client, collection.clone(), &snapshot).await;
             }
           }
         }
@@ -451,6 +482,41 @@ fn diagnose_dependency(
   }
 }
 
+/// Validate the import assertions of a code dependency against the media type
+/// of the module it resolved to, when the module is available.
+fn diagnose_import_assertions(
+  diagnostics: &mut Vec<lsp::Diagnostic>,
+  documents: &DocumentCache,
+  sources: &Sources,
+  dependency: &analysis::Dependency,
+) {
+  if let (Some(ResolvedDependency::Resolved(specifier)), Some(range)) = (
+    &dependency.maybe_code,
+    dependency.maybe_code_specifier_range,
+  ) {
+    let maybe_media_type = if documents.contains_key(specifier) {
+      Some(MediaType::from(specifier))
+    } else {
+      sources.get_media_type(specifier)
+    };
+    if let Some(media_type) = maybe_media_type {
+      if let Err(err) = import_assertions::validate(
+        dependency.maybe_assert_type.as_deref(),
+        &media_type,
+      ) {
+        diagnostics.push(lsp::Diagnostic {
+          range,
+          severity: Some(lsp::DiagnosticSeverity::Error),
+          code: Some(lsp::NumberOrString::String(err.as_code().to_string())),
+          source: Some("deno".to_string()),
+          message: err.to_string(),
+          ..Default::default()
+        });
+      }
+    }
+  }
+}
+
 /// Generate diagnostics for dependencies of a module, attempting to resolve
 /// dependencies on the local file system or in the DENO_DIR cache.
 async fn generate_deps_diagnostics(
@@ -490,6 +556,12 @@ async fn generate_deps_diagnostics(
               &dependency.maybe_type,
               &dependency.maybe_type_specifier_range,
             );
+            diagnose_import_assertions(
+              &mut diagnostics,
+              &documents,
+              &sources,
+              &dependency,
+            );
           }
         }
         diagnostics_vec.push((specifier.clone(), version, diagnostics));
@@ -536,13 +608,13 @@ async fn publish_diagnostics(
   }
 }
 
-/// Updates diagnostics for any specifiers that don't have the correct version
-/// generated and publishes the diagnostics to the client.
+/// Updates the lint and dependency diagnostics for any specifiers that don't
+/// have the correct version generated and publishes the diagnostics to the
+/// client.
 async fn update_diagnostics(
   client: &lspower::Client,
   collection: Arc<Mutex<DiagnosticCollection>>,
   snapshot: &language_server::StateSnapshot,
-  ts_server: &tsc::TsServer,
 ) {
   let mark = snapshot.performance.mark("update_diagnostics", None::<()>);
 
@@ -567,28 +639,6 @@ async fn update_diagnostics(
     snapshot.performance.measure(mark);
   };
 
-  let ts = async {
-    let mark = snapshot
-      .performance
-      .mark("update_diagnostics_ts", None::<()>);
-    let collection = collection.clone();
-    let diagnostics =
-      generate_ts_diagnostics(snapshot, collection.clone(), ts_server)
-        .await
-        .map_err(|err| {
-          error!("Error generating TypeScript diagnostics: {}", err);
-        })
-        .unwrap_or_default();
-    {
-      let mut collection = collection.lock().await;
-      for diagnostic_record in diagnostics {
-        collection.set(DiagnosticSource::TypeScript, diagnostic_record);
-      }
-    }
-    publish_diagnostics(client, collection, snapshot).await;
-    snapshot.performance.measure(mark);
-  };
-
   let deps = async {
     let mark = snapshot
       .performance
@@ -610,6 +660,34 @@ async fn update_diagnostics(
     snapshot.performance.measure(mark);
   };
 
-  tokio::join!(lint, ts, deps);
+  tokio::join!(lint, deps);
+  snapshot.performance.measure(mark);
+}
+
+/// Updates the TypeScript diagnostics for any specifiers that don't have the
+/// correct version generated and publishes the diagnostics to the client.
+async fn update_ts_diagnostics(
+  client: &lspower::Client,
+  collection: Arc<Mutex<DiagnosticCollection>>,
+  snapshot: &language_server::StateSnapshot,
+  ts_server: &tsc::TsServer,
+) {
+  let mark = snapshot
+    .performance
+    .mark("update_diagnostics_ts", None::<()>);
+  let diagnostics =
+    generate_ts_diagnostics(snapshot, collection.clone(), ts_server)
+      .await
+      .map_err(|err| {
+        error!("Error generating TypeScript diagnostics: {}", err);
+      })
+      .unwrap_or_default();
+  {
+    let mut collection = collection.lock().await;
+    for diagnostic_record in diagnostics {
+      collection.set(DiagnosticSource::TypeScript, diagnostic_record);
+    }
+  }
+  publish_diagnostics(client, collection, snapshot).await;
   snapshot.performance.measure(mark);
 }
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ops::Range;
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// A representation of the language id sent from the LSP client, which is used
 /// to determine how the document is handled within the language server.
@@ -149,44 +150,76 @@ impl DocumentData {
       Ok(None)
     }
   }
+
+  /// Returns the specifiers of the resolved code and type dependencies of the
+  /// document.
+  fn resolved_dependencies(&self) -> Vec<ModuleSpecifier> {
+    let mut resolved = Vec::new();
+    if let Some(dependencies) = &self.dependencies {
+      for dependency in dependencies.values() {
+        if let Some(analysis::ResolvedDependency::Resolved(specifier)) =
+          &dependency.maybe_code
+        {
+          resolved.push(specifier.clone());
+        }
+        if let Some(analysis::ResolvedDependency::Resolved(specifier)) =
+          &dependency.maybe_type
+        {
+          resolved.push(specifier.clone());
+        }
+      }
+    }
+    resolved
+  }
 }
 
+/// The documents which are open in the editor.  Documents are stored behind
+/// reference counted pointers, so that taking a snapshot of the cache (which
+/// occurs on every request) does not copy the contents of every document, and
+/// only documents which are changed are cloned.
 #[derive(Debug, Clone, Default)]
 pub struct DocumentCache {
-  dependents_graph: HashMap<ModuleSpecifier, HashSet<ModuleSpecifier>>,
-  pub docs: HashMap<ModuleSpecifier, DocumentData>,
+  dependents_graph: Arc<HashMap<ModuleSpecifier, HashSet<ModuleSpecifier>>>,
+  docs: HashMap<ModuleSpecifier, Arc<DocumentData>>,
 }
 
 impl DocumentCache {
-  /// Calculate a graph of dependents and set it on the structure.
-  fn calculate_dependents(&mut self) {
-    let mut dependents_graph: HashMap<
-      ModuleSpecifier,
-      HashSet<ModuleSpecifier>,
-    > = HashMap::new();
-    for (specifier, data) in &self.docs {
-      if let Some(dependencies) = &data.dependencies {
-        for dependency in dependencies.values() {
-          if let Some(analysis::ResolvedDependency::Resolved(dep_specifier)) =
-            &dependency.maybe_code
-          {
-            dependents_graph
-              .entry(dep_specifier.clone())
-              .or_default()
-              .insert(specifier.clone());
-          }
-          if let Some(analysis::ResolvedDependency::Resolved(dep_specifier)) =
-            &dependency.maybe_type
-          {
-            dependents_graph
-              .entry(dep_specifier.clone())
-              .or_default()
-              .insert(specifier.clone());
+  /// Add the document for the specifier as a dependent of each of its
+  /// dependencies in the dependents graph.
+  fn add_dependents(&mut self, specifier: &ModuleSpecifier) {
+    if let Some(doc) = self.docs.get(specifier) {
+      let dependencies = doc.resolved_dependencies();
+      if dependencies.is_empty() {
+        return;
+      }
+      let dependents_graph = Arc::make_mut(&mut self.dependents_graph);
+      for dependency in dependencies {
+        dependents_graph
+          .entry(dependency)
+          .or_default()
+          .insert(specifier.clone());
+      }
+    }
+  }
+
+  /// Remove the document for the specifier as a dependent of each of its
+  /// dependencies in the dependents graph.
+  fn remove_dependents(&mut self, specifier: &ModuleSpecifier) {
+    if let Some(doc) = self.docs.get(specifier) {
+      let dependencies = doc.resolved_dependencies();
+      if dependencies.is_empty() {
+        return;
+      }
+      let dependents_graph = Arc::make_mut(&mut self.dependents_graph);
+      for dependency in dependencies {
+        if let Some(dependents) = dependents_graph.get_mut(&dependency) {
+          dependents.remove(specifier);
+          if dependents.is_empty() {
+            dependents_graph.remove(&dependency);
           }
         }
       }
     }
-    self.dependents_graph = dependents_graph;
   }
 
   pub fn change(
@@ -205,15 +238,15 @@ impl DocumentCache {
       ));
     }
 
-    let doc = self.docs.get_mut(specifier).unwrap();
+    let doc = Arc::make_mut(self.docs.get_mut(specifier).unwrap());
     doc.apply_content_changes(content_changes)?;
     doc.version = Some(version);
     doc.content()
   }
 
   pub fn close(&mut self, specifier: &ModuleSpecifier) {
+    self.remove_dependents(specifier);
     self.docs.remove(specifier);
-    self.calculate_dependents();
   }
 
   pub fn contains_key(&self, specifier: &ModuleSpecifier) -> bool {
@@ -250,6 +283,10 @@ impl DocumentCache {
     doc.dependencies.clone()
   }
 
+  pub fn get(&self, specifier: &ModuleSpecifier) -> Option<&DocumentData> {
+    self.docs.get(specifier).map(|doc| doc.as_ref())
+  }
+
   pub fn get_navigation_tree(
     &self,
     specifier: &ModuleSpecifier,
@@ -307,9 +344,10 @@ impl DocumentCache {
     language_id: LanguageId,
     source: &str,
   ) {
+    self.remove_dependents(&specifier);
     self.docs.insert(
       specifier.clone(),
-      DocumentData::new(specifier, version, language_id, source),
+      Arc::new(DocumentData::new(specifier, version, language_id, source)),
     );
   }
 
@@ -347,9 +385,11 @@ impl DocumentCache {
     specifier: &ModuleSpecifier,
     maybe_dependencies: Option<HashMap<String, analysis::Dependency>>,
   ) -> Result<(), AnyError> {
-    if let Some(doc) = self.docs.get_mut(specifier) {
+    if self.docs.contains_key(specifier) {
+      self.remove_dependents(specifier);
+      let doc = Arc::make_mut(self.docs.get_mut(specifier).unwrap());
       doc.dependencies = maybe_dependencies;
-      self.calculate_dependents();
+      self.add_dependents(specifier);
       Ok(())
     } else {
       Err(custom_error(
@@ -368,7 +408,7 @@ impl DocumentCache {
     navigation_tree: tsc::NavigationTree,
   ) -> Result<(), AnyError> {
     if let Some(doc) = self.docs.get_mut(specifier) {
-      doc.maybe_navigation_tree = Some(navigation_tree);
+      Arc::make_mut(doc).maybe_navigation_tree = Some(navigation_tree);
       Ok(())
     } else {
       Err(custom_error(
@@ -483,6 +523,59 @@ mod tests {
     assert_eq!(actual, Some("console.log(\"Hello Deno\");\n".to_string()));
   }
 
+  #[test]
+  fn test_document_cache_dependents() {
+    let mut document_cache = DocumentCache::default();
+    let specifier_a = resolve_url("file:///a/a.ts").unwrap();
+    let specifier_b = resolve_url("file:///a/b.ts").unwrap();
+    let specifier_c = resolve_url("file:///a/c.ts").unwrap();
+    let dependency_on = |specifier: &ModuleSpecifier| {
+      let mut dependencies = HashMap::new();
+      dependencies.insert(
+        specifier.to_string(),
+        analysis::Dependency {
+          is_dynamic: false,
+          maybe_assert_type: None,
+          maybe_code: Some(analysis::ResolvedDependency::Resolved(
+            specifier.clone(),
+          )),
+          maybe_code_specifier_range: None,
+          maybe_type: None,
+          maybe_type_specifier_range: None,
+        },
+      );
+      Some(dependencies)
+    };
+    for specifier in [&specifier_a, &specifier_b].iter() {
+      document_cache.open(
+        (*specifier).clone(),
+        1,
+        LanguageId::TypeScript,
+        "console.log(\"Hello Deno\");\n",
+      );
+    }
+    document_cache
+      .set_dependencies(&specifier_a, dependency_on(&specifier_b))
+      .unwrap();
+    document_cache
+      .set_dependencies(&specifier_b, dependency_on(&specifier_c))
+      .unwrap();
+    let mut dependents = document_cache.dependents(&specifier_c);
+    dependents.sort();
+    assert_eq!(dependents, vec![specifier_a.clone(), specifier_b.clone()]);
+
+    // taking a snapshot of the cache and then changing the dependencies should
+    // not alter the snapshot.
+    let snapshot = document_cache.clone();
+    document_cache.set_dependencies(&specifier_b, None).unwrap();
+    assert!(document_cache.dependents(&specifier_c).is_empty());
+    assert_eq!(snapshot.dependents(&specifier_c).len(), 2);
+
+    document_cache.close(&specifier_a);
+    assert!(document_cache.dependents(&specifier_b).is_empty());
+    assert_eq!(snapshot.dependents(&specifier_b), vec![specifier_a]);
+  }
+
   #[test]
   fn test_is_diagnosable() {
     let mut document_cache = DocumentCache::default();
@@ -73,6 +73,7 @@ pub struct StateSnapshot {
   pub maybe_config_uri: Option<ModuleSpecifier>,
   pub module_registries: registries::ModuleRegistry,
   pub performance: Performance,
+  pub project_version: usize,
   pub sources: Sources,
   pub url_map: urls::LspUrlMap,
 }
@@ -105,6 +106,10 @@ pub(crate) struct Inner {
   maybe_import_map_uri: Option<Url>,
   /// A collection of measurements which instrument that performance of the LSP.
   performance: Performance,
+  /// A version which is incremented whenever something which can change the
+  /// TypeScript program changes, so that tsc only has to check the versions of
+  /// the scripts of the program when it has.
+  project_version: usize,
   /// Cached sources that are read-only.
   sources: Sources,
   /// A memoized version of fixable diagnostic codes retrieved from TypeScript.
@@ -149,6 +154,7 @@ impl Inner {
       module_registries,
       module_registries_location,
       performance,
+      project_version: 0,
       sources,
       ts_fixable_diagnostics: Default::default(),
       ts_server,
@@ -177,7 +183,12 @@ impl Inner {
         if dep.maybe_type.is_none() {
           if let Some(ResolvedDependency::Resolved(resolved)) = &dep.maybe_code
           {
-            dep.maybe_type = self.sources.get_maybe_types(resolved);
+            dep.maybe_type = self
+              .maybe_import_map
+              .as_ref()
+              .and_then(|import_map| import_map.get_types(resolved))
+              .map(|types| ResolvedDependency::Resolved(types.clone()))
+              .or_else(|| self.sources.get_maybe_types(resolved));
           }
         }
       }
@@ -187,6 +198,72 @@ impl Inner {
     }
   }
 
+  /// If the client supports the testing API, statically discover the tests in
+  /// the module and publish them to the client.
+  async fn publish_tests(&mut self, specifier: &ModuleSpecifier, source: &str) {
+    if !self.config.client_capabilities.testing_api
+      || !self.config.specifier_enabled(specifier)
+    {
+      return;
+    }
+    let media_type = MediaType::from(specifier);
+    // if the module can't be parsed, the last published tests are retained
+    if let Some(definitions) =
+      code_lens::collect_test_definitions(specifier, source, &media_type)
+    {
+      let uri = self
+        .url_map
+        .normalize_specifier(specifier)
+        .unwrap_or_else(|_| specifier.clone());
+      let tests = definitions
+        .into_iter()
+        .map(|definition| lsp_custom::TestData {
+          name: definition.name,
+          range: definition.range,
+        })
+        .collect();
+      self
+        .client
+        .send_custom_notification::<lsp_custom::TestModuleNotification>(
+          lsp_custom::TestModuleNotificationParams {
+            text_document: TextDocumentIdentifier { uri },
+            tests,
+          },
+        )
+        .await;
+    }
+  }
+
+  /// Re-analyze the dependencies of all the documents in memory and invalidate
+  /// their diagnostics, which is needed when the state used to resolve modules,
+  /// like the import map or configuration file, has changed.
+  async fn reanalyze_documents(&mut self) {
+    match self.snapshot() {
+      Ok(snapshot) => {
+        if let Err(err) = self
+          .ts_server
+          .request::<bool>(snapshot, tsc::RequestMethod::Restart)
+          .await
+        {
+          error!("Failed to restart tsserver: {}", err);
+        }
+      }
+      Err(err) => error!("{:?}", err),
+    }
+    let specifiers = self.documents.specifiers();
+    for specifier in &specifiers {
+      if self.documents.is_diagnosable(specifier) {
+        if let Ok(Some(source)) = self.documents.content(specifier) {
+          self.analyze_dependencies(specifier, &source);
+        }
+      }
+    }
+    self.diagnostics_server.invalidate(specifiers).await;
+    if let Err(err) = self.diagnostics_server.update() {
+      error!("{}", err);
+    }
+  }
+
   /// Searches assets, open documents and external sources for a line_index,
   /// which might be performed asynchronously, hydrating in memory caches for
   /// subsequent requests.
@@ -334,6 +411,7 @@ impl Inner {
       maybe_config_uri: self.maybe_config_uri.clone(),
       module_registries: self.module_registries.clone(),
       performance: self.performance.clone(),
+      project_version: self.project_version,
       sources: self.sources.clone(),
       url_map: self.url_map.clone(),
     })
@@ -386,9 +464,17 @@ impl Inner {
         ImportMap::from_json(&import_map_url.to_string(), &import_map_json)?;
       self.maybe_import_map_uri = Some(import_map_url);
       self.maybe_import_map = Some(import_map);
+    } else if let Some(config_file) = &self.maybe_config_file {
+      // an import map can also be embedded in the configuration file
+      let maybe_import_map = config_file.to_import_map()?;
+      self.maybe_import_map_uri = None;
+      self.maybe_import_map = maybe_import_map;
     } else {
+      self.maybe_import_map_uri = None;
       self.maybe_import_map = None;
     }
+    self.sources.set_import_map(self.maybe_import_map.clone());
+    self.project_version += 1;
     self.performance.measure(mark);
     Ok(())
   }
@@ -491,6 +577,7 @@ impl Inner {
         warn!("{}", ignored_options);
       }
     }
+    self.project_version += 1;
     let _ok: bool = self
       .ts_server
       .request(self.snapshot()?, tsc::RequestMethod::Configure(tsconfig))
@@ -609,13 +696,25 @@ impl Inner {
     {
       // we are going to watch all the JSON files in the workspace, and the
       // notification handler will pick up any of the changes of those files we
-      // are interested in.
+      // are interested in. Changes to the modules of the workspace which are
+      // not open in the editor are watched as well, as the TypeScript program
+      // is only re-synchronized after a change has been noticed.
       let watch_registration_options =
         DidChangeWatchedFilesRegistrationOptions {
-          watchers: vec![FileSystemWatcher {
-            glob_pattern: "**/*.json".to_string(),
-            kind: Some(WatchKind::Change),
-          }],
+          watchers: vec![
+            FileSystemWatcher {
+              glob_pattern: "**/*.json".to_string(),
+              kind: Some(WatchKind::all()),
+            },
+            FileSystemWatcher {
+              glob_pattern: "**/*.jsonc".to_string(),
+              kind: Some(WatchKind::all()),
+            },
+            FileSystemWatcher {
+              glob_pattern: "**/*.{js,jsx,mjs,cjs,ts,tsx,mts,cts}".to_string(),
+              kind: Some(WatchKind::all()),
+            },
+          ],
         };
       let registration = Registration {
         id: "workspace/didChangeWatchedFiles".to_string(),
@@ -669,9 +768,13 @@ impl Inner {
       language_id,
       &params.text_document.text,
     );
+    self.project_version += 1;
 
     if self.documents.is_diagnosable(&specifier) {
       self.analyze_dependencies(&specifier, &params.text_document.text);
+      self
+        .publish_tests(&specifier, &params.text_document.text)
+        .await;
       self
         .diagnostics_server
         .invalidate(self.documents.dependents(&specifier))
@@ -692,8 +795,10 @@ impl Inner {
       params.content_changes,
     ) {
       Ok(Some(source)) => {
+        self.project_version += 1;
         if self.documents.is_diagnosable(&specifier) {
           self.analyze_dependencies(&specifier, &source);
+          self.publish_tests(&specifier, &source).await;
           self
             .diagnostics_server
             .invalidate(self.documents.dependents(&specifier))
@@ -719,6 +824,7 @@ impl Inner {
     }
     let specifier = self.url_map.normalize_url(&params.text_document.uri);
     self.documents.close(&specifier);
+    self.project_version += 1;
 
     if self.documents.is_diagnosable(&specifier) {
       if let Err(err) = self.diagnostics_server.update() {
@@ -771,18 +877,17 @@ impl Inner {
     }
 
     self.update_debug_flag();
-    if let Err(err) = self.update_import_map().await {
+    // the configuration file is updated first, as it can contain an import map
+    if let Err(err) = self.update_tsconfig().await {
       self.client.show_message(MessageType::Warning, err).await;
     }
-    if let Err(err) = self.update_registries().await {
+    if let Err(err) = self.update_import_map().await {
       self.client.show_message(MessageType::Warning, err).await;
     }
-    if let Err(err) = self.update_tsconfig().await {
+    if let Err(err) = self.update_registries().await {
       self.client.show_message(MessageType::Warning, err).await;
     }
-    if let Err(err) = self.diagnostics_server.update() {
-      error!("{}", err);
-    }
+    self.reanalyze_documents().await;
 
     self.performance.measure(mark);
   }
@@ -794,12 +899,16 @@ impl Inner {
     let mark = self
       .performance
       .mark("did_change_watched_files", Some(&params));
+    // a module which isn't open in the editor might have changed
+    self.project_version += 1;
+    let mut touched = false;
     // if the current import map has changed, we need to reload it
     if let Some(import_map_uri) = &self.maybe_import_map_uri {
       if params.changes.iter().any(|fe| *import_map_uri == fe.uri) {
         if let Err(err) = self.update_import_map().await {
           self.client.show_message(MessageType::Warning, err).await;
         }
+        touched = true;
       }
     }
     // if the current tsconfig has changed, we need to reload it
@@ -808,8 +917,18 @@ impl Inner {
         if let Err(err) = self.update_tsconfig().await {
           self.client.show_message(MessageType::Warning, err).await;
         }
+        // the configuration file can contain an import map
+        if let Err(err) = self.update_import_map().await {
+          self.client.show_message(MessageType::Warning, err).await;
+        }
+        touched = true;
       }
     }
+    // the resolution of modules might have changed, so any dependencies and
+    // diagnostics need to be refreshed
+    if touched {
+      self.reanalyze_documents().await;
+    }
     self.performance.measure(mark);
   }
 
@@ -1049,18 +1168,16 @@ impl Inner {
             }
           }
         }
-        Some("deno") => {
-          code_actions
-            .add_deno_fix_action(diagnostic)
-            .map_err(|err| {
-              error!("{}", err);
-              LspError::internal_error()
-            })?
-        }
+        Some("deno") => code_actions
+          .add_deno_fix_action(&specifier, diagnostic)
+          .map_err(|err| {
+            error!("{}", err);
+            LspError::internal_error()
+          })?,
         Some("deno-lint") => code_actions
           .add_deno_lint_ignore_action(
             &specifier,
-            self.documents.docs.get(&specifier),
+            self.documents.get(&specifier),
             diagnostic,
           )
           .map_err(|err| {
@@ -1819,6 +1936,62 @@ impl Inner {
     }
   }
 
+  async fn execute_command(
+    &mut self,
+    params: ExecuteCommandParams,
+  ) -> LspResult<Option<Value>> {
+    match params.command.as_str() {
+      lsp_custom::CACHE_COMMAND => {
+        let mut arguments = params.arguments.into_iter();
+        let uris: Vec<Url> = match arguments.next().map(from_value) {
+          Some(Ok(uris)) => uris,
+          Some(Err(err)) => {
+            return Err(LspError::invalid_params(err.to_string()))
+          }
+          None => Vec::new(),
+        };
+        // the referrer is optional, and when it isn't provided, the first of
+        // the supplied specifiers is used as the referrer.
+        let referrer: Url = match arguments.next().map(from_value) {
+          Some(Ok(referrer)) => referrer,
+          Some(Err(err)) => {
+            return Err(LspError::invalid_params(err.to_string()))
+          }
+          None => match uris.first() {
+            Some(uri) => uri.clone(),
+            None => {
+              return Err(LspError::invalid_params(
+                "Missing specifiers to cache.",
+              ))
+            }
+          },
+        };
+        self
+          .cache(lsp_custom::CacheParams {
+            referrer: TextDocumentIdentifier { uri: referrer },
+            uris: uris
+              .into_iter()
+              .map(|uri| TextDocumentIdentifier { uri })
+              .collect(),
+          })
+          .await
+      }
+      lsp_custom::RELOAD_IMPORT_REGISTRIES_COMMAND => {
+        self.reload_import_registries().await
+      }
+      _ => {
+        error!(
+          "Got a {} command, but no handler is defined",
+          params.command
+        );
+        Err(LspError::invalid_params(format!(
+          "Unsupported command: {}",
+          params.command
+        )))
+      }
+    }
+  }
+
   async fn request_else(
     &mut self,
     method: &str,
@@ -2234,6 +2407,13 @@ impl lspower::LanguageServer for LanguageServer {
     self.0.lock().await.rename(params).await
   }
 
+  async fn execute_command(
+    &self,
+    params: ExecuteCommandParams,
+  ) -> LspResult<Option<Value>> {
+    self.0.lock().await.execute_command(params).await
+  }
+
   async fn request_else(
     &self,
     method: &str,
@@ -2313,6 +2493,7 @@ impl Inner {
     }
     // now that we have dependencies loaded, we need to re-analyze them and
     // invalidate some diagnostics
+    self.project_version += 1;
     if self.documents.contains_key(&referrer) {
       if let Some(source) = self.documents.content(&referrer).unwrap() {
         self.analyze_dependencies(&referrer, &source);
@@ -4,8 +4,11 @@ use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use lspower::lsp;
 
+pub const CACHE_COMMAND: &str = "deno.cache";
 pub const CACHE_REQUEST: &str = "deno/cache";
 pub const PERFORMANCE_REQUEST: &str = "deno/performance";
+pub const RELOAD_IMPORT_REGISTRIES_COMMAND: &str =
+  "deno.reloadImportRegistries";
 pub const RELOAD_IMPORT_REGISTRIES_REQUEST: &str =
   "deno/reloadImportRegistries";
 pub const VIRTUAL_TEXT_DOCUMENT: &str = "deno/virtualTextDocument";
@@ -35,6 +38,29 @@ impl lsp::notification::Notification for RegistryStateNotification {
   const METHOD: &'static str = "deno/registryState";
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TestData {
+  /// The name of the test, as provided to `Deno.test()`.
+  pub name: String,
+  /// The range of the `Deno.test()` call in the module.
+  pub range: lsp::Range,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestModuleNotificationParams {
+  pub text_document: lsp::TextDocumentIdentifier,
+  pub tests: Vec<TestData>,
+}
+
+pub enum TestModuleNotification {}
+
+impl lsp::notification::Notification for TestModuleNotification {
+  type Params = TestModuleNotificationParams;
+
+  const METHOD: &'static str = "deno/testModule";
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VirtualTextDocumentParams {
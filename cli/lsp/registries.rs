@@ -15,6 +15,7 @@ use crate::deno_dir;
 use crate::file_fetcher::CacheSetting;
 use crate::file_fetcher::FileFetcher;
 use crate::http_cache::HttpCache;
+use crate::http_util::ProxyConfig;
 
 use deno_core::error::anyhow;
 use deno_core::error::AnyError;
@@ -264,6 +265,7 @@ impl Default for ModuleRegistry {
       cache_setting,
       true,
       None,
+      ProxyConfig::from_env(None).ok().flatten(),
       BlobUrlStore::default(),
     )
     .unwrap();
@@ -283,6 +285,7 @@ impl ModuleRegistry {
       CacheSetting::Use,
       true,
       None,
+      ProxyConfig::from_env(None).ok().flatten(),
       BlobUrlStore::default(),
     )
     .context("Error creating file fetcher in module registry.")
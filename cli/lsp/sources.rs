@@ -160,7 +160,9 @@ impl Metadata {
 struct Inner {
   http_cache: HttpCache,
   maybe_import_map: Option<ImportMap>,
-  metadata: HashMap<ModuleSpecifier, Metadata>,
+  /// The metadata of the sources which have been loaded, which is shared with
+  /// the callers instead of being copied on every lookup.
+  metadata: HashMap<ModuleSpecifier, Arc<Metadata>>,
   redirects: HashMap<ModuleSpecifier, ModuleSpecifier>,
   remotes: HashMap<ModuleSpecifier, PathBuf>,
 }
@@ -239,6 +241,10 @@ impl Sources {
     self.0.lock().unwrap().metadata.keys().cloned().collect()
   }
 
+  pub fn set_import_map(&self, maybe_import_map: Option<ImportMap>) {
+    self.0.lock().unwrap().set_import_map(maybe_import_map)
+  }
+
   pub fn set_navigation_tree(
     &self,
     specifier: &ModuleSpecifier,
@@ -260,6 +266,14 @@ impl Inner {
     }
   }
 
+  /// Set the import map used to resolve the dependencies of sources, which
+  /// also invalidates any previously analyzed metadata, as the dependencies
+  /// might resolve differently.
+  fn set_import_map(&mut self, maybe_import_map: Option<ImportMap>) {
+    self.maybe_import_map = maybe_import_map;
+    self.metadata.clear();
+  }
+
   fn calculate_script_version(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -295,7 +309,7 @@ impl Inner {
     let specifier =
       resolve_specifier(specifier, &mut self.redirects, &self.http_cache)?;
     let metadata = self.get_metadata(&specifier)?;
-    Some(metadata.line_index)
+    Some(metadata.line_index.clone())
   }
 
   fn get_maybe_types(
@@ -305,7 +319,7 @@ impl Inner {
     let specifier =
       resolve_specifier(specifier, &mut self.redirects, &self.http_cache)?;
     let metadata = self.get_metadata(&specifier)?;
-    metadata.maybe_types
+    metadata.maybe_types.clone()
   }
 
   fn get_maybe_warning(
@@ -313,7 +327,7 @@ impl Inner {
     specifier: &ModuleSpecifier,
   ) -> Option<String> {
     let metadata = self.get_metadata(&specifier)?;
-    metadata.maybe_warning
+    metadata.maybe_warning.clone()
   }
 
   fn get_media_type(
@@ -326,7 +340,10 @@ impl Inner {
     Some(metadata.media_type)
   }
 
-  fn get_metadata(&mut self, specifier: &ModuleSpecifier) -> Option<Metadata> {
+  fn get_metadata(
+    &mut self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<Arc<Metadata>> {
     if let Some(metadata) = self.metadata.get(specifier).cloned() {
       if metadata.version == self.calculate_script_version(specifier)? {
         return Some(metadata);
@@ -366,6 +383,7 @@ impl Inner {
     if maybe_types.is_some() {
       metadata.maybe_types = maybe_types;
     }
+    let metadata = Arc::new(metadata);
     self.metadata.insert(specifier.clone(), metadata.clone());
     Some(metadata)
   }
@@ -377,7 +395,7 @@ impl Inner {
     let specifier =
       resolve_specifier(specifier, &mut self.redirects, &self.http_cache)?;
     let metadata = self.get_metadata(&specifier)?;
-    metadata.maybe_navigation_tree
+    metadata.maybe_navigation_tree.clone()
   }
 
   fn get_path(&mut self, specifier: &ModuleSpecifier) -> Option<PathBuf> {
@@ -403,14 +421,14 @@ impl Inner {
     let specifier =
       resolve_specifier(specifier, &mut self.redirects, &self.http_cache)?;
     let metadata = self.get_metadata(&specifier)?;
-    Some(metadata.version)
+    Some(metadata.version.clone())
   }
 
   fn get_source(&mut self, specifier: &ModuleSpecifier) -> Option<String> {
     let specifier =
       resolve_specifier(specifier, &mut self.redirects, &self.http_cache)?;
     let metadata = self.get_metadata(&specifier)?;
-    Some(metadata.source)
+    Some(metadata.source.clone())
   }
 
   fn resolution_result(
@@ -434,9 +452,12 @@ impl Inner {
   ) -> Option<(ModuleSpecifier, MediaType)> {
     let referrer =
       resolve_specifier(referrer, &mut self.redirects, &self.http_cache)?;
-    let metadata = self.get_metadata(&referrer)?;
-    let dependencies = &metadata.dependencies?;
-    let dependency = dependencies.get(specifier)?;
+    // the dependency is copied out of the metadata, so that setting the types
+    // of the dependency below doesn't have to copy the whole metadata
+    let dependency = {
+      let metadata = self.get_metadata(&referrer)?;
+      metadata.dependencies.as_ref()?.get(specifier)?.clone()
+    };
     if let Some(type_dependency) = &dependency.maybe_type {
       if let analysis::ResolvedDependency::Resolved(resolved_specifier) =
         type_dependency
@@ -491,7 +512,7 @@ impl Inner {
     dependency: &analysis::ResolvedDependency,
   ) {
     if let Some(metadata) = self.metadata.get_mut(referrer) {
-      if let Some(dependencies) = &mut metadata.dependencies {
+      if let Some(dependencies) = &mut Arc::make_mut(metadata).dependencies {
         if let Some(dep) = dependencies.get_mut(specifier) {
           dep.maybe_type = Some(dependency.clone());
         }
@@ -504,11 +525,11 @@ impl Inner {
     specifier: &ModuleSpecifier,
     navigation_tree: NavigationTree,
   ) -> Result<(), AnyError> {
-    let mut metadata = self
+    let metadata = self
       .metadata
       .get_mut(specifier)
       .ok_or_else(|| anyhow!("Specifier not found {}"))?;
-    metadata.maybe_navigation_tree = Some(navigation_tree);
+    Arc::make_mut(metadata).maybe_navigation_tree = Some(navigation_tree);
     Ok(())
   }
 }
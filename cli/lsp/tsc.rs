@@ -1702,13 +1702,14 @@ pub struct SignatureHelpItems {
 
 impl SignatureHelpItems {
   pub fn into_signature_help(self) -> lsp::SignatureHelp {
+    let argument_index = self.argument_index;
     lsp::SignatureHelp {
       signatures: self
         .items
         .into_iter()
-        .map(|item| item.into_signature_information())
+        .map(|item| item.into_signature_information(argument_index))
         .collect(),
-      active_parameter: Some(self.argument_index),
+      active_parameter: Some(argument_index),
       active_signature: Some(self.selected_item_index),
     }
   }
@@ -1727,7 +1728,12 @@ pub struct SignatureHelpItem {
 }
 
 impl SignatureHelpItem {
-  pub fn into_signature_information(self) -> lsp::SignatureInformation {
+  /// Convert the item into signature information, where the argument index is
+  /// the index of the argument the cursor is currently located at.
+  pub fn into_signature_information(
+    self,
+    argument_index: u32,
+  ) -> lsp::SignatureInformation {
     let prefix_text = display_parts_to_string(&self.prefix_display_parts);
     let params_text = self
       .parameters
@@ -1736,19 +1742,47 @@ impl SignatureHelpItem {
       .collect::<Vec<String>>()
       .join(", ");
     let suffix_text = display_parts_to_string(&self.suffix_display_parts);
+    // `@param` tags are surfaced as part of the documentation of each
+    // parameter, so only the other tags are displayed with the signature.
+    let mut documentation = display_parts_to_string(&self.documentation);
+    let tags_preview = self
+      .tags
+      .iter()
+      .filter(|tag| tag.name != "param")
+      .map(get_tag_documentation)
+      .collect::<Vec<String>>()
+      .join("  \n\n");
+    if !tags_preview.is_empty() {
+      documentation.push_str(&format!("\n\n{}", tags_preview));
+    }
+    // when typing past the last parameter of a variadic signature, the rest
+    // parameter remains the active one.
+    let parameters_len = self.parameters.len() as u32;
+    let active_parameter = if self.is_variadic
+      && parameters_len > 0
+      && argument_index >= parameters_len
+    {
+      parameters_len - 1
+    } else {
+      argument_index
+    };
+    let tags = self.tags;
     lsp::SignatureInformation {
       label: format!("{}{}{}", prefix_text, params_text, suffix_text),
-      documentation: Some(lsp::Documentation::String(display_parts_to_string(
-        &self.documentation,
-      ))),
+      documentation: Some(lsp::Documentation::MarkupContent(
+        lsp::MarkupContent {
+          kind: lsp::MarkupKind::Markdown,
+          value: documentation,
+        },
+      )),
       parameters: Some(
         self
           .parameters
           .into_iter()
-          .map(|param| param.into_parameter_information())
+          .map(|param| param.into_parameter_information(&tags))
           .collect(),
       ),
-      active_parameter: None,
+      active_parameter: Some(active_parameter),
     }
   }
 }
@@ -1763,14 +1797,41 @@ pub struct SignatureHelpParameter {
 }
 
 impl SignatureHelpParameter {
-  pub fn into_parameter_information(self) -> lsp::ParameterInformation {
+  /// Convert the parameter into parameter information.  When tsc does not
+  /// provide any documentation for the parameter, which can occur with some
+  /// declaration files, the `@param` tags of the signature are searched for
+  /// the documentation of the parameter.
+  pub fn into_parameter_information(
+    self,
+    tags: &[JsDocTagInfo],
+  ) -> lsp::ParameterInformation {
+    let mut documentation = display_parts_to_string(&self.documentation);
+    if documentation.is_empty() {
+      let name = self.name.as_str();
+      if let Some(text) = tags
+        .iter()
+        .filter(|tag| tag.name == "param")
+        .filter_map(|tag| tag.text.as_ref())
+        .map(|parts| display_parts_to_string(parts).trim_start().to_string())
+        .find(|text| text.split_whitespace().next() == Some(name))
+      {
+        documentation = text[name.len()..]
+          .trim_start()
+          .trim_start_matches('-')
+          .trim_start()
+          .to_string();
+      }
+    }
     lsp::ParameterInformation {
       label: lsp::ParameterLabel::Simple(display_parts_to_string(
         &self.display_parts,
       )),
-      documentation: Some(lsp::Documentation::String(display_parts_to_string(
-        &self.documentation,
-      ))),
+      documentation: Some(lsp::Documentation::MarkupContent(
+        lsp::MarkupContent {
+          kind: lsp::MarkupKind::Markdown,
+          value: replace_links(&documentation),
+        },
+      )),
     }
   }
 }
@@ -2040,19 +2101,19 @@ fn op_get_text(
     .performance
     .mark("op_get_text", Some(&args));
   let specifier = state.normalize_specifier(args.specifier)?;
-  let content =
+  let text =
     if let Some(Some(content)) = state.state_snapshot.assets.get(&specifier) {
-      content.text.clone()
+      text::slice(&content.text, args.start..args.end).to_string()
     } else {
       cache_snapshot(state, &specifier, args.version.clone())?;
-      state
+      let content = state
         .snapshots
         .get(&(specifier, args.version.into()))
-        .unwrap()
-        .clone()
+        .unwrap();
+      text::slice(content, args.start..args.end).to_string()
     };
   state.state_snapshot.performance.measure(mark);
-  Ok(text::slice(&content, args.start..args.end).to_string())
+  Ok(text)
 }
 
 fn op_load(
@@ -2182,6 +2243,13 @@ fn op_script_names(
   )
 }
 
+fn op_project_version(
+  state: &mut State,
+  _args: Value,
+) -> Result<String, AnyError> {
+  Ok(state.state_snapshot.project_version.to_string())
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ScriptVersionArgs {
@@ -2242,6 +2310,7 @@ fn load() -> Result<JsRuntime, AnyError> {
   runtime.register_op("op_get_text", op(op_get_text));
   runtime.register_op("op_load", op(op_load));
   runtime.register_op("op_resolve", op(op_resolve));
+  runtime.register_op("op_project_version", op(op_project_version));
   runtime.register_op("op_respond", op(op_respond));
   runtime.register_op("op_script_names", op(op_script_names));
   runtime.register_op("op_script_version", op(op_script_version));
@@ -2454,6 +2523,9 @@ pub enum RequestMethod {
   ProvideCallHierarchyIncomingCalls((ModuleSpecifier, u32)),
   /// Resolve outgoing call hierarchy items for a specific position.
   ProvideCallHierarchyOutgoingCalls((ModuleSpecifier, u32)),
+  /// Discard the current program, so that module resolution is performed
+  /// again on the next request.
+  Restart,
 }
 
 impl RequestMethod {
@@ -2628,6 +2700,10 @@ impl RequestMethod {
           "position": position
         })
       }
+      RequestMethod::Restart => json!({
+        "id": id,
+        "method": "restart",
+      }),
     }
   }
 }
@@ -3112,7 +3188,7 @@ mod tests {
 
   #[test]
   fn test_modify_sources() {
-    let (mut runtime, state_snapshot, location) = setup(
+    let (mut runtime, mut state_snapshot, location) = setup(
       true,
       json!({
         "target": "esnext",
@@ -3179,6 +3255,8 @@ mod tests {
         b"export const b = \"b\";\n\nexport const a = \"b\";\n",
       )
       .unwrap();
+    // the program is reused until the project version changes
+    state_snapshot.project_version += 1;
     let specifier = resolve_url("file:///a.ts").unwrap();
     let result = request(
       &mut runtime,
@@ -4,6 +4,7 @@ mod ast;
 mod auth_tokens;
 mod checksum;
 mod colors;
+mod compat;
 mod config_file;
 mod deno_dir;
 mod diagnostics;
@@ -18,6 +19,7 @@ mod fmt_errors;
 mod fs_util;
 mod http_cache;
 mod http_util;
+mod import_assertions;
 mod import_map;
 mod info;
 mod lockfile;
@@ -25,6 +27,7 @@ mod logger;
 mod lsp;
 mod media_type;
 mod module_graph;
+mod module_hooks;
 mod module_loader;
 mod ops;
 mod program_state;
@@ -37,12 +40,15 @@ mod tools;
 mod tsc;
 mod unix_util;
 mod version;
+mod wasm;
 
 use crate::file_fetcher::File;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::DenoSubcommand;
 use crate::flags::Flags;
+use crate::flags::TypeCheckMode;
 use crate::fmt_errors::PrettyJsError;
+use crate::http_cache::HttpCache;
 use crate::media_type::MediaType;
 use crate::module_graph::GraphBuilder;
 use crate::module_graph::Module;
@@ -53,6 +59,7 @@ use crate::specifier_handler::FetchHandler;
 use crate::tools::installer::infer_name_from_url;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::error::Context;
 use deno_core::futures::future::FutureExt;
 use deno_core::futures::Future;
 use deno_core::located_script_name;
@@ -61,6 +68,7 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::v8_set_flags;
 use deno_core::ModuleSpecifier;
+use deno_runtime::ops::os::ExitCode;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::web_worker::WebWorker;
@@ -74,13 +82,35 @@ use std::env;
 use std::io::Read;
 use std::io::Write;
 use std::iter::once;
+use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 use tools::test_runner;
 
+lazy_static::lazy_static! {
+  /// Shared by the main workers of the process, so that it exits with the
+  /// `Deno.exitCode` which was set last.
+  static ref EXIT_CODE: ExitCode = ExitCode::default();
+}
+
+/// The directory `localStorage` and `caches` of the `--location` origin are
+/// stored in.
+fn get_origin_storage_dir(program_state: &ProgramState) -> Option<PathBuf> {
+  program_state.flags.location.as_ref().map(|loc| {
+    program_state
+      .dir
+      .root
+      .clone()
+      // TODO(@crowlKats): change to origin_data for 2.0
+      .join("location_data")
+      .join(checksum::gen(&[loc.to_string().as_bytes()]))
+  })
+}
+
 fn create_web_worker_callback(
   program_state: Arc<ProgramState>,
 ) -> Arc<CreateWebWorkerCb> {
@@ -109,6 +139,7 @@ fn create_web_worker_callback(
         .log_level
         .map_or(false, |l| l == log::Level::Debug),
       unstable: program_state.flags.unstable,
+      op_latency_metrics: program_state.flags.op_latency_metrics,
       ca_data: program_state.ca_data.clone(),
       user_agent: version::get_user_agent(),
       seed: program_state.flags.seed,
@@ -121,8 +152,13 @@ fn create_web_worker_callback(
       ts_version: version::TYPESCRIPT.to_string(),
       no_color: !colors::use_color(),
       get_error_class_fn: Some(&crate::errors::get_error_class_name),
+      origin_storage_dir: get_origin_storage_dir(&program_state),
       blob_url_store: program_state.blob_url_store.clone(),
       broadcast_channel: program_state.broadcast_channel.clone(),
+      shared_array_buffer_store: program_state
+        .shared_array_buffer_store
+        .clone(),
+      resource_limits: args.resource_limits,
     };
 
     let mut worker = WebWorker::from_options(
@@ -185,6 +221,7 @@ pub fn create_main_worker(
       .log_level
       .map_or(false, |l| l == log::Level::Debug),
     unstable: program_state.flags.unstable,
+    op_latency_metrics: program_state.flags.op_latency_metrics,
     ca_data: program_state.ca_data.clone(),
     user_agent: version::get_user_agent(),
     seed: program_state.flags.seed,
@@ -198,17 +235,11 @@ pub fn create_main_worker(
     no_color: !colors::use_color(),
     get_error_class_fn: Some(&crate::errors::get_error_class_name),
     location: program_state.flags.location.clone(),
-    origin_storage_dir: program_state.flags.location.clone().map(|loc| {
-      program_state
-        .dir
-        .root
-        .clone()
-        // TODO(@crowlKats): change to origin_data for 2.0
-        .join("location_data")
-        .join(checksum::gen(&[loc.to_string().as_bytes()]))
-    }),
+    origin_storage_dir: get_origin_storage_dir(&program_state),
     blob_url_store: program_state.blob_url_store.clone(),
     broadcast_channel: program_state.broadcast_channel.clone(),
+    shared_array_buffer_store: program_state.shared_array_buffer_store.clone(),
+    exit_code: EXIT_CODE.clone(),
   };
 
   let mut worker = MainWorker::from_options(main_module, permissions, &options);
@@ -266,6 +297,23 @@ fn print_cache_info(
   json: bool,
   location: Option<deno_core::url::Url>,
 ) -> Result<(), AnyError> {
+  // the compiler options in effect are shown when a config file is provided
+  let maybe_ts_config = if let Some(config_file) = &state.maybe_config_file {
+    let lib = if state.flags.unstable {
+      module_graph::TypeLib::UnstableDenoWindow
+    } else {
+      module_graph::TypeLib::DenoWindow
+    };
+    let mut ts_config = module_graph::get_check_config(&lib, true);
+    if let Some(ignored_options) =
+      ts_config.merge_tsconfig_from_config_file(Some(config_file))?
+    {
+      eprintln!("{}", ignored_options);
+    }
+    Some(ts_config)
+  } else {
+    None
+  };
   let deno_dir = &state.dir.root;
   let modules_cache = &state.file_fetcher.get_http_cache_location();
   let typescript_cache = &state.dir.gen_cache.location;
@@ -291,6 +339,9 @@ fn print_cache_info(
       output["localStorage"] =
         serde_json::to_value(origin_dir.join("local_storage"))?;
     }
+    if let Some(ts_config) = &maybe_ts_config {
+      output["compilerOptions"] = serde_json::to_value(ts_config)?;
+    }
 
     write_json_to_stdout(&output)
   } else {
@@ -318,6 +369,13 @@ fn print_cache_info(
         origin_dir.join("local_storage"),
       );
     }
+    if let Some(ts_config) = &maybe_ts_config {
+      println!(
+        "{} {}",
+        colors::bold("Compiler options:"),
+        serde_json::to_string_pretty(ts_config)?
+      );
+    }
     Ok(())
   }
 }
@@ -332,6 +390,7 @@ pub fn get_types(unstable: bool) -> String {
     crate::tsc::DENO_WEBGPU_LIB,
     crate::tsc::DENO_WEBSOCKET_LIB,
     crate::tsc::DENO_WEBSTORAGE_LIB,
+    crate::tsc::DENO_CACHE_LIB,
     crate::tsc::DENO_CRYPTO_LIB,
     crate::tsc::DENO_BROADCAST_CHANNEL_LIB,
     crate::tsc::SHARED_GLOBALS_LIB,
@@ -410,6 +469,8 @@ async fn info_command(
   flags: Flags,
   maybe_specifier: Option<String>,
   json: bool,
+  dot: bool,
+  mermaid: bool,
 ) -> Result<(), AnyError> {
   let location = flags.location.clone();
   let program_state = ProgramState::build(flags).await?;
@@ -436,6 +497,12 @@ async fn info_command(
 
     if json {
       write_json_to_stdout(&json!(info))
+    } else if dot {
+      write_to_stdout_ignore_sigpipe(info.to_dot().as_bytes())
+        .map_err(|err| err.into())
+    } else if mermaid {
+      write_to_stdout_ignore_sigpipe(info.to_mermaid().as_bytes())
+        .map_err(|err| err.into())
     } else {
       write_to_stdout_ignore_sigpipe(info.to_string().as_bytes())
         .map_err(|err| err.into())
@@ -446,6 +513,31 @@ async fn info_command(
   }
 }
 
+async fn types_command(
+  flags: Flags,
+  specifiers: Vec<String>,
+) -> Result<(), AnyError> {
+  let program_state = ProgramState::build(flags).await?;
+  let handler = Arc::new(Mutex::new(specifier_handler::FetchHandler::new(
+    &program_state,
+    Permissions::allow_all(),
+    Permissions::allow_all(),
+  )?));
+  let mut builder = module_graph::GraphBuilder::new(
+    handler,
+    program_state.maybe_import_map.clone(),
+    program_state.lockfile.clone(),
+  );
+  for specifier in specifiers {
+    let specifier = resolve_url_or_path(&specifier)?;
+    builder.add(&specifier, false).await?;
+  }
+  let graph = builder.get_graph();
+  let declarations = graph.to_declarations()?;
+  write_to_stdout_ignore_sigpipe(declarations.as_bytes())
+    .map_err(|err| err.into())
+}
+
 async fn install_command(
   flags: Flags,
   module_url: String,
@@ -489,6 +581,7 @@ async fn lint_command(
 async fn cache_command(
   flags: Flags,
   files: Vec<String>,
+  maybe_gc_days: Option<u64>,
 ) -> Result<(), AnyError> {
   let lib = if flags.unstable {
     module_graph::TypeLib::UnstableDenoWindow
@@ -497,6 +590,19 @@ async fn cache_command(
   };
   let program_state = ProgramState::build(flags).await?;
 
+  if let Some(days) = maybe_gc_days {
+    let http_cache = HttpCache::new(&program_state.dir.root.join("deps"));
+    let stats =
+      http_cache.gc(Duration::from_secs(days.saturating_mul(24 * 60 * 60)))?;
+    info!(
+      "{} {} unused cache entries and {} unreferenced contents ({})",
+      colors::green("Removed"),
+      stats.removed_entries,
+      stats.removed_contents,
+      info::human_size(stats.freed_bytes as f64)
+    );
+  }
+
   for file in files {
     let specifier = resolve_url_or_path(&file)?;
     program_state
@@ -547,6 +653,7 @@ async fn eval_command(
       MediaType::Jsx
     },
     source: String::from_utf8(source_code)?,
+    maybe_raw_source: None,
     specifier: main_module.clone(),
   };
 
@@ -555,15 +662,9 @@ async fn eval_command(
   program_state.file_fetcher.insert_cached(file);
   debug!("main_module {}", &main_module);
   worker.execute_module(&main_module).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('load'))",
-  )?;
-  worker.run_event_loop(false).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'))",
-  )?;
+  worker.dispatch_load_event(&located_script_name!())?;
+  worker.run_event_loop_until_unload(false).await?;
+  worker.dispatch_unload_event(&located_script_name!())?;
   Ok(())
 }
 
@@ -590,7 +691,7 @@ async fn create_module_graph_and_maybe_check(
     .await?;
   let module_graph = builder.get_graph();
 
-  if !program_state.flags.no_check {
+  if program_state.flags.typecheck_mode != TypeCheckMode::None {
     // TODO(@kitsonk) support bundling for workers
     let lib = if program_state.flags.unstable {
       module_graph::TypeLib::UnstableDenoWindow
@@ -602,6 +703,7 @@ async fn create_module_graph_and_maybe_check(
         debug,
         emit: false,
         lib,
+        local_only: program_state.flags.typecheck_mode == TypeCheckMode::Local,
         maybe_config_file: program_state.maybe_config_file.clone(),
         reload: program_state.flags.reload,
         ..Default::default()
@@ -631,7 +733,7 @@ fn bundle_module_graph(
       maybe_config_file: program_state.maybe_config_file.clone(),
     })?;
   match maybe_ignored_options {
-    Some(ignored_options) if flags.no_check => {
+    Some(ignored_options) if flags.typecheck_mode == TypeCheckMode::None => {
       eprintln!("{}", ignored_options);
     }
     _ => {}
@@ -763,15 +865,27 @@ async fn format_command(
   Ok(())
 }
 
-async fn run_repl(flags: Flags) -> Result<(), AnyError> {
+async fn run_repl(
+  flags: Flags,
+  options: tools::repl::ReplOptions,
+) -> Result<(), AnyError> {
   let main_module = resolve_url_or_path("./$deno$repl.ts").unwrap();
   let permissions = Permissions::from_options(&flags.clone().into());
   let program_state = ProgramState::build(flags).await?;
-  let mut worker =
-    create_main_worker(&program_state, main_module.clone(), permissions, false);
-  worker.run_event_loop(false).await?;
+  // The REPL creates a new worker whenever the session is reset.
+  let create_worker = {
+    let program_state = program_state.clone();
+    move || {
+      create_main_worker(
+        &program_state,
+        main_module.clone(),
+        permissions.clone(),
+        false,
+      )
+    }
+  };
 
-  tools::repl::run(&program_state, worker).await
+  tools::repl::run(program_state, create_worker, options).await
 }
 
 async fn run_from_stdin(flags: Flags) -> Result<(), AnyError> {
@@ -793,6 +907,7 @@ async fn run_from_stdin(flags: Flags) -> Result<(), AnyError> {
     maybe_types: None,
     media_type: MediaType::TypeScript,
     source: String::from_utf8(source)?,
+    maybe_raw_source: None,
     specifier: main_module.clone(),
   };
   // Save our fake file into file fetcher cache
@@ -801,15 +916,9 @@ async fn run_from_stdin(flags: Flags) -> Result<(), AnyError> {
 
   debug!("main_module {}", main_module);
   worker.execute_module(&main_module).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('load'))",
-  )?;
-  worker.run_event_loop(false).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'))",
-  )?;
+  worker.dispatch_load_event(&located_script_name!())?;
+  worker.run_event_loop_until_unload(false).await?;
+  worker.dispatch_unload_event(&located_script_name!())?;
   Ok(())
 }
 
@@ -879,15 +988,9 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<(), AnyError> {
         );
         debug!("main_module {}", main_module);
         worker.execute_module(&main_module).await?;
-        worker.execute_script(
-          &located_script_name!(),
-          "window.dispatchEvent(new Event('load'))",
-        )?;
-        worker.run_event_loop(false).await?;
-        worker.execute_script(
-          &located_script_name!(),
-          "window.dispatchEvent(new Event('unload'))",
-        )?;
+        worker.dispatch_load_event(&located_script_name!())?;
+        worker.run_event_loop_until_unload(false).await?;
+        worker.dispatch_unload_event(&located_script_name!())?;
         Ok(())
       }
     };
@@ -926,25 +1029,70 @@ async fn run_command(flags: Flags, script: String) -> Result<(), AnyError> {
       None
     };
 
+  if flags.heap_snapshot_on_oom {
+    tools::profiler::write_heap_snapshot_on_oom(
+      &mut worker.js_runtime,
+      PathBuf::from("."),
+    );
+  }
+
+  let mut maybe_cpu_profiler = if let Some(ref prof_dir) = flags.prof_dir {
+    let session = worker.create_inspector_session().await;
+    let mut cpu_profiler =
+      tools::profiler::CpuProfiler::new(prof_dir.clone(), session);
+    worker
+      .with_event_loop(cpu_profiler.start_profiling().boxed_local())
+      .await?;
+    Some(cpu_profiler)
+  } else {
+    None
+  };
+
+  // the coverage and the profile have to be written when the program calls
+  // `Deno.exit()` too
+  let defer_exit =
+    maybe_coverage_collector.is_some() || maybe_cpu_profiler.is_some();
+  if defer_exit {
+    worker.defer_exit();
+  }
+
   debug!("main_module {}", main_module);
-  worker.execute_module(&main_module).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('load'))",
-  )?;
-  worker
-    .run_event_loop(maybe_coverage_collector.is_none())
-    .await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'))",
-  )?;
+  let result: Result<(), AnyError> = async {
+    if flags.compat {
+      // set up the node globals before any user code is executed
+      worker
+        .execute_module(&compat::get_node_globals_url())
+        .await?;
+    }
+    if flags.compat && compat::is_cjs_module(&main_module) {
+      compat::load_cjs_module(&mut worker.js_runtime, &main_module)?;
+    } else {
+      worker.execute_module(&main_module).await?;
+    }
+    worker.dispatch_load_event(&located_script_name!())?;
+    worker.run_event_loop_until_unload(!defer_exit).await?;
+    worker.dispatch_unload_event(&located_script_name!())?;
+    Ok(())
+  }
+  .await;
+  let maybe_exit_code = worker.take_deferred_exit();
+  if maybe_exit_code.is_none() {
+    result?;
+  }
 
   if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
     worker
       .with_event_loop(coverage_collector.stop_collecting().boxed_local())
       .await?;
   }
+  if let Some(cpu_profiler) = maybe_cpu_profiler.as_mut() {
+    worker
+      .with_event_loop(cpu_profiler.stop_profiling().boxed_local())
+      .await?;
+  }
+  if let Some(exit_code) = maybe_exit_code {
+    std::process::exit(exit_code);
+  }
   Ok(())
 }
 
@@ -983,6 +1131,7 @@ async fn test_command(
   allow_none: bool,
   filter: Option<String>,
   concurrent_jobs: usize,
+  trace_ops: bool,
 ) -> Result<(), AnyError> {
   if let Some(ref coverage_dir) = flags.coverage_dir {
     std::fs::create_dir_all(&coverage_dir)?;
@@ -1171,6 +1320,7 @@ async fn test_command(
           true,
           filter.clone(),
           concurrent_jobs,
+          trace_ops,
         )
         .map(|res| res.map(|_| ()))
       },
@@ -1206,6 +1356,7 @@ async fn test_command(
       allow_none,
       filter,
       concurrent_jobs,
+      trace_ops,
     )
     .await?;
 
@@ -1241,6 +1392,16 @@ fn init_v8_flags(v8_flags: &[String]) {
   }
 }
 
+fn init_icu_data(icu_data_path: &Path) -> Result<(), AnyError> {
+  let data = std::fs::read(icu_data_path).with_context(|| {
+    format!(
+      "Failed to read ICU data from \"{}\"",
+      icu_data_path.display()
+    )
+  })?;
+  deno_core::set_icu_data(&data)
+}
+
 fn get_subcommand(
   flags: Flags,
 ) -> Pin<Box<dyn Future<Output = Result<(), AnyError>>>> {
@@ -1258,8 +1419,8 @@ fn get_subcommand(
     DenoSubcommand::Eval { print, code, ext } => {
       eval_command(flags, code, ext, print).boxed_local()
     }
-    DenoSubcommand::Cache { files } => {
-      cache_command(flags, files).boxed_local()
+    DenoSubcommand::Cache { files, gc } => {
+      cache_command(flags, files, gc).boxed_local()
     }
     DenoSubcommand::Compile {
       source_file,
@@ -1283,9 +1444,12 @@ fn get_subcommand(
       ignore,
       ext,
     } => format_command(flags, files, ignore, check, ext).boxed_local(),
-    DenoSubcommand::Info { file, json } => {
-      info_command(flags, file, json).boxed_local()
-    }
+    DenoSubcommand::Info {
+      file,
+      json,
+      dot,
+      mermaid,
+    } => info_command(flags, file, json, dot, mermaid).boxed_local(),
     DenoSubcommand::Install {
       module_url,
       args,
@@ -1302,7 +1466,28 @@ fn get_subcommand(
       ignore,
       json,
     } => lint_command(flags, files, rules, ignore, json).boxed_local(),
-    DenoSubcommand::Repl => run_repl(flags).boxed_local(),
+    DenoSubcommand::Repl {
+      eval,
+      init,
+      check,
+      jsx,
+      record,
+      connect,
+      quiet,
+      exit_code_from_last_error,
+    } => {
+      let options = tools::repl::ReplOptions {
+        maybe_eval: eval,
+        maybe_init_file: init,
+        type_check: check,
+        jsx,
+        maybe_record_file: record,
+        maybe_connect: connect,
+        quiet,
+        exit_code_from_last_error,
+      };
+      run_repl(flags, options).boxed_local()
+    }
     DenoSubcommand::Run { script } => run_command(flags, script).boxed_local(),
     DenoSubcommand::Test {
       no_run,
@@ -1313,6 +1498,7 @@ fn get_subcommand(
       allow_none,
       filter,
       concurrent_jobs,
+      trace_ops,
     } => test_command(
       flags,
       include,
@@ -1323,6 +1509,7 @@ fn get_subcommand(
       allow_none,
       filter,
       concurrent_jobs,
+      trace_ops,
     )
     .boxed_local(),
     DenoSubcommand::Completions { buf } => {
@@ -1332,7 +1519,10 @@ fn get_subcommand(
       }
       std::process::exit(0);
     }
-    DenoSubcommand::Types => {
+    DenoSubcommand::Types { specifiers } if !specifiers.is_empty() => {
+      types_command(flags, specifiers).boxed_local()
+    }
+    DenoSubcommand::Types { .. } => {
       let types = get_types(flags.unstable);
       if let Err(e) = write_to_stdout_ignore_sigpipe(types.as_bytes()) {
         eprintln!("{}", e);
@@ -1358,7 +1548,12 @@ fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
   match result {
     Ok(value) => value,
     Err(error) => {
-      eprintln!("{}: {:?}", colors::red_bold("error"), error);
+      if logger::is_json() {
+        let message = format!("{:?}", error);
+        eprintln!("{}", logger::json_line(log::Level::Error, "deno", &message));
+      } else {
+        eprintln!("{}: {:?}", colors::red_bold("error"), error);
+      }
       std::process::exit(1);
     }
   }
@@ -1397,8 +1592,12 @@ pub fn main() {
   if !flags.v8_flags.is_empty() {
     init_v8_flags(&*flags.v8_flags);
   }
+  if let Some(icu_data_path) = &flags.icu_data_path {
+    unwrap_or_exit(init_icu_data(icu_data_path));
+  }
 
-  logger::init(flags.log_level);
+  logger::init(flags.log_level, flags.log_format);
 
   unwrap_or_exit(tokio_util::run_basic(get_subcommand(flags)));
+  std::process::exit(EXIT_CODE.get());
 }
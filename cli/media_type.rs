@@ -170,9 +170,8 @@ impl MediaType {
       MediaType::Dts => ".d.ts",
       MediaType::Tsx => ".tsx",
       MediaType::Json => ".json",
-      // TypeScript doesn't have an "unknown", so we will treat WASM as JS for
-      // mapping purposes, though in reality, it is unlikely to ever be passed
-      // to the compiler.
+      // WebAssembly modules are passed to the compiler as the JavaScript
+      // module which is generated for them.
       MediaType::Wasm => ".js",
       MediaType::TsBuildInfo => ".tsbuildinfo",
       // TypeScript doesn't have an "source map", so we will treat SourceMap as
@@ -190,6 +189,7 @@ impl MediaType {
   pub fn as_ts_script_kind(&self) -> i32 {
     match self {
       MediaType::JavaScript => 1,
+      MediaType::Wasm => 1,
       MediaType::Jsx => 2,
       MediaType::TypeScript => 3,
       MediaType::Dts => 3,
@@ -12,11 +12,16 @@ use crate::config_file::ConfigFile;
 use crate::config_file::IgnoredCompilerOptions;
 use crate::config_file::TsConfig;
 use crate::diagnostics::Diagnostics;
+use crate::file_fetcher::is_not_cached_error;
+use crate::import_assertions;
+use crate::import_assertions::ImportAssertionError;
 use crate::import_map::ImportMap;
 use crate::import_map::ImportMapError;
+use crate::import_map::UnmappedSpecifier;
 use crate::info;
 use crate::lockfile::Lockfile;
 use crate::media_type::MediaType;
+use crate::module_hooks::ModuleHooks;
 use crate::specifier_handler::CachedModule;
 use crate::specifier_handler::Dependency;
 use crate::specifier_handler::DependencyMap;
@@ -45,12 +50,18 @@ use deno_core::url::Url;
 use deno_core::ModuleResolutionError;
 use deno_core::ModuleSource;
 use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::Permissions;
+use indexmap::IndexMap;
 use log::debug;
+use rayon::prelude::*;
 use regex::Regex;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt;
+use std::ops::Range;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::result;
@@ -86,11 +97,20 @@ pub enum GraphError {
   /// A module using the HTTPS protocol is trying to import a module with an
   /// HTTP schema.
   InvalidDowngrade(ModuleSpecifier, Location),
+  /// The import assertions of a dependency are not satisfied by the module it
+  /// resolved to.
+  InvalidImportAssertion(ModuleSpecifier, ImportAssertionError, Location),
   /// A remote module is trying to import a local module.
   InvalidLocalImport(ModuleSpecifier, Location),
+  /// The resolution of a module is invalid, as its redirect or types do not
+  /// match the lockfile.
+  InvalidResolution(ModuleSpecifier, PathBuf),
   /// The source code is invalid, as it does not match the expected hash in the
   /// lockfile.
   InvalidSource(ModuleSpecifier, PathBuf),
+  /// The source code is invalid, as it does not match the integrity specified
+  /// in the import map.
+  InvalidIntegrity(ModuleSpecifier, String),
   /// An unexpected dependency was requested for a module.
   MissingDependency(ModuleSpecifier, String),
   /// An unexpected specifier was requested.
@@ -105,7 +125,10 @@ impl fmt::Display for GraphError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       GraphError::InvalidDowngrade(ref specifier, ref location) => write!(f, "Modules imported via https are not allowed to import http modules.\n  Importing: {}\n    at {}", specifier, location),
+      GraphError::InvalidImportAssertion(ref specifier, ref err, ref location) => write!(f, "{}\n  Importing: {}\n    at {}", err, specifier, location),
+      GraphError::InvalidIntegrity(ref specifier, ref integrity) => write!(f, "The source code is invalid, as it does not match the integrity in the import map.\n  Specifier: {}\n  Integrity: {}", specifier, integrity),
       GraphError::InvalidLocalImport(ref specifier, ref location) => write!(f, "Remote modules are not allowed to import local modules.  Consider using a dynamic import instead.\n  Importing: {}\n    at {}", specifier, location),
+      GraphError::InvalidResolution(ref specifier, ref lockfile) => write!(f, "The module resolution is invalid, as its redirect or types do not match the lock file.\n  Specifier: {}\n  Lock file: {}", specifier, lockfile.to_str().unwrap()),
       GraphError::InvalidSource(ref specifier, ref lockfile) => write!(f, "The source code is invalid, as it does not match the expected hash in the lock file.\n  Specifier: {}\n  Lock file: {}", specifier, lockfile.to_str().unwrap()),
       GraphError::MissingDependency(ref referrer, specifier) => write!(
         f,
@@ -125,6 +148,47 @@ impl fmt::Display for GraphError {
 
 impl Error for GraphError {}
 
+/// Return the configuration a module graph is type checked with, before the
+/// compiler options of a configuration file are merged.
+pub fn get_check_config(lib: &TypeLib, emit: bool) -> TsConfig {
+  let mut config = TsConfig::new(json!({
+    "allowJs": true,
+    // TODO(@kitsonk) is this really needed?
+    "esModuleInterop": true,
+    // Enabled by default to align to transpile/swc defaults
+    "experimentalDecorators": true,
+    "incremental": true,
+    "jsx": "react",
+    "isolatedModules": true,
+    "lib": lib,
+    "module": "esnext",
+    "strict": true,
+    "target": "esnext",
+    "tsBuildInfoFile": "deno:///.tsbuildinfo",
+    "useDefineForClassFields": true,
+  }));
+  if emit {
+    config.merge(&json!({
+      // TODO(@kitsonk) consider enabling this by default
+      //   see: https://github.com/denoland/deno/issues/7732
+      "emitDecoratorMetadata": false,
+      "importsNotUsedAsValues": "remove",
+      "inlineSourceMap": true,
+      "outDir": "deno://",
+      "removeComments": true,
+    }));
+  } else {
+    config.merge(&json!({
+      "noEmit": true,
+    }));
+  }
+  config
+}
+
+/// The bundles of a module-splitting bundle, which maps each module which is
+/// the entry of a bundle to the file name of the bundle.
+type Chunks = RefCell<IndexMap<ModuleSpecifier, String>>;
+
 /// A structure for handling bundle loading, which is implemented here, to
 /// avoid a circular dependency with `ast`.
 struct BundleLoader<'a> {
@@ -132,6 +196,9 @@ struct BundleLoader<'a> {
   emit_options: &'a ast::EmitOptions,
   globals: &'a swc_common::Globals,
   graph: &'a Graph,
+  /// When bundling a module-splitting bundle, the chunks which modules which
+  /// are dynamically imported are split into.
+  maybe_chunks: Option<&'a Chunks>,
 }
 
 impl<'a> BundleLoader<'a> {
@@ -140,14 +207,34 @@ impl<'a> BundleLoader<'a> {
     emit_options: &'a ast::EmitOptions,
     globals: &'a swc_common::Globals,
     cm: Rc<swc_common::SourceMap>,
+    maybe_chunks: Option<&'a Chunks>,
   ) -> Self {
     BundleLoader {
       cm,
       emit_options,
       globals,
       graph,
+      maybe_chunks,
     }
   }
+
+  /// Return the relative specifier of the bundle of a module which is
+  /// dynamically imported by the referrer, adding a bundle for the module if it
+  /// is not part of the split bundles yet.
+  fn get_chunk(
+    &self,
+    specifier: &str,
+    referrer: &ModuleSpecifier,
+  ) -> Option<String> {
+    let chunks = self.maybe_chunks?;
+    let specifier = self.graph.resolve(specifier, referrer, false).ok()?;
+    let mut chunks = chunks.borrow_mut();
+    let chunk_count = chunks.len();
+    let file_name = chunks
+      .entry(specifier)
+      .or_insert_with(|| format!("chunk-{}.js", chunk_count));
+    Some(format!("./{}", file_name))
+  }
 }
 
 impl swc_bundler::Load for BundleLoader<'_> {
@@ -164,7 +251,7 @@ impl swc_bundler::Load for BundleLoader<'_> {
             .graph
             .get_media_type(&specifier)
             .context("Looking up media type during bundling.")?;
-          let (source_file, module) = transpile_module(
+          let (source_file, mut module) = transpile_module(
             filename,
             &src,
             &media_type,
@@ -172,6 +259,11 @@ impl swc_bundler::Load for BundleLoader<'_> {
             self.globals,
             self.cm.clone(),
           )?;
+          if self.maybe_chunks.is_some() {
+            ast::rewrite_dynamic_imports(&mut module, &|import| {
+              self.get_chunk(import, &specifier)
+            });
+          }
           Ok(swc_bundler::ModuleData {
             fm: source_file,
             module,
@@ -204,6 +296,21 @@ fn match_to_span(comment: &Comment, m: &regex::Match) -> Span {
   }
 }
 
+/// Replace the byte ranges of the source with their replacements.  The ranges
+/// must not overlap.
+fn apply_edits(source: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+  edits.sort_by_key(|(range, _)| range.start);
+  let mut result = String::with_capacity(source.len());
+  let mut last = 0;
+  for (range, replacement) in edits {
+    result.push_str(&source[last..range.start]);
+    result.push_str(&replacement);
+    last = range.end;
+  }
+  result.push_str(&source[last..]);
+  result
+}
+
 /// Determine if a comment contains a triple slash reference and optionally
 /// return its kind and value.
 pub fn parse_ts_reference(
@@ -252,11 +359,13 @@ fn get_version(source: &str, version: &str, config: &[u8]) -> String {
 #[derive(Debug, Clone)]
 pub struct Module {
   pub dependencies: DependencyMap,
+  dynamic_import_templates: Vec<ast::DynamicImportTemplate>,
   is_dirty: bool,
   is_parsed: bool,
   maybe_emit: Option<Emit>,
   maybe_emit_path: Option<(PathBuf, Option<PathBuf>)>,
   maybe_import_map: Option<Arc<Mutex<ImportMap>>>,
+  maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
   maybe_types: Option<(String, ModuleSpecifier)>,
   maybe_version: Option<String>,
   media_type: MediaType,
@@ -269,11 +378,13 @@ impl Default for Module {
   fn default() -> Self {
     Module {
       dependencies: HashMap::new(),
+      dynamic_import_templates: Vec::new(),
       is_dirty: false,
       is_parsed: false,
       maybe_emit: None,
       maybe_emit_path: None,
       maybe_import_map: None,
+      maybe_module_hooks: None,
       maybe_types: None,
       maybe_version: None,
       media_type: MediaType::Unknown,
@@ -289,6 +400,7 @@ impl Module {
     cached_module: CachedModule,
     is_root: bool,
     maybe_import_map: Option<Arc<Mutex<ImportMap>>>,
+    maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
   ) -> Self {
     // If this is a local root file, and its media type is unknown, set the
     // media type to JavaScript.  This allows easier ability to create "shell"
@@ -304,6 +416,7 @@ impl Module {
     let mut module = Module {
       specifier: cached_module.specifier,
       maybe_import_map,
+      maybe_module_hooks,
       media_type,
       source: cached_module.source,
       source_path: cached_module.source_path,
@@ -313,7 +426,10 @@ impl Module {
       is_dirty: false,
       ..Self::default()
     };
-    if module.maybe_import_map.is_none() {
+    // the cached dependencies can't be used when the resolution of the
+    // specifiers can differ between runs
+    if module.maybe_import_map.is_none() && module.maybe_module_hooks.is_none()
+    {
       if let Some(dependencies) = cached_module.maybe_dependencies {
         module.dependencies = dependencies;
         module.is_parsed = true;
@@ -383,6 +499,8 @@ impl Module {
 
     // Parse out all the syntactical dependencies for a module
     let dependencies = parsed_module.analyze_dependencies();
+    let dynamic_import_assertions =
+      parsed_module.get_dynamic_import_assertions();
     for desc in dependencies.iter().filter(|desc| {
       desc.kind != swc_ecmascript::dep_graph::DependencyKind::Require
     }) {
@@ -434,6 +552,8 @@ impl Module {
         .entry(desc.specifier.to_string())
         .or_insert_with(|| Dependency::new(location));
       dep.is_dynamic = desc.is_dynamic;
+      dep.maybe_assert_type =
+        ast::get_assert_type(desc, &dynamic_import_assertions);
       if let Some(specifier) = maybe_specifier {
         if desc.kind == swc_ecmascript::dep_graph::DependencyKind::ExportType
           || desc.kind == swc_ecmascript::dep_graph::DependencyKind::ImportType
@@ -449,16 +569,116 @@ impl Module {
         dep.maybe_type = maybe_type;
       }
     }
+
+    self.dynamic_import_templates =
+      parsed_module.get_dynamic_import_templates();
     Ok(parsed_module)
   }
 
+  /// Add the local files which match the dynamic imports of template literals
+  /// of the module as its dynamic dependencies, so that they can be cached
+  /// ahead of time.  The directories of the files are read with the read
+  /// permission of `permissions`.
+  fn add_template_imports(
+    &mut self,
+    permissions: &mut Permissions,
+  ) -> Result<(), AnyError> {
+    for template in std::mem::take(&mut self.dynamic_import_templates) {
+      let imports = self.get_template_imports(
+        &template.prefix,
+        &template.suffix,
+        permissions,
+      )?;
+      for import in imports {
+        let specifier =
+          self.resolve_import(&import, Some(template.location.clone()))?;
+        let dep = self
+          .dependencies
+          .entry(import)
+          .or_insert_with(|| Dependency::new(template.location.clone()));
+        if dep.maybe_code.is_none() {
+          dep.is_dynamic = true;
+          dep.maybe_code = Some(specifier);
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Return the imports of the files which match the prefix and suffix of a
+  /// dynamic import of a template literal.  The directory of the prefix is read
+  /// to find the files, so only relative imports of local modules are
+  /// resolved.
+  fn get_template_imports(
+    &self,
+    prefix: &str,
+    suffix: &str,
+    permissions: &mut Permissions,
+  ) -> Result<Vec<String>, AnyError> {
+    if self.specifier.scheme() != "file"
+      || !(prefix.starts_with("./") || prefix.starts_with("../"))
+    {
+      return Ok(Vec::new());
+    }
+    let (dir, name_prefix) = prefix.split_at(prefix.rfind('/').unwrap() + 1);
+    // the expression can also be a directory, like `./plugins/${name}/mod.ts`
+    let (name_suffix, rest) = match suffix.find('/') {
+      Some(index) => suffix.split_at(index),
+      None => (suffix, ""),
+    };
+    let maybe_dir_path =
+      deno_core::resolve_import(dir, self.specifier.as_str())
+        .ok()
+        .and_then(|url| url.to_file_path().ok());
+    let dir_path = match maybe_dir_path {
+      Some(dir_path) => dir_path,
+      None => return Ok(Vec::new()),
+    };
+    permissions.read.check(&dir_path)?;
+    let entries = match std::fs::read_dir(&dir_path) {
+      Ok(entries) => entries,
+      Err(_) => return Ok(Vec::new()),
+    };
+    let mut imports: Vec<String> = entries
+      .filter_map(|entry| {
+        let name = entry.ok()?.file_name().into_string().ok()?;
+        if name.len() < name_prefix.len() + name_suffix.len()
+          || !name.starts_with(name_prefix)
+          || !name.ends_with(name_suffix)
+        {
+          return None;
+        }
+        let path = if rest.is_empty() {
+          dir_path.join(&name)
+        } else {
+          dir_path.join(&name).join(&rest[1..])
+        };
+        if path.is_file() && path != self.source_path {
+          Some(format!("{}{}{}", dir, name, rest))
+        } else {
+          None
+        }
+      })
+      .collect();
+    imports.sort();
+    Ok(imports)
+  }
+
   fn resolve_import(
     &self,
     specifier: &str,
     maybe_location: Option<Location>,
   ) -> Result<ModuleSpecifier, AnyError> {
-    let maybe_resolve = if let Some(import_map) = self.maybe_import_map.clone()
-    {
+    let maybe_resolve = if let Some(module_hooks) = &self.maybe_module_hooks {
+      module_hooks
+        .resolve(specifier, self.specifier.as_str())
+        .transpose()?
+    } else {
+      None
+    };
+    let maybe_resolve = if maybe_resolve.is_some() {
+      maybe_resolve
+    } else if let Some(import_map) = self.maybe_import_map.clone() {
       let import_map = import_map.lock().unwrap();
       Some(import_map.resolve(specifier, self.specifier.as_str())?)
     } else {
@@ -620,10 +840,16 @@ pub struct CheckOptions {
   pub emit: bool,
   /// The base type libraries that should be used when type checking.
   pub lib: TypeLib,
+  /// If `true` then only the local modules of the graph are type checked,
+  /// while remote modules are only used to provide the types of the modules
+  /// that import them.
+  pub local_only: bool,
   /// An optional config file with user supplied TypeScript configuration
   /// that augments the the default configuration passed to the TypeScript
   /// compiler.
   pub maybe_config_file: Option<ConfigFile>,
+  /// If `true` then the roots that are checked are not logged.
+  pub quiet: bool,
   /// Ignore any previously emits and ensure that all files are emitted from
   /// source.
   pub reload: bool,
@@ -661,6 +887,9 @@ pub struct EmitOptions {
   pub check: bool,
   /// Indicate the form the result of the emit should take.
   pub bundle_type: BundleType,
+  /// If `true`, a bundle of the type `Module` is split into a bundle for the
+  /// root module and a bundle for each module which is dynamically imported.
+  pub splitting: bool,
   /// If `true` then debug logging will be output from the isolate.
   pub debug: bool,
   /// An optional map that contains user supplied TypeScript compiler
@@ -709,6 +938,9 @@ pub struct Graph {
   /// Optional TypeScript build info that will be passed to `tsc` if `tsc` is
   /// invoked.
   maybe_tsbuildinfo: Option<String>,
+  /// The key the build info is cached with, which is derived from the roots of
+  /// the graph and the configuration it is checked with.
+  maybe_tsbuildinfo_key: Option<String>,
   /// The modules that are part of the graph.
   modules: HashMap<ModuleSpecifier, ModuleSlot>,
   /// A map of redirects, where a module specifier is redirected to another
@@ -716,6 +948,8 @@ pub struct Graph {
   /// resolved internally via this, before attempting to access the module via
   /// the handler, to make sure the correct modules is being dealt with.
   redirects: HashMap<ModuleSpecifier, ModuleSpecifier>,
+  /// The chains of the redirects, which are recorded in the lock file.
+  redirect_chains: HashMap<ModuleSpecifier, Vec<ModuleSpecifier>>,
   /// The module specifiers that have been uniquely added to the graph, which
   /// does not include any transient dependencies.
   roots: Vec<ModuleSpecifier>,
@@ -747,11 +981,23 @@ fn to_module_result(
         }
       } else {
         match module.media_type {
-          MediaType::JavaScript | MediaType::Unknown => Ok(ModuleSource {
-            code: module.source.clone(),
+          // JSON modules are loaded as a JavaScript module, which has the
+          // parsed JSON as its default export
+          MediaType::Json => Ok(ModuleSource {
+            code: format!(
+              "export default JSON.parse({});",
+              serde_json::to_string(&module.source).unwrap()
+            ),
             module_url_found: module.specifier.to_string(),
             module_url_specified: specifier.to_string(),
           }),
+          MediaType::JavaScript | MediaType::Wasm | MediaType::Unknown => {
+            Ok(ModuleSource {
+              code: module.source.clone(),
+              module_url_found: module.specifier.to_string(),
+              module_url_specified: specifier.to_string(),
+            })
+          }
           _ => Err(custom_error(
             "NotFound",
             format!("Compiled module not found \"{}\"", specifier),
@@ -779,8 +1025,10 @@ impl Graph {
     Graph {
       handler,
       maybe_tsbuildinfo: None,
+      maybe_tsbuildinfo_key: None,
       modules: HashMap::new(),
       redirects: HashMap::new(),
+      redirect_chains: HashMap::new(),
       roots: Vec::new(),
       roots_dynamic: true,
       maybe_lockfile,
@@ -816,6 +1064,7 @@ impl Graph {
       &root_specifier,
       &ts_config.into(),
       &BundleType::Module,
+      None,
     )?;
     let stats = Stats(vec![
       ("Files".to_string(), self.modules.len() as u32),
@@ -826,39 +1075,12 @@ impl Graph {
   }
 
   /// Type check the module graph, corresponding to the options provided.
-  pub fn check(self, options: CheckOptions) -> Result<ResultInfo, AnyError> {
+  pub fn check(
+    mut self,
+    options: CheckOptions,
+  ) -> Result<ResultInfo, AnyError> {
     self.validate()?;
-    let mut config = TsConfig::new(json!({
-      "allowJs": true,
-      // TODO(@kitsonk) is this really needed?
-      "esModuleInterop": true,
-      // Enabled by default to align to transpile/swc defaults
-      "experimentalDecorators": true,
-      "incremental": true,
-      "jsx": "react",
-      "isolatedModules": true,
-      "lib": options.lib,
-      "module": "esnext",
-      "strict": true,
-      "target": "esnext",
-      "tsBuildInfoFile": "deno:///.tsbuildinfo",
-      "useDefineForClassFields": true,
-    }));
-    if options.emit {
-      config.merge(&json!({
-        // TODO(@kitsonk) consider enabling this by default
-        //   see: https://github.com/denoland/deno/issues/7732
-        "emitDecoratorMetadata": false,
-        "importsNotUsedAsValues": "remove",
-        "inlineSourceMap": true,
-        "outDir": "deno://",
-        "removeComments": true,
-      }));
-    } else {
-      config.merge(&json!({
-        "noEmit": true,
-      }));
-    }
+    let mut config = get_check_config(&options.lib, options.emit);
     let maybe_ignored_options = config
       .merge_tsconfig_from_config_file(options.maybe_config_file.as_ref())?;
 
@@ -867,9 +1089,18 @@ impl Graph {
         .roots
         .iter()
         .all(|u| options.reload_exclusions.contains(u));
+    // The version of an emit also reflects if only the local modules were
+    // checked, so that checking all of the modules doesn't reuse the emit of
+    // a check which skipped the remote modules.
+    let mut version_config = config.as_bytes();
+    if options.local_only {
+      version_config.extend_from_slice(b"local_only");
+    }
     // Short circuit if none of the modules require an emit, or all of the
     // modules that require an emit have a valid emit.
-    if !self.needs_emit(&config) || self.is_emit_valid(&config) && !needs_reload
+    if !self.needs_emit(&config)
+      || self.is_emit_valid(config.get_check_js(), &version_config)
+        && !needs_reload
     {
       debug!("graph does not need to be checked or emitted.");
       return Ok(ResultInfo {
@@ -883,11 +1114,32 @@ impl Graph {
     // point where we know we are actually going to check the program.  If we
     // moved it out of here, we wouldn't know until after the check has already
     // happened, which isn't informative to the users.
-    for specifier in &self.roots {
-      log::info!("{} {}", colors::green("Check"), specifier);
+    if !options.quiet {
+      for specifier in &self.roots {
+        log::info!("{} {}", colors::green("Check"), specifier);
+      }
     }
 
     let root_names = self.get_root_names(!config.get_check_js())?;
+    let maybe_remote_names = if options.local_only {
+      Some(self.get_remote_names())
+    } else {
+      None
+    };
+    // The incremental state of the previous check of the same roots with the
+    // same configuration is reused, so that only the modules which changed
+    // and the modules which depend on them are checked again.
+    let tsbuildinfo_key = self.get_tsbuildinfo_key(&config, options.local_only);
+    self.maybe_tsbuildinfo = if needs_reload {
+      None
+    } else {
+      self
+        .handler
+        .lock()
+        .unwrap()
+        .get_tsbuildinfo(&tsbuildinfo_key)?
+    };
+    self.maybe_tsbuildinfo_key = Some(tsbuildinfo_key);
     let maybe_tsbuildinfo = self.maybe_tsbuildinfo.clone();
     let hash_data =
       vec![config.as_bytes(), version::deno().as_bytes().to_owned()];
@@ -907,6 +1159,7 @@ impl Graph {
       graph: graph.clone(),
       hash_data,
       maybe_config_specifier,
+      maybe_remote_names,
       maybe_tsbuildinfo,
       root_names,
     })?;
@@ -929,8 +1182,12 @@ impl Graph {
             // Sometimes if tsc sees a CommonJS file it will _helpfully_ output it
             // to ESM, which we don't really want unless someone has enabled the
             // check_js option.
-            if !check_js
-              && graph.get_media_type(&specifier) == Some(MediaType::JavaScript)
+            // JSON and WebAssembly modules are presented to tsc as
+            // declarations and JavaScript, and their emit is never used.
+            let media_type = graph.get_media_type(&specifier);
+            if media_type == Some(MediaType::Json)
+              || media_type == Some(MediaType::Wasm)
+              || (!check_js && media_type == Some(MediaType::JavaScript))
             {
               debug!("skipping emit for {}", specifier);
               continue;
@@ -946,13 +1203,12 @@ impl Graph {
             }
           }
         }
-        let config = config.as_bytes();
         for (specifier, code) in codes.iter() {
           if let ModuleSlot::Module(module) =
             graph.get_module_mut(specifier).unwrap()
           {
             module.set_emit(code.clone(), maps.get(specifier).cloned());
-            module.set_version(&config);
+            module.set_version(&version_config);
             module.is_dirty = true;
           } else {
             return Err(GraphError::MissingSpecifier(specifier.clone()).into());
@@ -1030,6 +1286,9 @@ impl Graph {
     if options.bundle_type != BundleType::None && config.get_declaration() {
       return Err(anyhow!("The bundle option is set, but the compiler option of `declaration` is true which is not currently supported."));
     }
+    if options.splitting && options.bundle_type != BundleType::Module {
+      return Err(anyhow!("The splitting option is set, but the bundle option is not \"module\", which is required."));
+    }
 
     let mut emitted_files = HashMap::new();
     if options.check {
@@ -1043,6 +1302,7 @@ impl Graph {
         graph: graph.clone(),
         hash_data,
         maybe_config_specifier: None,
+        maybe_remote_names: None,
         maybe_tsbuildinfo: None,
         root_names,
       })?;
@@ -1060,15 +1320,12 @@ impl Graph {
             "Only a single root module supported."
           );
           let specifier = &graph.roots[0];
-          let (src, maybe_src_map) = graph.emit_bundle(
+          emitted_files.extend(graph.emit_bundles(
             specifier,
             &config.into(),
             &options.bundle_type,
-          )?;
-          emitted_files.insert("deno:///bundle.js".to_string(), src);
-          if let Some(src_map) = maybe_src_map {
-            emitted_files.insert("deno:///bundle.js.map".to_string(), src_map);
-          }
+            options.splitting,
+          )?);
         }
         BundleType::None => {
           for emitted_file in &response.emitted_files {
@@ -1115,16 +1372,17 @@ impl Graph {
             "Only a single root module supported."
           );
           let specifier = &self.roots[0];
-          let (src, maybe_src_map) = self.emit_bundle(
+          let bundles = self.emit_bundles(
             specifier,
             &config.into(),
             &options.bundle_type,
+            options.splitting,
           )?;
-          emit_count += 1;
-          emitted_files.insert("deno:///bundle.js".to_string(), src);
-          if let Some(src_map) = maybe_src_map {
-            emitted_files.insert("deno:///bundle.js.map".to_string(), src_map);
-          }
+          emit_count += bundles
+            .keys()
+            .filter(|file_name| file_name.ends_with(".js"))
+            .count() as u32;
+          emitted_files.extend(bundles);
         }
         BundleType::None => {
           let check_js = config.get_check_js();
@@ -1171,18 +1429,64 @@ impl Graph {
     }
   }
 
+  /// Emit the bundles of the root module for `emit()`, keyed by their file
+  /// names.  When splitting, the bundle of the root module is followed by a
+  /// bundle for each module which is dynamically imported, where the dynamic
+  /// imports are rewritten to import the bundles.  Modules which are
+  /// statically imported by more than one of the bundles are included in each
+  /// of them.
+  fn emit_bundles(
+    &self,
+    specifier: &ModuleSpecifier,
+    emit_options: &ast::EmitOptions,
+    bundle_type: &BundleType,
+    splitting: bool,
+  ) -> Result<HashMap<String, String>, AnyError> {
+    let chunks = Chunks::default();
+    chunks
+      .borrow_mut()
+      .insert(specifier.clone(), "bundle.js".to_string());
+    let maybe_chunks = if splitting { Some(&chunks) } else { None };
+    let mut emitted_files = HashMap::new();
+    let mut index = 0;
+    loop {
+      let maybe_chunk = chunks
+        .borrow()
+        .get_index(index)
+        .map(|(specifier, file_name)| (specifier.clone(), file_name.clone()));
+      let (specifier, file_name) = match maybe_chunk {
+        Some(chunk) => chunk,
+        None => break,
+      };
+      let (src, maybe_src_map) = self.emit_bundle(
+        &specifier,
+        emit_options,
+        bundle_type,
+        maybe_chunks,
+      )?;
+      emitted_files.insert(format!("deno:///{}", file_name), src);
+      if let Some(src_map) = maybe_src_map {
+        emitted_files.insert(format!("deno:///{}.map", file_name), src_map);
+      }
+      index += 1;
+    }
+    Ok(emitted_files)
+  }
+
   /// Shared between `bundle()` and `emit()`.
   fn emit_bundle(
     &self,
     specifier: &ModuleSpecifier,
     emit_options: &ast::EmitOptions,
     bundle_type: &BundleType,
+    maybe_chunks: Option<&Chunks>,
   ) -> Result<(String, Option<String>), AnyError> {
     let cm = Rc::new(swc_common::SourceMap::new(
       swc_common::FilePathMapping::empty(),
     ));
     let globals = swc_common::Globals::new();
-    let loader = BundleLoader::new(self, emit_options, &globals, cm.clone());
+    let loader =
+      BundleLoader::new(self, emit_options, &globals, cm.clone(), maybe_chunks);
     let hook = Box::new(BundleHook);
     let module = match bundle_type {
       BundleType::Module => swc_bundler::ModuleType::Es,
@@ -1264,15 +1568,116 @@ impl Graph {
         }
       }
     }
-    for root_specifier in self.roots.iter() {
-      if let Some(tsbuildinfo) = &self.maybe_tsbuildinfo {
-        handler.set_tsbuildinfo(root_specifier, tsbuildinfo.to_owned())?;
-      }
+    if let (Some(key), Some(tsbuildinfo)) =
+      (&self.maybe_tsbuildinfo_key, &self.maybe_tsbuildinfo)
+    {
+      handler.set_tsbuildinfo(key, tsbuildinfo.to_owned())?;
     }
 
     Ok(())
   }
 
+  /// Return the key of the build info of the graph, which is a hash of the
+  /// roots of the graph and the configuration it is checked with, and of
+  /// whether only the local modules are checked, as the build info of such a
+  /// check doesn't have the diagnostics of the remote modules.
+  fn get_tsbuildinfo_key(&self, config: &TsConfig, local_only: bool) -> String {
+    let mut roots: Vec<&str> = self.roots.iter().map(|s| s.as_str()).collect();
+    roots.sort_unstable();
+    let roots = roots.join("\n");
+    let local_only: &[u8] = if local_only { b"local_only" } else { b"" };
+    checksum::gen(&[
+      roots.as_bytes(),
+      &config.as_bytes()[..],
+      local_only,
+      version::deno().as_bytes(),
+    ])
+  }
+
+  /// Return the modules which are statically reachable from the roots of the
+  /// graph, mapped to the module which imports them, where the roots are not
+  /// imported by any module.
+  fn get_static_importers(
+    &self,
+  ) -> HashMap<&ModuleSpecifier, Option<&ModuleSpecifier>> {
+    let mut importers = HashMap::new();
+    let mut queue = VecDeque::new();
+    for root in self.roots.iter() {
+      let root = self.resolve_specifier(root);
+      if !importers.contains_key(root) {
+        importers.insert(root, None);
+        queue.push_back(root);
+      }
+    }
+    while let Some(specifier) = queue.pop_front() {
+      if let ModuleSlot::Module(module) = self.get_module(specifier) {
+        for (_, dep) in module.dependencies.iter() {
+          if dep.is_dynamic {
+            continue;
+          }
+          for dep_specifier in
+            dep.maybe_code.iter().chain(dep.maybe_type.iter())
+          {
+            let dep_specifier = self.resolve_specifier(dep_specifier);
+            if !importers.contains_key(dep_specifier) {
+              importers.insert(dep_specifier, Some(specifier));
+              queue.push_back(dep_specifier);
+            }
+          }
+        }
+      }
+    }
+    importers
+  }
+
+  /// Return an error listing all the statically imported modules of the graph
+  /// which could not be fetched, because they are not in the cache and only
+  /// cached modules are allowed to be used, along with the chain of modules
+  /// importing them and a command to cache them.
+  fn check_cached(&self) -> Result<(), AnyError> {
+    let importers = self.get_static_importers();
+    let mut not_cached: Vec<&ModuleSpecifier> = importers
+      .keys()
+      .filter(|specifier| {
+        matches!(
+          self.modules.get(**specifier),
+          Some(ModuleSlot::Err(err)) if is_not_cached_error(err)
+        )
+      })
+      .cloned()
+      .collect();
+    if not_cached.is_empty() {
+      return Ok(());
+    }
+    not_cached.sort();
+
+    let mut message = format!(
+      "{} not found in cache, --cached-only is specified:",
+      if not_cached.len() == 1 {
+        "1 specifier".to_string()
+      } else {
+        format!("{} specifiers", not_cached.len())
+      }
+    );
+    for specifier in not_cached.iter() {
+      message.push_str(&format!("\n  {}", specifier));
+      let mut maybe_importer = importers.get(specifier).cloned().flatten();
+      while let Some(importer) = maybe_importer {
+        message.push_str(&format!("\n    imported from {}", importer));
+        maybe_importer = importers.get(importer).cloned().flatten();
+      }
+    }
+    message.push_str(&format!(
+      "\nRun the following command to cache the missing specifiers:\n  deno cache {}",
+      not_cached
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+    ));
+    Err(custom_error("NotFound", message))
+  }
+
   /// Retrieve the first module loading error from the graph and return it.
   pub fn get_errors(&self) -> HashMap<ModuleSpecifier, String> {
     self
@@ -1358,6 +1763,23 @@ impl Graph {
     self.modules.keys().map(|s| s.to_owned()).collect()
   }
 
+  /// Return the specifiers of the remote modules in the graph, which are the
+  /// modules which are not type checked when only checking local modules.
+  fn get_remote_names(&self) -> Vec<ModuleSpecifier> {
+    self
+      .modules
+      .iter()
+      .filter_map(|(specifier, module_slot)| match module_slot {
+        ModuleSlot::Module(_)
+          if !matches!(specifier.scheme(), "file" | "data" | "blob") =>
+        {
+          Some(specifier.clone())
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
   /// Transform `self.roots` into something that works for `tsc`, because `tsc`
   /// doesn't like root names without extensions that match its expectations,
   /// nor does it have any concept of redirection, so we have to resolve all
@@ -1437,6 +1859,98 @@ impl Graph {
     }
   }
 
+  /// Consolidate the type declarations of the roots of the graph, and of their
+  /// type dependencies, into a single declaration file, where each module is
+  /// declared as an ambient module named by its specifier.  This is used by
+  /// the `types` subcommand to vendor the types of remote modules for tooling
+  /// which is unable to fetch them.
+  pub fn to_declarations(&self) -> Result<String, AnyError> {
+    let mut declarations = String::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    for root in self.roots.iter() {
+      let module = self.get_specifier(root)?;
+      let types = if let Some((_, types)) = &module.maybe_types {
+        self.resolve_specifier(types).clone()
+      } else {
+        module.specifier.clone()
+      };
+      if &types != root {
+        declarations.push_str(&format!(
+          "declare module \"{}\" {{\n  export * from \"{}\";\n}}\n\n",
+          root, types
+        ));
+      }
+      if seen.insert(types.clone()) {
+        queue.push_back(types);
+      }
+    }
+
+    while let Some(specifier) = queue.pop_front() {
+      let module = self.get_specifier(&specifier)?;
+      if module.media_type != MediaType::Dts {
+        declarations.push_str(&format!(
+          "// \"{}\" is not a declaration file, so its types are not available.\ndeclare module \"{}\";\n\n",
+          specifier, specifier
+        ));
+        continue;
+      }
+      let parsed_module =
+        parse(specifier.as_str(), &module.source, &module.media_type)?;
+      let mut edits = Vec::new();
+      // the declarations are consolidated, so references to other files are
+      // replaced by the declarations of the files themselves
+      for comment in parsed_module.get_leading_comments() {
+        if parse_ts_reference(&comment).is_some() {
+          let mut range = parsed_module.get_range(&comment.span);
+          if module.source[range.end..].starts_with('\n') {
+            range.end += 1;
+          }
+          edits.push((range, String::new()));
+        }
+      }
+      for (span, dependency) in parsed_module.get_specifiers() {
+        if module.dependencies.contains_key(&dependency) {
+          let resolved = self.resolve(&dependency, &specifier, true)?;
+          edits.push((
+            parsed_module.get_range(&span),
+            format!("\"{}\"", resolved),
+          ));
+        }
+      }
+      let mut dependencies: Vec<&String> = module.dependencies.keys().collect();
+      dependencies.sort();
+      for dependency in dependencies {
+        let resolved = self.resolve(dependency, &specifier, true)?;
+        if seen.insert(resolved.clone()) {
+          queue.push_back(resolved);
+        }
+      }
+      let is_module = parsed_module.module.body.iter().any(|item| {
+        matches!(item, swc_ecmascript::ast::ModuleItem::ModuleDecl(_))
+      });
+      if is_module {
+        // the body of an ambient module is already an ambient context, where
+        // `declare` modifiers are not allowed
+        for range in parsed_module.get_declare_modifiers() {
+          edits.push((range, String::new()));
+        }
+      }
+      let source = apply_edits(&module.source, edits);
+      if is_module {
+        declarations.push_str(&format!(
+          "declare module \"{}\" {{\n{}\n}}\n\n",
+          specifier,
+          source.trim_end()
+        ));
+      } else {
+        declarations.push_str(&format!("{}\n\n", source.trim_end()));
+      }
+    }
+
+    Ok(declarations.trim_end().to_string() + "\n")
+  }
+
   /// Return a structure which provides information about the module graph and
   /// the relationship of the modules in the graph.  This structure is used to
   /// provide information for the `info` subcommand.
@@ -1515,9 +2029,7 @@ impl Graph {
   /// Determines if all of the modules in the graph that require an emit have
   /// a valid emit.  Returns `true` if all the modules have a valid emit,
   /// otherwise false.
-  fn is_emit_valid(&self, config: &TsConfig) -> bool {
-    let check_js = config.get_check_js();
-    let config = config.as_bytes();
+  fn is_emit_valid(&self, check_js: bool, config: &[u8]) -> bool {
     self.modules.iter().all(|(_, m)| {
       if let ModuleSlot::Module(m) = m {
         let needs_emit = match m.media_type {
@@ -1526,7 +2038,7 @@ impl Graph {
           _ => false,
         };
         if needs_emit {
-          m.is_emit_valid(&config)
+          m.is_emit_valid(config)
         } else {
           true
         }
@@ -1536,15 +2048,89 @@ impl Graph {
     })
   }
 
+  /// Validate the import assertions of the dependencies in the graph against
+  /// the media types of the modules they resolved to, where for example JSON
+  /// modules are required to be imported with a `"json"` type assertion.
+  fn validate_import_assertions(&self) -> Result<(), AnyError> {
+    for (_, module_slot) in self.modules.iter() {
+      if let ModuleSlot::Module(module) = module_slot {
+        for (_, dep) in module.dependencies.iter() {
+          if let Some(specifier) = &dep.maybe_code {
+            let specifier = self.resolve_specifier(specifier);
+            if let Some(media_type) = self.get_media_type(specifier) {
+              match import_assertions::validate(
+                dep.maybe_assert_type.as_deref(),
+                &media_type,
+              ) {
+                Ok(_) => (),
+                // this is the error that has always been returned for
+                // importing JSON without an assertion
+                Err(ImportAssertionError::MissingType(media_type)) => {
+                  return Err(
+                    GraphError::UnsupportedImportType(
+                      specifier.clone(),
+                      media_type,
+                    )
+                    .into(),
+                  )
+                }
+                Err(err) => {
+                  return Err(
+                    GraphError::InvalidImportAssertion(
+                      specifier.clone(),
+                      err,
+                      dep.location.clone(),
+                    )
+                    .into(),
+                  )
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
   /// Verify the subresource integrity of the graph based upon the optional
   /// lockfile, updating the lockfile with any missing resources.  This will
-  /// error if any of the resources do not match their lock status.
+  /// error if any of the resources, redirects or types do not match their lock
+  /// status.
   pub fn lock(&self) {
     if let Some(lf) = self.maybe_lockfile.as_ref() {
       let mut lockfile = lf.lock().unwrap();
+      for (from, to) in self.redirects.iter() {
+        let chain: Vec<&str> = match self.redirect_chains.get(from) {
+          Some(chain) => chain.iter().map(|s| s.as_str()).collect(),
+          None => vec![to.as_str()],
+        };
+        let valid = lockfile.check_or_insert_redirect(from.as_str(), &chain);
+        if !valid {
+          eprintln!(
+            "{}",
+            GraphError::InvalidResolution(
+              from.clone(),
+              lockfile.filename.clone()
+            )
+          );
+          std::process::exit(10);
+        }
+      }
       for (ms, module_slot) in self.modules.iter() {
         if let ModuleSlot::Module(module) = module_slot {
           let specifier = module.specifier.to_string();
+          // a module which was locked as a redirect must keep redirecting
+          if !lockfile.check_not_redirected(&specifier) {
+            eprintln!(
+              "{}",
+              GraphError::InvalidResolution(
+                ms.clone(),
+                lockfile.filename.clone()
+              )
+            );
+            std::process::exit(10);
+          }
           let valid = lockfile.check_or_insert(&specifier, &module.source);
           if !valid {
             eprintln!(
@@ -1553,6 +2139,20 @@ impl Graph {
             );
             std::process::exit(10);
           }
+          if let Some((_, types_specifier)) = &module.maybe_types {
+            let valid = lockfile
+              .check_or_insert_types(&specifier, types_specifier.as_str());
+            if !valid {
+              eprintln!(
+                "{}",
+                GraphError::InvalidResolution(
+                  ms.clone(),
+                  lockfile.filename.clone()
+                )
+              );
+              std::process::exit(10);
+            }
+          }
         }
       }
     }
@@ -1709,15 +2309,19 @@ impl Graph {
     let config = ts_config.as_bytes();
     let check_js = ts_config.get_check_js();
     let emit_options: ast::EmitOptions = ts_config.into();
-    let mut emit_count = 0_u32;
+    let mut modules = Vec::new();
     for (specifier, module_slot) in self.modules.iter_mut() {
       if let ModuleSlot::Module(module) = module_slot {
         // TODO(kitsonk) a lot of this logic should be refactored into `Module` as
         // we start to support other methods on the graph.  Especially managing
         // the dirty state is something the module itself should "own".
 
-        // if the module is a Dts file we should skip it
-        if module.media_type == MediaType::Dts {
+        // if the module is a Dts, JSON or Wasm file we should skip it, as
+        // there is nothing to transpile
+        if module.media_type == MediaType::Dts
+          || module.media_type == MediaType::Json
+          || module.media_type == MediaType::Wasm
+        {
           continue;
         }
         // if we don't have check_js enabled, we won't touch non TypeScript or JSX
@@ -1732,18 +2336,31 @@ impl Graph {
 
         let needs_reload =
           options.reload && !options.reload_exclusions.contains(specifier);
-        // skip modules that already have a valid emit
+        // skip modules that already have a valid emit, which is cached per
+        // module along with a hash of its source and the configuration
         if module.is_emit_valid(&config) && !needs_reload {
           continue;
         }
-        let parsed_module = module.parse()?;
+        modules.push(module);
+      }
+    }
+    // The transpilation of a module doesn't depend on any other module, so the
+    // modules are parsed and transpiled in parallel.
+    modules
+      .par_iter_mut()
+      .try_for_each(|module| -> Result<(), AnyError> {
+        let parsed_module = parse(
+          module.specifier.as_str(),
+          &module.source,
+          &module.media_type,
+        )?;
         let emit = parsed_module.transpile(&emit_options)?;
-        emit_count += 1;
         module.maybe_emit = Some(Emit::Cli(emit));
         module.set_version(&config);
         module.is_dirty = true;
-      }
-    }
+        Ok(())
+      })?;
+    let emit_count = modules.len() as u32;
     self.flush()?;
 
     let stats = Stats(vec![
@@ -1833,6 +2450,8 @@ impl swc_bundler::Resolve for Graph {
 pub struct GraphBuilder {
   graph: Graph,
   maybe_import_map: Option<Arc<Mutex<ImportMap>>>,
+  maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
+  maybe_template_permissions: Option<Permissions>,
   pending: FuturesUnordered<FetchFuture>,
 }
 
@@ -1847,10 +2466,29 @@ impl GraphBuilder {
     GraphBuilder {
       graph: Graph::new(handler, maybe_lockfile),
       maybe_import_map: internal_import_map,
+      maybe_module_hooks: None,
+      maybe_template_permissions: None,
       pending: FuturesUnordered::new(),
     }
   }
 
+  /// Resolve the dynamic imports of template literals in local modules, like
+  /// ``import(`./plugins/${name}.ts`)``, to the files which match them, which
+  /// are then added to the graph as dynamic dependencies.  The directories of
+  /// the files are read with the read permission of `permissions`.
+  pub fn set_template_imports(&mut self, permissions: Permissions) {
+    self.maybe_template_permissions = Some(permissions);
+  }
+
+  /// Set the hooks which are consulted when resolving the dependencies of the
+  /// modules added to the graph.
+  pub fn set_module_hooks(
+    &mut self,
+    maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
+  ) {
+    self.maybe_module_hooks = maybe_module_hooks;
+  }
+
   /// Add a module into the graph based on a module specifier.  The module
   /// and any dependencies will be fetched from the handler.  The module will
   /// also be treated as a _root_ module in the graph.
@@ -1864,11 +2502,9 @@ impl GraphBuilder {
     if !self.graph.roots.contains(specifier) {
       self.graph.roots.push(specifier.clone());
       self.graph.roots_dynamic = self.graph.roots_dynamic && is_dynamic;
-      if self.graph.maybe_tsbuildinfo.is_none() {
-        let handler = self.graph.handler.lock().unwrap();
-        self.graph.maybe_tsbuildinfo = handler.get_tsbuildinfo(specifier)?;
-      }
     }
+    self.graph.check_cached()?;
+    self.graph.validate_import_assertions()?;
 
     Ok(())
   }
@@ -1982,13 +2618,16 @@ impl GraphBuilder {
   ) -> Result<(), AnyError> {
     let specifier = cached_module.specifier.clone();
     let requested_specifier = cached_module.requested_specifier.clone();
-    let mut module =
-      Module::new(cached_module, is_root, self.maybe_import_map.clone());
+    let redirect_chain = cached_module.redirect_chain.clone();
+    let maybe_raw_source = cached_module.maybe_raw_source.clone();
+    let mut module = Module::new(
+      cached_module,
+      is_root,
+      self.maybe_import_map.clone(),
+      self.maybe_module_hooks.clone(),
+    );
     match module.media_type {
-      MediaType::Json
-      | MediaType::SourceMap
-      | MediaType::TsBuildInfo
-      | MediaType::Unknown => {
+      MediaType::SourceMap | MediaType::TsBuildInfo | MediaType::Unknown => {
         return Err(
           GraphError::UnsupportedImportType(
             module.specifier,
@@ -1999,10 +2638,39 @@ impl GraphBuilder {
       }
       _ => (),
     }
-    if !module.is_parsed {
+    // Verify the integrity of the module, when the import map specifies the
+    // integrity of either the requested or the redirected specifier. The
+    // integrity is that of the fetched bytes, not of the source they are
+    // turned into.
+    if let Some(import_map) = &self.maybe_import_map {
+      let import_map = import_map.lock().unwrap();
+      if let Some(integrity) = import_map
+        .get_integrity(&requested_specifier)
+        .or_else(|| import_map.get_integrity(&specifier))
+      {
+        let raw_source = match &maybe_raw_source {
+          Some(raw_source) => raw_source.as_slice(),
+          None => module.source.as_bytes(),
+        };
+        if !checksum::verify_integrity(raw_source, integrity) {
+          return Err(
+            GraphError::InvalidIntegrity(specifier, integrity.to_string())
+              .into(),
+          );
+        }
+      }
+    }
+    // The templates of dynamic imports are not part of the cached
+    // dependencies, so the module is parsed again to find them.
+    let resolve_templates = self.maybe_template_permissions.is_some()
+      && module.specifier.scheme() == "file";
+    // JSON modules don't have any dependencies
+    if (!module.is_parsed || resolve_templates)
+      && module.media_type != MediaType::Json
+    {
       let has_types = module.maybe_types.is_some();
       module.parse()?;
-      if self.maybe_import_map.is_none() {
+      if self.maybe_import_map.is_none() && self.maybe_module_hooks.is_none() {
         let mut handler = self.graph.handler.lock().unwrap();
         handler.set_deps(&specifier, module.dependencies.clone())?;
         if !has_types {
@@ -2012,20 +2680,40 @@ impl GraphBuilder {
         }
       }
     }
+    if let Some(permissions) = self.maybe_template_permissions.as_mut() {
+      if resolve_templates {
+        module.add_template_imports(permissions)?;
+      }
+    }
+    // Type declarations mapped to the module by the import map take precedence
+    // over any types provided by the module itself.
+    if let Some(import_map) = &self.maybe_import_map {
+      let import_map = import_map.lock().unwrap();
+      if let Some(types) = import_map
+        .get_types(&requested_specifier)
+        .or_else(|| import_map.get_types(&specifier))
+      {
+        module.maybe_types = Some((types.to_string(), types.clone()));
+      }
+    }
     for (_, dep) in module.dependencies.iter() {
       let maybe_referrer = Some(dep.location.clone());
       for maybe_specifier in &[dep.maybe_code.as_ref(), dep.maybe_type.as_ref()]
       {
         if let Some(&dep_specifier) = maybe_specifier.as_ref() {
           if dep_specifier.scheme() == "bare" {
+            let maybe_import_map =
+              self.maybe_import_map.as_ref().map(|m| m.lock().unwrap());
+            let unmapped = UnmappedSpecifier::new(
+              dep_specifier.path(),
+              Some(specifier.as_str()),
+              maybe_import_map.as_deref(),
+            );
             self.graph.modules.insert(
               dep_specifier.clone(),
               ModuleSlot::Err(Arc::new(
-                ModuleResolutionError::ImportPrefixMissing(
-                  dep_specifier.path().to_string(),
-                  Some(specifier.to_string()),
-                )
-                .into(),
+                ImportMapError::UnmappedBareSpecifier(Box::new(unmapped))
+                  .into(),
               )),
             );
           } else {
@@ -2042,6 +2730,12 @@ impl GraphBuilder {
       self.fetch(specifier, &None, is_root_dynamic);
     }
     if specifier != requested_specifier {
+      if !redirect_chain.is_empty() {
+        self
+          .graph
+          .redirect_chains
+          .insert(requested_specifier.clone(), redirect_chain);
+      }
       self
         .graph
         .redirects
@@ -2094,7 +2788,7 @@ pub mod tests {
   pub struct MockSpecifierHandler {
     pub fixtures: PathBuf,
     pub maybe_tsbuildinfo: Option<String>,
-    pub tsbuildinfo_calls: Vec<(ModuleSpecifier, String)>,
+    pub tsbuildinfo_calls: Vec<(String, String)>,
     pub cache_calls: Vec<(ModuleSpecifier, Emit)>,
     pub deps_calls: Vec<(ModuleSpecifier, DependencyMap)>,
     pub types_calls: Vec<(ModuleSpecifier, String)>,
@@ -2138,10 +2832,7 @@ pub mod tests {
     ) -> FetchFuture {
       Box::pin(future::ready(self.get_cache(specifier)))
     }
-    fn get_tsbuildinfo(
-      &self,
-      _specifier: &ModuleSpecifier,
-    ) -> Result<Option<String>, AnyError> {
+    fn get_tsbuildinfo(&self, _key: &str) -> Result<Option<String>, AnyError> {
       Ok(self.maybe_tsbuildinfo.clone())
     }
     fn set_cache(
@@ -2162,13 +2853,11 @@ pub mod tests {
     }
     fn set_tsbuildinfo(
       &mut self,
-      specifier: &ModuleSpecifier,
+      key: &str,
       tsbuildinfo: String,
     ) -> Result<(), AnyError> {
       self.maybe_tsbuildinfo = Some(tsbuildinfo.clone());
-      self
-        .tsbuildinfo_calls
-        .push((specifier.clone(), tsbuildinfo));
+      self.tsbuildinfo_calls.push((key.to_string(), tsbuildinfo));
       Ok(())
     }
     fn set_deps(
@@ -2431,6 +3120,19 @@ pub mod tests {
     assert_eq!(h.tsbuildinfo_calls.len(), 1);
   }
 
+  #[tokio::test]
+  async fn test_graph_tsbuildinfo_key() {
+    let specifier = resolve_url_or_path("file:///tests/main.ts")
+      .expect("could not resolve module");
+    let (graph, _) = setup(specifier).await;
+    let config_window = TsConfig::new(json!({ "lib": TypeLib::DenoWindow }));
+    let config_worker = TsConfig::new(json!({ "lib": TypeLib::DenoWorker }));
+    let key = graph.get_tsbuildinfo_key(&config_window, false);
+    assert_eq!(key, graph.get_tsbuildinfo_key(&config_window, false));
+    assert_ne!(key, graph.get_tsbuildinfo_key(&config_worker, false));
+    assert_ne!(key, graph.get_tsbuildinfo_key(&config_window, true));
+  }
+
   #[tokio::test]
   async fn fix_graph_check_mjs_root() {
     let specifier = resolve_url_or_path("file:///tests/a.mjs")
@@ -2539,6 +3241,7 @@ pub mod tests {
       .emit(EmitOptions {
         check: true,
         bundle_type: BundleType::None,
+        splitting: false,
         debug: false,
         maybe_user_config: None,
       })
@@ -2579,6 +3282,7 @@ pub mod tests {
       .emit(EmitOptions {
         check: true,
         bundle_type: BundleType::Module,
+        splitting: false,
         debug: false,
         maybe_user_config: None,
       })
@@ -2617,6 +3321,7 @@ pub mod tests {
       .emit(EmitOptions {
         check: true,
         bundle_type: BundleType::None,
+        splitting: false,
         debug: false,
         maybe_user_config: Some(user_config),
       })
@@ -2783,6 +3488,127 @@ pub mod tests {
     builder.get_graph();
   }
 
+  #[tokio::test]
+  async fn test_graph_import_map_types() {
+    let c = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let fixtures = c.join("tests/module_graph");
+    let maybe_import_map = Some(
+      ImportMap::from_json(
+        "file:///tests/importmap.json",
+        r#"{
+      "types": {
+        "https://deno.land/x/lib/b.js": "https://deno.land/x/lib/c.d.ts"
+      }
+    }
+    "#,
+      )
+      .expect("could not parse import map"),
+    );
+    let handler = Arc::new(Mutex::new(MockSpecifierHandler {
+      fixtures,
+      ..Default::default()
+    }));
+    let mut builder = GraphBuilder::new(handler, maybe_import_map, None);
+    let specifier = resolve_url_or_path("https://deno.land/x/lib/b.js")
+      .expect("could not resolve module");
+    builder.add(&specifier, false).await.expect("could not add");
+    let graph = builder.get_graph();
+    let types_specifier = resolve_url_or_path("https://deno.land/x/lib/c.d.ts")
+      .expect("could not resolve module");
+    if let ModuleSlot::Module(module) = graph.get_module(&specifier) {
+      assert_eq!(
+        module.maybe_types,
+        Some((types_specifier.to_string(), types_specifier.clone()))
+      );
+    } else {
+      panic!("module not in graph");
+    }
+    assert!(graph.contains(&types_specifier));
+  }
+
+  #[tokio::test]
+  async fn test_graph_to_declarations() {
+    let specifier = resolve_url_or_path("https://deno.land/x/lib/mod.d.ts")
+      .expect("could not resolve module");
+    let (graph, _) = setup(specifier).await;
+    let declarations = graph.to_declarations().unwrap();
+    assert!(declarations.starts_with(
+      r#"declare module "https://deno.land/x/lib/mod.d.ts" {
+export * as a from "https://deno.land/x/lib/a.ts";
+export * as b from "https://deno.land/x/lib/b.js";
+export * as c from "https://deno.land/x/lib/c.d.ts";
+"#
+    ));
+    assert!(declarations
+      .contains("declare module \"https://deno.land/x/lib/a.ts\";\n"));
+    assert!(declarations
+      .contains("declare module \"https://deno.land/x/lib/b.js\";\n"));
+    assert!(declarations.contains(
+      r#"declare module "https://deno.land/x/lib/c.d.ts" {
+export const c: string[];
+}
+"#
+    ));
+
+    let specifier = resolve_url_or_path("https://deno.land/x/lib/c.js")
+      .expect("could not resolve module");
+    let (graph, _) = setup(specifier).await;
+    let declarations = graph.to_declarations().unwrap();
+    assert_eq!(
+      declarations,
+      r#"declare module "https://deno.land/x/lib/c.js" {
+  export * from "https://deno.land/x/lib/c.d.ts";
+}
+
+declare module "https://deno.land/x/lib/c.d.ts" {
+export const c: string[];
+}
+"#
+    );
+  }
+
+  #[tokio::test]
+  async fn test_graph_to_declarations_only_rewrites_specifiers() {
+    let specifier = resolve_url_or_path("file:///a.d.ts").unwrap();
+    let graph = setup_memory(
+      specifier,
+      map!(
+        "/a.d.ts" => r#"/// <reference types="./c.d.ts" />
+// the types of "./b.d.ts" are re-exported
+import { B } from "./b.d.ts";
+export declare const path: "./b.d.ts";
+export declare function b(): B;
+declare global {
+  const a: string;
+}
+"#,
+        "/b.d.ts" => "export interface B {}\n",
+        "/c.d.ts" => "declare const c: string;\n"
+      ),
+    )
+    .await;
+    let declarations = graph.to_declarations().unwrap();
+    assert_eq!(
+      declarations,
+      r#"declare module "file:///a.d.ts" {
+// the types of "./b.d.ts" are re-exported
+import { B } from "file:///b.d.ts";
+export const path: "./b.d.ts";
+export function b(): B;
+declare global {
+  const a: string;
+}
+}
+
+declare module "file:///b.d.ts" {
+export interface B {}
+}
+
+declare const c: string;
+"#
+    );
+  }
+
   #[tokio::test]
   async fn test_graph_with_lockfile() {
     let c = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
@@ -2804,4 +3630,43 @@ pub mod tests {
       .expect("module not inserted");
     builder.get_graph();
   }
+
+  struct RemapHooks;
+
+  impl ModuleHooks for RemapHooks {
+    fn resolve(
+      &self,
+      specifier: &str,
+      _referrer: &str,
+    ) -> Option<Result<ModuleSpecifier, AnyError>> {
+      if specifier == "https://deno.land/x/a/mod.ts" {
+        Some(
+          resolve_url_or_path("file:///tests/c/mod.ts").map_err(|e| e.into()),
+        )
+      } else {
+        None
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_graph_module_hooks_resolve() {
+    let c = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let fixtures = c.join("tests/module_graph");
+    let handler = Arc::new(Mutex::new(MockSpecifierHandler {
+      fixtures,
+      ..Default::default()
+    }));
+    let mut builder = GraphBuilder::new(handler, None, None);
+    builder.set_module_hooks(Some(Arc::new(RemapHooks)));
+    let specifier = resolve_url_or_path("file:///tests/importremap.ts")
+      .expect("could not resolve module");
+    builder.add(&specifier, false).await.expect("could not add");
+    let graph = builder.get_graph();
+    assert!(
+      graph.contains(&resolve_url_or_path("file:///tests/c/mod.ts").unwrap())
+    );
+    assert!(!graph
+      .contains(&resolve_url_or_path("https://deno.land/x/a/mod.ts").unwrap()));
+  }
 }
@@ -0,0 +1,51 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Hooks which allow an embedder of the CLI to intercept the resolution of
+//! module specifiers and the loading of module sources, for example to serve
+//! modules from a database or from a custom scheme.  The hooks are provided
+//! when the `ProgramState` is built, and are consulted by both the module
+//! graph and the module loader before the default behavior is applied.
+
+use crate::file_fetcher::File;
+
+use deno_core::error::AnyError;
+use deno_core::futures::Future;
+use deno_core::ModuleSpecifier;
+use std::fmt;
+use std::pin::Pin;
+
+pub type LoadFuture =
+  Pin<Box<dyn Future<Output = Result<File, AnyError>> + 'static + Send>>;
+
+pub trait ModuleHooks: Sync + Send {
+  /// Resolve a specifier imported by the referrer.  Returning `None` falls
+  /// back to the default resolution, which applies the import map, if any,
+  /// before resolving the specifier relative to the referrer.
+  ///
+  /// Specifiers resolved by the hook are treated like specifiers remapped by
+  /// an import map, so a remote module is allowed to import a local one.
+  fn resolve(
+    &self,
+    _specifier: &str,
+    _referrer: &str,
+  ) -> Option<Result<ModuleSpecifier, AnyError>> {
+    None
+  }
+
+  /// Load the source of a resolved module.  Returning `None` falls back to
+  /// fetching the module with the file fetcher, which also applies the
+  /// permissions of the import.
+  fn load(
+    &self,
+    _specifier: &ModuleSpecifier,
+    _is_dynamic: bool,
+  ) -> Option<LoadFuture> {
+    None
+  }
+}
+
+impl fmt::Debug for dyn ModuleHooks {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "ModuleHooks {{ }}")
+  }
+}
@@ -1,6 +1,8 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::import_map::ImportMap;
+use crate::import_map::ImportMapError;
+use crate::import_map::UnmappedSpecifier;
 use crate::module_graph::TypeLib;
 use crate::program_state::ProgramState;
 use deno_core::error::AnyError;
@@ -8,6 +10,7 @@ use deno_core::futures::future::FutureExt;
 use deno_core::futures::Future;
 use deno_core::ModuleLoadId;
 use deno_core::ModuleLoader;
+use deno_core::ModuleResolutionError;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_runtime::permissions::Permissions;
@@ -74,13 +77,26 @@ impl ModuleLoader for CliModuleLoader {
     referrer: &str,
     is_main: bool,
   ) -> Result<ModuleSpecifier, AnyError> {
-    // FIXME(bartlomieju): hacky way to provide compatibility with repl
-    let referrer = if referrer.is_empty() && self.program_state.flags.repl {
-      deno_core::DUMMY_SPECIFIER
+    // Code evaluated in the REPL has no referrer, so it is resolved relative
+    // to the REPL's main module in the current directory. Using a real URL
+    // rather than a dummy one lets the import map apply to it, including its
+    // relative mappings and scopes, the same way it does under `deno run`.
+    let repl_referrer;
+    let referrer = if self.program_state.flags.repl
+      && (referrer.is_empty() || referrer == deno_core::DUMMY_SPECIFIER)
+    {
+      repl_referrer = deno_core::resolve_url_or_path("./$deno$repl.ts")?;
+      repl_referrer.as_str()
     } else {
       referrer
     };
 
+    if let Some(module_hooks) = &self.program_state.maybe_module_hooks {
+      if let Some(result) = module_hooks.resolve(specifier, referrer) {
+        return result;
+      }
+    }
+
     if !is_main {
       if let Some(import_map) = &self.import_map {
         return import_map
@@ -89,7 +105,16 @@ impl ModuleLoader for CliModuleLoader {
       }
     }
 
-    let module_specifier = deno_core::resolve_import(specifier, referrer)?;
+    let module_specifier = deno_core::resolve_import(specifier, referrer)
+      .map_err(|err| match err {
+        ModuleResolutionError::ImportPrefixMissing(..) => {
+          ImportMapError::UnmappedBareSpecifier(Box::new(
+            UnmappedSpecifier::new(specifier, Some(referrer), None),
+          ))
+          .into()
+        }
+        err => AnyError::from(err),
+      })?;
 
     Ok(module_specifier)
   }
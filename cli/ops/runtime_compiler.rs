@@ -48,6 +48,7 @@ struct EmitArgs {
   import_map_path: Option<String>,
   root_specifier: String,
   sources: Option<HashMap<String, String>>,
+  splitting: Option<bool>,
 }
 
 async fn op_emit(
@@ -122,10 +123,24 @@ async fn op_emit(
   let (files, mut result_info) = graph.emit(EmitOptions {
     bundle_type,
     check: args.check.unwrap_or(true),
+    splitting: args.splitting.unwrap_or(false),
     debug,
     maybe_user_config: args.compiler_options,
   })?;
   result_info.diagnostics.extend_graph_errors(graph_errors);
+  // The emitted code is evaluated outside of the module graph, if at all, so
+  // the source maps are registered with the names of the emitted files, which
+  // the code can be evaluated with as its source URL.
+  for (file_name, source_map) in files.iter() {
+    if let Some(code_file_name) = file_name.strip_suffix(".map") {
+      if files.contains_key(code_file_name) {
+        program_state.register_eval_source_map(
+          code_file_name,
+          source_map.as_bytes().to_vec(),
+        );
+      }
+    }
+  }
 
   Ok(json!({
     "diagnostics": result_info.diagnostics,
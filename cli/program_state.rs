@@ -1,17 +1,21 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::compat;
 use crate::config_file::ConfigFile;
 use crate::deno_dir;
 use crate::file_fetcher::CacheSetting;
 use crate::file_fetcher::FileFetcher;
+use crate::file_fetcher::ReloadList;
 use crate::flags;
-use crate::http_cache;
+use crate::flags::TypeCheckMode;
+use crate::http_util::ProxyConfig;
 use crate::import_map::ImportMap;
 use crate::lockfile::Lockfile;
 use crate::module_graph::CheckOptions;
 use crate::module_graph::GraphBuilder;
 use crate::module_graph::TranspileOptions;
 use crate::module_graph::TypeLib;
+use crate::module_hooks::ModuleHooks;
 use crate::source_maps::SourceMapGetter;
 use crate::specifier_handler::FetchHandler;
 use crate::version;
@@ -28,10 +32,12 @@ use deno_core::resolve_url;
 use deno_core::url::Url;
 use deno_core::ModuleSource;
 use deno_core::ModuleSpecifier;
+use deno_core::SharedArrayBufferStore;
 use log::debug;
 use log::warn;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::env;
 use std::fs::read;
 use std::sync::Arc;
@@ -55,14 +61,95 @@ pub struct ProgramState {
   pub ca_data: Option<Vec<u8>>,
   pub blob_url_store: BlobUrlStore,
   pub broadcast_channel: InMemoryBroadcastChannel,
+  /// Shared by all the workers when `--unstable` is passed, so that they can
+  /// send each other `SharedArrayBuffer`s.
+  pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
+  /// Hooks provided by an embedder to intercept the resolution and loading of
+  /// modules.
+  pub maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
+  /// Source maps of code which is evaluated without being part of the module
+  /// graph, like the code entered in the REPL.
+  pub eval_source_maps: Mutex<EvalSourceMaps>,
+}
+
+/// The maximum number of source maps of evaluated code which are kept, so
+/// that a long running REPL session doesn't keep all of them.
+const MAX_EVAL_SOURCE_MAPS: usize = 1000;
+
+/// The source maps of evaluated code, keyed by the source URL the code is
+/// evaluated with. Once there are `MAX_EVAL_SOURCE_MAPS` of them, the least
+/// recently used one is dropped to make room for a new one.
+#[derive(Debug, Default)]
+pub struct EvalSourceMaps {
+  maps: HashMap<String, Vec<u8>>,
+  /// The source URLs, from the least to the most recently used.
+  order: VecDeque<String>,
+}
+
+impl EvalSourceMaps {
+  pub fn get(&mut self, source_url: &str) -> Option<Vec<u8>> {
+    let source_map = self.maps.get(source_url)?.clone();
+    self.touch(source_url);
+    Some(source_map)
+  }
+
+  pub fn insert(&mut self, source_url: String, source_map: Vec<u8>) {
+    if self.maps.insert(source_url.clone(), source_map).is_some() {
+      self.touch(&source_url);
+      return;
+    }
+    self.order.push_back(source_url);
+    if self.order.len() > MAX_EVAL_SOURCE_MAPS {
+      if let Some(evicted) = self.order.pop_front() {
+        self.maps.remove(&evicted);
+      }
+    }
+  }
+
+  /// Marks the source map of `source_url` as the most recently used one.
+  fn touch(&mut self, source_url: &str) {
+    if let Some(index) = self.order.iter().position(|url| url == source_url) {
+      if let Some(url) = self.order.remove(index) {
+        self.order.push_back(url);
+      }
+    }
+  }
 }
 
 impl ProgramState {
   pub async fn build(flags: flags::Flags) -> Result<Arc<Self>, AnyError> {
+    Self::build_with_module_hooks(flags, None).await
+  }
+
+  /// Build the program state, where the optional module hooks are consulted
+  /// when resolving and loading modules.
+  pub async fn build_with_module_hooks(
+    flags: flags::Flags,
+    maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
+  ) -> Result<Arc<Self>, AnyError> {
+    let maybe_config_file =
+      if let Some(config_path) = flags.config_path.as_ref() {
+        Some(ConfigFile::read(config_path)?)
+      } else {
+        None
+      };
+
     let custom_root = env::var("DENO_DIR").map(String::into).ok();
-    let dir = deno_dir::DenoDir::new(custom_root)?;
-    let deps_cache_location = dir.root.join("deps");
-    let http_cache = http_cache::HttpCache::new(&deps_cache_location);
+    let global_dir = deno_dir::DenoDir::new(custom_root)?;
+    // a cache directory provided on the command line takes precedence over one
+    // which is set in the config file
+    let maybe_cache_dir = flags.cache_dir.clone().or_else(|| {
+      maybe_config_file
+        .as_ref()
+        .and_then(|config_file| config_file.to_cache_dir())
+    });
+    let dir = match maybe_cache_dir {
+      Some(cache_dir) => {
+        deno_dir::DenoDir::with_fallback(cache_dir, &global_dir)?
+      }
+      None => global_dir,
+    };
+    let http_cache = dir.get_http_cache();
     let ca_file = flags.ca_file.clone().or_else(|| env::var("DENO_CERT").ok());
     let ca_data = match &ca_file {
       Some(ca_file) => Some(read(ca_file).context("Failed to open ca file")?),
@@ -72,7 +159,7 @@ impl ProgramState {
     let cache_usage = if flags.cached_only {
       CacheSetting::Only
     } else if !flags.cache_blocklist.is_empty() {
-      CacheSetting::ReloadSome(flags.cache_blocklist.clone())
+      CacheSetting::ReloadSome(ReloadList::new(&flags.cache_blocklist)?)
     } else if flags.reload {
       CacheSetting::ReloadAll
     } else {
@@ -81,29 +168,35 @@ impl ProgramState {
 
     let blob_url_store = BlobUrlStore::default();
     let broadcast_channel = InMemoryBroadcastChannel::default();
+    let shared_array_buffer_store = if flags.unstable {
+      Some(SharedArrayBufferStore::default())
+    } else {
+      None
+    };
 
     let file_fetcher = FileFetcher::new(
       http_cache,
       cache_usage,
       !flags.no_remote,
       ca_data.clone(),
+      ProxyConfig::from_env(flags.proxy.as_deref())?,
       blob_url_store.clone(),
     )?;
 
-    let lockfile = if let Some(filename) = &flags.lock {
-      let lockfile = Lockfile::new(filename.clone(), flags.lock_write)?;
+    // a lock file provided on the command line takes precedence over one which
+    // is set in the config file
+    let maybe_lock_path = flags.lock.clone().or_else(|| {
+      maybe_config_file
+        .as_ref()
+        .and_then(|config_file| config_file.to_lock_file_path())
+    });
+    let lockfile = if let Some(filename) = maybe_lock_path {
+      let lockfile = Lockfile::new(filename, flags.lock_write)?;
       Some(Arc::new(Mutex::new(lockfile)))
     } else {
       None
     };
 
-    let maybe_config_file =
-      if let Some(config_path) = flags.config_path.as_ref() {
-        Some(ConfigFile::read(config_path)?)
-      } else {
-        None
-      };
-
     let maybe_import_map: Option<ImportMap> =
       match flags.import_map_path.as_ref() {
         None => None,
@@ -124,6 +217,27 @@ impl ProgramState {
           Some(import_map)
         }
       };
+    // an explicitly provided import map takes precedence over one which is
+    // embedded in the config file
+    let maybe_import_map = match (maybe_import_map, &maybe_config_file) {
+      (None, Some(config_file)) => config_file.to_import_map()?,
+      (maybe_import_map, _) => maybe_import_map,
+    };
+    // in compatibility mode the built-in node modules are mapped to their
+    // polyfills, unless they are already mapped by the user
+    let maybe_import_map = if flags.compat {
+      let mut import_map = match maybe_import_map {
+        Some(import_map) => import_map,
+        None => {
+          let base_url = Url::from_directory_path(env::current_dir()?).unwrap();
+          ImportMap::from_json(base_url.as_str(), "{}")?
+        }
+      };
+      import_map.update_imports(compat::get_node_imports());
+      Some(import_map)
+    } else {
+      maybe_import_map
+    };
 
     let maybe_inspect_host = flags.inspect.or(flags.inspect_brk);
     let maybe_inspector_server = maybe_inspect_host.map(|host| {
@@ -148,6 +262,9 @@ impl ProgramState {
       ca_data,
       blob_url_store,
       broadcast_channel,
+      shared_array_buffer_store,
+      maybe_module_hooks,
+      eval_source_maps: Default::default(),
     };
     Ok(Arc::new(program_state))
   }
@@ -170,6 +287,7 @@ impl ProgramState {
 
     let mut builder =
       GraphBuilder::new(handler, maybe_import_map, self.lockfile.clone());
+    builder.set_module_hooks(self.maybe_module_hooks.clone());
 
     for specifier in specifiers {
       builder.add(&specifier, false).await?;
@@ -184,7 +302,7 @@ impl ProgramState {
       modules.keys().cloned().collect::<HashSet<_>>()
     };
 
-    let result_modules = if self.flags.no_check {
+    let result_modules = if self.flags.typecheck_mode == TypeCheckMode::None {
       let result_info = graph.transpile(TranspileOptions {
         debug,
         maybe_config_file,
@@ -201,7 +319,9 @@ impl ProgramState {
         debug,
         emit: true,
         lib,
+        local_only: self.flags.typecheck_mode == TypeCheckMode::Local,
         maybe_config_file,
+        quiet: false,
         reload: self.flags.reload,
         reload_exclusions,
       })?;
@@ -241,6 +361,10 @@ impl ProgramState {
     maybe_import_map: Option<ImportMap>,
   ) -> Result<(), AnyError> {
     let specifier = specifier.clone();
+    let maybe_template_permissions = self
+      .flags
+      .template_imports
+      .then(|| root_permissions.clone());
     let handler = Arc::new(Mutex::new(FetchHandler::new(
       self,
       root_permissions,
@@ -248,6 +372,10 @@ impl ProgramState {
     )?));
     let mut builder =
       GraphBuilder::new(handler, maybe_import_map, self.lockfile.clone());
+    builder.set_module_hooks(self.maybe_module_hooks.clone());
+    if let Some(permissions) = maybe_template_permissions {
+      builder.set_template_imports(permissions);
+    }
     builder.add(&specifier, is_dynamic).await?;
     builder.analyze_config_file(&self.maybe_config_file).await?;
     let mut graph = builder.get_graph();
@@ -258,7 +386,7 @@ impl ProgramState {
       modules.keys().cloned().collect::<HashSet<_>>()
     };
 
-    let result_modules = if self.flags.no_check {
+    let result_modules = if self.flags.typecheck_mode == TypeCheckMode::None {
       let result_info = graph.transpile(TranspileOptions {
         debug,
         maybe_config_file,
@@ -275,7 +403,9 @@ impl ProgramState {
         debug,
         emit: true,
         lib,
+        local_only: self.flags.typecheck_mode == TypeCheckMode::Local,
         maybe_config_file,
+        quiet: false,
         reload: self.flags.reload,
         reload_exclusions,
       })?;
@@ -301,6 +431,21 @@ impl ProgramState {
     Ok(())
   }
 
+  /// Register the source map of code which is evaluated with the supplied
+  /// source URL, so that the locations in the stack traces of errors thrown
+  /// by the code are mapped to the original source.
+  pub fn register_eval_source_map(
+    &self,
+    source_url: &str,
+    source_map: Vec<u8>,
+  ) {
+    self
+      .eval_source_maps
+      .lock()
+      .unwrap()
+      .insert(source_url.to_string(), source_map);
+  }
+
   pub fn load(
     &self,
     specifier: ModuleSpecifier,
@@ -384,7 +529,11 @@ impl ProgramState {
 // else, like a refactored file_fetcher.
 impl SourceMapGetter for ProgramState {
   fn get_source_map(&self, file_name: &str) -> Option<Vec<u8>> {
-    if let Ok(specifier) = resolve_url(file_name) {
+    if let Some(source_map) =
+      self.eval_source_maps.lock().unwrap().get(file_name)
+    {
+      Some(source_map)
+    } else if let Ok(specifier) = resolve_url(file_name) {
       if let Some((code, maybe_map)) = self.get_emit(&specifier) {
         let code = String::from_utf8(code).unwrap();
         source_map_from_code(code).or(maybe_map)
@@ -436,3 +585,23 @@ fn source_map_from_code(code: String) -> Option<Vec<u8>> {
     None
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn eval_source_maps_drop_the_least_recently_used() {
+    let mut source_maps = EvalSourceMaps::default();
+    for i in 0..MAX_EVAL_SOURCE_MAPS {
+      source_maps.insert(format!("$deno$repl{}.ts", i), vec![i as u8]);
+    }
+    assert_eq!(source_maps.get("$deno$repl0.ts"), Some(vec![0]));
+
+    source_maps.insert("$deno$repl_new.ts".to_string(), vec![42]);
+    assert_eq!(source_maps.maps.len(), MAX_EVAL_SOURCE_MAPS);
+    assert_eq!(source_maps.get("$deno$repl0.ts"), Some(vec![0]));
+    assert_eq!(source_maps.get("$deno$repl1.ts"), None);
+    assert_eq!(source_maps.get("$deno$repl_new.ts"), Some(vec![42]));
+  }
+}
@@ -1,10 +1,11 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use crate::ast::Location;
-use crate::deno_dir::DenoDir;
 use crate::disk_cache::DiskCache;
 use crate::file_fetcher::FileFetcher;
+use crate::file_fetcher::NotCachedError;
 use crate::media_type::MediaType;
+use crate::module_hooks::ModuleHooks;
 use crate::program_state::ProgramState;
 use deno_runtime::permissions::Permissions;
 
@@ -19,7 +20,6 @@ use deno_core::serde_json;
 use deno_core::ModuleSpecifier;
 use log::debug;
 use std::collections::HashMap;
-use std::env;
 use std::fmt;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -40,6 +40,9 @@ pub type FetchFuture = Pin<
 pub enum HandlerError {
   /// A fetch error, where we have a location associated with it.
   FetchErrorWithLocation(String, Location),
+  /// A fetch error of a module which is not in the cache, where we have a
+  /// location associated with it.
+  NotCachedWithLocation(NotCachedError, Location),
 }
 
 impl fmt::Display for HandlerError {
@@ -48,6 +51,9 @@ impl fmt::Display for HandlerError {
       HandlerError::FetchErrorWithLocation(ref err, ref location) => {
         write!(f, "{}\n    at {}", err, location)
       }
+      HandlerError::NotCachedWithLocation(ref err, ref location) => {
+        write!(f, "{}\n    at {}", err, location)
+      }
     }
   }
 }
@@ -60,9 +66,15 @@ pub struct CachedModule {
   pub maybe_dependencies: Option<DependencyMap>,
   pub maybe_emit: Option<Emit>,
   pub maybe_emit_path: Option<(PathBuf, Option<PathBuf>)>,
+  /// The bytes of the module as they were fetched, when the source differs
+  /// from them.
+  pub maybe_raw_source: Option<Arc<Vec<u8>>>,
   pub maybe_types: Option<String>,
   pub maybe_version: Option<String>,
   pub media_type: MediaType,
+  /// The specifiers the requested specifier was redirected through, ending
+  /// with the final specifier.  Empty if the module was not redirected.
+  pub redirect_chain: Vec<ModuleSpecifier>,
   pub requested_specifier: ModuleSpecifier,
   pub source: String,
   pub source_path: PathBuf,
@@ -77,9 +89,11 @@ impl Default for CachedModule {
       maybe_dependencies: None,
       maybe_emit: None,
       maybe_emit_path: None,
+      maybe_raw_source: None,
       maybe_types: None,
       maybe_version: None,
       media_type: MediaType::Unknown,
+      redirect_chain: Vec::new(),
       requested_specifier: specifier.clone(),
       source: "".to_string(),
       source_path: PathBuf::new(),
@@ -118,6 +132,8 @@ pub struct Dependency {
   /// The module specifier that resolves to the type only dependency for the
   /// module.
   pub maybe_type: Option<ModuleSpecifier>,
+  /// The value of the `type` import assertion of the dependency, if any.
+  pub maybe_assert_type: Option<String>,
 }
 
 impl Dependency {
@@ -127,6 +143,7 @@ impl Dependency {
       location,
       maybe_code: None,
       maybe_type: None,
+      maybe_assert_type: None,
     }
   }
 }
@@ -141,15 +158,11 @@ pub trait SpecifierHandler: Sync + Send {
     is_dynamic: bool,
   ) -> FetchFuture;
 
-  /// Get the optional build info from the cache for a given module specifier.
-  /// Because build infos are only associated with the "root" modules, they are
-  /// not expected to be cached for each module, but are "lazily" checked when
-  /// a root module is identified.  The `emit_type` also indicates what form
-  /// of the module the build info is valid for.
-  fn get_tsbuildinfo(
-    &self,
-    specifier: &ModuleSpecifier,
-  ) -> Result<Option<String>, AnyError>;
+  /// Get the optional build info from the cache for a given key.  The build
+  /// info is the incremental state of the type checker, which is only valid
+  /// for a set of root modules checked with a specific configuration, which
+  /// the key is derived from.
+  fn get_tsbuildinfo(&self, key: &str) -> Result<Option<String>, AnyError>;
 
   /// Set the emit for the module specifier.
   fn set_cache(
@@ -166,10 +179,10 @@ pub trait SpecifierHandler: Sync + Send {
     types: String,
   ) -> Result<(), AnyError>;
 
-  /// Set the build info for a module specifier, also providing the cache type.
+  /// Set the build info for a given key.
   fn set_tsbuildinfo(
     &mut self,
-    specifier: &ModuleSpecifier,
+    key: &str,
     tsbuildinfo: String,
   ) -> Result<(), AnyError>;
 
@@ -216,6 +229,12 @@ impl CompiledFileMetadata {
   }
 }
 
+/// The build infos are stored in their own directory of the disk cache, as they
+/// are not associated with a single module.
+fn get_tsbuildinfo_filename(key: &str) -> PathBuf {
+  PathBuf::from("tsbuildinfo").join(format!("{}.buildinfo", key))
+}
+
 /// An implementation of the `SpecifierHandler` trait that integrates with the
 /// existing `file_fetcher` interface, which will eventually be refactored to
 /// align it more to the `SpecifierHandler` trait.
@@ -228,6 +247,8 @@ pub struct FetchHandler {
   dynamic_permissions: Permissions,
   /// A clone of the `program_state` file fetcher.
   file_fetcher: FileFetcher,
+  /// The hooks of the `program_state`, which can take over loading modules.
+  maybe_module_hooks: Option<Arc<dyn ModuleHooks>>,
 }
 
 impl FetchHandler {
@@ -236,16 +257,16 @@ impl FetchHandler {
     root_permissions: Permissions,
     dynamic_permissions: Permissions,
   ) -> Result<Self, AnyError> {
-    let custom_root = env::var("DENO_DIR").map(String::into).ok();
-    let deno_dir = DenoDir::new(custom_root)?;
-    let disk_cache = deno_dir.gen_cache;
+    let disk_cache = program_state.dir.gen_cache.clone();
     let file_fetcher = program_state.file_fetcher.clone();
+    let maybe_module_hooks = program_state.maybe_module_hooks.clone();
 
     Ok(FetchHandler {
       disk_cache,
       root_permissions,
       dynamic_permissions,
       file_fetcher,
+      maybe_module_hooks,
     })
   }
 }
@@ -267,47 +288,60 @@ impl SpecifierHandler for FetchHandler {
     };
     let file_fetcher = self.file_fetcher.clone();
     let disk_cache = self.disk_cache.clone();
+    let maybe_load =
+      self.maybe_module_hooks.as_ref().and_then(|module_hooks| {
+        module_hooks.load(&requested_specifier, is_dynamic)
+      });
 
     async move {
-      let source_file = file_fetcher
-        .fetch(&requested_specifier, &mut permissions)
-        .await
-        .map_err(|err| {
-          let err = if let Some(e) = err.downcast_ref::<std::io::Error>() {
-            if e.kind() == std::io::ErrorKind::NotFound {
-              let message = if let Some(location) = &maybe_location {
-                format!(
-                  "Cannot resolve module \"{}\" from \"{}\".",
-                  requested_specifier, location.filename
-                )
-              } else {
-                format!("Cannot resolve module \"{}\".", requested_specifier)
-              };
-              custom_error("NotFound", message)
+      let maybe_source_file = if let Some(load) = maybe_load {
+        load.await
+      } else {
+        file_fetcher
+          .fetch(&requested_specifier, &mut permissions)
+          .await
+      };
+      let source_file = maybe_source_file.map_err(|err| {
+        let err = if let Some(e) = err.downcast_ref::<std::io::Error>() {
+          if e.kind() == std::io::ErrorKind::NotFound {
+            let message = if let Some(location) = &maybe_location {
+              format!(
+                "Cannot resolve module \"{}\" from \"{}\".",
+                requested_specifier, location.filename
+              )
             } else {
-              err
-            }
+              format!("Cannot resolve module \"{}\".", requested_specifier)
+            };
+            custom_error("NotFound", message)
           } else {
             err
-          };
-          if let Some(location) = maybe_location {
-            // Injected modules (like test and eval) come with locations, but
-            // they are confusing to the user to print out the location because
-            // they cannot actually get to the source code that is quoted, as
-            // it only exists in the runtime memory of Deno.
-            if !location.filename.contains("$deno$") {
-              (
-                requested_specifier.clone(),
+          }
+        } else {
+          err
+        };
+        if let Some(location) = maybe_location {
+          // Injected modules (like test and eval) come with locations, but
+          // they are confusing to the user to print out the location because
+          // they cannot actually get to the source code that is quoted, as
+          // it only exists in the runtime memory of Deno.
+          if !location.filename.contains("$deno$") {
+            let err = match err.downcast_ref::<NotCachedError>() {
+              Some(not_cached) => HandlerError::NotCachedWithLocation(
+                not_cached.clone(),
+                location,
+              ),
+              None => {
                 HandlerError::FetchErrorWithLocation(err.to_string(), location)
-                  .into(),
-              )
-            } else {
-              (requested_specifier.clone(), err)
-            }
+              }
+            };
+            (requested_specifier.clone(), err.into())
           } else {
             (requested_specifier.clone(), err)
           }
-        })?;
+        } else {
+          (requested_specifier.clone(), err)
+        }
+      })?;
       let url = &source_file.specifier;
       let is_remote = !(url.scheme() == "file"
         || url.scheme() == "data"
@@ -354,14 +388,22 @@ impl SpecifierHandler for FetchHandler {
         }
       };
 
+      let redirect_chain = if is_remote && *url != requested_specifier {
+        file_fetcher.get_redirect_chain(&requested_specifier)
+      } else {
+        Vec::new()
+      };
+
       Ok(CachedModule {
         is_remote,
         maybe_dependencies: None,
         maybe_emit,
         maybe_emit_path,
+        maybe_raw_source: source_file.maybe_raw_source,
         maybe_types: source_file.maybe_types,
         maybe_version,
         media_type: source_file.media_type,
+        redirect_chain,
         requested_specifier,
         source: source_file.source,
         source_path: source_file.local,
@@ -371,19 +413,10 @@ impl SpecifierHandler for FetchHandler {
     .boxed()
   }
 
-  fn get_tsbuildinfo(
-    &self,
-    specifier: &ModuleSpecifier,
-  ) -> Result<Option<String>, AnyError> {
-    let filename = self
-      .disk_cache
-      .get_cache_filename_with_extension(specifier, "buildinfo");
-    if let Some(filename) = filename {
-      if let Ok(tsbuildinfo) = self.disk_cache.get(&filename) {
-        Ok(Some(String::from_utf8(tsbuildinfo)?))
-      } else {
-        Ok(None)
-      }
+  fn get_tsbuildinfo(&self, key: &str) -> Result<Option<String>, AnyError> {
+    let filename = get_tsbuildinfo_filename(key);
+    if let Ok(tsbuildinfo) = self.disk_cache.get(&filename) {
+      Ok(Some(String::from_utf8(tsbuildinfo)?))
     } else {
       Ok(None)
     }
@@ -391,13 +424,10 @@ impl SpecifierHandler for FetchHandler {
 
   fn set_tsbuildinfo(
     &mut self,
-    specifier: &ModuleSpecifier,
+    key: &str,
     tsbuildinfo: String,
   ) -> Result<(), AnyError> {
-    let filename = self
-      .disk_cache
-      .get_cache_filename_with_extension(specifier, "buildinfo")
-      .unwrap();
+    let filename = get_tsbuildinfo_filename(key);
     debug!("set_tsbuildinfo - filename {:?}", filename);
     self
       .disk_cache
@@ -520,10 +550,7 @@ impl SpecifierHandler for MemoryHandler {
     Box::pin(future::ready(result))
   }
 
-  fn get_tsbuildinfo(
-    &self,
-    _specifier: &ModuleSpecifier,
-  ) -> Result<Option<String>, AnyError> {
+  fn get_tsbuildinfo(&self, _key: &str) -> Result<Option<String>, AnyError> {
     Ok(None)
   }
 
@@ -545,7 +572,7 @@ impl SpecifierHandler for MemoryHandler {
 
   fn set_tsbuildinfo(
     &mut self,
-    _specifier: &ModuleSpecifier,
+    _key: &str,
     _tsbuildinfo: String,
   ) -> Result<(), AnyError> {
     Ok(())
@@ -571,10 +598,14 @@ impl SpecifierHandler for MemoryHandler {
 #[cfg(test)]
 pub mod tests {
   use super::*;
+  use crate::deno_dir::DenoDir;
   use crate::file_fetcher::CacheSetting;
+  use crate::file_fetcher::File;
   use crate::http_cache::HttpCache;
+  use crate::module_hooks::LoadFuture;
   use deno_core::resolve_url_or_path;
   use deno_runtime::deno_web::BlobUrlStore;
+  use std::env;
   use tempfile::TempDir;
 
   macro_rules! map (
@@ -599,6 +630,7 @@ pub mod tests {
       CacheSetting::Use,
       true,
       None,
+      None,
       BlobUrlStore::default(),
     )
     .expect("could not setup");
@@ -609,11 +641,50 @@ pub mod tests {
       root_permissions: Permissions::allow_all(),
       dynamic_permissions: Permissions::default(),
       file_fetcher,
+      maybe_module_hooks: None,
     };
 
     (temp_dir, fetch_handler)
   }
 
+  struct DatabaseHooks;
+
+  impl ModuleHooks for DatabaseHooks {
+    fn load(
+      &self,
+      specifier: &ModuleSpecifier,
+      _is_dynamic: bool,
+    ) -> Option<LoadFuture> {
+      if specifier.scheme() != "db" {
+        return None;
+      }
+      let file = File {
+        local: PathBuf::new(),
+        maybe_types: None,
+        media_type: MediaType::TypeScript,
+        source: "export const a: string = \"a\";\n".to_string(),
+        maybe_raw_source: None,
+        specifier: specifier.clone(),
+      };
+      Some(future::ready(Ok(file)).boxed())
+    }
+  }
+
+  #[tokio::test]
+  async fn test_fetch_handler_module_hooks() {
+    let (_, mut file_fetcher) = setup();
+    file_fetcher.maybe_module_hooks = Some(Arc::new(DatabaseHooks));
+    let specifier = resolve_url_or_path("db:modules/a.ts").unwrap();
+    let cached_module: CachedModule = file_fetcher
+      .fetch(specifier.clone(), None, false)
+      .await
+      .unwrap();
+    assert!(cached_module.is_remote);
+    assert_eq!(cached_module.media_type, MediaType::TypeScript);
+    assert_eq!(cached_module.source, "export const a: string = \"a\";\n");
+    assert_eq!(cached_module.specifier, specifier);
+  }
+
   #[tokio::test]
   async fn test_fetch_handler_fetch() {
     let _http_server_guard = test_util::http_server();
@@ -25,6 +25,7 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_runtime::deno_web::BlobUrlStore;
+use deno_runtime::ops::os::ExitCode;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsOptions;
 use deno_runtime::worker::MainWorker;
@@ -193,6 +194,7 @@ fn metadata_to_flags(metadata: &Metadata) -> Flags {
     seed: metadata.seed,
     location: metadata.location.clone(),
     allow_env: permissions.allow_env,
+    allow_ffi: permissions.allow_ffi,
     allow_hrtime: permissions.allow_hrtime,
     allow_net: permissions.allow_net,
     allow_plugin: permissions.allow_plugin,
@@ -227,12 +229,14 @@ pub async fn run(
       .collect::<Vec<_>>(),
   );
 
+  let exit_code = ExitCode::default();
   let options = WorkerOptions {
     apply_source_maps: false,
     args: metadata.argv,
     debug_flag: metadata.log_level.map_or(false, |l| l == log::Level::Debug),
     user_agent: version::get_user_agent(),
     unstable: metadata.unstable,
+    op_latency_metrics: false,
     ca_data: metadata.ca_data,
     seed: metadata.seed,
     js_error_create_fn: None,
@@ -248,6 +252,8 @@ pub async fn run(
     origin_storage_dir: None,
     blob_url_store,
     broadcast_channel,
+    shared_array_buffer_store: None,
+    exit_code: exit_code.clone(),
   };
   let mut worker =
     MainWorker::from_options(main_module.clone(), permissions, &options);
@@ -263,16 +269,10 @@ pub async fn run(
   }
   worker.bootstrap(&options);
   worker.execute_module(&main_module).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('load'))",
-  )?;
-  worker.run_event_loop(true).await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'))",
-  )?;
-  std::process::exit(0);
+  worker.dispatch_load_event(&located_script_name!())?;
+  worker.run_event_loop_until_unload(true).await?;
+  worker.dispatch_unload_event(&located_script_name!())?;
+  std::process::exit(exit_code.get());
 }
 
 fn get_error_class_name(e: &AnyError) -> &'static str {
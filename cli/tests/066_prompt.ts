@@ -19,3 +19,7 @@ alert();
 console.log("The end of test");
 const eof = prompt("What is EOF?");
 console.log(`Your answer is ${JSON.stringify(eof)}`);
+const eofDefault = prompt("What is EOF?", "Jane Doe");
+console.log(`Your answer is ${JSON.stringify(eofDefault)}`);
+const eofConfirm = confirm("Is this EOF?");
+console.log(`Your answer is ${eofConfirm}`);
@@ -0,0 +1,12 @@
+let count = 0;
+window.onbeforeunload = (e) => {
+  console.log("beforeunload", e.cancelable);
+  if (count++ < 2) {
+    // Keeps the process alive for another turn of the event loop.
+    e.preventDefault();
+    setTimeout(() => console.log("timeout", count), 0);
+  }
+};
+window.onunload = () => {
+  console.log("unload");
+};
@@ -0,0 +1,9 @@
+addEventListener("beforeunload", (e) => {
+  console.log("beforeunload", e.cancelable);
+  e.preventDefault();
+});
+addEventListener("unload", () => {
+  console.log("unload");
+});
+Deno.drain({ deadline: 60_000 });
+setTimeout(() => console.log("drained"), 10);
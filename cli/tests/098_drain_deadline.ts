@@ -0,0 +1,6 @@
+addEventListener("unload", () => {
+  console.log("unload");
+});
+// Never finishes on its own.
+setInterval(() => {}, 1000);
+Deno.drain({ deadline: 100, code: 3 });
@@ -0,0 +1,7 @@
+addEventListener("unload", () => {
+  console.log("unload", Deno.exitCode);
+  Deno.exitCode = 42;
+});
+console.log("initial", Deno.exitCode);
+Deno.exitCode = 3;
+setTimeout(() => console.log("cleanup", Deno.exitCode), 10);
@@ -0,0 +1,2 @@
+Deno.exitCode = 5;
+Deno.exit();
@@ -0,0 +1 @@
+export * from "http://localhost:4545/cli/tests/cached_only/missing_b.ts";
@@ -0,0 +1,2 @@
+import "http://localhost:4545/cli/tests/cached_only/missing_a.ts";
+import "./dep.ts";
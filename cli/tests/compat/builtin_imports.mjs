@@ -0,0 +1,4 @@
+import * as fs from "fs";
+import * as path from "node:path";
+
+console.log(fs.name, path.name, process.versions.node);
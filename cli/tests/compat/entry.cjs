@@ -0,0 +1 @@
+console.log("not loaded by the stand-in require");
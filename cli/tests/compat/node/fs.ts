@@ -0,0 +1,2 @@
+// A stand-in for the `fs` polyfill of `std/node`.
+export const name = "fs";
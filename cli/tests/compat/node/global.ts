@@ -0,0 +1,5 @@
+// A stand-in for the globals module of `std/node`.
+Object.defineProperty(globalThis, "process", {
+  value: { versions: { node: "16.0.0" } },
+  configurable: true,
+});
@@ -0,0 +1,8 @@
+// A stand-in for the `module` polyfill of `std/node`, whose require only
+// reports what it is asked to load.
+export function createRequire(main: string) {
+  return (specifier: string) => {
+    const name = specifier.split("/").pop();
+    console.log(`require ${name} relative to itself: ${main === specifier}`);
+  };
+}
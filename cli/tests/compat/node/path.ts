@@ -0,0 +1,2 @@
+// A stand-in for the `path` polyfill of `std/node`.
+export const name = "path";
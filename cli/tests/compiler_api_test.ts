@@ -199,6 +199,31 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "Deno.emit() - source maps of evaluated emits are applied",
+  async fn() {
+    const { files } = await Deno.emit("/error.ts", {
+      check: false,
+      sources: {
+        "/error.ts":
+          `type Message = string;\n\nfunction fail(message: Message): never {\n  throw new Error(message);\n}\n\nfail("boom");\n`,
+      },
+    });
+    const fileName = Object.keys(files).find((key) =>
+      key.endsWith("/error.ts.js")
+    );
+    assert(fileName);
+    let stack = "";
+    try {
+      (0, eval)(`${files[fileName]}\n//# sourceURL=${fileName}`);
+    } catch (err) {
+      stack = err.stack;
+    }
+    assertStringIncludes(stack, "Error: boom");
+    assertStringIncludes(stack, "/error.ts:4:");
+  },
+});
+
 Deno.test({
   name: "Deno.emit() - no check - config effects emit",
   async fn() {
@@ -291,6 +316,54 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "Deno.emit() - bundle as module script - splitting",
+  async fn() {
+    const { diagnostics, files } = await Deno.emit(
+      "/foo.ts",
+      {
+        bundle: "module",
+        splitting: true,
+        sources: {
+          "/foo.ts":
+            `export const foo = await import("./bar.ts");\nexport * from "./baz.ts";\n`,
+          "/bar.ts": `export * from "./baz.ts";\nexport const bar = "bar";\n`,
+          "/baz.ts": `export const baz = "baz";\n`,
+        },
+      },
+    );
+    assertEquals(diagnostics.length, 0);
+    assertEquals(
+      Object.keys(files).sort(),
+      [
+        "deno:///bundle.js",
+        "deno:///bundle.js.map",
+        "deno:///chunk-1.js",
+        "deno:///chunk-1.js.map",
+      ].sort(),
+    );
+    assert(files["deno:///bundle.js"].includes(`import("./chunk-1.js")`));
+    assert(files["deno:///bundle.js"].includes(`= "baz"`));
+    assert(files["deno:///chunk-1.js"].includes(`= "bar"`));
+    assert(files["deno:///chunk-1.js"].includes(`= "baz"`));
+  },
+});
+
+Deno.test({
+  name: "Deno.emit() - splitting requires a module bundle",
+  async fn() {
+    await assertThrowsAsync(async () => {
+      await Deno.emit("/foo.ts", {
+        bundle: "classic",
+        splitting: true,
+        sources: {
+          "/foo.ts": `export const foo = "foo";\n`,
+        },
+      });
+    });
+  },
+});
+
 Deno.test({
   name: "Deno.emit() - generates diagnostics",
   async fn() {
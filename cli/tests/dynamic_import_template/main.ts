@@ -0,0 +1,6 @@
+for (const name of ["a", "b"]) {
+  const plugin = await import(`./plugins/${name}.ts`);
+  console.log(plugin.name);
+}
+const { name } = await import(`./plugins/${"c"}/mod.ts`);
+console.log(name);
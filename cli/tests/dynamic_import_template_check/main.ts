@@ -0,0 +1,3 @@
+const name = Deno.args[0];
+const plugin = await import(`./plugins/${name}.ts`);
+console.log(plugin.name);
@@ -0,0 +1 @@
+export const name: string = 1;
@@ -0,0 +1,3 @@
+const format = new Intl.NumberFormat("de-DE");
+console.log(format.resolvedOptions().locale);
+console.log(format.format(1234567.891));
@@ -0,0 +1,3 @@
+import * as mod1 from "./subdir/mod1.ts" assert { type: "json" };
+
+console.log(mod1);
@@ -0,0 +1,3 @@
+import styles from "./subdir/config.json" assert { type: "css" };
+
+console.log(styles);
@@ -0,0 +1,10 @@
+import { a } from "data:text/javascript,export const a = 'a';";
+
+const b = await import("data:text/javascript,export default 'b';");
+const c = await import(
+  "data:application/javascript;base64,ZXhwb3J0IGNvbnN0IGMgPSAiYyI7Cg=="
+);
+
+console.log(a);
+console.log(b.default);
+console.log(c.c);
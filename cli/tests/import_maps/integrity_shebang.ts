@@ -0,0 +1,2 @@
+#!/usr/bin/env -S deno run
+console.log("hello from a shebang");
@@ -0,0 +1,2 @@
+globalThis.remoteValue = 42;
+setInterval(() => {}, 1000);
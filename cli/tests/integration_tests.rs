@@ -399,6 +399,85 @@ mod integration {
     remove_dir_all(deno_dir.path()).unwrap();
   }
 
+  fn compat_cmd(script: &str) -> std::process::Output {
+    util::deno_cmd()
+      .current_dir(util::tests_path())
+      .env(
+        "DENO_NODE_COMPAT_URL",
+        "http://localhost:4545/cli/tests/compat/node/",
+      )
+      .arg("run")
+      .arg("--reload")
+      .arg("--compat")
+      .arg(script)
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap()
+  }
+
+  #[test]
+  fn compat_builtin_imports() {
+    let _g = util::http_server();
+    let output = compat_cmd("compat/builtin_imports.mjs");
+    assert!(output.status.success());
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(stdout, "fs path 16.0.0\n");
+  }
+
+  #[test]
+  fn compat_cjs_entry_point() {
+    let _g = util::http_server();
+    let output = compat_cmd("compat/entry.cjs");
+    assert!(output.status.success());
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(stdout, "require entry.cjs relative to itself: true\n");
+  }
+
+  #[test]
+  fn prof_on_exit() {
+    let prof_dir = TempDir::new().expect("tempdir fail");
+    let output = util::deno_cmd()
+      .current_dir(util::tests_path())
+      .arg("run")
+      .arg(format!("--prof={}", prof_dir.path().display()))
+      .arg("profiler/exit.ts")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(stdout, "true\n");
+    let profiles: Vec<_> = std::fs::read_dir(prof_dir.path())
+      .unwrap()
+      .map(|entry| entry.unwrap().path())
+      .collect();
+    assert_eq!(profiles.len(), 1);
+    let profile = std::fs::read_to_string(&profiles[0]).unwrap();
+    let profile: serde_json::Value = serde_json::from_str(&profile).unwrap();
+    assert!(!profile["nodes"].as_array().unwrap().is_empty());
+  }
+
+  #[test]
+  fn profiler_start_without_stop() {
+    let output = util::deno_cmd()
+      .current_dir(util::tests_path())
+      .arg("run")
+      .arg("--unstable")
+      .arg("profiler/start_without_stop.ts")
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    let stdout = std::str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(stdout, "started\n");
+  }
+
   #[test]
   fn cache_test() {
     let _g = util::http_server();
@@ -1961,6 +2040,36 @@ mod integration {
   mod repl {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn pty_bracketed_paste() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        // The first line is a complete expression on its own, so it would be
+        // evaluated before the second one if they were typed.
+        master
+          .write_all(b"\x1b[200~[1, 2, 3]\n  .map((n) => n * 2)\x1b[201~\n")
+          .unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("[ 2, 4, 6 ]"));
+        assert!(!output.contains("Uncaught"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
     #[cfg(unix)]
     #[test]
     fn pty_multiline() {
@@ -2114,6 +2223,235 @@ mod integration {
       }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn pty_complete_fuzzy() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"let zebraQuagga = 6 * 7;\n").unwrap();
+        master.write_all(b"zqg\t\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("> zebraQuagga"));
+        assert!(output.contains("42"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    /// Runs the REPL in a terminal, with its history in `history_path`, and
+    /// types `input` into it.
+    #[cfg(unix)]
+    fn run_pty_repl_with_history(
+      input: &[u8],
+      history_path: &std::path::Path,
+      history_size: Option<&str>,
+    ) {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(input).unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+        assert!(!output.contains("Uncaught"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        std::env::set_var("DENO_REPL_HISTORY", history_path);
+        match history_size {
+          Some(size) => std::env::set_var("DENO_REPL_HISTORY_SIZE", size),
+          None => std::env::remove_var("DENO_REPL_HISTORY_SIZE"),
+        }
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_history_file_from_env() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let history_path = temp_dir.path().join("history").join("repl.txt");
+      run_pty_repl_with_history(b"1 + 1\n'two'\n3\n", &history_path, Some("2"));
+      let history = std::fs::read_to_string(&history_path).unwrap();
+      assert!(!history.contains("1 + 1"));
+      assert!(history.contains("'two'"));
+      assert!(history.contains('3'));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_history_file_multiline_entry() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let history_path = temp_dir.path().join("repl.txt");
+      run_pty_repl_with_history(
+        b"function f() {\nreturn 1;\n}\n",
+        &history_path,
+        None,
+      );
+      let history = std::fs::read_to_string(&history_path).unwrap();
+      assert!(history.contains("function f() {\\nreturn 1;\\n}\n"));
+
+      // The entry is loaded back as a whole and saved again unchanged.
+      run_pty_repl_with_history(b"2\n", &history_path, None);
+      let history = std::fs::read_to_string(&history_path).unwrap();
+      assert!(history.ends_with("function f() {\\nreturn 1;\\n}\n2\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_reverse_search_history() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"'needle' + 1\n").unwrap();
+        master.write_all(b"2\n").unwrap();
+        // Ctrl+R, then accept the found entry.
+        master.write_all(b"\x12needle\r").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("(reverse-i-search)`needle'"));
+        assert_eq!(output.matches("\"needle1\"").count(), 2);
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_complete_with_hints() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master
+          .write_all(b"function addOne(x) { return x + 1; }\n")
+          .unwrap();
+        master.write_all(b"globalThis.addends = [1, 2];\n").unwrap();
+        master.write_all(b"add\t\t\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("addOne(x)"));
+        assert!(output.contains("addends: Array"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_prompt_from_env() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"function f() {\n").unwrap();
+        master.write_all(b"return 1;\n").unwrap();
+        master.write_all(b"}\n").unwrap();
+        master.write_all(b"f()\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("[1]> function f() {"));
+        assert!(output.contains("[2]... return 1;"));
+        assert!(output.contains("[3]... }"));
+        assert!(output.contains("[4]> f()"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        std::env::set_var("DENO_REPL_PROMPT", "[{line}]> ");
+        std::env::set_var("DENO_REPL_CONTINUATION_PROMPT", "[{line}]... ");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_permission_prompt() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master
+          .write_all(b"await Deno.permissions.revoke({ name: 'env' });\n")
+          .unwrap();
+        master
+          .write_all(
+            b"(await Deno.permissions.request({ name: 'env' })).state\n",
+          )
+          .unwrap();
+        master.write_all(b"a\n").unwrap();
+        master
+          .write_all(b"await Deno.permissions.revoke({ name: 'env' });\n")
+          .unwrap();
+        master
+          .write_all(
+            b"(await Deno.permissions.request({ name: 'env' })).state\n",
+          )
+          .unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("Deno requests env access."));
+        assert_eq!(output.matches("\"granted\"").count(), 2);
+        // The answer is remembered, as the second request would otherwise
+        // take `close();` as its answer.
+        assert!(!output.contains("Unrecognized option"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
     #[cfg(unix)]
     #[test]
     fn pty_ignore_symbols() {
@@ -2141,82 +2479,783 @@ mod integration {
       }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn pty_complete_import_specifier() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master
+          .write_all(b"import { printHello } from \"./subdir/print_he\t\";\n")
+          .unwrap();
+        master.write_all(b"printHello();\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("\"./subdir/print_hello.ts\""));
+        assert!(output.contains("Hello"));
+        assert!(!output.contains("Uncaught"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        std::env::set_current_dir(util::tests_path()).unwrap();
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_complete_string_path() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"\"./subdir/form_url\t\"\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("\"./subdir/form_urlencoded.txt\""));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        std::env::set_current_dir(util::tests_path()).unwrap();
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_eager_eval_preview() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        // The line is transpiled before it is previewed, and the preview is
+        // shown once no key has been typed for a while.
+        master.write_all(b"(6 as number) * 7").unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        // Discarding the line means it is never evaluated, so the result can
+        // only come from the preview.
+        master.write_all(b"\x15close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("42"));
+        assert!(!output.contains("Uncaught"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_pager() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master
+          .write_all(
+            b"Array.from({ length: 20 }, (_, i) => \"-\".repeat(60) + \"item\" + i)\n",
+          )
+          .unwrap();
+        // Wait for the pager before answering its prompt.
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        master.write_all(b"q\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("item0"));
+        assert!(output.contains("-- 4/20 lines"));
+        assert!(!output.contains("item19"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::set_var("NO_COLOR", "1");
+        std::env::remove_var("DENO_REPL_PAGER");
+        std::env::remove_var("PAGER");
+        let size = libc::winsize {
+          ws_row: 5,
+          ws_col: 80,
+          ws_xpixel: 0,
+          ws_ypixel: 0,
+        };
+        unsafe {
+          libc::ioctl(libc::STDOUT_FILENO, libc::TIOCSWINSZ, &size);
+        }
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_highlight_matching_brackets() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"[(1 + 2)]\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        // Once the closing bracket is typed, it and the bracket it pairs
+        // with are painted intense blue.
+        assert!(output.contains("\x1b[38;5;12m[\x1b[0m"));
+        assert!(output.contains("\x1b[38;5;12m]\x1b[0m"));
+        assert!(output.contains("\x1b[38;5;12m(\x1b[0m"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("DENO_REPL_THEME");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pty_theme_from_env() {
+      use std::io::Read;
+      use util::pty::fork::*;
+      let deno_exe = util::deno_exe_path();
+      let fork = Fork::from_ptmx().unwrap();
+      if let Ok(mut master) = fork.is_parent() {
+        master.write_all(b"typeof 123\n").unwrap();
+        master.write_all(b"close();\n").unwrap();
+
+        let mut output = String::new();
+        master.read_to_string(&mut output).unwrap();
+
+        assert!(output.contains("\x1b[35mtypeof\x1b[0m"));
+        assert!(!output.contains("\x1b[36mtypeof\x1b[0m"));
+        assert!(!output.contains("\x1b[33m123\x1b[0m"));
+
+        fork.wait().unwrap();
+      } else {
+        std::env::remove_var("NO_COLOR");
+        std::env::set_var("DENO_REPL_THEME", "keyword=magenta,number=none");
+        let err = exec::Command::new(deno_exe).arg("repl").exec();
+        println!("err {}", err);
+        unreachable!()
+      }
+    }
+
+    #[test]
+    fn console_log() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["console.log('hello')", "'world'"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("hello\nundefined\n\"world\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn quiet_flag() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --quiet",
+        Some(vec!["1 + 1"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert_eq!(out, "2\n");
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn unload_listener_runs_on_exit() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --quiet",
+        Some(vec![
+          "addEventListener('unload', () => console.log('cleaned up'));",
+          "close();",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert_eq!(out, "undefined\ncleaned up\n");
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn object_literal() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["{}", "{ foo: 'bar' }"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("{}\n{ foo: \"bar\" }\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn block_expression() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["{};", "{\"\"}"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n\"\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn await_resolve() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["await Promise.resolve('done')"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("\"done\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn await_timeout() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["await new Promise((r) => setTimeout(r, 0, 'done'))"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("\"done\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn promise_result_is_awaited() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![
+          "new Promise((r) => setTimeout(r, 0, 'done'))",
+          "Promise.reject(new Error('boom'))",
+          "_error.message",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("\"done\"\nUncaught Error: boom"));
+      assert!(out.ends_with("\"boom\"\n"));
+      assert!(!out.contains("Promise {"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn promise_result_await_timeout() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["new Promise(() => {})", "Promise.resolve(1)"]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          ("DENO_REPL_AWAIT_TIMEOUT".to_owned(), "100".to_owned()),
+        ]),
+        false,
+      );
+      assert!(out.contains("Promise { <pending> }\n1\n"));
+      assert!(err.is_empty());
+
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["Promise.resolve(1)"]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          ("DENO_REPL_AWAIT_TIMEOUT".to_owned(), "0".to_owned()),
+        ]),
+        false,
+      );
+      assert!(out.ends_with("Promise { 1 }\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn let_redeclaration() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["let foo = 0;", "foo", "let foo = 1;", "foo"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n0\nundefined\n1\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_flag_valid_input() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval globalThis.t=10;",
+        Some(vec!["t * 500;"]),
+        None,
+        false,
+      );
+      assert!(out.contains("5000"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_flag_runs_after_prelude() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval 1+1",
+        Some(vec!["_"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("2\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_flag_parse_error() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval const_t=10;)",
+        Some(vec!["1 + 2"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("error in --eval flag. parse error: "));
+      assert!(out.ends_with("3\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn eval_flag_runtime_error() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --eval notDefined()",
+        Some(vec!["1 + 2"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("error in --eval flag. Uncaught ReferenceError"));
+      assert!(out.ends_with("3\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn help_command() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".help"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains(".exit     Exit the REPL"));
+      assert!(out.contains(".save     Save all evaluated input"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn exit_command() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["1 + 1", ".exit", "'unreachable'"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("2\n"));
+      assert!(!out.contains("unreachable"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_commands() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let session_path = temp_dir.path().join("session.js");
+      let session_path = session_path.to_str().unwrap();
+
+      let save_command = format!(".save {}", session_path);
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["const answer = 42;", &save_command]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains(&format!("Session saved to: {}", session_path)));
+      assert!(err.is_empty());
+      assert_eq!(
+        std::fs::read_to_string(session_path).unwrap(),
+        "const answer = 42;\n"
+      );
+
+      let load_command = format!(".load {}", session_path);
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![&load_command, "answer"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n42\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn load_command_missing_file() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".load ./does_not_exist.js", "1 + 2"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Failed to load './does_not_exist.js'"));
+      assert!(out.ends_with("3\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn import_map() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --import-map=import_maps/import_map.json",
+        Some(vec!["await import('moment');"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Hello from remapped moment!"));
+      assert!(!out.contains("Uncaught"));
+      assert!(!err.contains("error"));
+    }
+
+    #[test]
+    fn piped_input_is_not_saved_to_history() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let history_path = temp_dir.path().join("repl.txt");
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["1 + 1"]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          (
+            "DENO_REPL_HISTORY".to_owned(),
+            history_path.to_str().unwrap().to_owned(),
+          ),
+        ]),
+        false,
+      );
+      assert!(out.ends_with("2\n"));
+      assert!(err.is_empty());
+      assert!(!history_path.exists());
+    }
+
+    #[test]
+    fn piped_multiline_statement() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![
+          "function add(a, b) {",
+          "  return a + b;",
+          "}",
+          "add(1, 2)",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("undefined\n3\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn piped_last_evaluation_threw() {
+      let (out, err) = util::run_and_collect_output(
+        false,
+        "repl",
+        Some(vec!["1 + 1", "throw new Error('boom')"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("2\nUncaught Error: boom"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn piped_output_is_not_paged() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![
+          "Array.from({ length: 50 }, (_, i) => \"-\".repeat(60) + \"item\" + i)",
+        ]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          ("DENO_REPL_PAGER".to_owned(), "false".to_owned()),
+        ]),
+        false,
+      );
+      assert!(out.contains("item0"));
+      assert!(out.contains("item49"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn editor_command() {
+      let editor = format!(
+        "{} run --quiet --allow-write repl_editor.ts",
+        util::deno_exe_path().to_str().unwrap()
+      );
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec![".editor", "edited"]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          ("EDITOR".to_owned(), editor),
+        ]),
+        false,
+      );
+      assert!(out.ends_with("42\n42\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn init_flag() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --init repl_init.ts --eval globalThis.name='Deno'",
+        Some(vec!["greet(name)"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("\"Hello, Deno!\"\n"));
+      assert!(err.is_empty());
+    }
+
     #[test]
-    fn console_log() {
+    fn init_file_in_deno_dir() {
+      let deno_dir = TempDir::new().expect("tempdir fail");
+      std::fs::copy(
+        util::tests_path().join("repl_init.ts"),
+        deno_dir.path().join("repl_init.ts"),
+      )
+      .unwrap();
       let (out, err) = util::run_and_collect_output(
         true,
         "repl",
-        Some(vec!["console.log('hello')", "'world'"]),
+        Some(vec!["greet('world')"]),
+        Some(vec![
+          ("NO_COLOR".to_owned(), "1".to_owned()),
+          (
+            "DENO_DIR".to_owned(),
+            deno_dir.path().to_str().unwrap().to_owned(),
+          ),
+        ]),
+        false,
+      );
+      assert!(out.ends_with("\"Hello, world!\"\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn init_flag_missing_file() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl --init does_not_exist.ts",
+        Some(vec!["1 + 2"]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("hello\nundefined\n\"world\"\n"));
+      assert!(out.contains("Failed to load init file does_not_exist.ts"));
+      assert!(out.ends_with("3\n"));
       assert!(err.is_empty());
     }
 
     #[test]
-    fn object_literal() {
+    fn reset_command() {
       let (out, err) = util::run_and_collect_output(
         true,
         "repl",
-        Some(vec!["{}", "{ foo: 'bar' }"]),
+        Some(vec![
+          "const a = 1;",
+          "globalThis.b = 2;",
+          ".reset",
+          "typeof a",
+          "typeof b",
+          "const a = 3;",
+          "a",
+        ]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("{}\n{ foo: \"bar\" }\n"));
+      assert!(out.ends_with("\"undefined\"\n\"undefined\"\nundefined\n3\n"));
       assert!(err.is_empty());
     }
 
     #[test]
-    fn block_expression() {
+    fn check_flag() {
       let (out, err) = util::run_and_collect_output(
         true,
-        "repl",
-        Some(vec!["{};", "{\"\"}"]),
+        "repl --check",
+        Some(vec![
+          "const a: number = 1;",
+          "const b: string = a;",
+          "function add(x: number, y: number) { return x + y; }",
+          "add(a, 2)",
+        ]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("undefined\n\"\"\n"));
+      assert!(out.contains(
+        "TS2322 [WARN]: Type 'number' is not assignable to type 'string'."
+      ));
+      assert_eq!(out.matches("[WARN]").count(), 1);
+      assert!(out.ends_with("undefined\nundefined\nundefined\n3\n"));
       assert!(err.is_empty());
     }
 
     #[test]
-    fn await_resolve() {
+    fn check_flag_skips_inputs_which_threw() {
       let (out, err) = util::run_and_collect_output(
         true,
-        "repl",
-        Some(vec!["await Promise.resolve('done')"]),
+        "repl --check",
+        Some(vec![
+          "let a: number = JSON.parse('{');",
+          "let a = 'a';",
+          "a.toUpperCase()",
+        ]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("\"done\"\n"));
+      assert!(!out.contains("[WARN]"));
+      assert!(out.ends_with("undefined\n\"A\"\n"));
       assert!(err.is_empty());
     }
 
     #[test]
-    fn await_timeout() {
+    fn jsx_flag() {
       let (out, err) = util::run_and_collect_output(
         true,
-        "repl",
-        Some(vec!["await new Promise((r) => setTimeout(r, 0, 'done'))"]),
+        "repl --jsx",
+        Some(vec![
+          "const React = { createElement: (tag, props, ...children) => ({ tag, props, children }) };",
+          "<div id=\"deno\">land</div>",
+        ]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("\"done\"\n"));
+      assert!(out.ends_with(
+        "{ tag: \"div\", props: { id: \"deno\" }, children: [ \"land\" ] }\n"
+      ));
       assert!(err.is_empty());
     }
 
     #[test]
-    fn let_redeclaration() {
+    fn jsx_flag_with_config_factories() {
       let (out, err) = util::run_and_collect_output(
         true,
+        "repl --jsx --config repl_jsx.tsconfig.json",
+        Some(vec![
+          "const Fragment = 'fragment';",
+          "const h = (tag, props, ...children) => [tag, children.length];",
+          "<><b /><i /></>",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("[ \"fragment\", 2 ]\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn jsx_without_flag() {
+      let (out, _) = util::run_and_collect_output(
+        false,
         "repl",
-        Some(vec!["let foo = 0;", "foo", "let foo = 1;", "foo"]),
+        Some(vec!["<div />"]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
         false,
       );
-      assert!(out.ends_with("undefined\n0\nundefined\n1\n"));
+      assert!(out.contains("parse error"));
+    }
+
+    #[test]
+    fn record_flag() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let record_file = temp_dir.path().join("session.ts");
+      let (out, err) = util::run_and_collect_output(
+        true,
+        &format!("repl --record {}", record_file.display()),
+        Some(vec!["const a = 1 + 1;", "a", ".clear", "[a, 'b']"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("[ 2, \"b\" ]\n"));
+      assert!(err.is_empty());
+      assert_eq!(
+        std::fs::read_to_string(&record_file).unwrap(),
+        "const a = 1 + 1;\n// undefined\na\n// 2\n// .clear\n[a, 'b']\n// [ 2, \"b\" ]\n"
+      );
+    }
+
+    #[test]
+    fn record_flag_with_check_flag() {
+      let temp_dir = TempDir::new().expect("tempdir fail");
+      let record_file = temp_dir.path().join("session.ts");
+      let (out, err) = util::run_and_collect_output(
+        true,
+        &format!("repl --check --record {}", record_file.display()),
+        Some(vec!["const b: string = 1;"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("TS2322 [WARN]"));
       assert!(err.is_empty());
+      // The diagnostics are part of the output of the input they are about.
+      let recorded = std::fs::read_to_string(&record_file).unwrap();
+      assert!(recorded.starts_with("const b: string = 1;\n// TS2322 [WARN]"));
+      assert!(recorded.ends_with("// undefined\n"));
     }
 
     #[test]
@@ -2248,6 +3287,60 @@ mod integration {
       assert!(err.is_empty());
     }
 
+    #[test]
+    fn typescript_error_stack() {
+      let (out, err) = util::run_and_collect_output(
+        false,
+        "repl",
+        Some(vec![
+          "type Message = string; const message: Message = \"boom\";",
+          "throw new Error(message);",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Uncaught Error: boom"));
+      assert!(out.contains("at $deno$repl2.ts:1:7"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn multiline_error_stack() {
+      let (out, err) = util::run_and_collect_output(
+        false,
+        "repl",
+        Some(vec![
+          "function fail(message: string): never {",
+          "  throw new Error(message);",
+          "}",
+          "fail(\"boom\")",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Uncaught Error: boom"));
+      assert!(out.contains("at fail ($deno$repl1.ts:2:9)"));
+      assert!(out.contains("at $deno$repl2.ts:1:1"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn object_literal_error_stack() {
+      let (out, err) = util::run_and_collect_output(
+        false,
+        "repl",
+        Some(vec![
+          "{ fail() { throw new Error(\"boom\"); } }",
+          "_.fail()",
+        ]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.contains("Uncaught Error: boom"));
+      assert!(out.contains("$deno$repl1.ts:1:18)"));
+      assert!(err.is_empty());
+    }
+
     #[test]
     fn typescript_declarations() {
       let (out, err) = util::run_and_collect_output(
@@ -2300,7 +3393,7 @@ mod integration {
     #[test]
     fn strict() {
       let (out, err) = util::run_and_collect_output(
-        true,
+        false,
         "repl",
         Some(vec![
           "let a = {};",
@@ -2344,7 +3437,6 @@ mod integration {
     }
 
     #[test]
-    #[ignore]
     fn multiline() {
       let (out, err) = util::run_and_collect_output(
         true,
@@ -2372,7 +3464,7 @@ mod integration {
     #[test]
     fn eval_unterminated() {
       let (out, err) = util::run_and_collect_output(
-        true,
+        false,
         "repl",
         Some(vec!["eval('{')"]),
         None,
@@ -2386,7 +3478,7 @@ mod integration {
     fn unpaired_braces() {
       for right_brace in &[")", "]", "}"] {
         let (out, err) = util::run_and_collect_output(
-          true,
+          false,
           "repl",
           Some(vec![right_brace]),
           None,
@@ -2400,7 +3492,7 @@ mod integration {
     #[test]
     fn reference_error() {
       let (out, err) = util::run_and_collect_output(
-        true,
+        false,
         "repl",
         Some(vec!["not_a_variable"]),
         None,
@@ -2432,7 +3524,7 @@ mod integration {
     fn syntax_error_jsx() {
       // JSX is not supported in the REPL
       let (out, err) = util::run_and_collect_output(
-        true,
+        false,
         "repl",
         Some(vec!["const element = <div />;"]),
         Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
@@ -2445,7 +3537,7 @@ mod integration {
     #[test]
     fn type_error() {
       let (out, err) = util::run_and_collect_output(
-        true,
+        false,
         "repl",
         Some(vec!["console()"]),
         None,
@@ -2516,6 +3608,34 @@ mod integration {
       assert!(err.is_empty());
     }
 
+    #[test]
+    fn save_numbered_eval_results() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["1", "'two'", "3", "[_1, _2, _3, _4]"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with("[ 3, \"two\", 1, undefined ]\n"));
+      assert!(err.is_empty());
+    }
+
+    #[test]
+    fn assign_numbered_eval_result() {
+      let (out, err) = util::run_and_collect_output(
+        true,
+        "repl",
+        Some(vec!["_2 = 'kept'", "1", "2", "_2"]),
+        Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+        false,
+      );
+      assert!(out.ends_with(
+        "Evaluation result 2 is no longer saved to _2.\n\"kept\"\n1\n2\n\"kept\"\n"
+      ));
+      assert!(err.is_empty());
+    }
+
     #[test]
     fn save_last_thrown() {
       let (out, err) = util::run_and_collect_output(
@@ -2654,6 +3774,23 @@ mod integration {
     exit_code: 1,
   });
 
+  itest!(json_import_assertion {
+    args: "run --quiet --reload json_import_assertion.ts",
+    output: "json_import_assertion.ts.out",
+  });
+
+  itest!(import_assertion_unsupported_type {
+    args: "run --quiet --reload import_assertion_unsupported_type.ts",
+    output: "import_assertion_unsupported_type.ts.out",
+    exit_code: 1,
+  });
+
+  itest!(import_assertion_type_mismatch {
+    args: "run --quiet --reload import_assertion_type_mismatch.ts",
+    output: "import_assertion_type_mismatch.ts.out",
+    exit_code: 1,
+  });
+
   itest!(_021_mjs_modules {
     args: "run --quiet --reload 021_mjs_modules.ts",
     output: "021_mjs_modules.ts.out",
@@ -2772,6 +3909,12 @@ mod integration {
       exit_code: 1,
     });
 
+    itest!(ops_sanitizer_trace {
+      args: "test --allow-read --trace-ops test/ops_sanitizer_trace.ts",
+      exit_code: 1,
+      output: "test/ops_sanitizer_trace.out",
+    });
+
     itest!(quiet {
       args: "test --quiet test/quiet_test.ts",
       exit_code: 0,
@@ -2900,6 +4043,25 @@ console.log("finish");
     output: "033_import_map.out",
   });
 
+  itest!(import_map_integrity {
+    args:
+      "run --quiet --reload --import-map=import_maps/import_map_integrity.json import_maps/integrity.ts",
+    output: "import_maps/integrity.ts.out",
+  });
+
+  itest!(import_map_integrity_shebang {
+    args:
+      "run --quiet --reload --import-map=import_maps/import_map_integrity_shebang.json import_maps/integrity_shebang.ts",
+    output: "import_maps/integrity_shebang.ts.out",
+  });
+
+  itest!(import_map_integrity_invalid {
+    args:
+      "run --quiet --reload --import-map=import_maps/import_map_integrity_invalid.json import_maps/integrity.ts",
+    output: "import_maps/integrity_invalid.out",
+    exit_code: 1,
+  });
+
   itest!(_033_import_map_remote {
     args:
       "run --quiet --reload --import-map=http://127.0.0.1:4545/cli/tests/import_maps/import_map_remote.json --unstable import_maps/test_remote.ts",
@@ -2920,6 +4082,12 @@ console.log("finish");
     http_server: true,
   });
 
+  itest!(cached_only_reports_all_missing {
+    args: "run --cached-only cached_only/main.ts",
+    output: "cached_only/main.out",
+    exit_code: 1,
+  });
+
   itest!(_036_import_map_fetch {
     args:
       "cache --quiet --reload --import-map=import_maps/import_map.json import_maps/test.ts",
@@ -3271,6 +4439,39 @@ console.log("finish");
     exit_code: 1,
   });
 
+  itest!(_096_beforeunload {
+    args: "run 096_beforeunload.ts",
+    output: "096_beforeunload.ts.out",
+  });
+
+  itest!(_097_drain {
+    args: "run --unstable 097_drain.ts",
+    output: "097_drain.ts.out",
+  });
+
+  itest!(_098_drain_deadline {
+    args: "run --unstable 098_drain_deadline.ts",
+    output: "098_drain_deadline.ts.out",
+    exit_code: 3,
+  });
+
+  itest!(_099_exit_code {
+    args: "run 099_exit_code.ts",
+    output: "099_exit_code.ts.out",
+    exit_code: 42,
+  });
+
+  itest!(_100_exit_code_on_exit {
+    args: "run 100_exit_code_on_exit.ts",
+    output: "100_exit_code_on_exit.ts.out",
+    exit_code: 5,
+  });
+
+  itest!(unhandled_rejection_prevented {
+    args: "run unhandled_rejection_prevented.ts",
+    output: "unhandled_rejection_prevented.ts.out",
+  });
+
   itest!(dynamic_import_permissions_remote_remote {
     args: "run --quiet --reload --allow-net=localhost:4545 dynamic_import/permissions_remote_remote.ts",
     output: "dynamic_import/permissions_remote_remote.ts.out",
@@ -3359,6 +4560,13 @@ console.log("finish");
     http_server: true,
   });
 
+  itest!(lock_check_err_redirect {
+    args: "run --lock=lock_check_err_redirect.json http://localhost:4546/cli/tests/subdir/print_hello.ts",
+    output: "lock_check_err_redirect.out",
+    exit_code: 10,
+    http_server: true,
+  });
+
   itest!(lock_check_err_with_bundle {
     args: "bundle --lock=lock_check_err_with_bundle.json http://127.0.0.1:4545/cli/tests/subdir/mod1.ts",
     output: "lock_check_err_with_bundle.out",
@@ -3461,6 +4669,12 @@ console.log("finish");
     output_str: Some("Check file:[WILDCARD]tests/subdir/empty.ts\n"),
   });
 
+  itest!(log_format_json {
+    args: "run --reload --log-format=json error_001.ts",
+    exit_code: 1,
+    output: "log_format_json.out",
+  });
+
   itest!(error_001 {
     args: "run --reload error_001.ts",
     exit_code: 1,
@@ -3768,6 +4982,20 @@ console.log("finish");
     http_server: true,
   });
 
+  itest!(no_check_remote {
+    args: "run --quiet --reload --no-check=remote no_check_remote.ts",
+    output: "no_check_remote.ts.out",
+    http_server: true,
+  });
+
+  itest!(no_check_remote_local_error {
+    args:
+      "run --quiet --reload --no-check=remote no_check_remote_local_error.ts",
+    output: "no_check_remote_local_error.ts.out",
+    exit_code: 1,
+    http_server: true,
+  });
+
   itest!(no_check_decorators {
     args: "run --quiet --reload --no-check no_check_decorators.ts",
     output: "no_check_decorators.ts.out",
@@ -3793,6 +5021,22 @@ console.log("finish");
     output: "lib_runtime_api.ts.out",
   });
 
+  itest!(op_latency_metrics {
+    args: "run --quiet --unstable --op-latency-metrics --allow-read op_latency_metrics.js",
+    output: "op_latency_metrics.js.out",
+  });
+
+  itest!(icu_data {
+    args: "run --quiet --icu-data=../../core/icudtl.dat icu_data.js",
+    output: "icu_data.js.out",
+  });
+
+  itest!(icu_data_invalid {
+    args: "run --icu-data=hello.txt 002_hello.ts",
+    output_str: Some("error: Invalid ICU data (ICU error code [WILDCARD])\n"),
+    exit_code: 1,
+  });
+
   itest!(seed_random {
     args: "run --seed=100 seed_random.js",
 
@@ -3871,6 +5115,13 @@ console.log("finish");
     output: "types.out",
   });
 
+  itest!(types_for {
+    args:
+      "types --quiet --reload --for http://localhost:4545/xTypeScriptTypes.js",
+    output: "types_for.out",
+    http_server: true,
+  });
+
   itest!(unbuffered_stderr {
     args: "run --reload unbuffered_stderr.ts",
     output: "unbuffered_stderr.ts.out",
@@ -4202,6 +5453,16 @@ console.log("finish");
     output: "import_data_url.ts.out",
   });
 
+  itest!(wasm_import {
+    args: "run --quiet --reload wasm_import/main.ts",
+    output: "wasm_import/main.ts.out",
+  });
+
+  itest!(import_data_url_javascript {
+    args: "run --quiet --reload import_data_url_javascript.ts",
+    output: "import_data_url_javascript.ts.out",
+  });
+
   itest!(import_dynamic_data_url {
     args: "run --quiet --reload import_dynamic_data_url.ts",
     output: "import_dynamic_data_url.ts.out",
@@ -4252,11 +5513,32 @@ console.log("finish");
     exit_code: 0,
   });
 
+  itest!(info_config {
+    args: "info --config config_extends/tsconfig.json",
+    output: "info_config.out",
+  });
+
   itest!(info_type_import {
     args: "info info_type_import.ts",
     output: "info_type_import.out",
   });
 
+  itest!(cache_without_template_imports {
+    args: "cache --quiet --reload dynamic_import_template_check/main.ts",
+    output_str: Some(""),
+  });
+
+  itest!(cache_template_imports {
+    args: "cache --quiet --reload --template-imports dynamic_import_template_check/main.ts",
+    output: "cache_template_imports.out",
+    exit_code: 1,
+  });
+
+  itest!(run_dynamic_import_template {
+    args: "run --quiet --reload --allow-read dynamic_import_template/main.ts",
+    output_str: Some("a\nb\nc\n"),
+  });
+
   itest!(ignore_require {
     args: "cache --reload --no-check ignore_require.js",
     output_str: Some(""),
@@ -5589,6 +6871,49 @@ console.log("finish");
       assert!(matching_endpoint.is_some());
       child.kill().unwrap();
     }
+
+    #[test]
+    fn inspector_repl_connect() {
+      let script = util::tests_path().join("inspector_repl_connect.js");
+      let mut child = util::deno_cmd()
+        .arg("run")
+        .arg(inspect_flag_with_unique_port("--inspect"))
+        .arg(script)
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+      let stderr = child.stderr.as_mut().unwrap();
+      let mut stderr_lines =
+        std::io::BufReader::new(stderr).lines().map(|r| r.unwrap());
+      let ws_url = extract_ws_url_from_stderr(&mut stderr_lines);
+
+      let mut repl = util::deno_cmd()
+        .arg("repl")
+        .arg("--connect")
+        .arg(ws_url.as_str())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+      repl
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(
+          b"remoteValue\nremoteValue = 'changed'\n'_1' in globalThis\n",
+        )
+        .unwrap();
+      let output = repl.wait_with_output().unwrap();
+      let out = String::from_utf8(output.stdout).unwrap();
+      assert!(out.contains("42"));
+      assert!(out.contains("\"changed\""));
+      // The prelude isn't evaluated in the connected process.
+      assert!(out.contains("false"));
+      assert!(!out.contains("close()"));
+      child.kill().unwrap();
+      child.wait().unwrap();
+    }
   }
 
   #[test]
@@ -128,6 +128,69 @@ fn lsp_init_tsconfig() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_import_map_watched_file_change() {
+  let temp_dir = TempDir::new().expect("could not create temp dir");
+  let mut params: lsp::InitializeParams =
+    serde_json::from_value(load_fixture("initialize_params.json")).unwrap();
+  let import_map_path = temp_dir.path().join("import-map.json");
+  fs::write(&import_map_path, r#"{ "imports": {} }"#).unwrap();
+  fs::write(temp_dir.path().join("b.ts"), "export const b = \"b\";\n").unwrap();
+
+  params.root_uri = Some(Url::from_file_path(temp_dir.path()).unwrap());
+  if let Some(Value::Object(mut map)) = params.initialization_options {
+    map.insert("importMap".to_string(), json!("import-map.json"));
+    params.initialization_options = Some(Value::Object(map));
+  }
+
+  let deno_exe = deno_exe_path();
+  let mut client = LspClient::new(&deno_exe).unwrap();
+  client
+    .write_request::<_, _, Value>("initialize", params)
+    .unwrap();
+
+  client.write_notification("initialized", json!({})).unwrap();
+  let uri = Url::from_file_path(temp_dir.path().join("a.ts")).unwrap();
+
+  let diagnostics = did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": uri,
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import { b } from \"b\";\n\nconsole.log(b);\n"
+      }
+    }),
+  );
+  let diagnostics = diagnostics.into_iter().flat_map(|x| x.diagnostics);
+  assert!(diagnostics.count() > 0);
+
+  fs::write(&import_map_path, r#"{ "imports": { "b": "./b.ts" } }"#).unwrap();
+  client
+    .write_notification(
+      "workspace/didChangeWatchedFiles",
+      json!({
+        "changes": [{
+          "uri": Url::from_file_path(&import_map_path).unwrap(),
+          "type": 2
+        }]
+      }),
+    )
+    .unwrap();
+  let mut diagnostics = vec![];
+  for _ in 0..3 {
+    let (method, response) = client
+      .read_notification::<lsp::PublishDiagnosticsParams>()
+      .unwrap();
+    assert_eq!(method, "textDocument/publishDiagnostics");
+    diagnostics.extend(response.unwrap().diagnostics);
+  }
+  assert_eq!(diagnostics, vec![]);
+
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_tsconfig_types() {
   let mut params: lsp::InitializeParams =
@@ -1591,17 +1654,27 @@ fn lsp_signature_help() {
       "signatures": [
         {
           "label": "add(a: number, b: number): number",
-          "documentation": "Adds two numbers.",
+          "documentation": {
+            "kind": "markdown",
+            "value": "Adds two numbers."
+          },
           "parameters": [
             {
               "label": "a: number",
-              "documentation": "This is a first number."
+              "documentation": {
+                "kind": "markdown",
+                "value": "This is a first number."
+              }
             },
             {
               "label": "b: number",
-              "documentation": "This is a second number."
+              "documentation": {
+                "kind": "markdown",
+                "value": "This is a second number."
+              }
             }
-          ]
+          ],
+          "activeParameter": 0
         }
       ],
       "activeSignature": 0,
@@ -1655,17 +1728,27 @@ fn lsp_signature_help() {
       "signatures": [
         {
           "label": "add(a: number, b: number): number",
-          "documentation": "Adds two numbers.",
+          "documentation": {
+            "kind": "markdown",
+            "value": "Adds two numbers."
+          },
           "parameters": [
             {
               "label": "a: number",
-              "documentation": "This is a first number."
+              "documentation": {
+                "kind": "markdown",
+                "value": "This is a first number."
+              }
             },
             {
               "label": "b: number",
-              "documentation": "This is a second number."
+              "documentation": {
+                "kind": "markdown",
+                "value": "This is a second number."
+              }
             }
-          ]
+          ],
+          "activeParameter": 1
         }
       ],
       "activeSignature": 0,
@@ -2280,6 +2363,53 @@ fn lsp_diagnostics_warn() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_execute_command_cache() {
+  let _g = http_server();
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import * as a from \"http://127.0.0.1:4545/cli/tests/x_deno_warning.js\";\n\nconsole.log(a)\n",
+      },
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "workspace/executeCommand",
+      json!({
+        "command": "deno.cache",
+        "arguments": [
+          ["http://127.0.0.1:4545/cli/tests/x_deno_warning.js"],
+          "file:///a/file.ts",
+        ],
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(maybe_res, Some(json!(true)));
+
+  let (method, _) = client.read_notification::<Value>().unwrap();
+  assert_eq!(method, "textDocument/publishDiagnostics");
+  let (method, _) = client.read_notification::<Value>().unwrap();
+  assert_eq!(method, "textDocument/publishDiagnostics");
+  let (method, maybe_params) = client
+    .read_notification::<lsp::PublishDiagnosticsParams>()
+    .unwrap();
+  assert_eq!(method, "textDocument/publishDiagnostics");
+  let params = maybe_params.unwrap();
+  assert_eq!(params.diagnostics.len(), 1);
+  assert_eq!(
+    params.diagnostics[0].code,
+    Some(lsp::NumberOrString::String("deno-warn".to_string()))
+  );
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_diagnostics_deno_types() {
   let mut client = init("initialize_params.json");
@@ -0,0 +1,9 @@
+import config from "./subdir/config.json" assert { type: "json" };
+
+const bar: boolean = config.foo.bar;
+console.log(JSON.stringify(config), bar);
+
+const { default: dynamicConfig } = await import("./subdir/config.json", {
+  assert: { type: "json" },
+});
+console.log(dynamicConfig.foo.baz);
@@ -0,0 +1,3 @@
+import { b } from "http://localhost:4545/cli/tests/subdir/type_error.ts";
+
+console.log(b());
@@ -0,0 +1,4 @@
+import { b } from "http://localhost:4545/cli/tests/subdir/type_error.ts";
+
+const c: number = b();
+console.log(c);
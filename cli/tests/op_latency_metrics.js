@@ -0,0 +1,9 @@
+await Deno.readTextFile("op_latency_metrics.js");
+
+const metrics = Deno.metrics();
+console.log(metrics.latency);
+const { latency } = metrics.ops["op_open_async"];
+console.log(latency.count > 0);
+console.log(latency.total >= latency.mean);
+console.log(latency.p50 <= latency.p90 && latency.p90 <= latency.p99);
+console.log(latency.p99 <= latency.max);
@@ -0,0 +1,6 @@
+let sum = 0;
+for (let i = 0; i < 1e6; i++) {
+  sum += i;
+}
+console.log(sum > 0);
+Deno.exit(3);
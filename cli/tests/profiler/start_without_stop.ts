@@ -0,0 +1,2 @@
+await Deno.Profiler.start();
+console.log("started");
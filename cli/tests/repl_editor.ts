@@ -0,0 +1,2 @@
+// Used as the $EDITOR of the REPL, writing the input to the file to edit.
+Deno.writeTextFileSync(Deno.args[0], "const edited = 40 + 2;\nedited");
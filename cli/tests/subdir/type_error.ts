@@ -0,0 +1,7 @@
+// deno-lint-ignore-file
+
+export const a: string = 1 as unknown as number;
+
+export function b(): string {
+  return a;
+}
@@ -7,6 +7,7 @@ const permissions: Deno.PermissionName[] = [
   "env",
   "run",
   "plugin",
+  "ffi",
   "hrtime",
 ];
 
@@ -0,0 +1,11 @@
+Deno.test("leaking resource", async function () {
+  await Deno.open("hello.txt");
+});
+
+Deno.test("leaking sync resource", function () {
+  Deno.openSync("hello.txt");
+});
+
+Deno.test("leaking async op", function () {
+  setTimeout(() => {}, 1000);
+});
@@ -0,0 +1,13 @@
+addEventListener("unhandledrejection", (e) => {
+  console.log("unhandledrejection", e.reason.message);
+  e.preventDefault();
+});
+onrejectionhandled = (e) => {
+  console.log("rejectionhandled", e.reason.message);
+};
+
+const p = Promise.reject(new Error("boom"));
+setTimeout(() => {
+  p.catch(() => {});
+  setTimeout(() => console.log("still running"), 10);
+}, 10);
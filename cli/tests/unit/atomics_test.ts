@@ -0,0 +1,56 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import { assert, assertEquals, assertThrows, unitTest } from "./test_util.ts";
+
+unitTest(function atomicsWaitAsyncNotEqual(): void {
+  const view = new Int32Array(new SharedArrayBuffer(4));
+  view[0] = 1;
+  assertEquals(Atomics.waitAsync(view, 0, 0), {
+    async: false,
+    value: "not-equal",
+  });
+  assertEquals(Atomics.waitAsync(view, 0, 1, 0), {
+    async: false,
+    value: "timed-out",
+  });
+});
+
+unitTest(function atomicsWaitAsyncInvalidArray(): void {
+  assertThrows(
+    () => Atomics.waitAsync(new Int32Array(4), 0, 0),
+    TypeError,
+  );
+  assertThrows(
+    () => Atomics.waitAsync(new Int32Array(new SharedArrayBuffer(4)), 1, 0),
+    RangeError,
+  );
+});
+
+unitTest(async function atomicsWaitAsyncTimeout(): Promise<void> {
+  const view = new Int32Array(new SharedArrayBuffer(4));
+  const result = Atomics.waitAsync(view, 0, 0, 10);
+  assert(result.async);
+  assertEquals(await result.value, "timed-out");
+  // The wait that timed out isn't counted.
+  assertEquals(Atomics.notify(view, 0), 0);
+});
+
+unitTest(async function atomicsWaitAsyncNotify(): Promise<void> {
+  const view = new BigInt64Array(new SharedArrayBuffer(16));
+  const first = Atomics.waitAsync(view, 1, 0n);
+  const second = Atomics.waitAsync(view, 1, 0n);
+  assert(first.async && second.async);
+
+  assertEquals(Atomics.notify(view, 0), 0);
+  assertEquals(Atomics.notify(view, 1, 1), 1);
+  assertEquals(await first.value, "ok");
+  assertEquals(Atomics.notify(view, 1), 1);
+  assertEquals(await second.value, "ok");
+});
+
+unitTest(async function atomicsWaitAsyncHugeTimeout(): Promise<void> {
+  const view = new Int32Array(new SharedArrayBuffer(4));
+  const result = Atomics.waitAsync(view, 0, 0, 1e300);
+  assert(result.async);
+  assertEquals(Atomics.notify(view, 0), 1);
+  assertEquals(await result.value, "ok");
+});
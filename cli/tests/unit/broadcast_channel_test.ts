@@ -25,3 +25,36 @@ Deno.test("broadcastchannel worker", async () => {
 
   await promise;
 });
+
+Deno.test("broadcastchannel worker fan out", async () => {
+  const channel = new BroadcastChannel("fanout");
+  const reply = new BroadcastChannel("fanout-reply");
+
+  const url = new URL(
+    "../workers/broadcast_channel_fanout.ts",
+    import.meta.url,
+  );
+  const worker = new Worker(url.href, { type: "module", name: "worker" });
+  worker.onmessage = () => channel.postMessage("hello");
+
+  const other = new BroadcastChannel("other");
+  const replies: string[] = [];
+  const promise = deferred();
+  reply.onmessage = (e) => {
+    replies.push(e.data);
+    if (replies.length === 2) {
+      // Messages are delivered in the order they are posted, so the reply to
+      // this one comes after any reply of the other channel to "hello".
+      other.postMessage("done");
+    }
+    if (e.data === "c:done") promise.resolve();
+  };
+
+  await promise;
+  assertEquals(replies.sort(), ["a:hello", "b:hello", "c:done"]);
+
+  worker.terminate();
+  channel.close();
+  other.close();
+  reply.close();
+});
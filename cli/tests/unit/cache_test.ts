@@ -0,0 +1,152 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
+
+unitTest(async function cacheStorageOpenHasDelete() {
+  const name = "cache-storage-open-has-delete";
+  assertEquals(await caches.has(name), false);
+  const cache = await caches.open(name);
+  assert(cache instanceof Cache);
+  assert(await caches.has(name));
+  assert((await caches.keys()).includes(name));
+  assert(await caches.delete(name));
+  assertEquals(await caches.has(name), false);
+  assertEquals(await caches.delete(name), false);
+  await assertThrowsAsync(() => cache.keys(), TypeError);
+});
+
+unitTest(async function cachePutMatch() {
+  const cache = await caches.open("cache-put-match");
+  await cache.put(
+    "https://deno.land/x/#fragment",
+    new Response("hello", {
+      status: 201,
+      statusText: "Created",
+      headers: { "content-type": "text/plain" },
+    }),
+  );
+
+  const response = await cache.match("https://deno.land/x/");
+  assert(response);
+  assertEquals(response.status, 201);
+  assertEquals(response.statusText, "Created");
+  assertEquals(response.headers.get("content-type"), "text/plain");
+  assertEquals(await response.text(), "hello");
+
+  assertEquals(await cache.match("https://deno.land/x/?a=b"), undefined);
+  assert(
+    await cache.match("https://deno.land/x/?a=b", { ignoreSearch: true }),
+  );
+  assertEquals(
+    await cache.match("https://deno.land/", { ignoreSearch: true }),
+    undefined,
+  );
+  await cache.put("https://deno.land/y/?a=b", new Response("search"));
+  assertEquals(await cache.match("https://deno.land/y/"), undefined);
+  const searchResponse = await cache.match("https://deno.land/y/?c=d", {
+    ignoreSearch: true,
+  });
+  assertEquals(await searchResponse?.text(), "search");
+  assertEquals(
+    await cache.match(
+      new Request("https://deno.land/x/", { method: "POST" }),
+    ),
+    undefined,
+  );
+
+  const keys = await cache.keys();
+  assertEquals(keys.map((request) => request.url), [
+    "https://deno.land/x/",
+    "https://deno.land/y/?a=b",
+  ]);
+
+  await caches.delete("cache-put-match");
+});
+
+unitTest(async function cachePutReplaces() {
+  const cache = await caches.open("cache-put-replaces");
+  await cache.put("https://deno.land/", new Response("one"));
+  await cache.put("https://deno.land/", new Response("two"));
+  const responses = await cache.matchAll("https://deno.land/");
+  assertEquals(responses.length, 1);
+  assertEquals(await responses[0].text(), "two");
+  await caches.delete("cache-put-replaces");
+});
+
+unitTest(async function cacheVary() {
+  const cache = await caches.open("cache-vary");
+  await cache.put(
+    new Request("https://deno.land/", { headers: { "accept": "text/html" } }),
+    new Response("html", { headers: { "vary": "Accept" } }),
+  );
+  assertEquals(
+    await cache.match(
+      new Request("https://deno.land/", {
+        headers: { "accept": "application/json" },
+      }),
+    ),
+    undefined,
+  );
+  const response = await cache.match(
+    new Request("https://deno.land/", { headers: { "accept": "text/html" } }),
+  );
+  assertEquals(await response?.text(), "html");
+  assert(await cache.match("https://deno.land/", { ignoreVary: true }));
+  await caches.delete("cache-vary");
+});
+
+unitTest(async function cacheDelete() {
+  const cache = await caches.open("cache-delete");
+  await cache.put("https://deno.land/a", new Response("a"));
+  await cache.put("https://deno.land/b", new Response("b"));
+  assert(await cache.delete("https://deno.land/a"));
+  assertEquals(await cache.delete("https://deno.land/a"), false);
+  assertEquals(
+    (await cache.keys()).map((request) => request.url),
+    ["https://deno.land/b"],
+  );
+  await caches.delete("cache-delete");
+});
+
+unitTest(async function cachePutInvalid() {
+  const cache = await caches.open("cache-put-invalid");
+  await assertThrowsAsync(
+    () =>
+      cache.put(
+        new Request("https://deno.land/", { method: "POST" }),
+        new Response("a"),
+      ),
+    TypeError,
+  );
+  await assertThrowsAsync(
+    () => cache.put("https://deno.land/", new Response("a", { status: 206 })),
+    TypeError,
+  );
+  await assertThrowsAsync(
+    () =>
+      cache.put(
+        "https://deno.land/",
+        new Response("a", { headers: { "vary": "*" } }),
+      ),
+    TypeError,
+  );
+  assertEquals(await cache.keys(), []);
+  await caches.delete("cache-put-invalid");
+});
+
+unitTest(async function cacheStorageMatch() {
+  const cache = await caches.open("cache-storage-match");
+  await cache.put("https://deno.land/std", new Response("std"));
+  const response = await caches.match("https://deno.land/std");
+  assertEquals(await response?.text(), "std");
+  assertEquals(
+    await caches.match("https://deno.land/std", { cacheName: "missing" }),
+    undefined,
+  );
+  assertEquals(await caches.has("missing"), false);
+  await caches.delete("cache-storage-match");
+});
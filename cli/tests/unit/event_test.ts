@@ -1,5 +1,10 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
-import { assert, assertEquals, unitTest } from "./test_util.ts";
+import {
+  assert,
+  assertEquals,
+  assertThrows,
+  unitTest,
+} from "./test_util.ts";
 
 unitTest(function eventInitializedWithType(): void {
   const type = "click";
@@ -127,3 +132,32 @@ unitTest(function eventInspectOutput(): void {
     assertEquals(Deno.inspect(event), outputProvider(event));
   }
 });
+
+unitTest(function promiseRejectionEventInitializedWithDict(): void {
+  const promise = Promise.resolve();
+  const reason = new Error("reason");
+  const event = new PromiseRejectionEvent("unhandledrejection", {
+    cancelable: true,
+    promise,
+    reason,
+  });
+  assertEquals(event.type, "unhandledrejection");
+  assertEquals(event.cancelable, true);
+  assertEquals(event.promise, promise);
+  assertEquals(event.reason, reason);
+});
+
+unitTest(function promiseRejectionEventRequiresPromise(): void {
+  assertThrows(
+    () => Reflect.construct(PromiseRejectionEvent, ["unhandledrejection"]),
+    TypeError,
+  );
+  assertThrows(
+    () =>
+      new PromiseRejectionEvent(
+        "unhandledrejection",
+        {} as PromiseRejectionEventInit,
+      ),
+    TypeError,
+  );
+});
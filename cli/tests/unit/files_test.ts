@@ -5,6 +5,7 @@
 import {
   assert,
   assertEquals,
+  assertThrows,
   assertThrowsAsync,
   unitTest,
 } from "./test_util.ts";
@@ -650,3 +651,41 @@ unitTest({ perms: { read: true } }, async function seekMode(): Promise<void> {
   assertEquals(new TextDecoder().decode(buf), "H");
   file.close();
 });
+
+unitTest(
+  { perms: { read: true, write: true } },
+  async function filesReadableWritablePipeTo(): Promise<void> {
+    const filename = Deno.makeTempDirSync() + "/test.txt";
+    const from = await Deno.open("cli/tests/fixture.json");
+    const to = await Deno.open(filename, { write: true, create: true });
+    // Both streams are backed by resources, so this is piped by a single op.
+    await from.readable.pipeTo(to.writable);
+    assertEquals(
+      Deno.readFileSync(filename),
+      Deno.readFileSync("cli/tests/fixture.json"),
+    );
+    // Both files are closed once the pipe is done.
+    assertThrows(() => from.close(), Deno.errors.BadResource);
+    assertThrows(() => to.close(), Deno.errors.BadResource);
+  },
+);
+
+unitTest(
+  { perms: { read: true } },
+  async function filesReadableChunks(): Promise<void> {
+    const file = await Deno.open("cli/tests/hello.txt");
+    const chunks: Uint8Array[] = [];
+    await file.readable.pipeTo(
+      new WritableStream({
+        write(chunk) {
+          chunks.push(chunk);
+        },
+      }),
+    );
+    assertEquals(
+      new TextDecoder().decode(chunks[0]),
+      Deno.readTextFileSync("cli/tests/hello.txt"),
+    );
+    assertThrows(() => file.close(), Deno.errors.BadResource);
+  },
+);
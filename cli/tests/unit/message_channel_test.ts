@@ -4,9 +4,17 @@
 import {
   assert,
   assertEquals,
+  assertThrows,
 } from "../../../test_util/std/testing/asserts.ts";
 import { deferred } from "../../../test_util/std/async/deferred.ts";
 
+declare global {
+  namespace Deno {
+    // deno-lint-ignore no-explicit-any
+    var core: any;
+  }
+}
+
 Deno.test("messagechannel", async () => {
   const mc = new MessageChannel();
   const mc2 = new MessageChannel();
@@ -31,3 +39,236 @@ Deno.test("messagechannel", async () => {
   mc.port2.close();
   mc2.port2.close();
 });
+
+Deno.test("messagechannel transfer ArrayBuffer", async () => {
+  const mc = new MessageChannel();
+  const promise = deferred<MessageEvent>();
+  mc.port2.onmessage = (e) => promise.resolve(e);
+
+  const buffer = new Uint8Array([1, 2, 3]).buffer;
+  const view = new Uint8Array(buffer, 1);
+  mc.port1.postMessage({ buffer, view }, [buffer]);
+  assertEquals(buffer.byteLength, 0);
+  assertEquals(view.length, 0);
+
+  const { data } = await promise;
+  assertEquals(new Uint8Array(data.buffer), new Uint8Array([1, 2, 3]));
+  assert(data.view.buffer === data.buffer);
+  assertEquals(data.view, new Uint8Array([2, 3]));
+
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel transfer detached ArrayBuffer", () => {
+  const mc = new MessageChannel();
+  const buffer = new ArrayBuffer(1);
+  mc.port1.postMessage(null, [buffer]);
+  assertThrows(
+    () => mc.port1.postMessage(null, [buffer]),
+    DOMException,
+    "Can not transfer detached ArrayBuffer",
+  );
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel transfer ReadableStream", async () => {
+  const mc = new MessageChannel();
+  const promise = deferred<MessageEvent>();
+  mc.port2.onmessage = (e) => promise.resolve(e);
+
+  const stream = new ReadableStream({
+    start(controller) {
+      controller.enqueue("hello");
+      controller.enqueue("world");
+      controller.close();
+    },
+  });
+  mc.port1.postMessage({ stream }, [stream]);
+  assert(stream.locked);
+
+  const { data } = await promise;
+  assert(data.stream instanceof ReadableStream);
+  const chunks: string[] = [];
+  for await (const chunk of data.stream) {
+    chunks.push(chunk);
+  }
+  assertEquals(chunks, ["hello", "world"]);
+
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel transfer WritableStream", async () => {
+  const mc = new MessageChannel();
+  const promise = deferred<MessageEvent>();
+  mc.port2.onmessage = (e) => promise.resolve(e);
+
+  const chunks: string[] = [];
+  const closed = deferred();
+  const stream = new WritableStream({
+    write(chunk) {
+      chunks.push(chunk);
+    },
+    close() {
+      closed.resolve();
+    },
+  });
+  mc.port1.postMessage(stream, [stream]);
+
+  const { data } = await promise;
+  assert(data instanceof WritableStream);
+  const writer = data.getWriter();
+  await writer.write("hello");
+  await writer.close();
+  await closed;
+  assertEquals(chunks, ["hello"]);
+
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel transfer locked stream", () => {
+  const mc = new MessageChannel();
+  const stream = new ReadableStream();
+  stream.getReader();
+  assertThrows(
+    () => mc.port1.postMessage(stream, [stream]),
+    DOMException,
+    "Can not transfer locked stream",
+  );
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel transfer stream twice", () => {
+  const mc = new MessageChannel();
+  const stream = new ReadableStream();
+  assertThrows(
+    () => mc.port1.postMessage(stream, [stream, stream]),
+    DOMException,
+    "Can not transfer the same stream twice",
+  );
+  // Nothing was transferred, so the stream is still usable.
+  assert(!stream.locked);
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel high water mark", async () => {
+  const mc = new MessageChannel({ highWaterMark: 2 });
+  mc.port1.postMessage(1);
+  mc.port1.postMessage(2);
+  assertEquals(mc.port1.queuedMessageCount, 2);
+  assertEquals(mc.port2.queuedMessageCount, 0);
+  assertThrows(
+    () => mc.port1.postMessage(3),
+    DOMException,
+    "Message port queue is full",
+  );
+
+  const received = deferred();
+  const messages: number[] = [];
+  mc.port2.onmessage = (e) => {
+    messages.push(e.data);
+    if (messages.length === 2) received.resolve();
+  };
+  await received;
+  assertEquals(messages, [1, 2]);
+  assertEquals(mc.port1.queuedMessageCount, 0);
+  mc.port1.postMessage(3);
+
+  mc.port1.close();
+  mc.port2.close();
+  assertEquals(mc.port1.queuedMessageCount, 0);
+});
+
+Deno.test("messagechannel clone errors", async () => {
+  const mc = new MessageChannel();
+  const promise = deferred<MessageEvent>();
+  mc.port2.onmessage = (e) => promise.resolve(e);
+
+  const cause = new RangeError("out of range");
+  const error = Object.assign(new TypeError("wrong type"), { cause });
+  const domException = new DOMException("not cloneable", "DataCloneError");
+  mc.port1.postMessage({ error, domException, errors: [error, cause] });
+
+  const { data } = await promise;
+  assert(data.error instanceof TypeError);
+  assertEquals(data.error.message, "wrong type");
+  assertEquals(data.error.stack, error.stack);
+  assert(data.error.cause instanceof RangeError);
+  assertEquals(data.error.cause.message, "out of range");
+  assert(data.errors[0] === data.error);
+  assert(data.errors[1] === data.error.cause);
+  assert(data.domException instanceof DOMException);
+  assertEquals(data.domException.name, "DataCloneError");
+  assertEquals(data.domException.message, "not cloneable");
+  assertEquals(data.domException.code, DOMException.DATA_CLONE_ERR);
+
+  mc.port1.close();
+  mc.port2.close();
+});
+
+Deno.test("messagechannel receive timeout", async () => {
+  const [port1, port2] = Deno.core.opSync("op_message_port_create_entangled");
+  try {
+    await Deno.core.opAsync("op_message_port_recv_message", port2, 10);
+    throw new Error("receiving should have timed out");
+  } catch (err) {
+    assert(err instanceof Deno.errors.TimedOut);
+  }
+
+  Deno.core.opSync("op_message_port_post_message", port1, {
+    data: new Uint8Array(1),
+    transferables: [],
+  });
+  const message = await Deno.core.opAsync(
+    "op_message_port_recv_message",
+    port2,
+    1000,
+  );
+  assertEquals(message.data.length, 1);
+
+  Deno.core.close(port1);
+  Deno.core.close(port2);
+});
+
+Deno.test("messagechannel close event", async () => {
+  const mc = new MessageChannel();
+  const closed = deferred();
+  const messages: unknown[] = [];
+  mc.port2.onmessage = (e) => messages.push(e.data);
+  mc.port2.onclose = () => closed.resolve();
+
+  mc.port1.postMessage("last");
+  mc.port1.close();
+  await closed;
+  // Messages sent before the other end was closed are still delivered.
+  assertEquals(messages, ["last"]);
+  // The port is closed as well, so posting to it does nothing.
+  mc.port2.postMessage("ignored");
+});
+
+Deno.test("messagechannel high water mark closed port", () => {
+  const mc = new MessageChannel({ highWaterMark: 1 });
+  mc.port1.postMessage(1);
+  mc.port2.close();
+  // The queued message is never received, so it doesn't fill the queue.
+  assertEquals(mc.port1.queuedMessageCount, 0);
+  mc.port1.postMessage(2);
+  mc.port1.close();
+});
+
+Deno.test("messagechannel wasm module is not cloneable", () => {
+  // The smallest valid module: the magic number and the version.
+  const bytes = new Uint8Array([0, 97, 115, 109, 1, 0, 0, 0]);
+  const mc = new MessageChannel();
+  assertThrows(
+    () => mc.port1.postMessage(new WebAssembly.Module(bytes)),
+    DOMException,
+  );
+  mc.port1.close();
+  mc.port2.close();
+});
@@ -209,3 +209,36 @@ unitTest({ perms: { env: true } }, function systemCpuInfo(): void {
   assert(cores === undefined || cores > 0);
   assert(speed === undefined || speed > 0);
 });
+
+unitTest(function memoryUsage(): void {
+  const { rss, heapTotal, heapUsed, external } = Deno.memoryUsage();
+  assert(heapUsed > 0);
+  assert(heapTotal >= heapUsed);
+  assert(external >= 0);
+  if (Deno.build.os === "linux" || Deno.build.os === "darwin") {
+    assert(rss >= heapUsed);
+  }
+});
+
+unitTest(function exitCodeRejectsNonIntegers(): void {
+  assertEquals(Deno.exitCode, 0);
+  assertThrows(
+    () => {
+      Deno.exitCode = 1.5;
+    },
+    TypeError,
+    "Exit code must be an integer.",
+  );
+  assertEquals(Deno.exitCode, 0);
+});
+
+unitTest(function exitCodeRejectsOutOfRange(): void {
+  assertThrows(
+    () => {
+      Deno.exitCode = 2 ** 31;
+    },
+    RangeError,
+    "Exit code must fit in a 32-bit signed integer.",
+  );
+  assertEquals(Deno.exitCode, 0);
+});
@@ -0,0 +1,52 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import { assert, assertThrowsAsync, unitTest } from "./test_util.ts";
+
+unitTest(async function profilerStartStop() {
+  await Deno.Profiler.start();
+  let sum = 0;
+  for (let i = 0; i < 1e6; i++) {
+    sum += i;
+  }
+  assert(sum > 0);
+  const profile = await Deno.Profiler.stop();
+  assert(profile.nodes.length > 0);
+  assert(profile.endTime >= profile.startTime);
+});
+
+unitTest(async function profilerStopNotRunning() {
+  await assertThrowsAsync(
+    () => Deno.Profiler.stop(),
+    Error,
+    "The profiler is not running",
+  );
+});
+
+unitTest(async function profilerAlreadyRunning() {
+  await Deno.Profiler.start();
+  try {
+    await assertThrowsAsync(
+      () => Deno.Profiler.start(),
+      Error,
+      "The profiler is already running",
+    );
+  } finally {
+    await Deno.Profiler.stop();
+  }
+});
+
+unitTest(
+  { perms: { read: true, write: true } },
+  async function heapSnapshot() {
+    const path = await Deno.makeTempFile({ suffix: ".heapsnapshot" });
+    await Deno.heapSnapshot(path);
+    const snapshot = JSON.parse(await Deno.readTextFile(path));
+    assert(snapshot.snapshot.node_count > 0);
+    await Deno.remove(path);
+  },
+);
+
+unitTest({ perms: { write: false } }, async function heapSnapshotPerm() {
+  await assertThrowsAsync(async () => {
+    await Deno.heapSnapshot("./test.heapsnapshot");
+  }, Deno.errors.PermissionDenied);
+});
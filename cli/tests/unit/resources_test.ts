@@ -55,3 +55,30 @@ unitTest({ perms: { read: true } }, async function resourcesFile(): Promise<
   })!;
   assertEquals(resourcesAfter[newRid], "fsFile");
 });
+
+unitTest(
+  { perms: { read: true, net: true } },
+  async function resourcesMetadata(): Promise<void> {
+    const f = await Deno.open("cli/tests/hello.txt");
+    const listener = Deno.listen({ port: 4502 });
+    const dialerConn = await Deno.connect({ port: 4502 });
+    const listenerConn = await listener.accept();
+
+    const res = Deno.resources({ metadata: true });
+    assertEquals(res[f.rid], {
+      name: "fsFile",
+      metadata: { path: "cli/tests/hello.txt" },
+    });
+    assertEquals(res[dialerConn.rid].name, "tcpStream");
+    assertEquals(res[dialerConn.rid].metadata, {
+      localAddr: dialerConn.localAddr,
+      remoteAddr: dialerConn.remoteAddr,
+    });
+    assertEquals(res[listener.rid], { name: "tcpListener", metadata: null });
+
+    f.close();
+    listenerConn.close();
+    dialerConn.close();
+    listener.close();
+  },
+);
@@ -154,6 +154,60 @@ unitTest(
   },
 );
 
+unitTest(
+  { ignore: Deno.build.os !== "windows" },
+  function signalListenersNotImplemented(): void {
+    assertThrows(
+      () => {
+        Deno.addSignalListener(1, () => {});
+      },
+      Error,
+      "not implemented",
+    );
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows", perms: { run: true } },
+  async function signalListenersTest(): Promise<void> {
+    let a = 0;
+    let b = 0;
+    const listenerA = () => a++;
+    const listenerB = () => b++;
+    Deno.addSignalListener(Deno.Signal.SIGUSR2, listenerA);
+    Deno.addSignalListener(Deno.Signal.SIGUSR2, listenerB);
+    // Registering the same listener again is a no-op.
+    Deno.addSignalListener(Deno.Signal.SIGUSR2, listenerB);
+
+    Deno.kill(Deno.pid, Deno.Signal.SIGUSR2);
+    await defer(20);
+    assertEquals([a, b], [1, 1]);
+
+    Deno.removeSignalListener(Deno.Signal.SIGUSR2, listenerA);
+    Deno.kill(Deno.pid, Deno.Signal.SIGUSR2);
+    await defer(20);
+    assertEquals([a, b], [1, 2]);
+
+    Deno.removeSignalListener(Deno.Signal.SIGUSR2, listenerB);
+    // Lets the canceled poll of the signal stream settle.
+    await defer(20);
+  },
+);
+
+unitTest(
+  { ignore: Deno.build.os === "windows" },
+  function signalListenerTypeCheck(): void {
+    assertThrows(
+      () => {
+        // deno-lint-ignore no-explicit-any
+        Deno.addSignalListener(Deno.Signal.SIGUSR2, null as any);
+      },
+      TypeError,
+      "Signal listener must be a function.",
+    );
+  },
+);
+
 // https://github.com/denoland/deno/issues/9806
 unitTest(
   { ignore: Deno.build.os === "windows", perms: { run: true } },
@@ -32,6 +32,7 @@ interface UnitTestPermissions {
   env?: boolean;
   run?: boolean;
   plugin?: boolean;
+  ffi?: boolean;
   hrtime?: boolean;
 }
 
@@ -84,6 +85,7 @@ export function unitTest(
       env: false,
       run: false,
       plugin: false,
+      ffi: false,
       hrtime: false,
     }, options.perms),
   };
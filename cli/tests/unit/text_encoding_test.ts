@@ -92,6 +92,62 @@ unitTest(function textDecoderASCII(): void {
   assertEquals(decoder.decode(fixture), "‰•Ÿ¿");
 });
 
+unitTest(function textDecoderLegacyMultiByte(): void {
+  // deno-fmt-ignore
+  const fixtures: [string, number[], string][] = [
+    ["shift_jis", [0x93, 0xfa, 0x96, 0x7b, 0x8c, 0xea], "日本語"],
+    ["euc-jp", [0xc6, 0xfc, 0xcb, 0xdc, 0xb8, 0xec], "日本語"],
+    ["iso-2022-jp", [
+      0x1b, 0x24, 0x42, 0x46, 0x7c, 0x4b, 0x5c, 0x38, 0x6c, 0x1b, 0x28, 0x42,
+    ], "日本語"],
+    ["gbk", [0xd6, 0xd0, 0xce, 0xc4], "中文"],
+    ["big5", [0xa4, 0xa4, 0xa4, 0xe5], "中文"],
+    ["euc-kr", [0xc7, 0xd1, 0xb1, 0xb9, 0xbe, 0xee], "한국어"],
+  ];
+  for (const [label, bytes, expected] of fixtures) {
+    const decoder = new TextDecoder(label);
+    assertEquals(decoder.decode(new Uint8Array(bytes)), expected);
+  }
+});
+
+unitTest(function textDecoderLegacyMultiByteStreaming(): void {
+  // deno-fmt-ignore
+  const fixtures: [string, number[], string][] = [
+    ["shift_jis", [0x93, 0xfa, 0x96, 0x7b, 0x8c, 0xea], "日本語"],
+    ["iso-2022-jp", [
+      0x1b, 0x24, 0x42, 0x46, 0x7c, 0x4b, 0x5c, 0x38, 0x6c, 0x1b, 0x28, 0x42,
+    ], "日本語"],
+    ["big5", [0xa4, 0xa4, 0xa4, 0xe5], "中文"],
+  ];
+  for (const [label, bytes, expected] of fixtures) {
+    // Feed one byte at a time so every character is split across chunks.
+    const decoder = new TextDecoder(label);
+    let actual = "";
+    for (const byte of bytes) {
+      actual += decoder.decode(new Uint8Array([byte]), { stream: true });
+    }
+    actual += decoder.decode();
+    assertEquals(actual, expected);
+  }
+});
+
+unitTest(function textDecoderLegacyMultiByteFatal(): void {
+  const decoder = new TextDecoder("shift_jis", { fatal: true });
+  assertThrows(
+    () => decoder.decode(new Uint8Array([0x93])),
+    TypeError,
+    "The encoded data is not valid.",
+  );
+});
+
+unitTest(function textDecoderLegacyMultiByteEncodingName(): void {
+  assertEquals(new TextDecoder("sjis").encoding, "shift_jis");
+  assertEquals(new TextDecoder("x-euc-jp").encoding, "euc-jp");
+  assertEquals(new TextDecoder("csiso2022jp").encoding, "iso-2022-jp");
+  assertEquals(new TextDecoder("gb2312").encoding, "gbk");
+  assertEquals(new TextDecoder("big5-hkscs").encoding, "big5");
+});
+
 unitTest(function textDecoderErrorEncoding(): void {
   let didThrow = false;
   try {
@@ -260,3 +316,46 @@ unitTest(function textEncoderShouldCoerceToString(): void {
   const decoded = decoder.decode(bytes);
   assertEquals(decoded, fixutreText);
 });
+
+unitTest(async function textDecoderStreamSplitCodePoint(): Promise<void> {
+  // "€" is 0xE2 0x82 0xAC, split across three chunks.
+  const chunks = [
+    new Uint8Array([0x61, 0xE2]),
+    new Uint8Array([0x82]),
+    new Uint8Array([0xAC, 0x62]),
+  ];
+  const decoded = await new Blob(chunks).stream()
+    .pipeThrough(new TextDecoderStream());
+  let text = "";
+  for await (const chunk of decoded) {
+    text += chunk;
+  }
+  assertEquals(text, "a€b");
+});
+
+unitTest(async function textDecoderStreamTruncated(): Promise<void> {
+  const { readable, writable } = new TextDecoderStream();
+  const writer = writable.getWriter();
+  writer.write(new Uint8Array([0x61, 0xE2, 0x82]));
+  writer.close();
+  let text = "";
+  for await (const chunk of readable) {
+    text += chunk;
+  }
+  assertEquals(text, "a\ufffd");
+});
+
+unitTest(async function textEncoderStreamSplitSurrogatePair(): Promise<void> {
+  const { readable, writable } = new TextEncoderStream();
+  const writer = writable.getWriter();
+  // "\ud83d\ude00" is a surrogate pair, split across the first two chunks.
+  writer.write("a\ud83d");
+  writer.write("\ude00b");
+  writer.write("\ud83d");
+  writer.close();
+  const bytes: number[] = [];
+  for await (const chunk of readable) {
+    bytes.push(...chunk);
+  }
+  assertEquals(bytes, [0x61, 0xF0, 0x9F, 0x98, 0x80, 0x62, 0xEF, 0xBF, 0xBD]);
+});
@@ -0,0 +1,743 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+import {
+  assert,
+  assertEquals,
+  assertThrowsAsync,
+  unitTest,
+} from "./test_util.ts";
+
+function hexEncode(buffer: ArrayBuffer): string {
+  return Array.from(new Uint8Array(buffer))
+    .map((byte) => byte.toString(16).padStart(2, "0"))
+    .join("");
+}
+
+function hexDecode(hex: string): Uint8Array {
+  return new Uint8Array(
+    hex.match(/../g)!.map((byte) => parseInt(byte, 16)),
+  );
+}
+
+unitTest(async function testHmacSignVerify() {
+  const key = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-256" },
+    true,
+    ["sign", "verify"],
+  );
+  assert(key instanceof CryptoKey);
+  assertEquals(key.type, "secret");
+  assertEquals(key.algorithm, {
+    name: "HMAC",
+    hash: { name: "SHA-256" },
+    length: 512,
+  });
+
+  const data = new TextEncoder().encode("hello world");
+  const signature = await crypto.subtle.sign("HMAC", key, data);
+  assertEquals(signature.byteLength, 32);
+  assert(await crypto.subtle.verify("HMAC", key, signature, data));
+  assert(
+    !await crypto.subtle.verify(
+      "HMAC",
+      key,
+      signature,
+      new TextEncoder().encode("hello deno"),
+    ),
+  );
+});
+
+unitTest(async function testHmacJwkRoundTrip() {
+  const key = await crypto.subtle.generateKey(
+    { name: "HMAC", hash: "SHA-512" },
+    true,
+    ["sign"],
+  );
+  const jwk = await crypto.subtle.exportKey("jwk", key);
+  assertEquals(jwk.kty, "oct");
+  assertEquals(jwk.alg, "HS512");
+  assertEquals(jwk.key_ops, ["sign"]);
+  assertEquals(jwk.ext, true);
+
+  const imported = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    { name: "HMAC", hash: "SHA-512" },
+    true,
+    ["sign"],
+  );
+  assertEquals(
+    new Uint8Array(await crypto.subtle.exportKey("raw", imported)),
+    new Uint8Array(await crypto.subtle.exportKey("raw", key)),
+  );
+});
+
+unitTest(async function testAesGcmEncryptDecrypt() {
+  const key = await crypto.subtle.generateKey(
+    { name: "AES-GCM", length: 256 },
+    false,
+    ["encrypt", "decrypt"],
+  );
+  const iv = crypto.getRandomValues(new Uint8Array(12));
+  const data = new TextEncoder().encode("hello world");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-GCM", iv },
+    key,
+    data,
+  );
+  assertEquals(ciphertext.byteLength, data.byteLength + 16);
+  const plaintext = await crypto.subtle.decrypt(
+    { name: "AES-GCM", iv },
+    key,
+    ciphertext,
+  );
+  assertEquals(new Uint8Array(plaintext), data);
+
+  await assertThrowsAsync(async () => {
+    await crypto.subtle.exportKey("raw", key);
+  }, DOMException);
+});
+
+unitTest(async function testEcdsaSignVerify() {
+  const { privateKey, publicKey } = await crypto.subtle.generateKey(
+    { name: "ECDSA", namedCurve: "P-256" },
+    false,
+    ["sign", "verify"],
+  );
+  assertEquals(privateKey.type, "private");
+  assertEquals(privateKey.usages, ["sign"]);
+  assertEquals(publicKey.type, "public");
+  assertEquals(publicKey.usages, ["verify"]);
+  assert(publicKey.extractable);
+
+  const algorithm = { name: "ECDSA", hash: "SHA-256" };
+  const data = new TextEncoder().encode("hello world");
+  const signature = await crypto.subtle.sign(algorithm, privateKey, data);
+  assertEquals(signature.byteLength, 64);
+  assert(await crypto.subtle.verify(algorithm, publicKey, signature, data));
+
+  const raw = new Uint8Array(await crypto.subtle.exportKey("raw", publicKey));
+  assertEquals(raw.byteLength, 65);
+  assertEquals(raw[0], 0x04);
+});
+
+unitTest(async function testPbkdf2DeriveBits() {
+  const key = await crypto.subtle.importKey(
+    "raw",
+    new TextEncoder().encode("password"),
+    "PBKDF2",
+    false,
+    ["deriveBits"],
+  );
+  const bits = await crypto.subtle.deriveBits(
+    {
+      name: "PBKDF2",
+      hash: "SHA-256",
+      salt: new TextEncoder().encode("salt"),
+      iterations: 1,
+    },
+    key,
+    256,
+  );
+  assertEquals(
+    hexEncode(bits),
+    "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b",
+  );
+});
+
+unitTest(async function testEd25519SignVerify() {
+  // RFC 8032, section 7.1, test 1
+  const jwk = {
+    kty: "OKP",
+    crv: "Ed25519",
+    d: "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+    x: "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+  };
+  const privateKey = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    "Ed25519",
+    true,
+    ["sign"],
+  );
+  const publicKey = await crypto.subtle.importKey(
+    "raw",
+    hexDecode(
+      "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+    ),
+    "Ed25519",
+    true,
+    ["verify"],
+  );
+  assertEquals(privateKey.algorithm, { name: "Ed25519" });
+
+  const signature = await crypto.subtle.sign(
+    "Ed25519",
+    privateKey,
+    new Uint8Array(),
+  );
+  assertEquals(
+    hexEncode(signature),
+    "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+  );
+  assert(
+    await crypto.subtle.verify(
+      "Ed25519",
+      publicKey,
+      signature,
+      new Uint8Array(),
+    ),
+  );
+
+  const exported = await crypto.subtle.exportKey("jwk", privateKey);
+  assertEquals(exported.alg, "EdDSA");
+  assertEquals(exported.d, jwk.d);
+  assertEquals(exported.x, jwk.x);
+});
+
+unitTest(async function testX25519DeriveBits() {
+  // RFC 7748, section 6.1
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    hexDecode(
+      "302e020100300506032b656e0422042077076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+    ),
+    "X25519",
+    false,
+    ["deriveBits"],
+  );
+  const publicKey = await crypto.subtle.importKey(
+    "raw",
+    hexDecode(
+      "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+    ),
+    "X25519",
+    true,
+    [],
+  );
+  const bits = await crypto.subtle.deriveBits(
+    { name: "X25519", public: publicKey },
+    privateKey,
+    256,
+  );
+  assertEquals(
+    hexEncode(bits),
+    "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+  );
+});
+
+unitTest(async function testX25519GenerateKey() {
+  const alice = await crypto.subtle.generateKey("X25519", false, [
+    "deriveBits",
+  ]) as CryptoKeyPair;
+  const bob = await crypto.subtle.generateKey("X25519", false, [
+    "deriveBits",
+  ]) as CryptoKeyPair;
+  assertEquals(alice.privateKey.usages, ["deriveBits"]);
+  assertEquals(alice.publicKey.usages, []);
+  const aliceBits = await crypto.subtle.deriveBits(
+    { name: "X25519", public: bob.publicKey },
+    alice.privateKey,
+    null,
+  );
+  const bobBits = await crypto.subtle.deriveBits(
+    { name: "X25519", public: alice.publicKey },
+    bob.privateKey,
+    null,
+  );
+  assertEquals(aliceBits.byteLength, 32);
+  assertEquals(hexEncode(aliceBits), hexEncode(bobBits));
+});
+
+// A 1024-bit RSA key and values computed for it by an independent
+// implementation.
+const rsaPkcs8 = hexDecode(
+  "30820278020100300d06092a864886f70d0101010500048202623082025e0201" +
+    "0002818100bfa08359801e106661903a5d8a2f3d46244aa37820420b37be08a3" +
+    "c7f40e2757cfcf100a1106ec98ce5b87994d6f67d4910bcd3634d2d0b59b5b4b" +
+    "136f05f3c21d0b9688a2a0e2993a28a8110dc1f56a51df184f6dc638e05baee7" +
+    "be69dd5c3d2be73535eb348aceef7bfc0e756233a5244ee1da1cca4318850aaa" +
+    "5f3faccff1020301000102818100af28c88789ed712e34660283b82900085a6a" +
+    "034c6da810daee10b5acb33030d43a76da016998fbb19d86dac6cd9a54bd5fab" +
+    "faf35e8b890d47a9c651483d979631d20f70d453edbbbead5fafe09e9763373e" +
+    "d9e8e6663412dbbbbd38cfb20d12eb17f3cb658c2fc60a635a74c247b018cb21" +
+    "851703d4e80781e64d4dd0a991a5024100fed3f9cde4058a44567d9d8da83093" +
+    "436a606c77f5454d2b3041c7a424426867b478a801263be8473bc3c0a4da6ff1" +
+    "7d43b43644cad3bc4f386d220dd029f127024100c082208051b53ccdb4c0fdfc" +
+    "9c8269ddc87b72cbf3d6b53785e23d30850db4248a9a5f314d5d28502e4f5879" +
+    "c4bd1405a22470c51f850ce4b7ca234154443527024100aae71d8521253a0b04" +
+    "fab01cde2cddc6fbe3f93b268ab8eb7b2cd2a8da0c939ae1a57dff6f56043d29" +
+    "eeea1765cc67e83becf3ec851f260ef767f1cbd3cd1d47024100aeab63620ac1" +
+    "2f525657661cf6c53dd98636bc8a61ccc6d082222cf30146d5d4f20defa18458" +
+    "aa635621985da42d0b25b160ef116cf0042ae2b7fe1520a862b1024045c9a6c8" +
+    "bd0d791567f3d8bf4042fe4617f4803f50d6d1d064bdd5077f835dfff2c40dc1" +
+    "be2d5e0fa9f7a7ac5eebb1721da1f1ed1b08590f760868493ec8f217",
+);
+const rsaSpki = hexDecode(
+  "30819f300d06092a864886f70d010101050003818d0030818902818100bfa083" +
+    "59801e106661903a5d8a2f3d46244aa37820420b37be08a3c7f40e2757cfcf10" +
+    "0a1106ec98ce5b87994d6f67d4910bcd3634d2d0b59b5b4b136f05f3c21d0b96" +
+    "88a2a0e2993a28a8110dc1f56a51df184f6dc638e05baee7be69dd5c3d2be735" +
+    "35eb348aceef7bfc0e756233a5244ee1da1cca4318850aaa5f3faccff1020301" +
+    "0001",
+);
+
+unitTest(async function testRsaSsaPkcs1KnownAnswer() {
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    rsaPkcs8,
+    { name: "RSASSA-PKCS1-v1_5", hash: "SHA-256" },
+    true,
+    ["sign"],
+  );
+  const publicKey = await crypto.subtle.importKey(
+    "spki",
+    rsaSpki,
+    { name: "RSASSA-PKCS1-v1_5", hash: "SHA-256" },
+    true,
+    ["verify"],
+  );
+  assertEquals(privateKey.algorithm, {
+    name: "RSASSA-PKCS1-v1_5",
+    modulusLength: 1024,
+    publicExponent: new Uint8Array([1, 0, 1]),
+    hash: { name: "SHA-256" },
+  });
+  const data = new TextEncoder().encode("hello world");
+  const signature = await crypto.subtle.sign(
+    "RSASSA-PKCS1-v1_5",
+    privateKey,
+    data,
+  );
+  assertEquals(
+    hexEncode(signature),
+    "b5c8d434a6012a672d4fe0a5cc8251d92094a373173b95dd0320f56565309de3" +
+      "602bd56bb3535d650bc77f0675121230cb0f28492da820ab79ffe06db84aef5b" +
+      "338b572489fc02ba8294a6e899b27e7edb31c548a1bb0b6a9bc27d74371d03d2" +
+      "de1811a0de99011e02201938deae51a3d68d86ff90e3ed054a9947c2a3f49f0a",
+  );
+  assert(
+    await crypto.subtle.verify("RSASSA-PKCS1-v1_5", publicKey, signature, data),
+  );
+});
+
+unitTest(async function testRsaPssVerifyKnownAnswer() {
+  const publicKey = await crypto.subtle.importKey(
+    "spki",
+    rsaSpki,
+    { name: "RSA-PSS", hash: "SHA-256" },
+    false,
+    ["verify"],
+  );
+  const signature = hexDecode(
+    "b92dde68c3c36efdd88eae35f803e359ade6f8f28cecf3e17bcaa69e37405be0" +
+      "2197c847ec87fe2db683d92ce75d3ab11247b71eb2be12341765d801632c436d" +
+      "51f30a461b1a0742d1c39d923c121f5761f3dadf2c7c557d65d63f01dcbef895" +
+      "3f99a0a0aa152f292d60a01bcdda84c8fc0fac2b04765bb81516e4b37ba1c6e7",
+  );
+  const data = new TextEncoder().encode("hello world");
+  const params = { name: "RSA-PSS", saltLength: 32 };
+  assert(await crypto.subtle.verify(params, publicKey, signature, data));
+  assert(
+    !await crypto.subtle.verify(
+      params,
+      publicKey,
+      signature,
+      new TextEncoder().encode("hello deno"),
+    ),
+  );
+});
+
+unitTest(async function testRsaOaepDecryptKnownAnswer() {
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    rsaPkcs8,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    false,
+    ["decrypt"],
+  );
+  const ciphertext = hexDecode(
+    "903aa63a4e1b8dd418bf7e80a23e9bdc66ad86bcecfdb3febaeba4374eb9ccc6" +
+      "5dd74f28ed32dc5ae5b24981e5e7c5929cd18165bcf32f38097ee2e4b8620797" +
+      "226abc6e31df2b39399fda0877b8a7fc0e22fe228a45a996cff579b81fe75842" +
+      "57fb007e0f7acfbb59f748b7b7accbdbc211ca5762c10231d876cfa449182442",
+  );
+  const plaintext = await crypto.subtle.decrypt(
+    { name: "RSA-OAEP" },
+    privateKey,
+    ciphertext,
+  );
+  assertEquals(new TextDecoder().decode(plaintext), "hello world");
+});
+
+unitTest(async function testRsaExportKnownAnswer() {
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    rsaPkcs8,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["decrypt"],
+  );
+  const publicKey = await crypto.subtle.importKey(
+    "spki",
+    rsaSpki,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["encrypt"],
+  );
+  assertEquals(
+    hexEncode(await crypto.subtle.exportKey("pkcs8", privateKey)),
+    hexEncode(rsaPkcs8),
+  );
+  assertEquals(
+    hexEncode(await crypto.subtle.exportKey("spki", publicKey)),
+    hexEncode(rsaSpki),
+  );
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  assertEquals(jwk.kty, "RSA");
+  assertEquals(jwk.alg, "RSA-OAEP-256");
+  assertEquals(
+    jwk.n,
+    "v6CDWYAeEGZhkDpdii89RiRKo3ggQgs3vgijx_QOJ1fPzxAKEQbsmM5bh5lNb2fU" +
+      "kQvNNjTS0LWbW0sTbwXzwh0LloiioOKZOiioEQ3B9WpR3xhPbcY44Fuu575p3Vw9" +
+      "K-c1Nes0is7ve_wOdWIzpSRO4docykMYhQqqXz-sz_E",
+  );
+  assertEquals(jwk.e, "AQAB");
+  assertEquals(
+    jwk.d,
+    "ryjIh4ntcS40ZgKDuCkACFpqA0xtqBDa7hC1rLMwMNQ6dtoBaZj7sZ2G2sbNmlS9" +
+      "X6v6816LiQ1HqcZRSD2XljHSD3DUU-27vq1fr-Cel2M3Ptno5mY0Etu7vTjPsg0S" +
+      "6xfzy2WML8YKY1p0wkewGMshhRcD1OgHgeZNTdCpkaU",
+  );
+  assertEquals(
+    jwk.p,
+    "_tP5zeQFikRWfZ2NqDCTQ2pgbHf1RU0rMEHHpCRCaGe0eKgBJjvoRzvDwKTab_F9" +
+      "Q7Q2RMrTvE84bSIN0CnxJw",
+  );
+  assertEquals(
+    jwk.q,
+    "wIIggFG1PM20wP38nIJp3ch7csvz1rU3heI9MIUNtCSKml8xTV0oUC5PWHnEvRQF" +
+      "oiRwxR-FDOS3yiNBVEQ1Jw",
+  );
+  assertEquals(
+    jwk.dp,
+    "qucdhSElOgsE-rAc3izdxvvj-TsmirjreyzSqNoMk5rhpX3_b1YEPSnu6hdlzGfo" +
+      "O-zz7IUfJg73Z_HL080dRw",
+  );
+  assertEquals(
+    jwk.dq,
+    "rqtjYgrBL1JWV2Yc9sU92YY2vIphzMbQgiIs8wFG1dTyDe-hhFiqY1YhmF2kLQsl" +
+      "sWDvEWzwBCrit_4VIKhisQ",
+  );
+  assertEquals(
+    jwk.qi,
+    "RcmmyL0NeRVn89i_QEL-Rhf0gD9Q1tHQZL3VB3-DXf_yxA3Bvi1eD6n3p6xe67Fy" +
+      "HaHx7RsIWQ92CGhJPsjyFw",
+  );
+  const imported = await crypto.subtle.importKey(
+    "jwk",
+    jwk,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["decrypt"],
+  );
+  assertEquals(
+    hexEncode(await crypto.subtle.exportKey("pkcs8", imported)),
+    hexEncode(rsaPkcs8),
+  );
+});
+
+unitTest(async function testRsaGenerateKeyInvalidParams() {
+  for (
+    const [modulusLength, publicExponent] of [
+      [2048, new Uint8Array([2])],
+      [2048, new Uint8Array([1, 0, 0])],
+      [512, new Uint8Array([1, 0, 1])],
+      [32768, new Uint8Array([1, 0, 1])],
+    ]
+  ) {
+    await assertThrowsAsync(async () => {
+      await crypto.subtle.generateKey(
+        {
+          name: "RSA-OAEP",
+          modulusLength,
+          publicExponent,
+          hash: "SHA-256",
+        },
+        false,
+        ["encrypt", "decrypt"],
+      );
+    }, DOMException);
+  }
+});
+
+unitTest(async function testRsaImportJwkInvalidPrimes() {
+  const privateKey = await crypto.subtle.importKey(
+    "pkcs8",
+    rsaPkcs8,
+    { name: "RSA-OAEP", hash: "SHA-256" },
+    true,
+    ["decrypt"],
+  );
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  // "AA" and "AQ" are 0 and 1, and the square of a prime isn't the modulus.
+  for (const [p, q] of [["AA", jwk.q], [jwk.p, "AQ"], [jwk.q, jwk.q]]) {
+    await assertThrowsAsync(async () => {
+      await crypto.subtle.importKey(
+        "jwk",
+        { ...jwk, p, q },
+        { name: "RSA-OAEP", hash: "SHA-256" },
+        true,
+        ["decrypt"],
+      );
+    }, DOMException);
+  }
+});
+
+// The AES vectors are from NIST SP 800-38A.
+const aesKey = hexDecode("2b7e151628aed2a6abf7158809cf4f3c");
+const aesPlaintext = hexDecode(
+  "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51",
+);
+
+unitTest(async function testAesCbcKnownAnswer() {
+  const key = await crypto.subtle.importKey("raw", aesKey, "AES-CBC", false, [
+    "encrypt",
+    "decrypt",
+  ]);
+  const iv = hexDecode("000102030405060708090a0b0c0d0e0f");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-CBC", iv },
+    key,
+    aesPlaintext,
+  );
+  // The last block is the PKCS#7 padding.
+  assertEquals(
+    hexEncode(ciphertext),
+    "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2" +
+      "55e21d7100b988ffec32feeafaf23538",
+  );
+  const plaintext = await crypto.subtle.decrypt(
+    { name: "AES-CBC", iv },
+    key,
+    ciphertext,
+  );
+  assertEquals(hexEncode(plaintext), hexEncode(aesPlaintext));
+});
+
+unitTest(async function testAesCtrKnownAnswer() {
+  const key = await crypto.subtle.importKey("raw", aesKey, "AES-CTR", false, [
+    "encrypt",
+    "decrypt",
+  ]);
+  const counter = hexDecode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+  const ciphertext = await crypto.subtle.encrypt(
+    { name: "AES-CTR", counter, length: 128 },
+    key,
+    aesPlaintext,
+  );
+  assertEquals(
+    hexEncode(ciphertext),
+    "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff",
+  );
+  const plaintext = await crypto.subtle.decrypt(
+    { name: "AES-CTR", counter, length: 128 },
+    key,
+    ciphertext,
+  );
+  assertEquals(hexEncode(plaintext), hexEncode(aesPlaintext));
+});
+
+unitTest(async function testAesKwWrapKeyKnownAnswer() {
+  // RFC 3394, section 4.1.
+  const kek = await crypto.subtle.importKey(
+    "raw",
+    hexDecode("000102030405060708090a0b0c0d0e0f"),
+    "AES-KW",
+    false,
+    ["wrapKey", "unwrapKey"],
+  );
+  const key = await crypto.subtle.importKey(
+    "raw",
+    hexDecode("00112233445566778899aabbccddeeff"),
+    "AES-CBC",
+    true,
+    ["encrypt"],
+  );
+  const wrapped = await crypto.subtle.wrapKey("raw", key, kek, "AES-KW");
+  assertEquals(
+    hexEncode(wrapped),
+    "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5",
+  );
+  const unwrapped = await crypto.subtle.unwrapKey(
+    "raw",
+    wrapped,
+    kek,
+    "AES-KW",
+    "AES-CBC",
+    true,
+    ["encrypt"],
+  );
+  assertEquals(unwrapped.algorithm, { name: "AES-CBC", length: 128 });
+  assertEquals(
+    hexEncode(await crypto.subtle.exportKey("raw", unwrapped)),
+    "00112233445566778899aabbccddeeff",
+  );
+
+  // A wrapped key that fails the integrity check is rejected.
+  const tampered = new Uint8Array(wrapped);
+  tampered[0] ^= 1;
+  await assertThrowsAsync(
+    async () => {
+      await crypto.subtle.unwrapKey(
+        "raw",
+        tampered,
+        kek,
+        "AES-KW",
+        "AES-CBC",
+        true,
+        ["encrypt"],
+      );
+    },
+    DOMException,
+  );
+});
+
+unitTest(async function testEcdhKnownAnswer() {
+  // RFC 5903, section 8.1.
+  const privateJwk = {
+    kty: "EC",
+    crv: "P-256",
+    use: "enc",
+    d: "yI8B9RDZrD9wopLaojFt5UTpqriv6EBJxiqcV4YtFDM",
+    x: "2tC2U5QiHPmwUeH-yleH0Jjf5jf8kLnvlF0MN3JYEYA",
+    y: "UnGgRhzbglLWHxxFb6PlmrH0WzOsz19YOJ4Fd7iZC7M",
+  };
+  const privateKey = await crypto.subtle.importKey(
+    "jwk",
+    privateJwk,
+    { name: "ECDH", namedCurve: "P-256" },
+    true,
+    ["deriveBits"],
+  );
+  // ECDH keys are for encryption rather than for signatures.
+  await assertThrowsAsync(async () => {
+    await crypto.subtle.importKey(
+      "jwk",
+      { ...privateJwk, use: "sig" },
+      { name: "ECDH", namedCurve: "P-256" },
+      true,
+      ["deriveBits"],
+    );
+  }, DOMException);
+  const publicKey = await crypto.subtle.importKey(
+    "raw",
+    hexDecode(
+      "04d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf63" +
+        "56fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872ab",
+    ),
+    { name: "ECDH", namedCurve: "P-256" },
+    true,
+    [],
+  );
+  const bits = await crypto.subtle.deriveBits(
+    { name: "ECDH", public: publicKey },
+    privateKey,
+    256,
+  );
+  assertEquals(
+    hexEncode(bits),
+    "d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de",
+  );
+
+  // The private key's public half is exported as a known SPKI structure.
+  const jwk = await crypto.subtle.exportKey("jwk", privateKey);
+  const exportedPublicKey = await crypto.subtle.importKey(
+    "jwk",
+    { kty: "EC", crv: "P-256", x: jwk.x, y: jwk.y },
+    { name: "ECDH", namedCurve: "P-256" },
+    true,
+    [],
+  );
+  assertEquals(
+    hexEncode(await crypto.subtle.exportKey("spki", exportedPublicKey)),
+    "3059301306072a8648ce3d020106082a8648ce3d03010703420004" +
+      "dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c3772581180" +
+      "5271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3",
+  );
+});
+
+unitTest(async function testHkdfKnownAnswer() {
+  // RFC 5869, test case 1.
+  const key = await crypto.subtle.importKey(
+    "raw",
+    new Uint8Array(22).fill(0x0b),
+    "HKDF",
+    false,
+    ["deriveBits"],
+  );
+  const params = {
+    name: "HKDF",
+    hash: "SHA-256",
+    salt: hexDecode("000102030405060708090a0b0c"),
+    info: hexDecode("f0f1f2f3f4f5f6f7f8f9"),
+  };
+  const bits = await crypto.subtle.deriveBits(params, key, 42 * 8);
+  assertEquals(
+    hexEncode(bits),
+    "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf" +
+      "34007208d5b887185865",
+  );
+  await assertThrowsAsync(
+    async () => {
+      await crypto.subtle.deriveBits(params, key, 42 * 8 + 1);
+    },
+    DOMException,
+  );
+});
+
+unitTest(async function testAesGcmTruncatedTagKnownAnswer() {
+  const key = await crypto.subtle.importKey(
+    "raw",
+    hexDecode("000102030405060708090a0b0c0d0e0f"),
+    "AES-GCM",
+    false,
+    ["encrypt", "decrypt"],
+  );
+  const params = {
+    name: "AES-GCM",
+    iv: hexDecode("000102030405060708090a0b"),
+    additionalData: new TextEncoder().encode("deno"),
+    tagLength: 96,
+  };
+  const ciphertext = await crypto.subtle.encrypt(
+    params,
+    key,
+    new TextEncoder().encode("hello world"),
+  );
+  assertEquals(
+    hexEncode(ciphertext),
+    "fb09cba2093b803b39be05162386ab00e4d8d2bffdd156",
+  );
+  const plaintext = await crypto.subtle.decrypt(params, key, ciphertext);
+  assertEquals(new TextDecoder().decode(plaintext), "hello world");
+
+  const tampered = new Uint8Array(ciphertext);
+  tampered[tampered.length - 1] ^= 1;
+  await assertThrowsAsync(
+    async () => {
+      await crypto.subtle.decrypt(params, key, tampered);
+    },
+    DOMException,
+  );
+  await assertThrowsAsync(
+    async () => {
+      await crypto.subtle.encrypt(
+        { ...params, iv: new Uint8Array(16) },
+        key,
+        new Uint8Array(),
+      );
+    },
+    DOMException,
+    "Only an iv of 96 bits is supported",
+  );
+});
@@ -205,3 +205,85 @@ unitTest({
   const resources = Object.keys(Deno.resources());
   Deno.close(Number(resources[resources.length - 1]));
 });
+
+// Skip this test on linux CI, because the vulkan emulator is not good enough
+// yet, and skip on macOS because these do not have virtual GPUs.
+unitTest({
+  perms: { read: true, env: true },
+  ignore: (Deno.build.os === "linux" || Deno.build.os === "darwin") && isCI,
+}, async function webgpuPresentationContext() {
+  const adapter = await navigator.gpu.requestAdapter();
+  assert(adapter);
+
+  const device = await adapter.requestDevice();
+  assert(device);
+
+  const shaderCode = await Deno.readTextFile(
+    "cli/tests/webgpu_hellotriangle_shader.wgsl",
+  );
+
+  const shaderModule = device.createShaderModule({
+    code: shaderCode,
+  });
+
+  const context = new GPUPresentationContext();
+  const format = context.getPreferredFormat(adapter);
+  context.configure({
+    device,
+    format,
+    size: { width: 200, height: 200 },
+  });
+
+  const renderPipeline = device.createRenderPipeline({
+    layout: device.createPipelineLayout({ bindGroupLayouts: [] }),
+    vertex: {
+      module: shaderModule,
+      entryPoint: "vs_main",
+    },
+    fragment: {
+      module: shaderModule,
+      entryPoint: "fs_main",
+      targets: [{ format }],
+    },
+  });
+
+  const encoder = device.createCommandEncoder();
+  const renderPass = encoder.beginRenderPass({
+    colorAttachments: [
+      {
+        view: context.getCurrentTexture().createView(),
+        storeOp: "store",
+        loadValue: [0, 1, 0, 1],
+      },
+    ],
+  });
+  renderPass.setPipeline(renderPipeline);
+  renderPass.draw(3, 1);
+  renderPass.endPass();
+  device.queue.submit([encoder.finish()]);
+
+  // The expected output has rows padded to 256 bytes.
+  const padded = await Deno.readFile("cli/tests/webgpu_hellotriangle.out");
+  const expected = new Uint8Array(200 * 200 * 4);
+  for (let y = 0; y < 200; y++) {
+    expected.set(padded.subarray(y * 1024, y * 1024 + 800), y * 800);
+  }
+  assertEquals(await context.readFrame(), expected);
+  // Reads which overlap each read back into a buffer of their own.
+  const frames = await Promise.all([context.readFrame(), context.readFrame()]);
+  assertEquals(frames, [expected, expected]);
+
+  const png = await context.readFrameAsPng();
+  assertEquals(
+    png.subarray(0, 8),
+    new Uint8Array([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]),
+  );
+
+  context.unconfigure();
+  device.destroy();
+
+  // TODO(lucacasonato): webgpu spec should add a explicit destroy method for
+  // adapters.
+  const resources = Object.keys(Deno.resources());
+  Deno.close(Number(resources[resources.length - 1]));
+});
@@ -0,0 +1,5 @@
+self.onmessage = (e: MessageEvent) => {
+  const view = new Int32Array(e.data);
+  Atomics.store(view, 0, 1);
+  Atomics.notify(view, 0);
+};
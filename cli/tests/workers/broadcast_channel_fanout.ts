@@ -0,0 +1,9 @@
+const reply = new BroadcastChannel("fanout-reply");
+
+for (const [name, tag] of [["fanout", "a"], ["fanout", "b"], ["other", "c"]]) {
+  new BroadcastChannel(name).onmessage = (e) => {
+    reply.postMessage(`${tag}:${e.data}`);
+  };
+}
+
+self.postMessage("go");
@@ -0,0 +1,4 @@
+const chunks = [];
+while (true) {
+  chunks.push(new Array(1024 * 1024).fill(chunks.length));
+}
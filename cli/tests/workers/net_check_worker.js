@@ -0,0 +1,9 @@
+self.onmessage = async () => {
+  try {
+    await fetch("http://localhost:4545/cli/tests/workers/test_worker.ts");
+    self.postMessage(false);
+  } catch (e) {
+    self.postMessage(e instanceof Deno.errors.PermissionDenied);
+  }
+  self.close();
+};
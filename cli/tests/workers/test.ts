@@ -67,6 +67,76 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "worker from blob url",
+  async fn() {
+    const promise = deferred();
+    const blob = new Blob(
+      [
+        `if (self.name !== "tsWorker") {
+  throw Error(\`Invalid worker name: \${self.name}, expected tsWorker\`);
+}
+
+onmessage = function (e: MessageEvent): void {
+  postMessage(e.data);
+  close();
+};
+`,
+      ],
+      { type: "application/typescript" },
+    );
+    const url = URL.createObjectURL(blob);
+    const tsWorker = new Worker(url, { type: "module", name: "tsWorker" });
+
+    tsWorker.onmessage = (e): void => {
+      assertEquals(e.data, "Hello World");
+      promise.resolve();
+    };
+
+    tsWorker.postMessage("Hello World");
+
+    await promise;
+    tsWorker.terminate();
+    URL.revokeObjectURL(url);
+  },
+});
+
+Deno.test({
+  name: "dynamic import of blob url in worker",
+  async fn() {
+    const promise = deferred();
+    const moduleUrl = URL.createObjectURL(
+      new Blob(["export const a = 'a';"], { type: "application/javascript" }),
+    );
+    const workerUrl = URL.createObjectURL(
+      new Blob(
+        [
+          `onmessage = async function (e) {
+  const { a } = await import(e.data);
+  postMessage(a);
+  close();
+};
+`,
+        ],
+        { type: "application/javascript" },
+      ),
+    );
+    const jsWorker = new Worker(workerUrl, { type: "module" });
+
+    jsWorker.onmessage = (e): void => {
+      assertEquals(e.data, "a");
+      promise.resolve();
+    };
+
+    jsWorker.postMessage(moduleUrl);
+
+    await promise;
+    jsWorker.terminate();
+    URL.revokeObjectURL(moduleUrl);
+    URL.revokeObjectURL(workerUrl);
+  },
+});
+
 Deno.test({
   name: "worker nested",
   fn: async function (): Promise<void> {
@@ -343,6 +413,29 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "Worker caches",
+  fn: async function (): Promise<void> {
+    const promise = deferred();
+    const w = new Worker(
+      new URL("worker_caches.js", import.meta.url).href,
+      { type: "module" },
+    );
+    w.onmessage = (): void => {
+      promise.resolve();
+    };
+    w.postMessage(null);
+    await promise;
+    w.terminate();
+
+    const response = await caches.match("https://deno.land/worker", {
+      cacheName: "worker",
+    });
+    assertEquals(await response?.text(), "from worker");
+    await caches.delete("worker");
+  },
+});
+
 Deno.test({
   name: "Worker event handler order",
   fn: async function (): Promise<void> {
@@ -458,6 +551,32 @@ Deno.test("Worker limit children permissions", async function () {
   worker.terminate();
 });
 
+Deno.test("Worker without network access", async function () {
+  const promise = deferred();
+  const worker = new Worker(
+    new URL("./net_check_worker.js", import.meta.url).href,
+    {
+      type: "module",
+      deno: {
+        namespace: true,
+        permissions: {
+          net: false,
+        },
+      },
+    },
+  );
+
+  worker.onmessage = ({ data: denied }) => {
+    assert(denied);
+    promise.resolve();
+  };
+
+  worker.postMessage(null);
+
+  await promise;
+  worker.terminate();
+});
+
 Deno.test("Worker limit children permissions granularly", async function () {
   const promise = deferred();
   const worker = new Worker(
@@ -769,3 +888,120 @@ Deno.test({
     worker.terminate();
   },
 });
+
+Deno.test({
+  name: "worker postMessage errors",
+  fn: async function (): Promise<void> {
+    const result = deferred<MessageEvent>();
+    const worker = new Worker(
+      new URL("worker_error_cloning.ts", import.meta.url).href,
+      { type: "module" },
+    );
+    worker.onmessage = (e): void => result.resolve(e);
+
+    const cause = new SyntaxError("bad syntax");
+    const error = Object.assign(new TypeError("from host"), { cause });
+    const domException = new DOMException("aborted", "AbortError");
+    worker.postMessage({ error, domException });
+
+    const { data } = await result;
+    assert(data.received.error instanceof TypeError);
+    assertEquals(data.received.error.message, "from host");
+    assert(data.received.error.cause instanceof SyntaxError);
+    assertEquals(data.received.error.cause.message, "bad syntax");
+    assert(data.received.domException instanceof DOMException);
+    assertEquals(data.received.domException.name, "AbortError");
+    assert(data.error instanceof TypeError);
+    assertEquals(data.error.message, "from worker");
+    assert(data.error.cause instanceof RangeError);
+    assertEquals(data.error.cause.message, "out of range");
+    worker.terminate();
+  },
+});
+
+Deno.test({
+  name: "worker SharedArrayBuffer",
+  fn: async function (): Promise<void> {
+    const result = deferred();
+    const worker = new Worker(
+      new URL("shared_array_buffer.ts", import.meta.url).href,
+      { type: "module" },
+    );
+    const sab = new SharedArrayBuffer(4);
+    const view = new Int32Array(sab);
+
+    worker.onmessage = (): void => {
+      // The worker wrote into the same memory rather than into a copy.
+      assertEquals(Atomics.load(view, 0), 42);
+      result.resolve();
+    };
+
+    worker.postMessage(sab);
+    await result;
+    worker.terminate();
+  },
+});
+
+Deno.test("Worker exceeding its heap limit", async function () {
+  const promise = deferred<ErrorEvent>();
+  const worker = new Worker(
+    new URL("./heap_limit_worker.js", import.meta.url).href,
+    {
+      type: "module",
+      deno: {
+        resourceLimits: {
+          maxHeapSizeMb: 32,
+        },
+      },
+    },
+  );
+
+  worker.onerror = (e) => {
+    e.preventDefault();
+    promise.resolve(e);
+  };
+
+  const event = await promise;
+  assertEquals(
+    event.message,
+    "Worker terminated: exceeded its heap limit of 32 MB",
+  );
+  worker.terminate();
+});
+
+Deno.test("Worker with resource limits too large", function () {
+  for (const limit of ["maxHeapSizeMb", "stackSizeMb"]) {
+    assertThrows(
+      () =>
+        new Worker(
+          new URL("./heap_limit_worker.js", import.meta.url).href,
+          {
+            type: "module",
+            deno: {
+              resourceLimits: { [limit]: Number.MAX_SAFE_INTEGER },
+            },
+          },
+        ),
+      TypeError,
+      "is too large",
+    );
+  }
+});
+
+Deno.test({
+  name: "worker Atomics.waitAsync",
+  fn: async function (): Promise<void> {
+    const worker = new Worker(
+      new URL("atomics_notify_worker.ts", import.meta.url).href,
+      { type: "module" },
+    );
+    const view = new Int32Array(new SharedArrayBuffer(4));
+    const result = Atomics.waitAsync(view, 0, 0);
+    assert(result.async);
+
+    worker.postMessage(view.buffer);
+    assertEquals(await result.value, "ok");
+    assertEquals(Atomics.load(view, 0), 1);
+    worker.terminate();
+  },
+});
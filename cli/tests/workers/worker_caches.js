@@ -0,0 +1,5 @@
+onmessage = async function () {
+  const cache = await caches.open("worker");
+  await cache.put("https://deno.land/worker", new Response("from worker"));
+  postMessage(null);
+};
@@ -0,0 +1,5 @@
+self.onmessage = (e: MessageEvent) => {
+  const cause = new RangeError("out of range");
+  const error = Object.assign(new TypeError("from worker"), { cause });
+  self.postMessage({ received: e.data, error });
+};
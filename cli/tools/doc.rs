@@ -108,6 +108,7 @@ pub async fn print_docs(
       maybe_types: None,
       media_type: MediaType::TypeScript,
       source: format!("export * from \"{}\";", module_specifier),
+      maybe_raw_source: None,
       specifier: root_specifier.clone(),
     };
 
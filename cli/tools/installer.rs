@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 use crate::flags::Flags;
+use crate::flags::TypeCheckMode;
 use crate::fs_util::canonicalize_path;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
@@ -200,6 +201,10 @@ pub fn install(
     executable_args.push("--cert".to_string());
     executable_args.push(ca_file)
   }
+  if let Some(proxy) = flags.proxy {
+    executable_args.push("--proxy".to_string());
+    executable_args.push(proxy)
+  }
   if let Some(log_level) = flags.log_level {
     if log_level == Level::Error {
       executable_args.push("--quiet".to_string());
@@ -216,8 +221,12 @@ pub fn install(
     }
   }
 
-  if flags.no_check {
-    executable_args.push("--no-check".to_string());
+  match flags.typecheck_mode {
+    TypeCheckMode::All => (),
+    TypeCheckMode::None => executable_args.push("--no-check".to_string()),
+    TypeCheckMode::Local => {
+      executable_args.push("--no-check=remote".to_string())
+    }
   }
 
   if flags.unstable {
@@ -637,7 +646,7 @@ mod tests {
       Flags {
         allow_net: Some(vec![]),
         allow_read: Some(vec![]),
-        no_check: true,
+        typecheck_mode: TypeCheckMode::None,
         log_level: Some(Level::Error),
         ..Flags::default()
       },
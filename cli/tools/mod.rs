@@ -5,6 +5,7 @@ pub mod doc;
 pub mod fmt;
 pub mod installer;
 pub mod lint;
+pub mod profiler;
 pub mod repl;
 pub mod standalone;
 pub mod test_runner;
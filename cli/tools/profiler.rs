@@ -0,0 +1,114 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use deno_core::LocalInspectorSession;
+use std::fs;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// Runs the V8 CPU profiler for the lifetime of a program started with
+/// `deno run --prof`.
+pub struct CpuProfiler {
+  pub dir: PathBuf,
+  session: LocalInspectorSession,
+}
+
+impl CpuProfiler {
+  pub fn new(dir: PathBuf, session: LocalInspectorSession) -> Self {
+    Self { dir, session }
+  }
+
+  pub async fn start_profiling(&mut self) -> Result<(), AnyError> {
+    self.session.post_message("Profiler.enable", None).await?;
+    self.session.post_message("Profiler.start", None).await?;
+    Ok(())
+  }
+
+  /// Stops the profiler and writes the profile to a
+  /// `CPU.<timestamp>.<pid>.cpuprofile` file, whose path is returned.
+  pub async fn stop_profiling(&mut self) -> Result<PathBuf, AnyError> {
+    let result = self.session.post_message("Profiler.stop", None).await?;
+
+    fs::create_dir_all(&self.dir)?;
+
+    let path = self.dir.join(profile_filename("CPU", "cpuprofile"));
+    let json = serde_json::to_string(&result["profile"])?;
+    fs::write(&path, &json)?;
+
+    self.session.post_message("Profiler.disable", None).await?;
+
+    Ok(path)
+  }
+}
+
+/// Writes a heap snapshot to a `Heap.<timestamp>.<pid>.heapsnapshot` file in
+/// `dir` the first time the heap of the isolate approaches its limit, for
+/// `deno run --heap-snapshot-on-oom`.
+pub fn write_heap_snapshot_on_oom(js_runtime: &mut JsRuntime, dir: PathBuf) {
+  let isolate: *mut v8::Isolate = &mut **js_runtime.v8_isolate();
+  let mut written = false;
+  js_runtime.add_near_heap_limit_callback(
+    move |current_heap_limit, initial_heap_limit| {
+      if written {
+        return current_heap_limit;
+      }
+      written = true;
+
+      let path = dir.join(profile_filename("Heap", "heapsnapshot"));
+      eprintln!(
+        "The heap limit is about to be reached, writing a heap snapshot to {}",
+        path.display()
+      );
+      // The callback is called by V8 on the thread of the isolate, which is
+      // owned by `js_runtime` for as long as the callback is registered.
+      let isolate = unsafe { &mut *isolate };
+      if let Err(err) = write_heap_snapshot(isolate, &path) {
+        eprintln!("Failed to write the heap snapshot: {}", err);
+      }
+      // Leave some room for the program to report the error itself.
+      current_heap_limit + initial_heap_limit / 2
+    },
+  );
+}
+
+fn write_heap_snapshot(
+  isolate: &mut v8::Isolate,
+  path: &Path,
+) -> Result<(), AnyError> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  let mut file = BufWriter::new(fs::File::create(path)?);
+  let mut result = Ok(());
+  isolate.take_heap_snapshot(|chunk| match file.write_all(chunk) {
+    Ok(()) => true,
+    Err(err) => {
+      result = Err(err);
+      false
+    }
+  });
+  result?;
+  file.flush()?;
+  Ok(())
+}
+
+fn profile_filename(prefix: &str, extension: &str) -> String {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or_default();
+  format!(
+    "{}.{}.{}.{}",
+    prefix,
+    timestamp,
+    std::process::id(),
+    extension
+  )
+}
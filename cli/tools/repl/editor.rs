@@ -0,0 +1,1596 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use super::session::ReplCommand;
+use super::theme::paint;
+use super::theme::ReplTheme;
+use crate::ast;
+use crate::ast::TokenOrComment;
+use crate::colors;
+use crate::http_cache::HttpCache;
+use crate::media_type::MediaType;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use rustyline::completion::Completer;
+use rustyline::completion::Pair;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::History;
+use rustyline::validate::ValidationContext;
+use rustyline::validate::ValidationResult;
+use rustyline::validate::Validator;
+use rustyline::Cmd;
+use rustyline::CompletionType;
+use rustyline::Config;
+use rustyline::Context;
+use rustyline::Editor;
+use rustyline::KeyEvent;
+use rustyline_derive::Helper;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::SyncSender;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+use swc_ecmascript::parser::token::{Token, Word};
+
+// Provides helpers to the editor like validation for multi-line edits, completion candidates for
+// tab completion.
+#[derive(Helper)]
+pub struct EditorHelper {
+  pub context_id: u64,
+  pub message_tx: SyncSender<(String, Option<Value>)>,
+  pub response_rx: Receiver<Result<Value, AnyError>>,
+  /// Used to complete the remote modules that have already been downloaded.
+  pub http_cache: HttpCache,
+  /// The last line which was eagerly evaluated and its preview, so that
+  /// redrawing an unchanged line doesn't post another message to the
+  /// inspector.
+  pub last_preview: Mutex<Option<(String, Option<String>)>>,
+  /// The options and media type the line is transpiled with before it is
+  /// eagerly evaluated, the same as the session's.
+  pub emit_options: ast::EmitOptions,
+  pub media_type: MediaType,
+  /// Whether incomplete input is continued at a continuation prompt rather
+  /// than on a new line of the same buffer, which is set by `ReplEditor`.
+  pub line_by_line: bool,
+  pub state: Arc<Mutex<EditorState>>,
+  pub activity: Arc<EditorActivity>,
+  pub theme: ReplTheme,
+}
+
+/// What the line editor shows while it reads input, so that a permission
+/// prompt issued in the meantime can redraw it.
+#[derive(Default)]
+pub struct EditorState {
+  /// The prompt of the input being read, `None` while no input is read.
+  pub prompt: Option<String>,
+  pub line: String,
+  pub pos: usize,
+  /// Set while the answer to a permission prompt is read, during which the
+  /// event loop that would answer the helper's messages is blocked.
+  pub reading_permission_answer: bool,
+  /// The text searched for while the history is searched incrementally.
+  search_query: Option<String>,
+}
+
+/// Signals the release of the line editor and the messages of its helper to a
+/// permission prompt which waits for the editor.
+#[derive(Default)]
+pub struct EditorActivity {
+  generation: Mutex<u64>,
+  changed: Condvar,
+}
+
+impl EditorActivity {
+  pub fn generation(&self) -> u64 {
+    *self.generation.lock().unwrap()
+  }
+
+  fn notify(&self) {
+    *self.generation.lock().unwrap() += 1;
+    self.changed.notify_all();
+  }
+
+  /// Blocks until `notify` is called after `generation` was read.
+  pub fn wait_after(&self, generation: u64) {
+    let current = self.generation.lock().unwrap();
+    drop(
+      self
+        .changed
+        .wait_while(current, |current| *current == generation)
+        .unwrap(),
+    );
+  }
+}
+
+impl EditorHelper {
+  fn post_message(
+    &self,
+    method: &str,
+    params: Option<Value>,
+  ) -> Result<Value, AnyError> {
+    if self.state.lock().unwrap().reading_permission_answer {
+      return Err(generic_error("A permission prompt is being answered."));
+    }
+    self.message_tx.send((method.to_string(), params))?;
+    self.activity.notify();
+    self.response_rx.recv()?
+  }
+
+  fn get_global_lexical_scope_names(&self) -> Vec<String> {
+    let evaluate_response = match self.post_message(
+      "Runtime.globalLexicalScopeNames",
+      Some(json!({
+        "executionContextId": self.context_id,
+      })),
+    ) {
+      Ok(evaluate_response) => evaluate_response,
+      Err(_) => return Vec::new(),
+    };
+
+    evaluate_response
+      .get("names")
+      .unwrap()
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|n| n.as_str().unwrap().to_string())
+      .collect()
+  }
+
+  /// Evaluates `line` without side effects and returns a preview of its
+  /// result, or `None` if it can't be evaluated that way.
+  fn get_eager_eval_preview(&self, line: &str) -> Option<String> {
+    // The line is transpiled like an input, so that nothing is evaluated
+    // while it doesn't parse.
+    let specifier = format!("$deno$repl{}", self.media_type.as_ts_extension());
+    let (expression, _) = ast::parse(&specifier, line, &self.media_type)
+      .ok()?
+      .transpile(&self.emit_options)
+      .ok()?;
+    let evaluate_response = self
+      .post_message(
+        "Runtime.evaluate",
+        Some(json!({
+          "contextId": self.context_id,
+          "expression": expression,
+          "throwOnSideEffect": true,
+          "timeout": 200,
+        })),
+      )
+      .ok()?;
+
+    if evaluate_response.get("exceptionDetails").is_some() {
+      return None;
+    }
+
+    format_eager_eval_preview(line, evaluate_response.get("result")?)
+  }
+
+  /// Returns the property descriptors of the object that `expr` evaluates
+  /// to, as reported by `Runtime.getProperties`.
+  fn get_expression_properties(&self, expr: &str) -> Vec<Value> {
+    let evaluate_response = match self.post_message(
+      "Runtime.evaluate",
+      Some(json!({
+        "contextId": self.context_id,
+        "expression": expr,
+        "throwOnSideEffect": true,
+        "timeout": 200,
+      })),
+    ) {
+      Ok(evaluate_response) => evaluate_response,
+      Err(_) => return Vec::new(),
+    };
+
+    if evaluate_response.get("exceptionDetails").is_some() {
+      return Vec::new();
+    }
+
+    if let Some(result) = evaluate_response.get("result") {
+      if let Some(object_id) = result.get("objectId") {
+        let get_properties_response = self.post_message(
+          "Runtime.getProperties",
+          Some(json!({
+            "objectId": object_id,
+          })),
+        );
+
+        if let Ok(get_properties_response) = get_properties_response {
+          if let Some(result) = get_properties_response.get("result") {
+            return result.as_array().unwrap().clone();
+          }
+        }
+      }
+    }
+
+    Vec::new()
+  }
+}
+
+/// If the cursor is inside the string of an `import ... from "..."`,
+/// `import "..."`, `export ... from "..."` or `import("...")`, returns the
+/// position where the string starts together with the part of the specifier
+/// that precedes the cursor.
+fn get_import_specifier_at_pos(
+  line: &str,
+  cursor_pos: usize,
+) -> Option<(usize, &str)> {
+  let before_cursor = &line[..cursor_pos];
+  let quote_pos = before_cursor.rfind(|c| c == '"' || c == '\'')?;
+  let quote = before_cursor[quote_pos..].chars().next().unwrap();
+  // An even number of quotes of the same kind before this one means this one
+  // opens a string rather than closing it.
+  if before_cursor[..quote_pos].matches(quote).count() % 2 != 0 {
+    return None;
+  }
+
+  let before_quote = before_cursor[..quote_pos].trim_end();
+  let is_import = before_quote.ends_with("from")
+    || before_quote.ends_with("import")
+    || before_quote.ends_with("import(");
+  if !is_import {
+    return None;
+  }
+
+  let start = quote_pos + 1;
+  Some((start, &before_cursor[start..]))
+}
+
+/// If the cursor is inside a single or double quoted string literal, returns
+/// the position where the contents of the string start together with the part
+/// of them that precedes the cursor.
+fn get_string_literal_at_pos(
+  line: &str,
+  cursor_pos: usize,
+) -> Option<(usize, &str)> {
+  let before_cursor = &line[..cursor_pos];
+  // Cut at the cursor, a string containing it is left unterminated, which the
+  // lexer reports as an error token starting at the opening quote.
+  let item = ast::lex("", before_cursor, &MediaType::TypeScript).pop()?;
+  let span = item.span_as_range();
+  match item.inner {
+    TokenOrComment::Token(Token::Error(_))
+      if before_cursor[span.start..].starts_with(|c| c == '"' || c == '\'') =>
+    {
+      let start = span.start + 1;
+      Some((start, &before_cursor[start..]))
+    }
+    _ => None,
+  }
+}
+
+/// Completes a path of a local file, relative to the current directory unless
+/// it is absolute, returning the position where the completed file name starts
+/// and the candidates.
+fn complete_local_path(
+  specifier_start: usize,
+  specifier: &str,
+) -> (usize, Vec<String>) {
+  let (dir, file_prefix) = match specifier.rfind('/') {
+    Some(index) => (&specifier[..index + 1], &specifier[index + 1..]),
+    None => ("", specifier),
+  };
+  let read_dir = if dir.is_empty() { "." } else { dir };
+  let entries = match std::fs::read_dir(read_dir) {
+    Ok(entries) => entries,
+    Err(_) => return (specifier_start, Vec::new()),
+  };
+
+  let mut candidates = entries
+    .filter_map(Result::ok)
+    .filter_map(|entry| {
+      let mut name = entry.file_name().to_str()?.to_string();
+      if !name.starts_with(file_prefix)
+        || (name.starts_with('.') && !file_prefix.starts_with('.'))
+      {
+        return None;
+      }
+      if entry.file_type().ok()?.is_dir() {
+        name.push('/');
+      }
+      Some(name)
+    })
+    .collect::<Vec<_>>();
+  candidates.sort();
+
+  (specifier_start + dir.len(), candidates)
+}
+
+/// A completion candidate which is displayed as it is inserted.
+fn plain_candidate(name: String) -> Pair {
+  Pair {
+    display: name.clone(),
+    replacement: name,
+  }
+}
+
+/// The number of the most recent history entries whose identifiers are
+/// preferred when ranking completion candidates.
+const RECENT_HISTORY_ENTRIES: usize = 20;
+
+/// Returns the identifiers used in the most recent history entries, the most
+/// recently used first.
+fn get_recent_identifiers(history: &History) -> Vec<String> {
+  let mut identifiers = Vec::new();
+  for entry in history.iter().rev().take(RECENT_HISTORY_ENTRIES) {
+    let mut entry_identifiers = Vec::new();
+    for item in ast::lex("", entry, &MediaType::TypeScript) {
+      if let TokenOrComment::Token(Token::Word(Word::Ident(ident))) = item.inner
+      {
+        entry_identifiers.push(ident.to_string());
+      }
+    }
+    // The last identifier of an entry is the most recently typed one.
+    for identifier in entry_identifiers.into_iter().rev() {
+      if !identifiers.contains(&identifier) {
+        identifiers.push(identifier);
+      }
+    }
+  }
+  identifiers
+}
+
+/// Scores how well `query` matches `candidate`, which it does when all of its
+/// characters appear in the candidate in order, ignoring case. Prefixes
+/// score the highest, followed by characters matched consecutively or at the
+/// start of a word, like the `L` of `addEventListener`.
+fn fuzzy_match_score(candidate: &str, query: &str) -> Option<i32> {
+  let mut query_chars = query.chars().peekable();
+  let mut score = 0;
+  let mut previous: Option<char> = None;
+  let mut previous_matched = false;
+
+  for c in candidate.chars() {
+    let query_char = match query_chars.peek() {
+      Some(query_char) => *query_char,
+      None => break,
+    };
+
+    if c.to_lowercase().eq(query_char.to_lowercase()) {
+      query_chars.next();
+      score += if c == query_char { 2 } else { 1 };
+      let is_word_start = match previous {
+        None => true,
+        Some(previous) => {
+          (previous.is_lowercase() && c.is_uppercase())
+            || (!previous.is_alphanumeric() && c.is_alphanumeric())
+        }
+      };
+      if previous_matched {
+        score += 4;
+      } else if is_word_start {
+        score += 6;
+      }
+      previous_matched = true;
+    } else {
+      // Characters skipped between the matched ones lower the score.
+      if previous.is_some() {
+        score -= 1;
+      }
+      previous_matched = false;
+    }
+    previous = Some(c);
+  }
+
+  if query_chars.peek().is_some() {
+    return None;
+  }
+  if candidate.starts_with(query) {
+    score += 100;
+  }
+  Some(score)
+}
+
+/// Keeps the candidates which match `query` and ranks them by how well they
+/// match, preferring the names declared in the REPL, which are flagged, over
+/// the properties of `globalThis` and recently used identifiers over others.
+/// Fuzzy matches are only kept when no candidate starts with the query, so
+/// that a unique prefix is still completed at once.
+fn rank_completion_candidates(
+  candidates: Vec<(Pair, bool)>,
+  query: &str,
+  recent_identifiers: &[String],
+) -> Vec<Pair> {
+  let has_prefix_match = candidates
+    .iter()
+    .any(|(candidate, _)| candidate.replacement.starts_with(query));
+  let mut ranked = candidates
+    .into_iter()
+    .filter(|(candidate, _)| {
+      !has_prefix_match || candidate.replacement.starts_with(query)
+    })
+    .filter_map(|(candidate, is_declared)| {
+      let mut score = fuzzy_match_score(&candidate.replacement, query)?;
+      if is_declared {
+        score += 20;
+      }
+      if let Some(index) = recent_identifiers
+        .iter()
+        .position(|identifier| *identifier == candidate.replacement)
+      {
+        score += 20 - index.min(10) as i32;
+      }
+      Some((score, candidate))
+    })
+    .collect::<Vec<_>>();
+
+  ranked.sort_by(|(a_score, a), (b_score, b)| {
+    b_score
+      .cmp(a_score)
+      .then_with(|| a.replacement.cmp(&b.replacement))
+  });
+  ranked.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Makes the completion candidate of a property descriptor, displayed with a
+/// hint of its value like `map(callback, thisArg)` for a function or
+/// `length: number` for any other value, while only its name is inserted.
+/// Symbol keyed properties can't be completed after a dot and are skipped.
+fn format_completion_candidate(property: &Value) -> Option<Pair> {
+  let name = property.get("name")?.as_str()?;
+  if name.starts_with("Symbol(") {
+    return None;
+  }
+
+  // Accessor properties aren't invoked to find out the type of their value.
+  let display = match property.get("value") {
+    Some(value) => match value.get("type")?.as_str()? {
+      "function" => {
+        let description = value
+          .get("description")
+          .and_then(|d| d.as_str())
+          .unwrap_or("");
+        if description.starts_with("class") {
+          format!("{}: class", name)
+        } else {
+          format!("{}({})", name, get_function_parameters(description))
+        }
+      }
+      "object" => {
+        let class_name = match value.get("subtype").and_then(|s| s.as_str()) {
+          Some("null") => "null",
+          _ => value
+            .get("className")
+            .and_then(|c| c.as_str())
+            .unwrap_or("Object"),
+        };
+        format!("{}: {}", name, class_name)
+      }
+      value_type => format!("{}: {}", name, value_type),
+    },
+    None => name.to_string(),
+  };
+
+  Some(Pair {
+    display,
+    replacement: name.to_string(),
+  })
+}
+
+/// Extracts the parameter list from the source of a function, which is how
+/// the inspector describes them. Built-in functions have no source to take
+/// their parameters from, so they are shown as `...`.
+fn get_function_parameters(description: &str) -> String {
+  if description.contains("[native code]") {
+    return "...".to_string();
+  }
+
+  let arrow_pos = description.find("=>");
+  let parameters = match description.find('(') {
+    Some(start) if arrow_pos.map_or(true, |arrow_pos| start < arrow_pos) => {
+      let mut depth = 0;
+      let end = description[start..].char_indices().find_map(|(i, c)| {
+        match c {
+          '(' => depth += 1,
+          ')' => depth -= 1,
+          _ => {}
+        }
+        Some(start + i).filter(|_| depth == 0)
+      });
+      match end {
+        Some(end) => &description[start + 1..end],
+        None => return "...".to_string(),
+      }
+    }
+    // An arrow function with a single parameter, like `x => x * 2`.
+    _ => match arrow_pos {
+      Some(arrow_pos) => description[..arrow_pos].trim_start_matches("async "),
+      None => return "...".to_string(),
+    },
+  };
+
+  parameters.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The longest preview shown while typing, in characters.
+const MAX_PREVIEW_LENGTH: usize = 100;
+
+/// How long no key must be typed before the line is eagerly evaluated, so
+/// that typing quickly doesn't evaluate the line after every key.
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Renders the remote object resulting from eagerly evaluating `line` as a
+/// one line preview. Nothing is shown when it wouldn't tell the user anything
+/// new, like for `undefined` or when the line is the literal being previewed.
+fn format_eager_eval_preview(line: &str, result: &Value) -> Option<String> {
+  let value = result.get("value");
+  let preview = match result.get("type")?.as_str()? {
+    "undefined" => return None,
+    "function" => "[Function]".to_string(),
+    "string" => serde_json::to_string(value?).ok()?,
+    _ => match result.get("description").and_then(|d| d.as_str()) {
+      Some(description) => description.to_string(),
+      None => value?.to_string(),
+    },
+  };
+
+  let preview = preview.lines().next().unwrap_or("");
+  if preview.is_empty() || preview == line.trim() {
+    return None;
+  }
+
+  if preview.chars().count() > MAX_PREVIEW_LENGTH {
+    let truncated =
+      preview.chars().take(MAX_PREVIEW_LENGTH).collect::<String>();
+    Some(format!("{}…", truncated))
+  } else {
+    Some(preview.to_string())
+  }
+}
+
+fn is_word_boundary(c: char) -> bool {
+  if c == '.' {
+    false
+  } else {
+    char::is_ascii_whitespace(&c) || char::is_ascii_punctuation(&c)
+  }
+}
+
+fn get_expr_from_line_at_pos(line: &str, cursor_pos: usize) -> &str {
+  let start = line[..cursor_pos]
+    .rfind(is_word_boundary)
+    .map_or_else(|| 0, |i| i);
+  let end = line[cursor_pos..]
+    .rfind(is_word_boundary)
+    .map_or_else(|| cursor_pos, |i| cursor_pos + i);
+
+  let word = &line[start..end];
+  let word = word.strip_prefix(is_word_boundary).unwrap_or(word);
+  let word = word.strip_suffix(is_word_boundary).unwrap_or(word);
+
+  word
+}
+
+impl Completer for EditorHelper {
+  type Candidate = Pair;
+
+  fn complete(
+    &self,
+    line: &str,
+    pos: usize,
+    ctx: &Context<'_>,
+  ) -> Result<(usize, Vec<Pair>), ReadlineError> {
+    if let Some((start, specifier)) = get_import_specifier_at_pos(line, pos) {
+      if specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with('/')
+      {
+        let (start, names) = complete_local_path(start, specifier);
+        return Ok((start, names.into_iter().map(plain_candidate).collect()));
+      }
+
+      let candidates = self
+        .http_cache
+        .urls()
+        .into_iter()
+        .filter(|url| url.starts_with(specifier))
+        .map(plain_candidate)
+        .collect();
+      return Ok((start, candidates));
+    }
+
+    if let Some((start, path)) = get_string_literal_at_pos(line, pos) {
+      let (start, names) = complete_local_path(start, path);
+      return Ok((start, names.into_iter().map(plain_candidate).collect()));
+    }
+
+    let expr = get_expr_from_line_at_pos(line, pos);
+    let recent_identifiers = get_recent_identifiers(ctx.history());
+
+    // check if the expression is in the form `obj.prop`
+    if let Some(index) = expr.rfind('.') {
+      let sub_expr = &expr[..index];
+      let prop_name = &expr[index + 1..];
+      let candidates = self
+        .get_expression_properties(sub_expr)
+        .iter()
+        .filter_map(format_completion_candidate)
+        .map(|c| (c, false))
+        .collect();
+
+      Ok((
+        pos - prop_name.len(),
+        rank_completion_candidates(candidates, prop_name, &recent_identifiers),
+      ))
+    } else {
+      // combine results of declarations and globalThis properties, the
+      // latter coming first so that their hints are kept when deduplicating
+      let mut names = HashSet::new();
+      let candidates = self
+        .get_expression_properties("globalThis")
+        .iter()
+        .filter_map(format_completion_candidate)
+        .map(|c| (c, false))
+        .chain(
+          self
+            .get_global_lexical_scope_names()
+            .into_iter()
+            .map(|name| (plain_candidate(name), true)),
+        )
+        .filter(|(c, _)| names.insert(c.replacement.clone()))
+        .collect();
+
+      Ok((
+        pos - expr.len(),
+        rank_completion_candidates(candidates, expr, &recent_identifiers),
+      ))
+    }
+  }
+}
+
+impl Hinter for EditorHelper {
+  fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+    {
+      let mut state = self.state.lock().unwrap();
+      state.line = line.to_string();
+      state.pos = pos;
+    }
+
+    // Only preview single line input while the cursor is at its end, and
+    // not while typing a REPL command or an import specifier.
+    if pos != line.len()
+      || line.trim().is_empty()
+      || line.contains('\n')
+      || ReplCommand::parse(line).is_some()
+      || get_import_specifier_at_pos(line, pos).is_some()
+    {
+      return None;
+    }
+
+    let mut last_preview = self.last_preview.lock().unwrap();
+    if let Some((last_line, preview)) = last_preview.as_ref() {
+      if last_line == line {
+        return preview.as_ref().map(|preview| format!("\n{}", preview));
+      }
+    }
+
+    if wait_for_key(PREVIEW_DEBOUNCE) {
+      return None;
+    }
+
+    let preview = self.get_eager_eval_preview(line);
+    *last_preview = Some((line.to_string(), preview.clone()));
+    preview.map(|preview| format!("\n{}", preview))
+  }
+}
+
+impl Validator for EditorHelper {
+  fn validate(
+    &self,
+    ctx: &mut ValidationContext,
+  ) -> Result<ValidationResult, ReadlineError> {
+    if self.line_by_line {
+      return Ok(ValidationResult::Valid(None));
+    }
+    Ok(validate_input(ctx.input()))
+  }
+}
+
+/// Checks whether `input` is complete, or whether more lines are needed
+/// before it can be evaluated, like in an unclosed block.
+pub fn validate_input(input: &str) -> ValidationResult {
+  pair_brackets(input, |_, _| {})
+}
+
+/// Pairs up the brackets of `input` with a stack of the ones which are open,
+/// calling `on_pair` with the positions of each opening bracket and of the
+/// bracket closing it. Returns whether the input is complete, which it isn't
+/// while some bracket or template is left open.
+fn pair_brackets(
+  input: &str,
+  mut on_pair: impl FnMut(usize, usize),
+) -> ValidationResult {
+  let mut stack: Vec<(Token, usize)> = Vec::new();
+  let mut in_template = false;
+
+  for item in ast::lex("", input, &MediaType::TypeScript) {
+    // The brace of a `${` is its last character.
+    let pos = item.span_as_range().end - 1;
+    if let TokenOrComment::Token(token) = item.inner {
+      match token {
+        Token::BackQuote => in_template = !in_template,
+        Token::LParen
+        | Token::LBracket
+        | Token::LBrace
+        | Token::DollarLBrace => stack.push((token, pos)),
+        Token::RParen | Token::RBracket | Token::RBrace => {
+          match (stack.pop(), token) {
+            (Some((Token::LParen, start)), Token::RParen)
+            | (Some((Token::LBracket, start)), Token::RBracket)
+            | (Some((Token::LBrace, start)), Token::RBrace)
+            | (Some((Token::DollarLBrace, start)), Token::RBrace) => {
+              on_pair(start, pos)
+            }
+            (Some((left, _)), _) => {
+              return ValidationResult::Invalid(Some(format!(
+                "Mismatched pairs: {:?} is not properly closed",
+                left
+              )))
+            }
+            (None, _) => {
+              // While technically invalid when unpaired, it should be V8's task to output error instead.
+              // Thus marked as valid with no info.
+              return ValidationResult::Valid(None);
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  if !stack.is_empty() || in_template {
+    return ValidationResult::Incomplete;
+  }
+
+  ValidationResult::Valid(None)
+}
+
+/// Returns the positions of the bracket at the cursor and of the bracket it
+/// pairs with, preferring the one just before the cursor like after typing a
+/// closing bracket.
+fn find_matching_brackets(line: &str, pos: usize) -> Option<(usize, usize)> {
+  let mut pairs = Vec::new();
+  pair_brackets(line, |start, end| pairs.push((start, end)));
+
+  let find_pair = |pos: usize| {
+    pairs
+      .iter()
+      .copied()
+      .find(|(start, end)| *start == pos || *end == pos)
+  };
+  pos
+    .checked_sub(1)
+    .and_then(find_pair)
+    .or_else(|| find_pair(pos))
+}
+
+/// Returns the text searched for from the prompt of an incremental history
+/// search, like `(reverse-i-search)`foo': `.
+fn get_search_query(prompt: &str) -> Option<&str> {
+  let query = prompt
+    .strip_prefix("(reverse-i-search)`")
+    .or_else(|| prompt.strip_prefix("(failed reverse-i-search)`"))
+    .or_else(|| prompt.strip_prefix("(i-search)`"))
+    .or_else(|| prompt.strip_prefix("(failed i-search)`"))?;
+  query.strip_suffix("': ")
+}
+
+/// Returns the range of the line matched by a history search, which the
+/// cursor is moved to the start of.
+fn find_search_match(
+  line: &str,
+  pos: usize,
+  query: &str,
+) -> Option<std::ops::Range<usize>> {
+  if query.is_empty() {
+    return None;
+  }
+  let start = if line.get(pos..)?.starts_with(query) {
+    pos
+  } else {
+    line.find(query)?
+  };
+  Some(start..start + query.len())
+}
+
+impl Highlighter for EditorHelper {
+  fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+    &'s self,
+    prompt: &'p str,
+    _default: bool,
+  ) -> Cow<'b, str> {
+    // The prompt is highlighted before the line, which shows the match of
+    // the query while searching the history.
+    self.state.lock().unwrap().search_query =
+      get_search_query(prompt).map(ToOwned::to_owned);
+    prompt.into()
+  }
+
+  fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+    colors::gray(hint).to_string().into()
+  }
+
+  fn highlight_candidate<'c>(
+    &self,
+    candidate: &'c str,
+    _completion: rustyline::CompletionType,
+  ) -> Cow<'c, str> {
+    self.highlight(candidate, 0)
+  }
+
+  fn highlight_char(&self, line: &str, _: usize) -> bool {
+    colors::use_color() && !line.is_empty()
+  }
+
+  fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+    if !colors::use_color() {
+      return line.into();
+    }
+
+    let theme = &self.theme;
+    let maybe_search_query = self.state.lock().unwrap().search_query.clone();
+    if let Some(query) = maybe_search_query {
+      if let Some(range) = find_search_match(line, pos, &query) {
+        return format!(
+          "{}{}{}",
+          &line[..range.start],
+          paint(theme.search_match, &line[range.clone()]),
+          &line[range.end..]
+        )
+        .into();
+      }
+    }
+
+    let mut out_line = String::from(line);
+    let maybe_brackets = find_matching_brackets(line, pos);
+
+    for item in ast::lex("", line, &MediaType::TypeScript) {
+      // Adding color adds more bytes to the string,
+      // so an offset is needed to stop spans falling out of sync.
+      let offset = out_line.len() - line.len();
+      let span = item.span_as_range();
+
+      out_line.replace_range(
+        span.start + offset..span.end + offset,
+        &match item.inner {
+          TokenOrComment::Token(token) => match token {
+            Token::Str { .. } | Token::Template { .. } | Token::BackQuote => {
+              paint(theme.string, &line[span])
+            }
+            Token::Regex(_, _) => paint(theme.regexp, &line[span]),
+            Token::Num(_) | Token::BigInt(_) => {
+              paint(theme.number, &line[span])
+            }
+            Token::Word(word) => match word {
+              Word::True | Word::False | Word::Null => {
+                paint(theme.literal, &line[span])
+              }
+              Word::Keyword(_) => paint(theme.keyword, &line[span]),
+              Word::Ident(ident) => {
+                if ident == *"undefined" {
+                  paint(theme.undefined, &line[span])
+                } else if ident == *"Infinity" || ident == *"NaN" {
+                  paint(theme.literal, &line[span])
+                } else if ident == *"async" || ident == *"of" {
+                  paint(theme.keyword, &line[span])
+                } else {
+                  paint(theme.identifier, &line[span])
+                }
+              }
+            },
+            _ => match maybe_brackets {
+              Some((start, end))
+                if span.contains(&start) || span.contains(&end) =>
+              {
+                paint(theme.bracket, &line[span])
+              }
+              _ => line[span].to_string(),
+            },
+          },
+          TokenOrComment::Comment { .. } => paint(theme.comment, &line[span]),
+        },
+      );
+    }
+
+    out_line.into()
+  }
+}
+
+static DENO_REPL_HISTORY: &str = "DENO_REPL_HISTORY";
+static DENO_REPL_HISTORY_SIZE: &str = "DENO_REPL_HISTORY_SIZE";
+const DEFAULT_HISTORY_SIZE: usize = 100;
+static DENO_REPL_SEARCH_KEY: &str = "DENO_REPL_SEARCH_KEY";
+static DENO_REPL_PROMPT: &str = "DENO_REPL_PROMPT";
+static DENO_REPL_CONTINUATION_PROMPT: &str = "DENO_REPL_CONTINUATION_PROMPT";
+static DEFAULT_PROMPT: &str = "> ";
+
+/// Return the path of the history file, which can be configured with the
+/// `DENO_REPL_HISTORY` environment variable. An empty value disables the
+/// history.
+fn get_history_file_path(
+  maybe_value: Option<String>,
+  default_path: PathBuf,
+) -> Option<PathBuf> {
+  match maybe_value {
+    Some(value) if value.is_empty() => None,
+    Some(value) => Some(PathBuf::from(value)),
+    None => Some(default_path),
+  }
+}
+
+/// Return the maximum number of entries kept in the history, which can be
+/// configured with the `DENO_REPL_HISTORY_SIZE` environment variable.
+fn get_history_size(maybe_value: Option<String>) -> usize {
+  maybe_value
+    .and_then(|value| value.parse::<usize>().ok())
+    .unwrap_or(DEFAULT_HISTORY_SIZE)
+}
+
+/// Returns the key which starts a reverse search of the history, which can be
+/// configured with the `DENO_REPL_SEARCH_KEY` environment variable as
+/// `ctrl-<key>` or `alt-<key>`. An empty value disables the search.
+fn get_search_key(maybe_value: Option<String>) -> Option<KeyEvent> {
+  let default_key = Some(KeyEvent::ctrl('R'));
+  let value = match maybe_value {
+    Some(value) if value.is_empty() => return None,
+    Some(value) => value.to_ascii_lowercase(),
+    None => return default_key,
+  };
+
+  let (modifier, key) = match value.split_once('-') {
+    Some((modifier, key)) if key.chars().count() == 1 => {
+      (modifier, key.chars().next().unwrap())
+    }
+    _ => return default_key,
+  };
+  match modifier {
+    "ctrl" => Some(KeyEvent::ctrl(key.to_ascii_uppercase())),
+    "alt" => Some(KeyEvent::alt(key)),
+    _ => default_key,
+  }
+}
+
+/// Replaces `{line}` in a prompt with the number of the line it is shown
+/// for, counting every line entered in the session.
+fn format_prompt(prompt: &str, line_number: usize) -> String {
+  prompt.replace("{line}", &line_number.to_string())
+}
+
+#[derive(Clone)]
+pub struct ReplEditor {
+  pub inner: Arc<Mutex<Editor<EditorHelper>>>,
+  /// `None` when the history is disabled.
+  history_file_path: Option<PathBuf>,
+  prompt: String,
+  /// Without a continuation prompt, incomplete input is continued on a new
+  /// line of the same multi-line buffer.
+  maybe_continuation_prompt: Option<String>,
+  /// The number of lines entered so far.
+  line_count: Arc<AtomicUsize>,
+  pub state: Arc<Mutex<EditorState>>,
+  pub activity: Arc<EditorActivity>,
+}
+
+impl ReplEditor {
+  pub fn new(
+    mut helper: EditorHelper,
+    default_history_file_path: PathBuf,
+  ) -> Self {
+    let history_file_path = get_history_file_path(
+      env::var(DENO_REPL_HISTORY).ok(),
+      default_history_file_path,
+    );
+    let prompt =
+      env::var(DENO_REPL_PROMPT).unwrap_or_else(|_| DEFAULT_PROMPT.to_string());
+    let maybe_continuation_prompt = env::var(DENO_REPL_CONTINUATION_PROMPT)
+      .ok()
+      .filter(|prompt| !prompt.is_empty());
+    helper.line_by_line = maybe_continuation_prompt.is_some();
+    let state = helper.state.clone();
+    let activity = helper.activity.clone();
+
+    // With bracketed paste a pasted block is inserted as a whole, so that it
+    // is validated and evaluated once rather than line by line.
+    let editor_config = Config::builder()
+      .completion_type(CompletionType::List)
+      .bracketed_paste(true)
+      .max_history_size(get_history_size(env::var(DENO_REPL_HISTORY_SIZE).ok()))
+      .build();
+
+    let mut editor = Editor::with_config(editor_config);
+    editor.set_helper(Some(helper));
+    let default_search_key = KeyEvent::ctrl('R');
+    match get_search_key(env::var(DENO_REPL_SEARCH_KEY).ok()) {
+      Some(search_key) if search_key == default_search_key => {}
+      maybe_search_key => {
+        editor.bind_sequence(default_search_key, Cmd::Noop);
+        if let Some(search_key) = maybe_search_key {
+          editor.bind_sequence(search_key, Cmd::ReverseSearchHistory);
+        }
+      }
+    }
+    if let Some(history_file_path) = &history_file_path {
+      if let Ok(contents) = std::fs::read_to_string(history_file_path) {
+        for entry in parse_history_file(&contents) {
+          editor.add_history_entry(entry);
+        }
+      }
+    }
+
+    ReplEditor {
+      inner: Arc::new(Mutex::new(editor)),
+      history_file_path,
+      prompt,
+      maybe_continuation_prompt,
+      line_count: Arc::new(AtomicUsize::new(0)),
+      state,
+      activity,
+    }
+  }
+
+  pub fn readline(&self) -> Result<String, ReadlineError> {
+    let result = self.readline_and_continue(&mut self.inner.lock().unwrap());
+    self.activity.notify();
+    result
+  }
+
+  fn readline_and_continue(
+    &self,
+    editor: &mut Editor<EditorHelper>,
+  ) -> Result<String, ReadlineError> {
+    let mut input = self.readline_with_prompt(editor, &self.prompt)?;
+    if let Some(continuation_prompt) = &self.maybe_continuation_prompt {
+      while matches!(validate_input(&input), ValidationResult::Incomplete) {
+        let line = self.readline_with_prompt(editor, continuation_prompt)?;
+        input.push('\n');
+        input.push_str(&line);
+      }
+    }
+
+    Ok(input)
+  }
+
+  fn readline_with_prompt(
+    &self,
+    editor: &mut Editor<EditorHelper>,
+    prompt: &str,
+  ) -> Result<String, ReadlineError> {
+    let line_number = self.line_count.load(Ordering::SeqCst) + 1;
+    let prompt = format_prompt(prompt, line_number);
+    *self.state.lock().unwrap() = EditorState {
+      prompt: Some(prompt.clone()),
+      ..Default::default()
+    };
+    let result = editor.readline(&prompt);
+    self.state.lock().unwrap().prompt = None;
+    let line = result?;
+    self
+      .line_count
+      .fetch_add(line.lines().count().max(1), Ordering::SeqCst);
+    Ok(line)
+  }
+
+  pub fn add_history_entry(&self, entry: String) {
+    self.inner.lock().unwrap().add_history_entry(entry);
+    self.activity.notify();
+  }
+
+  pub fn save_history(&self) -> Result<(), AnyError> {
+    let history_file_path = match &self.history_file_path {
+      Some(history_file_path) => history_file_path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = history_file_path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+
+    let contents = format_history_file(
+      self
+        .inner
+        .lock()
+        .unwrap()
+        .history()
+        .iter()
+        .map(String::as_str),
+    );
+    std::fs::write(history_file_path, contents)?;
+    Ok(())
+  }
+}
+
+/// The first line of a history file in which the newlines and backslashes
+/// of the entries are escaped, so that a multi-line entry takes one line.
+static HISTORY_FILE_HEADER: &str = "#deno_repl_history_v2";
+
+fn format_history_file<'a>(entries: impl Iterator<Item = &'a str>) -> String {
+  let mut contents = format!("{}\n", HISTORY_FILE_HEADER);
+  for entry in entries {
+    contents.push_str(&entry.replace('\\', "\\\\").replace('\n', "\\n"));
+    contents.push('\n');
+  }
+  contents
+}
+
+/// Returns the entries of a history file. A file without the header, which
+/// was written before entries were escaped, has an entry on every line.
+fn parse_history_file(contents: &str) -> Vec<String> {
+  let mut lines = contents.lines();
+  if !contents.starts_with(HISTORY_FILE_HEADER) {
+    return lines.map(ToOwned::to_owned).collect();
+  }
+  lines.next();
+
+  lines
+    .map(|line| {
+      let mut entry = String::with_capacity(line.len());
+      let mut chars = line.chars();
+      while let Some(c) = chars.next() {
+        match c {
+          '\\' => match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some(escaped) => entry.push(escaped),
+            None => entry.push('\\'),
+          },
+          c => entry.push(c),
+        }
+      }
+      entry
+    })
+    .collect()
+}
+
+/// Waits up to `timeout` for a key to be typed, without reading it, and
+/// returns whether one was.
+#[cfg(unix)]
+fn wait_for_key(timeout: Duration) -> bool {
+  let mut stdin = libc::pollfd {
+    fd: libc::STDIN_FILENO,
+    events: libc::POLLIN,
+    revents: 0,
+  };
+  unsafe { libc::poll(&mut stdin, 1, timeout.as_millis() as libc::c_int) > 0 }
+}
+
+/// Waits up to `timeout` for a key to be pressed in the console, without
+/// reading it, and returns whether one was. The console also reports other
+/// events, like releasing a key, which are ignored.
+#[cfg(windows)]
+fn wait_for_key(timeout: Duration) -> bool {
+  use std::time::Instant;
+  use winapi::um::consoleapi::PeekConsoleInputW;
+  use winapi::um::processenv::GetStdHandle;
+  use winapi::um::winbase::STD_INPUT_HANDLE;
+  use winapi::um::wincon::INPUT_RECORD;
+  use winapi::um::wincon::KEY_EVENT;
+
+  let deadline = Instant::now() + timeout;
+  unsafe {
+    let handle = GetStdHandle(STD_INPUT_HANDLE);
+    loop {
+      let mut records: [INPUT_RECORD; 16] = std::mem::zeroed();
+      let mut count = 0;
+      if PeekConsoleInputW(handle, records.as_mut_ptr(), 16, &mut count) == 0 {
+        return false;
+      }
+      let key_pressed = records[..count as usize].iter().any(|record| {
+        record.EventType == KEY_EVENT && record.Event.KeyEvent().bKeyDown != 0
+      });
+      if key_pressed {
+        return true;
+      }
+      if Instant::now() >= deadline {
+        return false;
+      }
+      std::thread::sleep(Duration::from_millis(10));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn import_specifier_at_pos() {
+    let line = r#"import { serve } from "https://deno.land/std"#;
+    assert_eq!(
+      get_import_specifier_at_pos(line, line.len()),
+      Some((23, "https://deno.land/std"))
+    );
+    let line = "import './mod";
+    assert_eq!(
+      get_import_specifier_at_pos(line, line.len()),
+      Some((8, "./mod"))
+    );
+    let line = r#"const mod = await import("../"#;
+    assert_eq!(
+      get_import_specifier_at_pos(line, line.len()),
+      Some((26, "../"))
+    );
+    let line = r#"export * from "./"#;
+    assert_eq!(
+      get_import_specifier_at_pos(line, line.len()),
+      Some((15, "./"))
+    );
+  }
+
+  #[test]
+  fn import_specifier_at_pos_outside_import() {
+    let line = r#"console.log("./"#;
+    assert_eq!(get_import_specifier_at_pos(line, line.len()), None);
+    let line = r#"import { serve } from "./mod.ts";"#;
+    assert_eq!(get_import_specifier_at_pos(line, line.len()), None);
+    let line = "import { ser";
+    assert_eq!(get_import_specifier_at_pos(line, line.len()), None);
+  }
+
+  #[test]
+  fn string_literal_at_pos() {
+    let line = r#"await Deno.readTextFile("./cli/te"#;
+    assert_eq!(
+      get_string_literal_at_pos(line, line.len()),
+      Some((25, "./cli/te"))
+    );
+    let line = "Deno.readTextFile('README";
+    assert_eq!(
+      get_string_literal_at_pos(line, line.len()),
+      Some((19, "README"))
+    );
+    let line = r#"Deno.readTextFile("mod.ts")"#;
+    assert_eq!(get_string_literal_at_pos(line, 21), Some((19, "mo")));
+    assert_eq!(get_string_literal_at_pos(line, line.len()), None);
+    assert_eq!(get_string_literal_at_pos(line, 4), None);
+    let line = r#"["a", "b", Deno.c"#;
+    assert_eq!(get_string_literal_at_pos(line, line.len()), None);
+  }
+
+  #[test]
+  fn fuzzy_match() {
+    assert!(fuzzy_match_score("addEventListener", "ael").is_some());
+    assert!(fuzzy_match_score("addEventListener", "AEL").is_some());
+    assert!(fuzzy_match_score("addEventListener", "lae").is_none());
+    assert!(fuzzy_match_score("foo", "fooo").is_none());
+    assert_eq!(fuzzy_match_score("foo", ""), Some(100));
+
+    // A prefix beats a match at word starts, which beats a scattered match.
+    let prefix = fuzzy_match_score("removeEventListener", "rem").unwrap();
+    let word_starts = fuzzy_match_score("readEventMap", "rem").unwrap();
+    let scattered = fuzzy_match_score("returnedItems", "rem").unwrap();
+    assert!(prefix > word_starts);
+    assert!(word_starts > scattered);
+  }
+
+  #[test]
+  fn ranked_completion_candidates() {
+    let rank = |query: &str, recent_identifiers: &[String]| -> Vec<String> {
+      let candidates = ["Map", "WeakMap", "mapped", "Math", "Set"]
+        .iter()
+        .map(|name| (plain_candidate(name.to_string()), *name == "mapped"))
+        .collect();
+      rank_completion_candidates(candidates, query, recent_identifiers)
+        .into_iter()
+        .map(|c| c.replacement)
+        .collect()
+    };
+
+    assert_eq!(rank("map", &[]), vec!["mapped"]);
+    assert_eq!(rank("Ma", &[]), vec!["Map", "Math"]);
+    assert_eq!(rank("Ma", &["Math".to_string()]), vec!["Math", "Map"]);
+    assert_eq!(rank("mp", &[]), vec!["mapped", "Map", "WeakMap"]);
+  }
+
+  #[test]
+  fn completion_candidate() {
+    let display = |property| {
+      format_completion_candidate(&property).map(|pair| {
+        assert_eq!(pair.replacement, property["name"].as_str().unwrap());
+        pair.display
+      })
+    };
+    assert_eq!(
+      display(json!({
+        "name": "add",
+        "value": {
+          "type": "function",
+          "className": "Function",
+          "description": "function add(a, b = 1) { return a + b; }",
+        },
+      })),
+      Some("add(a, b = 1)".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "map",
+        "value": {
+          "type": "function",
+          "className": "Function",
+          "description": "function map() { [native code] }",
+        },
+      })),
+      Some("map(...)".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "Foo",
+        "value": {
+          "type": "function",
+          "className": "Function",
+          "description": "class Foo {}",
+        },
+      })),
+      Some("Foo: class".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "items",
+        "value": {
+          "type": "object",
+          "subtype": "array",
+          "className": "Array",
+          "description": "Array(2)",
+        },
+      })),
+      Some("items: Array".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "nothing",
+        "value": { "type": "object", "subtype": "null", "value": null },
+      })),
+      Some("nothing: null".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "length",
+        "value": { "type": "number", "value": 2, "description": "2" },
+      })),
+      Some("length: number".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "size",
+        "get": { "type": "function", "className": "Function" },
+      })),
+      Some("size".to_string())
+    );
+    assert_eq!(
+      display(json!({
+        "name": "Symbol(Symbol.iterator)",
+        "value": { "type": "function", "className": "Function" },
+      })),
+      None
+    );
+  }
+
+  #[test]
+  fn function_parameters() {
+    assert_eq!(get_function_parameters("(x, y) => x + y"), "x, y");
+    assert_eq!(get_function_parameters("x => x * 2"), "x");
+    assert_eq!(get_function_parameters("async x => x"), "x");
+    assert_eq!(
+      get_function_parameters("add(a,\n    b = f(1)) { return a + b; }"),
+      "a, b = f(1)"
+    );
+    assert_eq!(get_function_parameters("function f() {}"), "");
+  }
+
+  #[test]
+  fn prompt_line_number() {
+    assert_eq!(format_prompt("> ", 3), "> ");
+    assert_eq!(format_prompt("[{line}] ", 3), "[3] ");
+    assert_eq!(format_prompt("{line}{line}", 12), "1212");
+  }
+
+  #[test]
+  fn search_key_from_env() {
+    assert_eq!(get_search_key(None), Some(KeyEvent::ctrl('R')));
+    assert_eq!(get_search_key(Some("".to_string())), None);
+    assert_eq!(
+      get_search_key(Some("ctrl-s".to_string())),
+      Some(KeyEvent::ctrl('S'))
+    );
+    assert_eq!(
+      get_search_key(Some("Alt-R".to_string())),
+      Some(KeyEvent::alt('r'))
+    );
+    assert_eq!(
+      get_search_key(Some("hyper-r".to_string())),
+      Some(KeyEvent::ctrl('R'))
+    );
+  }
+
+  #[test]
+  fn search_match() {
+    assert_eq!(get_search_query("(reverse-i-search)`fo': "), Some("fo"));
+    assert_eq!(
+      get_search_query("(failed reverse-i-search)`x': "),
+      Some("x")
+    );
+    assert_eq!(get_search_query("> "), None);
+
+    // The match at the cursor is preferred.
+    assert_eq!(find_search_match("foo(foo)", 4, "foo"), Some(4..7));
+    assert_eq!(find_search_match("foo(foo)", 2, "foo"), Some(0..3));
+    assert_eq!(find_search_match("foo", 0, "bar"), None);
+    assert_eq!(find_search_match("foo", 0, ""), None);
+  }
+
+  #[test]
+  fn matching_brackets() {
+    let line = "foo(bar[0], { a: `${b}` })";
+    // after a closing bracket
+    assert_eq!(find_matching_brackets(line, 10), Some((7, 9)));
+    // on an opening bracket
+    assert_eq!(find_matching_brackets(line, 3), Some((3, 25)));
+    // on the brace of a template substitution
+    assert_eq!(find_matching_brackets(line, 19), Some((19, 21)));
+    assert_eq!(find_matching_brackets(line, 1), None);
+    assert_eq!(find_matching_brackets("foo(bar", 4), None);
+  }
+
+  #[test]
+  fn eager_eval_preview() {
+    let preview = |line, result| format_eager_eval_preview(line, &result);
+    assert_eq!(
+      preview(
+        "1 + 2",
+        json!({ "type": "number", "value": 3, "description": "3" })
+      ),
+      Some("3".to_string())
+    );
+    assert_eq!(
+      preview("'a' + 'b'", json!({ "type": "string", "value": "ab" })),
+      Some("\"ab\"".to_string())
+    );
+    assert_eq!(
+      preview(
+        "[1, 2]",
+        json!({ "type": "object", "subtype": "array", "description": "Array(2)" })
+      ),
+      Some("Array(2)".to_string())
+    );
+    assert_eq!(
+      preview(
+        "null",
+        json!({ "type": "object", "subtype": "null", "value": null })
+      ),
+      None
+    );
+    assert_eq!(
+      preview(
+        "42",
+        json!({ "type": "number", "value": 42, "description": "42" })
+      ),
+      None
+    );
+    assert_eq!(preview("void 0", json!({ "type": "undefined" })), None);
+    assert_eq!(
+      preview(
+        "Math.max",
+        json!({ "type": "function", "description": "function max() { [native code] }" })
+      ),
+      Some("[Function]".to_string())
+    );
+
+    let long = "a".repeat(MAX_PREVIEW_LENGTH + 1);
+    let expected = format!("\"{}…", "a".repeat(MAX_PREVIEW_LENGTH - 1));
+    assert_eq!(
+      preview(
+        "'a'.repeat(101)",
+        json!({ "type": "string", "value": long })
+      ),
+      Some(expected)
+    );
+  }
+
+  #[test]
+  fn history_file_path() {
+    let default_path = PathBuf::from("/deno_dir/deno_history.txt");
+    assert_eq!(
+      get_history_file_path(None, default_path.clone()),
+      Some(default_path.clone())
+    );
+    assert_eq!(
+      get_history_file_path(
+        Some("history.txt".to_string()),
+        default_path.clone()
+      ),
+      Some(PathBuf::from("history.txt"))
+    );
+    assert_eq!(
+      get_history_file_path(Some("".to_string()), default_path),
+      None
+    );
+  }
+
+  #[test]
+  fn history_file_entries() {
+    let entries = vec!["1 + 1", "function f() {\n  return \"\\n\";\n}", "\\"];
+    let contents = format_history_file(entries.iter().copied());
+    assert_eq!(
+      contents,
+      "#deno_repl_history_v2\n1 + 1\nfunction f() {\\n  return \"\\\\n\";\\n}\n\\\\\n"
+    );
+    assert_eq!(parse_history_file(&contents), entries);
+
+    // A history file written before entries were escaped.
+    assert_eq!(
+      parse_history_file("1 + 1\nconsole.log(\"\\n\")\n"),
+      vec!["1 + 1", "console.log(\"\\n\")"]
+    );
+  }
+
+  #[test]
+  fn history_size() {
+    assert_eq!(get_history_size(None), DEFAULT_HISTORY_SIZE);
+    assert_eq!(get_history_size(Some("10".to_string())), 10);
+    assert_eq!(get_history_size(Some("0".to_string())), 0);
+    assert_eq!(
+      get_history_size(Some("many".to_string())),
+      DEFAULT_HISTORY_SIZE
+    );
+  }
+
+  #[test]
+  fn validate_incomplete_input() {
+    assert!(matches!(
+      validate_input("1 + 2"),
+      ValidationResult::Valid(None)
+    ));
+    assert!(matches!(
+      validate_input("function add(a, b) {"),
+      ValidationResult::Incomplete
+    ));
+    assert!(matches!(
+      validate_input("`foo"),
+      ValidationResult::Incomplete
+    ));
+    assert!(matches!(
+      validate_input("function add(a, b) {\n  return a + b;\n}"),
+      ValidationResult::Valid(None)
+    ));
+    assert!(matches!(
+      validate_input("(]"),
+      ValidationResult::Invalid(Some(_))
+    ));
+  }
+
+  #[test]
+  fn local_path_completion() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("mod.ts"), "").unwrap();
+    std::fs::write(dir.path().join(".hidden.ts"), "").unwrap();
+    std::fs::create_dir(dir.path().join("lib")).unwrap();
+    std::fs::write(dir.path().join("other.js"), "").unwrap();
+
+    let specifier = format!("{}/", dir.path().to_str().unwrap());
+    let (start, candidates) = complete_local_path(10, &specifier);
+    assert_eq!(start, 10 + specifier.len());
+    assert_eq!(candidates, vec!["lib/", "mod.ts", "other.js"]);
+
+    let specifier = format!("{}/m", dir.path().to_str().unwrap());
+    let (_, candidates) = complete_local_path(0, &specifier);
+    assert_eq!(candidates, vec!["mod.ts"]);
+
+    let specifier = format!("{}/.", dir.path().to_str().unwrap());
+    let (_, candidates) = complete_local_path(0, &specifier);
+    assert_eq!(candidates, vec![".hidden.ts"]);
+
+    // Tests run in the directory of the crate.
+    let (start, candidates) = complete_local_path(10, "tool");
+    assert_eq!(start, 10);
+    assert_eq!(candidates, vec!["tools/"]);
+  }
+}
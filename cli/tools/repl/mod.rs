@@ -0,0 +1,352 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use self::editor::validate_input;
+use self::editor::EditorActivity;
+use self::editor::EditorHelper;
+use self::editor::EditorState;
+use self::editor::ReplEditor;
+use self::pager::print_paged;
+use self::permission_prompter::ReplPermissionPrompter;
+use self::session::CommandOutcome;
+use self::session::EvaluationOutput;
+use self::session::RemoteInspectorSession;
+use self::session::ReplCommand;
+use self::session::ReplInspector;
+use self::session::ReplSession;
+use self::theme::get_theme;
+use self::theme::DENO_REPL_THEME;
+
+use crate::colors;
+use crate::program_state::ProgramState;
+use deno_core::error::AnyError;
+use deno_core::serde_json::Value;
+use deno_runtime::worker::MainWorker;
+use rustyline::error::ReadlineError;
+use rustyline::validate::ValidationResult;
+use std::collections::HashMap;
+use std::env;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::sync_channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+use tokio::pin;
+
+mod editor;
+mod pager;
+mod permission_prompter;
+mod session;
+mod theme;
+
+async fn read_line_and_poll(
+  repl_session: &mut ReplSession,
+  message_rx: &Mutex<Receiver<(String, Option<Value>)>>,
+  response_tx: &Sender<Result<Value, AnyError>>,
+  read_line: impl FnOnce() -> Result<String, ReadlineError> + Send + 'static,
+) -> Result<String, ReadlineError> {
+  let mut line = tokio::task::spawn_blocking(read_line);
+
+  let mut poll_worker = true;
+
+  loop {
+    // The receiver isn't locked while the messages are posted, as evaluating
+    // them may issue a permission prompt, which answers the messages itself.
+    let messages: Vec<_> = message_rx.lock().unwrap().try_iter().collect();
+    for (method, params) in messages {
+      let result = repl_session
+        .post_message_with_event_loop(&method, params)
+        .await;
+      response_tx.send(result).unwrap();
+    }
+
+    // Because an inspector websocket client may choose to connect at anytime when we have an
+    // inspector server we need to keep polling the worker to pick up new connections.
+    // TODO(piscisaureus): the above comment is a red herring; figure out if/why
+    // the event loop isn't woken by a waker when a websocket client connects.
+    let timeout = tokio::time::sleep(tokio::time::Duration::from_millis(100));
+    pin!(timeout);
+
+    tokio::select! {
+      result = &mut line => {
+        return result.unwrap();
+      }
+      _ = repl_session.run_event_loop(), if poll_worker => {
+        poll_worker = false;
+      }
+      _ = timeout => {
+        poll_worker = true
+      }
+    }
+  }
+}
+
+/// Reads the next input from a stdin which is not a terminal, like when
+/// statements are piped into the REPL. Lines are read until they form a
+/// complete input, so that a statement may span several lines.
+fn read_stdin_input() -> Result<String, ReadlineError> {
+  let stdin = std::io::stdin();
+  let mut input = String::new();
+  loop {
+    let mut line = String::new();
+    if stdin.lock().read_line(&mut line)? == 0 {
+      return if input.is_empty() {
+        Err(ReadlineError::Eof)
+      } else {
+        Ok(input)
+      };
+    }
+
+    if !input.is_empty() {
+      input.push('\n');
+    }
+    input.push_str(line.trim_end_matches(|c| c == '\n' || c == '\r'));
+    if !matches!(validate_input(&input), ValidationResult::Incomplete) {
+      return Ok(input);
+    }
+  }
+}
+
+/// Appends an input and its output to the `--record` transcript, with the
+/// output commented out so that the transcript can be run as a script.
+fn record_evaluation(
+  record_file: &mut impl Write,
+  input: &str,
+  output: &str,
+) -> Result<(), AnyError> {
+  writeln!(record_file, "{}", input)?;
+  for line in colors::strip_ansi_codes(output).lines() {
+    writeln!(record_file, "// {}", line)?;
+  }
+  Ok(())
+}
+
+/// Appends a dot-command to the `--record` transcript, commented out as it
+/// isn't code.
+fn record_command(
+  record_file: &mut impl Write,
+  line: &str,
+) -> Result<(), AnyError> {
+  writeln!(record_file, "// {}", line.trim())?;
+  Ok(())
+}
+
+/// Creates a REPL session in a new worker, so that none of the state of a
+/// previous session is carried over, or in the process to connect to.
+async fn create_session(
+  program_state: &Arc<ProgramState>,
+  create_worker: &impl Fn() -> MainWorker,
+  options: &ReplOptions,
+) -> Result<ReplSession, AnyError> {
+  let inspector = match &options.maybe_connect {
+    Some(url) => {
+      ReplInspector::Remote(RemoteInspectorSession::connect(url).await?)
+    }
+    None => {
+      let mut worker = create_worker();
+      worker.run_event_loop(false).await?;
+      let session = worker.create_inspector_session().await;
+      ReplInspector::Local { worker, session }
+    }
+  };
+  ReplSession::initialize(program_state.clone(), inspector, options).await
+}
+
+/// The options of `deno repl`.
+pub struct ReplOptions {
+  /// Code evaluated before the first prompt, with `--eval`.
+  pub maybe_eval: Option<String>,
+  /// The file evaluated when a session starts, with `--init`.
+  pub maybe_init_file: Option<PathBuf>,
+  /// Whether each input is type checked, with `--check`.
+  pub type_check: bool,
+  /// Whether JSX is accepted in the input, with `--jsx`.
+  pub jsx: bool,
+  /// The file every input and its output are appended to, with `--record`.
+  pub maybe_record_file: Option<PathBuf>,
+  /// The inspector websocket URL of the process which the input is evaluated
+  /// in instead of a worker of the REPL, with `--connect`.
+  pub maybe_connect: Option<String>,
+  /// Whether the banner is left out, so that the output of the REPL only
+  /// consists of the outputs of the inputs, with `--quiet`.
+  pub quiet: bool,
+  /// Whether the exit code reports if the last input threw even when the
+  /// input is read from a terminal, with `--exit-code-from-last-error`.
+  pub exit_code_from_last_error: bool,
+}
+
+pub async fn run(
+  program_state: Arc<ProgramState>,
+  create_worker: impl Fn() -> MainWorker,
+  options: ReplOptions,
+) -> Result<(), AnyError> {
+  let mut repl_session =
+    create_session(&program_state, &create_worker, &options).await?;
+
+  let mut maybe_record_file = match &options.maybe_record_file {
+    Some(path) => Some(
+      std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?,
+    ),
+    None => None,
+  };
+
+  // Evaluate the `--eval` snippet before the first prompt, only reporting it
+  // when it fails so that the session starts out looking like any other.
+  if let Some(eval) = &options.maybe_eval {
+    let output = repl_session.evaluate_line_and_get_output(eval).await?;
+    if let Some(record_file) = &mut maybe_record_file {
+      record_evaluation(record_file, eval, &output.to_string())?;
+    }
+    if let EvaluationOutput::Error(error) = output {
+      println!("error in --eval flag. {}", error);
+    }
+  }
+
+  let (message_tx, message_rx) = sync_channel(1);
+  let (response_tx, response_rx) = channel();
+
+  let helper = EditorHelper {
+    context_id: repl_session.context_id,
+    message_tx,
+    response_rx,
+    http_cache: program_state.dir.get_http_cache(),
+    last_preview: Mutex::new(None),
+    emit_options: repl_session.emit_options.clone(),
+    media_type: repl_session.media_type,
+    line_by_line: false,
+    state: Arc::new(Mutex::new(EditorState::default())),
+    activity: Arc::new(EditorActivity::default()),
+    theme: get_theme(env::var(DENO_REPL_THEME).ok()),
+  };
+
+  let history_file_path = program_state.dir.root.join("deno_history.txt");
+  let editor = ReplEditor::new(helper, history_file_path);
+
+  // Calling `close()` in a process the session is connected to would close
+  // that process instead of the REPL.
+  let exit_hint = if options.maybe_connect.is_some() {
+    "exit using ctrl+d"
+  } else {
+    "exit using ctrl+d or close()"
+  };
+  if !options.quiet {
+    println!("Deno {}", crate::version::deno());
+    println!("{}", exit_hint);
+  }
+
+  // When stdin is not a terminal the statements are read from it without
+  // line editing or history, and the exit code reports whether the last one
+  // threw, as it does with `--exit-code-from-last-error`.
+  let is_interactive = atty::is(atty::Stream::Stdin);
+  let mut last_evaluation_threw = false;
+
+  let message_rx = Arc::new(Mutex::new(message_rx));
+  if is_interactive {
+    deno_runtime::permissions::set_prompter(Box::new(ReplPermissionPrompter {
+      editor: editor.inner.clone(),
+      state: editor.state.clone(),
+      activity: editor.activity.clone(),
+      message_rx: message_rx.clone(),
+      response_tx: response_tx.clone(),
+      answers: HashMap::new(),
+    }));
+  }
+
+  loop {
+    let line = if is_interactive {
+      let editor = editor.clone();
+      read_line_and_poll(
+        &mut repl_session,
+        &message_rx,
+        &response_tx,
+        move || editor.readline(),
+      )
+      .await
+    } else {
+      read_line_and_poll(
+        &mut repl_session,
+        &message_rx,
+        &response_tx,
+        read_stdin_input,
+      )
+      .await
+    };
+    match line {
+      Ok(line) => {
+        if let Some(command) = ReplCommand::parse(&line) {
+          if let Some(record_file) = &mut maybe_record_file {
+            record_command(record_file, &line)?;
+          }
+          if is_interactive {
+            editor.add_history_entry(line);
+          }
+          match repl_session.run_command(command).await? {
+            CommandOutcome::Print(output) => print!("{}", output),
+            // The new session has the same execution context id as the
+            // current one, as both are the first context of their isolate,
+            // so the editor helper can be kept along with the history.
+            CommandOutcome::Reset if options.maybe_connect.is_some() => {
+              println!("The state of a connected process can't be reset.");
+            }
+            CommandOutcome::Reset => {
+              repl_session =
+                create_session(&program_state, &create_worker, &options)
+                  .await?;
+            }
+            CommandOutcome::Exit => break,
+          }
+          continue;
+        }
+
+        let output = repl_session.evaluate_line_and_get_output(&line).await?;
+
+        // We check for close and break here instead of making it a loop condition to get
+        // consistent behavior in when the user evaluates a call to close().
+        if repl_session.is_closing().await? {
+          break;
+        }
+
+        last_evaluation_threw = matches!(output, EvaluationOutput::Error(_));
+        if let Some(record_file) = &mut maybe_record_file {
+          record_evaluation(record_file, &line, &output.to_string())?;
+        }
+        if is_interactive {
+          print_paged(&output.to_string())?;
+          editor.add_history_entry(line);
+        } else {
+          println!("{}", output);
+        }
+      }
+      Err(ReadlineError::Interrupted) => {
+        println!("{}", exit_hint);
+        continue;
+      }
+      Err(ReadlineError::Eof) => {
+        break;
+      }
+      Err(err) => {
+        println!("Error: {:?}", err);
+        break;
+      }
+    }
+  }
+
+  if is_interactive {
+    editor.save_history()?;
+  }
+  repl_session.dispatch_unload_event()?;
+
+  if (!is_interactive || options.exit_code_from_last_error)
+    && last_evaluation_threw
+  {
+    crate::EXIT_CODE.set(1);
+  }
+
+  Ok(())
+}
@@ -0,0 +1,238 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+use deno_core::error::AnyError;
+use std::env;
+use std::io::BufRead;
+use std::io::Write;
+
+static DENO_REPL_PAGER: &str = "DENO_REPL_PAGER";
+
+/// How an output longer than the terminal is shown.
+#[derive(Debug, PartialEq)]
+enum Pager {
+  /// A program which is given the output on its stdin, with its arguments.
+  External(String),
+  /// Shows a screen at a time, prompting for the next one.
+  Internal,
+}
+
+/// Returns the pager from `DENO_REPL_PAGER`, falling back to `PAGER` and
+/// then to the internal pager. An empty `DENO_REPL_PAGER` disables paging.
+fn get_pager(
+  maybe_repl_pager: Option<String>,
+  maybe_pager: Option<String>,
+) -> Option<Pager> {
+  match maybe_repl_pager {
+    Some(pager) if pager.trim().is_empty() => None,
+    Some(pager) => Some(Pager::External(pager)),
+    None => Some(
+      maybe_pager
+        .filter(|pager| !pager.trim().is_empty())
+        .map_or(Pager::Internal, Pager::External),
+    ),
+  }
+}
+
+/// Returns the number of rows of the terminal stdout is connected to.
+#[cfg(unix)]
+fn get_terminal_height() -> Option<usize> {
+  unsafe {
+    let mut size: libc::winsize = std::mem::zeroed();
+    if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size as *mut _)
+      != 0
+    {
+      return None;
+    }
+    Some(size.ws_row as usize).filter(|rows| *rows > 0)
+  }
+}
+
+/// Returns the number of rows of the console window stdout is connected to.
+#[cfg(windows)]
+fn get_terminal_height() -> Option<usize> {
+  use winapi::um::processenv::GetStdHandle;
+  use winapi::um::winbase::STD_OUTPUT_HANDLE;
+  use winapi::um::wincon::GetConsoleScreenBufferInfo;
+  use winapi::um::wincon::CONSOLE_SCREEN_BUFFER_INFO;
+
+  unsafe {
+    let mut info: CONSOLE_SCREEN_BUFFER_INFO = std::mem::zeroed();
+    if GetConsoleScreenBufferInfo(GetStdHandle(STD_OUTPUT_HANDLE), &mut info)
+      == 0
+    {
+      return None;
+    }
+    let rows = info.srWindow.Bottom - info.srWindow.Top + 1;
+    Some(rows as usize).filter(|rows| *rows > 0)
+  }
+}
+
+/// Prints the output of an evaluation, through the pager when it has more
+/// lines than fit in the terminal.
+pub fn print_paged(output: &str) -> Result<(), AnyError> {
+  let maybe_height = if atty::is(atty::Stream::Stdout) {
+    get_terminal_height()
+  } else {
+    None
+  };
+  let pager = match maybe_height {
+    Some(height) if output.lines().count() >= height => {
+      get_pager(env::var(DENO_REPL_PAGER).ok(), env::var("PAGER").ok())
+    }
+    _ => None,
+  };
+
+  match (pager, maybe_height) {
+    (Some(Pager::External(pager)), _) => {
+      let mut pager_args = pager.split_whitespace();
+      let program = pager_args.next().unwrap();
+      let mut command = std::process::Command::new(program);
+      command.args(pager_args).stdin(std::process::Stdio::piped());
+      // Like git, have less keep the colors and exit when the output fits.
+      if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+      }
+      // A pager that can't be started, like one that isn't installed,
+      // shouldn't hide the output.
+      let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(_) => {
+          println!("{}", output);
+          return Ok(());
+        }
+      };
+      let mut stdin = child.stdin.take().unwrap();
+      // The pager closing its stdin early, like when quitting less before
+      // reaching the end, is not an error.
+      let _ = writeln!(stdin, "{}", output);
+      drop(stdin);
+      child.wait()?;
+    }
+    (Some(Pager::Internal), Some(height)) => {
+      let lines = output.lines().collect::<Vec<_>>();
+      run_internal_pager(
+        &lines,
+        height.saturating_sub(1).max(1),
+        &mut std::io::stdin().lock(),
+        &mut std::io::stdout(),
+      )?;
+    }
+    _ => println!("{}", output),
+  }
+
+  Ok(())
+}
+
+/// Writes `page_size` lines at a time, prompting for what to do next: enter
+/// shows the next page, `/text` continues from the next line containing the
+/// text, `n` repeats the last search and `q` quits.
+fn run_internal_pager(
+  lines: &[&str],
+  page_size: usize,
+  input: &mut impl BufRead,
+  output: &mut impl Write,
+) -> Result<(), AnyError> {
+  let mut start = 0;
+  let mut last_search: Option<String> = None;
+
+  loop {
+    let end = (start + page_size).min(lines.len());
+    for line in &lines[start..end] {
+      writeln!(output, "{}", line)?;
+    }
+    if end == lines.len() {
+      return Ok(());
+    }
+
+    start = loop {
+      write!(
+        output,
+        "{}",
+        colors::gray(format!(
+          "-- {}/{} lines (enter: next page, /text: search, n: next match, \
+           q: quit) --",
+          end,
+          lines.len()
+        ))
+      )?;
+      output.flush()?;
+
+      let mut command = String::new();
+      if input.read_line(&mut command)? == 0 {
+        return Ok(());
+      }
+      let command = command.trim_end_matches(|c| c == '\n' || c == '\r');
+      let pattern = match command {
+        "" => break end,
+        "q" => return Ok(()),
+        "n" => match last_search.clone() {
+          Some(pattern) => pattern,
+          None => continue,
+        },
+        _ if command.starts_with('/') && command.len() > 1 => {
+          command[1..].to_string()
+        }
+        _ => continue,
+      };
+
+      let maybe_match = lines[start + 1..]
+        .iter()
+        .position(|line| colors::strip_ansi_codes(line).contains(&pattern));
+      last_search = Some(pattern);
+      match maybe_match {
+        Some(index) => break start + 1 + index,
+        None => writeln!(output, "Pattern not found")?,
+      }
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn pager_from_env() {
+    assert_eq!(get_pager(None, None), Some(Pager::Internal));
+    assert_eq!(get_pager(None, Some("".to_string())), Some(Pager::Internal));
+    assert_eq!(
+      get_pager(None, Some("less".to_string())),
+      Some(Pager::External("less".to_string()))
+    );
+    assert_eq!(
+      get_pager(Some("more -d".to_string()), Some("less".to_string())),
+      Some(Pager::External("more -d".to_string()))
+    );
+    assert_eq!(
+      get_pager(Some("".to_string()), Some("less".to_string())),
+      None
+    );
+  }
+
+  #[test]
+  fn internal_pager() {
+    let lines = ["a", "b", "c", "d", "e", "f"];
+    let page = |input: &str| {
+      let mut output = Vec::new();
+      run_internal_pager(&lines, 2, &mut input.as_bytes(), &mut output)
+        .unwrap();
+      colors::strip_ansi_codes(&String::from_utf8(output).unwrap()).to_string()
+    };
+    // The prompts end without a newline, as the terminal echoes the one the
+    // user types.
+    let shown_lines = |output: String| {
+      output
+        .lines()
+        .filter(|line| line.len() == 1)
+        .collect::<Vec<_>>()
+        .join(",")
+    };
+    assert_eq!(shown_lines(page("\n\n")), "a,b,c,d,e,f");
+    assert_eq!(shown_lines(page("q\n")), "a,b");
+    assert_eq!(shown_lines(page("")), "a,b");
+    assert_eq!(shown_lines(page("/e\n")), "a,b,e,f");
+    assert_eq!(shown_lines(page("/c\nn\n")), "a,b,c,d");
+    assert!(page("/x\nq\n").contains("Pattern not found"));
+  }
+}
@@ -0,0 +1,137 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use super::editor::EditorActivity;
+use super::editor::EditorHelper;
+use super::editor::EditorState;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json::Value;
+use deno_runtime::permissions::PermissionPrompter;
+use rustyline::Editor;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::TryLockError;
+
+/// Answers the permission prompts of an interactive REPL through its line
+/// editor, as reading stdin directly would garble the editor's state.
+pub struct ReplPermissionPrompter {
+  pub editor: Arc<Mutex<Editor<EditorHelper>>>,
+  pub state: Arc<Mutex<EditorState>>,
+  pub activity: Arc<EditorActivity>,
+  pub message_rx: Arc<Mutex<Receiver<(String, Option<Value>)>>>,
+  pub response_tx: Sender<Result<Value, AnyError>>,
+  /// The answers which are remembered for the rest of the session, by the
+  /// message they were given to.
+  pub answers: HashMap<String, bool>,
+}
+
+impl ReplPermissionPrompter {
+  /// Input which is being read can't be interrupted, so a prompt issued by a
+  /// background task meanwhile is shown above the input and answered once
+  /// the input is submitted.
+  fn lock_editor(&self, request: &str) -> MutexGuard<Editor<EditorHelper>> {
+    match self.editor.try_lock() {
+      Ok(editor) => return editor,
+      Err(TryLockError::Poisoned(error)) => panic!("{}", error),
+      Err(TryLockError::WouldBlock) => {}
+    }
+
+    {
+      let state = self.state.lock().unwrap();
+      if let Some(prompt) = &state.prompt {
+        let mut redraw = format!(
+          "\r\x1b[K{}(submit the current input to answer)\n{}{}",
+          request, prompt, state.line
+        );
+        let columns_after_cursor = state.line[state.pos..].chars().count();
+        if columns_after_cursor > 0 {
+          redraw.push_str(&format!("\x1b[{}D", columns_after_cursor));
+        }
+        eprint!("{}", redraw);
+        std::io::stderr().flush().unwrap();
+      }
+    }
+
+    // The messages of the helper would otherwise wait on the event loop,
+    // which is blocked until the prompt is answered.
+    loop {
+      let generation = self.activity.generation();
+      match self.editor.try_lock() {
+        Ok(editor) => return editor,
+        Err(TryLockError::Poisoned(error)) => panic!("{}", error),
+        Err(TryLockError::WouldBlock) => {}
+      }
+      for _ in self.message_rx.lock().unwrap().try_iter() {
+        self
+          .response_tx
+          .send(Err(generic_error("A permission prompt is pending.")))
+          .unwrap();
+      }
+      self.activity.wait_after(generation);
+    }
+  }
+}
+
+impl PermissionPrompter for ReplPermissionPrompter {
+  fn prompt(&mut self, message: &str) -> bool {
+    if let Some(granted) = self.answers.get(message) {
+      return *granted;
+    }
+
+    let request = format!(
+      "⚠️  Deno requests {}. Allow? [y/n/a/d (a = always allow, d = always deny)] ",
+      message
+    );
+    let mut editor = self.lock_editor(&request);
+    self.state.lock().unwrap().reading_permission_answer = true;
+    let granted = loop {
+      let input = match editor.readline(&request) {
+        Ok(input) => input,
+        Err(_) => break false,
+      };
+      match parse_permission_answer(&input) {
+        Some((granted, remember)) => {
+          if remember {
+            self.answers.insert(message.to_string(), granted);
+          }
+          break granted;
+        }
+        None => eprintln!("Unrecognized option '{}'", input.trim()),
+      }
+    };
+    self.state.lock().unwrap().reading_permission_answer = false;
+    granted
+  }
+}
+
+/// Parses the answer to a permission prompt into whether the access is
+/// granted and whether the answer is remembered for the rest of the session.
+fn parse_permission_answer(input: &str) -> Option<(bool, bool)> {
+  match input.trim().to_ascii_lowercase().as_str() {
+    "y" | "yes" => Some((true, false)),
+    "n" | "no" => Some((false, false)),
+    "a" | "always" => Some((true, true)),
+    "d" | "deny" => Some((false, true)),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn permission_answer() {
+    assert_eq!(parse_permission_answer("y"), Some((true, false)));
+    assert_eq!(parse_permission_answer(" No "), Some((false, false)));
+    assert_eq!(parse_permission_answer("a"), Some((true, true)));
+    assert_eq!(parse_permission_answer("D"), Some((false, true)));
+    assert_eq!(parse_permission_answer(""), None);
+    assert_eq!(parse_permission_answer("maybe"), None);
+  }
+}
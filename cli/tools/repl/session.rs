@@ -0,0 +1,1006 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use super::ReplOptions;
+use crate::ast;
+use crate::ast::Diagnostic;
+use crate::ast::ImportsNotUsedAsValues;
+use crate::colors;
+use crate::config_file::ConfigFile;
+use crate::config_file::TsConfig;
+use crate::diagnostics::DiagnosticCategory;
+use crate::diagnostics::Diagnostics;
+use crate::file_fetcher::File;
+use crate::flags::TypeCheckMode;
+use crate::media_type::MediaType;
+use crate::module_graph::CheckOptions;
+use crate::module_graph::GraphBuilder;
+use crate::module_graph::TypeLib;
+use crate::program_state::ProgramState;
+use crate::specifier_handler::FetchHandler;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::future;
+use deno_core::futures::FutureExt;
+use deno_core::futures::SinkExt;
+use deno_core::futures::StreamExt;
+use deno_core::located_script_name;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::LocalInspectorSession;
+use deno_runtime::deno_websocket::tokio_tungstenite;
+use deno_runtime::deno_websocket::tokio_tungstenite::tungstenite::Message;
+use deno_runtime::deno_websocket::tokio_tungstenite::MaybeTlsStream;
+use deno_runtime::deno_websocket::tokio_tungstenite::WebSocketStream;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::worker::MainWorker;
+use sourcemap::SourceMap;
+use sourcemap::SourceMapBuilder;
+use std::env;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+static HELP: &str = r#".clear    Clear the screen
+.editor   Compose input in $EDITOR, which is evaluated once it is closed
+.exit     Exit the REPL
+.help     Print this help message
+.load     Evaluate a file in the current session: .load <file>
+.reset    Start a new session, discarding all declarations and state
+.save     Save all evaluated input in this session to a file: .save <file>
+"#;
+
+/// A dot-command understood by the REPL in addition to JavaScript, in the
+/// style of Node's REPL.
+pub enum ReplCommand {
+  Help,
+  Exit,
+  Clear,
+  Editor,
+  Reset,
+  Save(String),
+  Load(String),
+}
+
+impl ReplCommand {
+  /// Parses `line` as a dot-command. Anything that isn't one of the known
+  /// commands is left to be evaluated as code, so that e.g. `.5` still works.
+  pub fn parse(line: &str) -> Option<Self> {
+    let line = line.trim();
+    let (name, arg) = match line.find(char::is_whitespace) {
+      Some(index) => (&line[..index], line[index..].trim()),
+      None => (line, ""),
+    };
+    match name {
+      ".help" => Some(ReplCommand::Help),
+      ".exit" => Some(ReplCommand::Exit),
+      ".clear" => Some(ReplCommand::Clear),
+      ".editor" => Some(ReplCommand::Editor),
+      ".reset" => Some(ReplCommand::Reset),
+      ".save" => Some(ReplCommand::Save(arg.to_string())),
+      ".load" => Some(ReplCommand::Load(arg.to_string())),
+      _ => None,
+    }
+  }
+}
+
+/// Opens a temporary TypeScript file in the editor from the `EDITOR`
+/// environment variable, which may include arguments, and returns what was
+/// written to it once the editor exits. Returns `None` if the editor exited
+/// with an error, in which case nothing should be evaluated.
+fn compose_in_editor() -> Result<Option<String>, AnyError> {
+  let default_editor = if cfg!(windows) { "notepad" } else { "vi" };
+  let editor = env::var("EDITOR")
+    .ok()
+    .filter(|e| !e.trim().is_empty())
+    .unwrap_or_else(|| default_editor.to_string());
+  let mut editor_args = editor.split_whitespace();
+  let program = editor_args.next().unwrap();
+
+  // The file is closed before the editor opens it, as editors on Windows
+  // can't write to a file which is still open.
+  let temp_path = tempfile::Builder::new()
+    .prefix("deno_repl_")
+    .suffix(".ts")
+    .tempfile()?
+    .into_temp_path();
+  let status = std::process::Command::new(program)
+    .args(editor_args)
+    .arg(&temp_path)
+    .status()?;
+  if !status.success() {
+    return Ok(None);
+  }
+
+  Ok(Some(std::fs::read_to_string(&temp_path)?))
+}
+
+/// What the REPL should do after running a dot-command.
+pub enum CommandOutcome {
+  Print(String),
+  Reset,
+  Exit,
+}
+
+/// The printable result of evaluating a line in the REPL.
+pub enum EvaluationOutput {
+  Value(String),
+  Error(String),
+}
+
+impl EvaluationOutput {
+  /// Puts `warnings` before the output, so that they are printed, paged and
+  /// recorded together with it.
+  fn with_warnings(self, warnings: &str) -> Self {
+    match self {
+      EvaluationOutput::Value(value) => {
+        EvaluationOutput::Value(format!("{}\n{}", warnings, value))
+      }
+      EvaluationOutput::Error(value) => {
+        EvaluationOutput::Error(format!("{}\n{}", warnings, value))
+      }
+    }
+  }
+}
+
+impl std::fmt::Display for EvaluationOutput {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      EvaluationOutput::Value(value) => f.write_str(value),
+      EvaluationOutput::Error(value) => f.write_str(value),
+    }
+  }
+}
+
+static PRELUDE: &str = r#"
+Object.defineProperty(globalThis, "_", {
+  configurable: true,
+  get: () => Deno[Deno.internal].lastEvalResult,
+  set: (value) => {
+   Object.defineProperty(globalThis, "_", {
+     value: value,
+     writable: true,
+     enumerable: true,
+     configurable: true,
+   });
+   console.log("Last evaluation result is no longer saved to _.");
+  },
+});
+
+Object.defineProperty(globalThis, "_error", {
+  configurable: true,
+  get: () => Deno[Deno.internal].lastThrownError,
+  set: (value) => {
+   Object.defineProperty(globalThis, "_error", {
+     value: value,
+     writable: true,
+     enumerable: true,
+     configurable: true,
+   });
+
+   console.log("Last thrown error is no longer saved to _error.");
+  },
+});
+
+Deno[Deno.internal].lastEvalResults = [];
+for (let i = 1; i <= 9; i++) {
+  Object.defineProperty(globalThis, `_${i}`, {
+    configurable: true,
+    get: () => Deno[Deno.internal].lastEvalResults[i - 1],
+    set: (value) => {
+     Object.defineProperty(globalThis, `_${i}`, {
+       value: value,
+       writable: true,
+       enumerable: true,
+       configurable: true,
+     });
+     console.log(`Evaluation result ${i} is no longer saved to _${i}.`);
+    },
+  });
+}
+"#;
+
+/// Declares the globals defined by the prelude for the type checking of the
+/// inputs.
+static TYPE_CHECK_PRELUDE: &str = r#"declare let _: any;
+declare let _error: any;
+declare let _1: any, _2: any, _3: any, _4: any, _5: any;
+declare let _6: any, _7: any, _8: any, _9: any;
+"#;
+
+/// Redeclaring variables, functions and classes is allowed in the REPL, as
+/// each input replaces the declarations of the previous ones.
+const REDECLARATION_DIAGNOSTIC_CODES: &[u64] = &[2300, 2393, 2451];
+
+/// The file in `$DENO_DIR` which is evaluated when the REPL starts.
+static INIT_FILE: &str = "repl_init.ts";
+
+static DENO_REPL_AWAIT_TIMEOUT: &str = "DENO_REPL_AWAIT_TIMEOUT";
+const DEFAULT_AWAIT_TIMEOUT: u64 = 5000;
+
+/// Races a promise against a timer, which resolves to `AWAIT_TIMED_OUT`.
+static AWAIT_WITH_TIMEOUT: &str = r#"function (timeout) {
+  let timer;
+  const timedOut = new Promise((resolve) => {
+    const value = Symbol.for("Deno.repl.awaitTimedOut");
+    timer = setTimeout(resolve, timeout, value);
+  });
+  return Promise.race([this, timedOut]).finally(() => clearTimeout(timer));
+}"#;
+static AWAIT_TIMED_OUT: &str = "Symbol(Deno.repl.awaitTimedOut)";
+
+/// Returns how many milliseconds a promise which an input evaluates to is
+/// awaited, which can be configured with the `DENO_REPL_AWAIT_TIMEOUT`
+/// environment variable. Zero disables awaiting the promise.
+fn get_await_timeout(maybe_value: Option<String>) -> u64 {
+  maybe_value
+    .and_then(|value| value.parse::<u64>().ok())
+    .unwrap_or(DEFAULT_AWAIT_TIMEOUT)
+}
+
+/// A CDP session with the inspector of another Deno process, over the
+/// websocket of its `--inspect` server.
+pub struct RemoteInspectorSession {
+  socket: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+  next_message_id: i32,
+  notification_queue: Vec<Value>,
+}
+
+impl RemoteInspectorSession {
+  pub async fn connect(url: &str) -> Result<Self, AnyError> {
+    let (socket, _) = tokio_tungstenite::connect_async(url).await?;
+    Ok(Self {
+      socket,
+      next_message_id: 0,
+      notification_queue: Vec::new(),
+    })
+  }
+
+  pub fn notifications(&mut self) -> Vec<Value> {
+    self.notification_queue.split_off(0)
+  }
+
+  pub async fn post_message(
+    &mut self,
+    method: &str,
+    params: Option<Value>,
+  ) -> Result<Value, AnyError> {
+    let id = self.next_message_id;
+    self.next_message_id += 1;
+
+    let message = json!({
+      "id": id,
+      "method": method,
+      "params": params,
+    });
+    self.socket.send(Message::Text(message.to_string())).await?;
+
+    loop {
+      let message = match self.socket.next().await {
+        Some(message) => message?,
+        None => {
+          return Err(generic_error("The inspector closed the connection."))
+        }
+      };
+      let message: Value = match message {
+        Message::Text(text) => serde_json::from_str(&text)?,
+        Message::Close(_) => {
+          return Err(generic_error("The inspector closed the connection."))
+        }
+        _ => continue,
+      };
+
+      // Messages without an id are notifications.
+      match message.get("id").and_then(|id| id.as_i64()) {
+        Some(message_id) if message_id == id as i64 => {
+          if let Some(error) = message.get("error") {
+            return Err(generic_error(error.to_string()));
+          }
+          return Ok(message.get("result").unwrap().clone());
+        }
+        Some(_) => {}
+        None => self.notification_queue.push(message),
+      }
+    }
+  }
+}
+
+/// Where the input of the REPL is evaluated: in a worker of its own, or in
+/// another process connected to with `--connect`.
+pub enum ReplInspector {
+  Local {
+    worker: MainWorker,
+    session: LocalInspectorSession,
+  },
+  Remote(RemoteInspectorSession),
+}
+
+impl ReplInspector {
+  pub fn notifications(&mut self) -> Vec<Value> {
+    match self {
+      ReplInspector::Local { session, .. } => session.notifications(),
+      ReplInspector::Remote(session) => session.notifications(),
+    }
+  }
+
+  pub async fn post_message(
+    &mut self,
+    method: &str,
+    params: Option<Value>,
+  ) -> Result<Value, AnyError> {
+    match self {
+      ReplInspector::Local { worker, session } => {
+        worker
+          .with_event_loop(session.post_message(method, params).boxed_local())
+          .await
+      }
+      ReplInspector::Remote(session) => {
+        session.post_message(method, params).await
+      }
+    }
+  }
+
+  /// Whether the input is evaluated in another process, whose globals are
+  /// left alone apart from what the input itself does.
+  pub fn is_remote(&self) -> bool {
+    matches!(self, ReplInspector::Remote(_))
+  }
+
+  /// The event loop of another process is run by that process itself.
+  pub async fn run_event_loop(&mut self) -> Result<(), AnyError> {
+    match self {
+      ReplInspector::Local { worker, .. } => worker.run_event_loop(false).await,
+      ReplInspector::Remote(_) => future::pending().await,
+    }
+  }
+}
+
+pub struct ReplSession {
+  program_state: Arc<ProgramState>,
+  inspector: ReplInspector,
+  pub context_id: u64,
+  eval_count: usize,
+  /// Every line evaluated in this session, written out by `.save`.
+  transcript: Vec<String>,
+  /// The options the inputs are transpiled with.
+  pub emit_options: ast::EmitOptions,
+  /// The media type of the inputs, which is TSX when JSX is accepted.
+  pub media_type: MediaType,
+  /// Whether each input is type checked before it is evaluated.
+  type_check: bool,
+  /// The inputs which were type checked and evaluated without throwing, which
+  /// a new input is appended to when it is type checked so that it can refer
+  /// to their declarations.
+  type_check_source: String,
+  /// How many milliseconds a promise which an input evaluates to is awaited.
+  await_timeout: u64,
+}
+
+impl ReplSession {
+  /// Creates a session, evaluating the init file after the prelude. Without
+  /// an init file in the options, `$DENO_DIR/repl_init.ts` is evaluated if it exists.
+  /// Neither is evaluated in a process the session is connected to.
+  pub async fn initialize(
+    program_state: Arc<ProgramState>,
+    mut inspector: ReplInspector,
+    options: &ReplOptions,
+  ) -> Result<Self, AnyError> {
+    inspector.post_message("Runtime.enable", None).await?;
+
+    // Enabling the runtime domain will always send trigger one executionContextCreated for each
+    // context the inspector knows about so we grab the execution context from that since
+    // our inspector does not support a default context (0 is an invalid context id).
+    let mut context_id: u64 = 0;
+    for notification in inspector.notifications() {
+      let method = notification.get("method").unwrap().as_str().unwrap();
+      let params = notification.get("params").unwrap();
+
+      if method == "Runtime.executionContextCreated" {
+        context_id = params
+          .get("context")
+          .unwrap()
+          .get("id")
+          .unwrap()
+          .as_u64()
+          .unwrap();
+      }
+    }
+
+    let emit_options =
+      get_emit_options(options.jsx, program_state.maybe_config_file.as_ref())?;
+    let media_type = if options.jsx {
+      MediaType::Tsx
+    } else {
+      MediaType::TypeScript
+    };
+    let is_remote = inspector.is_remote();
+    let mut repl_session = ReplSession {
+      program_state,
+      inspector,
+      context_id,
+      eval_count: 0,
+      transcript: Vec::new(),
+      emit_options,
+      media_type,
+      type_check: options.type_check,
+      type_check_source: if is_remote {
+        String::new()
+      } else {
+        TYPE_CHECK_PRELUDE.to_string()
+      },
+      await_timeout: get_await_timeout(env::var(DENO_REPL_AWAIT_TIMEOUT).ok()),
+    };
+
+    if is_remote {
+      return Ok(repl_session);
+    }
+
+    // inject prelude
+    repl_session.evaluate_expression(PRELUDE).await?;
+
+    let default_init_file = repl_session.program_state.dir.root.join(INIT_FILE);
+    let maybe_init_file = options
+      .maybe_init_file
+      .clone()
+      .or_else(|| Some(default_init_file).filter(|path| path.is_file()));
+    if let Some(init_file) = maybe_init_file {
+      match std::fs::read_to_string(&init_file) {
+        Ok(source) => {
+          let output = repl_session.evaluate_and_get_output(&source).await?;
+          if let EvaluationOutput::Error(error) = output {
+            println!("error in init file {}. {}", init_file.display(), error);
+          }
+        }
+        Err(err) => {
+          println!("Failed to load init file {}: {}", init_file.display(), err)
+        }
+      }
+    }
+
+    Ok(repl_session)
+  }
+
+  pub async fn run_command(
+    &mut self,
+    command: ReplCommand,
+  ) -> Result<CommandOutcome, AnyError> {
+    let output = match command {
+      ReplCommand::Help => HELP.to_string(),
+      ReplCommand::Exit => return Ok(CommandOutcome::Exit),
+      ReplCommand::Reset => return Ok(CommandOutcome::Reset),
+      // Erase the screen and move the cursor to the top left corner.
+      ReplCommand::Clear => "\x1b[2J\x1b[1;1H".to_string(),
+      ReplCommand::Editor => match compose_in_editor() {
+        Ok(Some(input)) if !input.trim().is_empty() => {
+          let output = self.evaluate_line_and_get_output(&input).await?;
+          format!("{}\n", output)
+        }
+        Ok(_) => String::new(),
+        Err(err) => format!("Failed to compose input in the editor: {}\n", err),
+      },
+      ReplCommand::Save(path) if path.is_empty() => {
+        "A file name is required: .save <file>\n".to_string()
+      }
+      ReplCommand::Save(path) => {
+        let mut contents = self.transcript.join("\n");
+        contents.push('\n');
+        match std::fs::write(&path, contents) {
+          Ok(()) => format!("Session saved to: {}\n", path),
+          Err(err) => format!("Failed to save '{}': {}\n", path, err),
+        }
+      }
+      ReplCommand::Load(path) if path.is_empty() => {
+        "A file name is required: .load <file>\n".to_string()
+      }
+      ReplCommand::Load(path) => match std::fs::read_to_string(&path) {
+        Ok(contents) => {
+          let output = self.evaluate_line_and_get_output(&contents).await?;
+          format!("{}\n", output)
+        }
+        Err(err) => format!("Failed to load '{}': {}\n", path, err),
+      },
+    };
+
+    Ok(CommandOutcome::Print(output))
+  }
+
+  /// Whether the input called `close()`. A process the session is connected
+  /// to has a `closed` of its own, which doesn't end the session.
+  pub async fn is_closing(&mut self) -> Result<bool, AnyError> {
+    if self.inspector.is_remote() {
+      return Ok(false);
+    }
+    let closed = self
+      .evaluate_expression("(globalThis.closed)")
+      .await?
+      .get("result")
+      .unwrap()
+      .get("value")
+      .unwrap()
+      .as_bool()
+      .unwrap();
+
+    Ok(closed)
+  }
+
+  /// Dispatches the `unload` event in the worker of the session, so that the
+  /// listeners added in the session can clean up. The process a session is
+  /// connected to keeps running, so it isn't unloaded.
+  pub fn dispatch_unload_event(&mut self) -> Result<(), AnyError> {
+    if let ReplInspector::Local { worker, .. } = &mut self.inspector {
+      worker.dispatch_unload_event(&located_script_name!())?;
+    }
+    Ok(())
+  }
+
+  pub async fn post_message_with_event_loop(
+    &mut self,
+    method: &str,
+    params: Option<Value>,
+  ) -> Result<Value, AnyError> {
+    self.inspector.post_message(method, params).await
+  }
+
+  pub async fn run_event_loop(&mut self) -> Result<(), AnyError> {
+    self.inspector.run_event_loop().await
+  }
+
+  pub async fn evaluate_line_and_get_output(
+    &mut self,
+    line: &str,
+  ) -> Result<EvaluationOutput, AnyError> {
+    self.transcript.push(line.to_string());
+    self.evaluate_and_get_output(line).await
+  }
+
+  /// Like `evaluate_line_and_get_output`, but for code which the user didn't
+  /// type and which is therefore left out of the transcript.
+  async fn evaluate_and_get_output(
+    &mut self,
+    line: &str,
+  ) -> Result<EvaluationOutput, AnyError> {
+    let (type_checked, maybe_warnings) = if self.type_check {
+      match self.type_check_input(line).await {
+        Ok(diagnostics) if diagnostics.is_empty() => (true, None),
+        Ok(diagnostics) => (true, Some(diagnostics.to_string())),
+        Err(err) => (
+          false,
+          Some(format!(
+            "{}: {}",
+            colors::yellow("Failed to type check"),
+            err
+          )),
+        ),
+      }
+    } else {
+      (false, None)
+    };
+
+    let output = match self.evaluate_line_with_object_wrapping(line).await {
+      Ok(evaluate_response) => {
+        let evaluate_response = self.await_promise(evaluate_response).await?;
+        let evaluate_result = evaluate_response.get("result").unwrap();
+        let evaluate_exception_details =
+          evaluate_response.get("exceptionDetails");
+
+        // `_` and `_error` are defined by the prelude, which isn't
+        // evaluated in a process the session is connected to.
+        if !self.inspector.is_remote() {
+          if evaluate_exception_details.is_some() {
+            self.set_last_thrown_error(evaluate_result).await?;
+          } else {
+            self.set_last_eval_result(evaluate_result).await?;
+          }
+        }
+
+        let value = self.get_eval_value(evaluate_result).await?;
+        match evaluate_exception_details {
+          Some(_) => EvaluationOutput::Error(format!("Uncaught {}", value)),
+          None => EvaluationOutput::Value(value),
+        }
+      }
+      Err(err) => {
+        // handle a parsing diagnostic
+        match err.downcast_ref::<Diagnostic>() {
+          Some(diagnostic) => EvaluationOutput::Error(format!(
+            "{}: {} at {}:{}",
+            colors::red("parse error"),
+            diagnostic.message,
+            diagnostic.location.line,
+            diagnostic.location.col
+          )),
+          None => return Err(err),
+        }
+      }
+    };
+
+    // An input which threw may not have made its declarations, and one which
+    // couldn't be type checked would fail every later check as well.
+    if type_checked && matches!(output, EvaluationOutput::Value(_)) {
+      self.type_check_source.push_str(&wrap_object_literal(line));
+      self.type_check_source.push('\n');
+    }
+
+    Ok(match maybe_warnings {
+      Some(warnings) => output.with_warnings(&warnings),
+      None => output,
+    })
+  }
+
+  /// Type checks the input as a module following the inputs evaluated so far,
+  /// returning the diagnostics located in the input as warnings.
+  async fn type_check_input(
+    &self,
+    line: &str,
+  ) -> Result<Diagnostics, AnyError> {
+    let program_state = &self.program_state;
+    // The specifier of the REPL's main module, so that imports in the input
+    // are resolved the same as when it is evaluated.
+    let specifier = resolve_url_or_path(&format!(
+      "./$deno$repl{}",
+      self.media_type.as_ts_extension()
+    ))?;
+    let line = wrap_object_literal(line);
+    // Syntax errors are left to be reported by the evaluation.
+    if ast::parse(specifier.as_str(), &line, &self.media_type).is_err() {
+      return Ok(Diagnostics::default());
+    }
+    let line_offset = self.type_check_source.matches('\n').count() as u64;
+    let source = format!("{}{}\nexport {{}};\n", self.type_check_source, line);
+    program_state.file_fetcher.insert_cached(File {
+      local: specifier.to_file_path().unwrap(),
+      maybe_types: None,
+      media_type: self.media_type,
+      source,
+      maybe_raw_source: None,
+      specifier: specifier.clone(),
+    });
+
+    let handler = Arc::new(Mutex::new(FetchHandler::new(
+      program_state,
+      Permissions::allow_all(),
+      Permissions::allow_all(),
+    )?));
+    let mut builder = GraphBuilder::new(
+      handler,
+      program_state.maybe_import_map.clone(),
+      program_state.lockfile.clone(),
+    );
+    builder.add(&specifier, false).await?;
+    builder
+      .analyze_config_file(&program_state.maybe_config_file)
+      .await?;
+    let lib = if program_state.flags.unstable {
+      TypeLib::UnstableDenoWindow
+    } else {
+      TypeLib::DenoWindow
+    };
+    let result_info = builder.get_graph().check(CheckOptions {
+      debug: program_state.flags.log_level == Some(log::Level::Debug),
+      emit: false,
+      lib,
+      local_only: program_state.flags.typecheck_mode == TypeCheckMode::Local,
+      maybe_config_file: program_state.maybe_config_file.clone(),
+      quiet: true,
+      ..Default::default()
+    })?;
+
+    let diagnostics = result_info
+      .diagnostics
+      .into_iter()
+      .filter_map(|mut diagnostic| {
+        if diagnostic.file_name.as_deref() != Some(specifier.as_str())
+          || REDECLARATION_DIAGNOSTIC_CODES.contains(&diagnostic.code)
+        {
+          return None;
+        }
+        let start = diagnostic.start.as_mut()?;
+        if start.line < line_offset {
+          return None;
+        }
+        start.line -= line_offset;
+        if let Some(end) = diagnostic.end.as_mut() {
+          end.line = end.line.saturating_sub(line_offset);
+        }
+        diagnostic.category = DiagnosticCategory::Warning;
+        Some(diagnostic)
+      })
+      .collect();
+
+    Ok(Diagnostics::new(diagnostics))
+  }
+
+  async fn evaluate_line_with_object_wrapping(
+    &mut self,
+    line: &str,
+  ) -> Result<Value, AnyError> {
+    let wrapped_line = wrap_object_literal(line);
+    // The locations in the wrapped line are one column past the ones in the
+    // line the user typed.
+    let column_offset = if wrapped_line != line { 1 } else { 0 };
+
+    let evaluate_response = self
+      .evaluate_ts_expression(&wrapped_line, column_offset)
+      .await?;
+
+    // If that fails, we retry it without wrapping in parens letting the error bubble up to the
+    // user if it is still an error.
+    let evaluate_response =
+      if evaluate_response.get("exceptionDetails").is_some()
+        && wrapped_line != line
+      {
+        self.evaluate_ts_expression(&line, 0).await?
+      } else {
+        evaluate_response
+      };
+
+    Ok(evaluate_response)
+  }
+
+  /// Awaits the result of an evaluation when it is a promise, so that its
+  /// value or rejection is shown rather than the pending promise. A promise
+  /// which isn't settled before the timeout is shown as it is.
+  async fn await_promise(
+    &mut self,
+    evaluate_response: Value,
+  ) -> Result<Value, AnyError> {
+    let evaluate_result = evaluate_response.get("result").unwrap();
+    let is_promise = evaluate_response.get("exceptionDetails").is_none()
+      && evaluate_result.get("subtype").and_then(|s| s.as_str())
+        == Some("promise");
+    let object_id = match evaluate_result.get("objectId") {
+      Some(object_id) if is_promise && self.await_timeout > 0 => object_id,
+      _ => return Ok(evaluate_response),
+    };
+
+    // The timeout is raced in the isolate rather than around the messages,
+    // as the response to an abandoned message would have nowhere to go.
+    let race_response = self
+      .post_message_with_event_loop(
+        "Runtime.callFunctionOn",
+        Some(json!({
+          "objectId": object_id,
+          "functionDeclaration": AWAIT_WITH_TIMEOUT,
+          "arguments": [{ "value": self.await_timeout }],
+        })),
+      )
+      .await?;
+    let await_response = self
+      .post_message_with_event_loop(
+        "Runtime.awaitPromise",
+        Some(json!({
+          "promiseObjectId": race_response["result"]["objectId"],
+        })),
+      )
+      .await?;
+
+    if await_response["result"]["description"] == AWAIT_TIMED_OUT {
+      Ok(evaluate_response)
+    } else {
+      Ok(await_response)
+    }
+  }
+
+  async fn set_last_thrown_error(
+    &mut self,
+    error: &Value,
+  ) -> Result<(), AnyError> {
+    self.post_message_with_event_loop(
+      "Runtime.callFunctionOn",
+      Some(json!({
+        "executionContextId": self.context_id,
+        "functionDeclaration": "function (object) { Deno[Deno.internal].lastThrownError = object; }",
+        "arguments": [
+          error,
+        ],
+      })),
+    ).await?;
+    Ok(())
+  }
+
+  async fn set_last_eval_result(
+    &mut self,
+    evaluate_result: &Value,
+  ) -> Result<(), AnyError> {
+    // `_1` to `_9` are the last nine results, the most recent first.
+    self.post_message_with_event_loop(
+      "Runtime.callFunctionOn",
+      Some(json!({
+        "executionContextId": self.context_id,
+        "functionDeclaration": "function (object) { Deno[Deno.internal].lastEvalResult = object; Deno[Deno.internal].lastEvalResults.unshift(object); Deno[Deno.internal].lastEvalResults.splice(9); }",
+        "arguments": [
+          evaluate_result,
+        ],
+      })),
+    ).await?;
+    Ok(())
+  }
+
+  pub async fn get_eval_value(
+    &mut self,
+    evaluate_result: &Value,
+  ) -> Result<String, AnyError> {
+    // TODO(caspervonb) we should investigate using previews here but to keep things
+    // consistent with the previous implementation we just get the preview result from
+    // Deno.inspectArgs.
+    let inspect_response = self.post_message_with_event_loop(
+      "Runtime.callFunctionOn",
+      Some(json!({
+        "executionContextId": self.context_id,
+        "functionDeclaration": "function (object) { return Deno[Deno.internal].inspectArgs(['%o', object], { colors: !Deno.noColor }); }",
+        "arguments": [
+          evaluate_result,
+        ],
+      })),
+    ).await?;
+
+    let inspect_result = inspect_response.get("result").unwrap();
+    let value = inspect_result.get("value").unwrap().as_str().unwrap();
+
+    Ok(value.to_string())
+  }
+
+  /// Transpiles and evaluates an input, registering its source map. Columns
+  /// on the first line of the expression are `column_offset` past where they
+  /// are in the input, like when it is wrapped in parentheses.
+  async fn evaluate_ts_expression(
+    &mut self,
+    expression: &str,
+    column_offset: u32,
+  ) -> Result<Value, AnyError> {
+    // every evaluation has its own source URL, which the source map of the
+    // transpiled expression is registered with
+    self.eval_count += 1;
+    let source_url = format!(
+      "$deno$repl{}{}",
+      self.eval_count,
+      self.media_type.as_ts_extension()
+    );
+    let parsed_module =
+      crate::ast::parse(&source_url, &expression, &self.media_type)?;
+
+    let (transpiled_src, maybe_source_map) =
+      parsed_module.transpile(&self.emit_options)?;
+    if let Some(source_map) = maybe_source_map {
+      self.program_state.register_eval_source_map(
+        &source_url,
+        adjust_source_map(&source_map, 1, column_offset)?,
+      );
+    }
+
+    self
+      .evaluate_expression(&format!(
+        "'use strict'; void 0;\n{}\n//# sourceURL={}",
+        transpiled_src, source_url
+      ))
+      .await
+  }
+
+  async fn evaluate_expression(
+    &mut self,
+    expression: &str,
+  ) -> Result<Value, AnyError> {
+    self
+      .post_message_with_event_loop(
+        "Runtime.evaluate",
+        Some(json!({
+          "expression": expression,
+          "contextId": self.context_id,
+          "replMode": true,
+        })),
+      )
+      .await
+  }
+}
+
+/// Adjusts the source map of a transpiled input to how it is evaluated and to
+/// what the user typed, so that stack traces point at the latter: generated
+/// lines are offset by the `generated_lines` prepended to the code, and the
+/// original columns of the first line are moved back by `column_offset`.
+fn adjust_source_map(
+  source_map: &str,
+  generated_lines: u32,
+  column_offset: u32,
+) -> Result<Vec<u8>, AnyError> {
+  let source_map = SourceMap::from_slice(source_map.as_bytes())?;
+  let mut builder = SourceMapBuilder::new(source_map.get_file());
+  for token in source_map.tokens() {
+    let src_col = if token.get_src_line() == 0 {
+      token.get_src_col().saturating_sub(column_offset)
+    } else {
+      token.get_src_col()
+    };
+    builder.add(
+      token.get_dst_line() + generated_lines,
+      token.get_dst_col(),
+      token.get_src_line(),
+      src_col,
+      token.get_source(),
+      token.get_name(),
+    );
+  }
+
+  let mut adjusted = Vec::new();
+  builder.into_sourcemap().to_writer(&mut adjusted)?;
+  Ok(adjusted)
+}
+
+/// It is a bit unexpected that { "foo": "bar" } is interpreted as a block
+/// statement rather than an object literal so we interpret it as an expression
+/// statement to match the behavior found in a typical prompt including browser
+/// developer tools.
+fn wrap_object_literal(line: &str) -> String {
+  if line.trim_start().starts_with('{') && !line.trim_end().ends_with(';') {
+    format!("({})", line)
+  } else {
+    line.to_string()
+  }
+}
+
+/// The options the inputs are transpiled with. JSX is only transformed with
+/// `--jsx`, using the factories set by the config file, while the rest of its
+/// options don't apply to the REPL.
+fn get_emit_options(
+  jsx: bool,
+  maybe_config_file: Option<&ConfigFile>,
+) -> Result<ast::EmitOptions, AnyError> {
+  let mut ts_config = TsConfig::new(json!({
+    "checkJs": false,
+    "emitDecoratorMetadata": false,
+    "importsNotUsedAsValues": "preserve",
+    "inlineSourceMap": false,
+    "sourceMap": true,
+    "jsx": "react",
+    "jsxFactory": "React.createElement",
+    "jsxFragmentFactory": "React.Fragment",
+  }));
+  if jsx {
+    ts_config.merge_tsconfig_from_config_file(maybe_config_file)?;
+  }
+  let config_options: ast::EmitOptions = ts_config.into();
+
+  Ok(ast::EmitOptions {
+    emit_metadata: false,
+    source_map: true,
+    inline_source_map: false,
+    imports_not_used_as_values: ImportsNotUsedAsValues::Preserve,
+    transform_jsx: jsx,
+    jsx_factory: config_options.jsx_factory,
+    jsx_fragment_factory: config_options.jsx_fragment_factory,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn adjusted_source_map() {
+    let mut builder = SourceMapBuilder::new(None);
+    builder.add(0, 0, 0, 1, Some("$deno$repl1.ts"), None);
+    builder.add(1, 2, 1, 4, Some("$deno$repl1.ts"), None);
+    let mut source_map = Vec::new();
+    builder.into_sourcemap().to_writer(&mut source_map).unwrap();
+
+    let adjusted =
+      adjust_source_map(std::str::from_utf8(&source_map).unwrap(), 1, 1)
+        .unwrap();
+    let adjusted = SourceMap::from_slice(&adjusted).unwrap();
+    let token = adjusted.lookup_token(1, 0).unwrap();
+    assert_eq!((token.get_src_line(), token.get_src_col()), (0, 0));
+    let token = adjusted.lookup_token(2, 2).unwrap();
+    assert_eq!((token.get_src_line(), token.get_src_col()), (1, 4));
+    assert_eq!(token.get_source(), Some("$deno$repl1.ts"));
+  }
+
+  #[test]
+  fn await_timeout() {
+    assert_eq!(get_await_timeout(None), DEFAULT_AWAIT_TIMEOUT);
+    assert_eq!(get_await_timeout(Some("100".to_string())), 100);
+    assert_eq!(get_await_timeout(Some("0".to_string())), 0);
+    assert_eq!(
+      get_await_timeout(Some("soon".to_string())),
+      DEFAULT_AWAIT_TIMEOUT
+    );
+  }
+}
@@ -0,0 +1,138 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+
+pub static DENO_REPL_THEME: &str = "DENO_REPL_THEME";
+
+/// Colors a token of the highlighted input.
+pub type Colorizer = fn(&str) -> String;
+
+/// The colors of the token classes of the highlighted input, `None` leaving a
+/// class uncolored. They are configured with the `DENO_REPL_THEME`
+/// environment variable, like `keyword=magenta,comment=none`.
+#[derive(Clone, Copy)]
+pub struct ReplTheme {
+  pub string: Option<Colorizer>,
+  pub regexp: Option<Colorizer>,
+  pub number: Option<Colorizer>,
+  /// `true`, `false`, `null`, `Infinity` and `NaN`.
+  pub literal: Option<Colorizer>,
+  pub undefined: Option<Colorizer>,
+  pub keyword: Option<Colorizer>,
+  pub identifier: Option<Colorizer>,
+  pub comment: Option<Colorizer>,
+  /// The bracket at the cursor and the one it pairs with.
+  pub bracket: Option<Colorizer>,
+  /// The text matched while searching the history.
+  pub search_match: Option<Colorizer>,
+}
+
+impl Default for ReplTheme {
+  fn default() -> Self {
+    ReplTheme {
+      string: Some(|s| colors::green(s).to_string()),
+      regexp: Some(|s| colors::red(s).to_string()),
+      number: Some(|s| colors::yellow(s).to_string()),
+      literal: Some(|s| colors::yellow(s).to_string()),
+      undefined: Some(|s| colors::gray(s).to_string()),
+      keyword: Some(|s| colors::cyan(s).to_string()),
+      identifier: None,
+      comment: Some(|s| colors::gray(s).to_string()),
+      bracket: Some(|s| colors::intense_blue(s).to_string()),
+      search_match: Some(|s| colors::black_on_green(s).to_string()),
+    }
+  }
+}
+
+impl ReplTheme {
+  /// Returns the default theme with the colors of a comma separated list of
+  /// `class=color` entries. Entries naming an unknown class or color are
+  /// ignored.
+  fn from_entries(entries: &str) -> Self {
+    let mut theme = ReplTheme::default();
+    for entry in entries.split(',') {
+      let (class, color) = match entry.split_once('=') {
+        Some((class, color)) => (class.trim(), color.trim()),
+        None => continue,
+      };
+      let colorizer: Colorizer = match color {
+        "none" => {
+          theme.set(class, None);
+          continue;
+        }
+        "red" => |s| colors::red(s).to_string(),
+        "green" => |s| colors::green(s).to_string(),
+        "yellow" => |s| colors::yellow(s).to_string(),
+        "blue" => |s| colors::blue(s).to_string(),
+        "intense_blue" => |s| colors::intense_blue(s).to_string(),
+        "magenta" => |s| colors::magenta(s).to_string(),
+        "cyan" => |s| colors::cyan(s).to_string(),
+        "gray" => |s| colors::gray(s).to_string(),
+        "bold" => |s| colors::bold(s).to_string(),
+        "italic" => |s| colors::italic(s).to_string(),
+        _ => continue,
+      };
+      theme.set(class, Some(colorizer));
+    }
+    theme
+  }
+
+  fn set(&mut self, class: &str, colorizer: Option<Colorizer>) {
+    match class {
+      "string" => self.string = colorizer,
+      "regexp" => self.regexp = colorizer,
+      "number" => self.number = colorizer,
+      "literal" => self.literal = colorizer,
+      "undefined" => self.undefined = colorizer,
+      "keyword" => self.keyword = colorizer,
+      "identifier" => self.identifier = colorizer,
+      "comment" => self.comment = colorizer,
+      "bracket" => self.bracket = colorizer,
+      "search_match" => self.search_match = colorizer,
+      _ => {}
+    }
+  }
+}
+
+/// Returns the theme configured with `DENO_REPL_THEME`.
+pub fn get_theme(maybe_value: Option<String>) -> ReplTheme {
+  match maybe_value {
+    Some(value) => ReplTheme::from_entries(&value),
+    None => ReplTheme::default(),
+  }
+}
+
+pub fn paint(colorizer: Option<Colorizer>, token: &str) -> String {
+  match colorizer {
+    Some(colorizer) => colorizer(token),
+    None => token.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn theme_from_env() {
+    let theme = get_theme(None);
+    assert!(theme.keyword.is_some());
+    assert!(theme.identifier.is_none());
+
+    let theme = get_theme(Some(
+      "keyword=magenta, comment=none,identifier=bold,number=plaid,foo=red"
+        .to_string(),
+    ));
+    assert_eq!(
+      paint(theme.keyword, "const"),
+      colors::magenta("const").to_string()
+    );
+    assert_eq!(paint(theme.comment, "// note"), "// note");
+    assert_eq!(
+      paint(theme.identifier, "foo"),
+      colors::bold("foo").to_string()
+    );
+    // An unknown color leaves the default one.
+    assert_eq!(paint(theme.number, "1"), colors::yellow("1").to_string());
+  }
+}
@@ -3,6 +3,8 @@
 use crate::deno_dir::DenoDir;
 use crate::flags::DenoSubcommand;
 use crate::flags::Flags;
+use crate::flags::LogFormat;
+use crate::flags::TypeCheckMode;
 use deno_core::error::bail;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
@@ -202,6 +204,7 @@ pub fn compile_to_runtime_flags(
       script: "placeholder".to_string(),
     },
     allow_env: flags.allow_env,
+    allow_ffi: flags.allow_ffi,
     allow_hrtime: flags.allow_hrtime,
     allow_net: flags.allow_net,
     allow_plugin: flags.allow_plugin,
@@ -209,10 +212,14 @@ pub fn compile_to_runtime_flags(
     allow_run: flags.allow_run,
     allow_write: flags.allow_write,
     cache_blocklist: vec![],
+    cache_dir: None,
     ca_file: flags.ca_file,
     cached_only: false,
+    compat: false,
     config_path: None,
     coverage_dir: flags.coverage_dir,
+    heap_snapshot_on_oom: false,
+    icu_data_path: None,
     ignore: vec![],
     import_map_path: None,
     inspect: None,
@@ -220,13 +227,18 @@ pub fn compile_to_runtime_flags(
     location: flags.location,
     lock: None,
     lock_write: false,
+    log_format: LogFormat::Text,
     log_level: flags.log_level,
-    no_check: false,
     prompt: flags.prompt,
+    prof_dir: None,
     no_remote: false,
+    op_latency_metrics: false,
+    proxy: None,
     reload: false,
     repl: false,
     seed: flags.seed,
+    template_imports: false,
+    typecheck_mode: TypeCheckMode::All,
     unstable: flags.unstable,
     v8_flags: flags.v8_flags,
     version: false,
@@ -303,21 +303,15 @@ pub async fn run_test_file(
   let execute_result = worker.execute_module(&main_module).await;
   execute_result?;
 
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('load'))",
-  )?;
+  worker.dispatch_load_event(&located_script_name!())?;
 
   let execute_result = worker.execute_module(&test_module).await;
   execute_result?;
 
   worker
-    .run_event_loop(maybe_coverage_collector.is_none())
+    .run_event_loop_until_unload(maybe_coverage_collector.is_none())
     .await?;
-  worker.execute_script(
-    &located_script_name!(),
-    "window.dispatchEvent(new Event('unload'))",
-  )?;
+  worker.dispatch_unload_event(&located_script_name!())?;
 
   if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
     worker
@@ -344,6 +338,7 @@ pub async fn run_tests(
   allow_none: bool,
   filter: Option<String>,
   concurrent_jobs: usize,
+  trace_ops: bool,
 ) -> Result<bool, AnyError> {
   if !doc_modules.is_empty() {
     let mut test_programs = Vec::new();
@@ -404,6 +399,7 @@ pub async fn run_tests(
             maybe_types: None,
             media_type: MediaType::TypeScript, // media_type.clone(),
             source: source.clone(),
+            maybe_raw_source: None,
             specifier: specifier.clone(),
           };
 
@@ -450,6 +446,7 @@ pub async fn run_tests(
   let test_options = json!({
       "disableLog": quiet,
       "filter": filter,
+      "traceOps": trace_ops,
   });
 
   let test_module = deno_core::resolve_path("$deno$test.js")?;
@@ -460,6 +457,7 @@ pub async fn run_tests(
     maybe_types: None,
     media_type: MediaType::JavaScript,
     source: test_source.clone(),
+    maybe_raw_source: None,
     specifier: test_module.clone(),
   };
 
@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use crate::ast;
 use crate::config_file::TsConfig;
 use crate::diagnostics::Diagnostics;
 use crate::media_type::MediaType;
@@ -41,6 +42,7 @@ pub static DENO_WEBSOCKET_LIB: &str =
   include_str!(env!("DENO_WEBSOCKET_LIB_PATH"));
 pub static DENO_WEBSTORAGE_LIB: &str =
   include_str!(env!("DENO_WEBSTORAGE_LIB_PATH"));
+pub static DENO_CACHE_LIB: &str = include_str!(env!("DENO_CACHE_LIB_PATH"));
 pub static DENO_CRYPTO_LIB: &str = include_str!(env!("DENO_CRYPTO_LIB_PATH"));
 pub static DENO_BROADCAST_CHANNEL_LIB: &str =
   include_str!(env!("DENO_BROADCAST_CHANNEL_LIB_PATH"));
@@ -182,6 +184,9 @@ pub struct Request {
   pub graph: Arc<Mutex<Graph>>,
   pub hash_data: Vec<Vec<u8>>,
   pub maybe_config_specifier: Option<ModuleSpecifier>,
+  /// An optional vector of the remote modules of the program, which are not
+  /// type checked if supplied.
+  pub maybe_remote_names: Option<Vec<ModuleSpecifier>>,
   pub maybe_tsbuildinfo: Option<String>,
   /// A vector of strings that represent the root/entry point modules for the
   /// program.
@@ -347,6 +352,57 @@ struct LoadArgs {
   specifier: String,
 }
 
+/// Convert a JSON value into the TypeScript type which describes its shape.
+fn get_json_type(value: &Value) -> String {
+  match value {
+    Value::Null => "null".to_string(),
+    Value::Bool(_) => "boolean".to_string(),
+    Value::Number(_) => "number".to_string(),
+    Value::String(_) => "string".to_string(),
+    Value::Array(items) => {
+      let mut types: Vec<String> = Vec::new();
+      for item in items {
+        let item_type = get_json_type(item);
+        if !types.contains(&item_type) {
+          types.push(item_type);
+        }
+      }
+      match types.len() {
+        0 => "unknown[]".to_string(),
+        1 => format!("{}[]", types[0]),
+        _ => format!("({})[]", types.join(" | ")),
+      }
+    }
+    Value::Object(map) => {
+      let properties: Vec<String> = map
+        .iter()
+        .map(|(key, value)| {
+          format!(
+            "{}: {};",
+            serde_json::to_string(key).unwrap(),
+            get_json_type(value)
+          )
+        })
+        .collect();
+      if properties.is_empty() {
+        "{}".to_string()
+      } else {
+        format!("{{ {} }}", properties.join(" "))
+      }
+    }
+  }
+}
+
+/// Generate the declaration of a JSON module, where the type of the default
+/// export is based on the shape of the JSON, or `unknown` if the JSON is
+/// invalid.
+fn get_json_module_declaration(source: &str) -> String {
+  let json_type = serde_json::from_str::<Value>(source)
+    .map(|value| get_json_type(&value))
+    .unwrap_or_else(|_| "unknown".to_string());
+  format!("declare const data: {};\nexport default data;\n", json_type)
+}
+
 fn op_load(state: &mut State, args: Value) -> Result<Value, AnyError> {
   let v: LoadArgs = serde_json::from_value(args)
     .context("Invalid request from JavaScript for \"op_load\".")?;
@@ -379,19 +435,41 @@ fn op_load(state: &mut State, args: Value) -> Result<Value, AnyError> {
     } else {
       specifier
     };
-    let maybe_source = graph.get_source(&specifier);
     media_type = if let Some(media_type) = graph.get_media_type(&specifier) {
       media_type
     } else {
       MediaType::Unknown
     };
+    let maybe_source = graph.get_source(&specifier).map(|source| {
+      match media_type {
+        MediaType::Json => get_json_module_declaration(&source),
+        // the TypeScript compiler does not support import assertions yet, so
+        // they are stripped before passing the source to it
+        MediaType::JavaScript
+        | MediaType::Jsx
+        | MediaType::TypeScript
+        | MediaType::Tsx
+          if source.contains("assert") =>
+        {
+          ast::parse(specifier.as_str(), &source, &media_type)
+            .map(|parsed_module| parsed_module.strip_import_assertions())
+            .unwrap_or(source)
+        }
+        _ => source,
+      }
+    });
     hash = get_maybe_hash(&maybe_source, &state.hash_data);
     maybe_source
   };
+  // JSON modules are presented to the compiler as a declaration of their
+  // default export
+  let script_kind = if media_type == MediaType::Json {
+    MediaType::TypeScript.as_ts_script_kind()
+  } else {
+    media_type.as_ts_script_kind()
+  };
 
-  Ok(
-    json!({ "data": data, "hash": hash, "scriptKind": media_type.as_ts_script_kind() }),
-  )
+  Ok(json!({ "data": data, "hash": hash, "scriptKind": script_kind }))
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -451,10 +529,12 @@ fn op_resolve(state: &mut State, args: Value) -> Result<Value, AnyError> {
             }
             _ => resolved_specifier.to_string(),
           };
-          resolved.push((
-            resolved_specifier_str,
-            media_type.as_ts_extension().into(),
-          ));
+          let extension = if media_type == MediaType::Json {
+            MediaType::TypeScript.as_ts_extension()
+          } else {
+            media_type.as_ts_extension()
+          };
+          resolved.push((resolved_specifier_str, extension.into()));
         }
         // in certain situations, like certain dynamic imports, we won't have
         // the source file in the graph, so we will return a fake module to
@@ -524,6 +604,21 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
       }
     })
     .collect();
+  // remote modules are only ever referred to by their specifier, unless they
+  // are a root that was remapped to be acceptable to tsc
+  let maybe_remote_names: Option<Vec<String>> =
+    request.maybe_remote_names.as_ref().map(|remote_names| {
+      remote_names
+        .iter()
+        .map(|s| {
+          root_map
+            .iter()
+            .find(|(_, root)| *root == s)
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| s.to_string())
+        })
+        .collect()
+    });
 
   {
     let op_state = runtime.op_state();
@@ -551,6 +646,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
   let request_value = json!({
     "config": request.config,
     "debug": request.debug,
+    "remoteNames": maybe_remote_names,
     "rootNames": root_names,
   });
   let request_str = request_value.to_string();
@@ -660,6 +756,7 @@ mod tests {
       graph,
       hash_data,
       maybe_config_specifier: None,
+      maybe_remote_names: None,
       maybe_tsbuildinfo: None,
       root_names: vec![(specifier.clone(), MediaType::TypeScript)],
     };
@@ -706,6 +803,20 @@ mod tests {
     assert_eq!(hash_data_url(&specifier, &MediaType::JavaScript), "data:///d300ea0796bd72b08df10348e0b70514c021f2e45bfe59cec24e12e97cd79c58.js");
   }
 
+  #[test]
+  fn test_get_json_module_declaration() {
+    assert_eq!(
+      get_json_module_declaration(
+        r#"{ "a": 1, "b": ["c", 2, "d"], "e": { "f": null }, "g": [], "h": {} }"#
+      ),
+      "declare const data: { \"a\": number; \"b\": (string | number)[]; \"e\": { \"f\": null; }; \"g\": unknown[]; \"h\": {}; };\nexport default data;\n"
+    );
+    assert_eq!(
+      get_json_module_declaration("{ invalid"),
+      "declare const data: unknown;\nexport default data;\n"
+    );
+  }
+
   #[test]
   fn test_get_tsc_media_type() {
     let fixtures = vec![
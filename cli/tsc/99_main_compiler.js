@@ -78,6 +78,9 @@ delete Object.prototype.__proto__;
   /** @type {Map<string, string>} */
   const scriptVersionCache = new Map();
 
+  /** @type {string | undefined} */
+  let projectVersionCache;
+
   /** @param {ts.DiagnosticRelatedInformation} diagnostic */
   function fromRelatedInformation({
     start,
@@ -368,6 +371,15 @@ delete Object.prototype.__proto__;
       debug("host.getCompilationSettings()");
       return compilationSettings;
     },
+    getProjectVersion() {
+      // when the project version is unchanged, tsc reuses the program of the
+      // previous request without checking the version of every script
+      if (projectVersionCache === undefined) {
+        debug("host.getProjectVersion()");
+        projectVersionCache = core.opSync("op_project_version", undefined);
+      }
+      return projectVersionCache;
+    },
     getScriptFileNames() {
       debug("host.getScriptFileNames()");
       return core.opSync("op_script_names", undefined);
@@ -489,10 +501,10 @@ delete Object.prototype.__proto__;
   /** The API that is called by Rust when executing a request.
    * @param {Request} request
    */
-  function exec({ config, debug: debugFlag, rootNames }) {
+  function exec({ config, debug: debugFlag, remoteNames, rootNames }) {
     setLogDebug(debugFlag, "TS");
     performanceStart();
-    debug(">>> exec start", { rootNames });
+    debug(">>> exec start", { rootNames, remoteNames });
     debug(config);
 
     rootNames.forEach(checkNormalizedPath);
@@ -513,12 +525,25 @@ delete Object.prototype.__proto__;
 
     const { diagnostics: emitDiagnostics } = program.emit();
 
+    // When the remote modules are supplied, only the semantic diagnostics of
+    // the other modules are requested, so the remote modules are only checked
+    // as far as is needed to check the modules which import them.
+    let semanticDiagnostics;
+    if (remoteNames) {
+      const remoteNameSet = new Set(remoteNames);
+      semanticDiagnostics = program.getSourceFiles()
+        .filter(({ fileName }) => !remoteNameSet.has(fileName))
+        .flatMap((sourceFile) => program.getSemanticDiagnostics(sourceFile));
+    } else {
+      semanticDiagnostics = program.getSemanticDiagnostics();
+    }
+
     const diagnostics = [
       ...program.getConfigFileParsingDiagnostics(),
       ...program.getSyntacticDiagnostics(),
       ...program.getOptionsDiagnostics(),
       ...program.getGlobalDiagnostics(),
-      ...program.getSemanticDiagnostics(),
+      ...semanticDiagnostics,
       ...emitDiagnostics,
     ].filter(({ code }) => !IGNORED_DIAGNOSTICS.includes(code));
     performanceProgram({ program });
@@ -545,6 +570,7 @@ delete Object.prototype.__proto__;
     debug(`serverRequest()`, { id, ...request });
     // evict all memoized source file versions
     scriptVersionCache.clear();
+    projectVersionCache = undefined;
     switch (request.method) {
       case "configure": {
         const { options, errors } = ts
@@ -783,6 +809,12 @@ delete Object.prototype.__proto__;
           ),
         );
       }
+      case "restart": {
+        // discarding the program forces module resolution to be performed
+        // again, which is needed when the import map changes.
+        languageService.cleanupSemanticCache();
+        return respond(id, true);
+      }
       default:
         throw new TypeError(
           // @ts-ignore exhausted case statement sets type to never
@@ -65,7 +65,8 @@ declare global {
     | GetSupportedCodeFixes
     | PrepareCallHierarchy
     | ProvideCallHierarchyIncomingCalls
-    | ProvideCallHierarchyOutgoingCalls;
+    | ProvideCallHierarchyOutgoingCalls
+    | Restart;
 
   interface BaseLanguageServerRequest {
     id: number;
@@ -214,4 +215,8 @@ declare global {
     specifier: string;
     position: number;
   }
+
+  interface Restart extends BaseLanguageServerRequest {
+    method: "restart";
+  }
 }
@@ -0,0 +1,336 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Support for importing WebAssembly modules as ES modules.  The binary is
+//! converted into a JavaScript module which compiles and instantiates the
+//! WebAssembly module, importing its dependencies as ES modules and exporting
+//! the exports of the instance, following the semantics of the WebAssembly ES
+//! module integration proposal.  The exports are annotated with JSDoc types,
+//! so that the type checker is able to type check their usage.
+
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use std::fmt::Write;
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+const WASM_VERSION: &[u8] = &[1, 0, 0, 0];
+
+const IMPORT_SECTION: u8 = 2;
+const EXPORT_SECTION: u8 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+  Function,
+  Table,
+  Memory,
+  Global,
+}
+
+impl ExportKind {
+  fn as_jsdoc_type(&self) -> &str {
+    match self {
+      ExportKind::Function => "(...args: any[]) => any",
+      ExportKind::Table => "WebAssembly.Table",
+      ExportKind::Memory => "WebAssembly.Memory",
+      ExportKind::Global => "WebAssembly.Global",
+    }
+  }
+}
+
+/// The information about a WebAssembly module which is required to generate
+/// its JavaScript module.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WasmModuleInfo {
+  /// The unique module names of the imports, in the order they first appear.
+  pub imports: Vec<String>,
+  /// The names and kinds of the exports.
+  pub exports: Vec<(String, ExportKind)>,
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn new(bytes: &'a [u8]) -> Self {
+    Self { bytes, pos: 0 }
+  }
+
+  fn is_empty(&self) -> bool {
+    self.pos >= self.bytes.len()
+  }
+
+  fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], AnyError> {
+    let end = self
+      .pos
+      .checked_add(len)
+      .filter(|end| *end <= self.bytes.len())
+      .ok_or_else(|| generic_error("Unexpected end of WebAssembly module."))?;
+    let bytes = &self.bytes[self.pos..end];
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  fn read_u8(&mut self) -> Result<u8, AnyError> {
+    Ok(self.read_bytes(1)?[0])
+  }
+
+  /// Read an unsigned LEB128 encoded 32 bit integer.
+  fn read_u32(&mut self) -> Result<u32, AnyError> {
+    let mut result: u32 = 0;
+    let mut shift = 0;
+    loop {
+      let byte = self.read_u8()?;
+      result |= ((byte & 0x7f) as u32) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(result);
+      }
+      shift += 7;
+      if shift > 28 {
+        return Err(generic_error("Invalid integer in WebAssembly module."));
+      }
+    }
+  }
+
+  fn read_name(&mut self) -> Result<String, AnyError> {
+    let len = self.read_u32()? as usize;
+    let bytes = self.read_bytes(len)?;
+    String::from_utf8(bytes.to_vec())
+      .map_err(|_| generic_error("Invalid name in WebAssembly module."))
+  }
+
+  fn skip_limits(&mut self) -> Result<(), AnyError> {
+    let flags = self.read_u8()?;
+    self.read_u32()?;
+    if flags & 0x01 != 0 {
+      self.read_u32()?;
+    }
+    Ok(())
+  }
+}
+
+fn read_imports(
+  reader: &mut Reader,
+  info: &mut WasmModuleInfo,
+) -> Result<(), AnyError> {
+  let count = reader.read_u32()?;
+  for _ in 0..count {
+    let module = reader.read_name()?;
+    reader.read_name()?;
+    match reader.read_u8()? {
+      // function, by type index
+      0x00 => {
+        reader.read_u32()?;
+      }
+      // table, by reference type and limits
+      0x01 => {
+        reader.read_u8()?;
+        reader.skip_limits()?;
+      }
+      // memory, by limits
+      0x02 => reader.skip_limits()?,
+      // global, by value type and mutability
+      0x03 => {
+        reader.read_bytes(2)?;
+      }
+      kind => {
+        return Err(generic_error(format!(
+          "Unsupported import kind {} in WebAssembly module.",
+          kind
+        )))
+      }
+    }
+    if !info.imports.contains(&module) {
+      info.imports.push(module);
+    }
+  }
+  Ok(())
+}
+
+fn read_exports(
+  reader: &mut Reader,
+  info: &mut WasmModuleInfo,
+) -> Result<(), AnyError> {
+  let count = reader.read_u32()?;
+  for _ in 0..count {
+    let name = reader.read_name()?;
+    let kind = match reader.read_u8()? {
+      0x00 => ExportKind::Function,
+      0x01 => ExportKind::Table,
+      0x02 => ExportKind::Memory,
+      0x03 => ExportKind::Global,
+      kind => {
+        return Err(generic_error(format!(
+          "Unsupported export kind {} in WebAssembly module.",
+          kind
+        )))
+      }
+    };
+    reader.read_u32()?;
+    info.exports.push((name, kind));
+  }
+  Ok(())
+}
+
+/// Parse the imports and exports of a binary WebAssembly module.
+pub fn parse_wasm_module(bytes: &[u8]) -> Result<WasmModuleInfo, AnyError> {
+  let mut reader = Reader::new(bytes);
+  if reader.read_bytes(4).ok() != Some(WASM_MAGIC)
+    || reader.read_bytes(4).ok() != Some(WASM_VERSION)
+  {
+    return Err(generic_error("Invalid WebAssembly module."));
+  }
+  let mut info = WasmModuleInfo::default();
+  while !reader.is_empty() {
+    let id = reader.read_u8()?;
+    let size = reader.read_u32()? as usize;
+    let mut section = Reader::new(reader.read_bytes(size)?);
+    match id {
+      IMPORT_SECTION => read_imports(&mut section, &mut info)?,
+      EXPORT_SECTION => read_exports(&mut section, &mut info)?,
+      _ => (),
+    }
+  }
+  Ok(info)
+}
+
+fn is_identifier_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  match chars.next() {
+    Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$' => {
+      chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    }
+    _ => false,
+  }
+}
+
+/// Generate the JavaScript module source for a binary WebAssembly module.
+pub fn generate_module_source(bytes: &[u8]) -> Result<String, AnyError> {
+  let info = parse_wasm_module(bytes)?;
+  let mut source = String::new();
+  writeln!(source, "// @ts-nocheck").unwrap();
+  for (i, module) in info.imports.iter().enumerate() {
+    writeln!(
+      source,
+      "import * as import{} from {};",
+      i,
+      serde_json::to_string(module)?
+    )
+    .unwrap();
+  }
+  writeln!(
+    source,
+    "const wasmBytes = Uint8Array.from(atob(\"{}\"), (c) => c.charCodeAt(0));",
+    base64::encode(bytes)
+  )
+  .unwrap();
+  writeln!(
+    source,
+    "const wasmModule = await WebAssembly.compile(wasmBytes);"
+  )
+  .unwrap();
+  let import_object = info
+    .imports
+    .iter()
+    .enumerate()
+    .map(|(i, module)| {
+      Ok(format!("{}: import{}", serde_json::to_string(module)?, i))
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?
+    .join(", ");
+  writeln!(
+    source,
+    "const wasmInstance = await WebAssembly.instantiate(wasmModule, {{ {} }});",
+    import_object
+  )
+  .unwrap();
+  let mut export_names = Vec::new();
+  for (i, (name, kind)) in info.exports.iter().enumerate() {
+    // export names which are not valid identifiers can't be expressed in the
+    // export clause, so they are only available via the instance
+    if !is_identifier_name(name) {
+      continue;
+    }
+    writeln!(source, "/** @type {{{}}} */", kind.as_jsdoc_type()).unwrap();
+    writeln!(
+      source,
+      "const export{} = wasmInstance.exports[{}];",
+      i,
+      serde_json::to_string(name)?
+    )
+    .unwrap();
+    export_names.push(format!("export{} as {}", i, name));
+  }
+  if !export_names.is_empty() {
+    writeln!(source, "export {{ {} }};", export_names.join(", ")).unwrap();
+  }
+  Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A module importing `log` from `./env.js` and exporting an `add` function
+  /// and `memory`.
+  fn fixture() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"\0asm");
+    bytes.extend_from_slice(&[1, 0, 0, 0]);
+    // type section: (i32, i32) -> i32
+    bytes.extend_from_slice(&[1, 7, 1, 0x60, 2, 0x7f, 0x7f, 1, 0x7f]);
+    // import section: "./env.js" "log" (func 0)
+    bytes.extend_from_slice(&[2, 16, 1, 8]);
+    bytes.extend_from_slice(b"./env.js");
+    bytes.push(3);
+    bytes.extend_from_slice(b"log");
+    bytes.extend_from_slice(&[0, 0]);
+    // function section
+    bytes.extend_from_slice(&[3, 2, 1, 0]);
+    // memory section
+    bytes.extend_from_slice(&[5, 3, 1, 0, 1]);
+    // export section: "add" (func 1), "memory" (memory 0)
+    bytes.extend_from_slice(&[7, 16, 2, 3]);
+    bytes.extend_from_slice(b"add");
+    bytes.extend_from_slice(&[0, 1, 6]);
+    bytes.extend_from_slice(b"memory");
+    bytes.extend_from_slice(&[2, 0]);
+    // code section
+    bytes.extend_from_slice(&[10, 9, 1, 7, 0, 0x20, 0, 0x20, 1, 0x6a, 0x0b]);
+    bytes
+  }
+
+  #[test]
+  fn test_parse_wasm_module() {
+    let info = parse_wasm_module(&fixture()).unwrap();
+    assert_eq!(
+      info,
+      WasmModuleInfo {
+        imports: vec!["./env.js".to_string()],
+        exports: vec![
+          ("add".to_string(), ExportKind::Function),
+          ("memory".to_string(), ExportKind::Memory),
+        ],
+      }
+    );
+  }
+
+  #[test]
+  fn test_parse_wasm_module_invalid() {
+    assert!(parse_wasm_module(b"export const a = 1;").is_err());
+    let mut bytes = fixture();
+    bytes.truncate(20);
+    assert!(parse_wasm_module(&bytes).is_err());
+  }
+
+  #[test]
+  fn test_generate_module_source() {
+    let source = generate_module_source(&fixture()).unwrap();
+    assert!(source.contains("import * as import0 from \"./env.js\";"));
+    assert!(source.contains("{ \"./env.js\": import0 }"));
+    assert!(source.contains("/** @type {(...args: any[]) => any} */"));
+    assert!(source.contains("/** @type {WebAssembly.Memory} */"));
+    assert!(source.contains("export { export0 as add, export1 as memory };"));
+  }
+}
@@ -8,6 +8,7 @@ use crate::OpId;
 use crate::OpPayload;
 use crate::OpTable;
 use crate::PromiseId;
+use crate::SharedArrayBufferStore;
 use crate::ZeroCopyBuf;
 use log::debug;
 use rusty_v8 as v8;
@@ -18,6 +19,8 @@ use std::convert::TryInto;
 use std::option::Option;
 use url::Url;
 use v8::MapFnTo;
+use v8::ValueDeserializerHelper;
+use v8::ValueSerializerHelper;
 
 lazy_static::lazy_static! {
   pub static ref EXTERNAL_REFERENCES: v8::ExternalReferences =
@@ -28,6 +31,9 @@ lazy_static::lazy_static! {
       v8::ExternalReference {
         function: set_macrotask_callback.map_fn_to()
       },
+      v8::ExternalReference {
+        function: set_promise_rejection_handler.map_fn_to()
+      },
       v8::ExternalReference {
         function: eval_context.map_fn_to()
       },
@@ -46,6 +52,9 @@ lazy_static::lazy_static! {
       v8::ExternalReference {
         function: deserialize.map_fn_to()
       },
+      v8::ExternalReference {
+        function: detach_array_buffer.map_fn_to()
+      },
       v8::ExternalReference {
         function: get_promise_details.map_fn_to()
       },
@@ -122,14 +131,21 @@ pub fn initialize_context<'s>(
     "setMacrotaskCallback",
     set_macrotask_callback,
   );
+  set_func(
+    scope,
+    core_val,
+    "setPromiseRejectionHandler",
+    set_promise_rejection_handler,
+  );
   set_func(scope, core_val, "evalContext", eval_context);
   set_func(scope, core_val, "encode", encode);
   set_func(scope, core_val, "decode", decode);
   set_func(scope, core_val, "serialize", serialize);
   set_func(scope, core_val, "deserialize", deserialize);
+  set_func(scope, core_val, "detachArrayBuffer", detach_array_buffer);
   set_func(scope, core_val, "getPromiseDetails", get_promise_details);
   set_func(scope, core_val, "getProxyDetails", get_proxy_details);
-  set_func(scope, core_val, "memoryUsage", memory_usage);
+  set_func(scope, core_val, "heapStats", heap_stats);
 
   // Direct bindings on `window`.
   set_func(scope, global, "queueMicrotask", queue_microtask);
@@ -265,7 +281,16 @@ pub extern "C" fn promise_reject_callback(message: v8::PromiseRejectMessage) {
         .insert(promise_global, error_global);
     }
     v8::PromiseRejectEvent::PromiseHandlerAddedAfterReject => {
-      state.pending_promise_exceptions.remove(&promise_global);
+      // A rejection that is no longer pending has already been reported to
+      // the promise rejection handler, which gets to know it's handled now.
+      if state
+        .pending_promise_exceptions
+        .remove(&promise_global)
+        .is_none()
+        && state.js_promise_rejection_handler.is_some()
+      {
+        state.pending_handled_rejections.push(promise_global);
+      }
     }
     v8::PromiseRejectEvent::PromiseRejectAfterResolved => {}
     v8::PromiseRejectEvent::PromiseResolveAfterResolved => {
@@ -375,6 +400,24 @@ fn set_macrotask_callback(
   slot.replace(v8::Global::new(scope, cb));
 }
 
+fn set_promise_rejection_handler(
+  scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  _rv: v8::ReturnValue,
+) {
+  let state_rc = JsRuntime::state(scope);
+  let mut state = state_rc.borrow_mut();
+
+  let handler = match v8::Local::<v8::Function>::try_from(args.get(0)) {
+    Ok(handler) => handler,
+    Err(err) => return throw_type_error(scope, err.to_string()),
+  };
+
+  state
+    .js_promise_rejection_handler
+    .replace(v8::Global::new(scope, handler));
+}
+
 fn eval_context(
   scope: &mut v8::HandleScope,
   args: v8::FunctionCallbackArguments,
@@ -514,7 +557,20 @@ fn decode(
   };
 }
 
-struct SerializeDeserialize {}
+struct SerializeDeserialize {
+  shared_array_buffer_store: Option<SharedArrayBufferStore>,
+}
+
+impl SerializeDeserialize {
+  fn new(scope: &mut v8::HandleScope) -> Box<Self> {
+    let state_rc = JsRuntime::state(scope);
+    let shared_array_buffer_store =
+      state_rc.borrow().shared_array_buffer_store.clone();
+    Box::new(Self {
+      shared_array_buffer_store,
+    })
+  }
+}
 
 impl v8::ValueSerializerImpl for SerializeDeserialize {
   #[allow(unused_variables)]
@@ -526,18 +582,62 @@ impl v8::ValueSerializerImpl for SerializeDeserialize {
     let error = v8::Exception::error(scope, message);
     scope.throw_exception(error);
   }
+
+  fn get_shared_array_buffer_id<'s>(
+    &mut self,
+    scope: &mut v8::HandleScope<'s>,
+    shared_array_buffer: v8::Local<'s, v8::SharedArrayBuffer>,
+  ) -> Option<u32> {
+    match &self.shared_array_buffer_store {
+      Some(store) => {
+        Some(store.insert(shared_array_buffer.get_backing_store()))
+      }
+      None => {
+        let message =
+          v8::String::new(scope, "SharedArrayBuffers can not be shared")
+            .unwrap();
+        self.throw_data_clone_error(scope, message);
+        None
+      }
+    }
+  }
 }
 
-impl v8::ValueDeserializerImpl for SerializeDeserialize {}
+impl v8::ValueDeserializerImpl for SerializeDeserialize {
+  fn get_shared_array_buffer_from_id<'s>(
+    &mut self,
+    scope: &mut v8::HandleScope<'s>,
+    transfer_id: u32,
+  ) -> Option<v8::Local<'s, v8::SharedArrayBuffer>> {
+    let backing_store =
+      self.shared_array_buffer_store.as_ref()?.take(transfer_id)?;
+    Some(v8::SharedArrayBuffer::with_backing_store(
+      scope,
+      &backing_store,
+    ))
+  }
+}
 
 fn serialize(
   scope: &mut v8::HandleScope,
   args: v8::FunctionCallbackArguments,
   mut rv: v8::ReturnValue,
 ) {
-  let serialize_deserialize = Box::new(SerializeDeserialize {});
+  let transferred_array_buffers =
+    match get_transferred_array_buffers(scope, args.get(1)) {
+      Ok(buffers) => buffers,
+      Err(message) => {
+        throw_type_error(scope, message);
+        return;
+      }
+    };
+
+  let serialize_deserialize = SerializeDeserialize::new(scope);
   let mut value_serializer =
     v8::ValueSerializer::new(scope, serialize_deserialize);
+  for (id, buffer) in transferred_array_buffers.into_iter().enumerate() {
+    value_serializer.transfer_array_buffer(id as u32, buffer);
+  }
   match value_serializer.write_value(scope.get_current_context(), args.get(0)) {
     Some(true) => {
       let vector = value_serializer.release();
@@ -563,10 +663,21 @@ fn deserialize(
     }
   };
   let buf = &zero_copy;
+  let transferred_array_buffers =
+    match get_transferred_array_buffers(scope, args.get(1)) {
+      Ok(buffers) => buffers,
+      Err(message) => {
+        throw_type_error(scope, message);
+        return;
+      }
+    };
 
-  let serialize_deserialize = Box::new(SerializeDeserialize {});
+  let serialize_deserialize = SerializeDeserialize::new(scope);
   let mut value_deserializer =
     v8::ValueDeserializer::new(scope, serialize_deserialize, buf);
+  for (id, buffer) in transferred_array_buffers.into_iter().enumerate() {
+    value_deserializer.transfer_array_buffer(id as u32, buffer);
+  }
   let value = value_deserializer.read_value(scope.get_current_context());
 
   match value {
@@ -579,6 +690,56 @@ fn deserialize(
   };
 }
 
+/// Gets the `transferredArrayBuffers` option of `Deno.core.serialize()` and
+/// `Deno.core.deserialize()`. The array buffers it lists are serialized as
+/// their index in the list instead of their contents, which is how the
+/// receiving side matches them up with the array buffers it was sent.
+fn get_transferred_array_buffers<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  options: v8::Local<'s, v8::Value>,
+) -> Result<Vec<v8::Local<'s, v8::ArrayBuffer>>, &'static str> {
+  if options.is_null_or_undefined() {
+    return Ok(Vec::new());
+  }
+  let options = v8::Local::<v8::Object>::try_from(options)
+    .map_err(|_| "Invalid options argument")?;
+  let key = v8::String::new(scope, "transferredArrayBuffers").unwrap();
+  let value = match options.get(scope, key.into()) {
+    Some(value) if !value.is_null_or_undefined() => value,
+    _ => return Ok(Vec::new()),
+  };
+  let array = v8::Local::<v8::Array>::try_from(value)
+    .map_err(|_| "transferredArrayBuffers is not an array")?;
+
+  let mut buffers = Vec::with_capacity(array.length() as usize);
+  for index in 0..array.length() {
+    let item = array.get_index(scope, index).unwrap();
+    let buffer = v8::Local::<v8::ArrayBuffer>::try_from(item)
+      .map_err(|_| "Item in transferredArrayBuffers is not an ArrayBuffer")?;
+    buffers.push(buffer);
+  }
+  Ok(buffers)
+}
+
+fn detach_array_buffer(
+  scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  _rv: v8::ReturnValue,
+) {
+  let buffer = match v8::Local::<v8::ArrayBuffer>::try_from(args.get(0)) {
+    Ok(buffer) => buffer,
+    Err(_) => {
+      throw_type_error(scope, "Invalid argument");
+      return;
+    }
+  };
+  if !buffer.is_detachable() {
+    throw_type_error(scope, "ArrayBuffer is not detachable");
+    return;
+  }
+  buffer.detach();
+}
+
 fn queue_microtask(
   scope: &mut v8::HandleScope,
   args: v8::FunctionCallbackArguments,
@@ -717,20 +878,21 @@ fn throw_type_error(scope: &mut v8::HandleScope, message: impl AsRef<str>) {
   scope.throw_exception(exception);
 }
 
-fn memory_usage(
+fn heap_stats(
   scope: &mut v8::HandleScope,
   _args: v8::FunctionCallbackArguments,
   mut rv: v8::ReturnValue,
 ) {
-  let stats = get_memory_usage(scope);
+  let stats = get_heap_stats(scope);
   rv.set(to_v8(scope, stats).unwrap());
 }
 
-// HeapStats stores values from a isolate.get_heap_statistics() call
+// HeapStats stores values from a isolate.get_heap_statistics() call. The RSS
+// of the process is not known to V8, the runtime adds it for
+// `Deno.memoryUsage()`.
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct MemoryUsage {
-  rss: usize,
+struct HeapStats {
   heap_total: usize,
   heap_used: usize,
   external: usize,
@@ -738,12 +900,11 @@ struct MemoryUsage {
   // but it's otherwise a subset of external so can be indirectly tracked
   // array_buffers: usize,
 }
-fn get_memory_usage(isolate: &mut v8::Isolate) -> MemoryUsage {
+fn get_heap_stats(isolate: &mut v8::Isolate) -> HeapStats {
   let mut s = v8::HeapStatistics::default();
   isolate.get_heap_statistics(&mut s);
 
-  MemoryUsage {
-    rss: s.total_physical_size(),
+  HeapStats {
     heap_total: s.total_heap_size(),
     heap_used: s.used_heap_size(),
     external: s.external_memory(),
@@ -21,6 +21,18 @@
   const NO_PROMISE = null; // Alias to null is faster than plain nulls
   const promiseRing = new Array(RING_SIZE).fill(NO_PROMISE);
 
+  let opCallTracingEnabled = false;
+  // Maps the promise id of each pending async op to its name and the stack
+  // trace of its call site, while tracing is enabled.
+  const opCallTraces = new Map();
+  // Maps the id of each open resource which was opened while tracing is
+  // enabled to the name and the call site of the op which opened it.
+  const resourceTraces = new Map();
+
+  function enableOpCallTracing() {
+    opCallTracingEnabled = true;
+  }
+
   function setPromise(promiseId) {
     const idx = promiseId % RING_SIZE;
     // Move old promise from ring to map
@@ -74,6 +86,31 @@
       const res = arguments[i + 1];
       const promise = getPromise(promiseId);
       promise.resolve(res);
+      if (opCallTracingEnabled) {
+        const trace = opCallTraces.get(promiseId);
+        if (trace) {
+          traceReturnedResource(res, trace);
+          opCallTraces.delete(promiseId);
+        }
+      }
+    }
+  }
+
+  function nextRid() {
+    return dispatch("op_next_rid", null, null, null);
+  }
+
+  // Other async ops may open resources while one is pending, so an async op
+  // is only credited with the resource it resolves to, like the rid returned
+  // by `op_open_async`, provided that it was opened after the op was
+  // dispatched.
+  function traceReturnedResource(res, { opName, stack, firstRid }) {
+    const rid = typeof res === "number" ? res : res?.rid;
+    if (
+      typeof rid === "number" && rid >= firstRid && rid < nextRid() &&
+      !resourceTraces.has(rid)
+    ) {
+      resourceTraces.set(rid, { opName, stack });
     }
   }
 
@@ -113,19 +150,43 @@
     const maybeError = dispatch(opName, promiseId, arg1, arg2);
     // Handle sync error (e.g: error parsing args)
     if (maybeError) return unwrapOpResult(maybeError);
+    if (opCallTracingEnabled) {
+      // Drop the "Error" line and the frame of `opAsync` itself.
+      const stack = new Error().stack.split("\n").slice(2).join("\n");
+      opCallTraces.set(promiseId, { opName, stack, firstRid: nextRid() });
+    }
     return setPromise(promiseId).then(unwrapOpResult);
   }
 
   function opSync(opName, arg1 = null, arg2 = null) {
-    return unwrapOpResult(dispatch(opName, null, arg1, arg2));
+    if (!opCallTracingEnabled) {
+      return unwrapOpResult(dispatch(opName, null, arg1, arg2));
+    }
+
+    // Every resource opened during a sync op was opened by it.
+    const firstRid = nextRid();
+    const res = dispatch(opName, null, arg1, arg2);
+    const endRid = nextRid();
+    if (endRid > firstRid) {
+      // Drop the "Error" line and the frame of `opSync` itself.
+      const stack = new Error().stack.split("\n").slice(2).join("\n");
+      for (let rid = firstRid; rid < endRid; rid++) {
+        resourceTraces.set(rid, { opName, stack });
+      }
+    }
+    return unwrapOpResult(res);
   }
 
-  function resources() {
+  function resources(options = {}) {
+    if (options?.metadata) {
+      return Object.fromEntries(opSync("op_resources_metadata"));
+    }
     return Object.fromEntries(opSync("op_resources"));
   }
 
   function close(rid) {
     opSync("op_close", rid);
+    resourceTraces.delete(rid);
   }
 
   function print(str, isErr = false) {
@@ -146,5 +207,8 @@
     registerErrorClass,
     handleAsyncMsgFromRust,
     syncOpsCache,
+    enableOpCallTracing,
+    opCallTraces,
+    resourceTraces,
   });
 })(this);
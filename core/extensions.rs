@@ -6,6 +6,9 @@ pub type SourceLoadFn = dyn Fn() -> Result<String, AnyError>;
 pub type OpPair = (&'static str, Box<OpFn>);
 pub type OpMiddlewareFn = dyn Fn(&'static str, Box<OpFn>) -> Box<OpFn>;
 pub type OpStateFn = dyn Fn(&mut OpState) -> Result<(), AnyError>;
+/// Names the JS error class of an error returned from an op, or `None` if the
+/// error is not one this function knows about.
+pub type ErrorClassFn = fn(&AnyError) -> Option<&'static str>;
 
 #[derive(Default)]
 pub struct Extension {
@@ -13,6 +16,7 @@ pub struct Extension {
   ops: Option<Vec<OpPair>>,
   opstate_fn: Option<Box<OpStateFn>>,
   middleware_fn: Option<Box<OpMiddlewareFn>>,
+  error_class_fns: Vec<ErrorClassFn>,
   initialized: bool,
 }
 
@@ -55,6 +59,12 @@ impl Extension {
   pub fn init_middleware(&mut self) -> Option<Box<OpMiddlewareFn>> {
     self.middleware_fn.take()
   }
+
+  /// Returns the functions classifying the errors of this extension, which are
+  /// consulted before the runtime's `get_error_class_fn`.
+  pub fn init_error_classes(&self) -> &[ErrorClassFn] {
+    &self.error_class_fns
+  }
 }
 
 // Provides a convenient builder pattern to declare Extensions
@@ -64,6 +74,7 @@ pub struct ExtensionBuilder {
   ops: Vec<OpPair>,
   state: Option<Box<OpStateFn>>,
   middleware: Option<Box<OpMiddlewareFn>>,
+  error_class_fns: Vec<ErrorClassFn>,
 }
 
 impl ExtensionBuilder {
@@ -93,6 +104,15 @@ impl ExtensionBuilder {
     self
   }
 
+  /// Registers a function naming the JS error classes of errors returned from
+  /// ops, e.g. by downcasting them to the extension's own error types. The
+  /// classes must be registered on the JS side with
+  /// `Deno.core.registerErrorClass()`.
+  pub fn error_class(&mut self, error_class_fn: ErrorClassFn) -> &mut Self {
+    self.error_class_fns.push(error_class_fn);
+    self
+  }
+
   pub fn build(&mut self) -> Extension {
     let js_files = Some(std::mem::take(&mut self.js));
     let ops = Some(std::mem::take(&mut self.ops));
@@ -101,6 +121,7 @@ impl ExtensionBuilder {
       ops,
       opstate_fn: self.state.take(),
       middleware_fn: self.middleware.take(),
+      error_class_fns: std::mem::take(&mut self.error_class_fns),
       initialized: false,
     }
   }
@@ -81,6 +81,7 @@ pub struct JsRuntimeInspector {
   v8_inspector_client: v8::inspector::V8InspectorClientBase,
   v8_inspector: Rc<RefCell<v8::UniquePtr<v8::inspector::V8Inspector>>>,
   new_session_tx: UnboundedSender<InspectorSessionProxy>,
+  new_internal_session_tx: UnboundedSender<InspectorSessionProxy>,
   sessions: RefCell<SessionContainer>,
   flags: RefCell<InspectorFlags>,
   waker: Arc<InspectorWaker>,
@@ -157,6 +158,8 @@ impl JsRuntimeInspector {
 
     let (new_session_tx, new_session_rx) =
       mpsc::unbounded::<InspectorSessionProxy>();
+    let (new_internal_session_tx, new_internal_session_rx) =
+      mpsc::unbounded::<InspectorSessionProxy>();
 
     let v8_inspector_client =
       v8::inspector::V8InspectorClientBase::new::<Self>();
@@ -170,6 +173,7 @@ impl JsRuntimeInspector {
       v8_inspector: Default::default(),
       sessions: Default::default(),
       new_session_tx,
+      new_internal_session_tx,
       flags,
       waker,
       deregister_tx: None,
@@ -177,8 +181,11 @@ impl JsRuntimeInspector {
     self_.v8_inspector = Rc::new(RefCell::new(
       v8::inspector::V8Inspector::create(scope, &mut *self_).into(),
     ));
-    self_.sessions =
-      SessionContainer::new(self_.v8_inspector.clone(), new_session_rx);
+    self_.sessions = SessionContainer::new(
+      self_.v8_inspector.clone(),
+      new_session_rx,
+      new_internal_session_rx,
+    );
 
     // Tell the inspector about the global context.
     let context = v8::Local::new(scope, context);
@@ -253,6 +260,19 @@ impl JsRuntimeInspector {
           Poll::Pending => {}
         }
 
+        // Accept new internal sessions, which skip the handshake.
+        if let Poll::Ready(Some(session)) =
+          sessions.new_internal.poll_next_unpin(cx)
+        {
+          sessions.internal.push(session);
+          continue;
+        }
+
+        // Poll internal sessions.
+        if let Poll::Ready(Some(_)) = sessions.internal.poll_next_unpin(cx) {
+          continue;
+        }
+
         // Poll established sessions.
         match sessions.established.poll_next_unpin(cx) {
           Poll::Ready(Some(_)) => continue,
@@ -331,6 +351,18 @@ impl JsRuntimeInspector {
     self.new_session_tx.clone()
   }
 
+  /// Obtain a sender for proxy channels of sessions used by the runtime
+  /// itself, e.g. by `Deno.Profiler`.
+  ///
+  /// These sessions are established without a "handshake", and unlike the
+  /// sessions of a frontend they are not reported by `has_active_sessions()`,
+  /// so they don't keep the event loop alive.
+  pub fn get_internal_session_sender(
+    &self,
+  ) -> UnboundedSender<InspectorSessionProxy> {
+    self.new_internal_session_tx.clone()
+  }
+
   /// Create a channel that notifies the frontend when inspector is dropped.
   ///
   /// NOTE: Only a single handler is currently available.
@@ -394,20 +426,30 @@ struct SessionContainer {
   new_incoming: Pin<Box<dyn Stream<Item = Box<InspectorSession>> + 'static>>,
   handshake: Option<Box<InspectorSession>>,
   established: FuturesUnordered<Box<InspectorSession>>,
+  new_internal: Pin<Box<dyn Stream<Item = Box<InspectorSession>> + 'static>>,
+  internal: FuturesUnordered<Box<InspectorSession>>,
 }
 
 impl SessionContainer {
   fn new(
     v8_inspector: Rc<RefCell<v8::UniquePtr<v8::inspector::V8Inspector>>>,
     new_session_rx: UnboundedReceiver<InspectorSessionProxy>,
+    new_internal_session_rx: UnboundedReceiver<InspectorSessionProxy>,
   ) -> RefCell<Self> {
+    let v8_inspector_ = v8_inspector.clone();
     let new_incoming = new_session_rx
+      .map(move |session_proxy| {
+        InspectorSession::new(v8_inspector_.clone(), session_proxy)
+      })
+      .boxed_local();
+    let new_internal = new_internal_session_rx
       .map(move |session_proxy| {
         InspectorSession::new(v8_inspector.clone(), session_proxy)
       })
       .boxed_local();
     let self_ = Self {
       new_incoming,
+      new_internal,
       ..Default::default()
     };
     RefCell::new(self_)
@@ -420,6 +462,8 @@ impl Default for SessionContainer {
       new_incoming: stream::empty().boxed_local(),
       handshake: None,
       established: FuturesUnordered::new(),
+      new_internal: stream::empty().boxed_local(),
+      internal: FuturesUnordered::new(),
     }
   }
 }
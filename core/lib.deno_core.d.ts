@@ -33,6 +33,35 @@ declare namespace Deno {
      */
     function resources(): Record<string, string>;
 
+    /**
+     * Retrieve a list of all open resources, in the form of a map that maps
+     * resource id to the resource name and the metadata of the resource.
+     */
+    function resources(options: { metadata: true }): Record<
+      string,
+      // deno-lint-ignore no-explicit-any
+      { name: string; metadata: Record<string, any> | null }
+    >;
+
+    /**
+     * Start recording the name and call site of every async op dispatched from
+     * now on, for as long as it is pending.
+     */
+    function enableOpCallTracing(): void;
+
+    /**
+     * The pending async ops recorded since `enableOpCallTracing()` was
+     * called, keyed by promise id.
+     */
+    const opCallTraces: Map<number, { opName: string; stack: string }>;
+
+    /**
+     * The name and call site of the op which opened each resource that is
+     * still open, for the resources opened since `enableOpCallTracing()` was
+     * called, keyed by resource id.
+     */
+    const resourceTraces: Map<number, { opName: string; stack: string }>;
+
     /** Close the resource with the specified op id. */
     function close(rid: number): void;
 
@@ -13,8 +13,10 @@ mod normalize_path;
 mod ops;
 mod ops_builtin;
 mod ops_json;
+mod plugin;
 mod resources;
 mod runtime;
+mod shared_array_buffer_store;
 
 // Re-exports
 pub use futures;
@@ -73,16 +75,24 @@ pub use crate::ops_builtin::op_close;
 pub use crate::ops_builtin::op_print;
 pub use crate::ops_builtin::op_resources;
 pub use crate::ops_json::op_async;
+pub use crate::ops_json::op_async_unref;
 pub use crate::ops_json::op_sync;
+pub use crate::plugin::PluginDeclaration;
+pub use crate::plugin::DENO_CORE_VERSION;
+pub use crate::plugin::PLUGIN_API_VERSION;
+pub use crate::plugin::PLUGIN_DECLARATION_SYMBOL;
 pub use crate::resources::Resource;
 pub use crate::resources::ResourceId;
 pub use crate::resources::ResourceTable;
+pub use crate::runtime::set_icu_data;
 pub use crate::runtime::GetErrorClassFn;
 pub use crate::runtime::JsErrorCreateFn;
 pub use crate::runtime::JsRuntime;
 pub use crate::runtime::RuntimeOptions;
 pub use crate::runtime::Snapshot;
+pub use crate::shared_array_buffer_store::SharedArrayBufferStore;
 // pub use crate::runtime_modules::include_js_files!;
+pub use crate::extensions::ErrorClassFn;
 pub use crate::extensions::Extension;
 pub use crate::extensions::OpMiddlewareFn;
 
@@ -2,6 +2,7 @@
 
 use crate::error::type_error;
 use crate::error::AnyError;
+use crate::extensions::ErrorClassFn;
 use crate::gotham_state::GothamState;
 use crate::resources::ResourceTable;
 use crate::runtime::GetErrorClassFn;
@@ -85,7 +86,7 @@ pub fn serialize_op_result<R: Serialize + 'static>(
   match result {
     Ok(v) => OpResult::Ok(v.into()),
     Err(err) => OpResult::Err(OpError {
-      class_name: (state.borrow().get_error_class_fn)(&err),
+      class_name: state.borrow().get_error_class_name(&err),
       message: err.to_string(),
     }),
   }
@@ -96,6 +97,7 @@ pub struct OpState {
   pub resource_table: ResourceTable,
   pub op_table: OpTable,
   pub get_error_class_fn: GetErrorClassFn,
+  error_class_fns: Vec<ErrorClassFn>,
   gotham_state: GothamState,
 }
 
@@ -105,9 +107,26 @@ impl OpState {
       resource_table: Default::default(),
       op_table: OpTable::default(),
       get_error_class_fn: &|_| "Error",
+      error_class_fns: vec![],
       gotham_state: Default::default(),
     }
   }
+
+  /// Adds a function naming the JS error class of errors returned from ops.
+  /// Functions are tried in the order they were added, falling back to
+  /// `get_error_class_fn` if none of them knows the error.
+  pub fn register_error_class_fn(&mut self, error_class_fn: ErrorClassFn) {
+    self.error_class_fns.push(error_class_fn);
+  }
+
+  /// Returns the name of the JS error class that `err` is thrown as.
+  pub fn get_error_class_name(&self, err: &AnyError) -> &'static str {
+    self
+      .error_class_fns
+      .iter()
+      .find_map(|error_class_fn| error_class_fn(err))
+      .unwrap_or_else(|| (self.get_error_class_fn)(err))
+  }
 }
 
 impl Deref for OpState {
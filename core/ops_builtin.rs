@@ -6,6 +6,8 @@ use crate::op_sync;
 use crate::resources::ResourceId;
 use crate::Extension;
 use crate::OpState;
+use serde::Serialize;
+use serde_json::Value;
 use std::io::{stderr, stdout, Write};
 
 pub(crate) fn init_builtins() -> Extension {
@@ -19,6 +21,8 @@ pub(crate) fn init_builtins() -> Extension {
       ("op_close", op_sync(op_close)),
       ("op_print", op_sync(op_print)),
       ("op_resources", op_sync(op_resources)),
+      ("op_resources_metadata", op_sync(op_resources_metadata)),
+      ("op_next_rid", op_sync(op_next_rid)),
     ])
     .build()
 }
@@ -38,6 +42,39 @@ pub fn op_resources(
   Ok(serialized_resources)
 }
 
+#[derive(Serialize)]
+pub struct ResourceInfo {
+  name: String,
+  metadata: Option<Value>,
+}
+
+/// Return map of resources with id as key
+/// and name and metadata of the resource as value.
+pub fn op_resources_metadata(
+  state: &mut OpState,
+  _args: (),
+  _: (),
+) -> Result<Vec<(ResourceId, ResourceInfo)>, AnyError> {
+  let serialized_resources = state
+    .resource_table
+    .metadata()
+    .map(|(rid, name, metadata)| {
+      let name = name.to_string();
+      (rid, ResourceInfo { name, metadata })
+    })
+    .collect();
+  Ok(serialized_resources)
+}
+
+/// Return the resource id the next resource added to the table will get.
+pub fn op_next_rid(
+  state: &mut OpState,
+  _args: (),
+  _: (),
+) -> Result<ResourceId, AnyError> {
+  Ok(state.resource_table.next_rid())
+}
+
 /// Remove a resource from the resource table.
 pub fn op_close(
   state: &mut OpState,
@@ -99,6 +99,35 @@ where
   })
 }
 
+/// Creates an op like [`op_async`], except that its pending future does not
+/// keep the event loop alive: once only such ops are left, the event loop
+/// finishes without waiting for them.
+pub fn op_async_unref<F, A, B, R, RV>(op_fn: F) -> Box<OpFn>
+where
+  F: Fn(Rc<RefCell<OpState>>, A, B) -> R + 'static,
+  A: DeserializeOwned,
+  B: DeserializeOwned,
+  R: Future<Output = Result<RV, AnyError>> + 'static,
+  RV: Serialize + 'static,
+{
+  Box::new(move |state, payload| -> Op {
+    let pid = payload.promise_id;
+    // Deserialize args, sync error on failure
+    let args = match payload.deserialize() {
+      Ok(args) => args,
+      Err(err) => {
+        return Op::Sync(serialize_op_result(Err::<(), AnyError>(err), state))
+      }
+    };
+    let (a, b) = args;
+
+    use crate::futures::FutureExt;
+    let fut = op_fn(state.clone(), a, b)
+      .map(move |result| (pid, serialize_op_result(result, state)));
+    Op::AsyncUnref(Box::pin(fut))
+  })
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -140,4 +169,24 @@ mod tests {
     assert!(e.contains("at async f1 (<init>:"));
     assert!(e.contains("at async f2 (<init>:"));
   }
+
+  #[tokio::test]
+  async fn op_async_unref_does_not_block_event_loop() {
+    let mut runtime = crate::JsRuntime::new(Default::default());
+
+    fn op_never(
+      _state: Rc<RefCell<OpState>>,
+      _: (),
+      _: (),
+    ) -> impl Future<Output = Result<(), AnyError>> {
+      futures::future::pending()
+    }
+
+    runtime.register_op("op_never", op_async_unref(op_never));
+    runtime.sync_ops_cache();
+    runtime
+      .execute_script("<init>", "Deno.core.opAsync('op_never');")
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+  }
 }
@@ -0,0 +1,111 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::error::generic_error;
+use crate::error::AnyError;
+use crate::Extension;
+
+/// The version of the plugin interface. It is bumped whenever
+/// `PluginDeclaration` or the way plugins are initialized changes.
+pub const PLUGIN_API_VERSION: u32 = 2;
+
+/// The version of `deno_core` a runtime or a plugin was built against.
+/// `Extension` crosses the plugin boundary as a Rust type, so a plugin must
+/// be built against the same `deno_core` as the runtime which loads it.
+pub const DENO_CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The name of the symbol exported by `declare_plugin!`.
+pub const PLUGIN_DECLARATION_SYMBOL: &str = "deno_plugin_declaration";
+
+/// The declaration a plugin exports to be loaded by `Deno.openPlugin()`.
+/// `api_version` is the first field of this `#[repr(C)]` struct so that it
+/// can be checked before relying on the layout of the other fields.
+#[repr(C)]
+pub struct PluginDeclaration {
+  pub api_version: u32,
+  pub core_version: &'static str,
+  /// Returns the extension with the ops and the op state of the plugin. Both
+  /// sync and async ops are supported, as well as resources added to the
+  /// resource table from those ops. JS files and middleware are not.
+  pub init: fn() -> Extension,
+}
+
+impl PluginDeclaration {
+  /// Checks that the plugin was built against the same plugin interface and
+  /// `deno_core` as the runtime loading it.
+  pub fn check(&self) -> Result<(), AnyError> {
+    if self.api_version != PLUGIN_API_VERSION {
+      return Err(generic_error(format!(
+        "Plugin API version mismatch: the plugin uses version {}, but the runtime supports version {}",
+        self.api_version, PLUGIN_API_VERSION
+      )));
+    }
+    if self.core_version != DENO_CORE_VERSION {
+      return Err(generic_error(format!(
+        "deno_core version mismatch: the plugin was built against {}, but the runtime was built against {}",
+        self.core_version, DENO_CORE_VERSION
+      )));
+    }
+    Ok(())
+  }
+}
+
+/// Exports the `PluginDeclaration` of a plugin with the given `init`
+/// function.
+///
+/// ```ignore
+/// deno_core::declare_plugin!(init);
+///
+/// fn init() -> deno_core::Extension {
+///   deno_core::Extension::builder()
+///     .ops(vec![("op_hello", deno_core::op_sync(op_hello))])
+///     .build()
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+  ($init:path) => {
+    #[no_mangle]
+    #[allow(non_upper_case_globals)]
+    pub static deno_plugin_declaration: $crate::PluginDeclaration =
+      $crate::PluginDeclaration {
+        api_version: $crate::PLUGIN_API_VERSION,
+        core_version: $crate::DENO_CORE_VERSION,
+        init: $init,
+      };
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn init() -> Extension {
+    Extension::builder().build()
+  }
+
+  #[test]
+  fn check_declaration() {
+    let declaration = PluginDeclaration {
+      api_version: PLUGIN_API_VERSION,
+      core_version: DENO_CORE_VERSION,
+      init,
+    };
+    assert!(declaration.check().is_ok());
+
+    let declaration = PluginDeclaration {
+      api_version: PLUGIN_API_VERSION - 1,
+      core_version: DENO_CORE_VERSION,
+      init,
+    };
+    let err = declaration.check().unwrap_err();
+    assert!(err.to_string().starts_with("Plugin API version mismatch"));
+
+    let declaration = PluginDeclaration {
+      api_version: PLUGIN_API_VERSION,
+      core_version: "0.0.0",
+      init,
+    };
+    let err = declaration.check().unwrap_err();
+    assert!(err.to_string().starts_with("deno_core version mismatch"));
+  }
+}
@@ -6,6 +6,7 @@
 // resources. Resources may or may not correspond to a real operating system
 // file descriptor (hence the different name).
 
+use serde_json::Value;
 use std::any::type_name;
 use std::any::Any;
 use std::any::TypeId;
@@ -25,6 +26,14 @@ pub trait Resource: Any + 'static {
     type_name::<Self>().into()
   }
 
+  /// Returns details about the resource, like the path of a file or the
+  /// addresses of a socket, which are made available to JavaScript code
+  /// through `op_resources_metadata`. The default implementation returns
+  /// `None`.
+  fn metadata(&self) -> Option<Value> {
+    None
+  }
+
   /// Resources may implement the `close()` trait method if they need to do
   /// resource specific clean-ups, such as cancelling pending futures, after a
   /// resource has been removed from the resource table.
@@ -97,6 +106,13 @@ impl ResourceTable {
     rid
   }
 
+  /// Returns the resource ID the next resource inserted into the table will
+  /// get. IDs are never reused, so every resource added after this call has
+  /// an ID at least as large.
+  pub fn next_rid(&self) -> ResourceId {
+    self.next_rid
+  }
+
   /// Returns true if any resource with the given `rid` exists.
   pub fn has(&self, rid: ResourceId) -> bool {
     self.index.contains_key(&rid)
@@ -161,4 +177,15 @@ impl ResourceTable {
       .iter()
       .map(|(&id, resource)| (id, resource.name()))
   }
+
+  /// Returns an iterator that yields a `(id, name, metadata)` tuple for every
+  /// resource that's in the resource table. See `Resource::metadata()`.
+  pub fn metadata(
+    &self,
+  ) -> impl Iterator<Item = (ResourceId, Cow<str>, Option<Value>)> {
+    self
+      .index
+      .iter()
+      .map(|(&id, resource)| (id, resource.name(), resource.metadata()))
+  }
 }
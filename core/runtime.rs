@@ -22,6 +22,7 @@ use crate::OpPayload;
 use crate::OpResult;
 use crate::OpState;
 use crate::PromiseId;
+use crate::SharedArrayBufferStore;
 use futures::channel::mpsc;
 use futures::future::poll_fn;
 use futures::future::FutureExt;
@@ -39,6 +40,8 @@ use std::mem::forget;
 use std::option::Option;
 use std::pin::Pin;
 use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Once;
 use std::task::Context;
 use std::task::Poll;
@@ -104,6 +107,8 @@ pub(crate) struct JsRuntimeState {
   pub global_context: Option<v8::Global<v8::Context>>,
   pub(crate) js_recv_cb: Option<v8::Global<v8::Function>>,
   pub(crate) js_macrotask_cb: Option<v8::Global<v8::Function>>,
+  pub(crate) js_promise_rejection_handler: Option<v8::Global<v8::Function>>,
+  pub(crate) pending_handled_rejections: Vec<v8::Global<v8::Promise>>,
   pub(crate) pending_promise_exceptions:
     HashMap<v8::Global<v8::Promise>, v8::Global<v8::Value>>,
   pending_dyn_mod_evaluate: VecDeque<DynImportModEvaluate>,
@@ -113,6 +118,7 @@ pub(crate) struct JsRuntimeState {
   pub(crate) pending_unref_ops: FuturesUnordered<PendingOpFuture>,
   pub(crate) have_unpolled_ops: bool,
   pub(crate) op_state: Rc<RefCell<OpState>>,
+  pub(crate) shared_array_buffer_store: Option<SharedArrayBufferStore>,
   waker: AtomicWaker,
 }
 
@@ -142,12 +148,36 @@ impl Drop for JsRuntime {
   }
 }
 
+static CUSTOM_ICU_DATA: AtomicBool = AtomicBool::new(false);
+
+/// Sets the ICU data used by V8 to the contents of an `icudtl.dat` file
+/// instead of the data included in `deno_core`, for example to use a build of
+/// the data with a different set of locales. This must be called before the
+/// first `JsRuntime` is created.
+pub fn set_icu_data(data: &[u8]) -> Result<(), AnyError> {
+  // ICU requires the data to be aligned to 16 bytes and to be valid for the
+  // rest of the process.
+  let aligned: &'static mut [u128] =
+    Box::leak(vec![0u128; (data.len() + 15) / 16].into_boxed_slice());
+  let bytes: &'static mut [u8] = unsafe {
+    std::slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, data.len())
+  };
+  bytes.copy_from_slice(data);
+  v8::icu::set_common_data(bytes).map_err(|code| {
+    generic_error(format!("Invalid ICU data (ICU error code {})", code))
+  })?;
+  CUSTOM_ICU_DATA.store(true, Ordering::SeqCst);
+  Ok(())
+}
+
 fn v8_init(v8_platform: Option<v8::UniquePtr<v8::Platform>>) {
-  // Include 10MB ICU data file.
-  #[repr(C, align(16))]
-  struct IcuData([u8; 10413584]);
-  static ICU_DATA: IcuData = IcuData(*include_bytes!("icudtl.dat"));
-  v8::icu::set_common_data(&ICU_DATA.0).unwrap();
+  if !CUSTOM_ICU_DATA.load(Ordering::SeqCst) {
+    // Include 10MB ICU data file.
+    #[repr(C, align(16))]
+    struct IcuData([u8; 10413584]);
+    static ICU_DATA: IcuData = IcuData(*include_bytes!("icudtl.dat"));
+    v8::icu::set_common_data(&ICU_DATA.0).unwrap();
+  }
 
   let v8_platform = v8_platform
     .unwrap_or_else(v8::new_default_platform)
@@ -207,6 +237,12 @@ pub struct RuntimeOptions {
   /// V8 platform instance to use. Used when Deno initializes V8
   /// (which it only does once), otherwise it's silenty dropped.
   pub v8_platform: Option<v8::UniquePtr<v8::Platform>>,
+
+  /// The store through which `SharedArrayBuffer`s are shared with other
+  /// runtimes using the same store when they are serialized.
+  ///
+  /// If not provided, serializing a `SharedArrayBuffer` fails.
+  pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
 }
 
 impl JsRuntime {
@@ -292,11 +328,14 @@ impl JsRuntime {
       pending_mod_evaluate: None,
       js_recv_cb: None,
       js_macrotask_cb: None,
+      js_promise_rejection_handler: None,
+      pending_handled_rejections: Vec::new(),
       js_error_create_fn,
       pending_ops: FuturesUnordered::new(),
       pending_unref_ops: FuturesUnordered::new(),
       op_state: op_state.clone(),
       have_unpolled_ops: false,
+      shared_array_buffer_store: options.shared_array_buffer_store.take(),
       waker: AtomicWaker::new(),
     })));
 
@@ -408,6 +447,11 @@ impl JsRuntime {
     // Register ops
     for e in extensions.iter_mut() {
       e.init_state(&mut op_state.borrow_mut())?;
+      for error_class_fn in e.init_error_classes() {
+        op_state
+          .borrow_mut()
+          .register_error_class_fn(*error_class_fn);
+      }
       // Register each op after middlewaring it
       let ops = e.init_ops().unwrap_or_default();
       for (name, opfn) in ops {
@@ -606,6 +650,18 @@ impl JsRuntime {
     poll_fn(|cx| self.poll_event_loop(cx, wait_for_inspector)).await
   }
 
+  /// Returns `true` if running the event loop wouldn't resolve right away,
+  /// because there are pending ops, dynamic imports or module evaluations.
+  pub fn has_pending_work(&mut self) -> bool {
+    let state_rc = Self::state(self.v8_isolate());
+    let module_map_rc = Self::module_map(self.v8_isolate());
+    let state = state_rc.borrow();
+    !state.pending_ops.is_empty()
+      || module_map_rc.borrow().has_pending_dynamic_imports()
+      || !state.pending_dyn_mod_evaluate.is_empty()
+      || state.pending_mod_evaluate.is_some()
+  }
+
   /// Runs a single tick of event loop
   ///
   /// If `wait_for_inspector` is set to true event loop
@@ -1316,26 +1372,75 @@ impl JsRuntime {
 
   fn check_promise_exceptions(&mut self) -> Result<(), AnyError> {
     let state_rc = Self::state(self.v8_isolate());
-    let mut state = state_rc.borrow_mut();
 
-    if state.pending_promise_exceptions.is_empty() {
-      return Ok(());
+    let handled_rejections =
+      std::mem::take(&mut state_rc.borrow_mut().pending_handled_rejections);
+    for promise in handled_rejections {
+      self.call_promise_rejection_handler("rejectionhandled", promise)?;
     }
 
-    let key = {
-      state
-        .pending_promise_exceptions
-        .keys()
-        .next()
-        .unwrap()
-        .clone()
+    loop {
+      let mut state = state_rc.borrow_mut();
+      let key = match state.pending_promise_exceptions.keys().next() {
+        Some(key) => key.clone(),
+        None => return Ok(()),
+      };
+      let handle = state.pending_promise_exceptions.remove(&key).unwrap();
+      let has_handler = state.js_promise_rejection_handler.is_some();
+      drop(state);
+
+      // The rejection handler can prevent the rejection from being an error,
+      // such as by calling `preventDefault()` on an "unhandledrejection"
+      // event.
+      if has_handler
+        && self.call_promise_rejection_handler("unhandledrejection", key)?
+      {
+        continue;
+      }
+
+      let scope = &mut self.handle_scope();
+      let exception = v8::Local::new(scope, handle);
+      return exception_to_err_result(scope, exception, true);
+    }
+  }
+
+  /// Calls the handler set with `Deno.core.setPromiseRejectionHandler()` with
+  /// the type of the event, the promise and its rejection reason. Returns
+  /// whether the handler returned true.
+  fn call_promise_rejection_handler(
+    &mut self,
+    event_type: &str,
+    promise: v8::Global<v8::Promise>,
+  ) -> Result<bool, AnyError> {
+    let handler_handle = match &Self::state(self.v8_isolate())
+      .borrow()
+      .js_promise_rejection_handler
+    {
+      Some(handle) => handle.clone(),
+      None => return Ok(false),
     };
-    let handle = state.pending_promise_exceptions.remove(&key).unwrap();
-    drop(state);
 
     let scope = &mut self.handle_scope();
-    let exception = v8::Local::new(scope, handle);
-    exception_to_err_result(scope, exception, true)
+    let handler = handler_handle.get(scope);
+    let promise = v8::Local::new(scope, promise);
+    let reason = promise.result(scope);
+    let event_type = v8::String::new(scope, event_type).unwrap();
+
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let this = v8::undefined(tc_scope).into();
+    let prevented = handler.call(
+      tc_scope,
+      this,
+      &[event_type.into(), promise.into(), reason],
+    );
+
+    if let Some(exception) = tc_scope.exception() {
+      return exception_to_err_result(tc_scope, exception, false);
+    }
+
+    // There is no result without an exception when the execution is
+    // terminated, in which case the rejection isn't taken as handled.
+    Ok(prevented.map_or(false, |prevented| prevented.is_true()))
   }
 
   // Send finished responses to JS
@@ -1693,6 +1798,52 @@ pub mod tests {
     });
   }
 
+  #[test]
+  fn test_extension_error_class() {
+    #[derive(Debug)]
+    struct OperationError;
+
+    impl std::fmt::Display for OperationError {
+      fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("abc")
+      }
+    }
+
+    impl std::error::Error for OperationError {}
+
+    fn op_err(
+      _: &mut OpState,
+      _: (),
+      _: Option<ZeroCopyBuf>,
+    ) -> Result<(), AnyError> {
+      Err(OperationError.into())
+    }
+
+    fn get_error_class_name(e: &AnyError) -> Option<&'static str> {
+      e.downcast_ref::<OperationError>()
+        .map(|_| "DOMExceptionOperationError")
+    }
+
+    run_in_task(|mut cx| {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        extensions: vec![Extension::builder()
+          .ops(vec![("op_err", op_sync(op_err))])
+          .error_class(get_error_class_name)
+          .build()],
+        ..Default::default()
+      });
+      runtime
+        .execute_script(
+          "error_builder_test.js",
+          include_str!("error_builder_test.js"),
+        )
+        .unwrap();
+      if let Poll::Ready(Err(_)) = runtime.poll_event_loop(&mut cx, false) {
+        unreachable!();
+      }
+    });
+  }
+
   #[test]
   fn will_snapshot() {
     let snapshot = {
@@ -1917,6 +2068,58 @@ main();
     assert_eq!(result.unwrap_err().to_string(), expected_error);
   }
 
+  #[test]
+  fn test_promise_rejection_handler() {
+    run_in_task(|cx| {
+      let mut runtime = JsRuntime::new(RuntimeOptions::default());
+      runtime
+        .execute_script(
+          "promise_rejection_handler.js",
+          r#"
+globalThis.events = [];
+Deno.core.setPromiseRejectionHandler((type, promise, reason) => {
+  events.push([type, reason.message]);
+  return reason.message === "prevented";
+});
+globalThis.p = Promise.reject(new Error("prevented"));
+"#,
+        )
+        .unwrap();
+      assert!(matches!(
+        runtime.poll_event_loop(cx, false),
+        Poll::Ready(Ok(()))
+      ));
+      runtime
+        .execute_script("handle.js", "p.catch(() => {});")
+        .unwrap();
+      assert!(matches!(
+        runtime.poll_event_loop(cx, false),
+        Poll::Ready(Ok(()))
+      ));
+      runtime
+        .execute_script(
+          "check.js",
+          r#"
+const expected = [
+  ["unhandledrejection", "prevented"],
+  ["rejectionhandled", "prevented"],
+];
+if (JSON.stringify(events) !== JSON.stringify(expected)) {
+  throw new Error(JSON.stringify(events));
+}
+Promise.reject(new Error("not prevented"));
+"#,
+        )
+        .unwrap();
+      match runtime.poll_event_loop(cx, false) {
+        Poll::Ready(Err(e)) => {
+          assert!(e.to_string().contains("not prevented"));
+        }
+        _ => panic!(),
+      };
+    })
+  }
+
   #[test]
   fn test_error_async_stack() {
     run_in_task(|cx| {
@@ -64,6 +64,24 @@ function main() {
     new Uint8Array(circularObjectSerialized),
   );
   assert(deserializedCircularObject.test == deserializedCircularObject);
+
+  // A transferred array buffer is serialized as a reference, which is
+  // deserialized as the array buffer it is matched up with.
+  const transferredBuffer = new Uint8Array([1, 2, 3]).buffer;
+  const transferSerialized = Deno.core.serialize(
+    { buffer: transferredBuffer, view: new Uint8Array(transferredBuffer, 1) },
+    { transferredArrayBuffers: [transferredBuffer] },
+  );
+  Deno.core.detachArrayBuffer(transferredBuffer);
+  assert(transferredBuffer.byteLength === 0);
+
+  const receivedBuffer = new Uint8Array([1, 2, 3]).buffer;
+  const transferDeserialized = Deno.core.deserialize(transferSerialized, {
+    transferredArrayBuffers: [receivedBuffer],
+  });
+  assert(transferDeserialized.buffer === receivedBuffer);
+  assert(transferDeserialized.view.buffer === receivedBuffer);
+  assertArrayEquals(transferDeserialized.view, [2, 3]);
 }
 
 main();
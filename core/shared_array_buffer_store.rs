@@ -0,0 +1,39 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use rusty_v8 as v8;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+/// Holds the backing stores of the `SharedArrayBuffer`s which are serialized
+/// by `Deno.core.serialize()` until they are deserialized, possibly by another
+/// isolate. The runtimes which share a store can send each other
+/// `SharedArrayBuffer`s, which then refer to the same memory on both sides.
+#[derive(Clone, Default)]
+pub struct SharedArrayBufferStore(Arc<Mutex<SharedArrayBufferStoreInner>>);
+
+#[derive(Default)]
+struct SharedArrayBufferStoreInner {
+  backing_stores: HashMap<u32, v8::SharedRef<v8::BackingStore>>,
+  next_id: u32,
+}
+
+impl SharedArrayBufferStore {
+  pub(crate) fn insert(
+    &self,
+    backing_store: v8::SharedRef<v8::BackingStore>,
+  ) -> u32 {
+    let mut inner = self.0.lock().unwrap();
+    let id = inner.next_id;
+    inner.next_id = inner.next_id.wrapping_add(1);
+    inner.backing_stores.insert(id, backing_store);
+    id
+  }
+
+  pub(crate) fn take(
+    &self,
+    id: u32,
+  ) -> Option<v8::SharedRef<v8::BackingStore>> {
+    self.0.lock().unwrap().backing_stores.remove(&id)
+  }
+}
@@ -0,0 +1,545 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+// @ts-check
+/// <reference path="../../core/internal.d.ts" />
+/// <reference path="../webidl/internal.d.ts" />
+/// <reference path="../fetch/internal.d.ts" />
+/// <reference path="../fetch/lib.deno_fetch.d.ts" />
+/// <reference lib="esnext" />
+"use strict";
+
+((window) => {
+  const core = window.Deno.core;
+  const webidl = window.__bootstrap.webidl;
+  const { InnerBody } = window.__bootstrap.fetchBody;
+  const {
+    Request,
+    fetch,
+    toInnerRequest,
+    fromInnerRequest,
+    newInnerRequest,
+    toInnerResponse,
+    fromInnerResponse,
+    newInnerResponse,
+  } = window.__bootstrap.fetch;
+  const { headerListFromHeaders } = window.__bootstrap.headers;
+  const { URL } = window.__bootstrap.url;
+
+  const _id = Symbol("[[id]]");
+
+  /**
+   * @param {[string, string][]} headerList
+   * @param {string} name
+   * @returns {string | null}
+   */
+  function getHeader(headerList, name) {
+    const lowercaseName = name.toLowerCase();
+    const values = headerList
+      .filter((entry) => entry[0].toLowerCase() === lowercaseName)
+      .map((entry) => entry[1]);
+    return values.length === 0 ? null : values.join(", ");
+  }
+
+  /**
+   * @param {[string, string][]} headerList
+   * @returns {string[]}
+   */
+  function varyFields(headerList) {
+    const vary = getHeader(headerList, "vary");
+    if (vary === null) return [];
+    return vary.split(",").map((field) => field.trim()).filter((field) =>
+      field !== ""
+    );
+  }
+
+  /**
+   * The URL of a request as it is used as a cache key: without fragment.
+   * @param {string} url
+   * @returns {string}
+   */
+  function cacheKeyUrl(url) {
+    const parsed = new URL(url);
+    parsed.hash = "";
+    return parsed.href;
+  }
+
+  /**
+   * @param {RequestInfo} input
+   * @returns {Request}
+   */
+  function toRequest(input) {
+    return input instanceof Request ? input : new Request(input);
+  }
+
+  /**
+   * https://w3c.github.io/ServiceWorker/#query-cache
+   * @param {number} cacheId
+   * @param {Request | null} request
+   * @param {CacheQueryOptions} options
+   */
+  function queryCache(cacheId, request, options) {
+    let innerRequest = null;
+    if (request !== null) {
+      innerRequest = toInnerRequest(request);
+      if (!options.ignoreMethod && innerRequest.method !== "GET") {
+        return [];
+      }
+    }
+    const requestUrl = innerRequest === null
+      ? null
+      : cacheKeyUrl(innerRequest.url());
+    const entries = core.opSync("op_cache_query", {
+      cacheId,
+      requestUrl,
+      ignoreSearch: options.ignoreSearch,
+    });
+    if (innerRequest === null || options.ignoreVary) {
+      return entries;
+    }
+    return entries.filter((entry) =>
+      varyFields(entry.responseHeaders).every((field) =>
+        field !== "*" &&
+        getHeader(entry.requestHeaders, field) ===
+          getHeader(innerRequest.headerList, field)
+      )
+    );
+  }
+
+  function entryToRequest(entry) {
+    const inner = newInnerRequest(
+      "GET",
+      entry.requestUrl,
+      entry.requestHeaders,
+    );
+    return fromInnerRequest(inner, null, "immutable");
+  }
+
+  function entryToResponse(entry) {
+    const inner = newInnerResponse(
+      entry.responseStatus,
+      entry.responseStatusText,
+    );
+    inner.type = entry.responseType;
+    inner.headerList = entry.responseHeaders;
+    if (entry.responseUrl !== null) {
+      inner.urlList = [entry.responseUrl];
+    }
+    if (entry.hasBody) {
+      const body = core.opSync("op_cache_read_body", entry.id);
+      inner.body = new InnerBody({ body, consumed: false });
+      inner.body.length = body.byteLength;
+    }
+    return fromInnerResponse(inner, "immutable");
+  }
+
+  /**
+   * Validates a request/response pair for storage, and reads out the body of
+   * the response. Throws without touching the cache if the pair is invalid.
+   * @param {Request} request
+   * @param {Response} response
+   */
+  async function prepareEntry(request, response) {
+    const innerRequest = toInnerRequest(request);
+    const url = new URL(innerRequest.url());
+    if (url.protocol !== "http:" && url.protocol !== "https:") {
+      throw new TypeError("Request url protocol must be 'http:' or 'https:'");
+    }
+    if (innerRequest.method !== "GET") {
+      throw new TypeError("Request method must be GET");
+    }
+    const innerResponse = toInnerResponse(response);
+    if (innerResponse.status === 206) {
+      throw new TypeError("Partial responses can not be cached");
+    }
+    if (varyFields(innerResponse.headerList).includes("*")) {
+      throw new TypeError("Vary header must not contain '*'");
+    }
+    if (innerResponse.body !== null && innerResponse.body.unusable()) {
+      throw new TypeError("Response body is already used");
+    }
+
+    let body = null;
+    if (innerResponse.body !== null) {
+      body = new Uint8Array(await response.arrayBuffer());
+    }
+
+    return {
+      request,
+      args: {
+        requestUrl: cacheKeyUrl(url.href),
+        requestHeaders: headerListFromHeaders(request.headers),
+        responseType: innerResponse.type,
+        responseUrl: innerResponse.url(),
+        responseStatus: innerResponse.status,
+        responseStatusText: innerResponse.statusMessage,
+        responseHeaders: innerResponse.headerList,
+      },
+      body,
+    };
+  }
+
+  /**
+   * @param {number} cacheId
+   * @param {Awaited<ReturnType<typeof prepareEntry>>} entry
+   */
+  function storeEntry(cacheId, entry) {
+    const replaces = queryCache(cacheId, entry.request, {
+      ignoreSearch: false,
+      ignoreMethod: false,
+      ignoreVary: false,
+    }).map((existing) => existing.id);
+    core.opSync("op_cache_put", {
+      cacheId,
+      replaces,
+      ...entry.args,
+    }, entry.body);
+  }
+
+  class Cache {
+    /** @type {number} */
+    [_id];
+
+    constructor() {
+      webidl.illegalConstructor();
+    }
+
+    /**
+     * @param {RequestInfo} request
+     * @param {CacheQueryOptions} options
+     * @returns {Promise<Response | undefined>}
+     */
+    async match(request, options = {}) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'match' on 'Cache'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      request = webidl.converters.RequestInfo(request, {
+        prefix,
+        context: "Argument 1",
+      });
+      options = webidl.converters.CacheQueryOptions(options, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const entry = queryCache(this[_id], toRequest(request), options)[0];
+      return entry === undefined ? undefined : entryToResponse(entry);
+    }
+
+    /**
+     * @param {RequestInfo} [request]
+     * @param {CacheQueryOptions} options
+     * @returns {Promise<Response[]>}
+     */
+    async matchAll(request = undefined, options = {}) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'matchAll' on 'Cache'";
+      if (request !== undefined) {
+        request = webidl.converters.RequestInfo(request, {
+          prefix,
+          context: "Argument 1",
+        });
+      }
+      options = webidl.converters.CacheQueryOptions(options, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const entries = queryCache(
+        this[_id],
+        request === undefined ? null : toRequest(request),
+        options,
+      );
+      return entries.map(entryToResponse);
+    }
+
+    /**
+     * @param {RequestInfo} request
+     * @returns {Promise<void>}
+     */
+    async add(request) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'add' on 'Cache'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      request = webidl.converters.RequestInfo(request, {
+        prefix,
+        context: "Argument 1",
+      });
+
+      await this.addAll([request]);
+    }
+
+    /**
+     * @param {RequestInfo[]} requests
+     * @returns {Promise<void>}
+     */
+    async addAll(requests) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'addAll' on 'Cache'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      requests = webidl.converters["sequence<RequestInfo>"](requests, {
+        prefix,
+        context: "Argument 1",
+      });
+
+      const entries = await Promise.all(requests.map(async (input) => {
+        const request = toRequest(input);
+        const url = new URL(request.url);
+        if (url.protocol !== "http:" && url.protocol !== "https:") {
+          throw new TypeError(
+            "Request url protocol must be 'http:' or 'https:'",
+          );
+        }
+        if (request.method !== "GET") {
+          throw new TypeError("Request method must be GET");
+        }
+        const response = await fetch(request);
+        if (!response.ok) {
+          throw new TypeError(
+            `Request for ${request.url} failed with status ${response.status}`,
+          );
+        }
+        return prepareEntry(request, response);
+      }));
+      for (const entry of entries) {
+        storeEntry(this[_id], entry);
+      }
+    }
+
+    /**
+     * @param {RequestInfo} request
+     * @param {Response} response
+     * @returns {Promise<void>}
+     */
+    async put(request, response) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'put' on 'Cache'";
+      webidl.requiredArguments(arguments.length, 2, { prefix });
+      request = webidl.converters.RequestInfo(request, {
+        prefix,
+        context: "Argument 1",
+      });
+      response = webidl.converters.Response(response, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const entry = await prepareEntry(toRequest(request), response);
+      storeEntry(this[_id], entry);
+    }
+
+    /**
+     * @param {RequestInfo} request
+     * @param {CacheQueryOptions} options
+     * @returns {Promise<boolean>}
+     */
+    async delete(request, options = {}) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'delete' on 'Cache'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      request = webidl.converters.RequestInfo(request, {
+        prefix,
+        context: "Argument 1",
+      });
+      options = webidl.converters.CacheQueryOptions(options, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const entries = queryCache(this[_id], toRequest(request), options);
+      return core.opSync("op_cache_delete", {
+        cacheId: this[_id],
+        entries: entries.map((entry) => entry.id),
+      });
+    }
+
+    /**
+     * @param {RequestInfo} [request]
+     * @param {CacheQueryOptions} options
+     * @returns {Promise<Request[]>}
+     */
+    async keys(request = undefined, options = {}) {
+      webidl.assertBranded(this, Cache);
+      const prefix = "Failed to execute 'keys' on 'Cache'";
+      if (request !== undefined) {
+        request = webidl.converters.RequestInfo(request, {
+          prefix,
+          context: "Argument 1",
+        });
+      }
+      options = webidl.converters.CacheQueryOptions(options, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const entries = queryCache(
+        this[_id],
+        request === undefined ? null : toRequest(request),
+        options,
+      );
+      return entries.map(entryToRequest);
+    }
+
+    get [Symbol.toStringTag]() {
+      return "Cache";
+    }
+  }
+
+  webidl.configurePrototype(Cache);
+
+  /**
+   * @param {number} id
+   * @returns {Cache}
+   */
+  function createCache(id) {
+    const cache = webidl.createBranded(Cache);
+    cache[_id] = id;
+    return cache;
+  }
+
+  class CacheStorage {
+    constructor() {
+      webidl.illegalConstructor();
+    }
+
+    /**
+     * @param {RequestInfo} request
+     * @param {MultiCacheQueryOptions} options
+     * @returns {Promise<Response | undefined>}
+     */
+    async match(request, options = {}) {
+      webidl.assertBranded(this, CacheStorage);
+      const prefix = "Failed to execute 'match' on 'CacheStorage'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      request = webidl.converters.RequestInfo(request, {
+        prefix,
+        context: "Argument 1",
+      });
+      options = webidl.converters.MultiCacheQueryOptions(options, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      const cacheNames = options.cacheName !== undefined
+        ? [options.cacheName]
+        : core.opSync("op_cache_storage_keys");
+      for (const cacheName of cacheNames) {
+        // Unlike `open()`, matching must not create a missing cache.
+        const id = core.opSync("op_cache_storage_get", cacheName);
+        if (id === null) {
+          continue;
+        }
+        const response = await createCache(id).match(request, options);
+        if (response !== undefined) {
+          return response;
+        }
+      }
+      return undefined;
+    }
+
+    /**
+     * @param {string} cacheName
+     * @returns {Promise<boolean>}
+     */
+    async has(cacheName) {
+      webidl.assertBranded(this, CacheStorage);
+      const prefix = "Failed to execute 'has' on 'CacheStorage'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      cacheName = webidl.converters.DOMString(cacheName, {
+        prefix,
+        context: "Argument 1",
+      });
+
+      return core.opSync("op_cache_storage_get", cacheName) !== null;
+    }
+
+    /**
+     * @param {string} cacheName
+     * @returns {Promise<Cache>}
+     */
+    async open(cacheName) {
+      webidl.assertBranded(this, CacheStorage);
+      const prefix = "Failed to execute 'open' on 'CacheStorage'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      cacheName = webidl.converters.DOMString(cacheName, {
+        prefix,
+        context: "Argument 1",
+      });
+
+      return createCache(core.opSync("op_cache_storage_open", cacheName));
+    }
+
+    /**
+     * @param {string} cacheName
+     * @returns {Promise<boolean>}
+     */
+    async delete(cacheName) {
+      webidl.assertBranded(this, CacheStorage);
+      const prefix = "Failed to execute 'delete' on 'CacheStorage'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      cacheName = webidl.converters.DOMString(cacheName, {
+        prefix,
+        context: "Argument 1",
+      });
+
+      return core.opSync("op_cache_storage_delete", cacheName);
+    }
+
+    /**
+     * @returns {Promise<string[]>}
+     */
+    async keys() {
+      webidl.assertBranded(this, CacheStorage);
+      return core.opSync("op_cache_storage_keys");
+    }
+
+    get [Symbol.toStringTag]() {
+      return "CacheStorage";
+    }
+  }
+
+  webidl.configurePrototype(CacheStorage);
+
+  webidl.converters["sequence<RequestInfo>"] = webidl
+    .createSequenceConverter(webidl.converters.RequestInfo);
+
+  const cacheQueryOptionsDictionary = [
+    {
+      key: "ignoreSearch",
+      converter: webidl.converters.boolean,
+      defaultValue: false,
+    },
+    {
+      key: "ignoreMethod",
+      converter: webidl.converters.boolean,
+      defaultValue: false,
+    },
+    {
+      key: "ignoreVary",
+      converter: webidl.converters.boolean,
+      defaultValue: false,
+    },
+  ];
+  webidl.converters.CacheQueryOptions = webidl.createDictionaryConverter(
+    "CacheQueryOptions",
+    cacheQueryOptionsDictionary,
+  );
+  webidl.converters.MultiCacheQueryOptions = webidl.createDictionaryConverter(
+    "MultiCacheQueryOptions",
+    cacheQueryOptionsDictionary,
+    [{ key: "cacheName", converter: webidl.converters.DOMString }],
+  );
+
+  let caches;
+
+  window.__bootstrap.caches = {
+    caches() {
+      window.location;
+      if (!caches) {
+        caches = webidl.createBranded(CacheStorage);
+      }
+      return caches;
+    },
+    CacheStorage,
+    Cache,
+  };
+})(this);
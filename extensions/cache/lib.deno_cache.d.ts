@@ -0,0 +1,74 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+/// <reference no-default-lib="true" />
+/// <reference lib="esnext" />
+
+interface CacheQueryOptions {
+  ignoreMethod?: boolean;
+  ignoreSearch?: boolean;
+  ignoreVary?: boolean;
+}
+
+interface MultiCacheQueryOptions extends CacheQueryOptions {
+  cacheName?: string;
+}
+
+/** Provides a storage mechanism for Request / Response object pairs that are
+ * cached, for example as part of the ServiceWorker life cycle. */
+interface Cache {
+  /** Fetches the given URLs, and adds the resulting response objects to the
+   * cache. Rejects without modifying the cache if any fetch fails. */
+  add(request: RequestInfo): Promise<void>;
+  addAll(requests: RequestInfo[]): Promise<void>;
+  /** Removes the entries matching the request, resolving to whether any
+   * entry was removed. */
+  delete(request: RequestInfo, options?: CacheQueryOptions): Promise<boolean>;
+  /** Returns the request keys of the entries matching the request, or of all
+   * entries if no request is given. */
+  keys(
+    request?: RequestInfo,
+    options?: CacheQueryOptions,
+  ): Promise<ReadonlyArray<Request>>;
+  /** Returns the response of the first entry matching the request. */
+  match(
+    request: RequestInfo,
+    options?: CacheQueryOptions,
+  ): Promise<Response | undefined>;
+  /** Returns the responses of all entries matching the request. */
+  matchAll(
+    request?: RequestInfo,
+    options?: CacheQueryOptions,
+  ): Promise<ReadonlyArray<Response>>;
+  /** Stores the request/response pair, replacing any entry that matches the
+   * request. Consumes the body of the response. */
+  put(request: RequestInfo, response: Response): Promise<void>;
+}
+
+declare var Cache: {
+  prototype: Cache;
+  new (): never;
+};
+
+/** The storage for named Cache objects, persisted per origin. */
+interface CacheStorage {
+  /** Removes the named cache, resolving to whether it existed. */
+  delete(cacheName: string): Promise<boolean>;
+  has(cacheName: string): Promise<boolean>;
+  /** Returns the names of all caches, in creation order. */
+  keys(): Promise<string[]>;
+  /** Looks the request up in the named cache, or in every cache in creation
+   * order if no `cacheName` is given. */
+  match(
+    request: RequestInfo,
+    options?: MultiCacheQueryOptions,
+  ): Promise<Response | undefined>;
+  /** Returns the named cache, creating it if it does not exist. */
+  open(cacheName: string): Promise<Cache>;
+}
+
+declare var CacheStorage: {
+  prototype: CacheStorage;
+  new (): never;
+};
+
+declare var caches: CacheStorage;
@@ -0,0 +1,382 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::include_js_files;
+use deno_core::op_sync;
+use deno_core::serde_json;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_core::ZeroCopyBuf;
+use deno_webstorage::DomExceptionNotSupportedError;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone)]
+struct OriginStorageDir(PathBuf);
+
+pub fn init(origin_storage_dir: Option<PathBuf>) -> Extension {
+  Extension::builder()
+    .js(include_js_files!(
+      prefix "deno:extensions/cache",
+      "01_cache.js",
+    ))
+    .ops(vec![
+      ("op_cache_storage_open", op_sync(op_cache_storage_open)),
+      ("op_cache_storage_get", op_sync(op_cache_storage_get)),
+      ("op_cache_storage_delete", op_sync(op_cache_storage_delete)),
+      ("op_cache_storage_keys", op_sync(op_cache_storage_keys)),
+      ("op_cache_put", op_sync(op_cache_put)),
+      ("op_cache_query", op_sync(op_cache_query)),
+      ("op_cache_read_body", op_sync(op_cache_read_body)),
+      ("op_cache_delete", op_sync(op_cache_delete)),
+    ])
+    .state(move |state| {
+      if let Some(origin_storage_dir) = origin_storage_dir.clone() {
+        state.put(OriginStorageDir(origin_storage_dir));
+      }
+      Ok(())
+    })
+    .build()
+}
+
+pub fn get_declaration() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib.deno_cache.d.ts")
+}
+
+struct CacheStorage(Connection);
+
+fn get_cache_storage(state: &mut OpState) -> Result<&Connection, AnyError> {
+  if state.try_borrow::<CacheStorage>().is_none() {
+    let path = state.try_borrow::<OriginStorageDir>().ok_or_else(|| {
+      DomExceptionNotSupportedError::new(
+        "CacheStorage is not supported in this context.",
+      )
+    })?;
+    std::fs::create_dir_all(&path.0)?;
+    let conn = Connection::open(path.0.join("cache_storage"))?;
+    conn.execute_batch(
+      "PRAGMA foreign_keys = ON;
+      CREATE TABLE IF NOT EXISTS caches (
+        id INTEGER PRIMARY KEY,
+        name VARCHAR UNIQUE
+      );
+      CREATE TABLE IF NOT EXISTS entries (
+        id INTEGER PRIMARY KEY,
+        cache_id INTEGER NOT NULL,
+        request_url VARCHAR NOT NULL,
+        request_headers VARCHAR NOT NULL,
+        response_type VARCHAR NOT NULL,
+        response_url VARCHAR,
+        response_status INTEGER NOT NULL,
+        response_status_text VARCHAR NOT NULL,
+        response_headers VARCHAR NOT NULL,
+        response_body BLOB,
+        FOREIGN KEY (cache_id) REFERENCES caches(id) ON DELETE CASCADE
+      );
+      CREATE INDEX IF NOT EXISTS entries_request_url
+        ON entries (cache_id, request_url);",
+    )?;
+
+    state.put(CacheStorage(conn));
+  }
+
+  Ok(&state.borrow::<CacheStorage>().0)
+}
+
+pub fn op_cache_storage_open(
+  state: &mut OpState,
+  cache_name: String,
+  _: (),
+) -> Result<i64, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  conn.execute(
+    "INSERT OR IGNORE INTO caches (name) VALUES (?)",
+    params![cache_name],
+  )?;
+  let id = conn.query_row(
+    "SELECT id FROM caches WHERE name = ?",
+    params![cache_name],
+    |row| row.get(0),
+  )?;
+
+  Ok(id)
+}
+
+/// Looks up a cache without creating it.
+pub fn op_cache_storage_get(
+  state: &mut OpState,
+  cache_name: String,
+  _: (),
+) -> Result<Option<i64>, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let id = conn
+    .query_row(
+      "SELECT id FROM caches WHERE name = ?",
+      params![cache_name],
+      |row| row.get(0),
+    )
+    .optional()?;
+
+  Ok(id)
+}
+
+pub fn op_cache_storage_delete(
+  state: &mut OpState,
+  cache_name: String,
+  _: (),
+) -> Result<bool, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let deleted =
+    conn.execute("DELETE FROM caches WHERE name = ?", params![cache_name])?;
+
+  Ok(deleted > 0)
+}
+
+pub fn op_cache_storage_keys(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<Vec<String>, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let mut stmt = conn.prepare("SELECT name FROM caches ORDER BY id")?;
+
+  let keys = stmt
+    .query_map(params![], |row| row.get::<_, String>(0))?
+    .collect::<Result<_, _>>()?;
+
+  Ok(keys)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePutArgs {
+  cache_id: i64,
+  request_url: String,
+  request_headers: Vec<(String, String)>,
+  response_type: String,
+  response_url: Option<String>,
+  response_status: u16,
+  response_status_text: String,
+  response_headers: Vec<(String, String)>,
+  /// Entries that the new one replaces, as computed by the query in JS.
+  replaces: Vec<i64>,
+}
+
+pub fn op_cache_put(
+  state: &mut OpState,
+  args: CachePutArgs,
+  body: Option<ZeroCopyBuf>,
+) -> Result<(), AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let tx = conn.unchecked_transaction()?;
+  ensure_cache_exists(&tx, args.cache_id)?;
+  for id in args.replaces {
+    tx.execute(
+      "DELETE FROM entries WHERE id = ? AND cache_id = ?",
+      params![id, args.cache_id],
+    )?;
+  }
+  tx.execute(
+    "INSERT INTO entries (
+      cache_id,
+      request_url,
+      request_headers,
+      response_type,
+      response_url,
+      response_status,
+      response_status_text,
+      response_headers,
+      response_body
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    params![
+      args.cache_id,
+      args.request_url,
+      serde_json::to_string(&args.request_headers)?,
+      args.response_type,
+      args.response_url,
+      args.response_status,
+      args.response_status_text,
+      serde_json::to_string(&args.response_headers)?,
+      body.as_deref(),
+    ],
+  )?;
+  tx.commit()?;
+
+  Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheQueryArgs {
+  cache_id: i64,
+  request_url: Option<String>,
+  ignore_search: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+  id: i64,
+  request_url: String,
+  request_headers: Vec<(String, String)>,
+  response_type: String,
+  response_url: Option<String>,
+  response_status: u16,
+  response_status_text: String,
+  response_headers: Vec<(String, String)>,
+  has_body: bool,
+}
+
+/// Returns the entries of a cache whose request URL matches, in insertion
+/// order. Matching on `Vary` and the request method is left to the caller.
+pub fn op_cache_query(
+  state: &mut OpState,
+  args: CacheQueryArgs,
+  _: (),
+) -> Result<Vec<CacheEntry>, AnyError> {
+  let conn = get_cache_storage(state)?;
+  ensure_cache_exists(conn, args.cache_id)?;
+
+  const COLUMNS: &str = "id,
+    request_url,
+    request_headers,
+    response_type,
+    response_url,
+    response_status,
+    response_status_text,
+    response_headers,
+    response_body IS NOT NULL";
+  let mut stmt;
+  let mut rows = match args.request_url.as_deref() {
+    None => {
+      stmt = conn.prepare(&format!(
+        "SELECT {} FROM entries WHERE cache_id = ? ORDER BY id",
+        COLUMNS
+      ))?;
+      stmt.query(params![args.cache_id])?
+    }
+    Some(url) if args.ignore_search => {
+      // The URLs with a search are exactly those in the range from
+      // `{url}?` to `{url}@`, as '@' follows '?'.
+      let url = strip_search(url);
+      stmt = conn.prepare(&format!(
+        "SELECT {} FROM entries WHERE cache_id = ?
+          AND (request_url = ? OR (request_url >= ? AND request_url < ?))
+          ORDER BY id",
+        COLUMNS
+      ))?;
+      stmt.query(params![
+        args.cache_id,
+        url,
+        format!("{}?", url),
+        format!("{}@", url)
+      ])?
+    }
+    Some(url) => {
+      stmt = conn.prepare(&format!(
+        "SELECT {} FROM entries WHERE cache_id = ? AND request_url = ?
+          ORDER BY id",
+        COLUMNS
+      ))?;
+      stmt.query(params![args.cache_id, url])?
+    }
+  };
+
+  let mut entries = vec![];
+  while let Some(row) = rows.next()? {
+    entries.push(CacheEntry {
+      id: row.get(0)?,
+      request_url: row.get(1)?,
+      request_headers: serde_json::from_str(&row.get::<_, String>(2)?)?,
+      response_type: row.get(3)?,
+      response_url: row.get(4)?,
+      response_status: row.get(5)?,
+      response_status_text: row.get(6)?,
+      response_headers: serde_json::from_str(&row.get::<_, String>(7)?)?,
+      has_body: row.get(8)?,
+    });
+  }
+
+  Ok(entries)
+}
+
+pub fn op_cache_read_body(
+  state: &mut OpState,
+  entry_id: i64,
+  _: (),
+) -> Result<Option<ZeroCopyBuf>, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let body: Option<Option<Vec<u8>>> = conn
+    .query_row(
+      "SELECT response_body FROM entries WHERE id = ?",
+      params![entry_id],
+      |row| row.get(0),
+    )
+    .optional()?;
+
+  Ok(body.flatten().map(ZeroCopyBuf::from))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheDeleteArgs {
+  cache_id: i64,
+  entries: Vec<i64>,
+}
+
+pub fn op_cache_delete(
+  state: &mut OpState,
+  args: CacheDeleteArgs,
+  _: (),
+) -> Result<bool, AnyError> {
+  let conn = get_cache_storage(state)?;
+
+  let tx = conn.unchecked_transaction()?;
+  ensure_cache_exists(&tx, args.cache_id)?;
+  let mut deleted = 0;
+  for id in args.entries {
+    deleted += tx.execute(
+      "DELETE FROM entries WHERE id = ? AND cache_id = ?",
+      params![id, args.cache_id],
+    )?;
+  }
+  tx.commit()?;
+
+  Ok(deleted > 0)
+}
+
+/// A `Cache` object outlives the deletion of its cache through
+/// `caches.delete()`, after which every operation on it fails.
+fn ensure_cache_exists(
+  conn: &Connection,
+  cache_id: i64,
+) -> Result<(), AnyError> {
+  let id: Option<i64> = conn
+    .query_row(
+      "SELECT id FROM caches WHERE id = ?",
+      params![cache_id],
+      |row| row.get(0),
+    )
+    .optional()?;
+
+  if id.is_none() {
+    return Err(type_error("Cache has been deleted"));
+  }
+
+  Ok(())
+}
+
+fn strip_search(url: &str) -> &str {
+  url.split('?').next().unwrap()
+}
@@ -12,6 +12,49 @@
     return webidl.converters["DOMString"](V, opts);
   };
 
+  webidl.converters["KeyType"] = webidl.createEnumConverter("KeyType", [
+    "public",
+    "private",
+    "secret",
+  ]);
+
+  webidl.converters["KeyFormat"] = webidl.createEnumConverter("KeyFormat", [
+    "raw",
+    "pkcs8",
+    "spki",
+    "jwk",
+  ]);
+
+  webidl.converters["KeyUsage"] = webidl.createEnumConverter("KeyUsage", [
+    "encrypt",
+    "decrypt",
+    "sign",
+    "verify",
+    "deriveKey",
+    "deriveBits",
+    "wrapKey",
+    "unwrapKey",
+  ]);
+
+  webidl.converters["sequence<KeyUsage>"] = webidl.createSequenceConverter(
+    webidl.converters["KeyUsage"],
+  );
+
+  webidl.converters["HashAlgorithmIdentifier"] =
+    webidl.converters["AlgorithmIdentifier"];
+
+  webidl.converters["BigInteger"] = (V, opts) => {
+    const view = webidl.converters["ArrayBufferView"](V, opts);
+    if (!(view instanceof Uint8Array)) {
+      throw new TypeError(
+        `${
+          opts.prefix ? opts.prefix + ": " : ""
+        }${opts.context} is not a Uint8Array.`,
+      );
+    }
+    return view;
+  };
+
   const algorithmDictionary = [
     {
       key: "name",
@@ -23,4 +66,370 @@
     "Algorithm",
     algorithmDictionary,
   );
+
+  const rsaKeyGenDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "modulusLength",
+      converter: (V, opts) =>
+        webidl.converters["unsigned long"](V, { ...opts, enforceRange: true }),
+      required: true,
+    },
+    {
+      key: "publicExponent",
+      converter: webidl.converters["BigInteger"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["RsaKeyGenParams"] = webidl.createDictionaryConverter(
+    "RsaKeyGenParams",
+    rsaKeyGenDictionary,
+  );
+
+  const rsaHashedKeyGenDictionary = [
+    ...rsaKeyGenDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["RsaHashedKeyGenParams"] = webidl
+    .createDictionaryConverter(
+      "RsaHashedKeyGenParams",
+      rsaHashedKeyGenDictionary,
+    );
+
+  const rsaHashedImportDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["RsaHashedImportParams"] = webidl
+    .createDictionaryConverter(
+      "RsaHashedImportParams",
+      rsaHashedImportDictionary,
+    );
+
+  const rsaPssDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "saltLength",
+      converter: (V, opts) =>
+        webidl.converters["unsigned long"](V, { ...opts, enforceRange: true }),
+      required: true,
+    },
+  ];
+
+  webidl.converters["RsaPssParams"] = webidl.createDictionaryConverter(
+    "RsaPssParams",
+    rsaPssDictionary,
+  );
+
+  const rsaOaepDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "label",
+      converter: webidl.converters["BufferSource"],
+    },
+  ];
+
+  webidl.converters["RsaOaepParams"] = webidl.createDictionaryConverter(
+    "RsaOaepParams",
+    rsaOaepDictionary,
+  );
+
+  const ecKeyGenDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "namedCurve",
+      converter: webidl.converters["DOMString"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["EcKeyGenParams"] = webidl.createDictionaryConverter(
+    "EcKeyGenParams",
+    ecKeyGenDictionary,
+  );
+
+  webidl.converters["EcKeyImportParams"] = webidl.createDictionaryConverter(
+    "EcKeyImportParams",
+    ecKeyGenDictionary,
+  );
+
+  const ecdsaDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["EcdsaParams"] = webidl.createDictionaryConverter(
+    "EcdsaParams",
+    ecdsaDictionary,
+  );
+
+  const aesKeyGenDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "length",
+      converter: (V, opts) =>
+        webidl.converters["unsigned short"](V, { ...opts, enforceRange: true }),
+      required: true,
+    },
+  ];
+
+  webidl.converters["AesKeyGenParams"] = webidl.createDictionaryConverter(
+    "AesKeyGenParams",
+    aesKeyGenDictionary,
+  );
+
+  webidl.converters["AesDerivedKeyParams"] = webidl.createDictionaryConverter(
+    "AesDerivedKeyParams",
+    aesKeyGenDictionary,
+  );
+
+  const aesCbcDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "iv",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["AesCbcParams"] = webidl.createDictionaryConverter(
+    "AesCbcParams",
+    aesCbcDictionary,
+  );
+
+  const aesCtrDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "counter",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+    {
+      key: "length",
+      converter: (V, opts) =>
+        webidl.converters["octet"](V, { ...opts, enforceRange: true }),
+      required: true,
+    },
+  ];
+
+  webidl.converters["AesCtrParams"] = webidl.createDictionaryConverter(
+    "AesCtrParams",
+    aesCtrDictionary,
+  );
+
+  const aesGcmDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "iv",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+    {
+      key: "additionalData",
+      converter: webidl.converters["BufferSource"],
+    },
+    {
+      key: "tagLength",
+      converter: (V, opts) =>
+        webidl.converters["octet"](V, { ...opts, enforceRange: true }),
+    },
+  ];
+
+  webidl.converters["AesGcmParams"] = webidl.createDictionaryConverter(
+    "AesGcmParams",
+    aesGcmDictionary,
+  );
+
+  const hmacKeyGenDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+    {
+      key: "length",
+      converter: (V, opts) =>
+        webidl.converters["unsigned long"](V, { ...opts, enforceRange: true }),
+    },
+  ];
+
+  webidl.converters["HmacKeyGenParams"] = webidl.createDictionaryConverter(
+    "HmacKeyGenParams",
+    hmacKeyGenDictionary,
+  );
+
+  webidl.converters["HmacImportParams"] = webidl.createDictionaryConverter(
+    "HmacImportParams",
+    hmacKeyGenDictionary,
+  );
+
+  const hkdfDictionary = [
+    ...algorithmDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+    {
+      key: "salt",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+    {
+      key: "info",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["HkdfParams"] = webidl.createDictionaryConverter(
+    "HkdfParams",
+    hkdfDictionary,
+  );
+
+  const pbkdf2Dictionary = [
+    ...algorithmDictionary,
+    {
+      key: "hash",
+      converter: webidl.converters["HashAlgorithmIdentifier"],
+      required: true,
+    },
+    {
+      key: "iterations",
+      converter: (V, opts) =>
+        webidl.converters["unsigned long"](V, { ...opts, enforceRange: true }),
+      required: true,
+    },
+    {
+      key: "salt",
+      converter: webidl.converters["BufferSource"],
+      required: true,
+    },
+  ];
+
+  webidl.converters["Pbkdf2Params"] = webidl.createDictionaryConverter(
+    "Pbkdf2Params",
+    pbkdf2Dictionary,
+  );
+
+  const rsaOtherPrimesInfoDictionary = [
+    {
+      key: "r",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "d",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "t",
+      converter: webidl.converters["DOMString"],
+    },
+  ];
+
+  webidl.converters["RsaOtherPrimesInfo"] = webidl.createDictionaryConverter(
+    "RsaOtherPrimesInfo",
+    rsaOtherPrimesInfoDictionary,
+  );
+
+  webidl.converters["sequence<RsaOtherPrimesInfo>"] = webidl
+    .createSequenceConverter(
+      webidl.converters["RsaOtherPrimesInfo"],
+    );
+
+  const jsonWebKeyDictionary = [
+    {
+      key: "kty",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "use",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "key_ops",
+      converter: webidl.converters["sequence<DOMString>"],
+    },
+    {
+      key: "alg",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "ext",
+      converter: webidl.converters["boolean"],
+    },
+    {
+      key: "crv",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "x",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "y",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "d",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "n",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "e",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "p",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "q",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "dp",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "dq",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "qi",
+      converter: webidl.converters["DOMString"],
+    },
+    {
+      key: "oth",
+      converter: webidl.converters["sequence<RsaOtherPrimesInfo>"],
+    },
+    {
+      key: "k",
+      converter: webidl.converters["DOMString"],
+    },
+  ];
+
+  webidl.converters["JsonWebKey"] = webidl.createDictionaryConverter(
+    "JsonWebKey",
+    jsonWebKeyDictionary,
+  );
 })(this);
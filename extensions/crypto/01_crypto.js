@@ -4,16 +4,121 @@
 ((window) => {
   const core = window.Deno.core;
   const webidl = window.__bootstrap.webidl;
+  const { forgivingBase64Encode, forgivingBase64Decode } =
+    window.__bootstrap.infra;
+
+  // P-521 is not yet supported.
+  const supportedNamedCurves = ["P-256", "P-384"];
+  // Should match op_crypto_derive_bits() in extensions/crypto/lib.rs
+  const supportedEcdhNamedCurves = ["P-256"];
+
+  // The members of the algorithm dictionaries which need to be normalized
+  // further, after the dictionary has been converted.
+  const simpleAlgorithmDictionaries = {
+    RsaHashedKeyGenParams: { hash: "HashAlgorithmIdentifier" },
+    RsaHashedImportParams: { hash: "HashAlgorithmIdentifier" },
+    RsaOaepParams: { label: "BufferSource" },
+    EcdsaParams: { hash: "HashAlgorithmIdentifier" },
+    HmacKeyGenParams: { hash: "HashAlgorithmIdentifier" },
+    HmacImportParams: { hash: "HashAlgorithmIdentifier" },
+    AesCbcParams: { iv: "BufferSource" },
+    AesCtrParams: { counter: "BufferSource" },
+    AesGcmParams: { iv: "BufferSource", additionalData: "BufferSource" },
+    HkdfParams: {
+      hash: "HashAlgorithmIdentifier",
+      salt: "BufferSource",
+      info: "BufferSource",
+    },
+    Pbkdf2Params: { hash: "HashAlgorithmIdentifier", salt: "BufferSource" },
+  };
 
   const supportedAlgorithms = {
     "digest": {
-      "SHA-1": {},
-      "SHA-256": {},
-      "SHA-384": {},
-      "SHA-512": {},
+      "SHA-1": null,
+      "SHA-256": null,
+      "SHA-384": null,
+      "SHA-512": null,
+    },
+    "generateKey": {
+      "RSASSA-PKCS1-v1_5": "RsaHashedKeyGenParams",
+      "RSA-PSS": "RsaHashedKeyGenParams",
+      "RSA-OAEP": "RsaHashedKeyGenParams",
+      "ECDSA": "EcKeyGenParams",
+      "ECDH": "EcKeyGenParams",
+      "Ed25519": null,
+      "X25519": null,
+      "AES-CTR": "AesKeyGenParams",
+      "AES-CBC": "AesKeyGenParams",
+      "AES-GCM": "AesKeyGenParams",
+      "AES-KW": "AesKeyGenParams",
+      "HMAC": "HmacKeyGenParams",
+    },
+    "sign": {
+      "RSASSA-PKCS1-v1_5": null,
+      "RSA-PSS": "RsaPssParams",
+      "ECDSA": "EcdsaParams",
+      "Ed25519": null,
+      "HMAC": null,
+    },
+    "verify": {
+      "RSASSA-PKCS1-v1_5": null,
+      "RSA-PSS": "RsaPssParams",
+      "ECDSA": "EcdsaParams",
+      "Ed25519": null,
+      "HMAC": null,
+    },
+    "importKey": {
+      "RSASSA-PKCS1-v1_5": "RsaHashedImportParams",
+      "RSA-PSS": "RsaHashedImportParams",
+      "RSA-OAEP": "RsaHashedImportParams",
+      "ECDSA": "EcKeyImportParams",
+      "ECDH": "EcKeyImportParams",
+      "Ed25519": null,
+      "X25519": null,
+      "AES-CTR": null,
+      "AES-CBC": null,
+      "AES-GCM": null,
+      "AES-KW": null,
+      "HMAC": "HmacImportParams",
+      "HKDF": null,
+      "PBKDF2": null,
+    },
+    "deriveBits": {
+      "ECDH": "EcdhKeyDeriveParams",
+      "X25519": "EcdhKeyDeriveParams",
+      "HKDF": "HkdfParams",
+      "PBKDF2": "Pbkdf2Params",
+    },
+    "encrypt": {
+      "RSA-OAEP": "RsaOaepParams",
+      "AES-CTR": "AesCtrParams",
+      "AES-CBC": "AesCbcParams",
+      "AES-GCM": "AesGcmParams",
+    },
+    "decrypt": {
+      "RSA-OAEP": "RsaOaepParams",
+      "AES-CTR": "AesCtrParams",
+      "AES-CBC": "AesCbcParams",
+      "AES-GCM": "AesGcmParams",
+    },
+    "get key length": {
+      "AES-CTR": "AesDerivedKeyParams",
+      "AES-CBC": "AesDerivedKeyParams",
+      "AES-GCM": "AesDerivedKeyParams",
+      "AES-KW": "AesDerivedKeyParams",
+      "HMAC": "HmacImportParams",
+      "HKDF": null,
+      "PBKDF2": null,
+    },
+    "wrapKey": {
+      "AES-KW": null,
+    },
+    "unwrapKey": {
+      "AES-KW": null,
     },
   };
 
+  // See https://www.w3.org/TR/WebCryptoAPI/#dfn-normalize-an-algorithm
   function normalizeAlgorithm(algorithm, op) {
     if (typeof algorithm == "string") {
       return normalizeAlgorithm({ name: algorithm }, op);
@@ -34,16 +139,72 @@
       );
     }
 
-    // TODO(caspervonb) Step 6 (create from webidl definition), when the need arises.
-    // See https://www.w3.org/TR/WebCryptoAPI/#dfn-normalize-an-algorithm
-    const normalizedAlgorithm = {};
+    const desiredType = registeredAlgorithms[algorithmName];
+    if (desiredType === null) {
+      return { name: algorithmName };
+    }
+
+    const normalizedAlgorithm = webidl.converters[desiredType](algorithm, {
+      context: "Argument 1",
+    });
     normalizedAlgorithm.name = algorithmName;
 
-    // TODO(caspervonb) Step 9 and 10, when the need arises.
-    // See https://www.w3.org/TR/WebCryptoAPI/#dfn-normalize-an-algorithm
+    const dict = simpleAlgorithmDictionaries[desiredType] ?? {};
+    for (const member in dict) {
+      const idlValue = normalizedAlgorithm[member];
+      if (idlValue === undefined) {
+        continue;
+      }
+      if (dict[member] === "BufferSource") {
+        normalizedAlgorithm[member] = copyBuffer(idlValue);
+      } else if (dict[member] === "HashAlgorithmIdentifier") {
+        normalizedAlgorithm[member] = normalizeAlgorithm(idlValue, "digest");
+      }
+    }
+
     return normalizedAlgorithm;
   }
 
+  /**
+   * @param {ArrayBufferView | ArrayBuffer} input
+   * @returns {Uint8Array}
+   */
+  function copyBuffer(input) {
+    if (ArrayBuffer.isView(input)) {
+      return new Uint8Array(input.buffer, input.byteOffset, input.byteLength)
+        .slice();
+    }
+    return new Uint8Array(input).slice();
+  }
+
+  /**
+   * @param {Uint8Array} data
+   * @returns {string}
+   */
+  function encodeBase64Url(data) {
+    return forgivingBase64Encode(data)
+      .replace(/\+/g, "-")
+      .replace(/\//g, "_")
+      .replace(/=+$/, "");
+  }
+
+  /**
+   * @param {string} data
+   * @returns {Uint8Array}
+   */
+  function decodeBase64Url(data) {
+    try {
+      return forgivingBase64Decode(
+        data.replace(/-/g, "+").replace(/_/g, "/"),
+      );
+    } catch {
+      throw new DOMException(
+        "The JSON Web Key contains invalid base64url data",
+        "DataError",
+      );
+    }
+  }
+
   // Should match op_crypto_subtle_digest() in extensions/crypto/lib.rs
   function digestToId(name) {
     switch (name) {
@@ -58,6 +219,1052 @@
     }
   }
 
+  const _handle = Symbol("[[handle]]");
+  const _algorithm = Symbol("[[algorithm]]");
+  const _extractable = Symbol("[[extractable]]");
+  const _usages = Symbol("[[usages]]");
+  const _type = Symbol("[[type]]");
+
+  // The key material of the keys, which is kept out of reach of user code.
+  // It maps the handle of a key to `{ type, data }`, where the data of a secret
+  // key is raw bytes, of a private key is PKCS#8 in DER and of a public key is
+  // a SubjectPublicKeyInfo in DER.
+  const KEY_STORE = new WeakMap();
+
+  class CryptoKey {
+    constructor() {
+      webidl.illegalConstructor();
+    }
+
+    get type() {
+      webidl.assertBranded(this, CryptoKey);
+      return this[_type];
+    }
+
+    get extractable() {
+      webidl.assertBranded(this, CryptoKey);
+      return this[_extractable];
+    }
+
+    get usages() {
+      webidl.assertBranded(this, CryptoKey);
+      return this[_usages];
+    }
+
+    get algorithm() {
+      webidl.assertBranded(this, CryptoKey);
+      return this[_algorithm];
+    }
+
+    get [Symbol.toStringTag]() {
+      return "CryptoKey";
+    }
+
+    [Symbol.for("Deno.customInspect")](inspect) {
+      return `${this.constructor.name} ${
+        inspect({
+          type: this.type,
+          extractable: this.extractable,
+          algorithm: this.algorithm,
+          usages: this.usages,
+        })
+      }`;
+    }
+  }
+
+  webidl.configurePrototype(CryptoKey);
+
+  webidl.converters["CryptoKey"] = webidl.createInterfaceConverter(
+    "CryptoKey",
+    CryptoKey,
+  );
+
+  webidl.converters["EcdhKeyDeriveParams"] = webidl.createDictionaryConverter(
+    "EcdhKeyDeriveParams",
+    [
+      {
+        key: "name",
+        converter: webidl.converters["DOMString"],
+      },
+      {
+        key: "public",
+        converter: webidl.converters["CryptoKey"],
+        required: true,
+      },
+    ],
+  );
+
+  /**
+   * @param {"public" | "private" | "secret"} type
+   * @param {boolean} extractable
+   * @param {string[]} usages
+   * @param {object} algorithm
+   * @param {{ type: string, data: Uint8Array }} keyData
+   * @returns {CryptoKey}
+   */
+  function constructKey(type, extractable, usages, algorithm, keyData) {
+    const handle = {};
+    KEY_STORE.set(handle, keyData);
+    const key = webidl.createBranded(CryptoKey);
+    key[_type] = type;
+    key[_extractable] = extractable;
+    key[_usages] = Object.freeze(usages);
+    key[_algorithm] = Object.freeze(algorithm);
+    key[_handle] = handle;
+    return key;
+  }
+
+  /**
+   * @param {string[]} usages
+   * @param {string[]} supported
+   */
+  function checkUsages(usages, supported) {
+    if (usages.some((usage) => !supported.includes(usage))) {
+      throw new DOMException("Unsupported key usage", "SyntaxError");
+    }
+  }
+
+  /**
+   * @param {string[]} a
+   * @param {string[]} b
+   * @returns {string[]}
+   */
+  function usageIntersection(a, b) {
+    return a.filter((usage) => b.includes(usage));
+  }
+
+  /**
+   * @param {CryptoKey} key
+   * @param {string} name
+   * @param {string} usage
+   */
+  function checkKey(key, name, usage) {
+    if (key[_algorithm].name !== name) {
+      throw new DOMException(
+        "The algorithm of the key does not match the requested algorithm",
+        "InvalidAccessError",
+      );
+    }
+    if (!key[_usages].includes(usage)) {
+      throw new DOMException(
+        `The key does not support the '${usage}' operation`,
+        "InvalidAccessError",
+      );
+    }
+  }
+
+  /**
+   * @param {CryptoKey} key
+   * @param {string} type
+   */
+  function checkKeyType(key, type) {
+    if (key[_type] !== type) {
+      throw new DOMException(
+        `The key must be a ${type} key`,
+        "InvalidAccessError",
+      );
+    }
+  }
+
+  /**
+   * @param {CryptoKey} key
+   * @param {string[]} usages
+   */
+  function checkUsagesNotEmpty(key, usages) {
+    if (
+      (key[_type] === "secret" || key[_type] === "private") &&
+      usages.length === 0
+    ) {
+      throw new DOMException(
+        "The usages of a secret or private key must not be empty",
+        "SyntaxError",
+      );
+    }
+  }
+
+  const rsaUsages = {
+    "RSASSA-PKCS1-v1_5": { private: ["sign"], public: ["verify"] },
+    "RSA-PSS": { private: ["sign"], public: ["verify"] },
+    "RSA-OAEP": {
+      private: ["decrypt", "unwrapKey"],
+      public: ["encrypt", "wrapKey"],
+    },
+  };
+
+  const ecUsages = {
+    "ECDSA": { private: ["sign"], public: ["verify"] },
+    "ECDH": { private: ["deriveKey", "deriveBits"], public: [] },
+  };
+
+  const curve25519Usages = {
+    "Ed25519": { private: ["sign"], public: ["verify"] },
+    "X25519": { private: ["deriveKey", "deriveBits"], public: [] },
+  };
+
+  const aesUsages = {
+    "AES-CTR": ["encrypt", "decrypt", "wrapKey", "unwrapKey"],
+    "AES-CBC": ["encrypt", "decrypt", "wrapKey", "unwrapKey"],
+    "AES-GCM": ["encrypt", "decrypt", "wrapKey", "unwrapKey"],
+    "AES-KW": ["wrapKey", "unwrapKey"],
+  };
+
+  // The suffixes of the `alg` member of the JSON Web Keys of AES keys.
+  const aesJwkAlgSuffixes = {
+    "AES-CTR": "CTR",
+    "AES-CBC": "CBC",
+    "AES-GCM": "GCM",
+    "AES-KW": "KW",
+  };
+
+  // The `alg` members of the JSON Web Keys of the algorithms, by hash.
+  const jwkAlgs = {
+    "HMAC": {
+      "SHA-1": "HS1",
+      "SHA-256": "HS256",
+      "SHA-384": "HS384",
+      "SHA-512": "HS512",
+    },
+    "RSASSA-PKCS1-v1_5": {
+      "SHA-1": "RS1",
+      "SHA-256": "RS256",
+      "SHA-384": "RS384",
+      "SHA-512": "RS512",
+    },
+    "RSA-PSS": {
+      "SHA-1": "PS1",
+      "SHA-256": "PS256",
+      "SHA-384": "PS384",
+      "SHA-512": "PS512",
+    },
+    "RSA-OAEP": {
+      "SHA-1": "RSA-OAEP",
+      "SHA-256": "RSA-OAEP-256",
+      "SHA-384": "RSA-OAEP-384",
+      "SHA-512": "RSA-OAEP-512",
+    },
+    "ECDSA": {
+      "P-256": "ES256",
+      "P-384": "ES384",
+    },
+  };
+
+  /**
+   * @param {string} name
+   * @returns {number}
+   */
+  function getHashBlockSize(name) {
+    switch (name) {
+      case "SHA-1":
+      case "SHA-256":
+        return 512;
+      case "SHA-384":
+      case "SHA-512":
+        return 1024;
+    }
+  }
+
+  /**
+   * The checks of the members of a JSON Web Key which are shared by all of the
+   * algorithms.
+   */
+  function checkJwk(jwk, kty, use, usages, extractable) {
+    if (jwk.kty !== kty) {
+      throw new DOMException(
+        `'kty' member of JsonWebKey must be '${kty}'`,
+        "DataError",
+      );
+    }
+    if (usages.length > 0 && jwk.use !== undefined && jwk.use !== use) {
+      throw new DOMException(
+        `'use' member of JsonWebKey must be '${use}'`,
+        "DataError",
+      );
+    }
+    if (
+      jwk.key_ops !== undefined &&
+      usages.some((usage) => !jwk.key_ops.includes(usage))
+    ) {
+      throw new DOMException(
+        "'key_ops' member of JsonWebKey is invalid",
+        "DataError",
+      );
+    }
+    if (jwk.ext === false && extractable) {
+      throw new DOMException(
+        "'ext' member of JsonWebKey is invalid",
+        "DataError",
+      );
+    }
+  }
+
+  /**
+   * @param {object} jwk
+   * @param {string | undefined} expected
+   */
+  function checkJwkAlg(jwk, expected) {
+    if (jwk.alg !== undefined && jwk.alg !== expected) {
+      throw new DOMException(
+        "'alg' member of JsonWebKey is invalid",
+        "DataError",
+      );
+    }
+  }
+
+  /**
+   * @param {object} jwk
+   * @returns {object}
+   */
+  function decodeJwkMembers(jwk) {
+    const members = {};
+    const names = ["n", "e", "d", "p", "q", "dp", "dq", "qi", "x", "y"];
+    for (const member of names) {
+      if (jwk[member] !== undefined) {
+        members[member] = decodeBase64Url(jwk[member]);
+      }
+    }
+    return members;
+  }
+
+  /**
+   * @param {object} members
+   * @returns {object}
+   */
+  function encodeJwkMembers(members) {
+    const jwk = {};
+    for (const [member, value] of Object.entries(members)) {
+      if (value !== null && value !== undefined) {
+        jwk[member] = encodeBase64Url(value);
+      }
+    }
+    return jwk;
+  }
+
+  async function generateKey(normalizedAlgorithm, extractable, usages) {
+    const algorithmName = normalizedAlgorithm.name;
+    switch (algorithmName) {
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS":
+      case "RSA-OAEP": {
+        const supportedUsages = rsaUsages[algorithmName];
+        checkUsages(usages, [
+          ...supportedUsages.private,
+          ...supportedUsages.public,
+        ]);
+        checkRsaKeyParams(
+          normalizedAlgorithm.modulusLength,
+          normalizedAlgorithm.publicExponent,
+        );
+        const keyPair = await core.opAsync("op_crypto_generate_key", {
+          name: algorithmName,
+          modulusLength: normalizedAlgorithm.modulusLength,
+          publicExponent: normalizedAlgorithm.publicExponent,
+        });
+        const algorithm = {
+          name: algorithmName,
+          modulusLength: normalizedAlgorithm.modulusLength,
+          publicExponent: normalizedAlgorithm.publicExponent,
+          hash: normalizedAlgorithm.hash,
+        };
+        return constructKeyPair(
+          keyPair,
+          algorithm,
+          extractable,
+          usageIntersection(usages, supportedUsages.private),
+          usageIntersection(usages, supportedUsages.public),
+        );
+      }
+      case "ECDSA":
+      case "ECDH": {
+        const supportedUsages = ecUsages[algorithmName];
+        checkUsages(usages, [
+          ...supportedUsages.private,
+          ...supportedUsages.public,
+        ]);
+        const namedCurve = normalizedAlgorithm.namedCurve;
+        checkNamedCurve(algorithmName, namedCurve);
+        const keyPair = await core.opAsync("op_crypto_generate_key", {
+          name: algorithmName,
+          namedCurve,
+        });
+        return constructKeyPair(
+          keyPair,
+          { name: algorithmName, namedCurve },
+          extractable,
+          usageIntersection(usages, supportedUsages.private),
+          usageIntersection(usages, supportedUsages.public),
+        );
+      }
+      case "Ed25519":
+      case "X25519": {
+        const supportedUsages = curve25519Usages[algorithmName];
+        checkUsages(usages, [
+          ...supportedUsages.private,
+          ...supportedUsages.public,
+        ]);
+        const keyPair = await core.opAsync("op_crypto_generate_key", {
+          name: algorithmName,
+        });
+        return constructKeyPair(
+          keyPair,
+          { name: algorithmName },
+          extractable,
+          usageIntersection(usages, supportedUsages.private),
+          usageIntersection(usages, supportedUsages.public),
+        );
+      }
+      case "AES-CTR":
+      case "AES-CBC":
+      case "AES-GCM":
+      case "AES-KW": {
+        checkUsages(usages, aesUsages[algorithmName]);
+        const length = normalizedAlgorithm.length;
+        if (![128, 192, 256].includes(length)) {
+          throw new DOMException(
+            "The length of an AES key must be 128, 192 or 256 bits",
+            "OperationError",
+          );
+        }
+        const data = new Uint8Array(length / 8);
+        core.opSync("op_crypto_get_random_values", data);
+        return constructKey(
+          "secret",
+          extractable,
+          usages,
+          { name: algorithmName, length },
+          { type: "secret", data },
+        );
+      }
+      case "HMAC": {
+        checkUsages(usages, ["sign", "verify"]);
+        const hash = normalizedAlgorithm.hash;
+        const length = normalizedAlgorithm.length ??
+          getHashBlockSize(hash.name);
+        if (length === 0) {
+          throw new DOMException(
+            "The length of an HMAC key must not be zero",
+            "OperationError",
+          );
+        }
+        const data = new Uint8Array(Math.ceil(length / 8));
+        core.opSync("op_crypto_get_random_values", data);
+        return constructKey(
+          "secret",
+          extractable,
+          usages,
+          { name: algorithmName, hash, length },
+          { type: "secret", data },
+        );
+      }
+    }
+  }
+
+  const minRsaModulusLength = 1024;
+  const maxRsaModulusLength = 16384;
+
+  /**
+   * Only the public exponents 3 and 65537 are supported, as generating the
+   * primes for an invalid exponent, like an even one, never finishes.
+   * @param {number} modulusLength
+   * @param {Uint8Array} publicExponent
+   */
+  function checkRsaKeyParams(modulusLength, publicExponent) {
+    if (
+      modulusLength < minRsaModulusLength ||
+      modulusLength > maxRsaModulusLength
+    ) {
+      throw new DOMException(
+        `The modulus length of an RSA key must be between ${minRsaModulusLength} and ${maxRsaModulusLength} bits`,
+        "OperationError",
+      );
+    }
+    let exponent = 0;
+    for (const byte of publicExponent) {
+      exponent = exponent * 256 + byte;
+    }
+    if (exponent !== 3 && exponent !== 65537) {
+      throw new DOMException(
+        "The public exponent of an RSA key must be 3 or 65537",
+        "OperationError",
+      );
+    }
+  }
+
+  function checkNamedCurve(algorithmName, namedCurve) {
+    const supported = algorithmName === "ECDH"
+      ? supportedEcdhNamedCurves
+      : supportedNamedCurves;
+    if (!supported.includes(namedCurve)) {
+      throw new DOMException(
+        `The named curve '${namedCurve}' is not supported`,
+        "NotSupportedError",
+      );
+    }
+  }
+
+  function constructKeyPair(
+    keyPair,
+    algorithm,
+    extractable,
+    privateUsages,
+    publicUsages,
+  ) {
+    const privateKey = constructKey(
+      "private",
+      extractable,
+      privateUsages,
+      algorithm,
+      { type: "private", data: keyPair.privateKey },
+    );
+    // The public key of a key pair is always extractable.
+    const publicKey = constructKey(
+      "public",
+      true,
+      publicUsages,
+      algorithm,
+      { type: "public", data: keyPair.publicKey },
+    );
+    return { privateKey, publicKey };
+  }
+
+  function importSecretKey(format, keyData, jwkCheck) {
+    switch (format) {
+      case "raw":
+        return keyData;
+      case "jwk": {
+        jwkCheck();
+        if (keyData.k === undefined) {
+          throw new DOMException(
+            "'k' member of JsonWebKey is required",
+            "DataError",
+          );
+        }
+        return decodeBase64Url(keyData.k);
+      }
+      default:
+        throw new DOMException(
+          `The '${format}' format is not supported for secret keys`,
+          "NotSupportedError",
+        );
+    }
+  }
+
+  function importKey(
+    format,
+    keyData,
+    normalizedAlgorithm,
+    extractable,
+    usages,
+  ) {
+    const algorithmName = normalizedAlgorithm.name;
+    switch (algorithmName) {
+      case "HMAC": {
+        checkUsages(usages, ["sign", "verify"]);
+        const hash = normalizedAlgorithm.hash;
+        const data = importSecretKey(format, keyData, () => {
+          checkJwk(keyData, "oct", "sig", usages, extractable);
+          checkJwkAlg(keyData, jwkAlgs.HMAC[hash.name]);
+        });
+        let length = data.byteLength * 8;
+        if (length === 0) {
+          throw new DOMException("The key data is empty", "DataError");
+        }
+        if (normalizedAlgorithm.length !== undefined) {
+          if (
+            normalizedAlgorithm.length > length ||
+            normalizedAlgorithm.length <= length - 8
+          ) {
+            throw new DOMException(
+              "The length does not match the key data",
+              "DataError",
+            );
+          }
+          length = normalizedAlgorithm.length;
+        }
+        return constructKey(
+          "secret",
+          extractable,
+          usages,
+          { name: algorithmName, hash, length },
+          { type: "secret", data },
+        );
+      }
+      case "AES-CTR":
+      case "AES-CBC":
+      case "AES-GCM":
+      case "AES-KW": {
+        checkUsages(usages, aesUsages[algorithmName]);
+        const data = importSecretKey(format, keyData, () => {
+          checkJwk(keyData, "oct", "enc", usages, extractable);
+        });
+        const length = data.byteLength * 8;
+        if (![128, 192, 256].includes(length)) {
+          throw new DOMException(
+            "The length of an AES key must be 128, 192 or 256 bits",
+            "DataError",
+          );
+        }
+        if (format === "jwk") {
+          checkJwkAlg(
+            keyData,
+            `A${length}${aesJwkAlgSuffixes[algorithmName]}`,
+          );
+        }
+        return constructKey(
+          "secret",
+          extractable,
+          usages,
+          { name: algorithmName, length },
+          { type: "secret", data },
+        );
+      }
+      case "HKDF":
+      case "PBKDF2": {
+        if (format !== "raw") {
+          throw new DOMException(
+            `The '${format}' format is not supported for ${algorithmName}`,
+            "NotSupportedError",
+          );
+        }
+        checkUsages(usages, ["deriveKey", "deriveBits"]);
+        if (extractable !== false) {
+          throw new DOMException(
+            `${algorithmName} keys must not be extractable`,
+            "SyntaxError",
+          );
+        }
+        return constructKey(
+          "secret",
+          false,
+          usages,
+          { name: algorithmName },
+          { type: "secret", data: keyData },
+        );
+      }
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS":
+      case "RSA-OAEP": {
+        const supportedUsages = rsaUsages[algorithmName];
+        const hash = normalizedAlgorithm.hash;
+        let result;
+        if (format === "jwk") {
+          checkJwk(
+            keyData,
+            "RSA",
+            algorithmName === "RSA-OAEP" ? "enc" : "sig",
+            usages,
+            extractable,
+          );
+          checkJwkAlg(keyData, jwkAlgs[algorithmName][hash.name]);
+          result = core.opSync("op_crypto_import_jwk", {
+            algorithm: algorithmName,
+            jwk: decodeJwkMembers(keyData),
+          });
+        } else if (format === "spki" || format === "pkcs8") {
+          result = core.opSync("op_crypto_import_key", {
+            algorithm: algorithmName,
+            format,
+          }, keyData);
+        } else {
+          throw new DOMException(
+            `The '${format}' format is not supported for ${algorithmName}`,
+            "NotSupportedError",
+          );
+        }
+        checkUsages(usages, supportedUsages[result.type]);
+        return constructKey(
+          result.type,
+          extractable,
+          usages,
+          {
+            name: algorithmName,
+            modulusLength: result.modulusLength,
+            publicExponent: result.publicExponent,
+            hash,
+          },
+          { type: result.type, data: result.data },
+        );
+      }
+      case "ECDSA":
+      case "ECDH": {
+        const supportedUsages = ecUsages[algorithmName];
+        const namedCurve = normalizedAlgorithm.namedCurve;
+        checkNamedCurve(algorithmName, namedCurve);
+        let result;
+        if (format === "jwk") {
+          checkJwk(
+            keyData,
+            "EC",
+            algorithmName === "ECDSA" ? "sig" : "enc",
+            usages,
+            extractable,
+          );
+          if (keyData.crv !== namedCurve) {
+            throw new DOMException(
+              "'crv' member of JsonWebKey does not match the named curve",
+              "DataError",
+            );
+          }
+          if (algorithmName === "ECDSA") {
+            checkJwkAlg(keyData, jwkAlgs.ECDSA[namedCurve]);
+          }
+          result = core.opSync("op_crypto_import_jwk", {
+            algorithm: algorithmName,
+            namedCurve,
+            jwk: decodeJwkMembers(keyData),
+          });
+        } else if (["raw", "spki", "pkcs8"].includes(format)) {
+          result = core.opSync("op_crypto_import_key", {
+            algorithm: algorithmName,
+            format,
+            namedCurve,
+          }, keyData);
+        } else {
+          throw new DOMException(
+            `The '${format}' format is not supported for ${algorithmName}`,
+            "NotSupportedError",
+          );
+        }
+        checkUsages(usages, supportedUsages[result.type]);
+        return constructKey(
+          result.type,
+          extractable,
+          usages,
+          { name: algorithmName, namedCurve },
+          { type: result.type, data: result.data },
+        );
+      }
+      case "Ed25519":
+      case "X25519": {
+        const supportedUsages = curve25519Usages[algorithmName];
+        let result;
+        if (format === "jwk") {
+          checkJwk(
+            keyData,
+            "OKP",
+            algorithmName === "Ed25519" ? "sig" : "enc",
+            usages,
+            extractable,
+          );
+          if (keyData.crv !== algorithmName) {
+            throw new DOMException(
+              `'crv' member of JsonWebKey must be '${algorithmName}'`,
+              "DataError",
+            );
+          }
+          if (
+            algorithmName === "Ed25519" && keyData.alg !== undefined &&
+            keyData.alg !== "EdDSA" && keyData.alg !== "Ed25519"
+          ) {
+            throw new DOMException(
+              "'alg' member of JsonWebKey is invalid",
+              "DataError",
+            );
+          }
+          result = core.opSync("op_crypto_import_jwk", {
+            algorithm: algorithmName,
+            jwk: decodeJwkMembers(keyData),
+          });
+        } else if (["raw", "spki", "pkcs8"].includes(format)) {
+          result = core.opSync("op_crypto_import_key", {
+            algorithm: algorithmName,
+            format,
+          }, keyData);
+        } else {
+          throw new DOMException(
+            `The '${format}' format is not supported for ${algorithmName}`,
+            "NotSupportedError",
+          );
+        }
+        checkUsages(usages, supportedUsages[result.type]);
+        return constructKey(
+          result.type,
+          extractable,
+          usages,
+          { name: algorithmName },
+          { type: result.type, data: result.data },
+        );
+      }
+    }
+  }
+
+  function exportKey(format, key) {
+    const keyData = KEY_STORE.get(key[_handle]);
+    const algorithm = key[_algorithm];
+    const algorithmName = algorithm.name;
+
+    if (format === "jwk") {
+      const jwk = exportJwk(key, keyData);
+      jwk.key_ops = [...key[_usages]];
+      jwk.ext = key[_extractable];
+      return jwk;
+    }
+
+    switch (algorithmName) {
+      case "HMAC":
+      case "AES-CTR":
+      case "AES-CBC":
+      case "AES-GCM":
+      case "AES-KW":
+        if (format === "raw") {
+          return keyData.data.slice().buffer;
+        }
+        break;
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS":
+      case "RSA-OAEP":
+      case "ECDSA":
+      case "ECDH":
+      case "Ed25519":
+      case "X25519":
+        if (format === "pkcs8" || format === "spki") {
+          checkKeyType(key, format === "pkcs8" ? "private" : "public");
+          return keyData.data.slice().buffer;
+        }
+        if (format === "raw" && algorithmName.startsWith("EC")) {
+          checkKeyType(key, "public");
+          const { x, y } = core.opSync("op_crypto_export_jwk", {
+            algorithm: algorithmName,
+            key: keyData,
+          });
+          const point = new Uint8Array(1 + x.byteLength + y.byteLength);
+          point[0] = 0x04;
+          point.set(x, 1);
+          point.set(y, 1 + x.byteLength);
+          return point.buffer;
+        }
+        if (format === "raw") {
+          checkKeyType(key, "public");
+          const { x } = core.opSync("op_crypto_export_jwk", {
+            algorithm: algorithmName,
+            key: keyData,
+          });
+          return x.buffer;
+        }
+        break;
+    }
+
+    throw new DOMException(
+      `The '${format}' format is not supported for ${algorithmName}`,
+      "NotSupportedError",
+    );
+  }
+
+  function exportJwk(key, keyData) {
+    const algorithm = key[_algorithm];
+    const algorithmName = algorithm.name;
+    switch (algorithmName) {
+      case "HMAC":
+        return {
+          kty: "oct",
+          k: encodeBase64Url(keyData.data),
+          alg: jwkAlgs.HMAC[algorithm.hash.name],
+        };
+      case "AES-CTR":
+      case "AES-CBC":
+      case "AES-GCM":
+      case "AES-KW":
+        return {
+          kty: "oct",
+          k: encodeBase64Url(keyData.data),
+          alg: `A${algorithm.length}${aesJwkAlgSuffixes[algorithmName]}`,
+        };
+      case "RSASSA-PKCS1-v1_5":
+      case "RSA-PSS":
+      case "RSA-OAEP": {
+        const members = core.opSync("op_crypto_export_jwk", {
+          algorithm: algorithmName,
+          key: keyData,
+        });
+        return {
+          kty: "RSA",
+          alg: jwkAlgs[algorithmName][algorithm.hash.name],
+          ...encodeJwkMembers(members),
+        };
+      }
+      case "ECDSA":
+      case "ECDH": {
+        const members = core.opSync("op_crypto_export_jwk", {
+          algorithm: algorithmName,
+          key: keyData,
+        });
+        const jwk = {
+          kty: "EC",
+          crv: algorithm.namedCurve,
+          ...encodeJwkMembers(members),
+        };
+        if (algorithmName === "ECDSA") {
+          jwk.alg = jwkAlgs.ECDSA[algorithm.namedCurve];
+        }
+        return jwk;
+      }
+      case "Ed25519":
+      case "X25519": {
+        const members = core.opSync("op_crypto_export_jwk", {
+          algorithm: algorithmName,
+          key: keyData,
+        });
+        const jwk = {
+          kty: "OKP",
+          crv: algorithmName,
+          ...encodeJwkMembers(members),
+        };
+        if (algorithmName === "Ed25519") {
+          jwk.alg = "EdDSA";
+        }
+        return jwk;
+      }
+      default:
+        throw new DOMException(
+          `The 'jwk' format is not supported for ${algorithmName}`,
+          "NotSupportedError",
+        );
+    }
+  }
+
+  async function encrypt(normalizedAlgorithm, key, data, op = "encrypt") {
+    const keyData = KEY_STORE.get(key[_handle]);
+    const args = { key: keyData, algorithm: normalizedAlgorithm.name };
+    switch (normalizedAlgorithm.name) {
+      case "RSA-OAEP":
+        checkKeyType(key, op === "encrypt" ? "public" : "private");
+        args.hash = key[_algorithm].hash.name;
+        args.label = normalizedAlgorithm.label;
+        break;
+      case "AES-CBC":
+        if (normalizedAlgorithm.iv.byteLength !== 16) {
+          throw new DOMException(
+            "The iv must be 16 bytes long",
+            "OperationError",
+          );
+        }
+        args.iv = normalizedAlgorithm.iv;
+        break;
+      case "AES-CTR":
+        if (normalizedAlgorithm.counter.byteLength !== 16) {
+          throw new DOMException(
+            "The counter must be 16 bytes long",
+            "OperationError",
+          );
+        }
+        if (
+          normalizedAlgorithm.length < 1 || normalizedAlgorithm.length > 128
+        ) {
+          throw new DOMException(
+            "The counter length must be between 1 and 128 bits",
+            "OperationError",
+          );
+        }
+        args.counter = normalizedAlgorithm.counter;
+        args.length = normalizedAlgorithm.length;
+        break;
+      case "AES-GCM": {
+        const tagLength = normalizedAlgorithm.tagLength ?? 128;
+        if (![32, 64, 96, 104, 112, 120, 128].includes(tagLength)) {
+          throw new DOMException(
+            "The tag length is invalid",
+            "OperationError",
+          );
+        }
+        args.iv = normalizedAlgorithm.iv;
+        args.additionalData = normalizedAlgorithm.additionalData;
+        args.tagLength = tagLength;
+        break;
+      }
+    }
+    const result = await core.opAsync(`op_crypto_${op}_key`, args, data);
+    return result.buffer;
+  }
+
+  async function deriveBits(normalizedAlgorithm, baseKey, length) {
+    const keyData = KEY_STORE.get(baseKey[_handle]);
+    const args = { key: keyData, algorithm: normalizedAlgorithm.name, length };
+    switch (normalizedAlgorithm.name) {
+      case "ECDH":
+      case "X25519": {
+        const publicKey = normalizedAlgorithm.public;
+        checkKeyType(publicKey, "public");
+        if (
+          publicKey[_algorithm].name !== normalizedAlgorithm.name ||
+          publicKey[_algorithm].namedCurve !== baseKey[_algorithm].namedCurve
+        ) {
+          throw new DOMException(
+            "The public key does not match the base key",
+            "InvalidAccessError",
+          );
+        }
+        args.publicKey = KEY_STORE.get(publicKey[_handle]);
+        break;
+      }
+      case "HKDF":
+      case "PBKDF2":
+        if (length === null || length === 0 || length % 8 !== 0) {
+          throw new DOMException(
+            "The length must be a non-zero multiple of 8",
+            "OperationError",
+          );
+        }
+        if (
+          normalizedAlgorithm.name === "PBKDF2" &&
+          normalizedAlgorithm.iterations === 0
+        ) {
+          throw new DOMException(
+            "The iterations must not be zero",
+            "OperationError",
+          );
+        }
+        args.hash = normalizedAlgorithm.hash.name;
+        args.salt = normalizedAlgorithm.salt;
+        args.info = normalizedAlgorithm.info;
+        args.iterations = normalizedAlgorithm.iterations;
+        break;
+    }
+    const result = await core.opAsync("op_crypto_derive_bits", args);
+    return result.buffer;
+  }
+
+  function getKeyLength(normalizedAlgorithm) {
+    switch (normalizedAlgorithm.name) {
+      case "AES-CTR":
+      case "AES-CBC":
+      case "AES-GCM":
+      case "AES-KW":
+        if (![128, 192, 256].includes(normalizedAlgorithm.length)) {
+          throw new DOMException(
+            "The length of an AES key must be 128, 192 or 256 bits",
+            "OperationError",
+          );
+        }
+        return normalizedAlgorithm.length;
+      case "HMAC":
+        if (normalizedAlgorithm.length === 0) {
+          throw new DOMException(
+            "The length of an HMAC key must not be zero",
+            "TypeError",
+          );
+        }
+        return normalizedAlgorithm.length ??
+          getHashBlockSize(normalizedAlgorithm.hash.name);
+      case "HKDF":
+      case "PBKDF2":
+        return null;
+    }
+  }
+
+  /**
+   * Normalize the algorithm of `wrapKey()` and `unwrapKey()`, which falls back
+   * to the algorithms of `encrypt()` and `decrypt()`.
+   */
+  function normalizeWrapAlgorithm(algorithm, op, fallbackOp) {
+    try {
+      return normalizeAlgorithm(algorithm, op);
+    } catch (err) {
+      if (err instanceof DOMException && err.name === "NotSupportedError") {
+        return normalizeAlgorithm(algorithm, fallbackOp);
+      }
+      throw err;
+    }
+  }
+
   class SubtleCrypto {
     constructor() {
       webidl.illegalConstructor();
@@ -79,13 +1286,7 @@
         context: "Argument 2",
       });
 
-      if (ArrayBuffer.isView(data)) {
-        data = new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
-      } else {
-        data = new Uint8Array(data);
-      }
-
-      data = data.slice();
+      data = copyBuffer(data);
 
       algorithm = normalizeAlgorithm(algorithm, "digest");
 
@@ -97,8 +1298,443 @@
 
       return result.buffer;
     }
+
+    async encrypt(algorithm, key, data) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'encrypt' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 3, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+      data = webidl.converters.BufferSource(data, {
+        prefix,
+        context: "Argument 3",
+      });
+      data = copyBuffer(data);
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "encrypt");
+      checkKey(key, normalizedAlgorithm.name, "encrypt");
+      return await encrypt(normalizedAlgorithm, key, data, "encrypt");
+    }
+
+    async decrypt(algorithm, key, data) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'decrypt' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 3, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+      data = webidl.converters.BufferSource(data, {
+        prefix,
+        context: "Argument 3",
+      });
+      data = copyBuffer(data);
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "decrypt");
+      checkKey(key, normalizedAlgorithm.name, "decrypt");
+      return await encrypt(normalizedAlgorithm, key, data, "decrypt");
+    }
+
+    async sign(algorithm, key, data) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'sign' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 3, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+      data = webidl.converters.BufferSource(data, {
+        prefix,
+        context: "Argument 3",
+      });
+      data = copyBuffer(data);
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "sign");
+      checkKey(key, normalizedAlgorithm.name, "sign");
+      if (normalizedAlgorithm.name !== "HMAC") {
+        checkKeyType(key, "private");
+      }
+
+      const signature = await core.opAsync("op_crypto_sign_key", {
+        key: KEY_STORE.get(key[_handle]),
+        algorithm: normalizedAlgorithm.name,
+        hash: (normalizedAlgorithm.hash ?? key[_algorithm].hash)?.name,
+        saltLength: normalizedAlgorithm.saltLength,
+        namedCurve: key[_algorithm].namedCurve,
+      }, data);
+
+      return signature.buffer;
+    }
+
+    async verify(algorithm, key, signature, data) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'verify' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 4, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+      signature = webidl.converters.BufferSource(signature, {
+        prefix,
+        context: "Argument 3",
+      });
+      data = webidl.converters.BufferSource(data, {
+        prefix,
+        context: "Argument 4",
+      });
+      signature = copyBuffer(signature);
+      data = copyBuffer(data);
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "verify");
+      checkKey(key, normalizedAlgorithm.name, "verify");
+      if (normalizedAlgorithm.name !== "HMAC") {
+        checkKeyType(key, "public");
+      }
+
+      return await core.opAsync("op_crypto_verify_key", {
+        key: KEY_STORE.get(key[_handle]),
+        algorithm: normalizedAlgorithm.name,
+        hash: (normalizedAlgorithm.hash ?? key[_algorithm].hash)?.name,
+        saltLength: normalizedAlgorithm.saltLength,
+        namedCurve: key[_algorithm].namedCurve,
+        signature,
+      }, data);
+    }
+
+    async generateKey(algorithm, extractable, keyUsages) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'generateKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 3, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      extractable = webidl.converters["boolean"](extractable, {
+        prefix,
+        context: "Argument 2",
+      });
+      keyUsages = webidl.converters["sequence<KeyUsage>"](keyUsages, {
+        prefix,
+        context: "Argument 3",
+      });
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "generateKey");
+      const result = await generateKey(
+        normalizedAlgorithm,
+        extractable,
+        keyUsages,
+      );
+
+      if (result instanceof CryptoKey) {
+        checkUsagesNotEmpty(result, result[_usages]);
+      } else {
+        checkUsagesNotEmpty(result.privateKey, result.privateKey[_usages]);
+      }
+
+      return result;
+    }
+
+    async importKey(format, keyData, algorithm, extractable, keyUsages) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'importKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 5, { prefix });
+      format = webidl.converters.KeyFormat(format, {
+        prefix,
+        context: "Argument 1",
+      });
+      if (format === "jwk") {
+        keyData = webidl.converters.JsonWebKey(keyData, {
+          prefix,
+          context: "Argument 2",
+        });
+      } else {
+        keyData = webidl.converters.BufferSource(keyData, {
+          prefix,
+          context: "Argument 2",
+        });
+        keyData = copyBuffer(keyData);
+      }
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 3",
+      });
+      extractable = webidl.converters["boolean"](extractable, {
+        prefix,
+        context: "Argument 4",
+      });
+      keyUsages = webidl.converters["sequence<KeyUsage>"](keyUsages, {
+        prefix,
+        context: "Argument 5",
+      });
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "importKey");
+      const result = importKey(
+        format,
+        keyData,
+        normalizedAlgorithm,
+        extractable,
+        keyUsages,
+      );
+      checkUsagesNotEmpty(result, result[_usages]);
+      return result;
+    }
+
+    async exportKey(format, key) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'exportKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 2, { prefix });
+      format = webidl.converters.KeyFormat(format, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+
+      if (!key[_extractable]) {
+        throw new DOMException(
+          "The key is not extractable",
+          "InvalidAccessError",
+        );
+      }
+
+      return exportKey(format, key);
+    }
+
+    async deriveBits(algorithm, baseKey, length) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'deriveBits' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 3, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      baseKey = webidl.converters.CryptoKey(baseKey, {
+        prefix,
+        context: "Argument 2",
+      });
+      if (length !== null) {
+        length = webidl.converters["unsigned long"](length, {
+          prefix,
+          context: "Argument 3",
+        });
+      }
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "deriveBits");
+      checkKey(baseKey, normalizedAlgorithm.name, "deriveBits");
+      return await deriveBits(normalizedAlgorithm, baseKey, length);
+    }
+
+    async deriveKey(
+      algorithm,
+      baseKey,
+      derivedKeyType,
+      extractable,
+      keyUsages,
+    ) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'deriveKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 5, { prefix });
+      algorithm = webidl.converters.AlgorithmIdentifier(algorithm, {
+        prefix,
+        context: "Argument 1",
+      });
+      baseKey = webidl.converters.CryptoKey(baseKey, {
+        prefix,
+        context: "Argument 2",
+      });
+      derivedKeyType = webidl.converters.AlgorithmIdentifier(derivedKeyType, {
+        prefix,
+        context: "Argument 3",
+      });
+      extractable = webidl.converters["boolean"](extractable, {
+        prefix,
+        context: "Argument 4",
+      });
+      keyUsages = webidl.converters["sequence<KeyUsage>"](keyUsages, {
+        prefix,
+        context: "Argument 5",
+      });
+
+      const normalizedAlgorithm = normalizeAlgorithm(algorithm, "deriveBits");
+      const normalizedDerivedKeyAlgorithmImport = normalizeAlgorithm(
+        derivedKeyType,
+        "importKey",
+      );
+      const normalizedDerivedKeyAlgorithmLength = normalizeAlgorithm(
+        derivedKeyType,
+        "get key length",
+      );
+      checkKey(baseKey, normalizedAlgorithm.name, "deriveKey");
+
+      const length = getKeyLength(normalizedDerivedKeyAlgorithmLength);
+      const secret = await deriveBits(normalizedAlgorithm, baseKey, length);
+      const result = importKey(
+        "raw",
+        new Uint8Array(secret),
+        normalizedDerivedKeyAlgorithmImport,
+        extractable,
+        keyUsages,
+      );
+      checkUsagesNotEmpty(result, keyUsages);
+      return result;
+    }
+
+    async wrapKey(format, key, wrappingKey, wrapAlgorithm) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'wrapKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 4, { prefix });
+      format = webidl.converters.KeyFormat(format, {
+        prefix,
+        context: "Argument 1",
+      });
+      key = webidl.converters.CryptoKey(key, {
+        prefix,
+        context: "Argument 2",
+      });
+      wrappingKey = webidl.converters.CryptoKey(wrappingKey, {
+        prefix,
+        context: "Argument 3",
+      });
+      wrapAlgorithm = webidl.converters.AlgorithmIdentifier(wrapAlgorithm, {
+        prefix,
+        context: "Argument 4",
+      });
+
+      const normalizedAlgorithm = normalizeWrapAlgorithm(
+        wrapAlgorithm,
+        "wrapKey",
+        "encrypt",
+      );
+      checkKey(wrappingKey, normalizedAlgorithm.name, "wrapKey");
+      if (!key[_extractable]) {
+        throw new DOMException(
+          "The key is not extractable",
+          "InvalidAccessError",
+        );
+      }
+
+      const exportedKey = exportKey(format, key);
+      const bytes = format === "jwk"
+        ? core.encode(JSON.stringify(exportedKey))
+        : new Uint8Array(exportedKey);
+      return await encrypt(normalizedAlgorithm, wrappingKey, bytes, "encrypt");
+    }
+
+    async unwrapKey(
+      format,
+      wrappedKey,
+      unwrappingKey,
+      unwrapAlgorithm,
+      unwrappedKeyAlgorithm,
+      extractable,
+      keyUsages,
+    ) {
+      webidl.assertBranded(this, SubtleCrypto);
+      const prefix = "Failed to execute 'unwrapKey' on 'SubtleCrypto'";
+      webidl.requiredArguments(arguments.length, 7, { prefix });
+      format = webidl.converters.KeyFormat(format, {
+        prefix,
+        context: "Argument 1",
+      });
+      wrappedKey = webidl.converters.BufferSource(wrappedKey, {
+        prefix,
+        context: "Argument 2",
+      });
+      unwrappingKey = webidl.converters.CryptoKey(unwrappingKey, {
+        prefix,
+        context: "Argument 3",
+      });
+      unwrapAlgorithm = webidl.converters.AlgorithmIdentifier(
+        unwrapAlgorithm,
+        {
+          prefix,
+          context: "Argument 4",
+        },
+      );
+      unwrappedKeyAlgorithm = webidl.converters.AlgorithmIdentifier(
+        unwrappedKeyAlgorithm,
+        {
+          prefix,
+          context: "Argument 5",
+        },
+      );
+      extractable = webidl.converters["boolean"](extractable, {
+        prefix,
+        context: "Argument 6",
+      });
+      keyUsages = webidl.converters["sequence<KeyUsage>"](keyUsages, {
+        prefix,
+        context: "Argument 7",
+      });
+      wrappedKey = copyBuffer(wrappedKey);
+
+      const normalizedAlgorithm = normalizeWrapAlgorithm(
+        unwrapAlgorithm,
+        "unwrapKey",
+        "decrypt",
+      );
+      const normalizedKeyAlgorithm = normalizeAlgorithm(
+        unwrappedKeyAlgorithm,
+        "importKey",
+      );
+      checkKey(unwrappingKey, normalizedAlgorithm.name, "unwrapKey");
+
+      const key = new Uint8Array(
+        await encrypt(
+          normalizedAlgorithm,
+          unwrappingKey,
+          wrappedKey,
+          "decrypt",
+        ),
+      );
+      let keyData = key;
+      if (format === "jwk") {
+        try {
+          keyData = webidl.converters.JsonWebKey(
+            JSON.parse(core.decode(key)),
+            { prefix, context: "The unwrapped key" },
+          );
+        } catch {
+          throw new DOMException(
+            "The unwrapped key is not a valid JSON Web Key",
+            "DataError",
+          );
+        }
+      }
+      const result = importKey(
+        format,
+        keyData,
+        normalizedKeyAlgorithm,
+        extractable,
+        keyUsages,
+      );
+      checkUsagesNotEmpty(result, result[_usages]);
+      return result;
+    }
   }
 
+  webidl.configurePrototype(SubtleCrypto);
+
   const subtle = webidl.createBranded(SubtleCrypto);
 
   class Crypto {
@@ -164,5 +1800,6 @@
     SubtleCrypto,
     crypto: webidl.createBranded(Crypto),
     Crypto,
+    CryptoKey,
   };
 })(this);
@@ -0,0 +1,102 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! The AES key wrap algorithm of RFC 3394, which is the algorithm of the
+//! `AES-KW` key wrapping of the Web Cryptography API.
+
+use aes::cipher::consts::U16;
+use aes::cipher::BlockCipher;
+use aes::cipher::BlockDecrypt;
+use aes::cipher::BlockEncrypt;
+use aes::Block;
+
+/// The default initial value of RFC 3394, section 2.2.3.1.
+const DEFAULT_IV: [u8; 8] = [0xa6; 8];
+
+fn xor_counter(a: &mut [u8], t: u64) {
+  for (a, t) in a.iter_mut().zip(t.to_be_bytes().iter()) {
+    *a ^= t;
+  }
+}
+
+/// Wrap the key data, of which the length is required to be a multiple of 8
+/// bytes of at least 16 bytes.
+pub fn wrap<C>(cipher: &C, data: &[u8]) -> Vec<u8>
+where
+  C: BlockCipher<BlockSize = U16> + BlockEncrypt,
+{
+  let n = data.len() / 8;
+  let mut a = DEFAULT_IV;
+  let mut r: Vec<u8> = data.to_vec();
+  let mut block = Block::default();
+  for j in 0..6 {
+    for i in 0..n {
+      block[..8].copy_from_slice(&a);
+      block[8..].copy_from_slice(&r[i * 8..i * 8 + 8]);
+      cipher.encrypt_block(&mut block);
+      a.copy_from_slice(&block[..8]);
+      xor_counter(&mut a, (n * j + i + 1) as u64);
+      r[i * 8..i * 8 + 8].copy_from_slice(&block[8..]);
+    }
+  }
+  let mut out = a.to_vec();
+  out.extend_from_slice(&r);
+  out
+}
+
+/// Unwrap the wrapped key data, returning `None` if the integrity check of the
+/// unwrapped key data fails.
+pub fn unwrap<C>(cipher: &C, data: &[u8]) -> Option<Vec<u8>>
+where
+  C: BlockCipher<BlockSize = U16> + BlockDecrypt,
+{
+  let n = data.len() / 8 - 1;
+  let mut a = [0; 8];
+  a.copy_from_slice(&data[..8]);
+  let mut r: Vec<u8> = data[8..].to_vec();
+  let mut block = Block::default();
+  for j in (0..6).rev() {
+    for i in (0..n).rev() {
+      xor_counter(&mut a, (n * j + i + 1) as u64);
+      block[..8].copy_from_slice(&a);
+      block[8..].copy_from_slice(&r[i * 8..i * 8 + 8]);
+      cipher.decrypt_block(&mut block);
+      a.copy_from_slice(&block[..8]);
+      r[i * 8..i * 8 + 8].copy_from_slice(&block[8..]);
+    }
+  }
+  // the integrity check is done in constant time, so that its timing doesn't
+  // tell how much of the initial value was recovered
+  if ring::constant_time::verify_slices_are_equal(&a, &DEFAULT_IV).is_ok() {
+    Some(r)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use aes::cipher::NewBlockCipher;
+  use aes::Aes128;
+
+  #[test]
+  fn test_wrap_unwrap() {
+    // RFC 3394, section 4.1
+    let kek: Vec<u8> = (0..16).collect();
+    let key = [
+      0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb,
+      0xcc, 0xdd, 0xee, 0xff,
+    ];
+    let expected = [
+      0x1f, 0xa6, 0x8b, 0x0a, 0x81, 0x12, 0xb4, 0x47, 0xae, 0xf3, 0x4b, 0xd8,
+      0xfb, 0x5a, 0x7b, 0x82, 0x9d, 0x3e, 0x86, 0x23, 0x71, 0xd2, 0xcf, 0xe5,
+    ];
+    let cipher = Aes128::new_from_slice(&kek).unwrap();
+    let wrapped = wrap(&cipher, &key);
+    assert_eq!(wrapped, expected);
+    assert_eq!(unwrap(&cipher, &wrapped).unwrap(), key);
+    let mut tampered = wrapped;
+    tampered[0] ^= 1;
+    assert!(unwrap(&cipher, &tampered).is_none());
+  }
+}
@@ -0,0 +1,221 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Encoding and decoding of the Ed25519 and X25519 keys of RFC 8410.  Private
+//! keys are held as PKCS#8 documents of the 32 byte private key, from which
+//! the public key is derived, and public keys as SubjectPublicKeyInfo
+//! documents.
+
+use crate::data_error;
+use crate::der::write;
+use crate::der::DerReader;
+use crate::der::TAG_BIT_STRING;
+use crate::der::TAG_INTEGER;
+use crate::der::TAG_OCTET_STRING;
+use crate::der::TAG_OID;
+use crate::der::TAG_SEQUENCE;
+use crate::key::Algorithm;
+use crate::operation_error;
+use deno_core::error::AnyError;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
+use std::convert::TryInto;
+
+/// id-X25519 (1.3.101.110)
+const OID_X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+/// id-Ed25519 (1.3.101.112)
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+
+/// The length of both the private and the public keys in bytes.
+pub const KEY_LENGTH: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Curve25519 {
+  Ed25519,
+  X25519,
+}
+
+impl Curve25519 {
+  pub fn from_algorithm(algorithm: Algorithm) -> Option<Self> {
+    match algorithm {
+      Algorithm::Ed25519 => Some(Curve25519::Ed25519),
+      Algorithm::X25519 => Some(Curve25519::X25519),
+      _ => None,
+    }
+  }
+
+  fn oid(&self) -> &'static [u8] {
+    match self {
+      Curve25519::Ed25519 => OID_ED25519,
+      Curve25519::X25519 => OID_X25519,
+    }
+  }
+}
+
+fn key_bytes(key: &[u8]) -> Result<[u8; KEY_LENGTH], AnyError> {
+  key
+    .try_into()
+    .map_err(|_| data_error("The key has an invalid length."))
+}
+
+/// Derive the public key of a private key.
+pub fn public_key(
+  curve: Curve25519,
+  private_key: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let private_key = key_bytes(private_key)?;
+  match curve {
+    Curve25519::Ed25519 => {
+      let key_pair = Ed25519KeyPair::from_seed_unchecked(&private_key)
+        .map_err(|_| data_error("The key is not a valid Ed25519 key."))?;
+      Ok(key_pair.public_key().as_ref().to_vec())
+    }
+    Curve25519::X25519 => {
+      let secret = x25519_dalek::StaticSecret::from(private_key);
+      Ok(x25519_dalek::PublicKey::from(&secret).as_bytes().to_vec())
+    }
+  }
+}
+
+/// Compute the shared secret of an X25519 private and public key, which fails
+/// for public keys of small order, for which the shared secret is all zeros.
+pub fn x25519_shared_secret(
+  private_key: &[u8],
+  public_key: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let secret = x25519_dalek::StaticSecret::from(key_bytes(private_key)?);
+  let public_key = x25519_dalek::PublicKey::from(key_bytes(public_key)?);
+  let shared_secret = secret.diffie_hellman(&public_key);
+  if shared_secret.as_bytes().iter().all(|b| *b == 0) {
+    return Err(operation_error("The public key is of small order."));
+  }
+  Ok(shared_secret.as_bytes().to_vec())
+}
+
+fn algorithm_identifier(curve: Curve25519) -> Vec<u8> {
+  let mut contents = Vec::new();
+  write(&mut contents, TAG_OID, curve.oid());
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  out
+}
+
+fn read_algorithm_identifier(
+  reader: &mut DerReader,
+  curve: Curve25519,
+) -> Result<(), AnyError> {
+  let mut algorithm = DerReader::new(reader.read(TAG_SEQUENCE)?);
+  if algorithm.read(TAG_OID)? != curve.oid() || !algorithm.is_empty() {
+    return Err(data_error("The algorithm of the key does not match."));
+  }
+  Ok(())
+}
+
+/// Encode the SubjectPublicKeyInfo of a public key.
+pub fn encode_spki(
+  curve: Curve25519,
+  public_key: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let public_key = key_bytes(public_key)?;
+  let mut bit_string = vec![0];
+  bit_string.extend_from_slice(&public_key);
+  let mut contents = algorithm_identifier(curve);
+  write(&mut contents, TAG_BIT_STRING, &bit_string);
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  Ok(out)
+}
+
+/// Decode a SubjectPublicKeyInfo, returning the public key.
+pub fn decode_spki(
+  curve: Curve25519,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let mut reader = DerReader::new(DerReader::new(data).read(TAG_SEQUENCE)?);
+  read_algorithm_identifier(&mut reader, curve)?;
+  match reader.read(TAG_BIT_STRING)?.split_first() {
+    Some((0, public_key)) => Ok(key_bytes(public_key)?.to_vec()),
+    _ => Err(data_error("The public key is not a valid bit string.")),
+  }
+}
+
+/// Encode the PKCS#8 document of a private key.
+pub fn encode_pkcs8(
+  curve: Curve25519,
+  private_key: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let private_key = key_bytes(private_key)?;
+  let mut curve_private_key = Vec::new();
+  write(&mut curve_private_key, TAG_OCTET_STRING, &private_key);
+  let mut contents = Vec::new();
+  write(&mut contents, TAG_INTEGER, &[0]);
+  contents.extend_from_slice(&algorithm_identifier(curve));
+  write(&mut contents, TAG_OCTET_STRING, &curve_private_key);
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  Ok(out)
+}
+
+/// Decode a PKCS#8 document, returning the private key.  The attributes and
+/// public key of version 2 documents are ignored, as the public key is
+/// always derived from the private key.
+pub fn decode_pkcs8(
+  curve: Curve25519,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let mut reader = DerReader::new(DerReader::new(data).read(TAG_SEQUENCE)?);
+  reader.read(TAG_INTEGER)?;
+  read_algorithm_identifier(&mut reader, curve)?;
+  let private_key =
+    DerReader::new(reader.read(TAG_OCTET_STRING)?).read(TAG_OCTET_STRING)?;
+  Ok(key_bytes(private_key)?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// The private key of RFC 8032, section 7.1, test 1, as encoded by OpenSSL.
+  const PKCS8: &str = "302e020100300506032b6570042204209d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+  const SPKI: &str = "302a300506032b6570032100d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+  fn hex_decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_ed25519_pkcs8_spki() {
+    let pkcs8 = hex_decode(PKCS8);
+    let spki = hex_decode(SPKI);
+    let private_key = decode_pkcs8(Curve25519::Ed25519, &pkcs8).unwrap();
+    assert_eq!(
+      encode_pkcs8(Curve25519::Ed25519, &private_key).unwrap(),
+      pkcs8
+    );
+    let public_key = public_key(Curve25519::Ed25519, &private_key).unwrap();
+    assert_eq!(decode_spki(Curve25519::Ed25519, &spki).unwrap(), public_key);
+    assert_eq!(encode_spki(Curve25519::Ed25519, &public_key).unwrap(), spki);
+    assert!(decode_pkcs8(Curve25519::X25519, &pkcs8).is_err());
+    assert!(decode_spki(Curve25519::X25519, &spki).is_err());
+  }
+
+  #[test]
+  fn test_x25519_shared_secret() {
+    // RFC 7748, section 6.1
+    let alice = hex_decode(
+      "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+    );
+    let bob = hex_decode(
+      "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+    );
+    assert_eq!(
+      x25519_shared_secret(&alice, &bob).unwrap(),
+      hex_decode(
+        "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+      )
+    );
+    assert!(x25519_shared_secret(&alice, &[0; KEY_LENGTH]).is_err());
+  }
+}
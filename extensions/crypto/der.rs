@@ -0,0 +1,66 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! A minimal reader and writer of the DER encoding, which is enough for the
+//! key documents of the elliptic curve and Curve25519 algorithms.
+
+use crate::data_error;
+use deno_core::error::AnyError;
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_CONTEXT_0: u8 = 0xa0;
+pub const TAG_CONTEXT_1: u8 = 0xa1;
+
+pub struct DerReader<'a> {
+  input: &'a [u8],
+}
+
+impl<'a> DerReader<'a> {
+  pub fn new(input: &'a [u8]) -> Self {
+    Self { input }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.input.is_empty()
+  }
+
+  pub fn peek_tag(&self) -> Option<u8> {
+    self.input.first().copied()
+  }
+
+  /// Read an element of the tag, returning its contents.
+  pub fn read(&mut self, tag: u8) -> Result<&'a [u8], AnyError> {
+    let invalid = || data_error("The key is not a valid DER encoded key.");
+    if self.input.len() < 2 || self.input[0] != tag {
+      return Err(invalid());
+    }
+    let (len, header_len) = match self.input[1] {
+      len if len < 0x80 => (len as usize, 2),
+      0x81 if self.input.len() > 2 => (self.input[2] as usize, 3),
+      0x82 if self.input.len() > 3 => {
+        (((self.input[2] as usize) << 8) | self.input[3] as usize, 4)
+      }
+      _ => return Err(invalid()),
+    };
+    let end = header_len + len;
+    if self.input.len() < end {
+      return Err(invalid());
+    }
+    let contents = &self.input[header_len..end];
+    self.input = &self.input[end..];
+    Ok(contents)
+  }
+}
+
+pub fn write(out: &mut Vec<u8>, tag: u8, contents: &[u8]) {
+  out.push(tag);
+  match contents.len() {
+    len if len < 0x80 => out.push(len as u8),
+    len if len <= 0xff => out.extend_from_slice(&[0x81, len as u8]),
+    len => out.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+  }
+  out.extend_from_slice(contents);
+}
@@ -0,0 +1,236 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! Encoding and decoding of elliptic curve keys.  Private keys are held as
+//! PKCS#8 documents which include the public key, as required by `ring`, and
+//! public keys as SubjectPublicKeyInfo documents, both in the canonical DER
+//! produced here, so that keys imported from other encoders are normalized.
+
+use crate::data_error;
+use crate::der::write;
+use crate::der::DerReader;
+use crate::der::TAG_BIT_STRING;
+use crate::der::TAG_CONTEXT_0;
+use crate::der::TAG_CONTEXT_1;
+use crate::der::TAG_INTEGER;
+use crate::der::TAG_OCTET_STRING;
+use crate::der::TAG_OID;
+use crate::der::TAG_SEQUENCE;
+use crate::key::CryptoNamedCurve;
+use deno_core::error::AnyError;
+
+/// id-ecPublicKey (1.2.840.10045.2.1)
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+/// secp256r1 (1.2.840.10045.3.1.7)
+const OID_P256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+/// secp384r1 (1.3.132.0.34)
+const OID_P384: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+
+impl CryptoNamedCurve {
+  /// The size of a field element of the curve in bytes.
+  pub fn field_size(&self) -> usize {
+    match self {
+      CryptoNamedCurve::P256 => 32,
+      CryptoNamedCurve::P384 => 48,
+    }
+  }
+
+  fn oid(&self) -> &'static [u8] {
+    match self {
+      CryptoNamedCurve::P256 => OID_P256,
+      CryptoNamedCurve::P384 => OID_P384,
+    }
+  }
+
+  fn from_oid(oid: &[u8]) -> Option<Self> {
+    if oid == OID_P256 {
+      Some(CryptoNamedCurve::P256)
+    } else if oid == OID_P384 {
+      Some(CryptoNamedCurve::P384)
+    } else {
+      None
+    }
+  }
+}
+
+fn algorithm_identifier(curve: CryptoNamedCurve) -> Vec<u8> {
+  let mut contents = Vec::new();
+  write(&mut contents, TAG_OID, OID_EC_PUBLIC_KEY);
+  write(&mut contents, TAG_OID, curve.oid());
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  out
+}
+
+fn read_algorithm_identifier(
+  reader: &mut DerReader,
+) -> Result<CryptoNamedCurve, AnyError> {
+  let mut algorithm = DerReader::new(reader.read(TAG_SEQUENCE)?);
+  if algorithm.read(TAG_OID)? != OID_EC_PUBLIC_KEY {
+    return Err(data_error("The key is not an elliptic curve key."));
+  }
+  CryptoNamedCurve::from_oid(algorithm.read(TAG_OID)?)
+    .ok_or_else(|| data_error("The named curve of the key is not supported."))
+}
+
+/// Validate an uncompressed point of the curve.
+pub fn validate_point(
+  curve: CryptoNamedCurve,
+  point: &[u8],
+) -> Result<(), AnyError> {
+  if point.len() != 1 + 2 * curve.field_size() || point[0] != 0x04 {
+    return Err(data_error(
+      "The public key is not a valid uncompressed point.",
+    ));
+  }
+  if curve == CryptoNamedCurve::P256 {
+    p256::PublicKey::from_sec1_bytes(point)
+      .map_err(|_| data_error("The public key is not a point on the curve."))?;
+  }
+  Ok(())
+}
+
+/// Encode the SubjectPublicKeyInfo of an uncompressed point.
+pub fn encode_spki(
+  curve: CryptoNamedCurve,
+  point: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  validate_point(curve, point)?;
+  let mut bit_string = vec![0];
+  bit_string.extend_from_slice(point);
+  let mut contents = algorithm_identifier(curve);
+  write(&mut contents, TAG_BIT_STRING, &bit_string);
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  Ok(out)
+}
+
+/// Decode a SubjectPublicKeyInfo, returning the curve and uncompressed point.
+pub fn decode_spki(
+  data: &[u8],
+) -> Result<(CryptoNamedCurve, Vec<u8>), AnyError> {
+  let mut reader = DerReader::new(DerReader::new(data).read(TAG_SEQUENCE)?);
+  let curve = read_algorithm_identifier(&mut reader)?;
+  let bit_string = reader.read(TAG_BIT_STRING)?;
+  match bit_string.split_first() {
+    Some((0, point)) => {
+      validate_point(curve, point)?;
+      Ok((curve, point.to_vec()))
+    }
+    _ => Err(data_error("The public key is not a valid bit string.")),
+  }
+}
+
+/// Encode the PKCS#8 document of a private scalar and its public point.
+pub fn encode_pkcs8(
+  curve: CryptoNamedCurve,
+  d: &[u8],
+  point: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  if d.len() != curve.field_size() {
+    return Err(data_error("The private key has an invalid length."));
+  }
+  validate_point(curve, point)?;
+  let mut bit_string = vec![0];
+  bit_string.extend_from_slice(point);
+  let mut public_key = Vec::new();
+  write(&mut public_key, TAG_BIT_STRING, &bit_string);
+  let mut ec_private_key_contents = Vec::new();
+  write(&mut ec_private_key_contents, TAG_INTEGER, &[1]);
+  write(&mut ec_private_key_contents, TAG_OCTET_STRING, d);
+  write(&mut ec_private_key_contents, TAG_CONTEXT_1, &public_key);
+  let mut ec_private_key = Vec::new();
+  write(&mut ec_private_key, TAG_SEQUENCE, &ec_private_key_contents);
+
+  let mut contents = Vec::new();
+  write(&mut contents, TAG_INTEGER, &[0]);
+  contents.extend_from_slice(&algorithm_identifier(curve));
+  write(&mut contents, TAG_OCTET_STRING, &ec_private_key);
+  let mut out = Vec::new();
+  write(&mut out, TAG_SEQUENCE, &contents);
+  Ok(out)
+}
+
+/// Decode a PKCS#8 document, returning the curve, the private scalar and the
+/// uncompressed public point, if the document includes it.
+pub fn decode_pkcs8(
+  data: &[u8],
+) -> Result<(CryptoNamedCurve, Vec<u8>, Option<Vec<u8>>), AnyError> {
+  let mut reader = DerReader::new(DerReader::new(data).read(TAG_SEQUENCE)?);
+  reader.read(TAG_INTEGER)?;
+  let curve = read_algorithm_identifier(&mut reader)?;
+  let mut ec_private_key = DerReader::new(
+    DerReader::new(reader.read(TAG_OCTET_STRING)?).read(TAG_SEQUENCE)?,
+  );
+  ec_private_key.read(TAG_INTEGER)?;
+  let scalar = ec_private_key.read(TAG_OCTET_STRING)?;
+  let size = curve.field_size();
+  if scalar.len() > size {
+    return Err(data_error("The private key has an invalid length."));
+  }
+  // scalars with leading zeros may be encoded without them
+  let mut d = vec![0; size - scalar.len()];
+  d.extend_from_slice(scalar);
+  if ec_private_key.peek_tag() == Some(TAG_CONTEXT_0) {
+    let parameters =
+      DerReader::new(ec_private_key.read(TAG_CONTEXT_0)?).read(TAG_OID)?;
+    if CryptoNamedCurve::from_oid(parameters) != Some(curve) {
+      return Err(data_error("The named curves of the key do not match."));
+    }
+  }
+  let mut maybe_point = None;
+  if !ec_private_key.is_empty() {
+    let bit_string = DerReader::new(ec_private_key.read(TAG_CONTEXT_1)?)
+      .read(TAG_BIT_STRING)?;
+    match bit_string.split_first() {
+      Some((0, point)) => {
+        validate_point(curve, point)?;
+        maybe_point = Some(point.to_vec());
+      }
+      _ => return Err(data_error("The public key is not a valid bit string.")),
+    }
+  }
+  Ok((curve, d, maybe_point))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A P-256 key pair, as encoded by OpenSSL.
+  const PKCS8: &str = "308187020100301306072a8648ce3d020106082a8648ce3d030107046d306b0201010420071afc90ffdb75bb74575ef9d5c7d4f0252800cf1a73d9e9b1d066e2c2856164a14403420004a2e730edc00f3983f58f04e1294d8a9b4a0228645b37f304c3c490c0fc92eb9fb2eb7c1592d9dd051c237b58edad062f0d27f3269d08b571965e85431c0bcc8c";
+  const SPKI: &str = "3059301306072a8648ce3d020106082a8648ce3d03010703420004a2e730edc00f3983f58f04e1294d8a9b4a0228645b37f304c3c490c0fc92eb9fb2eb7c1592d9dd051c237b58edad062f0d27f3269d08b571965e85431c0bcc8c";
+
+  fn hex_decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_decode_pkcs8() {
+    let pkcs8 = hex_decode(PKCS8);
+    let (curve, d, maybe_point) = decode_pkcs8(&pkcs8).unwrap();
+    assert_eq!(curve, CryptoNamedCurve::P256);
+    assert_eq!(d.len(), 32);
+    assert_eq!(maybe_point.as_ref().map(|p| p.len()), Some(65));
+    assert_eq!(
+      encode_pkcs8(curve, &d, &maybe_point.unwrap()).unwrap(),
+      pkcs8
+    );
+  }
+
+  #[test]
+  fn test_decode_spki() {
+    let spki = hex_decode(SPKI);
+    let (curve, point) = decode_spki(&spki).unwrap();
+    assert_eq!(curve, CryptoNamedCurve::P256);
+    assert_eq!(encode_spki(curve, &point).unwrap(), spki);
+  }
+
+  #[test]
+  fn test_decode_spki_invalid() {
+    assert!(decode_spki(&[0x30, 0x03, 0x02, 0x01, 0x00]).is_err());
+    assert!(decode_spki(&[]).is_err());
+  }
+}
@@ -0,0 +1,189 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::ZeroCopyBuf;
+use ring::digest;
+use ring::hkdf;
+use ring::hmac;
+use ring::pbkdf2;
+use ring::signature::EcdsaSigningAlgorithm;
+use ring::signature::EcdsaVerificationAlgorithm;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum CryptoHash {
+  #[serde(rename = "SHA-1")]
+  Sha1,
+  #[serde(rename = "SHA-256")]
+  Sha256,
+  #[serde(rename = "SHA-384")]
+  Sha384,
+  #[serde(rename = "SHA-512")]
+  Sha512,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum CryptoNamedCurve {
+  #[serde(rename = "P-256")]
+  P256,
+  #[serde(rename = "P-384")]
+  P384,
+}
+
+impl From<CryptoHash> for &'static digest::Algorithm {
+  fn from(hash: CryptoHash) -> &'static digest::Algorithm {
+    match hash {
+      CryptoHash::Sha1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+      CryptoHash::Sha256 => &digest::SHA256,
+      CryptoHash::Sha384 => &digest::SHA384,
+      CryptoHash::Sha512 => &digest::SHA512,
+    }
+  }
+}
+
+impl From<CryptoHash> for hmac::Algorithm {
+  fn from(hash: CryptoHash) -> hmac::Algorithm {
+    match hash {
+      CryptoHash::Sha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+      CryptoHash::Sha256 => hmac::HMAC_SHA256,
+      CryptoHash::Sha384 => hmac::HMAC_SHA384,
+      CryptoHash::Sha512 => hmac::HMAC_SHA512,
+    }
+  }
+}
+
+impl From<CryptoHash> for hkdf::Algorithm {
+  fn from(hash: CryptoHash) -> hkdf::Algorithm {
+    match hash {
+      CryptoHash::Sha1 => hkdf::HKDF_SHA1_FOR_LEGACY_USE_ONLY,
+      CryptoHash::Sha256 => hkdf::HKDF_SHA256,
+      CryptoHash::Sha384 => hkdf::HKDF_SHA384,
+      CryptoHash::Sha512 => hkdf::HKDF_SHA512,
+    }
+  }
+}
+
+impl From<CryptoHash> for pbkdf2::Algorithm {
+  fn from(hash: CryptoHash) -> pbkdf2::Algorithm {
+    match hash {
+      CryptoHash::Sha1 => pbkdf2::PBKDF2_HMAC_SHA1,
+      CryptoHash::Sha256 => pbkdf2::PBKDF2_HMAC_SHA256,
+      CryptoHash::Sha384 => pbkdf2::PBKDF2_HMAC_SHA384,
+      CryptoHash::Sha512 => pbkdf2::PBKDF2_HMAC_SHA512,
+    }
+  }
+}
+
+impl CryptoNamedCurve {
+  /// The ECDSA signing algorithm of the curve, where the curve is only
+  /// supported with the hash of the matching size.
+  pub fn signing_algorithm(
+    &self,
+    hash: CryptoHash,
+  ) -> Option<&'static EcdsaSigningAlgorithm> {
+    match (self, hash) {
+      (CryptoNamedCurve::P256, CryptoHash::Sha256) => {
+        Some(&ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING)
+      }
+      (CryptoNamedCurve::P384, CryptoHash::Sha384) => {
+        Some(&ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING)
+      }
+      _ => None,
+    }
+  }
+
+  pub fn verification_algorithm(
+    &self,
+    hash: CryptoHash,
+  ) -> Option<&'static EcdsaVerificationAlgorithm> {
+    match (self, hash) {
+      (CryptoNamedCurve::P256, CryptoHash::Sha256) => {
+        Some(&ring::signature::ECDSA_P256_SHA256_FIXED)
+      }
+      (CryptoNamedCurve::P384, CryptoHash::Sha384) => {
+        Some(&ring::signature::ECDSA_P384_SHA384_FIXED)
+      }
+      _ => None,
+    }
+  }
+
+  /// The algorithm used to validate a key of the curve, which is independent
+  /// of the hash the key is used with.
+  pub fn key_algorithm(&self) -> &'static EcdsaSigningAlgorithm {
+    match self {
+      CryptoNamedCurve::P256 => {
+        &ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING
+      }
+      CryptoNamedCurve::P384 => {
+        &ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING
+      }
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum Algorithm {
+  #[serde(rename = "RSASSA-PKCS1-v1_5")]
+  RsassaPkcs1v15,
+  #[serde(rename = "RSA-PSS")]
+  RsaPss,
+  #[serde(rename = "RSA-OAEP")]
+  RsaOaep,
+  #[serde(rename = "ECDSA")]
+  Ecdsa,
+  #[serde(rename = "ECDH")]
+  Ecdh,
+  #[serde(rename = "Ed25519")]
+  Ed25519,
+  #[serde(rename = "X25519")]
+  X25519,
+  #[serde(rename = "AES-CTR")]
+  AesCtr,
+  #[serde(rename = "AES-CBC")]
+  AesCbc,
+  #[serde(rename = "AES-GCM")]
+  AesGcm,
+  #[serde(rename = "AES-KW")]
+  AesKw,
+  #[serde(rename = "HMAC")]
+  Hmac,
+  #[serde(rename = "HKDF")]
+  Hkdf,
+  #[serde(rename = "PBKDF2")]
+  Pbkdf2,
+}
+
+impl Algorithm {
+  pub fn is_rsa(&self) -> bool {
+    matches!(
+      self,
+      Algorithm::RsassaPkcs1v15 | Algorithm::RsaPss | Algorithm::RsaOaep
+    )
+  }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+  Secret,
+  Private,
+  Public,
+}
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyFormat {
+  Raw,
+  Pkcs8,
+  Spki,
+}
+
+/// The material of a `CryptoKey`, as it is held by the key store in JavaScript.
+/// Secret keys are raw bytes, private keys are PKCS#8 and public keys are
+/// SubjectPublicKeyInfo documents in DER.
+#[derive(Deserialize)]
+pub struct KeyData {
+  #[serde(rename = "type")]
+  pub kind: KeyType,
+  pub data: ZeroCopyBuf,
+}
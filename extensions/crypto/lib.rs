@@ -1,6 +1,13 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+use block_modes::block_padding::Pkcs7;
+use block_modes::BlockMode;
+use block_modes::Cbc;
+use ctr::cipher::NewCipher;
+use ctr::cipher::StreamCipher;
+use deno_core::error::custom_error;
 use deno_core::error::null_opbuf;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::include_js_files;
 use deno_core::op_async;
@@ -12,13 +19,55 @@ use rand::rngs::StdRng;
 use rand::thread_rng;
 use rand::Rng;
 use rand::SeedableRng;
+use ring::aead;
 use ring::digest;
+use ring::hkdf;
+use ring::hmac;
+use ring::pbkdf2;
+use ring::rand::SystemRandom;
+use ring::signature::EcdsaKeyPair;
+use ring::signature::Ed25519KeyPair;
+use ring::signature::KeyPair;
+use ring::signature::UnparsedPublicKey;
+use rsa::pkcs8::FromPrivateKey;
+use rsa::pkcs8::FromPublicKey;
+use rsa::pkcs8::ToPrivateKey;
+use rsa::pkcs8::ToPublicKey;
+use rsa::BigUint;
+use rsa::PaddingScheme;
+use rsa::PublicKey;
+use rsa::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use rsa::RsaPublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::Sha256;
+use sha2::Sha384;
+use sha2::Sha512;
+use std::borrow::Cow;
 use std::cell::RefCell;
+use std::convert::TryInto;
+use std::num::NonZeroU32;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 pub use rand; // Re-export rand
 
+mod aes_kw;
+mod curve25519;
+mod der;
+mod ec;
+mod key;
+
+use crate::curve25519::Curve25519;
+use crate::key::Algorithm;
+use crate::key::CryptoHash;
+use crate::key::CryptoNamedCurve;
+use crate::key::KeyData;
+use crate::key::KeyFormat;
+use crate::key::KeyType;
+
 pub fn init(maybe_seed: Option<u64>) -> Extension {
   Extension::builder()
     .js(include_js_files!(
@@ -33,6 +82,15 @@ pub fn init(maybe_seed: Option<u64>) -> Extension {
       ),
       ("op_crypto_subtle_digest", op_async(op_crypto_subtle_digest)),
       ("op_crypto_random_uuid", op_sync(op_crypto_random_uuid)),
+      ("op_crypto_generate_key", op_async(op_crypto_generate_key)),
+      ("op_crypto_import_key", op_sync(op_crypto_import_key)),
+      ("op_crypto_import_jwk", op_sync(op_crypto_import_jwk)),
+      ("op_crypto_export_jwk", op_sync(op_crypto_export_jwk)),
+      ("op_crypto_sign_key", op_async(op_crypto_sign_key)),
+      ("op_crypto_verify_key", op_async(op_crypto_verify_key)),
+      ("op_crypto_encrypt_key", op_async(op_crypto_encrypt_key)),
+      ("op_crypto_decrypt_key", op_async(op_crypto_decrypt_key)),
+      ("op_crypto_derive_bits", op_async(op_crypto_derive_bits)),
     ])
     .state(move |state| {
       if let Some(seed) = maybe_seed {
@@ -107,6 +165,1089 @@ pub async fn op_crypto_subtle_digest(
   Ok(output)
 }
 
+pub(crate) fn operation_error(msg: impl Into<Cow<'static, str>>) -> AnyError {
+  custom_error("DOMExceptionOperationError", msg)
+}
+
+pub(crate) fn data_error(msg: impl Into<Cow<'static, str>>) -> AnyError {
+  custom_error("DOMExceptionDataError", msg)
+}
+
+pub(crate) fn not_supported_error(
+  msg: impl Into<Cow<'static, str>>,
+) -> AnyError {
+  custom_error("DOMExceptionNotSupportedError", msg)
+}
+
+/// Get a random number generator for an operation which runs on another
+/// thread, which is derived from the seeded generator, if any, so that seeded
+/// runs stay deterministic.
+fn get_rng(state: &mut OpState) -> StdRng {
+  match state.try_borrow_mut::<StdRng>() {
+    Some(seeded_rng) => StdRng::from_rng(seeded_rng).unwrap(),
+    None => StdRng::from_entropy(),
+  }
+}
+
+fn rsa_private_key(key: &KeyData) -> Result<RsaPrivateKey, AnyError> {
+  RsaPrivateKey::from_pkcs8_der(&key.data)
+    .map_err(|_| data_error("The key is not a valid RSA private key."))
+}
+
+fn rsa_public_key(key: &KeyData) -> Result<RsaPublicKey, AnyError> {
+  RsaPublicKey::from_public_key_der(&key.data)
+    .map_err(|_| data_error("The key is not a valid RSA public key."))
+}
+
+fn rsa_hash(hash: CryptoHash) -> rsa::Hash {
+  match hash {
+    CryptoHash::Sha1 => rsa::Hash::SHA1,
+    CryptoHash::Sha256 => rsa::Hash::SHA2_256,
+    CryptoHash::Sha384 => rsa::Hash::SHA2_384,
+    CryptoHash::Sha512 => rsa::Hash::SHA2_512,
+  }
+}
+
+fn pss_padding(
+  hash: CryptoHash,
+  rng: StdRng,
+  salt_length: usize,
+) -> PaddingScheme {
+  match hash {
+    CryptoHash::Sha1 => {
+      PaddingScheme::new_pss_with_salt::<Sha1, _>(rng, salt_length)
+    }
+    CryptoHash::Sha256 => {
+      PaddingScheme::new_pss_with_salt::<Sha256, _>(rng, salt_length)
+    }
+    CryptoHash::Sha384 => {
+      PaddingScheme::new_pss_with_salt::<Sha384, _>(rng, salt_length)
+    }
+    CryptoHash::Sha512 => {
+      PaddingScheme::new_pss_with_salt::<Sha512, _>(rng, salt_length)
+    }
+  }
+}
+
+fn oaep_padding(
+  hash: CryptoHash,
+  label: Option<&[u8]>,
+) -> Result<PaddingScheme, AnyError> {
+  let label = match label {
+    Some(label) if !label.is_empty() => String::from_utf8(label.to_vec())
+      .map_err(|_| {
+        not_supported_error("Only UTF-8 labels are supported for RSA-OAEP.")
+      })?,
+    _ => String::new(),
+  };
+  Ok(match hash {
+    CryptoHash::Sha1 => PaddingScheme::new_oaep_with_label::<Sha1, _>(label),
+    CryptoHash::Sha256 => {
+      PaddingScheme::new_oaep_with_label::<Sha256, _>(label)
+    }
+    CryptoHash::Sha384 => {
+      PaddingScheme::new_oaep_with_label::<Sha384, _>(label)
+    }
+    CryptoHash::Sha512 => {
+      PaddingScheme::new_oaep_with_label::<Sha512, _>(label)
+    }
+  })
+}
+
+fn digest(hash: CryptoHash, data: &[u8]) -> Vec<u8> {
+  digest::digest(hash.into(), data).as_ref().to_vec()
+}
+
+fn required<T>(value: Option<T>, name: &str) -> Result<T, AnyError> {
+  value.ok_or_else(|| type_error(format!("Missing argument {}", name)))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateKeyArg {
+  name: Algorithm,
+  modulus_length: Option<usize>,
+  public_exponent: Option<ZeroCopyBuf>,
+  named_curve: Option<CryptoNamedCurve>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyPairData {
+  private_key: ZeroCopyBuf,
+  public_key: ZeroCopyBuf,
+}
+
+const MIN_RSA_MODULUS_LENGTH: usize = 1024;
+const MAX_RSA_MODULUS_LENGTH: usize = 16384;
+
+/// Generate the key pair of an asymmetric algorithm, returning the PKCS#8
+/// document of the private key and the SubjectPublicKeyInfo of the public key.
+/// Secret keys are generated from random values in JavaScript.
+pub async fn op_crypto_generate_key(
+  state: Rc<RefCell<OpState>>,
+  args: GenerateKeyArg,
+  _: (),
+) -> Result<KeyPairData, AnyError> {
+  let mut rng = get_rng(&mut state.borrow_mut());
+  tokio::task::spawn_blocking(move || match args.name {
+    name if name.is_rsa() => {
+      let modulus_length = required(args.modulus_length, "modulusLength")?;
+      let public_exponent = BigUint::from_bytes_be(&required(
+        args.public_exponent,
+        "publicExponent",
+      )?);
+      // Searching for the primes doesn't finish for some invalid exponents.
+      if !(MIN_RSA_MODULUS_LENGTH..=MAX_RSA_MODULUS_LENGTH)
+        .contains(&modulus_length)
+        || (public_exponent != BigUint::from(3u32)
+          && public_exponent != BigUint::from(65537u32))
+      {
+        return Err(operation_error("Unsupported RSA key parameters."));
+      }
+      let private_key =
+        RsaPrivateKey::new_with_exp(&mut rng, modulus_length, &public_exponent)
+          .map_err(|e| operation_error(e.to_string()))?;
+      let public_key = private_key
+        .to_public_key()
+        .to_public_key_der()
+        .map_err(|e| operation_error(e.to_string()))?;
+      let private_key = private_key
+        .to_pkcs8_der()
+        .map_err(|e| operation_error(e.to_string()))?;
+      Ok(KeyPairData {
+        private_key: private_key.as_ref().to_vec().into(),
+        public_key: public_key.as_ref().to_vec().into(),
+      })
+    }
+    Algorithm::Ecdsa | Algorithm::Ecdh => {
+      let curve = required(args.named_curve, "namedCurve")?;
+      let algorithm = curve.key_algorithm();
+      let pkcs8 = EcdsaKeyPair::generate_pkcs8(algorithm, &SystemRandom::new())
+        .map_err(|_| operation_error("Failed to generate the key pair."))?;
+      let key_pair = EcdsaKeyPair::from_pkcs8(algorithm, pkcs8.as_ref())
+        .map_err(|_| operation_error("Failed to generate the key pair."))?;
+      let public_key = ec::encode_spki(curve, key_pair.public_key().as_ref())?;
+      Ok(KeyPairData {
+        private_key: pkcs8.as_ref().to_vec().into(),
+        public_key: public_key.into(),
+      })
+    }
+    Algorithm::Ed25519 | Algorithm::X25519 => {
+      let curve = Curve25519::from_algorithm(args.name).unwrap();
+      let mut private_key = [0; curve25519::KEY_LENGTH];
+      rng.fill(&mut private_key);
+      let public_key = curve25519::public_key(curve, &private_key)?;
+      Ok(KeyPairData {
+        private_key: curve25519::encode_pkcs8(curve, &private_key)?.into(),
+        public_key: curve25519::encode_spki(curve, &public_key)?.into(),
+      })
+    }
+    _ => Err(not_supported_error(
+      "The algorithm does not have key pairs.",
+    )),
+  })
+  .await?
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignArg {
+  key: KeyData,
+  algorithm: Algorithm,
+  hash: Option<CryptoHash>,
+  salt_length: Option<usize>,
+  named_curve: Option<CryptoNamedCurve>,
+  signature: Option<ZeroCopyBuf>,
+}
+
+pub async fn op_crypto_sign_key(
+  state: Rc<RefCell<OpState>>,
+  args: SignArg,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let data = zero_copy.ok_or_else(null_opbuf)?;
+  let mut rng = get_rng(&mut state.borrow_mut());
+  tokio::task::spawn_blocking(move || {
+    let maybe_hash = args.hash;
+    let hash = || required(maybe_hash, "hash");
+    // operations with RSA private keys are blinded, so that their timing
+    // doesn't depend on the private key
+    let signature = match args.algorithm {
+      Algorithm::RsassaPkcs1v15 => {
+        let hash = hash()?;
+        rsa_private_key(&args.key)?
+          .sign_blinded(
+            &mut rng,
+            PaddingScheme::new_pkcs1v15_sign(Some(rsa_hash(hash))),
+            &digest(hash, &data),
+          )
+          .map_err(|e| operation_error(e.to_string()))?
+      }
+      Algorithm::RsaPss => {
+        let hash = hash()?;
+        let salt_length = required(args.salt_length, "saltLength")?;
+        let salt_rng = StdRng::from_rng(&mut rng)
+          .map_err(|e| operation_error(e.to_string()))?;
+        rsa_private_key(&args.key)?
+          .sign_blinded(
+            &mut rng,
+            pss_padding(hash, salt_rng, salt_length),
+            &digest(hash, &data),
+          )
+          .map_err(|e| operation_error(e.to_string()))?
+      }
+      Algorithm::Ecdsa => {
+        let curve = required(args.named_curve, "namedCurve")?;
+        let algorithm = curve.signing_algorithm(hash()?).ok_or_else(|| {
+          not_supported_error("The hash is not supported with the named curve.")
+        })?;
+        EcdsaKeyPair::from_pkcs8(algorithm, &args.key.data)
+          .map_err(|_| data_error("The key is not a valid EC private key."))?
+          .sign(&SystemRandom::new(), &data)
+          .map_err(|_| operation_error("Failed to sign the data."))?
+          .as_ref()
+          .to_vec()
+      }
+      Algorithm::Ed25519 => {
+        let private_key =
+          curve25519::decode_pkcs8(Curve25519::Ed25519, &args.key.data)?;
+        Ed25519KeyPair::from_seed_unchecked(&private_key)
+          .map_err(|_| data_error("The key is not a valid Ed25519 key."))?
+          .sign(&data)
+          .as_ref()
+          .to_vec()
+      }
+      Algorithm::Hmac => {
+        let key = hmac::Key::new(hash()?.into(), &args.key.data);
+        hmac::sign(&key, &data).as_ref().to_vec()
+      }
+      _ => return Err(type_error("Unsupported algorithm")),
+    };
+    Ok(signature.into())
+  })
+  .await?
+}
+
+pub async fn op_crypto_verify_key(
+  state: Rc<RefCell<OpState>>,
+  args: SignArg,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<bool, AnyError> {
+  let data = zero_copy.ok_or_else(null_opbuf)?;
+  let rng = get_rng(&mut state.borrow_mut());
+  tokio::task::spawn_blocking(move || {
+    let maybe_hash = args.hash;
+    let hash = || required(maybe_hash, "hash");
+    let signature = required(args.signature, "signature")?;
+    let verified = match args.algorithm {
+      Algorithm::RsassaPkcs1v15 => {
+        let hash = hash()?;
+        rsa_public_key(&args.key)?
+          .verify(
+            PaddingScheme::new_pkcs1v15_sign(Some(rsa_hash(hash))),
+            &digest(hash, &data),
+            &signature,
+          )
+          .is_ok()
+      }
+      Algorithm::RsaPss => {
+        let hash = hash()?;
+        let salt_length = required(args.salt_length, "saltLength")?;
+        rsa_public_key(&args.key)?
+          .verify(
+            pss_padding(hash, rng, salt_length),
+            &digest(hash, &data),
+            &signature,
+          )
+          .is_ok()
+      }
+      Algorithm::Ecdsa => {
+        let curve = required(args.named_curve, "namedCurve")?;
+        let algorithm =
+          curve.verification_algorithm(hash()?).ok_or_else(|| {
+            not_supported_error(
+              "The hash is not supported with the named curve.",
+            )
+          })?;
+        let (_, point) = ec::decode_spki(&args.key.data)?;
+        UnparsedPublicKey::new(algorithm, point)
+          .verify(&data, &signature)
+          .is_ok()
+      }
+      Algorithm::Ed25519 => {
+        let public_key =
+          curve25519::decode_spki(Curve25519::Ed25519, &args.key.data)?;
+        UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+          .verify(&data, &signature)
+          .is_ok()
+      }
+      Algorithm::Hmac => {
+        let key = hmac::Key::new(hash()?.into(), &args.key.data);
+        hmac::verify(&key, &data, &signature).is_ok()
+      }
+      _ => return Err(type_error("Unsupported algorithm")),
+    };
+    Ok(verified)
+  })
+  .await?
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CipherArg {
+  key: KeyData,
+  algorithm: Algorithm,
+  hash: Option<CryptoHash>,
+  label: Option<ZeroCopyBuf>,
+  iv: Option<ZeroCopyBuf>,
+  counter: Option<ZeroCopyBuf>,
+  length: Option<usize>,
+  additional_data: Option<ZeroCopyBuf>,
+  tag_length: Option<usize>,
+}
+
+/// Run the block of code with a type alias of the AES block cipher of the
+/// size of the key.
+macro_rules! with_aes {
+  ($key:expr, $cipher:ident => $body:block) => {
+    match $key.len() {
+      16 => {
+        type $cipher = aes::Aes128;
+        $body
+      }
+      24 => {
+        type $cipher = aes::Aes192;
+        $body
+      }
+      32 => {
+        type $cipher = aes::Aes256;
+        $body
+      }
+      _ => Err(data_error(
+        "The length of an AES key must be 128, 192 or 256 bits.",
+      )),
+    }
+  };
+}
+
+fn aes_cbc(
+  key: &[u8],
+  iv: &[u8],
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  with_aes!(key, C => {
+    let cipher = Cbc::<C, Pkcs7>::new_from_slices(key, iv)
+      .map_err(|_| operation_error("The iv must be 16 bytes long."))?;
+    if encrypt {
+      Ok(cipher.encrypt_vec(data))
+    } else {
+      cipher
+        .decrypt_vec(data)
+        .map_err(|_| operation_error("Failed to decrypt the data."))
+    }
+  })
+}
+
+fn aes_ctr(
+  key: &[u8],
+  counter: &[u8],
+  length: usize,
+  data: &[u8],
+) -> Result<Vec<u8>, AnyError> {
+  let mut data = data.to_vec();
+  with_aes!(key, C => {
+    let result = match length {
+      32 => ctr::Ctr32BE::<C>::new_from_slices(key, counter)
+        .map_err(|_| operation_error("The counter must be 16 bytes long."))?
+        .try_apply_keystream(&mut data),
+      64 => ctr::Ctr64BE::<C>::new_from_slices(key, counter)
+        .map_err(|_| operation_error("The counter must be 16 bytes long."))?
+        .try_apply_keystream(&mut data),
+      128 => ctr::Ctr128BE::<C>::new_from_slices(key, counter)
+        .map_err(|_| operation_error("The counter must be 16 bytes long."))?
+        .try_apply_keystream(&mut data),
+      _ => return Err(not_supported_error("The counter length must be 32, 64 or 128 bits.")),
+    };
+    result.map_err(|_| operation_error("The counter would wrap around."))?;
+    Ok(data)
+  })
+}
+
+/// Encrypt or decrypt with AES-GCM. Only keys of 128 and 256 bits and an iv of
+/// 96 bits are supported.
+fn aes_gcm(
+  key: &[u8],
+  iv: &[u8],
+  additional_data: &[u8],
+  tag_length: usize,
+  data: &[u8],
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  let algorithm = match key.len() {
+    16 => &aead::AES_128_GCM,
+    32 => &aead::AES_256_GCM,
+    _ => {
+      return Err(not_supported_error(
+        "Only 128 and 256 bit keys are supported for AES-GCM.",
+      ))
+    }
+  };
+  if tag_length % 8 != 0 || !(32..=128).contains(&tag_length) {
+    return Err(operation_error("The tag length is invalid."));
+  }
+  let tag_len = tag_length / 8;
+  let raw_key = key;
+  let key = aead::LessSafeKey::new(
+    aead::UnboundKey::new(algorithm, raw_key)
+      .map_err(|_| data_error("The key is not a valid AES key."))?,
+  );
+  let iv: [u8; aead::NONCE_LEN] = iv.try_into().map_err(|_| {
+    not_supported_error("Only an iv of 96 bits is supported for AES-GCM.")
+  })?;
+  let nonce = || aead::Nonce::assume_unique_for_key(iv);
+  let aad = || aead::Aad::from(additional_data);
+  if encrypt {
+    let mut in_out = data.to_vec();
+    key
+      .seal_in_place_append_tag(nonce(), aad(), &mut in_out)
+      .map_err(|_| operation_error("Failed to encrypt the data."))?;
+    // a shorter tag is the truncation of the full tag
+    in_out.truncate(data.len() + tag_len);
+    Ok(in_out)
+  } else if tag_len == aead::MAX_TAG_LEN {
+    let mut in_out = data.to_vec();
+    let plaintext = key
+      .open_in_place(nonce(), aad(), &mut in_out)
+      .map_err(|_| operation_error("Failed to decrypt the data."))?;
+    Ok(plaintext.to_vec())
+  } else {
+    if data.len() < tag_len {
+      return Err(operation_error("Failed to decrypt the data."));
+    }
+    let (ciphertext, tag) = data.split_at(data.len() - tag_len);
+    // ring can only verify full tags, so the ciphertext is decrypted with the
+    // counter mode of GCM, which starts at the block after the iv, and the
+    // full tag of the plaintext is computed to be compared with the tag
+    let mut plaintext = ciphertext.to_vec();
+    let mut counter = [0; 16];
+    counter[..aead::NONCE_LEN].copy_from_slice(&iv);
+    counter[15] = 2;
+    with_aes!(raw_key, C => {
+      ctr::Ctr32BE::<C>::new_from_slices(raw_key, &counter)
+        .map_err(|_| data_error("The key is not a valid AES key."))?
+        .try_apply_keystream(&mut plaintext)
+        .map_err(|_| operation_error("Failed to decrypt the data."))
+    })?;
+    let mut sealed = plaintext.clone();
+    key
+      .seal_in_place_append_tag(nonce(), aad(), &mut sealed)
+      .map_err(|_| operation_error("Failed to decrypt the data."))?;
+    ring::constant_time::verify_slices_are_equal(
+      &sealed[ciphertext.len()..ciphertext.len() + tag_len],
+      tag,
+    )
+    .map_err(|_| operation_error("Failed to decrypt the data."))?;
+    Ok(plaintext)
+  }
+}
+
+fn aes_key_wrap(
+  key: &[u8],
+  data: &[u8],
+  wrap: bool,
+) -> Result<Vec<u8>, AnyError> {
+  let min_length = if wrap { 16 } else { 24 };
+  if data.len() % 8 != 0 || data.len() < min_length {
+    return Err(operation_error(
+      "The data must be a multiple of 64 bits long to be used with AES-KW.",
+    ));
+  }
+  with_aes!(key, C => {
+    let cipher: C = aes::NewBlockCipher::new_from_slice(key)
+      .map_err(|_| data_error("The key is not a valid AES key."))?;
+    if wrap {
+      Ok(aes_kw::wrap(&cipher, data))
+    } else {
+      aes_kw::unwrap(&cipher, data)
+        .ok_or_else(|| operation_error("Failed to unwrap the key."))
+    }
+  })
+}
+
+fn cipher(
+  args: CipherArg,
+  data: &[u8],
+  mut rng: StdRng,
+  encrypt: bool,
+) -> Result<Vec<u8>, AnyError> {
+  match args.algorithm {
+    Algorithm::RsaOaep => {
+      let hash = required(args.hash, "hash")?;
+      let padding = oaep_padding(hash, args.label.as_deref())?;
+      if encrypt {
+        rsa_public_key(&args.key)?
+          .encrypt(&mut rng, padding, data)
+          .map_err(|e| operation_error(e.to_string()))
+      } else {
+        rsa_private_key(&args.key)?
+          .decrypt_blinded(&mut rng, padding, data)
+          .map_err(|e| operation_error(e.to_string()))
+      }
+    }
+    Algorithm::AesCbc => {
+      let iv = required(args.iv, "iv")?;
+      aes_cbc(&args.key.data, &iv, data, encrypt)
+    }
+    Algorithm::AesCtr => {
+      let counter = required(args.counter, "counter")?;
+      let length = required(args.length, "length")?;
+      aes_ctr(&args.key.data, &counter, length, data)
+    }
+    Algorithm::AesGcm => {
+      let iv = required(args.iv, "iv")?;
+      let additional_data = args.additional_data.as_deref().unwrap_or_default();
+      let tag_length = args.tag_length.unwrap_or(128);
+      aes_gcm(
+        &args.key.data,
+        &iv,
+        additional_data,
+        tag_length,
+        data,
+        encrypt,
+      )
+    }
+    Algorithm::AesKw => aes_key_wrap(&args.key.data, data, encrypt),
+    _ => Err(type_error("Unsupported algorithm")),
+  }
+}
+
+pub async fn op_crypto_encrypt_key(
+  state: Rc<RefCell<OpState>>,
+  args: CipherArg,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let data = zero_copy.ok_or_else(null_opbuf)?;
+  let rng = get_rng(&mut state.borrow_mut());
+  tokio::task::spawn_blocking(move || {
+    cipher(args, &data, rng, true).map(ZeroCopyBuf::from)
+  })
+  .await?
+}
+
+pub async fn op_crypto_decrypt_key(
+  state: Rc<RefCell<OpState>>,
+  args: CipherArg,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let data = zero_copy.ok_or_else(null_opbuf)?;
+  let rng = get_rng(&mut state.borrow_mut());
+  tokio::task::spawn_blocking(move || {
+    cipher(args, &data, rng, false).map(ZeroCopyBuf::from)
+  })
+  .await?
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveBitsArg {
+  key: KeyData,
+  algorithm: Algorithm,
+  hash: Option<CryptoHash>,
+  length: Option<usize>,
+  iterations: Option<u32>,
+  salt: Option<ZeroCopyBuf>,
+  info: Option<ZeroCopyBuf>,
+  public_key: Option<KeyData>,
+}
+
+/// The length in bytes of the output of PBKDF2 and HKDF, of which the length in
+/// bits has to be a multiple of 8.
+fn byte_length(length: Option<usize>) -> Result<usize, AnyError> {
+  match required(length, "length")? {
+    length if length == 0 || length % 8 != 0 => Err(operation_error(
+      "The length must be a non-zero multiple of 8.",
+    )),
+    length => Ok(length / 8),
+  }
+}
+
+struct HkdfOutput(usize);
+
+impl hkdf::KeyType for HkdfOutput {
+  fn len(&self) -> usize {
+    self.0
+  }
+}
+
+/// Truncate a shared secret to the requested length in bits, if any.
+fn truncate_bits(
+  mut bits: Vec<u8>,
+  maybe_length: Option<usize>,
+) -> Result<Vec<u8>, AnyError> {
+  if let Some(length) = maybe_length {
+    if length > bits.len() * 8 {
+      return Err(operation_error("The length is too long for the secret."));
+    }
+    bits.truncate((length + 7) / 8);
+    if length % 8 != 0 {
+      if let Some(last) = bits.last_mut() {
+        *last &= 0xff << (8 - length % 8);
+      }
+    }
+  }
+  Ok(bits)
+}
+
+pub async fn op_crypto_derive_bits(
+  _state: Rc<RefCell<OpState>>,
+  args: DeriveBitsArg,
+  _: (),
+) -> Result<ZeroCopyBuf, AnyError> {
+  tokio::task::spawn_blocking(move || {
+    let bits = match args.algorithm {
+      Algorithm::Pbkdf2 => {
+        let hash = required(args.hash, "hash")?;
+        let salt = required(args.salt, "salt")?;
+        let iterations =
+          NonZeroU32::new(required(args.iterations, "iterations")?)
+            .ok_or_else(|| {
+              operation_error("The iterations must not be zero.")
+            })?;
+        let mut out = vec![0; byte_length(args.length)?];
+        pbkdf2::derive(
+          hash.into(),
+          iterations,
+          &salt,
+          &args.key.data,
+          &mut out,
+        );
+        out
+      }
+      Algorithm::Hkdf => {
+        let hash = required(args.hash, "hash")?;
+        let salt = required(args.salt, "salt")?;
+        let info = required(args.info, "info")?;
+        let length = byte_length(args.length)?;
+        let info = [&info[..]];
+        let too_long = || operation_error("The length is too long for HKDF.");
+        let okm = hkdf::Salt::new(hash.into(), &salt)
+          .extract(&args.key.data)
+          .expand(&info, HkdfOutput(length))
+          .map_err(|_| too_long())?;
+        let mut out = vec![0; length];
+        okm.fill(&mut out).map_err(|_| too_long())?;
+        out
+      }
+      Algorithm::Ecdh => {
+        let public_key = required(args.public_key, "publicKey")?;
+        let (curve, d, _) = ec::decode_pkcs8(&args.key.data)?;
+        let (public_curve, point) = ec::decode_spki(&public_key.data)?;
+        if curve != public_curve {
+          return Err(operation_error(
+            "The named curves of the keys do not match.",
+          ));
+        }
+        if curve != CryptoNamedCurve::P256 {
+          return Err(not_supported_error(
+            "Only the P-256 curve is supported for ECDH.",
+          ));
+        }
+        let secret_key = p256::SecretKey::from_be_bytes(&d)
+          .map_err(|_| data_error("The key is not a valid EC private key."))?;
+        let public_key = p256::PublicKey::from_sec1_bytes(&point)
+          .map_err(|_| data_error("The key is not a valid EC public key."))?;
+        let shared_secret = p256::ecdh::diffie_hellman(
+          secret_key.to_nonzero_scalar(),
+          public_key.as_affine(),
+        );
+        truncate_bits(shared_secret.as_bytes().to_vec(), args.length)?
+      }
+      Algorithm::X25519 => {
+        let public_key = required(args.public_key, "publicKey")?;
+        let private_key =
+          curve25519::decode_pkcs8(Curve25519::X25519, &args.key.data)?;
+        let public_key =
+          curve25519::decode_spki(Curve25519::X25519, &public_key.data)?;
+        truncate_bits(
+          curve25519::x25519_shared_secret(&private_key, &public_key)?,
+          args.length,
+        )?
+      }
+      _ => return Err(type_error("Unsupported algorithm")),
+    };
+    Ok(bits.into())
+  })
+  .await?
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportKeyArg {
+  algorithm: Algorithm,
+  format: KeyFormat,
+  named_curve: Option<CryptoNamedCurve>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportKeyResult {
+  #[serde(rename = "type")]
+  kind: KeyType,
+  data: ZeroCopyBuf,
+  modulus_length: Option<usize>,
+  public_exponent: Option<ZeroCopyBuf>,
+}
+
+impl ImportKeyResult {
+  fn rsa_private_key(key: RsaPrivateKey) -> Result<Self, AnyError> {
+    key
+      .validate()
+      .map_err(|_| data_error("The key is not a valid RSA private key."))?;
+    let data = key
+      .to_pkcs8_der()
+      .map_err(|_| data_error("The key is not a valid RSA private key."))?;
+    Ok(ImportKeyResult {
+      kind: KeyType::Private,
+      data: data.as_ref().to_vec().into(),
+      modulus_length: Some(key.size() * 8),
+      public_exponent: Some(key.e().to_bytes_be().into()),
+    })
+  }
+
+  fn rsa_public_key(key: RsaPublicKey) -> Result<Self, AnyError> {
+    let data = key
+      .to_public_key_der()
+      .map_err(|_| data_error("The key is not a valid RSA public key."))?;
+    Ok(ImportKeyResult {
+      kind: KeyType::Public,
+      data: data.as_ref().to_vec().into(),
+      modulus_length: Some(key.size() * 8),
+      public_exponent: Some(key.e().to_bytes_be().into()),
+    })
+  }
+
+  fn ec_private_key(
+    curve: CryptoNamedCurve,
+    d: &[u8],
+    point: &[u8],
+  ) -> Result<Self, AnyError> {
+    let data = ec::encode_pkcs8(curve, d, point)?;
+    // validate that the private key matches its public key
+    EcdsaKeyPair::from_pkcs8(curve.key_algorithm(), &data)
+      .map_err(|_| data_error("The key is not a valid EC private key."))?;
+    Ok(ImportKeyResult {
+      kind: KeyType::Private,
+      data: data.into(),
+      modulus_length: None,
+      public_exponent: None,
+    })
+  }
+
+  fn ec_public_key(
+    curve: CryptoNamedCurve,
+    point: &[u8],
+  ) -> Result<Self, AnyError> {
+    Ok(ImportKeyResult {
+      kind: KeyType::Public,
+      data: ec::encode_spki(curve, point)?.into(),
+      modulus_length: None,
+      public_exponent: None,
+    })
+  }
+
+  fn curve25519_private_key(
+    curve: Curve25519,
+    private_key: &[u8],
+  ) -> Result<Self, AnyError> {
+    Ok(ImportKeyResult {
+      kind: KeyType::Private,
+      data: curve25519::encode_pkcs8(curve, private_key)?.into(),
+      modulus_length: None,
+      public_exponent: None,
+    })
+  }
+
+  fn curve25519_public_key(
+    curve: Curve25519,
+    public_key: &[u8],
+  ) -> Result<Self, AnyError> {
+    Ok(ImportKeyResult {
+      kind: KeyType::Public,
+      data: curve25519::encode_spki(curve, public_key)?.into(),
+      modulus_length: None,
+      public_exponent: None,
+    })
+  }
+}
+
+fn check_named_curve(
+  expected: CryptoNamedCurve,
+  actual: CryptoNamedCurve,
+) -> Result<(), AnyError> {
+  if expected == actual {
+    Ok(())
+  } else {
+    Err(data_error("The named curve of the key does not match."))
+  }
+}
+
+/// Import the key data of an asymmetric key in the `spki`, `pkcs8` or `raw`
+/// format, normalizing it into the format held by the key store.
+pub fn op_crypto_import_key(
+  _state: &mut OpState,
+  args: ImportKeyArg,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<ImportKeyResult, AnyError> {
+  let data = zero_copy.ok_or_else(null_opbuf)?;
+  match (args.algorithm, args.format) {
+    (algorithm, KeyFormat::Pkcs8) if algorithm.is_rsa() => {
+      ImportKeyResult::rsa_private_key(
+        RsaPrivateKey::from_pkcs8_der(&data)
+          .map_err(|_| data_error("The key is not a valid RSA private key."))?,
+      )
+    }
+    (algorithm, KeyFormat::Spki) if algorithm.is_rsa() => {
+      ImportKeyResult::rsa_public_key(
+        RsaPublicKey::from_public_key_der(&data)
+          .map_err(|_| data_error("The key is not a valid RSA public key."))?,
+      )
+    }
+    (Algorithm::Ecdsa | Algorithm::Ecdh, format) => {
+      let curve = required(args.named_curve, "namedCurve")?;
+      match format {
+        KeyFormat::Pkcs8 => {
+          let (key_curve, d, maybe_point) = ec::decode_pkcs8(&data)?;
+          check_named_curve(curve, key_curve)?;
+          let point = maybe_point.ok_or_else(|| {
+            data_error("The private key does not include its public key.")
+          })?;
+          ImportKeyResult::ec_private_key(curve, &d, &point)
+        }
+        KeyFormat::Spki => {
+          let (key_curve, point) = ec::decode_spki(&data)?;
+          check_named_curve(curve, key_curve)?;
+          ImportKeyResult::ec_public_key(curve, &point)
+        }
+        KeyFormat::Raw => ImportKeyResult::ec_public_key(curve, &data),
+      }
+    }
+    (Algorithm::Ed25519 | Algorithm::X25519, format) => {
+      let curve = Curve25519::from_algorithm(args.algorithm).unwrap();
+      match format {
+        KeyFormat::Pkcs8 => ImportKeyResult::curve25519_private_key(
+          curve,
+          &curve25519::decode_pkcs8(curve, &data)?,
+        ),
+        KeyFormat::Spki => ImportKeyResult::curve25519_public_key(
+          curve,
+          &curve25519::decode_spki(curve, &data)?,
+        ),
+        KeyFormat::Raw => ImportKeyResult::curve25519_public_key(curve, &data),
+      }
+    }
+    _ => Err(not_supported_error("The key format is not supported.")),
+  }
+}
+
+/// The members of a JSON Web Key of an asymmetric key, decoded from base64url.
+#[derive(Deserialize, Serialize, Default)]
+pub struct JwkData {
+  n: Option<ZeroCopyBuf>,
+  e: Option<ZeroCopyBuf>,
+  d: Option<ZeroCopyBuf>,
+  p: Option<ZeroCopyBuf>,
+  q: Option<ZeroCopyBuf>,
+  dp: Option<ZeroCopyBuf>,
+  dq: Option<ZeroCopyBuf>,
+  qi: Option<ZeroCopyBuf>,
+  x: Option<ZeroCopyBuf>,
+  y: Option<ZeroCopyBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJwkArg {
+  algorithm: Algorithm,
+  named_curve: Option<CryptoNamedCurve>,
+  jwk: JwkData,
+}
+
+fn jwk_member(
+  value: Option<ZeroCopyBuf>,
+  name: &str,
+) -> Result<ZeroCopyBuf, AnyError> {
+  value.ok_or_else(|| {
+    data_error(format!(
+      "The '{}' member of the JSON Web Key is required.",
+      name
+    ))
+  })
+}
+
+fn jwk_uint(
+  value: Option<ZeroCopyBuf>,
+  name: &str,
+) -> Result<BigUint, AnyError> {
+  Ok(BigUint::from_bytes_be(&jwk_member(value, name)?))
+}
+
+/// Import an asymmetric key from the members of a JSON Web Key.
+pub fn op_crypto_import_jwk(
+  _state: &mut OpState,
+  args: ImportJwkArg,
+  _: (),
+) -> Result<ImportKeyResult, AnyError> {
+  let jwk = args.jwk;
+  match args.algorithm {
+    algorithm if algorithm.is_rsa() => {
+      let n = jwk_uint(jwk.n, "n")?;
+      let e = jwk_uint(jwk.e, "e")?;
+      match jwk.d {
+        Some(d) => {
+          let d = BigUint::from_bytes_be(&d);
+          let p = jwk_uint(jwk.p, "p")?;
+          let q = jwk_uint(jwk.q, "q")?;
+          // The CRT values are computed from the primes when the key is put
+          // together, which panics for primes of 0 or 1.
+          let one = BigUint::from(1u8);
+          if p <= one || q <= one || &p * &q != n {
+            return Err(data_error("The key is not a valid RSA private key."));
+          }
+          ImportKeyResult::rsa_private_key(RsaPrivateKey::from_components(
+            n,
+            e,
+            d,
+            vec![p, q],
+          ))
+        }
+        None => {
+          ImportKeyResult::rsa_public_key(RsaPublicKey::new(n, e).map_err(
+            |_| data_error("The key is not a valid RSA public key."),
+          )?)
+        }
+      }
+    }
+    Algorithm::Ecdsa | Algorithm::Ecdh => {
+      let curve = required(args.named_curve, "namedCurve")?;
+      let mut point = vec![0x04];
+      point.extend_from_slice(&jwk_member(jwk.x, "x")?);
+      point.extend_from_slice(&jwk_member(jwk.y, "y")?);
+      match jwk.d {
+        Some(d) => ImportKeyResult::ec_private_key(curve, &d, &point),
+        None => ImportKeyResult::ec_public_key(curve, &point),
+      }
+    }
+    Algorithm::Ed25519 | Algorithm::X25519 => {
+      let curve = Curve25519::from_algorithm(args.algorithm).unwrap();
+      let x = jwk_member(jwk.x, "x")?;
+      match jwk.d {
+        Some(d) => {
+          if curve25519::public_key(curve, &d)? != &x[..] {
+            return Err(data_error(
+              "The public key does not match the private key.",
+            ));
+          }
+          ImportKeyResult::curve25519_private_key(curve, &d)
+        }
+        None => ImportKeyResult::curve25519_public_key(curve, &x),
+      }
+    }
+    _ => Err(not_supported_error("The key format is not supported.")),
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJwkArg {
+  algorithm: Algorithm,
+  key: KeyData,
+}
+
+/// Export the members of the JSON Web Key of an asymmetric key, which are
+/// encoded with base64url in JavaScript.
+pub fn op_crypto_export_jwk(
+  _state: &mut OpState,
+  args: ExportJwkArg,
+  _: (),
+) -> Result<JwkData, AnyError> {
+  let to_buf = |n: &BigUint| Some(ZeroCopyBuf::from(n.to_bytes_be()));
+  match (args.algorithm, args.key.kind) {
+    (algorithm, KeyType::Private) if algorithm.is_rsa() => {
+      let key = rsa_private_key(&args.key)?;
+      let primes = key.primes();
+      if primes.len() != 2 {
+        return Err(not_supported_error(
+          "Multi-prime RSA keys are not supported.",
+        ));
+      }
+      let (p, q) = (&primes[0], &primes[1]);
+      let one = BigUint::from(1u8);
+      let two = BigUint::from(2u8);
+      Ok(JwkData {
+        n: to_buf(key.n()),
+        e: to_buf(key.e()),
+        d: to_buf(key.d()),
+        p: to_buf(p),
+        q: to_buf(q),
+        dp: to_buf(&(key.d() % &(p - &one))),
+        dq: to_buf(&(key.d() % &(q - &one))),
+        // the inverse of q modulo the prime p, by Fermat's little theorem
+        qi: to_buf(&q.modpow(&(p - &two), p)),
+        ..Default::default()
+      })
+    }
+    (algorithm, KeyType::Public) if algorithm.is_rsa() => {
+      let key = rsa_public_key(&args.key)?;
+      Ok(JwkData {
+        n: to_buf(key.n()),
+        e: to_buf(key.e()),
+        ..Default::default()
+      })
+    }
+    (Algorithm::Ecdsa | Algorithm::Ecdh, kind) => {
+      let (curve, maybe_d, point) = match kind {
+        KeyType::Private => {
+          let (curve, d, maybe_point) = ec::decode_pkcs8(&args.key.data)?;
+          let point = maybe_point.ok_or_else(|| {
+            data_error("The private key does not include its public key.")
+          })?;
+          (curve, Some(d), point)
+        }
+        _ => {
+          let (curve, point) = ec::decode_spki(&args.key.data)?;
+          (curve, None, point)
+        }
+      };
+      let (x, y) = point[1..].split_at(curve.field_size());
+      Ok(JwkData {
+        d: maybe_d.map(ZeroCopyBuf::from),
+        x: Some(x.to_vec().into()),
+        y: Some(y.to_vec().into()),
+        ..Default::default()
+      })
+    }
+    (Algorithm::Ed25519 | Algorithm::X25519, kind) => {
+      let curve = Curve25519::from_algorithm(args.algorithm).unwrap();
+      let (maybe_d, x) = match kind {
+        KeyType::Private => {
+          let d = curve25519::decode_pkcs8(curve, &args.key.data)?;
+          let x = curve25519::public_key(curve, &d)?;
+          (Some(d), x)
+        }
+        _ => (None, curve25519::decode_spki(curve, &args.key.data)?),
+      };
+      Ok(JwkData {
+        d: maybe_d.map(ZeroCopyBuf::from),
+        x: Some(x.into()),
+        ..Default::default()
+      })
+    }
+    _ => Err(not_supported_error("The key format is not supported.")),
+  }
+}
+
 pub fn get_declaration() -> PathBuf {
   PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("lib.deno_crypto.d.ts")
 }
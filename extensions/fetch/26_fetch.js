@@ -14,7 +14,7 @@
 ((window) => {
   const core = window.Deno.core;
   const webidl = window.__bootstrap.webidl;
-  const { errorReadableStream } = window.__bootstrap.streams;
+  const { errorReadableStream, _resourceBacking } = window.__bootstrap.streams;
   const { InnerBody, extractBody } = window.__bootstrap.fetchBody;
   const {
     toInnerRequest,
@@ -136,6 +136,7 @@
         }
       },
     });
+    readable[_resourceBacking] = { rid: responseBodyRid, autoClose: true };
     return readable;
   }
 
@@ -381,11 +381,8 @@ pub async fn op_fetch_response_read(
     .resource_table
     .get::<FetchResponseBodyResource>(rid)
     .ok_or_else(bad_resource_id)?;
-  let mut reader = RcRef::map(&resource, |r| &r.reader).borrow_mut().await;
-  let cancel = RcRef::map(resource, |r| &r.cancel);
   let mut buf = data.clone();
-  let read = reader.read(&mut buf).try_or_cancel(cancel).await?;
-  Ok(read)
+  resource.read(&mut buf).await
 }
 
 type CancelableResponseResult =
@@ -431,11 +428,25 @@ impl Resource for FetchRequestBodyResource {
 type BytesStream =
   Pin<Box<dyn Stream<Item = Result<bytes::Bytes, std::io::Error>> + Unpin>>;
 
-struct FetchResponseBodyResource {
+pub struct FetchResponseBodyResource {
   reader: AsyncRefCell<StreamReader<BytesStream, bytes::Bytes>>,
   cancel: CancelHandle,
 }
 
+impl FetchResponseBodyResource {
+  /// Reads the next chunk of the body into `buf`, returning 0 at the end of
+  /// the body.
+  pub async fn read(
+    self: &Rc<Self>,
+    buf: &mut [u8],
+  ) -> Result<usize, AnyError> {
+    let mut reader = RcRef::map(self, |r| &r.reader).borrow_mut().await;
+    let cancel = RcRef::map(self, |r| &r.cancel);
+    let read = reader.read(buf).try_or_cancel(cancel).await?;
+    Ok(read)
+  }
+}
+
 impl Resource for FetchResponseBodyResource {
   fn name(&self) -> Cow<str> {
     "fetchResponseBody".into()
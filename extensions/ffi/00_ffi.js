@@ -0,0 +1,57 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+"use strict";
+
+((window) => {
+  const core = window.Deno.core;
+
+  class DynamicLibrary {
+    #rid;
+    symbols = {};
+
+    constructor(path, symbols) {
+      this.#rid = core.opSync("op_ffi_load", { path, symbols });
+
+      for (const symbol in symbols) {
+        const { parameters, result } = symbols[symbol];
+        this.symbols[symbol] = (...args) => {
+          const buffers = [];
+          const params = args.map((arg, i) => {
+            if (typeof arg === "bigint") {
+              return arg.toString();
+            }
+            if (parameters[i] !== "buffer") {
+              return arg;
+            }
+            if (!ArrayBuffer.isView(arg)) {
+              throw new TypeError(
+                `Expected argument ${i} of '${symbol}' to be a typed array`,
+              );
+            }
+            buffers.push(
+              new Uint8Array(arg.buffer, arg.byteOffset, arg.byteLength),
+            );
+            return buffers.length - 1;
+          });
+          const value = core.opSync("op_ffi_call", {
+            rid: this.#rid,
+            symbol,
+            parameters: params,
+            buffers,
+          });
+          // 64-bit integers are returned as strings to keep their precision.
+          return result === "u64" || result === "i64" ? BigInt(value) : value;
+        };
+      }
+    }
+
+    close() {
+      core.close(this.#rid);
+    }
+  }
+
+  function dlopen(path, symbols) {
+    return new DynamicLibrary(path, symbols);
+  }
+
+  window.__bootstrap.ffi = { dlopen };
+})(this);
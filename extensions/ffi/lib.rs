@@ -0,0 +1,443 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::bad_resource_id;
+use deno_core::error::generic_error;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::include_js_files;
+use deno_core::op_sync;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_core::Resource;
+use deno_core::ResourceId;
+use deno_core::ZeroCopyBuf;
+use dlopen::raw::Library;
+use libffi::middle::Arg;
+use libffi::middle::Cif;
+use libffi::middle::CodePtr;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ffi::c_void;
+
+pub struct Unstable(pub bool);
+
+fn check_unstable(state: &OpState, api_name: &str) {
+  let unstable = state.borrow::<Unstable>();
+
+  if !unstable.0 {
+    eprintln!(
+      "Unstable API '{}'. The --unstable flag must be provided.",
+      api_name
+    );
+    std::process::exit(70);
+  }
+}
+
+pub trait FfiPermissions {
+  fn check(&mut self, path: &str) -> Result<(), AnyError>;
+}
+
+/// For use with `deno_ffi::init()` when the user does not want permissions.
+pub struct NoFfiPermissions;
+
+impl FfiPermissions for NoFfiPermissions {
+  fn check(&mut self, _path: &str) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
+
+pub fn init<P: FfiPermissions + 'static>(unstable: bool) -> Extension {
+  Extension::builder()
+    .js(include_js_files!(
+      prefix "deno:extensions/ffi",
+      "00_ffi.js",
+    ))
+    .ops(vec![
+      ("op_ffi_load", op_sync(op_ffi_load::<P>)),
+      ("op_ffi_call", op_sync(op_ffi_call)),
+    ])
+    .state(move |state| {
+      state.put(Unstable(unstable));
+      Ok(())
+    })
+    .build()
+}
+
+/// The types of the parameters and results of foreign functions. Pointers are
+/// passed and returned as numbers, and buffers are typed arrays which are
+/// passed as a pointer to their contents. Integers can also be passed as
+/// `BigInt`s, which reach the op as decimal strings, and `u64` and `i64`
+/// results are returned as decimal strings, which the JS side turns into
+/// `BigInt`s so that they don't lose precision.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum NativeType {
+  Void,
+  U8,
+  I8,
+  U16,
+  I16,
+  U32,
+  I32,
+  U64,
+  I64,
+  USize,
+  ISize,
+  F32,
+  F64,
+  Pointer,
+  Buffer,
+}
+
+impl From<NativeType> for libffi::middle::Type {
+  fn from(native_type: NativeType) -> Self {
+    match native_type {
+      NativeType::Void => libffi::middle::Type::void(),
+      NativeType::U8 => libffi::middle::Type::u8(),
+      NativeType::I8 => libffi::middle::Type::i8(),
+      NativeType::U16 => libffi::middle::Type::u16(),
+      NativeType::I16 => libffi::middle::Type::i16(),
+      NativeType::U32 => libffi::middle::Type::u32(),
+      NativeType::I32 => libffi::middle::Type::i32(),
+      NativeType::U64 => libffi::middle::Type::u64(),
+      NativeType::I64 => libffi::middle::Type::i64(),
+      NativeType::USize => libffi::middle::Type::usize(),
+      NativeType::ISize => libffi::middle::Type::isize(),
+      NativeType::F32 => libffi::middle::Type::f32(),
+      NativeType::F64 => libffi::middle::Type::f64(),
+      NativeType::Pointer | NativeType::Buffer => {
+        libffi::middle::Type::pointer()
+      }
+    }
+  }
+}
+
+/// The value of an argument of a foreign function call, which must outlive the
+/// `Arg` referring to it.
+#[repr(C)]
+union NativeValue {
+  u8_value: u8,
+  i8_value: i8,
+  u16_value: u16,
+  i16_value: i16,
+  u32_value: u32,
+  i32_value: i32,
+  u64_value: u64,
+  i64_value: i64,
+  usize_value: usize,
+  isize_value: isize,
+  f32_value: f32,
+  f64_value: f64,
+  pointer: *const u8,
+}
+
+impl NativeValue {
+  fn new(native_type: NativeType, value: Value) -> Result<Self, AnyError> {
+    let value = match native_type {
+      NativeType::Void => {
+        return Err(type_error("Parameters of foreign functions can't be void"))
+      }
+      NativeType::U8 => Self {
+        u8_value: value_as_uint::<u8>(value)?,
+      },
+      NativeType::I8 => Self {
+        i8_value: value_as_int::<i8>(value)?,
+      },
+      NativeType::U16 => Self {
+        u16_value: value_as_uint::<u16>(value)?,
+      },
+      NativeType::I16 => Self {
+        i16_value: value_as_int::<i16>(value)?,
+      },
+      NativeType::U32 => Self {
+        u32_value: value_as_uint::<u32>(value)?,
+      },
+      NativeType::I32 => Self {
+        i32_value: value_as_int::<i32>(value)?,
+      },
+      NativeType::U64 => Self {
+        u64_value: value_as_uint::<u64>(value)?,
+      },
+      NativeType::I64 => Self {
+        i64_value: value_as_int::<i64>(value)?,
+      },
+      NativeType::USize => Self {
+        usize_value: value_as_uint::<usize>(value)?,
+      },
+      NativeType::ISize => Self {
+        isize_value: value_as_int::<isize>(value)?,
+      },
+      NativeType::F32 => Self {
+        f32_value: value_as_f64(value)? as f32,
+      },
+      NativeType::F64 => Self {
+        f64_value: value_as_f64(value)?,
+      },
+      NativeType::Pointer => Self {
+        pointer: value_as_uint::<usize>(value)? as *const u8,
+      },
+      NativeType::Buffer => unreachable!(),
+    };
+    Ok(value)
+  }
+
+  fn buffer(ptr: *const u8) -> Self {
+    Self { pointer: ptr }
+  }
+
+  unsafe fn as_arg(&self, native_type: NativeType) -> Arg {
+    match native_type {
+      NativeType::Void => unreachable!(),
+      NativeType::U8 => Arg::new(&self.u8_value),
+      NativeType::I8 => Arg::new(&self.i8_value),
+      NativeType::U16 => Arg::new(&self.u16_value),
+      NativeType::I16 => Arg::new(&self.i16_value),
+      NativeType::U32 => Arg::new(&self.u32_value),
+      NativeType::I32 => Arg::new(&self.i32_value),
+      NativeType::U64 => Arg::new(&self.u64_value),
+      NativeType::I64 => Arg::new(&self.i64_value),
+      NativeType::USize => Arg::new(&self.usize_value),
+      NativeType::ISize => Arg::new(&self.isize_value),
+      NativeType::F32 => Arg::new(&self.f32_value),
+      NativeType::F64 => Arg::new(&self.f64_value),
+      NativeType::Pointer | NativeType::Buffer => Arg::new(&self.pointer),
+    }
+  }
+}
+
+// Integers outside of the range of 32-bit integers are numbers with a
+// fractional part of zero by the time they reach `Value`, and `BigInt`s are
+// decimal strings.
+fn value_as_uint<T: TryFrom<u64>>(value: Value) -> Result<T, AnyError> {
+  value
+    .as_u64()
+    .or_else(|| {
+      value
+        .as_f64()
+        .filter(|n| n.fract() == 0.0 && *n >= 0.0 && *n <= u64::MAX as f64)
+        .map(|n| n as u64)
+    })
+    .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    .and_then(|n| T::try_from(n).ok())
+    .ok_or_else(|| {
+      type_error(format!(
+        "Expected FFI argument to be an unsigned integer, but got {}",
+        value
+      ))
+    })
+}
+
+fn value_as_int<T: TryFrom<i64>>(value: Value) -> Result<T, AnyError> {
+  value
+    .as_i64()
+    .or_else(|| {
+      value
+        .as_f64()
+        .filter(|n| {
+          n.fract() == 0.0 && *n >= i64::MIN as f64 && *n <= i64::MAX as f64
+        })
+        .map(|n| n as i64)
+    })
+    .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    .and_then(|n| T::try_from(n).ok())
+    .ok_or_else(|| {
+      type_error(format!(
+        "Expected FFI argument to be a signed integer, but got {}",
+        value
+      ))
+    })
+}
+
+fn value_as_f64(value: Value) -> Result<f64, AnyError> {
+  value.as_f64().ok_or_else(|| {
+    type_error(format!(
+      "Expected FFI argument to be a number, but got {}",
+      value
+    ))
+  })
+}
+
+#[derive(Deserialize)]
+struct ForeignFunction {
+  parameters: Vec<NativeType>,
+  result: NativeType,
+}
+
+struct Symbol {
+  cif: Cif,
+  ptr: CodePtr,
+  parameter_types: Vec<NativeType>,
+  result_type: NativeType,
+}
+
+struct DynamicLibraryResource {
+  lib: Library,
+  symbols: HashMap<String, Symbol>,
+}
+
+impl Resource for DynamicLibraryResource {
+  fn name(&self) -> Cow<str> {
+    "dynamicLibrary".into()
+  }
+}
+
+impl DynamicLibraryResource {
+  fn register(
+    &mut self,
+    symbol: String,
+    foreign_fn: ForeignFunction,
+  ) -> Result<(), AnyError> {
+    if foreign_fn.parameters.contains(&NativeType::Void) {
+      return Err(type_error(format!(
+        "Parameters of the foreign function '{}' can't be void",
+        symbol
+      )));
+    }
+    let fn_ptr =
+      unsafe { self.lib.symbol::<*const c_void>(&symbol) }.map_err(|e| {
+        generic_error(format!("Failed to register symbol {}: {}", symbol, e))
+      })?;
+    let ptr = CodePtr::from_ptr(fn_ptr);
+    let cif = Cif::new(
+      foreign_fn
+        .parameters
+        .clone()
+        .into_iter()
+        .map(libffi::middle::Type::from),
+      foreign_fn.result.into(),
+    );
+    self.symbols.insert(
+      symbol,
+      Symbol {
+        cif,
+        ptr,
+        parameter_types: foreign_fn.parameters,
+        result_type: foreign_fn.result,
+      },
+    );
+    Ok(())
+  }
+}
+
+#[derive(Deserialize)]
+struct FfiLoadArgs {
+  path: String,
+  symbols: HashMap<String, ForeignFunction>,
+}
+
+fn op_ffi_load<FP>(
+  state: &mut OpState,
+  args: FfiLoadArgs,
+  _: (),
+) -> Result<ResourceId, AnyError>
+where
+  FP: FfiPermissions + 'static,
+{
+  check_unstable(state, "Deno.dlopen");
+  let permissions = state.borrow_mut::<FP>();
+  permissions.check(&args.path)?;
+
+  let lib = Library::open(&args.path).map_err(|e| {
+    generic_error(format!(
+      "Failed to load dynamic library {}: {}",
+      args.path, e
+    ))
+  })?;
+  let mut resource = DynamicLibraryResource {
+    lib,
+    symbols: HashMap::new(),
+  };
+  for (symbol, foreign_fn) in args.symbols {
+    resource.register(symbol, foreign_fn)?;
+  }
+
+  Ok(state.resource_table.add(resource))
+}
+
+#[derive(Deserialize)]
+struct FfiCallArgs {
+  rid: ResourceId,
+  symbol: String,
+  parameters: Vec<Value>,
+  buffers: Vec<ZeroCopyBuf>,
+}
+
+fn op_ffi_call(
+  state: &mut OpState,
+  args: FfiCallArgs,
+  _: (),
+) -> Result<Value, AnyError> {
+  let resource = state
+    .resource_table
+    .get::<DynamicLibraryResource>(args.rid)
+    .ok_or_else(bad_resource_id)?;
+  let symbol = resource.symbols.get(&args.symbol).ok_or_else(|| {
+    type_error(format!("Symbol '{}' is not registered", args.symbol))
+  })?;
+  if args.parameters.len() != symbol.parameter_types.len() {
+    return Err(type_error(format!(
+      "Expected {} arguments for '{}', but got {}",
+      symbol.parameter_types.len(),
+      args.symbol,
+      args.parameters.len()
+    )));
+  }
+
+  let buffers = &args.buffers;
+  let native_values = symbol
+    .parameter_types
+    .iter()
+    .zip(args.parameters.into_iter())
+    .map(|(&native_type, value)| match native_type {
+      NativeType::Buffer => {
+        let buf = value_as_uint::<usize>(value)
+          .ok()
+          .and_then(|index| buffers.get(index))
+          .ok_or_else(|| {
+            type_error("Expected FFI argument to be a typed array")
+          })?;
+        Ok(NativeValue::buffer(buf.as_ptr()))
+      }
+      _ => NativeValue::new(native_type, value),
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
+  let call_args = symbol
+    .parameter_types
+    .iter()
+    .zip(native_values.iter())
+    .map(|(&native_type, native_value)| unsafe {
+      native_value.as_arg(native_type)
+    })
+    .collect::<Vec<_>>();
+
+  let cif = &symbol.cif;
+  let ptr = symbol.ptr;
+  let result = unsafe {
+    match symbol.result_type {
+      NativeType::Void => {
+        cif.call::<()>(ptr, &call_args);
+        Value::Null
+      }
+      NativeType::U8 => json!(cif.call::<u8>(ptr, &call_args)),
+      NativeType::I8 => json!(cif.call::<i8>(ptr, &call_args)),
+      NativeType::U16 => json!(cif.call::<u16>(ptr, &call_args)),
+      NativeType::I16 => json!(cif.call::<i16>(ptr, &call_args)),
+      NativeType::U32 => json!(cif.call::<u32>(ptr, &call_args)),
+      NativeType::I32 => json!(cif.call::<i32>(ptr, &call_args)),
+      NativeType::U64 => json!(cif.call::<u64>(ptr, &call_args).to_string()),
+      NativeType::I64 => json!(cif.call::<i64>(ptr, &call_args).to_string()),
+      NativeType::USize => json!(cif.call::<usize>(ptr, &call_args)),
+      NativeType::ISize => json!(cif.call::<isize>(ptr, &call_args)),
+      NativeType::F32 => json!(cif.call::<f32>(ptr, &call_args)),
+      NativeType::F64 => json!(cif.call::<f64>(ptr, &call_args)),
+      NativeType::Pointer | NativeType::Buffer => {
+        json!(cif.call::<*const u8>(ptr, &call_args) as usize)
+      }
+    }
+  };
+  Ok(result)
+}
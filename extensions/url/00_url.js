@@ -51,11 +51,7 @@
       if (url === null) {
         return;
       }
-      const parts = core.opSync("op_url_parse", {
-        href: url.href,
-        setSearch: this.toString(),
-      });
-      url[_url] = parts;
+      url[_url] = reparseUrl(url[_url].href, "search", this.toString());
     }
 
     append(name, value) {
@@ -203,6 +199,71 @@
 
   const _url = Symbol("url");
 
+  // The indices of the offsets of the components of a URL serialization,
+  // which must match COMPONENT_POSITIONS in extensions/url/lib.rs.
+  const AFTER_SCHEME = 0;
+  const BEFORE_USERNAME = 1;
+  const AFTER_USERNAME = 2;
+  const BEFORE_PASSWORD = 3;
+  const AFTER_PASSWORD = 4;
+  const BEFORE_HOST = 5;
+  const AFTER_HOST = 6;
+  const BEFORE_PORT = 7;
+  const AFTER_PORT = 8;
+  const BEFORE_PATH = 9;
+  const AFTER_PATH = 10;
+  const AFTER_QUERY = 11;
+
+  // The buffer the ops write the offsets of the components to, which is
+  // shared by all calls to avoid allocating and serializing them.
+  const componentsBuf = new Uint32Array(12);
+  const componentsBytes = new Uint8Array(componentsBuf.buffer);
+
+  /**
+   * @typedef UrlRecord
+   * @property {string} href
+   * @property {Uint32Array} components
+   */
+
+  /**
+   * @param {string} href
+   * @param {string | undefined} maybeBase
+   * @returns {UrlRecord}
+   */
+  function parseUrl(href, maybeBase) {
+    const maybeHref = core.opSync(
+      "op_url_parse",
+      { href, baseHref: maybeBase },
+      componentsBytes,
+    );
+    return { href: maybeHref ?? href, components: componentsBuf.slice() };
+  }
+
+  /**
+   * @param {string} href
+   * @param {string} setter
+   * @param {string} value
+   * @returns {UrlRecord}
+   */
+  function reparseUrl(href, setter, value) {
+    const maybeHref = core.opSync(
+      "op_url_reparse",
+      { href, setter, value },
+      componentsBytes,
+    );
+    return { href: maybeHref ?? href, components: componentsBuf.slice() };
+  }
+
+  /**
+   * The search and hash of a URL are empty, rather than only their delimiter,
+   * if the query or fragment is empty.
+   * @param {string} component
+   * @returns {string}
+   */
+  function trimDelimiter(component) {
+    return component.length === 1 ? "" : component;
+  }
+
   class URL {
     [_url];
     #queryObject = null;
@@ -218,8 +279,7 @@
       }
       this[webidl.brand] = webidl.brand;
 
-      const parts = core.opSync("op_url_parse", { href: url, baseHref: base });
-      this[_url] = parts;
+      this[_url] = parseUrl(url, base);
     }
 
     [Symbol.for("Deno.customInspect")](inspect) {
@@ -252,7 +312,8 @@
 
     get hash() {
       webidl.assertBranded(this, URL);
-      return this[_url].hash;
+      const { href, components } = this[_url];
+      return trimDelimiter(href.slice(components[AFTER_QUERY]));
     }
 
     set hash(value) {
@@ -264,10 +325,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setHash: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "hash", value);
       } catch {
         /* pass */
       }
@@ -275,7 +333,8 @@
 
     get host() {
       webidl.assertBranded(this, URL);
-      return this[_url].host;
+      const { href, components } = this[_url];
+      return href.slice(components[BEFORE_HOST], components[AFTER_PORT]);
     }
 
     set host(value) {
@@ -287,10 +346,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setHost: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "host", value);
       } catch {
         /* pass */
       }
@@ -298,7 +354,8 @@
 
     get hostname() {
       webidl.assertBranded(this, URL);
-      return this[_url].hostname;
+      const { href, components } = this[_url];
+      return href.slice(components[BEFORE_HOST], components[AFTER_HOST]);
     }
 
     set hostname(value) {
@@ -310,10 +367,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setHostname: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "hostname", value);
       } catch {
         /* pass */
       }
@@ -332,20 +386,39 @@
         prefix,
         context: "Argument 1",
       });
-      this[_url] = core.opSync("op_url_parse", {
-        href: value,
-      });
+      this[_url] = parseUrl(value);
       this.#updateSearchParams();
     }
 
     get origin() {
       webidl.assertBranded(this, URL);
-      return this[_url].origin;
+      // https://url.spec.whatwg.org/#concept-url-origin
+      const protocol = this.protocol;
+      switch (protocol) {
+        case "blob:":
+          try {
+            return new URL(this.pathname).origin;
+          } catch {
+            return "null";
+          }
+        case "ftp:":
+        case "http:":
+        case "https:":
+        case "ws:":
+        case "wss:":
+          return `${protocol}//${this.host}`;
+        default:
+          return "null";
+      }
     }
 
     get password() {
       webidl.assertBranded(this, URL);
-      return this[_url].password;
+      const { href, components } = this[_url];
+      return href.slice(
+        components[BEFORE_PASSWORD],
+        components[AFTER_PASSWORD],
+      );
     }
 
     set password(value) {
@@ -357,10 +430,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setPassword: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "password", value);
       } catch {
         /* pass */
       }
@@ -368,7 +438,8 @@
 
     get pathname() {
       webidl.assertBranded(this, URL);
-      return this[_url].pathname;
+      const { href, components } = this[_url];
+      return href.slice(components[BEFORE_PATH], components[AFTER_PATH]);
     }
 
     set pathname(value) {
@@ -380,10 +451,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setPathname: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "pathname", value);
       } catch {
         /* pass */
       }
@@ -391,7 +459,8 @@
 
     get port() {
       webidl.assertBranded(this, URL);
-      return this[_url].port;
+      const { href, components } = this[_url];
+      return href.slice(components[BEFORE_PORT], components[AFTER_PORT]);
     }
 
     set port(value) {
@@ -403,10 +472,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setPort: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "port", value);
       } catch {
         /* pass */
       }
@@ -414,7 +480,8 @@
 
     get protocol() {
       webidl.assertBranded(this, URL);
-      return this[_url].protocol;
+      const { href, components } = this[_url];
+      return href.slice(0, components[AFTER_SCHEME] + 1);
     }
 
     set protocol(value) {
@@ -426,10 +493,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setProtocol: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "protocol", value);
       } catch {
         /* pass */
       }
@@ -437,7 +501,10 @@
 
     get search() {
       webidl.assertBranded(this, URL);
-      return this[_url].search;
+      const { href, components } = this[_url];
+      return trimDelimiter(
+        href.slice(components[AFTER_PATH], components[AFTER_QUERY]),
+      );
     }
 
     set search(value) {
@@ -449,10 +516,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setSearch: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "search", value);
         this.#updateSearchParams();
       } catch {
         /* pass */
@@ -461,7 +525,11 @@
 
     get username() {
       webidl.assertBranded(this, URL);
-      return this[_url].username;
+      const { href, components } = this[_url];
+      return href.slice(
+        components[BEFORE_USERNAME],
+        components[AFTER_USERNAME],
+      );
     }
 
     set username(value) {
@@ -473,10 +541,7 @@
         context: "Argument 1",
       });
       try {
-        this[_url] = core.opSync("op_url_parse", {
-          href: this.href,
-          setUsername: value,
-        });
+        this[_url] = reparseUrl(this[_url].href, "username", value);
       } catch {
         /* pass */
       }
@@ -12,7 +12,13 @@ fn setup() -> Vec<Extension> {
       .js(vec![(
         "setup",
         Box::new(|| {
-          Ok(r#"const { URL } = globalThis.__bootstrap.url;"#.to_owned())
+          Ok(
+            r#"
+            const { URL } = globalThis.__bootstrap.url;
+            const url = new URL("https://deno.land/std/http/server.ts?v=1");
+            "#
+            .to_owned(),
+          )
         }),
       )])
       .build(),
@@ -23,5 +29,27 @@ fn bench_url_parse(b: &mut Bencher) {
   bench_js_sync(b, r#"new URL(`http://www.google.com/`);"#, setup);
 }
 
-benchmark_group!(benches, bench_url_parse,);
+fn bench_url_parse_with_base(b: &mut Bencher) {
+  bench_js_sync(
+    b,
+    r#"new URL("/users/42?tab=posts", "https://example.com/")"#,
+    setup,
+  );
+}
+
+fn bench_url_components(b: &mut Bencher) {
+  bench_js_sync(b, r#"url.host; url.pathname; url.search;"#, setup);
+}
+
+fn bench_url_set_search(b: &mut Bencher) {
+  bench_js_sync(b, r#"url.search = "?v=2";"#, setup);
+}
+
+benchmark_group!(
+  benches,
+  bench_url_parse,
+  bench_url_parse_with_base,
+  bench_url_components,
+  bench_url_set_search,
+);
 bench_or_profile!(benches);
@@ -1,6 +1,7 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::error::generic_error;
+use deno_core::error::null_opbuf;
 use deno_core::error::type_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
@@ -8,11 +9,11 @@ use deno_core::include_js_files;
 use deno_core::op_sync;
 use deno_core::url::form_urlencoded;
 use deno_core::url::quirks;
+use deno_core::url::Position;
 use deno_core::url::Url;
 use deno_core::Extension;
 use deno_core::ZeroCopyBuf;
 use serde::Deserialize;
-use serde::Serialize;
 use std::panic::catch_unwind;
 use std::path::PathBuf;
 
@@ -24,6 +25,7 @@ pub fn init() -> Extension {
     ))
     .ops(vec![
       ("op_url_parse", op_sync(op_url_parse)),
+      ("op_url_reparse", op_sync(op_url_reparse)),
       (
         "op_url_parse_search_params",
         op_sync(op_url_parse_search_params),
@@ -41,101 +43,150 @@ pub fn init() -> Extension {
 pub struct UrlParseArgs {
   href: String,
   base_href: Option<String>,
-  // If one of the following are present, this is a setter call. Apply the
-  // proper `Url::set_*()` method after (re)parsing `href`.
-  set_hash: Option<String>,
-  set_host: Option<String>,
-  set_hostname: Option<String>,
-  set_password: Option<String>,
-  set_pathname: Option<String>,
-  set_port: Option<String>,
-  set_protocol: Option<String>,
-  set_search: Option<String>,
-  set_username: Option<String>,
 }
 
-#[derive(Serialize)]
-pub struct UrlParts {
-  href: String,
-  hash: String,
-  host: String,
-  hostname: String,
-  origin: String,
-  password: String,
-  pathname: String,
-  port: String,
-  protocol: String,
-  search: String,
-  username: String,
-}
+/// The number of component offsets written by `op_url_parse()` and
+/// `op_url_reparse()`, which must match `componentsBuf` in `00_url.js`.
+const COMPONENT_COUNT: usize = 12;
+
+/// The positions of the components of a URL serialization, in the order in
+/// which their offsets are written to the components buffer.
+const COMPONENT_POSITIONS: [Position; COMPONENT_COUNT] = [
+  Position::AfterScheme,
+  Position::BeforeUsername,
+  Position::AfterUsername,
+  Position::BeforePassword,
+  Position::AfterPassword,
+  Position::BeforeHost,
+  Position::AfterHost,
+  Position::BeforePort,
+  Position::AfterPort,
+  Position::BeforePath,
+  Position::AfterPath,
+  Position::AfterQuery,
+];
 
-/// Parse `UrlParseArgs::href` with an optional `UrlParseArgs::base_href`, or an
-/// optional part to "set" after parsing. Return `UrlParts`.
+/// Parse `UrlParseArgs::href` with an optional `UrlParseArgs::base_href`. The
+/// offsets of the components of the serialization are written to `buf`, so
+/// that they don't have to be serialized as separate strings. The
+/// serialization is only returned if it differs from `href`.
 pub fn op_url_parse(
   _state: &mut deno_core::OpState,
   args: UrlParseArgs,
-  _: (),
-) -> Result<UrlParts, AnyError> {
+  buf: Option<ZeroCopyBuf>,
+) -> Result<Option<String>, AnyError> {
+  let mut buf = buf.ok_or_else(null_opbuf)?;
   let base_url = args
     .base_href
     .as_ref()
     .map(|b| Url::parse(b).map_err(|_| type_error("Invalid base URL")))
     .transpose()?;
-  let mut url = Url::options()
+  let url = Url::options()
     .base_url(base_url.as_ref())
     .parse(&args.href)
     .map_err(|_| type_error("Invalid URL"))?;
+  write_components(&url, &mut buf, &args.href, args.base_href.as_deref())?;
+  Ok(serialization(url, &args.href))
+}
 
-  if let Some(hash) = args.set_hash.as_ref() {
-    quirks::set_hash(&mut url, hash);
-  } else if let Some(host) = args.set_host.as_ref() {
-    quirks::set_host(&mut url, host).map_err(|_| uri_error("Invalid host"))?;
-  } else if let Some(hostname) = args.set_hostname.as_ref() {
-    quirks::set_hostname(&mut url, hostname)
-      .map_err(|_| uri_error("Invalid hostname"))?;
-  } else if let Some(password) = args.set_password.as_ref() {
-    quirks::set_password(&mut url, password)
-      .map_err(|_| uri_error("Invalid password"))?;
-  } else if let Some(pathname) = args.set_pathname.as_ref() {
-    quirks::set_pathname(&mut url, pathname);
-  } else if let Some(port) = args.set_port.as_ref() {
-    quirks::set_port(&mut url, port).map_err(|_| uri_error("Invalid port"))?;
-  } else if let Some(protocol) = args.set_protocol.as_ref() {
-    quirks::set_protocol(&mut url, protocol)
-      .map_err(|_| uri_error("Invalid protocol"))?;
-  } else if let Some(search) = args.set_search.as_ref() {
-    quirks::set_search(&mut url, search);
-  } else if let Some(username) = args.set_username.as_ref() {
-    quirks::set_username(&mut url, username)
-      .map_err(|_| uri_error("Invalid username"))?;
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UrlSetter {
+  Hash,
+  Host,
+  Hostname,
+  Password,
+  Pathname,
+  Port,
+  Protocol,
+  Search,
+  Username,
+}
+
+#[derive(Deserialize)]
+pub struct UrlReparseArgs {
+  href: String,
+  setter: UrlSetter,
+  value: String,
+}
+
+/// Reparse the serialization `UrlReparseArgs::href` of a URL and apply the
+/// `Url::set_*()` method of `UrlReparseArgs::setter`. The offsets of the
+/// components are written to `buf` and the serialization is only returned if
+/// it changed, as with `op_url_parse()`.
+pub fn op_url_reparse(
+  _state: &mut deno_core::OpState,
+  args: UrlReparseArgs,
+  buf: Option<ZeroCopyBuf>,
+) -> Result<Option<String>, AnyError> {
+  let mut buf = buf.ok_or_else(null_opbuf)?;
+  let mut url =
+    Url::parse(&args.href).map_err(|_| type_error("Invalid URL"))?;
+  let value = args.value.as_str();
+  match args.setter {
+    UrlSetter::Hash => quirks::set_hash(&mut url, value),
+    UrlSetter::Host => quirks::set_host(&mut url, value)
+      .map_err(|_| uri_error("Invalid host"))?,
+    UrlSetter::Hostname => quirks::set_hostname(&mut url, value)
+      .map_err(|_| uri_error("Invalid hostname"))?,
+    UrlSetter::Password => quirks::set_password(&mut url, value)
+      .map_err(|_| uri_error("Invalid password"))?,
+    UrlSetter::Pathname => quirks::set_pathname(&mut url, value),
+    UrlSetter::Port => quirks::set_port(&mut url, value)
+      .map_err(|_| uri_error("Invalid port"))?,
+    UrlSetter::Protocol => quirks::set_protocol(&mut url, value)
+      .map_err(|_| uri_error("Invalid protocol"))?,
+    UrlSetter::Search => quirks::set_search(&mut url, value),
+    UrlSetter::Username => quirks::set_username(&mut url, value)
+      .map_err(|_| uri_error("Invalid username"))?,
+  }
+  write_components(&url, &mut buf, &args.href, None)?;
+  Ok(serialization(url, &args.href))
+}
+
+fn serialization(url: Url, href: &str) -> Option<String> {
+  let serialization = String::from(url);
+  if serialization == href {
+    None
+  } else {
+    Some(serialization)
   }
+}
 
+/// Write the offsets of the components of `url` to `buf` as native endian
+/// `u32`s. The serialization of a URL is always ASCII, so the byte offsets are
+/// also the offsets in the UTF-16 string in JavaScript.
+fn write_components(
+  url: &Url,
+  buf: &mut [u8],
+  href: &str,
+  maybe_base_href: Option<&str>,
+) -> Result<(), AnyError> {
+  if buf.len() != COMPONENT_COUNT * 4 {
+    return Err(type_error("Invalid components buffer"));
+  }
   // TODO(nayeemrmn): Panic that occurs in rust-url for the `non-spec:`
   // url-constructor wpt tests: https://github.com/servo/rust-url/issues/670.
-  let username = catch_unwind(|| quirks::username(&url)).map_err(|_| {
+  let offsets = catch_unwind(|| {
+    COMPONENT_POSITIONS
+      .iter()
+      .map(|position| url[..*position].len() as u32)
+      .collect::<Vec<_>>()
+  })
+  .map_err(|_| {
     generic_error(format!(
       "Internal error while parsing \"{}\"{}, \
        see https://github.com/servo/rust-url/issues/670",
-      args.href,
-      args
-        .base_href
+      href,
+      maybe_base_href
         .map(|b| format!(" against \"{}\"", b))
         .unwrap_or_default()
     ))
   })?;
-  Ok(UrlParts {
-    href: quirks::href(&url).to_string(),
-    hash: quirks::hash(&url).to_string(),
-    host: quirks::host(&url).to_string(),
-    hostname: quirks::hostname(&url).to_string(),
-    origin: quirks::origin(&url),
-    password: quirks::password(&url).to_string(),
-    pathname: quirks::pathname(&url).to_string(),
-    port: quirks::port(&url).to_string(),
-    protocol: quirks::protocol(&url).to_string(),
-    search: quirks::search(&url).to_string(),
-    username: username.to_string(),
-  })
+  for (chunk, offset) in buf.chunks_exact_mut(4).zip(offsets) {
+    chunk.copy_from_slice(&offset.to_ne_bytes());
+  }
+  Ok(())
 }
 
 pub fn op_url_parse_search_params(
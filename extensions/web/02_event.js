@@ -1197,6 +1197,82 @@
     }
   }
 
+  const promiseRejectionEventInitConverter = webidl.createDictionaryConverter(
+    "PromiseRejectionEventInit",
+    [{
+      key: "bubbles",
+      defaultValue: false,
+      converter: webidl.converters.boolean,
+    }, {
+      key: "cancelable",
+      defaultValue: false,
+      converter: webidl.converters.boolean,
+    }, {
+      key: "composed",
+      defaultValue: false,
+      converter: webidl.converters.boolean,
+    }, {
+      key: "promise",
+      required: true,
+      converter: webidl.converters.object,
+    }, {
+      key: "reason",
+      converter: webidl.converters.any,
+    }],
+  );
+
+  class PromiseRejectionEvent extends Event {
+    #promise = null;
+    #reason = undefined;
+
+    get promise() {
+      return this.#promise;
+    }
+    get reason() {
+      return this.#reason;
+    }
+
+    constructor(type, eventInitDict) {
+      const prefix = "Failed to construct 'PromiseRejectionEvent'";
+      webidl.requiredArguments(arguments.length, 2, { prefix });
+      const {
+        bubbles,
+        cancelable,
+        composed,
+        promise,
+        reason,
+      } = promiseRejectionEventInitConverter(eventInitDict, {
+        prefix,
+        context: "Argument 2",
+      });
+      super(type, {
+        bubbles: bubbles,
+        cancelable: cancelable,
+        composed: composed,
+      });
+
+      this.#promise = promise;
+      this.#reason = reason;
+    }
+
+    get [Symbol.toStringTag]() {
+      return "PromiseRejectionEvent";
+    }
+
+    [Symbol.for("Deno.customInspect")](inspect) {
+      return buildCustomInspectOutput(this, [
+        ...EVENT_PROPS,
+        "promise",
+        "reason",
+      ], inspect);
+    }
+  }
+
+  defineEnumerableProps(PromiseRejectionEvent, [
+    "promise",
+    "reason",
+  ]);
+
   const _eventHandlers = Symbol("eventHandlers");
 
   function makeWrappedHandler(handler) {
@@ -1244,6 +1320,7 @@
   window.MessageEvent = MessageEvent;
   window.CustomEvent = CustomEvent;
   window.ProgressEvent = ProgressEvent;
+  window.PromiseRejectionEvent = PromiseRejectionEvent;
   window.dispatchEvent = EventTarget.prototype.dispatchEvent;
   window.addEventListener = EventTarget.prototype.addEventListener;
   window.removeEventListener = EventTarget.prototype.removeEventListener;
@@ -8,6 +8,7 @@
 "use strict";
 
 ((window) => {
+  const core = window.Deno.core;
   const webidl = window.__bootstrap.webidl;
 
   class AssertionError extends Error {
@@ -228,6 +229,12 @@
   const _reader = Symbol("[[reader]]");
   const _readRequests = Symbol("[[readRequests]]");
   const _readyPromise = Symbol("[[readyPromise]]");
+  /**
+   * Set by embedders on streams which read from or write to a resource, to
+   * `{ rid, autoClose }` for readable streams and to
+   * `{ rid, pipeFrom(rid): Promise<void> }` for writable streams.
+   */
+  const _resourceBacking = Symbol("[[resourceBacking]]");
   const _started = Symbol("[[started]]");
   const _state = Symbol("[[state]]");
   const _storedError = Symbol("[[storedError]]");
@@ -1095,6 +1102,95 @@
     return false;
   }
 
+  /**
+   * Returns whether `source` and `dest` are both backed by resources and
+   * neither has chunks buffered in JS, in which case `pipeTo()` can copy
+   * between the resources without reading and writing every chunk in JS.
+   * @param {ReadableStream} source
+   * @param {WritableStream} dest
+   * @returns {boolean}
+   */
+  function isResourceBackedPipeIdle(source, dest) {
+    return source[_resourceBacking] !== undefined &&
+      dest[_resourceBacking] !== undefined &&
+      source[_state] === "readable" && dest[_state] === "writable" &&
+      source[_controller][_queue].length === 0 &&
+      dest[_controller][_queue].length === 0 &&
+      dest[_writeRequests].length === 0 &&
+      dest[_inFlightWriteRequest] === undefined &&
+      writableStreamCloseQueuedOrInFlight(dest) === false;
+  }
+
+  /**
+   * The fast path of `readableStreamPipeTo()` for streams which are both
+   * backed by resources. The copy is done by the `pipeFrom` function of the
+   * resource backing of `dest`, after which the streams are closed as if each
+   * chunk had been piped. If the copy fails, `source` is cancelled and `dest`
+   * is aborted with the error.
+   * @param {ReadableStream<Uint8Array>} source
+   * @param {WritableStream<Uint8Array>} dest
+   * @returns {Promise<void>}
+   */
+  function resourceBackedPipeTo(source, dest) {
+    const reader = acquireReadableStreamDefaultReader(source);
+    const writer = acquireWritableStreamDefaultWriter(dest);
+    source[_disturbed] = true;
+    const sourceBacking = source[_resourceBacking];
+    /** @type {Deferred<void>} */
+    const promise = new Deferred();
+
+    /**
+     * @param {boolean=} isError
+     * @param {any=} error
+     */
+    function finalize(isError, error) {
+      writableStreamDefaultWriterRelease(writer);
+      readableStreamReaderGenericRelease(reader);
+      if (isError) {
+        promise.reject(error);
+      } else {
+        promise.resolve(undefined);
+      }
+    }
+
+    uponPromise(
+      dest[_resourceBacking].pipeFrom(sourceBacking.rid),
+      () => {
+        if (source[_state] === "readable") {
+          readableStreamClose(source);
+          if (sourceBacking.autoClose) {
+            try {
+              core.close(sourceBacking.rid);
+            } catch (_) {
+              // might have already been closed
+            }
+          }
+        }
+        uponPromise(
+          writableStreamDefaultWriterCloseWithErrorPropagation(writer),
+          () => finalize(),
+          (error) => finalize(true, error),
+        );
+      },
+      (error) => {
+        const actions = [];
+        if (dest[_state] === "writable") {
+          actions.push(writableStreamAbort(dest, error));
+        }
+        if (source[_state] === "readable") {
+          actions.push(readableStreamCancel(source, error));
+        }
+        uponPromise(
+          Promise.all(actions),
+          () => finalize(true, error),
+          (newError) => finalize(true, newError),
+        );
+      },
+    );
+
+    return promise.promise;
+  }
+
   /**
    * @template T
    * @param {ReadableStream<T>} source
@@ -1122,6 +1218,13 @@
     assert(signal === undefined || signal instanceof AbortSignal);
     assert(!isReadableStreamLocked(source));
     assert(!isWritableStreamLocked(dest));
+    if (
+      preventClose === false && preventAbort === false &&
+      preventCancel === false && signal === undefined &&
+      isResourceBackedPipeIdle(source, dest)
+    ) {
+      return resourceBackedPipeTo(source, dest);
+    }
     const reader = acquireReadableStreamDefaultReader(source);
     const writer = acquireWritableStreamDefaultWriter(dest);
     source[_disturbed] = true;
@@ -2891,6 +2994,227 @@
     stream[_backpressure] = backpressure;
   }
 
+  /**
+   * @param {MessagePort} port
+   * @param {string} type
+   * @param {any} value
+   */
+  function packAndPostMessage(port, type, value) {
+    port.postMessage({ type, value });
+  }
+
+  /**
+   * @param {MessagePort} port
+   * @param {string} type
+   * @param {any} value
+   */
+  function packAndPostMessageHandlingError(port, type, value) {
+    try {
+      packAndPostMessage(port, type, value);
+    } catch (e) {
+      crossRealmTransformSendError(port, e);
+      throw e;
+    }
+  }
+
+  /**
+   * @param {MessagePort} port
+   * @param {any} error
+   */
+  function crossRealmTransformSendError(port, error) {
+    try {
+      packAndPostMessage(port, "error", error);
+    } catch {
+      // The error is not reported if it can't be sent either.
+    }
+  }
+
+  /**
+   * @param {ReadableStream} stream
+   * @param {MessagePort} port
+   */
+  function setUpCrossRealmTransformReadable(stream, port) {
+    initializeReadableStream(stream);
+    const controller = webidl.createBranded(ReadableStreamDefaultController);
+    port.addEventListener("message", (event) => {
+      const { type, value } = event.data;
+      assert(typeof type === "string");
+      if (type === "chunk") {
+        readableStreamDefaultControllerEnqueue(controller, value);
+      } else if (type === "close") {
+        readableStreamDefaultControllerClose(controller);
+        port.close();
+      } else if (type === "error") {
+        readableStreamDefaultControllerError(controller, value);
+        port.close();
+      }
+    });
+    port.addEventListener("messageerror", () => {
+      const error = new DOMException("Failed to deserialize", "DataCloneError");
+      crossRealmTransformSendError(port, error);
+      readableStreamDefaultControllerError(controller, error);
+      port.close();
+    });
+    port.start();
+
+    function startAlgorithm() {}
+
+    function pullAlgorithm() {
+      packAndPostMessage(port, "pull", undefined);
+      return resolvePromiseWith(undefined);
+    }
+
+    function cancelAlgorithm(reason) {
+      try {
+        packAndPostMessageHandlingError(port, "error", reason);
+      } catch (e) {
+        return Promise.reject(e);
+      } finally {
+        port.close();
+      }
+      return resolvePromiseWith(undefined);
+    }
+
+    setUpReadableStreamDefaultController(
+      stream,
+      controller,
+      startAlgorithm,
+      pullAlgorithm,
+      cancelAlgorithm,
+      0,
+      () => 1,
+    );
+  }
+
+  /**
+   * @param {WritableStream} stream
+   * @param {MessagePort} port
+   */
+  function setUpCrossRealmTransformWritable(stream, port) {
+    initializeWritableStream(stream);
+    const controller = webidl.createBranded(WritableStreamDefaultController);
+    /** @type {Deferred<void> | undefined} */
+    let backpressurePromise = new Deferred();
+    port.addEventListener("message", (event) => {
+      const { type, value } = event.data;
+      assert(typeof type === "string");
+      if (type === "pull") {
+        if (backpressurePromise !== undefined) {
+          backpressurePromise.resolve(undefined);
+          backpressurePromise = undefined;
+        }
+      } else if (type === "error") {
+        writableStreamDefaultControllerErrorIfNeeded(controller, value);
+        if (backpressurePromise !== undefined) {
+          backpressurePromise.resolve(undefined);
+          backpressurePromise = undefined;
+        }
+      }
+    });
+    port.addEventListener("messageerror", () => {
+      const error = new DOMException("Failed to deserialize", "DataCloneError");
+      crossRealmTransformSendError(port, error);
+      writableStreamDefaultControllerErrorIfNeeded(controller, error);
+      port.close();
+    });
+    port.start();
+
+    function startAlgorithm() {}
+
+    function writeAlgorithm(chunk) {
+      if (backpressurePromise === undefined) {
+        backpressurePromise = new Deferred();
+        backpressurePromise.resolve(undefined);
+      }
+      return transformPromiseWith(backpressurePromise.promise, () => {
+        backpressurePromise = new Deferred();
+        try {
+          packAndPostMessageHandlingError(port, "chunk", chunk);
+        } catch (e) {
+          port.close();
+          throw e;
+        }
+      });
+    }
+
+    function closeAlgorithm() {
+      packAndPostMessage(port, "close", undefined);
+      port.close();
+      return resolvePromiseWith(undefined);
+    }
+
+    function abortAlgorithm(reason) {
+      try {
+        packAndPostMessageHandlingError(port, "error", reason);
+      } catch (e) {
+        return Promise.reject(e);
+      } finally {
+        port.close();
+      }
+      return resolvePromiseWith(undefined);
+    }
+
+    setUpWritableStreamDefaultController(
+      stream,
+      controller,
+      startAlgorithm,
+      writeAlgorithm,
+      closeAlgorithm,
+      abortAlgorithm,
+      1,
+      () => 1,
+    );
+  }
+
+  /**
+   * The transfer steps of a ReadableStream, which pipe it to the other side
+   * of `port`, where it is received with `readableStreamTransferReceive()`.
+   * @param {ReadableStream} stream
+   * @param {MessagePort} port
+   */
+  function readableStreamTransfer(stream, port) {
+    assert(!isReadableStreamLocked(stream));
+    const writable = webidl.createBranded(WritableStream);
+    setUpCrossRealmTransformWritable(writable, port);
+    const promise = readableStreamPipeTo(stream, writable, false, false, false);
+    setPromiseIsHandledToTrue(promise);
+  }
+
+  /**
+   * @param {MessagePort} port
+   * @returns {ReadableStream}
+   */
+  function readableStreamTransferReceive(port) {
+    const stream = webidl.createBranded(ReadableStream);
+    setUpCrossRealmTransformReadable(stream, port);
+    return stream;
+  }
+
+  /**
+   * The transfer steps of a WritableStream, which pipe what is written to the
+   * other side of `port` into it. It is received there with
+   * `writableStreamTransferReceive()`.
+   * @param {WritableStream} stream
+   * @param {MessagePort} port
+   */
+  function writableStreamTransfer(stream, port) {
+    assert(!isWritableStreamLocked(stream));
+    const readable = webidl.createBranded(ReadableStream);
+    setUpCrossRealmTransformReadable(readable, port);
+    const promise = readableStreamPipeTo(readable, stream, false, false, false);
+    setPromiseIsHandledToTrue(promise);
+  }
+
+  /**
+   * @param {MessagePort} port
+   * @returns {WritableStream}
+   */
+  function writableStreamTransferReceive(port) {
+    const stream = webidl.createBranded(WritableStream);
+    setUpCrossRealmTransformWritable(stream, port);
+    return stream;
+  }
+
   /**
    * @template T
    * @param {T} value
@@ -4403,6 +4727,11 @@
     // Non-Public
     isReadableStreamDisturbed,
     errorReadableStream,
+    readableStreamTransfer,
+    readableStreamTransferReceive,
+    writableStreamTransfer,
+    writableStreamTransferReceive,
+    _resourceBacking,
     // Exposed in global runtime scope
     ByteLengthQueuingStrategy,
     CountQueuingStrategy,
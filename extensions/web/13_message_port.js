@@ -13,6 +13,14 @@
   const webidl = window.__bootstrap.webidl;
   const { setEventTargetData } = window.__bootstrap.eventTarget;
   const { defineEventHandler } = window.__bootstrap.event;
+  const {
+    ReadableStream,
+    WritableStream,
+    readableStreamTransfer,
+    readableStreamTransferReceive,
+    writableStreamTransfer,
+    writableStreamTransferReceive,
+  } = window.__bootstrap.streams;
 
   class MessageChannel {
     /** @type {MessagePort} */
@@ -20,9 +28,19 @@
     /** @type {MessagePort} */
     #port2;
 
-    constructor() {
+    /**
+     * @param {MessageChannelInit} options
+     */
+    constructor(options = {}) {
+      const prefix = "Failed to construct 'MessageChannel'";
+      options = webidl.converters.MessageChannelInit(options, {
+        prefix,
+        context: "Argument 1",
+      });
       this[webidl.brand] = webidl.brand;
-      const [port1Id, port2Id] = opCreateEntangledMessagePort();
+      const [port1Id, port2Id] = opCreateEntangledMessagePort(
+        options.highWaterMark,
+      );
       const port1 = createMessagePort(port1Id);
       const port2 = createMessagePort(port2Id);
       this.#port1 = port1;
@@ -63,9 +81,20 @@
     const port = webidl.createBranded(MessagePort);
     setEventTargetData(port);
     port[_id] = id;
+    portRegistry.register(port, id, port);
     return port;
   }
 
+  // Closing the resources of ports that are garbage collected lets their
+  // entangled ports know that they are disentangled.
+  const portRegistry = new FinalizationRegistry((id) => {
+    try {
+      core.close(id);
+    } catch {
+      // The port was already closed.
+    }
+  });
+
   class MessagePort extends EventTarget {
     /** @type {number | null} */
     [_id] = null;
@@ -108,8 +137,16 @@
         throw new DOMException("Can not tranfer self", "DataCloneError");
       }
       const data = serializeJsMessageData(message, transfer);
-      if (this[_id] === null) return;
-      core.opSync("op_message_port_post_message", this[_id], data);
+      if (this[_id] !== null) {
+        core.opSync("op_message_port_post_message", this[_id], data);
+      }
+      // The contents of the transferred array buffers have been copied by the
+      // op, so they can only be detached now.
+      for (const transferable of transfer) {
+        if (transferable instanceof ArrayBuffer) {
+          core.detachArrayBuffer(transferable);
+        }
+      }
     }
 
     start() {
@@ -119,11 +156,26 @@
         this[_enabled] = true;
         while (true) {
           if (this[_id] === null) break;
-          const data = await core.opAsync(
-            "op_message_port_recv_message",
-            this[_id],
-          );
-          if (data === null) break;
+          let data;
+          try {
+            data = await core.opAsync(
+              "op_message_port_recv_message",
+              this[_id],
+            );
+          } catch (err) {
+            // The receive is canceled when this port is closed or transferred.
+            if (this[_id] === null) break;
+            throw err;
+          }
+          if (data === null) {
+            // The entangled port has been closed or garbage collected, so no
+            // more messages can arrive.
+            if (this[_id] !== null) {
+              this.close();
+              this.dispatchEvent(new Event("close"));
+            }
+            break;
+          }
           let message, transfer;
           try {
             const v = deserializeJsMessageData(data);
@@ -144,9 +196,21 @@
       })();
     }
 
+    /**
+     * The number of messages posted through this port that the entangled port
+     * has not received yet.
+     * @returns {number}
+     */
+    get queuedMessageCount() {
+      webidl.assertBranded(this, MessagePort);
+      if (this[_id] === null) return 0;
+      return core.opSync("op_message_port_queued_count", this[_id]);
+    }
+
     close() {
       webidl.assertBranded(this, MessagePort);
       if (this[_id] !== null) {
+        portRegistry.unregister(this);
         core.close(this[_id]);
         this[_id] = null;
       }
@@ -157,14 +221,193 @@
     self.start();
   });
   defineEventHandler(MessagePort.prototype, "messageerror");
+  defineEventHandler(MessagePort.prototype, "close");
 
   webidl.configurePrototype(MessagePort);
 
   /**
+   * @param {number} [highWaterMark]
    * @returns {[number, number]}
    */
-  function opCreateEntangledMessagePort() {
-    return core.opSync("op_message_port_create_entangled");
+  function opCreateEntangledMessagePort(highWaterMark) {
+    return core.opSync("op_message_port_create_entangled", highWaterMark);
+  }
+
+  /**
+   * Transferred objects other than array buffers are serialized as empty
+   * objects, which are deserialized as placeholders for the objects that are
+   * received for them. This replaces the placeholders in `value`.
+   * @param {any} value
+   * @param {object[]} placeholders
+   * @param {object[]} objects
+   * @returns {any}
+   */
+  function replaceTransferPlaceholders(value, placeholders, objects) {
+    const seen = new Set();
+    const replace = (item) => {
+      if (typeof item !== "object" || item === null) return item;
+      const index = placeholders.indexOf(item);
+      if (index !== -1) return objects[index];
+      if (seen.has(item) || ArrayBuffer.isView(item)) return item;
+      seen.add(item);
+      if (item instanceof Map) {
+        const entries = [...item];
+        item.clear();
+        for (const [key, entry] of entries) {
+          item.set(replace(key), replace(entry));
+        }
+      } else if (item instanceof Set) {
+        const values = [...item];
+        item.clear();
+        for (const entry of values) item.add(replace(entry));
+      } else {
+        for (const key of Object.keys(item)) item[key] = replace(item[key]);
+      }
+      return item;
+    };
+    return replace(value);
+  }
+
+  const errorConstructors = {
+    Error,
+    EvalError,
+    RangeError,
+    ReferenceError,
+    SyntaxError,
+    TypeError,
+    URIError,
+  };
+
+  /**
+   * @param {Error} error
+   * @returns {string}
+   */
+  function errorKind(error) {
+    if (error instanceof DOMException) return "DOMException";
+    for (const [kind, constructor] of Object.entries(errorConstructors)) {
+      if (kind !== "Error" && error instanceof constructor) return kind;
+    }
+    return "Error";
+  }
+
+  /**
+   * @param {any} value
+   * @returns {boolean}
+   */
+  function isErrorContainer(value) {
+    if (Array.isArray(value) || value instanceof Map || value instanceof Set) {
+      return true;
+    }
+    const proto = Object.getPrototypeOf(value);
+    return proto === Object.prototype || proto === null;
+  }
+
+  /**
+   * V8 serializes errors without their `cause`, and DOMExceptions and
+   * subclasses of the native errors without their class. This replaces the
+   * errors in `value` with descriptions of them, which are collected in
+   * `descriptors` and revived by `reviveErrors()`. Only arrays, maps, sets and
+   * plain objects are searched for errors, and copied if they contain any, so
+   * `value` itself is left untouched.
+   * @param {any} value
+   * @param {object[]} descriptors
+   * @returns {any}
+   */
+  function describeErrors(value, descriptors) {
+    const seen = new Set();
+    const containsError = (item) => {
+      if (typeof item !== "object" || item === null) return false;
+      if (item instanceof Error) return true;
+      if (seen.has(item) || !isErrorContainer(item)) return false;
+      seen.add(item);
+      if (item instanceof Map) {
+        for (const [key, entry] of item) {
+          if (containsError(key) || containsError(entry)) return true;
+        }
+        return false;
+      }
+      if (item instanceof Set) {
+        for (const entry of item) {
+          if (containsError(entry)) return true;
+        }
+        return false;
+      }
+      return Object.keys(item).some((key) => containsError(item[key]));
+    };
+    if (!containsError(value)) return value;
+
+    const copies = new Map();
+    const describe = (item) => {
+      if (typeof item !== "object" || item === null) return item;
+      if (copies.has(item)) return copies.get(item);
+      if (item instanceof Error) {
+        const descriptor = {
+          kind: errorKind(item),
+          name: String(item.name),
+          message: String(item.message),
+          stack: typeof item.stack === "string" ? item.stack : undefined,
+          hasCause: "cause" in item,
+          cause: undefined,
+        };
+        copies.set(item, descriptor);
+        descriptors.push(descriptor);
+        if (descriptor.hasCause) descriptor.cause = describe(item.cause);
+        return descriptor;
+      }
+      if (!isErrorContainer(item)) return item;
+      if (item instanceof Map) {
+        const copy = new Map();
+        copies.set(item, copy);
+        for (const [key, entry] of item) {
+          copy.set(describe(key), describe(entry));
+        }
+        return copy;
+      }
+      if (item instanceof Set) {
+        const copy = new Set();
+        copies.set(item, copy);
+        for (const entry of item) copy.add(describe(entry));
+        return copy;
+      }
+      const copy = Array.isArray(item)
+        ? new Array(item.length)
+        : Object.create(Object.getPrototypeOf(item));
+      copies.set(item, copy);
+      for (const key of Object.keys(item)) copy[key] = describe(item[key]);
+      return copy;
+    };
+    return describe(value);
+  }
+
+  /**
+   * Creates the errors described by `describeErrors()`. Their causes are left
+   * to the caller, as they may refer to objects that are yet to be replaced.
+   * @param {object[]} descriptors
+   * @returns {Error[]}
+   */
+  function reviveErrors(descriptors) {
+    return descriptors.map((descriptor) => {
+      let error;
+      if (descriptor.kind === "DOMException") {
+        error = new DOMException(descriptor.message, descriptor.name);
+      } else {
+        const constructor = errorConstructors[descriptor.kind] ?? Error;
+        error = new constructor(descriptor.message);
+        if (descriptor.name !== error.name) {
+          Object.defineProperty(error, "name", {
+            value: descriptor.name,
+            writable: true,
+            configurable: true,
+          });
+        }
+      }
+      Object.defineProperty(error, "stack", {
+        value: descriptor.stack,
+        writable: true,
+        configurable: true,
+      });
+      return error;
+    });
   }
 
   /**
@@ -174,6 +417,8 @@
   function deserializeJsMessageData(messageData) {
     /** @type {object[]} */
     const transferables = [];
+    /** @type {ArrayBuffer[]} */
+    const transferredArrayBuffers = [];
 
     for (const transferable of messageData.transferables) {
       switch (transferable.kind) {
@@ -182,14 +427,51 @@
           transferables.push(port);
           break;
         }
+        case "readableStream": {
+          const port = createMessagePort(transferable.data);
+          transferables.push(readableStreamTransferReceive(port));
+          break;
+        }
+        case "writableStream": {
+          const port = createMessagePort(transferable.data);
+          transferables.push(writableStreamTransferReceive(port));
+          break;
+        }
+        case "arrayBuffer": {
+          // The bytes are received in a view spanning a new array buffer,
+          // which takes the place of the transferred one.
+          transferredArrayBuffers.push(transferable.data.buffer);
+          break;
+        }
         default:
           throw new TypeError("Unreachable");
       }
     }
 
-    const data = core.deserialize(messageData.data);
+    const [placeholders, data, errorDescriptors] = core.deserialize(
+      messageData.data,
+      { transferredArrayBuffers },
+    );
+    const ports = transferables.filter((t) => t instanceof MessagePort);
 
-    return [data, transferables];
+    if (placeholders.length === 0 && errorDescriptors.length === 0) {
+      return [data, ports];
+    }
+    const errors = reviveErrors(errorDescriptors);
+    const [replacedData, causes] = replaceTransferPlaceholders(
+      [data, errorDescriptors.map((descriptor) => descriptor.cause)],
+      [...placeholders, ...errorDescriptors],
+      [...transferables, ...errors],
+    );
+    errors.forEach((error, i) => {
+      if (!errorDescriptors[i].hasCause) return;
+      Object.defineProperty(error, "cause", {
+        value: causes[i],
+        writable: true,
+        configurable: true,
+      });
+    });
+    return [replacedData, ports];
   }
 
   /**
@@ -198,9 +480,65 @@
    * @returns {globalThis.__bootstrap.messagePort.MessageData}
    */
   function serializeJsMessageData(data, tranferables) {
+    /** @type {ArrayBuffer[]} */
+    const transferredArrayBuffers = [];
+    /** @type {Uint8Array[]} */
+    const transferredBytes = [];
+    for (const transferable of tranferables) {
+      if (transferable instanceof ArrayBuffer) {
+        if (transferredArrayBuffers.includes(transferable)) {
+          throw new DOMException(
+            "Can not transfer the same ArrayBuffer twice",
+            "DataCloneError",
+          );
+        }
+        try {
+          transferredBytes.push(new Uint8Array(transferable));
+        } catch {
+          throw new DOMException(
+            "Can not transfer detached ArrayBuffer",
+            "DataCloneError",
+          );
+        }
+        transferredArrayBuffers.push(transferable);
+      }
+    }
+
+    for (const [i, transferable] of tranferables.entries()) {
+      if (
+        !(transferable instanceof ReadableStream ||
+          transferable instanceof WritableStream)
+      ) {
+        continue;
+      }
+      if (tranferables.indexOf(transferable) !== i) {
+        throw new DOMException(
+          "Can not transfer the same stream twice",
+          "DataCloneError",
+        );
+      }
+      if (transferable.locked) {
+        throw new DOMException(
+          "Can not transfer locked stream",
+          "DataCloneError",
+        );
+      }
+    }
+
+    // The other transferred objects are serialized along with the data, so
+    // that the references to them in it can be told apart on the other side.
+    const transferredObjects = tranferables.filter((t) =>
+      !(t instanceof ArrayBuffer)
+    );
+    /** @type {object[]} */
+    const errorDescriptors = [];
     let serializedData;
     try {
-      serializedData = core.serialize(data);
+      const described = describeErrors(data, errorDescriptors);
+      serializedData = core.serialize(
+        [transferredObjects, described, errorDescriptors],
+        { transferredArrayBuffers },
+      );
     } catch (err) {
       throw new DOMException(err.message, "DataCloneError");
     }
@@ -219,7 +557,30 @@
           );
         }
         transferable[_id] = null;
+        portRegistry.unregister(transferable);
         serializedTransferables.push({ kind: "messagePort", data: id });
+      } else if (transferable instanceof ReadableStream) {
+        webidl.assertBranded(transferable, ReadableStream);
+        const [port1Id, port2Id] = opCreateEntangledMessagePort();
+        readableStreamTransfer(transferable, createMessagePort(port1Id));
+        serializedTransferables.push({
+          kind: "readableStream",
+          data: port2Id,
+        });
+      } else if (transferable instanceof WritableStream) {
+        webidl.assertBranded(transferable, WritableStream);
+        const [port1Id, port2Id] = opCreateEntangledMessagePort();
+        writableStreamTransfer(transferable, createMessagePort(port1Id));
+        serializedTransferables.push({
+          kind: "writableStream",
+          data: port2Id,
+        });
+      } else if (transferable instanceof ArrayBuffer) {
+        const index = transferredArrayBuffers.indexOf(transferable);
+        serializedTransferables.push({
+          kind: "arrayBuffer",
+          data: transferredBytes[index],
+        });
       } else {
         throw new DOMException("Value not transferable", "DataCloneError");
       }
@@ -244,6 +605,20 @@
     ],
   );
 
+  webidl.converters.MessageChannelInit = webidl.createDictionaryConverter(
+    "MessageChannelInit",
+    [
+      {
+        key: "highWaterMark",
+        converter: (V, opts) =>
+          webidl.converters["unsigned long"](V, {
+            ...opts,
+            enforceRange: true,
+          }),
+      },
+    ],
+  );
+
   window.__bootstrap.messagePort = {
     MessageChannel,
     MessagePort,
@@ -87,6 +87,12 @@ declare namespace globalThis {
       declare type Transferable = {
         kind: "messagePort";
         data: number;
+      } | {
+        kind: "readableStream" | "writableStream";
+        data: number;
+      } | {
+        kind: "arrayBuffer";
+        data: Uint8Array;
       };
       declare interface MessageData {
         data: Uint8Array;
@@ -186,6 +186,15 @@ declare interface TextDecodeOptions {
   stream?: boolean;
 }
 
+/** Decodes bytes in any encoding from the Encoding Standard, including legacy
+ * multi-byte encodings such as Shift_JIS, EUC-JP, ISO-2022-JP, GBK and Big5.
+ * Pass `{ stream: true }` to `decode()` to decode input split across chunks.
+ *
+ * ```ts
+ * const decoder = new TextDecoder("shift_jis");
+ * decoder.decode(new Uint8Array([0x93, 0xfa, 0x96, 0x7b])); // "日本"
+ * ```
+ */
 declare class TextDecoder {
   constructor(label?: string, options?: TextDecoderOptions);
 
@@ -671,17 +680,24 @@ declare class MessageEvent<T = any> extends Event {
   constructor(type: string, eventInitDict?: MessageEventInit);
 }
 
-type Transferable = ArrayBuffer | MessagePort;
+type Transferable = ArrayBuffer | MessagePort | ReadableStream | WritableStream;
 
 interface PostMessageOptions {
   transfer?: Transferable[];
 }
 
+interface MessageChannelInit {
+  /** Non-standard. If set, `postMessage()` on either port throws a
+   * "QuotaExceededError" DOMException while this many of the messages posted
+   * through that port have not been received yet. */
+  highWaterMark?: number;
+}
+
 /** The MessageChannel interface of the Channel Messaging API allows us to
  * create a new message channel and send data through it via its two MessagePort
  * properties. */
 declare class MessageChannel {
-  constructor();
+  constructor(options?: MessageChannelInit);
   readonly port1: MessagePort;
   readonly port2: MessagePort;
 }
@@ -689,6 +705,7 @@ declare class MessageChannel {
 interface MessagePortEventMap {
   "message": MessageEvent;
   "messageerror": MessageEvent;
+  "close": Event;
 }
 
 /** The MessagePort interface of the Channel Messaging API represents one of the
@@ -697,10 +714,16 @@ interface MessagePortEventMap {
 declare class MessagePort extends EventTarget {
   onmessage: ((this: MessagePort, ev: MessageEvent) => any) | null;
   onmessageerror: ((this: MessagePort, ev: MessageEvent) => any) | null;
+  /** Called when the entangled port is closed or garbage collected, after
+   * which this port is closed too. Only fired on started ports. */
+  onclose: ((this: MessagePort, ev: Event) => any) | null;
   /**
    * Disconnects the port, so that it is no longer active.
    */
   close(): void;
+  /** Non-standard. The number of messages posted through this port that the
+   * entangled port has not received yet. */
+  readonly queuedMessageCount: number;
   /**
    * Posts a message through the channel. Objects listed in transfer are
    * transferred, not just cloned, meaning that they are no longer usable on the
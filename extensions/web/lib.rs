@@ -37,6 +37,7 @@ use uuid::Uuid;
 
 use crate::message_port::op_message_port_create_entangled;
 use crate::message_port::op_message_port_post_message;
+use crate::message_port::op_message_port_queued_count;
 use crate::message_port::op_message_port_recv_message;
 
 /// Load and execute the javascript code.
@@ -89,6 +90,10 @@ pub fn init(
         "op_message_port_post_message",
         op_sync(op_message_port_post_message),
       ),
+      (
+        "op_message_port_queued_count",
+        op_sync(op_message_port_queued_count),
+      ),
       (
         "op_message_port_recv_message",
         op_async(op_message_port_recv_message),
@@ -101,6 +106,7 @@ pub fn init(
       }
       Ok(())
     })
+    .error_class(get_error_class_name)
     .build()
 }
 
@@ -1,8 +1,13 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
 use deno_core::error::bad_resource_id;
+use deno_core::error::custom_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::ZeroCopyBuf;
@@ -15,8 +20,17 @@ use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::DomExceptionQuotaExceededError;
+
 enum Transferable {
   MessagePort(MessagePort),
+  /// A `ReadableStream` is transferred as the port the chunks piped from it
+  /// are received through.
+  ReadableStream(MessagePort),
+  /// A `WritableStream` is transferred as the port the chunks piped into it
+  /// are sent through.
+  WritableStream(MessagePort),
+  ArrayBuffer(Vec<u8>),
 }
 
 type MessagePortMessage = (Vec<u8>, Vec<Transferable>);
@@ -24,6 +38,13 @@ type MessagePortMessage = (Vec<u8>, Vec<Transferable>);
 pub struct MessagePort {
   rx: RefCell<UnboundedReceiver<MessagePortMessage>>,
   tx: UnboundedSender<MessagePortMessage>,
+  /// The number of messages sent through `tx` that the entangled port has not
+  /// received yet.
+  tx_queued: Arc<AtomicUsize>,
+  /// The number of messages waiting in `rx`.
+  rx_queued: Arc<AtomicUsize>,
+  /// If set, sending fails while this many messages are queued.
+  high_water_mark: Option<usize>,
 }
 
 impl MessagePort {
@@ -32,16 +53,38 @@ impl MessagePort {
     state: &mut OpState,
     data: JsMessageData,
   ) -> Result<(), AnyError> {
+    if let Some(high_water_mark) = self.high_water_mark {
+      if self.queued_count() >= high_water_mark {
+        return Err(
+          DomExceptionQuotaExceededError::new("Message port queue is full")
+            .into(),
+        );
+      }
+    }
+
     let transferables =
       deserialize_js_transferables(state, data.transferables)?;
 
     // Swallow the failed to send error. It means the channel was disentangled,
     // but not cleaned up.
-    self.tx.send((data.data.to_vec(), transferables)).ok();
+    self.tx_queued.fetch_add(1, Ordering::SeqCst);
+    if self.tx.send((data.data.to_vec(), transferables)).is_err() {
+      self.tx_queued.fetch_sub(1, Ordering::SeqCst);
+    }
 
     Ok(())
   }
 
+  /// Returns the number of messages sent through this port that the entangled
+  /// port has not received yet. Once the entangled port is closed, the
+  /// messages are never received, so none count as queued.
+  pub fn queued_count(&self) -> usize {
+    if self.tx.is_closed() {
+      return 0;
+    }
+    self.tx_queued.load(Ordering::SeqCst)
+  }
+
   pub async fn recv(
     &self,
     state: Rc<RefCell<OpState>>,
@@ -51,6 +94,7 @@ impl MessagePort {
       .try_borrow_mut()
       .map_err(|_| type_error("Port receiver is already borrowed"))?;
     if let Some((data, transferables)) = rx.recv().await {
+      self.rx_queued.fetch_sub(1, Ordering::SeqCst);
       let js_transferables =
         serialize_transferables(&mut state.borrow_mut(), transferables);
       return Ok(Some(JsMessageData {
@@ -62,18 +106,30 @@ impl MessagePort {
   }
 }
 
-pub fn create_entangled_message_port() -> (MessagePort, MessagePort) {
+/// Creates a pair of entangled ports. If `high_water_mark` is set, each port
+/// refuses to send while that many of its messages are still queued.
+pub fn create_entangled_message_port(
+  high_water_mark: Option<usize>,
+) -> (MessagePort, MessagePort) {
   let (port1_tx, port2_rx) = unbounded_channel::<MessagePortMessage>();
   let (port2_tx, port1_rx) = unbounded_channel::<MessagePortMessage>();
+  let port1_queued = Arc::new(AtomicUsize::new(0));
+  let port2_queued = Arc::new(AtomicUsize::new(0));
 
   let port1 = MessagePort {
     rx: RefCell::new(port1_rx),
     tx: port1_tx,
+    tx_queued: port1_queued.clone(),
+    rx_queued: port2_queued.clone(),
+    high_water_mark,
   };
 
   let port2 = MessagePort {
     rx: RefCell::new(port2_rx),
     tx: port2_tx,
+    tx_queued: port2_queued,
+    rx_queued: port1_queued,
+    high_water_mark,
   };
 
   (port1, port2)
@@ -96,10 +152,13 @@ impl Resource for MessagePortResource {
 
 pub fn op_message_port_create_entangled(
   state: &mut OpState,
-  _: (),
+  high_water_mark: Option<usize>,
   _: (),
 ) -> Result<(ResourceId, ResourceId), AnyError> {
-  let (port1, port2) = create_entangled_message_port();
+  if high_water_mark == Some(0) {
+    return Err(type_error("High water mark must be greater than 0"));
+  }
+  let (port1, port2) = create_entangled_message_port(high_water_mark);
 
   let port1_id = state.resource_table.add(MessagePortResource {
     port: port1,
@@ -119,6 +178,11 @@ pub fn op_message_port_create_entangled(
 pub enum JsTransferable {
   #[serde(rename_all = "camelCase")]
   MessagePort(ResourceId),
+  ReadableStream(ResourceId),
+  WritableStream(ResourceId),
+  /// The contents of a transferred `ArrayBuffer`, which is detached by the
+  /// sender and recreated from them by the receiver.
+  ArrayBuffer(ZeroCopyBuf),
 }
 
 fn deserialize_js_transferables(
@@ -129,20 +193,46 @@ fn deserialize_js_transferables(
   for js_transferable in js_transferables {
     match js_transferable {
       JsTransferable::MessagePort(id) => {
-        let resource = state
-          .resource_table
-          .take::<MessagePortResource>(id)
-          .ok_or_else(|| type_error("Invalid message port transfer"))?;
-        resource.cancel.cancel();
-        let resource = Rc::try_unwrap(resource)
-          .map_err(|_| type_error("Message port is not ready for transfer"))?;
-        transferables.push(Transferable::MessagePort(resource.port));
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::MessagePort(port));
+      }
+      JsTransferable::ReadableStream(id) => {
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::ReadableStream(port));
+      }
+      JsTransferable::WritableStream(id) => {
+        let port = take_message_port(state, id)?;
+        transferables.push(Transferable::WritableStream(port));
+      }
+      JsTransferable::ArrayBuffer(buf) => {
+        transferables.push(Transferable::ArrayBuffer(buf.to_vec()));
       }
     }
   }
   Ok(transferables)
 }
 
+fn take_message_port(
+  state: &mut OpState,
+  id: ResourceId,
+) -> Result<MessagePort, AnyError> {
+  let resource = state
+    .resource_table
+    .take::<MessagePortResource>(id)
+    .ok_or_else(|| type_error("Invalid message port transfer"))?;
+  resource.cancel.cancel();
+  let resource = Rc::try_unwrap(resource)
+    .map_err(|_| type_error("Message port is not ready for transfer"))?;
+  Ok(resource.port)
+}
+
+fn add_message_port(state: &mut OpState, port: MessagePort) -> ResourceId {
+  state.resource_table.add(MessagePortResource {
+    port,
+    cancel: CancelHandle::new(),
+  })
+}
+
 fn serialize_transferables(
   state: &mut OpState,
   transferables: Vec<Transferable>,
@@ -151,12 +241,20 @@ fn serialize_transferables(
   for transferable in transferables {
     match transferable {
       Transferable::MessagePort(port) => {
-        let rid = state.resource_table.add(MessagePortResource {
-          port,
-          cancel: CancelHandle::new(),
-        });
+        let rid = add_message_port(state, port);
         js_transferables.push(JsTransferable::MessagePort(rid));
       }
+      Transferable::ReadableStream(port) => {
+        let rid = add_message_port(state, port);
+        js_transferables.push(JsTransferable::ReadableStream(rid));
+      }
+      Transferable::WritableStream(port) => {
+        let rid = add_message_port(state, port);
+        js_transferables.push(JsTransferable::WritableStream(rid));
+      }
+      Transferable::ArrayBuffer(data) => {
+        js_transferables.push(JsTransferable::ArrayBuffer(data.into()));
+      }
     }
   }
   js_transferables
@@ -174,11 +272,9 @@ pub fn op_message_port_post_message(
   data: JsMessageData,
 ) -> Result<(), AnyError> {
   for js_transferable in &data.transferables {
-    match js_transferable {
-      JsTransferable::MessagePort(id) => {
-        if *id == rid {
-          return Err(type_error("Can not transfer self message port"));
-        }
+    if let JsTransferable::MessagePort(id) = js_transferable {
+      if *id == rid {
+        return Err(type_error("Can not transfer self message port"));
       }
     }
   }
@@ -191,10 +287,25 @@ pub fn op_message_port_post_message(
   resource.port.send(state, data)
 }
 
+pub fn op_message_port_queued_count(
+  state: &mut OpState,
+  rid: ResourceId,
+  _: (),
+) -> Result<usize, AnyError> {
+  let resource = state
+    .resource_table
+    .get::<MessagePortResource>(rid)
+    .ok_or_else(bad_resource_id)?;
+  Ok(resource.port.queued_count())
+}
+
+/// Receives the next message of a port, or `None` once the port is closed. If
+/// `timeout` is given in milliseconds and no message arrives in time, fails
+/// with a `TimedOut` error.
 pub async fn op_message_port_recv_message(
   state: Rc<RefCell<OpState>>,
   rid: ResourceId,
-  _: (),
+  timeout: Option<u64>,
 ) -> Result<Option<JsMessageData>, AnyError> {
   let resource = {
     let state = state.borrow();
@@ -204,5 +315,15 @@ pub async fn op_message_port_recv_message(
     }
   };
   let cancel = RcRef::map(resource.clone(), |r| &r.cancel);
-  resource.port.recv(state.clone()).or_cancel(cancel).await?
+  let recv = resource.port.recv(state.clone()).or_cancel(cancel);
+  let result = match timeout {
+    Some(timeout) => {
+      let timeout = Duration::from_millis(timeout);
+      tokio::time::timeout(timeout, recv).await.map_err(|_| {
+        custom_error("TimedOut", "Receiving a message timed out")
+      })?
+    }
+    None => recv.await,
+  };
+  result?
 }
@@ -4981,6 +4981,189 @@
   }
   GPUObjectBaseMixin("GPUQuerySet", GPUQuerySet);
 
+  const _configuration = Symbol("[[configuration]]");
+
+  /** The formats supported by `GPUPresentationContext`, which all have four
+   * bytes per pixel. */
+  const presentationFormats = [
+    "rgba8unorm",
+    "rgba8unorm-srgb",
+    "bgra8unorm",
+    "bgra8unorm-srgb",
+  ];
+
+  /**
+   * A presentation surface which isn't backed by a window. Frames are
+   * rendered to the texture returned by `getCurrentTexture()` and read back
+   * with `readFrame()` or `readFrameAsPng()`.
+   */
+  class GPUPresentationContext {
+    /** @type {{ device: GPUDevice, format: GPUTextureFormat, width: number, height: number, bytesPerRow: number } | undefined} */
+    [_configuration];
+    /** @type {GPUTexture | undefined} */
+    [_texture];
+
+    constructor() {
+      this[webidl.brand] = webidl.brand;
+    }
+
+    /**
+     * @param {GPUPresentationConfiguration} configuration
+     */
+    configure(configuration) {
+      webidl.assertBranded(this, GPUPresentationContext);
+      const prefix =
+        "Failed to execute 'configure' on 'GPUPresentationContext'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      configuration = webidl.converters.GPUPresentationConfiguration(
+        configuration,
+        { prefix, context: "Argument 1" },
+      );
+      const { device, format, usage } = configuration;
+      if (!presentationFormats.includes(format)) {
+        throw new TypeError(
+          `${prefix}: Unsupported presentation format '${format}'.`,
+        );
+      }
+      const size = normalizeGPUExtent3D(configuration.size);
+      const width = size.width;
+      const height = size.height ?? 1;
+      // Rows of a texture copied to a buffer have to be aligned to 256 bytes.
+      const bytesPerRow = Math.ceil((width * 4) / 256) * 256;
+
+      this.unconfigure();
+      this[_texture] = device.createTexture({
+        label: "GPUPresentationContext",
+        size: { width, height },
+        format,
+        usage: usage | GPUTextureUsage.COPY_SRC,
+      });
+      this[_configuration] = { device, format, width, height, bytesPerRow };
+    }
+
+    unconfigure() {
+      webidl.assertBranded(this, GPUPresentationContext);
+      this[_texture]?.destroy();
+      this[_texture] = undefined;
+      this[_configuration] = undefined;
+    }
+
+    /**
+     * @param {GPUAdapter} adapter
+     * @returns {GPUTextureFormat}
+     */
+    getPreferredFormat(adapter) {
+      webidl.assertBranded(this, GPUPresentationContext);
+      const prefix =
+        "Failed to execute 'getPreferredFormat' on 'GPUPresentationContext'";
+      webidl.requiredArguments(arguments.length, 1, { prefix });
+      webidl.converters.GPUAdapter(adapter, {
+        prefix,
+        context: "Argument 1",
+      });
+      return "rgba8unorm-srgb";
+    }
+
+    /**
+     * @returns {GPUTexture}
+     */
+    getCurrentTexture() {
+      webidl.assertBranded(this, GPUPresentationContext);
+      const prefix =
+        "Failed to execute 'getCurrentTexture' on 'GPUPresentationContext'";
+      assertConfigured(this, prefix);
+      return this[_texture];
+    }
+
+    /**
+     * Reads back the current texture as tightly packed pixels in the
+     * configured format, row by row from the top. Every call reads back into
+     * a buffer of its own, so that calls which overlap don't map the same
+     * buffer.
+     * @returns {Promise<Uint8Array>}
+     */
+    async readFrame() {
+      webidl.assertBranded(this, GPUPresentationContext);
+      const prefix =
+        "Failed to execute 'readFrame' on 'GPUPresentationContext'";
+      const { device, width, height, bytesPerRow } = assertConfigured(
+        this,
+        prefix,
+      );
+      const texture = this[_texture];
+      const buffer = device.createBuffer({
+        label: "GPUPresentationContext readback",
+        size: bytesPerRow * height,
+        usage: GPUBufferUsage.MAP_READ | GPUBufferUsage.COPY_DST,
+      });
+
+      const encoder = device.createCommandEncoder();
+      encoder.copyTextureToBuffer(
+        { texture },
+        { buffer, bytesPerRow, rowsPerImage: 0 },
+        { width, height },
+      );
+      device.queue.submit([encoder.finish()]);
+
+      await buffer.mapAsync(GPUMapMode.READ);
+      const padded = new Uint8Array(buffer.getMappedRange());
+      const rowSize = width * 4;
+      const frame = new Uint8Array(rowSize * height);
+      for (let y = 0; y < height; y++) {
+        frame.set(
+          padded.subarray(y * bytesPerRow, y * bytesPerRow + rowSize),
+          y * rowSize,
+        );
+      }
+      buffer.unmap();
+      buffer.destroy();
+      return frame;
+    }
+
+    /**
+     * Reads back the current texture and encodes it as a PNG image.
+     * @returns {Promise<Uint8Array>}
+     */
+    async readFrameAsPng() {
+      webidl.assertBranded(this, GPUPresentationContext);
+      const prefix =
+        "Failed to execute 'readFrameAsPng' on 'GPUPresentationContext'";
+      const { format, width, height } = assertConfigured(this, prefix);
+      const frame = await this.readFrame();
+      return core.opSync(
+        "op_webgpu_encode_png",
+        { width, height, format },
+        frame,
+      );
+    }
+
+    [Symbol.for("Deno.customInspect")](inspect) {
+      const configuration = this[_configuration];
+      return `${this.constructor.name} ${
+        inspect({
+          format: configuration?.format,
+          width: configuration?.width,
+          height: configuration?.height,
+        })
+      }`;
+    }
+  }
+
+  /**
+   * @param {GPUPresentationContext} context
+   * @param {string} prefix
+   */
+  function assertConfigured(context, prefix) {
+    const configuration = context[_configuration];
+    if (configuration === undefined) {
+      throw new DOMException(
+        `${prefix}: the context is not configured.`,
+        "InvalidStateError",
+      );
+    }
+    return configuration;
+  }
+
   window.__bootstrap.webgpu = {
     gpu: webidl.createBranded(GPU),
     GPU,
@@ -5011,6 +5194,7 @@
     GPURenderBundleEncoder,
     GPURenderBundle,
     GPUQuerySet,
+    GPUPresentationContext,
     GPUOutOfMemoryError,
     GPUValidationError,
   };
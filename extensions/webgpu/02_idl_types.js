@@ -36,6 +36,7 @@
     GPURenderBundleEncoder,
     GPURenderBundle,
     GPUQuerySet,
+    GPUPresentationContext,
     GPUOutOfMemoryError,
     GPUValidationError,
   } = window.__bootstrap.webgpu;
@@ -1924,4 +1925,39 @@
 
   // TYPEDEF: GPUFlagsConstant
   webidl.converters["GPUFlagsConstant"] = webidl.converters["unsigned long"];
+
+  // INTERFACE: GPUPresentationContext
+  webidl.converters.GPUPresentationContext = webidl.createInterfaceConverter(
+    "GPUPresentationContext",
+    GPUPresentationContext,
+  );
+
+  // DICTIONARY: GPUPresentationConfiguration
+  const dictMembersGPUPresentationConfiguration = [
+    {
+      key: "device",
+      converter: webidl.converters["GPUDevice"],
+      required: true,
+    },
+    {
+      key: "format",
+      converter: webidl.converters["GPUTextureFormat"],
+      required: true,
+    },
+    {
+      key: "usage",
+      converter: webidl.converters["GPUTextureUsageFlags"],
+      defaultValue: 0x10,
+    },
+    {
+      key: "size",
+      converter: webidl.converters["GPUExtent3D"],
+      required: true,
+    },
+  ];
+  webidl.converters["GPUPresentationConfiguration"] = webidl
+    .createDictionaryConverter(
+      "GPUPresentationConfiguration",
+      dictMembersGPUPresentationConfiguration,
+    );
 })(this);
@@ -1050,6 +1050,32 @@ declare class GPUQuerySet implements GPUObjectBase {
   destroy(): undefined;
 }
 
+/** A presentation surface which isn't backed by a window. Frames are
+ * rendered to the texture returned by `getCurrentTexture()` and read back
+ * with `readFrame()` or `readFrameAsPng()`. */
+declare class GPUPresentationContext {
+  constructor();
+
+  configure(configuration: GPUPresentationConfiguration): undefined;
+  unconfigure(): undefined;
+  getPreferredFormat(adapter: GPUAdapter): GPUTextureFormat;
+  getCurrentTexture(): GPUTexture;
+  /** Reads back the current texture as tightly packed pixels in the
+   * configured format, row by row from the top. */
+  readFrame(): Promise<Uint8Array>;
+  /** Reads back the current texture and encodes it as a PNG image. */
+  readFrameAsPng(): Promise<Uint8Array>;
+}
+
+declare interface GPUPresentationConfiguration {
+  device: GPUDevice;
+  /** One of "rgba8unorm", "rgba8unorm-srgb", "bgra8unorm" and
+   * "bgra8unorm-srgb". */
+  format: GPUTextureFormat;
+  usage?: GPUTextureUsageFlags;
+  size: GPUExtent3D;
+}
+
 declare interface GPUQuerySetDescriptor extends GPUObjectDescriptorBase {
   type: GPUQueryType;
   count: number;
@@ -65,6 +65,7 @@ pub mod command_encoder;
 pub mod compute_pass;
 pub mod error;
 pub mod pipeline;
+pub mod presentation;
 pub mod queue;
 pub mod render_pass;
 pub mod sampler;
@@ -124,6 +125,7 @@ pub fn init(unstable: bool) -> Extension {
       state.put(Unstable(unstable));
       Ok(())
     })
+    .error_class(error::get_error_class_name)
     .build()
 }
 
@@ -895,5 +897,10 @@ fn declare_webgpu_ops() -> Vec<(&'static str, Box<OpFn>)> {
       "op_webgpu_create_shader_module",
       op_sync(shader::op_webgpu_create_shader_module),
     ),
+    // presentation
+    (
+      "op_webgpu_encode_png",
+      op_sync(presentation::op_webgpu_encode_png),
+    ),
   ]
 }
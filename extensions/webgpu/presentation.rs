@@ -0,0 +1,80 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::error::null_opbuf;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::OpState;
+use deno_core::ZeroCopyBuf;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use flate2::Crc;
+use serde::Deserialize;
+use std::io::Write;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodePngArgs {
+  width: u32,
+  height: u32,
+  format: String,
+}
+
+/// Encodes a frame of a `GPUPresentationContext`, which is tightly packed
+/// pixels of four bytes in one of the presentation formats, as a PNG image.
+pub fn op_webgpu_encode_png(
+  _state: &mut OpState,
+  args: EncodePngArgs,
+  zero_copy: Option<ZeroCopyBuf>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  let pixels = zero_copy.ok_or_else(null_opbuf)?;
+  if args.width == 0 || args.height == 0 {
+    return Err(type_error("The frame must not be empty"));
+  }
+  let row_size = args.width as usize * 4;
+  if pixels.len() != row_size * args.height as usize {
+    return Err(type_error("The frame does not match its size"));
+  }
+  let bgra = args.format.starts_with("bgra");
+
+  // Every row starts with its filter type, which is 0 (none).
+  let mut encoder = ZlibEncoder::new(
+    Vec::with_capacity(pixels.len() / 2),
+    Compression::default(),
+  );
+  let mut row = vec![0; 1 + row_size];
+  for source in pixels.chunks(row_size) {
+    row[1..].copy_from_slice(source);
+    if bgra {
+      for pixel in row[1..].chunks_mut(4) {
+        pixel.swap(0, 2);
+      }
+    }
+    encoder.write_all(&row)?;
+  }
+  let image_data = encoder.finish()?;
+
+  let mut header = Vec::with_capacity(13);
+  header.extend_from_slice(&args.width.to_be_bytes());
+  header.extend_from_slice(&args.height.to_be_bytes());
+  // A bit depth of 8 with the RGBA color type, the default compression and
+  // filter methods, and no interlacing.
+  header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+  let mut png = PNG_SIGNATURE.to_vec();
+  write_chunk(&mut png, b"IHDR", &header);
+  write_chunk(&mut png, b"IDAT", &image_data);
+  write_chunk(&mut png, b"IEND", &[]);
+  Ok(png.into())
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+  png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+  png.extend_from_slice(kind);
+  png.extend_from_slice(data);
+  let mut crc = Crc::new();
+  crc.update(kind);
+  crc.update(data);
+  png.extend_from_slice(&crc.sum().to_be_bytes());
+}
@@ -39,6 +39,7 @@ pub fn init(origin_storage_dir: Option<PathBuf>) -> Extension {
       }
       Ok(())
     })
+    .error_class(get_not_supported_error_class_name)
     .build()
 }
 
@@ -52,8 +52,10 @@ fn create_runtime_snapshot(snapshot_path: &Path, files: Vec<PathBuf>) {
       None,
     ),
     deno_webstorage::init(None),
+    deno_cache::init(None),
     deno_crypto::init(None),
     deno_webgpu::init(false),
+    deno_ffi::init::<deno_ffi::NoFfiPermissions>(false),
     deno_timers::init::<deno_timers::NoTimersPermission>(),
     deno_broadcast_channel::init(
       deno_broadcast_channel::InMemoryBroadcastChannel::default(),
@@ -4,6 +4,7 @@ use deno_core::error::AnyError;
 use deno_core::FsModuleLoader;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_runtime::deno_web::BlobUrlStore;
+use deno_runtime::ops::os::ExitCode;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
@@ -27,6 +28,7 @@ async fn main() -> Result<(), AnyError> {
     args: vec![],
     debug_flag: false,
     unstable: false,
+    op_latency_metrics: false,
     ca_data: None,
     user_agent: "hello_runtime".to_string(),
     seed: None,
@@ -43,6 +45,8 @@ async fn main() -> Result<(), AnyError> {
     origin_storage_dir: None,
     blob_url_store: BlobUrlStore::default(),
     broadcast_channel: InMemoryBroadcastChannel::default(),
+    shared_array_buffer_store: None,
+    exit_code: ExitCode::default(),
   };
 
   let js_path =
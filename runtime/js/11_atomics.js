@@ -0,0 +1,91 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+"use strict";
+
+((window) => {
+  const core = window.Deno.core;
+  const nativeNotify = Atomics.notify;
+
+  /**
+   * Returns a view of the single element of `typedArray` at `index`, which lets
+   * the ops find the shared memory it occupies.
+   * @param {Int32Array | BigInt64Array} typedArray
+   * @param {number} index
+   * @returns {Uint8Array}
+   */
+  function cellOf(typedArray, index) {
+    const size = typedArray.BYTES_PER_ELEMENT;
+    return new Uint8Array(
+      typedArray.buffer,
+      typedArray.byteOffset + index * size,
+      size,
+    );
+  }
+
+  /**
+   * @param {any} typedArray
+   */
+  function assertSharedIntegerArray(typedArray) {
+    if (
+      !(typedArray instanceof Int32Array ||
+        typedArray instanceof BigInt64Array) ||
+      !(typedArray.buffer instanceof SharedArrayBuffer)
+    ) {
+      throw new TypeError(
+        "Expected an Int32Array or BigInt64Array backed by a SharedArrayBuffer",
+      );
+    }
+  }
+
+  // https://tc39.es/proposal-atomics-wait-async/#sec-atomics.waitasync
+  function waitAsync(typedArray, index, value, timeout = undefined) {
+    assertSharedIntegerArray(typedArray);
+    // Validates the index the way the other `Atomics` functions do.
+    const current = Atomics.load(typedArray, index);
+    index = Math.trunc(Number(index)) || 0;
+    value = typedArray instanceof BigInt64Array ? BigInt(value) : value | 0;
+    timeout = Number(timeout);
+    timeout = Number.isNaN(timeout) ? Infinity : Math.max(timeout, 0);
+
+    if (current !== value) {
+      return { async: false, value: "not-equal" };
+    }
+    if (timeout === 0) {
+      return { async: false, value: "timed-out" };
+    }
+    const promise = core.opAsync(
+      "op_atomics_wait_async",
+      {
+        value: String(value),
+        timeout: timeout === Infinity ? null : timeout,
+      },
+      cellOf(typedArray, index),
+    );
+    return { async: true, value: promise };
+  }
+
+  // Wakes the waits of `waitAsync()` after those of `Atomics.wait()`.
+  function notify(typedArray, index, count = undefined) {
+    const woken = nativeNotify(typedArray, index, count);
+    if (!(typedArray.buffer instanceof SharedArrayBuffer)) {
+      return woken;
+    }
+    index = Math.trunc(Number(index)) || 0;
+    let remaining = Infinity;
+    if (count !== undefined) {
+      remaining = Math.max(Math.trunc(Number(count)) || 0, 0) - woken;
+      if (remaining <= 0) {
+        return woken;
+      }
+    }
+    return woken + core.opSync(
+      "op_atomics_notify",
+      remaining === Infinity ? null : remaining,
+      cellOf(typedArray, index),
+    );
+  }
+
+  window.__bootstrap.atomics = {
+    waitAsync,
+    notify,
+  };
+})(this);
@@ -7,6 +7,8 @@
   const { getLocationHref } = window.__bootstrap.location;
   const { log, pathFromURL } = window.__bootstrap.util;
   const { defineEventHandler } = window.__bootstrap.webUtil;
+  const { deserializeJsMessageData, serializeJsMessageData } =
+    window.__bootstrap.messagePort;
 
   function createWorker(
     specifier,
@@ -14,12 +16,14 @@
     sourceCode,
     useDenoNamespace,
     permissions,
+    resourceLimits,
     name,
   ) {
     return core.opSync("op_create_worker", {
       hasSourceCode,
       name,
       permissions,
+      resourceLimits,
       sourceCode,
       specifier,
       useDenoNamespace,
@@ -90,6 +94,7 @@
    */
   function parsePermissions({
     env = "inherit",
+    ffi = "inherit",
     hrtime = "inherit",
     net = "inherit",
     plugin = "inherit",
@@ -99,6 +104,7 @@
   }) {
     return {
       env: parseUnitPermission(env, "env"),
+      ffi: parseUnitPermission(ffi, "ffi"),
       hrtime: parseUnitPermission(hrtime, "hrtime"),
       net: parseArrayPermission(net, "net"),
       plugin: parseUnitPermission(plugin, "plugin"),
@@ -131,6 +137,7 @@
           // Change this to enable the Deno namespace by default
           namespace: deno,
           permissions: null,
+          resourceLimits: null,
         };
       } else {
         workerDenoAttributes = {
@@ -139,6 +146,7 @@
           permissions: (deno?.permissions ?? "inherit") === "inherit"
             ? null
             : deno?.permissions,
+          resourceLimits: deno?.resourceLimits ?? null,
         };
 
         // If the permission option is set to "none", all permissions
@@ -146,6 +154,7 @@
         if (workerDenoAttributes.permissions === "none") {
           workerDenoAttributes.permissions = {
             env: false,
+            ffi: false,
             hrtime: false,
             net: false,
             plugin: false,
@@ -184,6 +193,7 @@
         workerDenoAttributes.permissions === null
           ? null
           : parsePermissions(workerDenoAttributes.permissions),
+        workerDenoAttributes.resourceLimits,
         options?.name,
       );
       this.#id = id;
@@ -230,7 +240,10 @@
 
         switch (type) {
           case 0: { // Message
-            const msg = core.deserialize(data);
+            const [msg] = deserializeJsMessageData({
+              data,
+              transferables: [],
+            });
             this.#handleMessage(msg);
             break;
           }
@@ -273,8 +286,9 @@
         return;
       }
 
-      const bufferMsg = core.serialize(message);
-      hostPostMessage(this.#id, bufferMsg);
+      // The message port serialization keeps the class and cause of errors.
+      const { data } = serializeJsMessageData(message, []);
+      hostPostMessage(this.#id, data);
     }
 
     terminate() {
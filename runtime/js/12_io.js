@@ -7,6 +7,8 @@
 
 ((window) => {
   const core = window.Deno.core;
+  const { ReadableStream, WritableStream, _resourceBacking } =
+    window.__bootstrap.streams;
   const DEFAULT_BUFFER_SIZE = 32 * 1024;
   // Seek whence values.
   // https://golang.org/pkg/io/#pkg-constants
@@ -167,6 +169,98 @@
     return contents;
   }
 
+  function tryClose(rid) {
+    try {
+      core.close(rid);
+    } catch (_) {
+      // might have already been closed
+    }
+  }
+
+  /**
+   * Creates a readable stream of the chunks read from the resource `rid`. If
+   * `autoClose` is set, the resource is closed once the stream ends, errors or
+   * is cancelled.
+   * @param {number} rid
+   * @param {boolean} autoClose
+   * @returns {ReadableStream<Uint8Array>}
+   */
+  function readableStreamForRid(rid, autoClose = true) {
+    const stream = new ReadableStream({
+      type: "bytes",
+      async pull(controller) {
+        const chunk = new Uint8Array(DEFAULT_BUFFER_SIZE);
+        try {
+          const nread = await read(rid, chunk);
+          if (nread === null) {
+            controller.close();
+            if (autoClose) {
+              tryClose(rid);
+            }
+          } else {
+            controller.enqueue(chunk.subarray(0, nread));
+          }
+        } catch (e) {
+          controller.error(e);
+          if (autoClose) {
+            tryClose(rid);
+          }
+        }
+      },
+      cancel() {
+        if (autoClose) {
+          tryClose(rid);
+        }
+      },
+    });
+    stream[_resourceBacking] = { rid, autoClose };
+    return stream;
+  }
+
+  /**
+   * Creates a writable stream of chunks which are written to the resource
+   * `rid`. If `autoClose` is set, the resource is closed once the stream is
+   * closed, errors or is aborted. Piping a stream created by
+   * `readableStreamForRid()` into it is done by a single op, without the
+   * chunks going through JS.
+   * @param {number} rid
+   * @param {boolean} autoClose
+   * @returns {WritableStream<Uint8Array>}
+   */
+  function writableStreamForRid(rid, autoClose = true) {
+    const stream = new WritableStream({
+      async write(chunk, controller) {
+        try {
+          let nwritten = 0;
+          while (nwritten < chunk.length) {
+            nwritten += await write(rid, chunk.subarray(nwritten));
+          }
+        } catch (e) {
+          controller.error(e);
+          if (autoClose) {
+            tryClose(rid);
+          }
+        }
+      },
+      close() {
+        if (autoClose) {
+          tryClose(rid);
+        }
+      },
+      abort() {
+        if (autoClose) {
+          tryClose(rid);
+        }
+      },
+    });
+    stream[_resourceBacking] = {
+      rid,
+      pipeFrom: (from) =>
+        core.opAsync("op_pipe_resources", { from, to: rid }),
+    };
+    return stream;
+  }
+
   window.__bootstrap.io = {
     iterSync,
     iter,
@@ -178,5 +272,7 @@
     writeSync,
     readAll,
     readAllSync,
+    readableStreamForRid,
+    writableStreamForRid,
   };
 })(this);
@@ -4,7 +4,8 @@
 ((window) => {
   const core = window.Deno.core;
   const { errors } = window.__bootstrap.errors;
-  const { read, write } = window.__bootstrap.io;
+  const { read, write, readableStreamForRid, writableStreamForRid } =
+    window.__bootstrap.io;
 
   function shutdown(rid) {
     return core.opAsync("op_shutdown", rid);
@@ -42,6 +43,8 @@
     #rid = 0;
     #remoteAddr = null;
     #localAddr = null;
+    #readable;
+    #writable;
     constructor(rid, remoteAddr, localAddr) {
       this.#rid = rid;
       this.#remoteAddr = remoteAddr;
@@ -60,6 +63,20 @@
       return this.#localAddr;
     }
 
+    get readable() {
+      if (this.#readable === undefined) {
+        this.#readable = readableStreamForRid(this.rid);
+      }
+      return this.#readable;
+    }
+
+    get writable() {
+      if (this.#writable === undefined) {
+        this.#writable = writableStreamForRid(this.rid);
+      }
+      return this.#writable;
+    }
+
     write(p) {
       return write(this.rid, p);
     }
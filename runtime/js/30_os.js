@@ -20,6 +20,11 @@
     return core.opSync("op_system_memory_info");
   }
 
+  function memoryUsage() {
+    const { heapTotal, heapUsed, external } = core.heapStats();
+    return { rss: core.opSync("op_rss"), heapTotal, heapUsed, external };
+  }
+
   function systemCpuInfo() {
     const { cores, speed } = core.opSync("op_system_cpu_info");
     // Map nulls to undefined for compatibility
@@ -36,7 +41,21 @@
     exitHandler = fn;
   }
 
-  function exit(code = 0) {
+  function getExitCode() {
+    return core.opSync("op_get_exit_code");
+  }
+
+  function setExitCode(code) {
+    if (!Number.isInteger(code)) {
+      throw new TypeError("Exit code must be an integer.");
+    }
+    if (code < -2147483648 || code > 2147483647) {
+      throw new RangeError("Exit code must fit in a 32-bit signed integer.");
+    }
+    core.opSync("op_set_exit_code", code);
+  }
+
+  function exit(code) {
     // Dispatches `unload` only when it's not dispatched yet.
     if (!window[Symbol.for("isUnloadDispatched")]) {
       // Invokes the `unload` hooks before exiting
@@ -44,6 +63,9 @@
       window.dispatchEvent(new Event("unload"));
     }
 
+    // Read after `unload` so that its listeners can still set the code.
+    code ??= getExitCode();
+
     if (exitHandler) {
       exitHandler(code);
       return;
@@ -53,6 +75,18 @@
     throw new Error("Code not reachable");
   }
 
+  let draining = false;
+
+  function drain({ deadline = 5000, code } = {}) {
+    if (draining) {
+      return;
+    }
+    draining = true;
+    // The deadline op doesn't keep the event loop alive, so a process that
+    // drains in time exits on its own before it resolves.
+    core.opAsync("op_drain", deadline).then(() => exit(code));
+  }
+
   function setEnv(key, value) {
     core.opSync("op_set_env", { key, value });
   }
@@ -81,8 +115,12 @@
   window.__bootstrap.os = {
     env,
     execPath,
+    memoryUsage,
     setExitHandler,
     exit,
+    getExitCode,
+    setExitCode,
+    drain,
     osRelease,
     systemMemoryInfo,
     systemCpuInfo,
@@ -24,6 +24,7 @@
    * @property {string=} importMapPath
    * @property {string} rootSpecifier
    * @property {Record<string, string>=} sources
+   * @property {boolean=} splitting
    */
 
   /**
@@ -60,6 +61,7 @@
    * @property {ImportMap=} importMap
    * @property {string=} importMapPath
    * @property {Record<string, string>=} sources
+   * @property {boolean=} splitting
    */
 
   /**
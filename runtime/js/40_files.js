@@ -3,7 +3,14 @@
 
 ((window) => {
   const core = window.Deno.core;
-  const { read, readSync, write, writeSync } = window.__bootstrap.io;
+  const {
+    read,
+    readSync,
+    write,
+    writeSync,
+    readableStreamForRid,
+    writableStreamForRid,
+  } = window.__bootstrap.io;
   const { ftruncate, ftruncateSync, fstat, fstatSync } = window.__bootstrap.fs;
   const { pathFromURL } = window.__bootstrap.util;
 
@@ -71,6 +78,8 @@
 
   class File {
     #rid = 0;
+    #readable;
+    #writable;
 
     constructor(rid) {
       this.#rid = rid;
@@ -80,6 +89,20 @@
       return this.#rid;
     }
 
+    get readable() {
+      if (this.#readable === undefined) {
+        this.#readable = readableStreamForRid(this.rid);
+      }
+      return this.#readable;
+    }
+
+    get writable() {
+      if (this.#writable === undefined) {
+        this.#writable = writableStreamForRid(this.rid);
+      }
+      return this.#writable;
+    }
+
     write(p) {
       return write(this.rid, p);
     }
@@ -126,6 +149,8 @@
   }
 
   class Stdin {
+    #readable;
+
     constructor() {
     }
 
@@ -133,6 +158,13 @@
       return 0;
     }
 
+    get readable() {
+      if (this.#readable === undefined) {
+        this.#readable = readableStreamForRid(this.rid, false);
+      }
+      return this.#readable;
+    }
+
     read(p) {
       return read(this.rid, p);
     }
@@ -147,6 +179,8 @@
   }
 
   class Stdout {
+    #writable;
+
     constructor() {
     }
 
@@ -154,6 +188,13 @@
       return 1;
     }
 
+    get writable() {
+      if (this.#writable === undefined) {
+        this.#writable = writableStreamForRid(this.rid, false);
+      }
+      return this.#writable;
+    }
+
     write(p) {
       return write(this.rid, p);
     }
@@ -168,6 +209,8 @@
   }
 
   class Stderr {
+    #writable;
+
     constructor() {
     }
 
@@ -175,6 +218,13 @@
       return 2;
     }
 
+    get writable() {
+      if (this.#writable === undefined) {
+        this.#writable = writableStreamForRid(this.rid, false);
+      }
+      return this.#writable;
+    }
+
     write(p) {
       return write(this.rid, p);
     }
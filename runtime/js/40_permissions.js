@@ -15,7 +15,7 @@
    * @property {PermissionStatus} status
    */
 
-  /** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "run" | "plugin" | "hrtime">} */
+  /** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "run" | "plugin" | "ffi" | "hrtime">} */
   const permissionNames = [
     "read",
     "write",
@@ -23,6 +23,7 @@
     "env",
     "run",
     "plugin",
+    "ffi",
     "hrtime",
   ];
 
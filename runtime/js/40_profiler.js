@@ -0,0 +1,24 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+"use strict";
+
+((window) => {
+  const core = window.Deno.core;
+  const { pathFromURL } = window.__bootstrap.util;
+
+  function start() {
+    return core.opAsync("op_profiler_start");
+  }
+
+  function stop() {
+    return core.opAsync("op_profiler_stop");
+  }
+
+  async function heapSnapshot(path) {
+    await core.opAsync("op_heap_snapshot", pathFromURL(path));
+  }
+
+  window.__bootstrap.profiler = {
+    Profiler: Object.freeze({ start, stop }),
+    heapSnapshot,
+  };
+})(this);
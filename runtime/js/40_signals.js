@@ -167,6 +167,73 @@
     return new SignalStream(signo);
   }
 
+  // Maps a signal number to the listeners registered for it through
+  // `addSignalListener()`. They share a single signal stream, which is polled
+  // for as long as there are any.
+  const signalListeners = new Map();
+
+  function checkSignalListenerType(listener) {
+    if (typeof listener !== "function") {
+      throw new TypeError(
+        `Signal listener must be a function. "${typeof listener}" is given.`,
+      );
+    }
+  }
+
+  async function pollSignalListeners(signo, rid) {
+    while (true) {
+      let done;
+      try {
+        done = await pollSignal(rid);
+      } catch (error) {
+        if (error instanceof errors.BadResource) {
+          return;
+        }
+        throw error;
+      }
+      const listeners = signalListeners.get(signo);
+      if (done || !listeners) {
+        return;
+      }
+      for (const listener of [...listeners]) {
+        listener();
+      }
+    }
+  }
+
+  function addSignalListener(signo, listener) {
+    if (build.os === "windows") {
+      throw new Error("not implemented!");
+    }
+    checkSignalListenerType(listener);
+    let listeners = signalListeners.get(signo);
+    if (listeners?.has(listener)) {
+      return;
+    }
+    const rid = core.opSync("op_signal_listen", signo);
+    if (!listeners) {
+      listeners = new Set();
+      signalListeners.set(signo, listeners);
+      pollSignalListeners(signo, rid);
+    }
+    listeners.add(listener);
+  }
+
+  function removeSignalListener(signo, listener) {
+    if (build.os === "windows") {
+      throw new Error("not implemented!");
+    }
+    checkSignalListenerType(listener);
+    const listeners = signalListeners.get(signo);
+    if (!listeners?.delete(listener)) {
+      return;
+    }
+    if (listeners.size === 0) {
+      signalListeners.delete(signo);
+    }
+    core.opSync("op_signal_unlisten", signo);
+  }
+
   const signals = {
     alarm() {
       return signal(Signal.SIGALRM);
@@ -265,6 +332,8 @@
   }
 
   window.__bootstrap.signals = {
+    addSignalListener,
+    removeSignalListener,
     signal,
     signals,
     Signal,
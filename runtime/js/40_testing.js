@@ -6,9 +6,18 @@
   const { parsePermissions } = window.__bootstrap.worker;
   const { setExitHandler } = window.__bootstrap.os;
   const { Console, inspectArgs } = window.__bootstrap.console;
-  const { metrics } = window.__bootstrap.metrics;
   const { assert } = window.__bootstrap.util;
 
+  let opCallTracingEnabled = false;
+
+  function opMetricsByOp() {
+    return core.opSync("op_metrics_by_op");
+  }
+
+  function plural(count, word) {
+    return `${count} ${word}${count === 1 ? "" : "s"}`;
+  }
+
   // Wrap test function in additional assertion that makes sure
   // the test case does not leak async "ops" - ie. number of async
   // completed ops after the test is the same as number of dispatched
@@ -16,7 +25,8 @@
   // optional.
   function assertOps(fn) {
     return async function asyncOpSanitizer() {
-      const pre = metrics();
+      const pre = opMetricsByOp();
+      const preTraces = new Set(core.opCallTraces.keys());
       try {
         await fn();
       } finally {
@@ -26,27 +36,57 @@
         await new Promise((resolve) => setTimeout(resolve, 0));
       }
 
-      const post = metrics();
+      const post = opMetricsByOp();
       // We're checking diff because one might spawn HTTP server in the background
       // that will be a pending async op before test starts.
-      const dispatchedDiff = post.opsDispatchedAsync - pre.opsDispatchedAsync;
-      const completedDiff = post.opsCompletedAsync - pre.opsCompletedAsync;
-      assert(
-        dispatchedDiff === completedDiff,
-        `Test case is leaking async ops.
-Before:
-  - dispatched: ${pre.opsDispatchedAsync}
-  - completed: ${pre.opsCompletedAsync}
-After:
-  - dispatched: ${post.opsDispatchedAsync}
-  - completed: ${post.opsCompletedAsync}
+      const details = [];
+      for (const [opName, postMetrics] of Object.entries(post)) {
+        const preMetrics = pre[opName];
+        const dispatched = postMetrics.opsDispatchedAsync -
+          (preMetrics?.opsDispatchedAsync ?? 0);
+        const completed = postMetrics.opsCompletedAsync -
+          (preMetrics?.opsCompletedAsync ?? 0);
+
+        if (dispatched > completed) {
+          const count = plural(dispatched - completed, "async call");
+          details.push(
+            `  - ${count} to "${opName}" started in this test never completed.`,
+          );
+          for (const [promiseId, trace] of core.opCallTraces) {
+            if (trace.opName === opName && !preTraces.has(promiseId)) {
+              details.push(`    Started at:\n${trace.stack}`);
+            }
+          }
+        } else if (completed > dispatched) {
+          const count = plural(completed - dispatched, "async call");
+          details.push(
+            `  - ${count} to "${opName}" completed in this test, but started before it.`,
+          );
+        }
+      }
+
+      if (details.length > 0) {
+        const hint = opCallTracingEnabled
+          ? ""
+          : "\nTo get the call sites of the leaking ops, run again with --trace-ops.";
+        assert(
+          false,
+          `Test case is leaking async ops.
+
+${details.join("\n")}
 
 Make sure to await all promises returned from Deno APIs before
-finishing test case.`,
-      );
+finishing test case.${hint}`,
+        );
+      }
     };
   }
 
+  function formatResource(rid, { name, metadata }) {
+    const info = metadata ? ` ${JSON.stringify(metadata)}` : "";
+    return `${name} (rid ${rid})${info}`;
+  }
+
   // Wrap test function in additional assertion that makes sure
   // the test case does not "leak" resources - ie. resource table after
   // the test has exactly the same contents as before the test.
@@ -54,19 +94,40 @@ finishing test case.`,
     fn,
   ) {
     return async function resourceSanitizer() {
-      const pre = core.resources();
+      const pre = core.resources({ metadata: true });
       await fn();
-      const post = core.resources();
+      const post = core.resources({ metadata: true });
+
+      const details = [];
+      for (const [rid, resource] of Object.entries(post)) {
+        if (pre[rid]?.name !== resource.name) {
+          const desc = formatResource(rid, resource);
+          details.push(`  - ${desc} was opened in this test, but never closed.`);
+          const trace = core.resourceTraces.get(Number(rid));
+          if (trace) {
+            details.push(`    Opened by "${trace.opName}" at:\n${trace.stack}`);
+          }
+        }
+      }
+      for (const [rid, resource] of Object.entries(pre)) {
+        if (post[rid]?.name !== resource.name) {
+          const desc = formatResource(rid, resource);
+          details.push(
+            `  - ${desc} was opened before this test, but closed during it.`,
+          );
+        }
+      }
 
-      const preStr = JSON.stringify(pre, null, 2);
-      const postStr = JSON.stringify(post, null, 2);
+      const hint = opCallTracingEnabled
+        ? ""
+        : "\nTo get the call sites which opened the leaking resources, run again with --trace-ops.";
       const msg = `Test case is leaking resources.
-Before: ${preStr}
-After: ${postStr}
+
+${details.join("\n")}
 
 Make sure to close all open resource handles returned from Deno APIs before
-finishing test case.`;
-      assert(preStr === postStr, msg);
+finishing test case.${hint}`;
+      assert(details.length === 0, msg);
     };
   }
 
@@ -224,7 +285,13 @@ finishing test case.`;
   async function runTests({
     disableLog = false,
     filter = null,
+    traceOps = false,
   } = {}) {
+    if (traceOps) {
+      core.enableOpCallTracing();
+      opCallTracingEnabled = true;
+    }
+
     const originalConsole = globalThis.console;
     if (disableLog) {
       globalThis.console = new Console(() => {});
@@ -12,7 +12,7 @@
       return;
     }
 
-    core.print(`${message} [Enter] `, false);
+    core.print(`${message} [Enter] `, true);
 
     readLineFromStdinSync();
   }
@@ -22,7 +22,7 @@
       return false;
     }
 
-    core.print(`${message} [y/N] `, false);
+    core.print(`${message} [y/N] `, true);
 
     const answer = readLineFromStdinSync();
 
@@ -36,15 +36,27 @@
       return null;
     }
 
-    core.print(`${message} `, false);
+    core.print(`${message} `, true);
 
     if (defaultValue) {
-      core.print(`[${defaultValue}] `, false);
+      core.print(`[${defaultValue}] `, true);
     }
 
-    return readLineFromStdinSync() || defaultValue;
+    const answer = readLineFromStdinSync();
+    // Reaching the end of stdin cancels the prompt, like dismissing the dialog
+    // would in a browser, rather than accepting the default.
+    if (answer === null) {
+      return null;
+    }
+
+    return answer || defaultValue;
   }
 
+  /**
+   * Reads a line from stdin, without the line terminator. Returns null if
+   * stdin is at EOF before anything is read.
+   * @returns {string | null}
+   */
   function readLineFromStdinSync() {
     const c = new Uint8Array(1);
     const buf = [];
@@ -52,6 +64,9 @@
     while (true) {
       const n = stdin.readSync(c);
       if (n === null || n === 0) {
+        if (buf.length === 0) {
+          return null;
+        }
         break;
       }
       if (c[0] === CR) {
@@ -58,6 +58,7 @@
     env: __bootstrap.os.env,
     exit: __bootstrap.os.exit,
     execPath: __bootstrap.os.execPath,
+    memoryUsage: __bootstrap.os.memoryUsage,
     Buffer: __bootstrap.buffer.Buffer,
     readAll: __bootstrap.buffer.readAll,
     readAllSync: __bootstrap.buffer.readAllSync,
@@ -103,16 +104,22 @@
 
   __bootstrap.denoNsUnstable = {
     signal: __bootstrap.signals.signal,
+    addSignalListener: __bootstrap.signals.addSignalListener,
+    removeSignalListener: __bootstrap.signals.removeSignalListener,
     signals: __bootstrap.signals.signals,
     Signal: __bootstrap.signals.Signal,
     SignalStream: __bootstrap.signals.SignalStream,
     emit: __bootstrap.compilerApi.emit,
     openPlugin: __bootstrap.plugins.openPlugin,
+    Profiler: __bootstrap.profiler.Profiler,
+    heapSnapshot: __bootstrap.profiler.heapSnapshot,
+    dlopen: __bootstrap.ffi.dlopen,
     kill: __bootstrap.process.kill,
     setRaw: __bootstrap.tty.setRaw,
     consoleSize: __bootstrap.tty.consoleSize,
     DiagnosticCategory: __bootstrap.diagnostics.DiagnosticCategory,
     loadavg: __bootstrap.os.loadavg,
+    drain: __bootstrap.os.drain,
     hostname: __bootstrap.os.hostname,
     osRelease: __bootstrap.os.osRelease,
     systemMemoryInfo: __bootstrap.os.systemMemoryInfo,
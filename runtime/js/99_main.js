@@ -20,6 +20,7 @@ delete Object.prototype.__proto__;
   const encoding = window.__bootstrap.encoding;
   const Console = window.__bootstrap.console.Console;
   const worker = window.__bootstrap.worker;
+  const atomics = window.__bootstrap.atomics;
   const signals = window.__bootstrap.signals;
   const internals = window.__bootstrap.internals;
   const performance = window.__bootstrap.performance;
@@ -31,6 +32,7 @@ delete Object.prototype.__proto__;
   const webgpu = window.__bootstrap.webgpu;
   const webSocket = window.__bootstrap.webSocket;
   const webStorage = window.__bootstrap.webStorage;
+  const caches = window.__bootstrap.caches;
   const broadcastChannel = window.__bootstrap.broadcastChannel;
   const file = window.__bootstrap.file;
   const formData = window.__bootstrap.formData;
@@ -78,7 +80,10 @@ delete Object.prototype.__proto__;
   const onerror = () => {};
 
   function postMessage(data) {
-    const dataIntArray = core.serialize(data);
+    const { data: dataIntArray } = messagePort.serializeJsMessageData(
+      data,
+      [],
+    );
     core.opSync("op_worker_post_message", null, dataIntArray);
   }
 
@@ -91,7 +96,10 @@ delete Object.prototype.__proto__;
     }
     while (!isClosing) {
       const bufferMsg = await core.opAsync("op_worker_get_message");
-      const data = core.deserialize(bufferMsg);
+      const [data] = messagePort.deserializeJsMessageData({
+        data: bufferMsg,
+        transferables: [],
+      });
 
       const msgEvent = new MessageEvent("message", {
         cancelable: false,
@@ -145,8 +153,22 @@ delete Object.prototype.__proto__;
     return core.opSync("op_main_module");
   }
 
+  // Returns whether the runtime should stop treating an unhandled rejection as
+  // fatal, which listeners of "unhandledrejection" ask for by calling
+  // `preventDefault()`.
+  function promiseRejectionHandler(type, promise, reason) {
+    const event = new PromiseRejectionEvent(type, {
+      cancelable: type === "unhandledrejection",
+      promise,
+      reason,
+    });
+    globalThis.dispatchEvent(event);
+    return event.defaultPrevented;
+  }
+
   function runtimeStart(runtimeOptions, source) {
     core.setMacrotaskCallback(timers.handleTimerMacrotask);
+    core.setPromiseRejectionHandler(promiseRejectionHandler);
     version.setVersions(
       runtimeOptions.denoVersion,
       runtimeOptions.v8Version,
@@ -206,6 +228,12 @@ delete Object.prototype.__proto__;
         return new DOMException(msg, "NotSupported");
       },
     );
+    core.registerErrorBuilder(
+      "DOMExceptionDataError",
+      function DOMExceptionDataError(msg) {
+        return new DOMException(msg, "DataError");
+      },
+    );
     core.registerErrorBuilder(
       "DOMExceptionInvalidCharacterError",
       function DOMExceptionInvalidCharacterError(msg) {
@@ -266,6 +294,8 @@ delete Object.prototype.__proto__;
     ByteLengthQueuingStrategy: util.nonEnumerable(
       streams.ByteLengthQueuingStrategy,
     ),
+    Cache: util.nonEnumerable(caches.Cache),
+    CacheStorage: util.nonEnumerable(caches.CacheStorage),
     CloseEvent: util.nonEnumerable(CloseEvent),
     CountQueuingStrategy: util.nonEnumerable(
       streams.CountQueuingStrategy,
@@ -285,6 +315,7 @@ delete Object.prototype.__proto__;
     PerformanceMark: util.nonEnumerable(performance.PerformanceMark),
     PerformanceMeasure: util.nonEnumerable(performance.PerformanceMeasure),
     ProgressEvent: util.nonEnumerable(ProgressEvent),
+    PromiseRejectionEvent: util.nonEnumerable(PromiseRejectionEvent),
     ReadableStream: util.nonEnumerable(streams.ReadableStream),
     ReadableStreamDefaultReader: util.nonEnumerable(
       streams.ReadableStreamDefaultReader,
@@ -328,6 +359,7 @@ delete Object.prototype.__proto__;
     ),
     crypto: util.readOnly(crypto.crypto),
     Crypto: util.nonEnumerable(crypto.Crypto),
+    CryptoKey: util.nonEnumerable(crypto.CryptoKey),
     SubtleCrypto: util.nonEnumerable(crypto.SubtleCrypto),
     fetch: util.writable(fetch.fetch),
     performance: util.writable(performance.performance),
@@ -362,8 +394,14 @@ delete Object.prototype.__proto__;
     GPURenderBundleEncoder: util.nonEnumerable(webgpu.GPURenderBundleEncoder),
     GPURenderBundle: util.nonEnumerable(webgpu.GPURenderBundle),
     GPUQuerySet: util.nonEnumerable(webgpu.GPUQuerySet),
+    GPUPresentationContext: util.nonEnumerable(webgpu.GPUPresentationContext),
     GPUOutOfMemoryError: util.nonEnumerable(webgpu.GPUOutOfMemoryError),
     GPUValidationError: util.nonEnumerable(webgpu.GPUValidationError),
+    caches: {
+      configurable: true,
+      enumerable: true,
+      get: caches.caches,
+    },
   };
 
   // The console seems to be the only one that should be writable and non-enumerable
@@ -386,6 +424,7 @@ delete Object.prototype.__proto__;
     // TODO(bartlomieju): from MDN docs (https://developer.mozilla.org/en-US/docs/Web/API/WorkerGlobalScope)
     // it seems those two properties should be available to workers as well
     onload: util.writable(null),
+    onbeforeunload: util.writable(null),
     onunload: util.writable(null),
     close: util.writable(windowClose),
     closed: util.getterOnly(() => windowIsClosing),
@@ -427,6 +466,11 @@ delete Object.prototype.__proto__;
 
   let hasBootstrapped = false;
 
+  const atomicsProperties = {
+    waitAsync: util.nonEnumerable(atomics.waitAsync),
+    notify: util.nonEnumerable(atomics.notify),
+  };
+
   function bootstrapMainRuntime(runtimeOptions) {
     if (hasBootstrapped) {
       throw new Error("Worker runtime already bootstrapped");
@@ -438,11 +482,15 @@ delete Object.prototype.__proto__;
     hasBootstrapped = true;
     Object.defineProperties(globalThis, windowOrWorkerGlobalScope);
     Object.defineProperties(globalThis, mainRuntimeGlobalProperties);
+    Object.defineProperties(Atomics, atomicsProperties);
     Object.setPrototypeOf(globalThis, Window.prototype);
     eventTarget.setEventTargetData(globalThis);
 
     defineEventHandler(window, "load", null);
+    defineEventHandler(window, "beforeunload", null);
     defineEventHandler(window, "unload", null);
+    defineEventHandler(window, "unhandledrejection", null);
+    defineEventHandler(window, "rejectionhandled", null);
 
     const isUnloadDispatched = Symbol.for("isUnloadDispatched");
     // Stores the flag for checking whether unload is dispatched or not.
@@ -477,7 +525,6 @@ delete Object.prototype.__proto__;
       [internalSymbol]: internals,
       resources: core.resources,
       close: core.close,
-      memoryUsage: core.memoryUsage,
       ...denoNs,
     };
     Object.defineProperties(finalDenoNs, {
@@ -486,6 +533,12 @@ delete Object.prototype.__proto__;
       noColor: util.readOnly(noColor),
       args: util.readOnly(Object.freeze(args)),
       mainModule: util.getterOnly(opMainModule),
+      exitCode: {
+        enumerable: true,
+        configurable: true,
+        get: os.getExitCode,
+        set: os.setExitCode,
+      },
     });
 
     if (unstableFlag) {
@@ -519,9 +572,13 @@ delete Object.prototype.__proto__;
     Object.defineProperties(globalThis, windowOrWorkerGlobalScope);
     Object.defineProperties(globalThis, workerRuntimeGlobalProperties);
     Object.defineProperties(globalThis, { name: util.readOnly(name) });
+    Object.defineProperties(Atomics, atomicsProperties);
     Object.setPrototypeOf(globalThis, DedicatedWorkerGlobalScope.prototype);
     eventTarget.setEventTargetData(globalThis);
 
+    defineEventHandler(self, "unhandledrejection", null);
+    defineEventHandler(self, "rejectionhandled", null);
+
     runtimeStart(
       runtimeOptions,
       internalName ?? name,
@@ -1,9 +1,11 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
 pub use deno_broadcast_channel;
+pub use deno_cache;
 pub use deno_console;
 pub use deno_crypto;
 pub use deno_fetch;
+pub use deno_ffi;
 pub use deno_timers;
 pub use deno_url;
 pub use deno_web;
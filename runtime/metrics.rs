@@ -8,11 +8,20 @@ use deno_core::serde_json::Value;
 use deno_core::Extension;
 use deno_core::OpState;
 
-pub fn init() -> Extension {
+/// When `track_latencies` is set the latency of every op is recorded in
+/// addition to the counters, which is opt-in because it requires reading the
+/// clock twice per op.
+pub fn init(track_latencies: bool) -> Extension {
   Extension::builder()
-    .ops(vec![("op_metrics", op_sync(op_metrics))])
-    .state(|state| {
-      state.put(RuntimeMetrics::default());
+    .ops(vec![
+      ("op_metrics", op_sync(op_metrics)),
+      ("op_metrics_by_op", op_sync(op_metrics_by_op)),
+    ])
+    .state(move |state| {
+      state.put(RuntimeMetrics {
+        ops: HashMap::new(),
+        track_latencies,
+      });
       Ok(())
     })
     .middleware(metrics_op)
@@ -43,9 +52,22 @@ fn op_metrics(
     ops: json!(maybe_ops),
   })
 }
+
+/// Unlike `op_metrics`, always returns the metrics of each op, for internal
+/// users such as the op sanitizer of the test runner.
+fn op_metrics_by_op(
+  state: &mut OpState,
+  _args: (),
+  _: (),
+) -> Result<Value, AnyError> {
+  let m = state.borrow::<RuntimeMetrics>();
+  Ok(json!(m.ops))
+}
+
 #[derive(Default, Debug)]
 pub struct RuntimeMetrics {
   pub ops: HashMap<&'static str, OpMetrics>,
+  pub track_latencies: bool,
 }
 
 impl RuntimeMetrics {
@@ -84,6 +106,9 @@ pub struct OpMetrics {
   pub bytes_sent_control: u64,
   pub bytes_sent_data: u64,
   pub bytes_received: u64,
+  /// Only recorded with `--op-latency-metrics`, and not combined across ops.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub latency: Option<OpLatency>,
 }
 
 impl OpMetrics {
@@ -141,11 +166,105 @@ impl OpMetrics {
     self.ops_completed_async_unref += 1;
     self.op_completed(bytes_received);
   }
+
+  pub fn record_latency(&mut self, latency: Duration) {
+    self
+      .latency
+      .get_or_insert_with(OpLatency::default)
+      .record(latency);
+  }
+}
+
+/// The number of buckets of `OpLatency`. Bucket `i` counts the latencies
+/// below `2^i` nanoseconds which aren't counted by a lower bucket.
+const LATENCY_BUCKETS: usize = 64;
+
+/// A histogram of the latencies of an op, with buckets of powers of two
+/// nanoseconds. Percentiles are therefore approximated by the upper bound of
+/// the bucket they fall into, which keeps recording a latency cheap and the
+/// memory per op constant.
+#[derive(Debug)]
+pub struct OpLatency {
+  count: u64,
+  total_ns: u64,
+  max_ns: u64,
+  buckets: [u64; LATENCY_BUCKETS],
+}
+
+impl Default for OpLatency {
+  fn default() -> Self {
+    Self {
+      count: 0,
+      total_ns: 0,
+      max_ns: 0,
+      buckets: [0; LATENCY_BUCKETS],
+    }
+  }
+}
+
+impl OpLatency {
+  pub fn record(&mut self, latency: Duration) {
+    let ns = latency.as_nanos().min(u64::MAX as u128) as u64;
+    self.count += 1;
+    self.total_ns = self.total_ns.saturating_add(ns);
+    self.max_ns = self.max_ns.max(ns);
+    let bucket = (64 - ns.leading_zeros() as usize).min(LATENCY_BUCKETS - 1);
+    self.buckets[bucket] += 1;
+  }
+
+  /// Returns the approximate latency in nanoseconds below which `percentile`
+  /// percent of the recorded latencies fall.
+  pub fn percentile_ns(&self, percentile: f64) -> u64 {
+    if self.count == 0 {
+      return 0;
+    }
+    let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0);
+    let mut seen = 0;
+    for (i, count) in self.buckets.iter().enumerate() {
+      seen += count;
+      if seen as f64 >= rank {
+        let upper_bound = if i == 0 { 0 } else { (1u64 << i) - 1 };
+        return upper_bound.min(self.max_ns);
+      }
+    }
+    self.max_ns
+  }
+}
+
+fn ns_to_ms(ns: u64) -> f64 {
+  ns as f64 / 1e6
+}
+
+/// Serializes to a summary of the histogram, with latencies in milliseconds.
+impl Serialize for OpLatency {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let mean_ns = if self.count == 0 {
+      0
+    } else {
+      self.total_ns / self.count
+    };
+    let mut s = serializer.serialize_struct("OpLatency", 7)?;
+    s.serialize_field("count", &self.count)?;
+    s.serialize_field("total", &ns_to_ms(self.total_ns))?;
+    s.serialize_field("mean", &ns_to_ms(mean_ns))?;
+    s.serialize_field("p50", &ns_to_ms(self.percentile_ns(50.0)))?;
+    s.serialize_field("p90", &ns_to_ms(self.percentile_ns(90.0)))?;
+    s.serialize_field("p99", &ns_to_ms(self.percentile_ns(99.0)))?;
+    s.serialize_field("max", &ns_to_ms(self.max_ns))?;
+    s.end()
+  }
 }
 
+use deno_core::serde::ser::SerializeStruct;
+use deno_core::serde::Serializer;
 use deno_core::Op;
 use deno_core::OpFn;
 use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
 
 pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
   Box::new(move |op_state, payload| -> Op {
@@ -160,6 +279,13 @@ pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
     let bytes_sent_control = 0;
     let bytes_sent_data = 0;
 
+    let maybe_start =
+      if op_state.borrow().borrow::<RuntimeMetrics>().track_latencies {
+        Some(Instant::now())
+      } else {
+        None
+      };
+
     let op = (op_fn)(op_state.clone(), payload);
 
     let op_state_ = op_state.clone();
@@ -178,6 +304,9 @@ pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
     match op {
       Op::Sync(result) => {
         metrics.op_sync(bytes_sent_control, bytes_sent_data, 0);
+        if let Some(start) = maybe_start {
+          metrics.record_latency(start.elapsed());
+        }
         Op::Sync(result)
       }
       Op::Async(fut) => {
@@ -188,6 +317,9 @@ pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
             let runtime_metrics = s.borrow_mut::<RuntimeMetrics>();
             let metrics = runtime_metrics.ops.get_mut(name).unwrap();
             metrics.op_completed_async(0);
+            if let Some(start) = maybe_start {
+              metrics.record_latency(start.elapsed());
+            }
           })
           .boxed_local();
         Op::Async(fut)
@@ -200,6 +332,9 @@ pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
             let runtime_metrics = s.borrow_mut::<RuntimeMetrics>();
             let metrics = runtime_metrics.ops.get_mut(name).unwrap();
             metrics.op_completed_async_unref(0);
+            if let Some(start) = maybe_start {
+              metrics.record_latency(start.elapsed());
+            }
           })
           .boxed_local();
         Op::AsyncUnref(fut)
@@ -208,3 +343,32 @@ pub fn metrics_op(name: &'static str, op_fn: Box<OpFn>) -> Box<OpFn> {
     }
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn op_latency_percentiles() {
+    let mut latency = OpLatency::default();
+    assert_eq!(latency.percentile_ns(50.0), 0);
+
+    for _ in 0..90 {
+      latency.record(Duration::from_nanos(100));
+    }
+    for _ in 0..9 {
+      latency.record(Duration::from_micros(10));
+    }
+    latency.record(Duration::from_millis(1));
+
+    assert_eq!(latency.count, 100);
+    assert_eq!(latency.max_ns, 1_000_000);
+    // 100ns falls into the bucket below 128ns.
+    assert_eq!(latency.percentile_ns(50.0), 127);
+    assert_eq!(latency.percentile_ns(90.0), 127);
+    // 10µs falls into the bucket below 16384ns.
+    assert_eq!(latency.percentile_ns(99.0), 16_383);
+    // The upper bound of the last bucket is capped at the maximum.
+    assert_eq!(latency.percentile_ns(100.0), 1_000_000);
+  }
+}
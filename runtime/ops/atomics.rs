@@ -0,0 +1,250 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+//! The waiting part of `Atomics.waitAsync()`. V8 leaves it to the embedder,
+//! so the waiters are kept here and woken by the `Atomics.notify()` that the
+//! runtime installs in place of the built-in one.
+
+use deno_core::error::null_opbuf;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op_async;
+use deno_core::op_sync;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_core::ZeroCopyBuf;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// A pending wait, along with the isolate it belongs to.
+struct Waiter {
+  isolate: u64,
+  tx: oneshot::Sender<()>,
+}
+
+lazy_static::lazy_static! {
+  /// The pending waits of every isolate in the process, by the address of the
+  /// shared memory they wait on, in the order they started waiting.
+  static ref WAITERS: Mutex<HashMap<usize, VecDeque<Waiter>>> =
+    Mutex::new(HashMap::new());
+}
+
+static NEXT_ISOLATE: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies the waits of an isolate in `WAITERS`, and removes them once the
+/// op state of the isolate is dropped. Otherwise the waits that are never
+/// notified would be kept for as long as the process runs.
+struct IsolateWaiters(u64);
+
+impl IsolateWaiters {
+  fn new() -> Self {
+    Self(NEXT_ISOLATE.fetch_add(1, Ordering::Relaxed))
+  }
+}
+
+impl Drop for IsolateWaiters {
+  fn drop(&mut self) {
+    let mut waiters = WAITERS.lock().unwrap();
+    waiters.retain(|_, list| {
+      list.retain(|waiter| waiter.isolate != self.0);
+      !list.is_empty()
+    });
+  }
+}
+
+pub fn init() -> Extension {
+  Extension::builder()
+    .ops(vec![
+      ("op_atomics_wait_async", op_async(op_atomics_wait_async)),
+      ("op_atomics_notify", op_sync(op_atomics_notify)),
+    ])
+    .state(|state| {
+      state.put(IsolateWaiters::new());
+      Ok(())
+    })
+    .build()
+}
+
+/// Loads the element of a `SharedArrayBuffer` that `cell` spans.
+fn load(cell: &[u8]) -> Result<i64, AnyError> {
+  let ptr = cell.as_ptr();
+  // SAFETY: `cell` is a single aligned element of an `Int32Array` or a
+  // `BigInt64Array` in shared memory, which is only accessed atomically.
+  let value = match cell.len() {
+    4 => unsafe { (*(ptr as *const AtomicI32)).load(Ordering::SeqCst) as i64 },
+    8 => unsafe { (*(ptr as *const AtomicI64)).load(Ordering::SeqCst) },
+    _ => return Err(type_error("Invalid atomics cell")),
+  };
+  Ok(value)
+}
+
+#[derive(Deserialize)]
+pub struct WaitAsyncArgs {
+  /// The expected value, as a string so that it can be a `BigInt`.
+  value: String,
+  /// In milliseconds. Waits indefinitely if not set.
+  timeout: Option<f64>,
+}
+
+/// Resolves with "ok" when the cell is notified, or "timed-out". The wait is
+/// registered when the op is called rather than when its future is first
+/// polled, so that a notification right after `Atomics.waitAsync()` isn't
+/// missed. The cell is compared to the expected value while registering, and
+/// a change is taken as a notification that is underway.
+fn op_atomics_wait_async(
+  state: Rc<RefCell<OpState>>,
+  args: WaitAsyncArgs,
+  cell: Option<ZeroCopyBuf>,
+) -> impl Future<Output = Result<&'static str, AnyError>> {
+  let isolate = state.borrow().borrow::<IsolateWaiters>().0;
+  let registration = match cell {
+    Some(cell) => register_waiter(isolate, &args.value, &cell),
+    None => Err(null_opbuf()),
+  };
+  async move {
+    let (address, rx) = match registration? {
+      Some(waiter) => waiter,
+      None => return Ok("ok"),
+    };
+
+    let timeout = match args.timeout.and_then(timeout_duration) {
+      Some(timeout) => timeout,
+      None => {
+        // The sender is only dropped once it has been used.
+        rx.await.ok();
+        return Ok("ok");
+      }
+    };
+    match tokio::time::timeout(timeout, rx).await {
+      Ok(_) => Ok("ok"),
+      Err(_) => {
+        let mut waiters = WAITERS.lock().unwrap();
+        if let Some(list) = waiters.get_mut(&address) {
+          list.retain(|waiter| !waiter.tx.is_closed());
+          if list.is_empty() {
+            waiters.remove(&address);
+          }
+        }
+        Ok("timed-out")
+      }
+    }
+  }
+}
+
+/// Converts a timeout in milliseconds to a duration, or `None` if it is too
+/// long to be represented, which is the same as waiting indefinitely.
+fn timeout_duration(timeout: f64) -> Option<Duration> {
+  let secs = timeout.max(0.0) / 1000.0;
+  if secs < u64::MAX as f64 {
+    Some(Duration::from_secs_f64(secs))
+  } else {
+    None
+  }
+}
+
+/// Returns the address of the cell and the receiver of its notification, or
+/// `None` if the cell no longer holds `value`.
+fn register_waiter(
+  isolate: u64,
+  value: &str,
+  cell: &[u8],
+) -> Result<Option<(usize, oneshot::Receiver<()>)>, AnyError> {
+  let value: i64 = value
+    .parse()
+    .map_err(|_| type_error("Invalid atomics value"))?;
+  let address = cell.as_ptr() as usize;
+
+  let mut waiters = WAITERS.lock().unwrap();
+  if load(cell)? != value {
+    return Ok(None);
+  }
+  let (tx, rx) = oneshot::channel();
+  waiters
+    .entry(address)
+    .or_default()
+    .push_back(Waiter { isolate, tx });
+  Ok(Some((address, rx)))
+}
+
+/// Wakes up to `count` of the waits on the cell, or all of them if `count` is
+/// not set, and returns how many were woken.
+fn op_atomics_notify(
+  _state: &mut OpState,
+  count: Option<usize>,
+  cell: Option<ZeroCopyBuf>,
+) -> Result<usize, AnyError> {
+  let cell = cell.ok_or_else(null_opbuf)?;
+  let address = cell.as_ptr() as usize;
+  let count = count.unwrap_or(usize::MAX);
+
+  let mut waiters = WAITERS.lock().unwrap();
+  let list = match waiters.get_mut(&address) {
+    Some(list) => list,
+    None => return Ok(0),
+  };
+  let mut woken = 0;
+  while woken < count {
+    match list.pop_front() {
+      // Waits that timed out have dropped their receiver.
+      Some(waiter) => {
+        if waiter.tx.send(()).is_ok() {
+          woken += 1;
+        }
+      }
+      None => break,
+    }
+  }
+  if list.is_empty() {
+    waiters.remove(&address);
+  }
+  Ok(woken)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn timeout_duration_of_huge_timeouts() {
+    assert_eq!(timeout_duration(1500.0), Some(Duration::from_millis(1500)));
+    assert_eq!(timeout_duration(-1.0), Some(Duration::from_secs(0)));
+    assert_eq!(timeout_duration(1e300), None);
+    assert_eq!(timeout_duration(f64::INFINITY), None);
+  }
+
+  #[test]
+  fn dropped_isolate_removes_its_waiters() {
+    let cell = AtomicI32::new(0);
+    // SAFETY: the bytes of `cell` are only read atomically, by `load()`.
+    let cell = unsafe {
+      std::slice::from_raw_parts(&cell as *const AtomicI32 as *const u8, 4)
+    };
+    let isolate = IsolateWaiters::new();
+    let other_isolate = IsolateWaiters::new();
+    let (address, _rx) =
+      register_waiter(isolate.0, "0", cell).unwrap().unwrap();
+    let (_, _other_rx) = register_waiter(other_isolate.0, "0", cell)
+      .unwrap()
+      .unwrap();
+    assert_eq!(WAITERS.lock().unwrap()[&address].len(), 2);
+
+    drop(isolate);
+    let waiters = WAITERS.lock().unwrap();
+    assert_eq!(waiters[&address].len(), 1);
+    assert_eq!(waiters[&address][0].isolate, other_isolate.0);
+    drop(waiters);
+
+    drop(other_isolate);
+    assert!(!WAITERS.lock().unwrap().contains_key(&address));
+  }
+}
@@ -159,9 +159,9 @@ fn op_open_sync(
   _: (),
 ) -> Result<ResourceId, AnyError> {
   let (path, open_options) = open_helper(state, args)?;
-  let std_file = open_options.open(path)?;
+  let std_file = open_options.open(&path)?;
   let tokio_file = tokio::fs::File::from_std(std_file);
-  let resource = StdFileResource::fs_file(tokio_file);
+  let resource = StdFileResource::fs_file(tokio_file).with_path(path);
   let rid = state.resource_table.add(resource);
   Ok(rid)
 }
@@ -173,9 +173,9 @@ async fn op_open_async(
 ) -> Result<ResourceId, AnyError> {
   let (path, open_options) = open_helper(&mut state.borrow_mut(), args)?;
   let tokio_file = tokio::fs::OpenOptions::from(open_options)
-    .open(path)
+    .open(&path)
     .await?;
-  let resource = StdFileResource::fs_file(tokio_file);
+  let resource = StdFileResource::fs_file(tokio_file).with_path(path);
   let rid = state.borrow_mut().resource_table.add(resource);
   Ok(rid)
 }
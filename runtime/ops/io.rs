@@ -7,6 +7,8 @@ use deno_core::error::AnyError;
 use deno_core::error::{bad_resource_id, not_supported};
 use deno_core::op_async;
 use deno_core::op_sync;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::AsyncMutFuture;
 use deno_core::AsyncRefCell;
 use deno_core::CancelHandle;
@@ -17,10 +19,13 @@ use deno_core::RcRef;
 use deno_core::Resource;
 use deno_core::ResourceId;
 use deno_core::ZeroCopyBuf;
+use deno_fetch::FetchResponseBodyResource;
+use serde::Deserialize;
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::io::Read;
 use std::io::Write;
+use std::path::PathBuf;
 use std::rc::Rc;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
@@ -101,6 +106,7 @@ pub fn init() -> Extension {
       ("op_read_sync", op_sync(op_read_sync)),
       ("op_write_sync", op_sync(op_write_sync)),
       ("op_shutdown", op_async(op_shutdown)),
+      ("op_pipe_resources", op_async(op_pipe_resources)),
     ])
     .build()
 }
@@ -248,6 +254,7 @@ pub struct FullDuplexResource<R, W> {
   // canceled, while 'write' ops are allowed to complete. Therefore only
   // 'read' futures should be attached to this cancel handle.
   cancel_handle: CancelHandle,
+  metadata: Option<Value>,
 }
 
 impl<R, W> FullDuplexResource<R, W>
@@ -260,9 +267,17 @@ where
       rd: rd.into(),
       wr: wr.into(),
       cancel_handle: Default::default(),
+      metadata: None,
     }
   }
 
+  /// Sets the details reported by `Resource::metadata()`, like the addresses
+  /// of the connection.
+  pub fn with_metadata(mut self, metadata: Value) -> Self {
+    self.metadata = Some(metadata);
+    self
+  }
+
   pub fn into_inner(self) -> (R, W) {
     (self.rd.into_inner(), self.wr.into_inner())
   }
@@ -342,6 +357,10 @@ impl Resource for TcpStreamResource {
     "tcpStream".into()
   }
 
+  fn metadata(&self) -> Option<Value> {
+    self.metadata.clone()
+  }
+
   fn close(self: Rc<Self>) {
     self.cancel_read_ops();
   }
@@ -354,6 +373,10 @@ impl Resource for TlsStreamResource {
     "tlsStream".into()
   }
 
+  fn metadata(&self) -> Option<Value> {
+    self.metadata.clone()
+  }
+
   fn close(self: Rc<Self>) {
     self.cancel_read_ops();
   }
@@ -364,7 +387,9 @@ pub type UnixStreamResource =
   FullDuplexResource<unix::OwnedReadHalf, unix::OwnedWriteHalf>;
 
 #[cfg(not(unix))]
-struct UnixStreamResource;
+struct UnixStreamResource {
+  metadata: Option<Value>,
+}
 
 #[cfg(not(unix))]
 impl UnixStreamResource {
@@ -387,6 +412,10 @@ impl Resource for UnixStreamResource {
     "unixStream".into()
   }
 
+  fn metadata(&self) -> Option<Value> {
+    self.metadata.clone()
+  }
+
   fn close(self: Rc<Self>) {
     self.cancel_read_ops();
   }
@@ -398,6 +427,7 @@ pub struct StdFileResource {
     Option<AsyncRefCell<(Option<tokio::fs::File>, Option<FileMetadata>)>>,
   cancel: CancelHandle,
   name: String,
+  path: Option<PathBuf>,
 }
 
 impl StdFileResource {
@@ -423,6 +453,13 @@ impl StdFileResource {
     }
   }
 
+  /// Sets the path the file was opened with, reported by
+  /// `Resource::metadata()`.
+  pub fn with_path(mut self, path: PathBuf) -> Self {
+    self.path = Some(path);
+    self
+  }
+
   async fn read(self: &Rc<Self>, buf: &mut [u8]) -> Result<usize, AnyError> {
     if self.fs_file.is_some() {
       let mut fs_file = RcRef::map(&*self, |r| r.fs_file.as_ref().unwrap())
@@ -504,6 +541,11 @@ impl Resource for StdFileResource {
     self.name.as_str().into()
   }
 
+  fn metadata(&self) -> Option<Value> {
+    let path = self.path.as_ref()?;
+    Some(json!({ "path": path.to_string_lossy() }))
+  }
+
   fn close(self: Rc<Self>) {
     // TODO: do not cancel file I/O when file is writable.
     self.cancel.cancel()
@@ -536,6 +578,14 @@ async fn op_read_async(
     .resource_table
     .get_any(rid)
     .ok_or_else(bad_resource_id)?;
+  let nread = read_resource(&resource, buf).await?;
+  Ok(nread as u32)
+}
+
+async fn read_resource(
+  resource: &Rc<dyn Resource>,
+  buf: &mut [u8],
+) -> Result<usize, AnyError> {
   let nread = if let Some(s) = resource.downcast_rc::<ChildStdoutResource>() {
     s.read(buf).await?
   } else if let Some(s) = resource.downcast_rc::<ChildStderrResource>() {
@@ -548,10 +598,12 @@ async fn op_read_async(
     s.read(buf).await?
   } else if let Some(s) = resource.downcast_rc::<StdFileResource>() {
     s.read(buf).await?
+  } else if let Some(s) = resource.downcast_rc::<FetchResponseBodyResource>() {
+    s.read(buf).await?
   } else {
     return Err(not_supported());
   };
-  Ok(nread as u32)
+  Ok(nread)
 }
 
 fn op_write_sync(
@@ -580,6 +632,14 @@ async fn op_write_async(
     .resource_table
     .get_any(rid)
     .ok_or_else(bad_resource_id)?;
+  let nwritten = write_resource(&resource, buf).await?;
+  Ok(nwritten as u32)
+}
+
+async fn write_resource(
+  resource: &Rc<dyn Resource>,
+  buf: &[u8],
+) -> Result<usize, AnyError> {
   let nwritten = if let Some(s) = resource.downcast_rc::<ChildStdinResource>() {
     s.write(buf).await?
   } else if let Some(s) = resource.downcast_rc::<TcpStreamResource>() {
@@ -593,7 +653,58 @@ async fn op_write_async(
   } else {
     return Err(not_supported());
   };
-  Ok(nwritten as u32)
+  Ok(nwritten)
+}
+
+/// The size of the buffer `op_pipe_resources()` reads into, which is the
+/// largest possible size of a single packet on a TLS stream.
+const PIPE_BUFFER_SIZE: usize = 16 * 1024 + 256;
+
+#[derive(Deserialize)]
+pub struct PipeResourcesArgs {
+  from: ResourceId,
+  to: ResourceId,
+}
+
+/// Writes everything read from the resource `from` to the resource `to` and
+/// returns the number of bytes copied. This is used by `pipeTo()` when both
+/// streams are backed by resources, so that chunks don't go through JS. The
+/// resources are not closed, which is left to the streams.
+async fn op_pipe_resources(
+  state: Rc<RefCell<OpState>>,
+  args: PipeResourcesArgs,
+  _: (),
+) -> Result<u64, AnyError> {
+  let (from, to) = {
+    let state = state.borrow();
+    let from = state
+      .resource_table
+      .get_any(args.from)
+      .ok_or_else(bad_resource_id)?;
+    let to = state
+      .resource_table
+      .get_any(args.to)
+      .ok_or_else(bad_resource_id)?;
+    (from, to)
+  };
+
+  let mut buf = vec![0; PIPE_BUFFER_SIZE];
+  let mut ncopied = 0;
+  loop {
+    let nread = read_resource(&from, &mut buf).await?;
+    if nread == 0 {
+      return Ok(ncopied);
+    }
+    let mut nwritten = 0;
+    while nwritten < nread {
+      let n = write_resource(&to, &buf[nwritten..nread]).await?;
+      if n == 0 {
+        return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+      }
+      nwritten += n;
+    }
+    ncopied += nread as u64;
+  }
 }
 
 async fn op_shutdown(
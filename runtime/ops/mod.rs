@@ -1,5 +1,6 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 
+pub mod atomics;
 pub mod fs;
 pub mod fs_events;
 pub mod http;
@@ -11,6 +12,7 @@ pub mod os;
 pub mod permissions;
 pub mod plugin;
 pub mod process;
+pub mod profiler;
 pub mod runtime;
 pub mod signal;
 pub mod tls;
@@ -11,6 +11,8 @@ use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op_async;
 use deno_core::op_sync;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::AsyncRefCell;
 use deno_core::CancelHandle;
 use deno_core::CancelTryFuture;
@@ -91,6 +93,24 @@ pub struct IpAddr {
   pub port: u16,
 }
 
+/// Describes a TCP connection, or a TLS connection running over one, in the
+/// metadata of its resource.
+pub(crate) fn tcp_metadata(
+  local_addr: &SocketAddr,
+  remote_addr: &SocketAddr,
+) -> Value {
+  json!({
+    "localAddr": OpAddr::Tcp(IpAddr {
+      hostname: local_addr.ip().to_string(),
+      port: local_addr.port(),
+    }),
+    "remoteAddr": OpAddr::Tcp(IpAddr {
+      hostname: remote_addr.ip().to_string(),
+      port: remote_addr.port(),
+    }),
+  })
+}
+
 #[derive(Deserialize)]
 pub(crate) struct AcceptArgs {
   pub rid: ResourceId,
@@ -125,10 +145,10 @@ async fn accept_tcp(
   let local_addr = tcp_stream.local_addr()?;
   let remote_addr = tcp_stream.peer_addr()?;
 
+  let resource = TcpStreamResource::new(tcp_stream.into_split())
+    .with_metadata(tcp_metadata(&local_addr, &remote_addr));
   let mut state = state.borrow_mut();
-  let rid = state
-    .resource_table
-    .add(TcpStreamResource::new(tcp_stream.into_split()));
+  let rid = state.resource_table.add(resource);
   Ok(OpConn {
     rid,
     local_addr: Some(OpAddr::Tcp(IpAddr {
@@ -310,10 +330,10 @@ async fn op_connect(
       let local_addr = tcp_stream.local_addr()?;
       let remote_addr = tcp_stream.peer_addr()?;
 
+      let resource = TcpStreamResource::new(tcp_stream.into_split())
+        .with_metadata(tcp_metadata(&local_addr, &remote_addr));
       let mut state_ = state.borrow_mut();
-      let rid = state_
-        .resource_table
-        .add(TcpStreamResource::new(tcp_stream.into_split()));
+      let rid = state_.resource_table.add(resource);
       Ok(OpConn {
         rid,
         local_addr: Some(OpAddr::Tcp(IpAddr {
@@ -350,7 +370,8 @@ async fn op_connect(
       let remote_addr = unix_stream.peer_addr()?;
 
       let mut state_ = state.borrow_mut();
-      let resource = UnixStreamResource::new(unix_stream.into_split());
+      let resource = UnixStreamResource::new(unix_stream.into_split())
+        .with_metadata(net_unix::unix_metadata(&local_addr, &remote_addr));
       let rid = state_.resource_table.add(resource);
       Ok(OpConn {
         rid,
@@ -11,6 +11,8 @@ use deno_core::error::bad_resource;
 use deno_core::error::custom_error;
 use deno_core::error::null_opbuf;
 use deno_core::error::AnyError;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::AsyncRefCell;
 use deno_core::CancelHandle;
 use deno_core::CancelTryFuture;
@@ -64,6 +66,21 @@ pub struct UnixAddr {
   pub path: Option<String>,
 }
 
+/// Describes a Unix socket connection in the metadata of its resource.
+pub(crate) fn unix_metadata(
+  local_addr: &tokio::net::unix::SocketAddr,
+  remote_addr: &tokio::net::unix::SocketAddr,
+) -> Value {
+  json!({
+    "localAddr": OpAddr::Unix(UnixAddr {
+      path: local_addr.as_pathname().and_then(pathstring),
+    }),
+    "remoteAddr": OpAddr::Unix(UnixAddr {
+      path: remote_addr.as_pathname().and_then(pathstring),
+    }),
+  })
+}
+
 #[derive(Deserialize)]
 pub struct UnixListenArgs {
   pub path: String,
@@ -90,7 +107,8 @@ pub(crate) async fn accept_unix(
 
   let local_addr = unix_stream.local_addr()?;
   let remote_addr = unix_stream.peer_addr()?;
-  let resource = UnixStreamResource::new(unix_stream.into_split());
+  let resource = UnixStreamResource::new(unix_stream.into_split())
+    .with_metadata(unix_metadata(&local_addr, &remote_addr));
   let mut state = state.borrow_mut();
   let rid = state.resource_table.add(resource);
   Ok(OpConn {
@@ -3,19 +3,32 @@
 use super::utils::into_string;
 use crate::permissions::Permissions;
 use deno_core::error::{type_error, AnyError};
+use deno_core::op_async_unref;
 use deno_core::op_sync;
 use deno_core::url::Url;
+use deno_core::v8;
 use deno_core::Extension;
 use deno_core::OpState;
 use serde::Deserialize;
 use serde::Serialize;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub fn init() -> Extension {
+pub fn init(exit_code: ExitCode) -> Extension {
   Extension::builder()
     .ops(vec![
       ("op_exit", op_sync(op_exit)),
+      ("op_get_exit_code", op_sync(op_get_exit_code)),
+      ("op_set_exit_code", op_sync(op_set_exit_code)),
+      ("op_drain", op_async_unref(op_drain)),
+      ("op_unload_prevented", op_sync(op_unload_prevented)),
       ("op_env", op_sync(op_env)),
       ("op_exec_path", op_sync(op_exec_path)),
       ("op_set_env", op_sync(op_set_env)),
@@ -26,7 +39,12 @@ pub fn init() -> Extension {
       ("op_os_release", op_sync(op_os_release)),
       ("op_system_memory_info", op_sync(op_system_memory_info)),
       ("op_system_cpu_info", op_sync(op_system_cpu_info)),
+      ("op_rss", op_sync(op_rss)),
     ])
+    .state(move |state| {
+      state.put::<ExitCode>(exit_code.clone());
+      Ok(())
+    })
     .build()
 }
 
@@ -108,10 +126,99 @@ fn op_delete_env(
   Ok(())
 }
 
-fn op_exit(_state: &mut OpState, code: i32, _: ()) -> Result<(), AnyError> {
+/// Put in the op state by an embedder which has to finish its own work, like
+/// writing a CPU profile, before the process exits. `Deno.exit()` then
+/// terminates the execution of the isolate and records the code instead of
+/// exiting the process.
+pub struct DeferredExit {
+  pub code: Option<i32>,
+  isolate_handle: v8::IsolateHandle,
+}
+
+impl DeferredExit {
+  pub fn new(isolate_handle: v8::IsolateHandle) -> Self {
+    Self {
+      code: None,
+      isolate_handle,
+    }
+  }
+}
+
+fn op_exit(state: &mut OpState, code: i32, _: ()) -> Result<(), AnyError> {
+  if let Some(deferred_exit) = state.try_borrow_mut::<DeferredExit>() {
+    if deferred_exit.code.is_none() {
+      deferred_exit.code = Some(code);
+      deferred_exit.isolate_handle.terminate_execution();
+    }
+    return Ok(());
+  }
   std::process::exit(code)
 }
 
+/// The exit code set through `Deno.exitCode`, which a worker should exit
+/// with when it finishes without an explicit `Deno.exit(code)`. Each worker
+/// has its own, unless its embedder hands the same one to several workers.
+#[derive(Clone, Default)]
+pub struct ExitCode(Arc<AtomicI32>);
+
+impl ExitCode {
+  pub fn get(&self) -> i32 {
+    self.0.load(Ordering::Relaxed)
+  }
+
+  pub fn set(&self, code: i32) {
+    self.0.store(code, Ordering::Relaxed);
+  }
+}
+
+fn op_get_exit_code(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<i32, AnyError> {
+  Ok(state.borrow::<ExitCode>().get())
+}
+
+fn op_set_exit_code(
+  state: &mut OpState,
+  code: i32,
+  _: (),
+) -> Result<(), AnyError> {
+  state.borrow::<ExitCode>().set(code);
+  Ok(())
+}
+
+/// Present in the op state once `Deno.drain()` has been called. A draining
+/// worker no longer lets "beforeunload" listeners keep it alive.
+pub struct Draining;
+
+/// Put in the op state by a "beforeunload" listener canceling the event.
+pub struct UnloadPrevented;
+
+/// Marks the worker as draining and resolves once `deadline` milliseconds
+/// have passed, without keeping the event loop alive until then.
+fn op_drain(
+  state: Rc<RefCell<OpState>>,
+  deadline: u64,
+  _: (),
+) -> impl Future<Output = Result<(), AnyError>> {
+  super::check_unstable2(&state, "Deno.drain");
+  state.borrow_mut().put(Draining);
+  async move {
+    tokio::time::sleep(Duration::from_millis(deadline)).await;
+    Ok(())
+  }
+}
+
+fn op_unload_prevented(
+  state: &mut OpState,
+  _: (),
+  _: (),
+) -> Result<(), AnyError> {
+  state.put(UnloadPrevented);
+  Ok(())
+}
+
 fn op_loadavg(
   state: &mut OpState,
   _args: (),
@@ -200,3 +307,74 @@ fn op_system_cpu_info(
 
   Ok(CpuInfo { cores, speed })
 }
+
+/// The resident set size of the process in bytes, which complements the heap
+/// statistics of the isolate in `Deno.memoryUsage()`.
+fn op_rss(_state: &mut OpState, _args: (), _: ()) -> Result<usize, AnyError> {
+  Ok(rss())
+}
+
+#[cfg(target_os = "linux")]
+fn rss() -> usize {
+  // The second field of /proc/self/statm is the number of resident pages.
+  let resident_pages = std::fs::read_to_string("/proc/self/statm")
+    .ok()
+    .and_then(|statm| {
+      statm
+        .split_whitespace()
+        .nth(1)
+        .and_then(|pages| pages.parse::<usize>().ok())
+    })
+    .unwrap_or(0);
+  let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+  resident_pages * page_size.max(0) as usize
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn rss() -> usize {
+  let mut task_info =
+    std::mem::MaybeUninit::<libc::mach_task_basic_info_data_t>::uninit();
+  let mut count = libc::MACH_TASK_BASIC_INFO_COUNT;
+  let r = unsafe {
+    libc::task_info(
+      libc::mach_task_self(),
+      libc::MACH_TASK_BASIC_INFO,
+      task_info.as_mut_ptr() as libc::task_info_t,
+      &mut count as *mut libc::mach_msg_type_number_t,
+    )
+  };
+  if r != libc::KERN_SUCCESS {
+    return 0;
+  }
+  let task_info = unsafe { task_info.assume_init() };
+  task_info.resident_size as usize
+}
+
+#[cfg(windows)]
+fn rss() -> usize {
+  use winapi::shared::minwindef::DWORD;
+  use winapi::shared::minwindef::FALSE;
+  use winapi::um::processthreadsapi::GetCurrentProcess;
+  use winapi::um::psapi::GetProcessMemoryInfo;
+  use winapi::um::psapi::PROCESS_MEMORY_COUNTERS;
+
+  unsafe {
+    let mut pmc: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+    if GetProcessMemoryInfo(
+      GetCurrentProcess(),
+      &mut pmc,
+      std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as DWORD,
+    ) != FALSE
+    {
+      pmc.WorkingSetSize
+    } else {
+      0
+    }
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn rss() -> usize {
+  0
+}
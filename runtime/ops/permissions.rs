@@ -50,6 +50,7 @@ pub fn op_query_permission(
     "env" => permissions.env.query(args.variable.as_deref()),
     "run" => permissions.run.query(args.command.as_deref()),
     "plugin" => permissions.plugin.query(),
+    "ffi" => permissions.ffi.query(),
     "hrtime" => permissions.hrtime.query(),
     n => {
       return Err(custom_error(
@@ -81,6 +82,7 @@ pub fn op_revoke_permission(
     "env" => permissions.env.revoke(args.variable.as_deref()),
     "run" => permissions.run.revoke(args.command.as_deref()),
     "plugin" => permissions.plugin.revoke(),
+    "ffi" => permissions.ffi.revoke(),
     "hrtime" => permissions.hrtime.revoke(),
     n => {
       return Err(custom_error(
@@ -112,6 +114,7 @@ pub fn op_request_permission(
     "env" => permissions.env.request(args.variable.as_deref()),
     "run" => permissions.run.request(args.command.as_deref()),
     "plugin" => permissions.plugin.request(),
+    "ffi" => permissions.ffi.request(),
     "hrtime" => permissions.hrtime.request(),
     n => {
       return Err(custom_error(
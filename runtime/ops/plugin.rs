@@ -1,11 +1,15 @@
 // Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
 use crate::permissions::Permissions;
+use deno_core::error::generic_error;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op_sync;
 use deno_core::Extension;
 use deno_core::OpState;
+use deno_core::PluginDeclaration;
 use deno_core::Resource;
 use deno_core::ResourceId;
+use deno_core::PLUGIN_DECLARATION_SYMBOL;
 use dlopen::symbor::Library;
 use log::debug;
 use std::borrow::Cow;
@@ -13,15 +17,16 @@ use std::mem;
 use std::path::PathBuf;
 use std::rc::Rc;
 
-/// A default `init` function for plugins which mimics the way the internal
+/// Plugins export a `PluginDeclaration` with `deno_core::declare_plugin!()`,
+/// whose `init` function returns an extension in the same way as the internal
 /// extensions are initalized. Plugins currently do not support all extension
 /// features and are most likely not going to in the future. Currently only
-/// `init_state` and `init_ops` are supported while `init_middleware` and `init_js`
-/// are not. Currently the `PluginResource` does not support being closed due to
-/// certain risks in unloading the dynamic library without unloading dependent
-/// functions and resources.
-pub type InitFn = fn() -> Extension;
-
+/// `init_state`, `init_ops` and `init_error_classes` are supported while
+/// `init_middleware` and `init_js` are not; the JS error classes named by a
+/// plugin have to be registered by user code with
+/// `Deno.core.registerErrorClass()`. Currently the `PluginResource` does not
+/// support being closed due to certain risks in unloading the dynamic library
+/// without unloading dependent functions and resources.
 pub fn init() -> Extension {
   Extension::builder()
     .ops(vec![("op_open_plugin", op_sync(op_open_plugin))])
@@ -40,25 +45,42 @@ pub fn op_open_plugin(
   permissions.plugin.check()?;
 
   debug!("Loading Plugin: {:#?}", filename);
-  let plugin_lib = Library::open(filename).map(Rc::new)?;
+  let plugin_lib = Library::open(&filename).map(Rc::new)?;
   let plugin_resource = PluginResource::new(&plugin_lib);
 
   // Forgets the plugin_lib value to prevent segfaults when the process exits
   mem::forget(plugin_lib);
 
-  let init = *unsafe { plugin_resource.0.symbol::<InitFn>("init") }?;
-  let rid = state.resource_table.add(plugin_resource);
+  let init = {
+    let declaration = unsafe {
+      plugin_resource
+        .0
+        .reference::<PluginDeclaration>(PLUGIN_DECLARATION_SYMBOL)
+    }
+    .map_err(|_| {
+      generic_error(format!(
+        "{} does not declare a plugin, it must be built with deno_core::declare_plugin!()",
+        filename.display()
+      ))
+    })?;
+    declaration.check()?;
+    declaration.init
+  };
   let mut extension = init();
 
   if !extension.init_js().is_empty() {
-    panic!("Plugins do not support loading js");
+    return Err(type_error("Plugins do not support loading js"));
   }
 
   if extension.init_middleware().is_some() {
-    panic!("Plugins do not support middleware");
+    return Err(type_error("Plugins do not support middleware"));
   }
 
+  let rid = state.resource_table.add(plugin_resource);
   extension.init_state(state)?;
+  for error_class_fn in extension.init_error_classes() {
+    state.register_error_class_fn(*error_class_fn);
+  }
   let ops = extension.init_ops().unwrap_or_default();
   for (name, opfn) in ops {
     state.op_table.register_op(name, opfn);
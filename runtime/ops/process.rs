@@ -10,6 +10,8 @@ use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op_async;
 use deno_core::op_sync;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
 use deno_core::AsyncMutFuture;
 use deno_core::AsyncRefCell;
 use deno_core::Extension;
@@ -72,12 +74,17 @@ pub struct RunArgs {
 
 struct ChildResource {
   child: AsyncRefCell<tokio::process::Child>,
+  pid: Option<u32>,
 }
 
 impl Resource for ChildResource {
   fn name(&self) -> Cow<str> {
     "child".into()
   }
+
+  fn metadata(&self) -> Option<Value> {
+    Some(json!({ "pid": self.pid }))
+  }
 }
 
 impl ChildResource {
@@ -178,6 +185,7 @@ fn op_run(
 
   let child_resource = ChildResource {
     child: AsyncRefCell::new(child),
+    pid,
   };
   let child_rid = state.resource_table.add(child_resource);
 
@@ -0,0 +1,133 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use crate::permissions::Permissions;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::futures::channel::mpsc;
+use deno_core::futures::channel::mpsc::UnboundedSender;
+use deno_core::op_async;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::Extension;
+use deno_core::InspectorSessionProxy;
+use deno_core::LocalInspectorSession;
+use deno_core::OpState;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// The sender for new internal sessions of the inspector of the isolate, which
+/// has to be put into the op state once the `JsRuntime` is created.  Internal
+/// sessions don't keep the event loop alive, so a program which never calls
+/// `Deno.Profiler.stop()` still exits.
+pub struct InspectorSessionSender(pub UnboundedSender<InspectorSessionProxy>);
+
+/// The inspector session of a running `Deno.Profiler`.
+struct ProfilerSession(LocalInspectorSession);
+
+pub fn init() -> Extension {
+  Extension::builder()
+    .ops(vec![
+      ("op_profiler_start", op_async(op_profiler_start)),
+      ("op_profiler_stop", op_async(op_profiler_stop)),
+      ("op_heap_snapshot", op_async(op_heap_snapshot)),
+    ])
+    .build()
+}
+
+/// Connects a new internal session through the session sender of the
+/// inspector.
+fn connect_session(
+  state: &Rc<RefCell<OpState>>,
+) -> Result<LocalInspectorSession, AnyError> {
+  let session_sender =
+    state.borrow().borrow::<InspectorSessionSender>().0.clone();
+
+  // The 'outbound' channel carries messages sent to the session.
+  let (outbound_tx, outbound_rx) = mpsc::unbounded();
+  // The 'inbound' channel carries messages received from the session.
+  let (inbound_tx, inbound_rx) = mpsc::unbounded();
+
+  session_sender
+    .unbounded_send(InspectorSessionProxy {
+      tx: outbound_tx,
+      rx: inbound_rx,
+    })
+    .map_err(|_| generic_error("The inspector is not available"))?;
+  Ok(LocalInspectorSession::new(inbound_tx, outbound_rx))
+}
+
+async fn op_profiler_start(
+  state: Rc<RefCell<OpState>>,
+  _args: (),
+  _: (),
+) -> Result<(), AnyError> {
+  super::check_unstable2(&state, "Deno.Profiler.start");
+  if state.borrow().has::<ProfilerSession>() {
+    return Err(generic_error("The profiler is already running"));
+  }
+
+  let mut session = connect_session(&state)?;
+  session.post_message("Profiler.enable", None).await?;
+  session.post_message("Profiler.start", None).await?;
+
+  let mut state = state.borrow_mut();
+  if state.has::<ProfilerSession>() {
+    return Err(generic_error("The profiler is already running"));
+  }
+  state.put(ProfilerSession(session));
+  Ok(())
+}
+
+async fn op_profiler_stop(
+  state: Rc<RefCell<OpState>>,
+  _args: (),
+  _: (),
+) -> Result<Value, AnyError> {
+  super::check_unstable2(&state, "Deno.Profiler.stop");
+  let ProfilerSession(mut session) = state
+    .borrow_mut()
+    .try_take::<ProfilerSession>()
+    .ok_or_else(|| generic_error("The profiler is not running"))?;
+
+  let mut result = session.post_message("Profiler.stop", None).await?;
+  session.post_message("Profiler.disable", None).await?;
+  Ok(result["profile"].take())
+}
+
+async fn op_heap_snapshot(
+  state: Rc<RefCell<OpState>>,
+  path: String,
+  _: (),
+) -> Result<(), AnyError> {
+  super::check_unstable2(&state, "Deno.heapSnapshot");
+  let path = PathBuf::from(path);
+  state
+    .borrow_mut()
+    .borrow_mut::<Permissions>()
+    .write
+    .check(&path)?;
+
+  let mut session = connect_session(&state)?;
+  session.post_message("HeapProfiler.enable", None).await?;
+  session
+    .post_message(
+      "HeapProfiler.takeHeapSnapshot",
+      Some(json!({ "reportProgress": false })),
+    )
+    .await?;
+  session.post_message("HeapProfiler.disable", None).await?;
+
+  // The snapshot is sent in chunks as notifications before the response to
+  // "HeapProfiler.takeHeapSnapshot".
+  let mut snapshot = String::new();
+  for notification in session.notifications() {
+    if notification["method"] == "HeapProfiler.addHeapSnapshotChunk" {
+      if let Some(chunk) = notification["params"]["chunk"].as_str() {
+        snapshot.push_str(chunk);
+      }
+    }
+  }
+  tokio::fs::write(&path, snapshot).await?;
+  Ok(())
+}
@@ -24,6 +24,8 @@ use deno_core::ResourceId;
 #[cfg(unix)]
 use std::borrow::Cow;
 #[cfg(unix)]
+use std::collections::HashMap;
+#[cfg(unix)]
 use tokio::signal::unix::{signal, Signal, SignalKind};
 
 pub fn init() -> Extension {
@@ -32,6 +34,8 @@ pub fn init() -> Extension {
       ("op_signal_bind", op_sync(op_signal_bind)),
       ("op_signal_unbind", op_sync(op_signal_unbind)),
       ("op_signal_poll", op_async(op_signal_poll)),
+      ("op_signal_listen", op_sync(op_signal_listen)),
+      ("op_signal_unlisten", op_sync(op_signal_unlisten)),
     ])
     .build()
 }
@@ -106,6 +110,67 @@ pub fn op_signal_unbind(
   Ok(())
 }
 
+#[cfg(unix)]
+/// Maps each signal number with listeners registered through
+/// `Deno.addSignalListener()` to the signal stream they share, and the number
+/// of those listeners.
+#[derive(Default)]
+struct SignalListenerTable(HashMap<i32, (ResourceId, usize)>);
+
+#[cfg(unix)]
+fn op_signal_listen(
+  state: &mut OpState,
+  signo: i32,
+  _: (),
+) -> Result<ResourceId, AnyError> {
+  super::check_unstable(state, "Deno.addSignalListener");
+  if let Some(entry) = state
+    .try_borrow_mut::<SignalListenerTable>()
+    .and_then(|table| table.0.get_mut(&signo))
+  {
+    entry.1 += 1;
+    return Ok(entry.0);
+  }
+
+  let resource = SignalStreamResource {
+    signal: AsyncRefCell::new(signal(SignalKind::from_raw(signo)).expect("")),
+    cancel: Default::default(),
+  };
+  let rid = state.resource_table.add(resource);
+  if !state.has::<SignalListenerTable>() {
+    state.put(SignalListenerTable::default());
+  }
+  state
+    .borrow_mut::<SignalListenerTable>()
+    .0
+    .insert(signo, (rid, 1));
+  Ok(rid)
+}
+
+#[cfg(unix)]
+fn op_signal_unlisten(
+  state: &mut OpState,
+  signo: i32,
+  _: (),
+) -> Result<(), AnyError> {
+  super::check_unstable(state, "Deno.removeSignalListener");
+  let mut unused_rid = None;
+  if let Some(table) = state.try_borrow_mut::<SignalListenerTable>() {
+    if let Some((rid, count)) = table.0.get_mut(&signo) {
+      *count -= 1;
+      if *count == 0 {
+        unused_rid = Some(*rid);
+        table.0.remove(&signo);
+      }
+    }
+  }
+  // Closing the stream cancels the poll shared by the listeners.
+  if let Some(rid) = unused_rid {
+    state.resource_table.close(rid);
+  }
+  Ok(())
+}
+
 #[cfg(not(unix))]
 pub fn op_signal_bind(
   _state: &mut OpState,
@@ -132,3 +197,21 @@ async fn op_signal_poll(
 ) -> Result<(), AnyError> {
   unimplemented!();
 }
+
+#[cfg(not(unix))]
+fn op_signal_listen(
+  _state: &mut OpState,
+  _args: (),
+  _: (),
+) -> Result<(), AnyError> {
+  unimplemented!();
+}
+
+#[cfg(not(unix))]
+fn op_signal_unlisten(
+  _state: &mut OpState,
+  _args: (),
+  _: (),
+) -> Result<(), AnyError> {
+  unimplemented!();
+}
@@ -5,6 +5,7 @@ pub use webpki;
 
 use crate::ops::io::TcpStreamResource;
 use crate::ops::io::TlsStreamResource;
+use crate::ops::net::tcp_metadata;
 use crate::ops::net::IpAddr;
 use crate::ops::net::OpAddr;
 use crate::ops::net::OpConn;
@@ -743,11 +744,14 @@ async fn op_start_tls(
   let tls_stream =
     TlsStream::new_client_side(tcp_stream, &tls_config, hostname_dns);
 
+  let mut metadata = tcp_metadata(&local_addr, &remote_addr);
+  metadata["serverName"] = hostname.into();
+
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split()).with_metadata(metadata),
+    )
   };
 
   Ok(OpConn {
@@ -811,11 +815,14 @@ async fn op_connect_tls(
   let tls_stream =
     TlsStream::new_client_side(tcp_stream, &tls_config, hostname_dns);
 
+  let mut metadata = tcp_metadata(&local_addr, &remote_addr);
+  metadata["serverName"] = hostname.into();
+
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split()).with_metadata(metadata),
+    )
   };
 
   Ok(OpConn {
@@ -998,9 +1005,10 @@ async fn op_accept_tls(
 
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split())
+        .with_metadata(tcp_metadata(&local_addr, &remote_addr)),
+    )
   };
 
   Ok(OpConn {
@@ -18,6 +18,7 @@ use crate::web_worker::WorkerEvent;
 use crate::web_worker::WorkerId;
 use deno_core::error::custom_error;
 use deno_core::error::null_opbuf;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::op_async;
@@ -49,6 +50,45 @@ pub struct CreateWebWorkerArgs {
   pub permissions: Permissions,
   pub main_module: ModuleSpecifier,
   pub use_deno_namespace: bool,
+  pub resource_limits: WorkerResourceLimits,
+}
+
+/// Limits on the resources a worker can use, set with the
+/// `deno.resourceLimits` option of the `Worker` constructor.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerResourceLimits {
+  /// The size the worker's V8 heap can grow to, in megabytes. A worker that
+  /// exceeds it is terminated with an error event. V8 only takes a limit on
+  /// the young and old generations together, and divides it between them.
+  pub max_heap_size_mb: Option<usize>,
+  /// The stack size of the worker's thread, in megabytes. JavaScript still
+  /// stops recursing at V8's own limit, which can be raised through
+  /// `--v8-flags=--stack-size` as long as it fits in this.
+  pub stack_size_mb: Option<usize>,
+}
+
+impl WorkerResourceLimits {
+  /// Returns the heap size limit in bytes.
+  pub fn max_heap_size(&self) -> Result<Option<usize>, AnyError> {
+    self
+      .max_heap_size_mb
+      .map(|mb| mb_to_bytes(mb, "Worker heap size limit"))
+      .transpose()
+  }
+
+  /// Returns the stack size in bytes.
+  pub fn stack_size(&self) -> Result<Option<usize>, AnyError> {
+    self
+      .stack_size_mb
+      .map(|mb| mb_to_bytes(mb, "Worker stack size"))
+      .transpose()
+  }
+}
+
+fn mb_to_bytes(mb: usize, name: &str) -> Result<usize, AnyError> {
+  mb.checked_mul(1 << 20)
+    .ok_or_else(|| type_error(format!("{} is too large", name)))
 }
 
 pub type CreateWebWorkerCb =
@@ -224,6 +264,7 @@ pub fn create_worker_permissions(
 ) -> Result<Permissions, AnyError> {
   Ok(Permissions {
     env: merge_env_permission(main_perms.env, worker_perms.env)?,
+    ffi: merge_boolean_permission(main_perms.ffi, worker_perms.ffi)?,
     hrtime: merge_boolean_permission(main_perms.hrtime, worker_perms.hrtime)?,
     net: merge_net_permission(main_perms.net, worker_perms.net)?,
     plugin: merge_boolean_permission(main_perms.plugin, worker_perms.plugin)?,
@@ -238,6 +279,8 @@ pub struct PermissionsArg {
   #[serde(default, deserialize_with = "as_unary_env_permission")]
   env: Option<UnaryPermission<EnvDescriptor>>,
   #[serde(default, deserialize_with = "as_permission_state")]
+  ffi: Option<PermissionState>,
+  #[serde(default, deserialize_with = "as_permission_state")]
   hrtime: Option<PermissionState>,
   #[serde(default, deserialize_with = "as_unary_net_permission")]
   net: Option<UnaryPermission<NetDescriptor>>,
@@ -420,6 +463,7 @@ pub struct CreateWorkerArgs {
   has_source_code: bool,
   name: Option<String>,
   permissions: Option<PermissionsArg>,
+  resource_limits: Option<WorkerResourceLimits>,
   source_code: String,
   specifier: String,
   use_deno_namespace: bool,
@@ -450,6 +494,20 @@ fn op_create_worker(
     parent_permissions.clone()
   };
 
+  let resource_limits = if let Some(resource_limits) = args.resource_limits {
+    super::check_unstable(state, "Worker.deno.resourceLimits");
+    if resource_limits.max_heap_size_mb == Some(0) {
+      return Err(type_error("Worker heap size limit must be greater than 0"));
+    }
+    if matches!(resource_limits.stack_size_mb, Some(mb) if mb < 2) {
+      return Err(type_error("Worker stack size must be at least 2 MB"));
+    }
+    resource_limits.max_heap_size()?;
+    resource_limits
+  } else {
+    WorkerResourceLimits::default()
+  };
+
   let worker_id = state.take::<WorkerId>();
   let create_module_loader = state.take::<CreateWebWorkerCbHolder>();
   state.put::<CreateWebWorkerCbHolder>(create_module_loader.clone());
@@ -462,8 +520,11 @@ fn op_create_worker(
     std::sync::mpsc::sync_channel::<Result<WebWorkerHandle, AnyError>>(1);
 
   // Setup new thread
-  let thread_builder =
+  let mut thread_builder =
     std::thread::Builder::new().name(format!("{}", worker_id));
+  if let Some(stack_size) = resource_limits.stack_size()? {
+    thread_builder = thread_builder.stack_size(stack_size);
+  }
 
   // Spawn it
   let join_handle = thread_builder.spawn(move || {
@@ -479,6 +540,7 @@ fn op_create_worker(
       permissions: worker_permissions,
       main_module: module_specifier.clone(),
       use_deno_namespace,
+      resource_limits,
     });
 
     // Send thread safe handle from newly created worker to host thread
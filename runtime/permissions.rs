@@ -21,8 +21,8 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 #[cfg(test)]
 use std::sync::atomic::Ordering;
-#[cfg(test)]
 use std::sync::Mutex;
+use std::sync::RwLock;
 
 const PERMISSION_EMOJI: &str = "⚠️";
 
@@ -795,12 +795,14 @@ pub struct Permissions {
   pub env: UnaryPermission<EnvDescriptor>,
   pub run: UnaryPermission<RunDescriptor>,
   pub plugin: UnitPermission,
+  pub ffi: UnitPermission,
   pub hrtime: UnitPermission,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct PermissionsOptions {
   pub allow_env: Option<Vec<String>>,
+  pub allow_ffi: bool,
   pub allow_hrtime: bool,
   pub allow_net: Option<Vec<String>>,
   pub allow_plugin: bool,
@@ -908,6 +910,15 @@ impl Permissions {
     unit_permission_from_flag_bool(state, "plugin", "open a plugin", prompt)
   }
 
+  pub fn new_ffi(state: bool, prompt: bool) -> UnitPermission {
+    unit_permission_from_flag_bool(
+      state,
+      "ffi",
+      "load a dynamic library",
+      prompt,
+    )
+  }
+
   pub fn new_hrtime(state: bool, prompt: bool) -> UnitPermission {
     unit_permission_from_flag_bool(
       state,
@@ -925,6 +936,7 @@ impl Permissions {
       env: Permissions::new_env(&opts.allow_env, opts.prompt),
       run: Permissions::new_run(&opts.allow_run, opts.prompt),
       plugin: Permissions::new_plugin(opts.allow_plugin, opts.prompt),
+      ffi: Permissions::new_ffi(opts.allow_ffi, opts.prompt),
       hrtime: Permissions::new_hrtime(opts.allow_hrtime, opts.prompt),
     }
   }
@@ -937,6 +949,7 @@ impl Permissions {
       env: Permissions::new_env(&Some(vec![]), false),
       run: Permissions::new_run(&Some(vec![]), false),
       plugin: Permissions::new_plugin(true, false),
+      ffi: Permissions::new_ffi(true, false),
       hrtime: Permissions::new_hrtime(true, false),
     }
   }
@@ -982,6 +995,12 @@ impl deno_timers::TimersPermission for Permissions {
   }
 }
 
+impl deno_ffi::FfiPermissions for Permissions {
+  fn check(&mut self, _path: &str) -> Result<(), AnyError> {
+    self.ffi.check()
+  }
+}
+
 impl deno_websocket::WebSocketPermissions for Permissions {
   fn check_net_url(&mut self, url: &url::Url) -> Result<(), AnyError> {
     self.net.check_url(url)
@@ -1050,10 +1069,44 @@ fn resolved_and_display_path(path: &Path) -> (PathBuf, PathBuf) {
   (resolved_path, display_path)
 }
 
+lazy_static::lazy_static! {
+  static ref PROMPT_PRINTER: RwLock<fn(&str)> = RwLock::new(print_prompt);
+  static ref PROMPTER: Mutex<Option<Box<dyn PermissionPrompter>>> =
+    Mutex::new(None);
+}
+
+fn print_prompt(message: &str) {
+  eprint!("{}", colors::bold(message));
+}
+
+/// Replaces how the messages of permission prompts are printed, for example
+/// to print them as structured log lines. The messages don't end with a
+/// newline, as the answer is read from stdin after printing them.
+pub fn set_prompt_printer(printer: fn(&str)) {
+  *PROMPT_PRINTER.write().unwrap() = printer;
+}
+
+/// Answers permission prompts in place of stdin, for example in the REPL,
+/// whose line editor owns the terminal.
+pub trait PermissionPrompter: Send {
+  /// Returns whether the access described by the message, like
+  /// `read access to "foo.txt"`, is granted.
+  fn prompt(&mut self, message: &str) -> bool;
+}
+
+/// Replaces how permission prompts are answered. Prompts are answered one
+/// at a time, even when they are issued by several workers.
+pub fn set_prompter(prompter: Box<dyn PermissionPrompter>) {
+  *PROMPTER.lock().unwrap() = Some(prompter);
+}
+
 /// Shows the permission prompt and returns the answer according to the user input.
 /// This loops until the user gives the proper input.
 #[cfg(not(test))]
 fn permission_prompt(message: &str) -> bool {
+  if let Some(prompter) = PROMPTER.lock().unwrap().as_mut() {
+    return prompter.prompt(message);
+  }
   if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
     return false;
   };
@@ -1063,7 +1116,8 @@ fn permission_prompt(message: &str) -> bool {
     PERMISSION_EMOJI, message, opts
   );
   // print to stderr so that if deno is > to a file this is still displayed.
-  eprint!("{}", colors::bold(&msg));
+  let print_prompt = *PROMPT_PRINTER.read().unwrap();
+  print_prompt(&msg);
   loop {
     let mut input = String::new();
     let stdin = io::stdin();
@@ -1081,7 +1135,7 @@ fn permission_prompt(message: &str) -> bool {
       _ => {
         // If we don't get a recognized option try again.
         let msg_again = format!("Unrecognized option '{}' {}", ch, opts);
-        eprint!("{}", colors::bold(&msg_again));
+        print_prompt(&msg_again);
       }
     };
   }
@@ -1444,6 +1498,10 @@ mod tests {
         state: PermissionState::Prompt,
         ..Default::default()
       },
+      ffi: UnitPermission {
+        state: PermissionState::Prompt,
+        ..Default::default()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Prompt,
         ..Default::default()
@@ -1475,6 +1533,8 @@ mod tests {
       assert_eq!(perms2.run.query(Some(&"deno".to_string())), PermissionState::Granted);
       assert_eq!(perms1.plugin.query(), PermissionState::Granted);
       assert_eq!(perms2.plugin.query(), PermissionState::Prompt);
+      assert_eq!(perms1.ffi.query(), PermissionState::Granted);
+      assert_eq!(perms2.ffi.query(), PermissionState::Prompt);
       assert_eq!(perms1.hrtime.query(), PermissionState::Granted);
       assert_eq!(perms2.hrtime.query(), PermissionState::Prompt);
     };
@@ -1514,6 +1574,10 @@ mod tests {
       assert_eq!(perms.plugin.request(), PermissionState::Granted);
       set_prompt_result(false);
       assert_eq!(perms.plugin.request(), PermissionState::Granted);
+      set_prompt_result(true);
+      assert_eq!(perms.ffi.request(), PermissionState::Granted);
+      set_prompt_result(false);
+      assert_eq!(perms.ffi.request(), PermissionState::Granted);
       set_prompt_result(false);
       assert_eq!(perms.hrtime.request(), PermissionState::Denied);
       set_prompt_result(true);
@@ -1548,6 +1612,10 @@ mod tests {
         state: PermissionState::Prompt,
         ..Default::default()
       },
+      ffi: UnitPermission {
+        state: PermissionState::Prompt,
+        ..Default::default()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Denied,
         ..Default::default()
@@ -1566,6 +1634,7 @@ mod tests {
       assert_eq!(perms.env.revoke(Some(&"HOME".to_string())), PermissionState::Prompt);
       assert_eq!(perms.run.revoke(Some(&"deno".to_string())), PermissionState::Prompt);
       assert_eq!(perms.plugin.revoke(), PermissionState::Prompt);
+      assert_eq!(perms.ffi.revoke(), PermissionState::Prompt);
       assert_eq!(perms.hrtime.revoke(), PermissionState::Denied);
     };
   }
@@ -1579,6 +1648,7 @@ mod tests {
       env: Permissions::new_env(&None, true),
       run: Permissions::new_run(&None, true),
       plugin: Permissions::new_plugin(false, true),
+      ffi: Permissions::new_ffi(false, true),
       hrtime: Permissions::new_hrtime(false, true),
     };
 
@@ -1632,6 +1702,7 @@ mod tests {
       env: Permissions::new_env(&None, true),
       run: Permissions::new_run(&None, true),
       plugin: Permissions::new_plugin(false, true),
+      ffi: Permissions::new_ffi(false, true),
       hrtime: Permissions::new_hrtime(false, true),
     };
 
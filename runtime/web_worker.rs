@@ -4,9 +4,11 @@ use crate::inspector_server::InspectorServer;
 use crate::js;
 use crate::metrics;
 use crate::ops;
+use crate::ops::worker_host::WorkerResourceLimits;
 use crate::permissions::Permissions;
 use crate::tokio_util::create_basic_runtime;
 use deno_broadcast_channel::InMemoryBroadcastChannel;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::channel::mpsc;
 use deno_core::futures::future::poll_fn;
@@ -26,9 +28,11 @@ use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
+use deno_core::SharedArrayBufferStore;
 use deno_core::ZeroCopyBuf;
 use deno_web::BlobUrlStore;
 use log::debug;
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::env;
 use std::fmt;
@@ -203,6 +207,8 @@ pub struct WebWorker {
   external_handle: WebWorkerHandle,
   pub use_deno_namespace: bool,
   pub main_module: ModuleSpecifier,
+  max_heap_size_mb: Option<usize>,
+  heap_limit_exceeded: Rc<Cell<bool>>,
 }
 
 pub struct WebWorkerOptions {
@@ -210,6 +216,8 @@ pub struct WebWorkerOptions {
   pub args: Vec<String>,
   pub debug_flag: bool,
   pub unstable: bool,
+  /// Sets whether the latencies of ops are recorded for `Deno.metrics()`.
+  pub op_latency_metrics: bool,
   pub ca_data: Option<Vec<u8>>,
   pub user_agent: String,
   pub seed: Option<u64>,
@@ -226,8 +234,14 @@ pub struct WebWorkerOptions {
   /// Sets `Deno.noColor` in JS runtime.
   pub no_color: bool,
   pub get_error_class_fn: Option<GetErrorClassFn>,
+  /// The directory `caches` is stored in, shared with the main worker.
+  pub origin_storage_dir: Option<std::path::PathBuf>,
   pub blob_url_store: BlobUrlStore,
   pub broadcast_channel: InMemoryBroadcastChannel,
+  /// Shared with the workers this one creates, so that they can send each
+  /// other `SharedArrayBuffer`s.
+  pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
+  pub resource_limits: WorkerResourceLimits,
 }
 
 impl WebWorker {
@@ -267,11 +281,13 @@ impl WebWorker {
         options.broadcast_channel.clone(),
         options.unstable,
       ),
+      deno_cache::init(options.origin_storage_dir.clone()),
       deno_crypto::init(options.seed),
       deno_webgpu::init(options.unstable),
+      deno_ffi::init::<Permissions>(options.unstable),
       deno_timers::init::<Permissions>(),
       // Metrics
-      metrics::init(),
+      metrics::init(options.op_latency_metrics),
       // Permissions ext (worker specific state)
       perm_ext,
     ];
@@ -280,6 +296,7 @@ impl WebWorker {
     let runtime_exts = vec![
       ops::web_worker::init(),
       ops::runtime::init(main_module.clone()),
+      ops::atomics::init(),
       ops::worker_host::init(options.create_web_worker_cb.clone()),
       ops::io::init(),
     ];
@@ -290,11 +307,12 @@ impl WebWorker {
         ops::fs_events::init(),
         ops::fs::init(),
         ops::net::init(),
-        ops::os::init(),
+        ops::os::init(ops::os::ExitCode::default()),
         ops::http::init(),
         ops::permissions::init(),
         ops::plugin::init(),
         ops::process::init(),
+        ops::profiler::init(),
         ops::signal::init(),
         ops::tls::init(),
         ops::tty::init(),
@@ -308,15 +326,46 @@ impl WebWorker {
     extensions.extend(runtime_exts);
     extensions.extend(deno_ns_exts); // May be empty
 
+    let max_heap_size_mb = options.resource_limits.max_heap_size_mb;
+    let create_params = options
+      .resource_limits
+      .max_heap_size()
+      .expect("the heap size limit is checked when the worker is created")
+      .map(|size| v8::Isolate::create_params().heap_limits(0, size));
+
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
       module_loader: Some(options.module_loader.clone()),
       startup_snapshot: Some(js::deno_isolate_init()),
       js_error_create_fn: options.js_error_create_fn.clone(),
       get_error_class_fn: options.get_error_class_fn,
       extensions,
+      shared_array_buffer_store: options.shared_array_buffer_store.clone(),
+      create_params,
       ..Default::default()
     });
 
+    // Instead of letting V8 abort the whole process when the worker runs out
+    // of memory, the worker is terminated and its parent gets an error event.
+    let heap_limit_exceeded = Rc::new(Cell::new(false));
+    if max_heap_size_mb.is_some() {
+      let isolate_handle = js_runtime.v8_isolate().thread_safe_handle();
+      let heap_limit_exceeded = heap_limit_exceeded.clone();
+      js_runtime.add_near_heap_limit_callback(
+        move |current_limit, _initial_limit| {
+          heap_limit_exceeded.set(true);
+          isolate_handle.terminate_execution();
+          // Leave room for the termination to unwind the stack.
+          current_limit * 2
+        },
+      );
+    }
+
+    let session_sender = js_runtime.inspector().get_internal_session_sender();
+    js_runtime
+      .op_state()
+      .borrow_mut()
+      .put(ops::profiler::InspectorSessionSender(session_sender));
+
     if let Some(server) = options.maybe_inspector_server.clone() {
       let inspector = js_runtime.inspector();
       let session_sender = inspector.get_session_sender();
@@ -341,7 +390,20 @@ impl WebWorker {
       external_handle,
       use_deno_namespace: options.use_deno_namespace,
       main_module,
+      max_heap_size_mb,
+      heap_limit_exceeded,
+    }
+  }
+
+  /// Returns the error the worker is terminated with if it ran out of memory.
+  fn heap_limit_error(&self) -> Option<AnyError> {
+    if !self.heap_limit_exceeded.get() {
+      return None;
     }
+    Some(generic_error(format!(
+      "Worker terminated: exceeded its heap limit of {} MB",
+      self.max_heap_size_mb.unwrap_or_default()
+    )))
   }
 
   pub fn bootstrap(&mut self, options: &WebWorkerOptions) {
@@ -441,6 +503,17 @@ impl WebWorker {
           return Poll::Ready(Ok(()));
         }
 
+        // Running out of memory is terminal, as the heap limit has been raised
+        // to let the termination unwind.
+        if let Some(e) = self.heap_limit_error() {
+          print_worker_error(e.to_string(), &self.name);
+          self
+            .internal_handle
+            .post_event(WorkerEvent::TerminalError(e))
+            .expect("Failed to post message to host");
+          return Poll::Ready(Ok(()));
+        }
+
         // In case of an error, pass to parent without terminating worker
         if let Err(e) = r {
           print_worker_error(e.to_string(), &self.name);
@@ -511,6 +584,7 @@ pub fn run_web_worker(
   }
 
   if let Err(e) = result {
+    let e = worker.heap_limit_error().unwrap_or(e);
     print_worker_error(e.to_string(), &name);
     internal_handle
       .post_event(WorkerEvent::TerminalError(e))
@@ -540,6 +614,7 @@ mod tests {
       apply_source_maps: false,
       debug_flag: false,
       unstable: false,
+      op_latency_metrics: false,
       ca_data: None,
       user_agent: "x".to_string(),
       seed: None,
@@ -552,8 +627,11 @@ mod tests {
       ts_version: "x".to_string(),
       no_color: true,
       get_error_class_fn: None,
+      origin_storage_dir: None,
       blob_url_store: BlobUrlStore::default(),
       broadcast_channel: InMemoryBroadcastChannel::default(),
+      shared_array_buffer_store: None,
+      resource_limits: WorkerResourceLimits::default(),
     };
 
     let mut worker = WebWorker::from_options(
@@ -23,6 +23,7 @@ use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
+use deno_core::SharedArrayBufferStore;
 use deno_web::BlobUrlStore;
 use log::debug;
 use std::env;
@@ -50,6 +51,8 @@ pub struct WorkerOptions {
   pub args: Vec<String>,
   pub debug_flag: bool,
   pub unstable: bool,
+  /// Sets whether the latencies of ops are recorded for `Deno.metrics()`.
+  pub op_latency_metrics: bool,
   pub ca_data: Option<Vec<u8>>,
   pub user_agent: String,
   pub seed: Option<u64>,
@@ -71,6 +74,11 @@ pub struct WorkerOptions {
   pub origin_storage_dir: Option<std::path::PathBuf>,
   pub blob_url_store: BlobUrlStore,
   pub broadcast_channel: InMemoryBroadcastChannel,
+  /// Shared with the workers this one creates, so that they can send each
+  /// other `SharedArrayBuffer`s.
+  pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
+  /// Where `Deno.exitCode` is stored, so that the embedder can exit with it.
+  pub exit_code: ops::os::ExitCode,
 }
 
 impl MainWorker {
@@ -106,17 +114,20 @@ impl MainWorker {
         options.ca_data.clone(),
       ),
       deno_webstorage::init(options.origin_storage_dir.clone()),
+      deno_cache::init(options.origin_storage_dir.clone()),
       deno_crypto::init(options.seed),
       deno_broadcast_channel::init(
         options.broadcast_channel.clone(),
         options.unstable,
       ),
       deno_webgpu::init(options.unstable),
+      deno_ffi::init::<Permissions>(options.unstable),
       deno_timers::init::<Permissions>(),
       // Metrics
-      metrics::init(),
+      metrics::init(options.op_latency_metrics),
       // Runtime ops
       ops::runtime::init(main_module),
+      ops::atomics::init(),
       ops::worker_host::init(options.create_web_worker_cb.clone()),
       ops::fs_events::init(),
       ops::fs::init(),
@@ -124,10 +135,11 @@ impl MainWorker {
       ops::io::init(),
       ops::io::init_stdio(),
       ops::net::init(),
-      ops::os::init(),
+      ops::os::init(options.exit_code.clone()),
       ops::permissions::init(),
       ops::plugin::init(),
       ops::process::init(),
+      ops::profiler::init(),
       ops::signal::init(),
       ops::tls::init(),
       ops::tty::init(),
@@ -141,9 +153,16 @@ impl MainWorker {
       js_error_create_fn: options.js_error_create_fn.clone(),
       get_error_class_fn: options.get_error_class_fn,
       extensions,
+      shared_array_buffer_store: options.shared_array_buffer_store.clone(),
       ..Default::default()
     });
 
+    let session_sender = js_runtime.inspector().get_internal_session_sender();
+    js_runtime
+      .op_state()
+      .borrow_mut()
+      .put(ops::profiler::InspectorSessionSender(session_sender));
+
     if let Some(server) = options.maybe_inspector_server.clone() {
       let inspector = js_runtime.inspector();
       let session_sender = inspector.get_session_sender();
@@ -237,6 +256,31 @@ impl MainWorker {
     inspector.create_local_session()
   }
 
+  /// Makes `Deno.exit()` terminate the execution of the program instead of
+  /// exiting the process, so that the embedder can finish its own work first.
+  /// The requested exit code is returned by `take_deferred_exit()`.
+  pub fn defer_exit(&mut self) {
+    let isolate_handle = self.js_runtime.v8_isolate().thread_safe_handle();
+    self
+      .js_runtime
+      .op_state()
+      .borrow_mut()
+      .put(ops::os::DeferredExit::new(isolate_handle));
+  }
+
+  /// Returns the code passed to `Deno.exit()` since `defer_exit()` was called,
+  /// if any. The execution stays terminated, so no more JavaScript of the
+  /// program runs, but inspector sessions can still be used through
+  /// `with_event_loop()`.
+  pub fn take_deferred_exit(&mut self) -> Option<i32> {
+    self
+      .js_runtime
+      .op_state()
+      .borrow_mut()
+      .try_take::<ops::os::DeferredExit>()?
+      .code
+  }
+
   pub fn poll_event_loop(
     &mut self,
     cx: &mut Context,
@@ -252,6 +296,24 @@ impl MainWorker {
     poll_fn(|cx| self.poll_event_loop(cx, wait_for_inspector)).await
   }
 
+  /// Runs the event loop until the program is done, which is when it has
+  /// become idle and the "beforeunload" event is not canceled. A listener
+  /// which cancels the event without scheduling more work, like a timer,
+  /// can't keep the program alive.
+  pub async fn run_event_loop_until_unload(
+    &mut self,
+    wait_for_inspector: bool,
+  ) -> Result<(), AnyError> {
+    loop {
+      self.run_event_loop(wait_for_inspector).await?;
+      if !self.dispatch_beforeunload_event(&located_script_name!())?
+        || !self.js_runtime.has_pending_work()
+      {
+        return Ok(());
+      }
+    }
+  }
+
   /// A utility function that runs provided future concurrently with the event loop.
   ///
   /// Useful when using a local inspector session.
@@ -268,6 +330,47 @@ impl MainWorker {
       };
     }
   }
+
+  /// Dispatches the "load" event to the JavaScript runtime.
+  pub fn dispatch_load_event(
+    &mut self,
+    script_name: &str,
+  ) -> Result<(), AnyError> {
+    self.execute_script(script_name, "window.dispatchEvent(new Event('load'))")
+  }
+
+  /// Dispatches the "beforeunload" event to the JavaScript runtime, once the
+  /// event loop has become idle. Returns `true` if a listener canceled the
+  /// event, in which case the event loop should be run again. After
+  /// `Deno.drain()` the event is no longer cancelable.
+  pub fn dispatch_beforeunload_event(
+    &mut self,
+    script_name: &str,
+  ) -> Result<bool, AnyError> {
+    let op_state = self.js_runtime.op_state();
+    let cancelable = !op_state.borrow().has::<ops::os::Draining>();
+    let script = format!(
+      "if (!window.dispatchEvent(new Event('beforeunload', {{
+        cancelable: {},
+      }}))) Deno.core.opSync('op_unload_prevented');",
+      cancelable
+    );
+    self.execute_script(script_name, &script)?;
+    let prevented = op_state
+      .borrow_mut()
+      .try_take::<ops::os::UnloadPrevented>()
+      .is_some();
+    Ok(prevented)
+  }
+
+  /// Dispatches the "unload" event to the JavaScript runtime.
+  pub fn dispatch_unload_event(
+    &mut self,
+    script_name: &str,
+  ) -> Result<(), AnyError> {
+    self
+      .execute_script(script_name, "window.dispatchEvent(new Event('unload'))")
+  }
 }
 
 #[cfg(test)]
@@ -278,13 +381,17 @@ mod tests {
   fn create_test_worker() -> MainWorker {
     let main_module = resolve_url_or_path("./hello.js").unwrap();
     let permissions = Permissions::default();
+    MainWorker::from_options(main_module, permissions, &create_test_options())
+  }
 
-    let options = WorkerOptions {
+  fn create_test_options() -> WorkerOptions {
+    WorkerOptions {
       apply_source_maps: false,
       user_agent: "x".to_string(),
       args: vec![],
       debug_flag: false,
       unstable: false,
+      op_latency_metrics: false,
       ca_data: None,
       seed: None,
       js_error_create_fn: None,
@@ -300,9 +407,35 @@ mod tests {
       origin_storage_dir: None,
       blob_url_store: BlobUrlStore::default(),
       broadcast_channel: InMemoryBroadcastChannel::default(),
-    };
+      shared_array_buffer_store: None,
+      exit_code: ops::os::ExitCode::default(),
+    }
+  }
 
-    MainWorker::from_options(main_module, permissions, &options)
+  #[tokio::test]
+  async fn beforeunload_prevented_without_work() {
+    let mut worker = create_test_worker();
+    worker.bootstrap(&create_test_options());
+    worker
+      .execute_script(
+        "beforeunload.js",
+        r#"
+        globalThis.dispatched = 0;
+        addEventListener("beforeunload", (e) => {
+          globalThis.dispatched++;
+          e.preventDefault();
+          if (globalThis.dispatched < 3) setTimeout(() => {}, 0);
+        });
+        "#,
+      )
+      .unwrap();
+    worker.run_event_loop_until_unload(false).await.unwrap();
+    worker
+      .execute_script(
+        "check.js",
+        "if (globalThis.dispatched !== 3) throw new Error('not dispatched');",
+      )
+      .unwrap();
   }
 
   #[tokio::test]
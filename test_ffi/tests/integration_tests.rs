@@ -0,0 +1,53 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+
+use std::process::Command;
+use test_util::deno_cmd;
+
+#[cfg(debug_assertions)]
+const BUILD_VARIANT: &str = "debug";
+
+#[cfg(not(debug_assertions))]
+const BUILD_VARIANT: &str = "release";
+
+#[test]
+fn basic() {
+  let mut build_ffi_base = Command::new("cargo");
+  let mut build_ffi = build_ffi_base.arg("build").arg("-p").arg("test_ffi");
+  if BUILD_VARIANT == "release" {
+    build_ffi = build_ffi.arg("--release");
+  }
+  let build_ffi_output = build_ffi.output().unwrap();
+  assert!(build_ffi_output.status.success());
+  let output = deno_cmd()
+    .arg("run")
+    .arg("--allow-ffi")
+    .arg("--unstable")
+    .arg("tests/test.js")
+    .arg(BUILD_VARIANT)
+    .output()
+    .unwrap();
+  let stdout = std::str::from_utf8(&output.stdout).unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  if !output.status.success() {
+    println!("stdout {}", stdout);
+    println!("stderr {}", stderr);
+  }
+  println!("{:?}", output.status);
+  assert!(output.status.success());
+  let expected = "\
+    something\n\
+    [1, 2, 3, 4, 5, 6, 7, 8]\n\
+    579\n\
+    579\n\
+    579n\n\
+    579n\n\
+    18446744073709551615n\n\
+    -9223372036854775807n\n\
+    579\n\
+    579\n\
+    579.9119873046875\n\
+    579.912\n\
+    Correct number of resources\n";
+  assert_eq!(stdout, expected);
+  assert_eq!(stderr, "");
+}
@@ -0,0 +1,63 @@
+// Copyright 2018-2021 the Deno authors. All rights reserved. MIT license.
+// deno-lint-ignore-file
+
+const filenameBase = "test_ffi";
+
+let filenameSuffix = ".so";
+let filenamePrefix = "lib";
+
+if (Deno.build.os === "windows") {
+  filenameSuffix = ".dll";
+  filenamePrefix = "";
+} else if (Deno.build.os === "darwin") {
+  filenameSuffix = ".dylib";
+}
+
+const filename = `../target/${
+  Deno.args[0]
+}/${filenamePrefix}${filenameBase}${filenameSuffix}`;
+
+const resourcesPre = Deno.resources();
+
+const dylib = Deno.dlopen(filename, {
+  "print_something": { parameters: [], result: "void" },
+  "print_buffer": { parameters: ["buffer", "usize"], result: "void" },
+  "add_u32": { parameters: ["u32", "u32"], result: "u32" },
+  "add_i32": { parameters: ["i32", "i32"], result: "i32" },
+  "add_u64": { parameters: ["u64", "u64"], result: "u64" },
+  "add_i64": { parameters: ["i64", "i64"], result: "i64" },
+  "add_usize": { parameters: ["usize", "usize"], result: "usize" },
+  "add_isize": { parameters: ["isize", "isize"], result: "isize" },
+  "add_f32": { parameters: ["f32", "f32"], result: "f32" },
+  "add_f64": { parameters: ["f64", "f64"], result: "f64" },
+});
+
+dylib.symbols.print_something();
+const buffer = new Uint8Array([1, 2, 3, 4, 5, 6, 7, 8]);
+dylib.symbols.print_buffer(buffer, buffer.length);
+console.log(dylib.symbols.add_u32(123, 456));
+console.log(dylib.symbols.add_i32(123, 456));
+console.log(dylib.symbols.add_u64(123, 456));
+console.log(dylib.symbols.add_i64(123, 456));
+console.log(dylib.symbols.add_u64(0xffffffffn, 0xffffffff00000000n));
+console.log(dylib.symbols.add_i64(-(2n ** 62n), -(2n ** 62n) + 1n));
+console.log(dylib.symbols.add_usize(123, 456));
+console.log(dylib.symbols.add_isize(123, 456));
+console.log(dylib.symbols.add_f32(123.123, 456.789));
+console.log(dylib.symbols.add_f64(123.123, 456.789));
+
+dylib.close();
+
+const resourcesPost = Deno.resources();
+
+const preStr = JSON.stringify(resourcesPre, null, 2);
+const postStr = JSON.stringify(resourcesPost, null, 2);
+if (preStr !== postStr) {
+  throw new Error(
+    `Difference in open resources before dlopen and after closing:
+Before: ${preStr}
+After: ${postStr}`,
+  );
+}
+
+console.log("Correct number of resources");
@@ -15,8 +15,9 @@ use deno_core::ResourceId;
 use deno_core::ZeroCopyBuf;
 use serde::Deserialize;
 
-#[no_mangle]
-pub fn init() -> Extension {
+deno_core::declare_plugin!(init);
+
+fn init() -> Extension {
   Extension::builder()
     .ops(vec![
       ("op_test_sync", op_sync(op_test_sync)),
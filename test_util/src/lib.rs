@@ -497,6 +497,24 @@ async fn main_server(req: Request<Body>) -> hyper::Result<Response<Body>> {
         Ok(resp)
       }
     }
+    (_, "/last_modified_script.ts") => {
+      let last_modified =
+        HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT");
+      let if_modified_since = req.headers().get("if-modified-since");
+      let mut resp = if if_modified_since == Some(&last_modified) {
+        let mut resp = Response::new(Body::empty());
+        *resp.status_mut() = StatusCode::NOT_MODIFIED;
+        resp
+      } else {
+        Response::new(Body::from("console.log('last-modified')"))
+      };
+      resp.headers_mut().insert(
+        "Content-type",
+        HeaderValue::from_static("application/typescript"),
+      );
+      resp.headers_mut().insert("Last-Modified", last_modified);
+      Ok(resp)
+    }
     (_, "/xTypeScriptTypes.js") => {
       let mut res = Response::new(Body::from("export const foo = 'foo';"));
       res.headers_mut().insert(